@@ -0,0 +1,72 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dt_common::{
+    config::{config_enums::DbType, filter_config::FilterConfig},
+    meta::row_type::RowType,
+    rdb_filter::RdbFilter,
+};
+use dt_connector::extractor::mysql::binlog_filter_pushdown::MysqlBinlogFilterPushdown;
+
+const TB_COUNT: u64 = 100;
+const EVENT_COUNT: u64 = 10000;
+const ROWS_PER_EVENT: u64 = 10;
+
+// 10 of 100 tables are synced, 90% of binlog events are filtered
+fn mock_filter() -> RdbFilter {
+    let do_tbs: Vec<String> = (0..TB_COUNT / 10)
+        .map(|i| format!("db_1.tb_{}", i))
+        .collect();
+    let config = FilterConfig {
+        do_tbs: do_tbs.join(","),
+        ..Default::default()
+    };
+    RdbFilter::from_config(&config, &DbType::Mysql).unwrap()
+}
+
+fn bench_binlog_filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("binlog_filter");
+    let tbs: Vec<String> = (0..TB_COUNT).map(|i| format!("tb_{}", i)).collect();
+
+    // filter evaluated for each row
+    let mut filter = mock_filter();
+    group.bench_function("per_row", |b| {
+        b.iter(|| {
+            let mut kept = 0;
+            for i in 0..EVENT_COUNT {
+                let tb = &tbs[(i % TB_COUNT) as usize];
+                for _ in 0..ROWS_PER_EVENT {
+                    if !filter.filter_event(black_box("db_1"), black_box(tb), &RowType::Insert) {
+                        kept += 1;
+                    }
+                }
+            }
+            kept
+        })
+    });
+
+    // filter evaluated for each TABLE_MAP_EVENT, rows events checked by table_id
+    let mut filter = mock_filter();
+    group.bench_function("pushdown", |b| {
+        b.iter(|| {
+            let mut pushdown = MysqlBinlogFilterPushdown::default();
+            let mut kept = 0;
+            for i in 0..EVENT_COUNT {
+                let table_id = i % TB_COUNT;
+                pushdown.on_table_map(
+                    table_id,
+                    "db_1",
+                    &tbs[table_id as usize],
+                    &mut filter,
+                    false,
+                );
+                if !pushdown.is_filtered(black_box(table_id), &RowType::Insert) {
+                    kept += ROWS_PER_EVENT;
+                }
+            }
+            kept
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_binlog_filter);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dt_benchmark::mock_all_col_values;
+
+fn bench_col_value_to_string(c: &mut Criterion) {
+    let mut group = c.benchmark_group("col_value_to_string");
+    for (name, col_value) in mock_all_col_values() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &col_value, |b, v| {
+            b.iter(|| black_box(v).to_string())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_col_value_to_string);
+criterion_main!(benches);
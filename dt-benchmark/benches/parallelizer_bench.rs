@@ -0,0 +1,27 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use dt_parallelizer::snapshot_parallelizer::SnapshotParallelizer;
+
+const ROW_COUNT: usize = 1_000_000;
+
+fn bench_snapshot_partition(c: &mut Criterion) {
+    let mut group = c.benchmark_group("snapshot_partition");
+    for parallel_size in [4, 8, 16] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(parallel_size),
+            &parallel_size,
+            |b, parallel_size| {
+                b.iter_batched(
+                    || (0..ROW_COUNT as u64).collect::<Vec<u64>>(),
+                    |data| {
+                        SnapshotParallelizer::partition(black_box(data), *parallel_size).unwrap()
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_snapshot_partition);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dt_benchmark::{mock_insert_rows, mock_mysql_tb_meta};
+use dt_connector::rdb_query_builder::RdbQueryBuilder;
+
+const COL_COUNT: usize = 20;
+
+fn bench_get_batch_insert_query(c: &mut Criterion) {
+    let tb_meta = mock_mysql_tb_meta(COL_COUNT);
+    let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
+
+    let mut group = c.benchmark_group("get_batch_insert_query");
+    for batch_size in [1, 100, 1000] {
+        let data = mock_insert_rows(COL_COUNT, batch_size);
+        group.bench_with_input(BenchmarkId::from_parameter(batch_size), &data, |b, data| {
+            b.iter(|| {
+                query_builder
+                    .get_batch_insert_query(black_box(data), 0, batch_size, false)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_batch_insert_query);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dt_common::{
+    config::{config_enums::DbType, filter_config::FilterConfig},
+    rdb_filter::RdbFilter,
+};
+
+fn bench_filter_tb(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter_tb");
+    for pattern_count in [1, 10, 100] {
+        let do_tbs: Vec<String> = (0..pattern_count)
+            .map(|i| format!("db_{}.tb_*", i))
+            .collect();
+        let config = FilterConfig {
+            do_tbs: do_tbs.join(","),
+            ..Default::default()
+        };
+        let mut filter = RdbFilter::from_config(&config, &DbType::Mysql).unwrap();
+        // the last pattern matches, so all patterns are checked in the worst case
+        let schema = format!("db_{}", pattern_count - 1);
+
+        group.bench_function(BenchmarkId::from_parameter(pattern_count), |b| {
+            b.iter(|| {
+                // results are cached by filter, clear to benchmark the matching
+                filter.cache.clear();
+                filter.filter_tb(black_box(&schema), black_box("tb_1"))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_filter_tb);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dt_benchmark::{mock_col_values, BENCH_SCHEMA, BENCH_TB};
+use dt_common::meta::{row_data::RowData, row_type::RowType};
+
+// RowData::from_mysql_row requires a MySqlRow fetched from a live connection, benchmark
+// RowData::new with the same column counts instead, which includes computing data_size
+fn bench_row_data_new(c: &mut Criterion) {
+    let mut group = c.benchmark_group("row_data_new");
+    for col_count in [10, 50, 200] {
+        let after = mock_col_values(col_count, 1);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(col_count),
+            &after,
+            |b, after| {
+                b.iter(|| {
+                    RowData::new(
+                        BENCH_SCHEMA.into(),
+                        BENCH_TB.into(),
+                        RowType::Insert,
+                        None,
+                        Some(black_box(after.clone())),
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_row_data_new);
+criterion_main!(benches);
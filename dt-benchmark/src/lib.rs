@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+
+use dt_common::meta::{
+    col_value::ColValue,
+    mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+    rdb_tb_meta::RdbTbMeta,
+    row_data::RowData,
+    row_type::RowType,
+};
+
+pub const BENCH_SCHEMA: &str = "bench_db";
+pub const BENCH_TB: &str = "bench_tb";
+
+pub fn mock_cols(col_count: usize) -> Vec<String> {
+    (0..col_count).map(|i| format!("col_{}", i)).collect()
+}
+
+// table with an auto-increment id and string columns
+pub fn mock_mysql_tb_meta(col_count: usize) -> MysqlTbMeta {
+    let cols = mock_cols(col_count);
+    let id_cols = vec![cols[0].clone()];
+    let mut key_map = HashMap::new();
+    key_map.insert("primary".to_string(), id_cols.clone());
+
+    let mut col_type_map = HashMap::new();
+    for (i, col) in cols.iter().enumerate() {
+        let col_type = if i == 0 {
+            MysqlColType::BigInt { unsigned: false }
+        } else {
+            MysqlColType::Varchar {
+                length: 255,
+                charset: "utf8mb4".into(),
+            }
+        };
+        col_type_map.insert(col.clone(), col_type);
+    }
+
+    let basic = RdbTbMeta {
+        schema: BENCH_SCHEMA.into(),
+        tb: BENCH_TB.into(),
+        cols,
+        key_map,
+        order_col: Some(id_cols[0].clone()),
+        partition_col: id_cols[0].clone(),
+        id_cols,
+        ..Default::default()
+    };
+    MysqlTbMeta {
+        basic,
+        col_type_map,
+        auto_increment_col: None,
+        generated_cols: HashSet::new(),
+    }
+}
+
+pub fn mock_col_values(col_count: usize, id: i64) -> HashMap<String, ColValue> {
+    let mut after = HashMap::new();
+    for (i, col) in mock_cols(col_count).into_iter().enumerate() {
+        let col_value = if i == 0 {
+            ColValue::LongLong(id)
+        } else {
+            ColValue::String(format!("value_{}_{}", id, i))
+        };
+        after.insert(col, col_value);
+    }
+    after
+}
+
+pub fn mock_insert_rows(col_count: usize, row_count: usize) -> Vec<RowData> {
+    (0..row_count)
+        .map(|i| {
+            RowData::new(
+                BENCH_SCHEMA.into(),
+                BENCH_TB.into(),
+                RowType::Insert,
+                None,
+                Some(mock_col_values(col_count, i as i64)),
+            )
+        })
+        .collect()
+}
+
+// one value for each ColValue variant
+pub fn mock_all_col_values() -> Vec<(&'static str, ColValue)> {
+    vec![
+        ("none", ColValue::None),
+        ("bool", ColValue::Bool(true)),
+        ("tiny", ColValue::Tiny(-1)),
+        ("unsigned_tiny", ColValue::UnsignedTiny(1)),
+        ("short", ColValue::Short(-1000)),
+        ("unsigned_short", ColValue::UnsignedShort(1000)),
+        ("long", ColValue::Long(-100000)),
+        ("unsigned_long", ColValue::UnsignedLong(100000)),
+        ("long_long", ColValue::LongLong(-10000000000)),
+        (
+            "unsigned_long_long",
+            ColValue::UnsignedLongLong(10000000000),
+        ),
+        ("float", ColValue::Float(1.5)),
+        ("double", ColValue::Double(1.23456789)),
+        ("decimal", ColValue::Decimal("12345.6789".into())),
+        ("time", ColValue::Time("12:34:56.123456".into())),
+        ("date", ColValue::Date("2024-01-01".into())),
+        (
+            "date_time",
+            ColValue::DateTime("2024-01-01 12:34:56.123456".into()),
+        ),
+        (
+            "timestamp",
+            ColValue::Timestamp("2024-01-01 12:34:56.123456".into()),
+        ),
+        ("year", ColValue::Year(2024)),
+        ("string", ColValue::String("a".repeat(255))),
+        ("raw_string", ColValue::RawString(vec![b'a'; 255])),
+        ("blob", ColValue::Blob(vec![0xff; 1024])),
+        ("bit", ColValue::Bit(0b1010)),
+        ("set", ColValue::Set(3)),
+        ("enum", ColValue::Enum(1)),
+        ("set2", ColValue::Set2("a,b".into())),
+        ("enum2", ColValue::Enum2("a".into())),
+        ("json", ColValue::Json(br#"{"k":"v"}"#.to_vec())),
+        ("json2", ColValue::Json2(r#"{"k":"v"}"#.into())),
+        ("json3", ColValue::Json3(serde_json::json!({"k": "v"}))),
+    ]
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::error::Error;
+
+use super::credential_resolver::{CredentialProvider, Secret};
+
+// read secrets from aws secrets manager, path is the secret id (name or arn),
+// the secret string should be a json object, E.g. {"username":"dts","password":"123456"}
+pub struct AwsSecretsCredentialProvider {
+    client: aws_sdk_secretsmanager::Client,
+}
+
+impl AwsSecretsCredentialProvider {
+    pub async fn new() -> Self {
+        // credentials and region are resolved by the default provider chain (env, profile, instance role)
+        let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+            .load()
+            .await;
+        Self {
+            client: aws_sdk_secretsmanager::Client::new(&config),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for AwsSecretsCredentialProvider {
+    async fn fetch(&self, path: &str) -> anyhow::Result<Secret> {
+        let output = self
+            .client
+            .get_secret_value()
+            .secret_id(path)
+            .send()
+            .await
+            .with_context(|| format!("failed to read secret: {} from aws secrets manager", path))?;
+
+        let secret_string = match output.secret_string() {
+            Some(secret_string) => secret_string,
+            None => bail! {Error::ConfigError(format!(
+                "secret: {} has no secret string",
+                path
+            ))},
+        };
+
+        let mut values = HashMap::new();
+        if let Value::Object(map) = serde_json::from_str(secret_string)? {
+            for (key, value) in map {
+                let value = match value {
+                    Value::String(s) => s,
+                    _ => value.to_string(),
+                };
+                values.insert(key, value);
+            }
+        }
+        // rotated secrets are not leased, they are read once for each task
+        Ok(Secret {
+            values,
+            lease_duration_secs: 0,
+            lease_id: String::new(),
+            renewable: false,
+        })
+    }
+}
@@ -48,6 +48,8 @@ pub enum ExtractType {
     Reshard,
     #[strum(serialize = "foxlake_s3")]
     FoxlakeS3,
+    #[strum(serialize = "rds_data_api")]
+    RdsDataApi,
 }
 
 #[derive(Display, EnumString, IntoStaticStr)]
@@ -64,10 +66,16 @@ pub enum SinkType {
     Statistic,
     #[strum(serialize = "sql")]
     Sql,
+    #[strum(serialize = "csv")]
+    Csv,
+    #[strum(serialize = "parquet")]
+    Parquet,
     #[strum(serialize = "push")]
     Push,
     #[strum(serialize = "merge")]
     Merge,
+    #[strum(serialize = "server_side")]
+    ServerSide,
 }
 
 #[derive(EnumString, IntoStaticStr, Clone, Display)]
@@ -80,6 +88,8 @@ pub enum ParallelType {
     RdbPartition,
     #[strum(serialize = "rdb_merge")]
     RdbMerge,
+    #[strum(serialize = "transaction")]
+    Transaction,
     #[strum(serialize = "rdb_check")]
     RdbCheck,
     #[strum(serialize = "table")]
@@ -116,3 +126,176 @@ pub enum MetaCenterType {
     #[strum(serialize = "dbengine")]
     DbEngine,
 }
+
+// lock acquired on target tables before executing ddls
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum TableLockMode {
+    #[default]
+    #[strum(serialize = "none")]
+    None,
+    #[strum(serialize = "share")]
+    Share,
+    #[strum(serialize = "exclusive")]
+    Exclusive,
+    #[strum(serialize = "access_exclusive")]
+    AccessExclusive,
+}
+
+impl TableLockMode {
+    pub fn to_pg_lock_mode(&self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Share => "SHARE",
+            Self::Exclusive => "EXCLUSIVE",
+            Self::AccessExclusive => "ACCESS EXCLUSIVE",
+        }
+    }
+}
+
+// how pg inserts resolve conflicts on the primary key or conflict_cols
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum PgConflictMode {
+    // ON CONFLICT (...) DO UPDATE SET ...
+    #[default]
+    #[strum(serialize = "do_update")]
+    DoUpdate,
+    // ON CONFLICT (...) DO NOTHING
+    #[strum(serialize = "do_nothing")]
+    DoNothing,
+    // no ON CONFLICT clause, conflicts are raised as errors
+    #[strum(serialize = "error")]
+    Error,
+}
+
+// how mysql inserts resolve conflicts on the primary key or unique keys
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum MysqlConflictMode {
+    // REPLACE INTO ...
+    #[default]
+    #[strum(serialize = "replace")]
+    Replace,
+    // INSERT ... ON DUPLICATE KEY UPDATE col=VALUES(col), ...
+    #[strum(serialize = "do_update")]
+    DoUpdate,
+    // plain INSERT, conflicts are raised as errors
+    #[strum(serialize = "error")]
+    Error,
+}
+
+// what to do when source and target columns have different collations
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum CollationPolicy {
+    #[default]
+    #[strum(serialize = "ignore")]
+    Ignore,
+    #[strum(serialize = "warn")]
+    Warn,
+    #[strum(serialize = "error")]
+    Error,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum KafkaMessageFormat {
+    #[default]
+    #[strum(serialize = "avro")]
+    Avro,
+    // json envelope: {"op":..,"db":..,"tb":..,"before":{..},"after":{..},"ts_ms":..}
+    #[strum(serialize = "json")]
+    Json,
+}
+
+// key of kafka messages, which decides the partition
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum KafkaPartitionKey {
+    // values of id_cols (primary key or unique key), changes of a row go to the same partition
+    #[default]
+    #[strum(serialize = "id_cols")]
+    IdCols,
+    // db.tb, changes of a table go to the same partition in order
+    #[strum(serialize = "db_tb")]
+    DbTb,
+}
+
+// how SnapshotParallelizer splits a batch among sinkers
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum SnapshotPartitionMode {
+    // split by position into parallel_size even slices
+    #[default]
+    #[strum(serialize = "position")]
+    Position,
+    // hash id_cols (primary key or unique key) of rows, rows of the same key go to
+    // the same sinker in order
+    #[strum(serialize = "id_cols")]
+    IdCols,
+}
+
+// format of default.log / commit.log / stdout, other logs (position.log, monitor.log, etc.)
+// are parsed by ape-dts and always in text
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum LogFormat {
+    #[default]
+    #[strum(serialize = "text")]
+    Text,
+    // one json object for each line: {"timestamp":..,"level":..,"module":..,"message":..}
+    #[strum(serialize = "json")]
+    Json,
+}
+
+// whether [extractor] max_rows_per_sec / max_mb_per_sec are shared by all tables being extracted
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum RateLimitScope {
+    // one limit for the whole task, however many tables are extracted in parallel
+    #[default]
+    #[strum(serialize = "global")]
+    Global,
+    // each table being extracted has its own limit
+    #[strum(serialize = "table")]
+    Table,
+}
+
+// isolation level of the mysql consistent snapshot transaction
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum SnapshotIsolationLevel {
+    // one read view for the whole snapshot, undo logs can not be purged until it finishes
+    #[default]
+    #[strum(serialize = "repeatable_read")]
+    RepeatableRead,
+    // a new read view for each query, rows changed during the snapshot may be seen
+    #[strum(serialize = "read_committed")]
+    ReadCommitted,
+}
+
+impl SnapshotIsolationLevel {
+    // read_committed needs no shared read view, so tables are extracted without
+    // a long transaction holding metadata locks
+    pub fn shares_transaction(&self) -> bool {
+        match self {
+            Self::RepeatableRead => true,
+            Self::ReadCommitted => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_snapshot_isolation_level() {
+        let isolation = SnapshotIsolationLevel::from_str("repeatable_read").unwrap();
+        assert_eq!(isolation, SnapshotIsolationLevel::RepeatableRead);
+        assert!(isolation.shares_transaction());
+
+        let isolation = SnapshotIsolationLevel::from_str("read_committed").unwrap();
+        assert_eq!(isolation, SnapshotIsolationLevel::ReadCommitted);
+        assert!(!isolation.shares_transaction());
+
+        assert_eq!(
+            SnapshotIsolationLevel::default(),
+            SnapshotIsolationLevel::RepeatableRead
+        );
+        assert!(SnapshotIsolationLevel::from_str("serializable").is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+const JSON_PREFIX: &str = "json:";
+
+// columns used as the target of ON CONFLICT (...) DO UPDATE in pg upserts, for tables whose
+// conflicts should be resolved by a unique constraint other than the primary key
+pub struct ConflictColsConfig {}
+
+impl ConflictColsConfig {
+    // conflict_cols=json:[{"db":"public","tb":"tb_1","conflict_cols":["f_1","f_2"]}]
+    pub fn parse_config(
+        config_str: &str,
+    ) -> anyhow::Result<HashMap<(String, String), Vec<String>>> {
+        let mut results = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct ConflictColsType {
+            db: String,
+            tb: String,
+            conflict_cols: Vec<String>,
+        }
+        let config: Vec<ConflictColsType> =
+            serde_json::from_str(config_str.trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            results.insert((i.db, i.tb), i.conflict_cols);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConflictColsConfig;
+
+    #[test]
+    fn test_parse_config() {
+        let config_str = r#"json:[{"db":"public","tb":"tb_1","conflict_cols":["f_1","f_2"]},{"db":"public","tb":"tb_2","conflict_cols":["code"]}]"#;
+        let conflict_cols = ConflictColsConfig::parse_config(config_str).unwrap();
+        assert_eq!(conflict_cols.len(), 2);
+        assert_eq!(
+            conflict_cols[&("public".to_string(), "tb_1".to_string())],
+            vec!["f_1".to_string(), "f_2".to_string()]
+        );
+        assert_eq!(
+            conflict_cols[&("public".to_string(), "tb_2".to_string())],
+            vec!["code".to_string()]
+        );
+
+        assert!(ConflictColsConfig::parse_config("").unwrap().is_empty());
+    }
+}
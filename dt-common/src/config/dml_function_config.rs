@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+const JSON_PREFIX: &str = "json:";
+
+// functions used as DML wrappers, e.g. SECURITY DEFINER functions enforcing row-level access
+// control, each function takes all columns of the table as parameters in column order
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DmlFunctionConfig {
+    #[serde(default)]
+    pub insert_fn: String,
+    #[serde(default)]
+    pub update_fn: String,
+    #[serde(default)]
+    pub delete_fn: String,
+}
+
+impl DmlFunctionConfig {
+    // dml_functions=json:[{"db":"public","tb":"tb_1","insert_fn":"public.insert_tb_1","update_fn":"public.update_tb_1","delete_fn":"public.delete_tb_1"}]
+    pub fn parse_config(
+        config_str: &str,
+    ) -> anyhow::Result<HashMap<(String, String), DmlFunctionConfig>> {
+        let mut results = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct DmlFunctionType {
+            db: String,
+            tb: String,
+            #[serde(flatten)]
+            functions: DmlFunctionConfig,
+        }
+        let config: Vec<DmlFunctionType> =
+            serde_json::from_str(config_str.trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            results.insert((i.db, i.tb), i.functions);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DmlFunctionConfig;
+
+    #[test]
+    fn test_parse_config() {
+        let config_str = r#"json:[{"db":"public","tb":"tb_1","insert_fn":"public.insert_tb_1","update_fn":"public.update_tb_1","delete_fn":"public.delete_tb_1"},{"db":"public","tb":"tb_2","insert_fn":"public.upsert_tb_2"}]"#;
+        let dml_functions = DmlFunctionConfig::parse_config(config_str).unwrap();
+        assert_eq!(dml_functions.len(), 2);
+
+        let tb_1 = dml_functions
+            .get(&("public".to_string(), "tb_1".to_string()))
+            .unwrap();
+        assert_eq!(tb_1.insert_fn, "public.insert_tb_1");
+        assert_eq!(tb_1.update_fn, "public.update_tb_1");
+        assert_eq!(tb_1.delete_fn, "public.delete_tb_1");
+
+        let tb_2 = dml_functions
+            .get(&("public".to_string(), "tb_2".to_string()))
+            .unwrap();
+        assert_eq!(tb_2.insert_fn, "public.upsert_tb_2");
+        assert!(tb_2.update_fn.is_empty());
+        assert!(tb_2.delete_fn.is_empty());
+
+        assert!(DmlFunctionConfig::parse_config("").unwrap().is_empty());
+    }
+}
@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
 use super::{
-    config_enums::{DbType, ExtractType},
+    config_enums::{DbType, ExtractType, RateLimitScope, SnapshotIsolationLevel},
     s3_config::S3Config,
 };
 
+const JSON_PREFIX: &str = "json:";
+
 #[derive(Clone, Debug)]
 pub enum ExtractorConfig {
     MysqlStruct {
@@ -22,6 +28,20 @@ pub enum ExtractorConfig {
         sample_interval: usize,
         parallel_size: usize,
         batch_size: usize,
+        force_index: String,
+        auto_detect_force_index: bool,
+        use_partition_based_extraction: bool,
+        partition_concurrency: usize,
+        consistent_snapshot: bool,
+        // transactions started at the same point for slices to extract in parallel
+        consistent_snapshot_conns: usize,
+        consistent_snapshot_isolation: SnapshotIsolationLevel,
+        parallel_slices: usize,
+        slice_progress_interval: usize,
+        snapshot_desc: bool,
+        checksum_check: bool,
+        // (db, tb) -> hint added to the snapshot queries of the table verbatim
+        tb_query_hints: HashMap<(String, String), String>,
     },
 
     MysqlCdc {
@@ -37,12 +57,28 @@ pub enum ExtractorConfig {
         heartbeat_tb: String,
         start_time_utc: String,
         end_time_utc: String,
+        large_event_threshold_bytes: u64,
+    },
+
+    MysqlJoin {
+        url: String,
+        join_sql: String,
+        // db.tb
+        virtual_table: String,
+    },
+
+    MysqlRds {
+        resource_arn: String,
+        secret_arn: String,
+        region: String,
+        batch_size: usize,
     },
 
     MysqlCheck {
         url: String,
         check_log_dir: String,
         batch_size: usize,
+        delete_extra: bool,
     },
 
     PgSnapshot {
@@ -51,6 +87,10 @@ pub enum ExtractorConfig {
         tb: String,
         sample_interval: usize,
         batch_size: usize,
+        shared_snapshot: bool,
+        shared_snapshot_id: String,
+        ctid_slicing: bool,
+        checksum_check: bool,
     },
 
     PgCdc {
@@ -71,6 +111,7 @@ pub enum ExtractorConfig {
         url: String,
         check_log_dir: String,
         batch_size: usize,
+        delete_extra: bool,
     },
 
     MongoSnapshot {
@@ -155,9 +196,92 @@ pub enum ExtractorConfig {
     },
 }
 
+impl ExtractorConfig {
+    pub fn url_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::MysqlStruct { url, .. }
+            | Self::PgStruct { url, .. }
+            | Self::MysqlSnapshot { url, .. }
+            | Self::MysqlCdc { url, .. }
+            | Self::MysqlJoin { url, .. }
+            | Self::MysqlCheck { url, .. }
+            | Self::PgSnapshot { url, .. }
+            | Self::PgCdc { url, .. }
+            | Self::PgCheck { url, .. }
+            | Self::MongoSnapshot { url, .. }
+            | Self::MongoCdc { url, .. }
+            | Self::MongoCheck { url, .. }
+            | Self::RedisSnapshot { url, .. }
+            | Self::RedisCdc { url, .. }
+            | Self::RedisSnapshotAndCdc { url, .. }
+            | Self::RedisScan { url, .. }
+            | Self::RedisReshard { url, .. }
+            | Self::Kafka { url, .. }
+            | Self::FoxlakeS3 { url, .. } => Some(url),
+            _ => None,
+        }
+    }
+
+    // tb_query_hints=json:[{"db":"db_1","tb":"tb_1","query_hint":"FORCE INDEX (idx_created)"}]
+    pub fn parse_tb_query_hints(
+        config_str: &str,
+    ) -> anyhow::Result<HashMap<(String, String), String>> {
+        let mut results = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct TbQueryHintType {
+            db: String,
+            tb: String,
+            query_hint: String,
+        }
+        let config: Vec<TbQueryHintType> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            results.insert((i.db, i.tb), i.query_hint);
+        }
+        Ok(results)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BasicExtractorConfig {
     pub db_type: DbType,
     pub extract_type: ExtractType,
     pub url: String,
+    // throttle of rows extracted by snapshot tasks, 0 means unlimited
+    pub max_rows_per_sec: u64,
+    pub max_mb_per_sec: u64,
+    pub rate_limit_scope: RateLimitScope,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tb_query_hints() {
+        let config_str = r#"json:[{"db":"db_1","tb":"tb_1","query_hint":"FORCE INDEX (`idx_created`)"},{"db":"db_1","tb":"tb_2","query_hint":"/*+ MAX_EXECUTION_TIME(1000) */"}]"#;
+        let hints = ExtractorConfig::parse_tb_query_hints(config_str).unwrap();
+        assert_eq!(hints.len(), 2);
+        assert_eq!(
+            hints
+                .get(&("db_1".to_string(), "tb_1".to_string()))
+                .unwrap(),
+            "FORCE INDEX (`idx_created`)"
+        );
+        assert_eq!(
+            hints
+                .get(&("db_1".to_string(), "tb_2".to_string()))
+                .unwrap(),
+            "/*+ MAX_EXECUTION_TIME(1000) */"
+        );
+
+        assert!(ExtractorConfig::parse_tb_query_hints("")
+            .unwrap()
+            .is_empty());
+        assert!(ExtractorConfig::parse_tb_query_hints("json:[{\"db\":\"db_1\"}]").is_err());
+    }
 }
@@ -5,9 +5,14 @@ pub struct FilterConfig {
     pub do_tbs: String,
     pub ignore_tbs: String,
     pub ignore_cols: String,
+    pub include_cols: String,
     pub do_events: String,
     pub do_structures: String,
     pub do_ddls: String,
     pub ignore_cmds: String,
+    pub do_keys: String,
+    pub ignore_keys: String,
     pub where_conditions: String,
+    pub row_filters: String,
+    pub include_server_settings: bool,
 }
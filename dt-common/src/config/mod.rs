@@ -1,6 +1,10 @@
+pub mod aws_secrets_credential_provider;
 pub mod config_enums;
 pub mod config_token_parser;
+pub mod conflict_cols_config;
+pub mod credential_resolver;
 pub mod data_marker_config;
+pub mod dml_function_config;
 pub mod extractor_config;
 pub mod filter_config;
 pub mod ini_loader;
@@ -15,3 +19,4 @@ pub mod runtime_config;
 pub mod s3_config;
 pub mod sinker_config;
 pub mod task_config;
+pub mod vault_credential_provider;
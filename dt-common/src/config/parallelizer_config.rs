@@ -1,7 +1,151 @@
-use super::config_enums::ParallelType;
+use std::collections::HashMap;
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+use super::config_enums::{ParallelType, SnapshotPartitionMode};
+
+const JSON_PREFIX: &str = "json:";
 
 #[derive(Clone)]
 pub struct ParallelizerConfig {
     pub parallel_type: ParallelType,
     pub parallel_size: usize,
+    // overrides parallel_size for snapshot tasks of the tables, (db, tb) -> parallel_size
+    pub tb_parallel_sizes: HashMap<(String, String), usize>,
+    // stddev / mean of partition sizes to warn data skew, 0 means disabled
+    pub skew_threshold: f64,
+    pub skew_rebalance: bool,
+    // sink each drained chunk of a sinker in one transaction, only for sinkers supporting it
+    pub chunk_transaction: bool,
+    // only for snapshot parallel_type
+    pub snapshot_partition_mode: SnapshotPartitionMode,
+}
+
+impl ParallelizerConfig {
+    pub fn get_tb_parallel_size(&self, schema: &str, tb: &str) -> usize {
+        self.tb_parallel_sizes
+            .get(&(schema.to_string(), tb.to_string()))
+            .cloned()
+            .unwrap_or(self.parallel_size)
+    }
+
+    // the most sinkers a single task may create, tables without overrides use parallel_size
+    pub fn get_max_parallel_size(&self) -> usize {
+        self.tb_parallel_sizes
+            .values()
+            .cloned()
+            .fold(self.parallel_size, usize::max)
+    }
+
+    // parallel_size and tb_parallel_sizes are capped so that sinker connection pools of
+    // tb_parallel_size concurrent tables (parallel_size * 2 each) fit in max_connections,
+    // returns the cap if any parallel size is lowered
+    pub fn cap_parallel_sizes(
+        &mut self,
+        tb_parallel_size: usize,
+        max_connections: usize,
+    ) -> Option<usize> {
+        let cap = usize::max(1, max_connections / (tb_parallel_size.max(1) * 2));
+        if self.get_max_parallel_size() <= cap {
+            return None;
+        }
+        self.parallel_size = self.parallel_size.min(cap);
+        for parallel_size in self.tb_parallel_sizes.values_mut() {
+            *parallel_size = (*parallel_size).min(cap);
+        }
+        Some(cap)
+    }
+
+    // tb_parallel_sizes=json:[{"db":"db_1","tb":"tb_1","parallel_size":16}]
+    pub fn parse_tb_parallel_sizes(
+        config_str: &str,
+    ) -> anyhow::Result<HashMap<(String, String), usize>> {
+        let mut results = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct TbParallelSizeType {
+            db: String,
+            tb: String,
+            parallel_size: usize,
+        }
+        let config: Vec<TbParallelSizeType> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            if i.parallel_size == 0 {
+                bail! {Error::ConfigError(format!(
+                    "[parallelizer] tb_parallel_sizes, parallel_size of {}.{} should be greater than 0",
+                    i.db, i.tb
+                ))}
+            }
+            results.insert((i.db, i.tb), i.parallel_size);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tb_parallel_sizes() {
+        let config_str = r#"json:[{"db":"db_1","tb":"tb_1","parallel_size":16},{"db":"db_1","tb":"tb_2","parallel_size":1}]"#;
+        let config = ParallelizerConfig {
+            parallel_type: ParallelType::Snapshot,
+            parallel_size: 4,
+            tb_parallel_sizes: ParallelizerConfig::parse_tb_parallel_sizes(config_str).unwrap(),
+            skew_threshold: 0.0,
+            skew_rebalance: false,
+            chunk_transaction: false,
+            snapshot_partition_mode: SnapshotPartitionMode::default(),
+        };
+        assert_eq!(config.get_tb_parallel_size("db_1", "tb_1"), 16);
+        assert_eq!(config.get_tb_parallel_size("db_1", "tb_2"), 1);
+        assert_eq!(config.get_tb_parallel_size("db_1", "tb_3"), 4);
+        assert_eq!(config.get_max_parallel_size(), 16);
+
+        assert!(ParallelizerConfig::parse_tb_parallel_sizes("")
+            .unwrap()
+            .is_empty());
+        assert!(ParallelizerConfig::parse_tb_parallel_sizes(
+            r#"json:[{"db":"db_1","tb":"tb_1","parallel_size":0}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_cap_parallel_sizes() {
+        let config_str = r#"json:[{"db":"db_1","tb":"tb_1","parallel_size":16},{"db":"db_1","tb":"tb_2","parallel_size":1}]"#;
+        let mut config = ParallelizerConfig {
+            parallel_type: ParallelType::Snapshot,
+            parallel_size: 8,
+            tb_parallel_sizes: ParallelizerConfig::parse_tb_parallel_sizes(config_str).unwrap(),
+            skew_threshold: 0.0,
+            skew_rebalance: false,
+            chunk_transaction: false,
+            snapshot_partition_mode: SnapshotPartitionMode::default(),
+        };
+
+        // 4 tables * 16 * 2 connections fit in 200
+        assert_eq!(config.clone().cap_parallel_sizes(4, 200), None);
+        assert_eq!(config.clone().cap_parallel_sizes(4, 128), None);
+
+        // 4 tables * 6 * 2 connections at most
+        assert_eq!(config.cap_parallel_sizes(4, 50), Some(6));
+        assert_eq!(config.parallel_size, 6);
+        assert_eq!(config.get_tb_parallel_size("db_1", "tb_1"), 6);
+        assert_eq!(config.get_tb_parallel_size("db_1", "tb_2"), 1);
+        assert_eq!(config.get_max_parallel_size(), 6);
+
+        // at least 1
+        assert_eq!(config.cap_parallel_sizes(100, 50), Some(1));
+        assert_eq!(config.get_max_parallel_size(), 1);
+        assert_eq!(config.cap_parallel_sizes(0, 10), None);
+    }
 }
@@ -14,4 +14,14 @@ pub struct PipelineConfig {
     pub http_host: String,
     pub http_port: u64,
     pub with_field_defs: bool,
+    // chain hashes of sinked batches, and sign the final hash in a manifest file
+    pub integrity_hash_chain: bool,
+    pub integrity_manifest_file: String,
+    pub integrity_private_key_file: String,
+    // hex, the trusted key to verify manifest signatures, derived from the private key if empty
+    pub integrity_public_key: String,
+    // rows failed by data errors are appended to this file and skipped, empty: disabled
+    pub dead_letter_file: String,
+    // db.tb of the target, rows failed by data errors are inserted into it instead of a file
+    pub dead_letter_tb: String,
 }
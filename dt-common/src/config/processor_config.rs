@@ -2,4 +2,11 @@
 pub struct ProcessorConfig {
     pub lua_code_file: String,
     pub lua_code: String,
+    pub truncate_col_limits: String,
+    pub truncate_auto_detect_limits: bool,
+    pub truncation_log_path: String,
+    pub source_time_zone: String,
+    pub target_time_zone: String,
+    pub zero_date_value: String,
+    pub transform_cols: String,
 }
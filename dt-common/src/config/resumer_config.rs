@@ -3,4 +3,7 @@ pub struct ResumerConfig {
     pub resume_config_file: String,
     pub resume_from_log: bool,
     pub resume_log_dir: String,
+    // schema.tb in the target db, empty to disable
+    pub position_store_tb: String,
+    pub position_store_channel: String,
 }
@@ -5,5 +5,23 @@ pub enum RouterConfig {
         tb_map: String,
         col_map: String,
         topic_map: String,
+        // column stamped with the source `db`.`tb` of rows routed by tb_map,
+        // so rows of many source tables merged into one target are distinguishable
+        src_id_col: String,
+        // route rows of a source table to shard tables by hash of a column
+        shard_map: String,
     },
 }
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self::Rdb {
+            schema_map: String::new(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            src_id_col: String::new(),
+            shard_map: String::new(),
+        }
+    }
+}
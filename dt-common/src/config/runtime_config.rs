@@ -1,7 +1,19 @@
+use super::config_enums::LogFormat;
+
 #[derive(Clone)]
 pub struct RuntimeConfig {
     pub log_level: String,
     pub log_dir: String,
     pub log4rs_file: String,
+    pub log_format: LogFormat,
     pub tb_parallel_size: usize,
+    pub hot_reload: bool,
+    pub admin_addr: String,
+    pub admin_api_key: String,
+    // serve /metrics in prometheus text format and /healthz, empty to disable
+    pub metrics_addr: String,
+    pub credential_refresh_margin_secs: u64,
+    // on SIGTERM / SIGINT, running tasks are stopped and flushed within this period,
+    // the process is aborted after it, 0: no limit
+    pub shutdown_grace_secs: u64,
 }
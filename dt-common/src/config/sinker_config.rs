@@ -1,5 +1,8 @@
 use super::{
-    config_enums::{ConflictPolicyEnum, DbType},
+    config_enums::{
+        CollationPolicy, ConflictPolicyEnum, DbType, KafkaMessageFormat, KafkaPartitionKey,
+        MysqlConflictMode, PgConflictMode, TableLockMode,
+    },
     s3_config::S3Config,
 };
 
@@ -10,13 +13,27 @@ pub enum SinkerConfig {
     Mysql {
         url: String,
         batch_size: usize,
-        replace: bool,
+        conflict_mode: MysqlConflictMode,
+        json_merge: bool,
     },
 
     Pg {
         url: String,
         batch_size: usize,
-        replace: bool,
+        conflict_mode: PgConflictMode,
+        warn_on_unlogged: bool,
+        use_dml_functions: bool,
+        dml_functions: String,
+        table_lock: TableLockMode,
+        lock_timeout_ms: u64,
+        use_advisory_locks: bool,
+        lock_retry_count: u32,
+        lock_retry_delay_ms: u64,
+        collation_mismatch_policy: CollationPolicy,
+        partman_aware: bool,
+        prepared_statement_cache_size: usize,
+        exclude_conflict_policy: ConflictPolicyEnum,
+        conflict_cols: String,
     },
 
     Mongo {
@@ -25,16 +42,23 @@ pub enum SinkerConfig {
         batch_size: usize,
     },
 
+    MysqlServerSide {
+        url: String,
+        batch_size: usize,
+    },
+
     MysqlCheck {
         url: String,
         batch_size: usize,
         check_log_dir: String,
+        sample_percent: u8,
     },
 
     PgCheck {
         url: String,
         batch_size: usize,
         check_log_dir: String,
+        sample_percent: u8,
     },
 
     MongoCheck {
@@ -42,6 +66,7 @@ pub enum SinkerConfig {
         app_name: String,
         batch_size: usize,
         check_log_dir: String,
+        sample_percent: u8,
     },
 
     MysqlStruct {
@@ -60,6 +85,9 @@ pub enum SinkerConfig {
         ack_timeout_secs: u64,
         required_acks: String,
         with_field_defs: bool,
+        message_format: KafkaMessageFormat,
+        partition_key: KafkaPartitionKey,
+        ddl_topic: String,
     },
 
     Redis {
@@ -67,6 +95,8 @@ pub enum SinkerConfig {
         batch_size: usize,
         method: String,
         is_cluster: bool,
+        ignore_ttl: bool,
+        expired_key_policy: String,
     },
 
     RedisStatistic {
@@ -140,6 +170,48 @@ pub enum SinkerConfig {
     Sql {
         reverse: bool,
     },
+
+    Csv {
+        output_dir: String,
+        delimiter: String,
+        null_token: String,
+        ignore_non_insert: bool,
+    },
+
+    Parquet {
+        output_dir: String,
+        batch_size: usize,
+        ignore_non_insert: bool,
+    },
+}
+
+impl SinkerConfig {
+    pub fn url_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::Mysql { url, .. }
+            | Self::Pg { url, .. }
+            | Self::Mongo { url, .. }
+            | Self::MysqlServerSide { url, .. }
+            | Self::MysqlCheck { url, .. }
+            | Self::PgCheck { url, .. }
+            | Self::MongoCheck { url, .. }
+            | Self::MysqlStruct { url, .. }
+            | Self::PgStruct { url, .. }
+            | Self::Kafka { url, .. }
+            | Self::Redis { url, .. }
+            | Self::StarRocks { url, .. }
+            | Self::DorisStruct { url, .. }
+            | Self::Doris { url, .. }
+            | Self::StarRocksStruct { url, .. }
+            | Self::ClickHouse { url, .. }
+            | Self::ClickhouseStruct { url, .. }
+            | Self::Foxlake { url, .. }
+            | Self::FoxlakePush { url, .. }
+            | Self::FoxlakeMerge { url, .. }
+            | Self::FoxlakeStruct { url, .. } => Some(url),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -147,4 +219,26 @@ pub struct BasicSinkerConfig {
     pub db_type: DbType,
     pub url: String,
     pub batch_size: usize,
+    // warn if checking out a connection from the sinker pool takes longer, 0 means no warning
+    pub max_acceptable_wait_ms: u64,
+    // retries of a sub batch failed by deadlocks or lock wait timeouts, for mysql/pg sinkers
+    pub deadlock_max_retries: u32,
+    pub deadlock_retry_interval_ms: u64,
+    // max time to wait for the pool to recover from connection errors before giving up,
+    // the failed sub batch is replayed after recovered, 0 means disabled, for mysql/pg sinkers
+    pub reconnect_max_wait_secs: u64,
+    pub reconnect_interval_ms: u64,
+    // adjust batch_size by the latency of sub batches, for mysql/pg sinkers
+    pub adaptive_batch_size: bool,
+    pub max_batch_size: usize,
+    pub adaptive_target_latency_ms: u64,
+    // limit of estimated sql text + bind payload of a sub batch, 0 means unlimited
+    pub max_packet_bytes: usize,
+    // create tables missing in target from source structures before snapshot
+    pub auto_create_tables: bool,
+    // create secondary indexes and check / foreign key constraints of the created tables
+    // after all tables are snapshotted
+    pub defer_indexes: bool,
+    // tables whose indexes are deferred, in the format of [filter] do_tbs
+    pub defer_indexes_tbs: String,
 }
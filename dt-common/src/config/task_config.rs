@@ -9,9 +9,10 @@ use crate::error::Error;
 
 use super::{
     config_enums::{
-        ConflictPolicyEnum, DbType, ExtractType, MetaCenterType, ParallelType, PipelineType,
-        SinkType,
+        ConflictPolicyEnum, DbType, ExtractType, LogFormat, MetaCenterType, MysqlConflictMode,
+        ParallelType, PgConflictMode, PipelineType, SinkType,
     },
+    credential_resolver::CredentialResolver,
     data_marker_config::DataMarkerConfig,
     extractor_config::{BasicExtractorConfig, ExtractorConfig},
     filter_config::FilterConfig,
@@ -62,6 +63,7 @@ const DB_TYPE: &str = "db_type";
 const URL: &str = "url";
 const BATCH_SIZE: &str = "batch_size";
 const SAMPLE_INTERVAL: &str = "sample_interval";
+const CHECKSUM_CHECK: &str = "checksum_check";
 const HEARTBEAT_INTERVAL_SECS: &str = "heartbeat_interval_secs";
 const KEEPALIVE_INTERVAL_SECS: &str = "keepalive_interval_secs";
 const HEARTBEAT_TB: &str = "heartbeat_tb";
@@ -84,6 +86,8 @@ impl TaskConfig {
         let resumer = Self::load_resumer_config(&loader, &runtime)?;
         let (extractor_basic, extractor) = Self::load_extractor_config(&loader, &pipeline)?;
         let (sinker_basic, sinker) = Self::load_sinker_config(&loader)?;
+        Self::validate_checksum_check(&extractor, &sinker)?;
+        Self::validate_auto_create_tables(&extractor, &sinker_basic, &sinker)?;
         Ok(Self {
             extractor_basic,
             extractor,
@@ -101,6 +105,76 @@ impl TaskConfig {
         })
     }
 
+    // substitute credential placeholders in urls, E.g. ${vault:secret/data/dts/mysql/password}
+    pub async fn resolve_credentials(
+        &mut self,
+        resolver: &mut CredentialResolver,
+    ) -> anyhow::Result<()> {
+        let mut urls = vec![&mut self.extractor_basic.url, &mut self.sinker_basic.url];
+        urls.extend(self.extractor.url_mut());
+        urls.extend(self.sinker.url_mut());
+        if let Some(MetaCenterConfig::MySqlDbEngine { url, .. }) = &mut self.meta_center {
+            urls.push(url);
+        }
+
+        for url in urls {
+            *url = resolver.resolve(url).await?;
+        }
+        Ok(())
+    }
+
+    // slices are checksummed by sql in both dbs, so the target must be of the same db type
+    fn validate_checksum_check(
+        extractor: &ExtractorConfig,
+        sinker: &SinkerConfig,
+    ) -> anyhow::Result<()> {
+        let supported = match extractor {
+            ExtractorConfig::MysqlSnapshot {
+                checksum_check: true,
+                ..
+            } => matches!(sinker, SinkerConfig::MysqlCheck { .. }),
+            ExtractorConfig::PgSnapshot {
+                checksum_check: true,
+                ..
+            } => matches!(sinker, SinkerConfig::PgCheck { .. }),
+            _ => true,
+        };
+        if !supported {
+            bail! {Error::ConfigError(
+                "config [extractor] checksum_check is only supported for mysql / pg snapshot tasks with [sinker] sink_type=check of the same db_type".into()
+            )}
+        }
+        Ok(())
+    }
+
+    // tables are created by the struct statements of the source, so the target must be
+    // of the same db type
+    fn validate_auto_create_tables(
+        extractor: &ExtractorConfig,
+        sinker_basic: &BasicSinkerConfig,
+        sinker: &SinkerConfig,
+    ) -> anyhow::Result<()> {
+        if !sinker_basic.auto_create_tables {
+            if sinker_basic.defer_indexes {
+                bail! {Error::ConfigError(
+                    "config [sinker] defer_indexes requires [sinker] auto_create_tables=true".into()
+                )}
+            }
+            return Ok(());
+        }
+        let supported = match extractor {
+            ExtractorConfig::MysqlSnapshot { .. } => matches!(sinker, SinkerConfig::Mysql { .. }),
+            ExtractorConfig::PgSnapshot { .. } => matches!(sinker, SinkerConfig::Pg { .. }),
+            _ => false,
+        };
+        if !supported {
+            bail! {Error::ConfigError(
+                "config [sinker] auto_create_tables is only supported for mysql / pg snapshot tasks with [sinker] sink_type=write of the same db_type".into()
+            )}
+        }
+        Ok(())
+    }
+
     fn load_extractor_config(
         loader: &IniLoader,
         pipeline: &PipelineConfig,
@@ -119,22 +193,74 @@ impl TaskConfig {
             db_type: db_type.clone(),
             extract_type: extract_type.clone(),
             url: url.clone(),
+            max_rows_per_sec: loader.get_optional(EXTRACTOR, "max_rows_per_sec"),
+            max_mb_per_sec: loader.get_optional(EXTRACTOR, "max_mb_per_sec"),
+            rate_limit_scope: loader.get_optional(EXTRACTOR, "rate_limit_scope"),
         };
 
         let not_supported_err =
             Error::ConfigError(format!("extract type: {} not supported", extract_type));
 
+        // rows of JOIN results have no binlog, only snapshot is supported
+        let join_sql: String = loader.get_optional(EXTRACTOR, "join_sql");
+        if !join_sql.is_empty()
+            && (db_type != DbType::Mysql || !matches!(extract_type, ExtractType::Snapshot))
+        {
+            bail! {Error::ConfigError(
+                "config [extractor] join_sql is only supported for mysql snapshot tasks".into()
+            )}
+        }
+
         let extractor = match db_type {
             DbType::Mysql => match extract_type {
-                ExtractType::Snapshot => ExtractorConfig::MysqlSnapshot {
+                ExtractType::Snapshot if !join_sql.is_empty() => ExtractorConfig::MysqlJoin {
                     url,
-                    db: String::new(),
-                    tb: String::new(),
-                    sample_interval: loader.get_with_default(EXTRACTOR, SAMPLE_INTERVAL, 1),
-                    parallel_size: loader.get_with_default(EXTRACTOR, PARALLEL_SIZE, 1),
-                    batch_size,
+                    join_sql,
+                    virtual_table: loader.get_required(EXTRACTOR, "virtual_table"),
                 },
 
+                ExtractType::Snapshot => {
+                    let parallel_slices = loader.get_with_default(EXTRACTOR, "parallel_slices", 1);
+                    ExtractorConfig::MysqlSnapshot {
+                        url,
+                        db: String::new(),
+                        tb: String::new(),
+                        sample_interval: loader.get_with_default(EXTRACTOR, SAMPLE_INTERVAL, 1),
+                        parallel_size: loader.get_with_default(EXTRACTOR, PARALLEL_SIZE, 1),
+                        batch_size,
+                        force_index: loader.get_optional(EXTRACTOR, "force_index"),
+                        auto_detect_force_index: loader
+                            .get_optional(EXTRACTOR, "auto_detect_force_index"),
+                        use_partition_based_extraction: loader
+                            .get_optional(EXTRACTOR, "use_partition_based_extraction"),
+                        partition_concurrency: loader.get_with_default(
+                            EXTRACTOR,
+                            "partition_concurrency",
+                            4,
+                        ),
+                        consistent_snapshot: loader.get_optional(EXTRACTOR, "consistent_snapshot"),
+                        // by default every slice has its own connection in the snapshot
+                        consistent_snapshot_conns: loader.get_with_default(
+                            EXTRACTOR,
+                            "consistent_snapshot_conns",
+                            parallel_slices,
+                        ),
+                        consistent_snapshot_isolation: loader
+                            .get_optional(EXTRACTOR, "consistent_snapshot_isolation"),
+                        parallel_slices,
+                        slice_progress_interval: loader.get_with_default(
+                            EXTRACTOR,
+                            "slice_progress_interval",
+                            100,
+                        ),
+                        snapshot_desc: loader.get_optional(EXTRACTOR, "snapshot_desc"),
+                        checksum_check: loader.get_optional(EXTRACTOR, CHECKSUM_CHECK),
+                        tb_query_hints: ExtractorConfig::parse_tb_query_hints(
+                            &loader.get_optional::<String>(EXTRACTOR, "tb_query_hints"),
+                        )?,
+                    }
+                }
+
                 ExtractType::Cdc => ExtractorConfig::MysqlCdc {
                     url,
                     binlog_filename: loader.get_optional(EXTRACTOR, "binlog_filename"),
@@ -156,12 +282,18 @@ impl TaskConfig {
                     heartbeat_tb,
                     start_time_utc: loader.get_optional(EXTRACTOR, "start_time_utc"),
                     end_time_utc: loader.get_optional(EXTRACTOR, "end_time_utc"),
+                    large_event_threshold_bytes: loader.get_with_default(
+                        EXTRACTOR,
+                        "large_event_threshold_bytes",
+                        10 * 1024 * 1024,
+                    ),
                 },
 
                 ExtractType::CheckLog => ExtractorConfig::MysqlCheck {
                     url,
                     check_log_dir: loader.get_required(EXTRACTOR, CHECK_LOG_DIR),
                     batch_size: loader.get_with_default(EXTRACTOR, BATCH_SIZE, 200),
+                    delete_extra: loader.get_optional(EXTRACTOR, "delete_extra"),
                 },
 
                 ExtractType::Struct => ExtractorConfig::MysqlStruct {
@@ -169,6 +301,13 @@ impl TaskConfig {
                     db: String::new(),
                 },
 
+                ExtractType::RdsDataApi => ExtractorConfig::MysqlRds {
+                    resource_arn: loader.get_required(EXTRACTOR, "resource_arn"),
+                    secret_arn: loader.get_required(EXTRACTOR, "secret_arn"),
+                    region: loader.get_optional(EXTRACTOR, "region"),
+                    batch_size,
+                },
+
                 ExtractType::FoxlakeS3 => {
                     let s3_config = S3Config {
                         bucket: loader.get_optional(EXTRACTOR, "s3_bucket"),
@@ -198,6 +337,10 @@ impl TaskConfig {
                     tb: String::new(),
                     sample_interval: loader.get_with_default(EXTRACTOR, SAMPLE_INTERVAL, 1),
                     batch_size,
+                    shared_snapshot: loader.get_optional(EXTRACTOR, "shared_snapshot"),
+                    shared_snapshot_id: loader.get_optional(EXTRACTOR, "shared_snapshot_id"),
+                    ctid_slicing: loader.get_optional(EXTRACTOR, "ctid_slicing"),
+                    checksum_check: loader.get_optional(EXTRACTOR, CHECKSUM_CHECK),
                 },
 
                 ExtractType::Cdc => ExtractorConfig::PgCdc {
@@ -219,6 +362,7 @@ impl TaskConfig {
                     url,
                     check_log_dir: loader.get_required(EXTRACTOR, CHECK_LOG_DIR),
                     batch_size: loader.get_with_default(EXTRACTOR, BATCH_SIZE, 200),
+                    delete_extra: loader.get_optional(EXTRACTOR, "delete_extra"),
                 },
 
                 ExtractType::Struct => ExtractorConfig::PgStruct {
@@ -341,11 +485,44 @@ impl TaskConfig {
             db_type: db_type.clone(),
             url: url.clone(),
             batch_size,
+            max_acceptable_wait_ms: loader.get_optional(SINKER, "max_acceptable_wait_ms"),
+            deadlock_max_retries: loader.get_with_default(SINKER, "deadlock_max_retries", 3),
+            deadlock_retry_interval_ms: loader.get_with_default(
+                SINKER,
+                "deadlock_retry_interval_ms",
+                100,
+            ),
+            reconnect_max_wait_secs: loader.get_optional(SINKER, "reconnect_max_wait_secs"),
+            reconnect_interval_ms: loader.get_with_default(SINKER, "reconnect_interval_ms", 1000),
+            adaptive_batch_size: loader.get_optional(SINKER, "adaptive_batch_size"),
+            max_batch_size: loader.get_with_default(SINKER, "max_batch_size", batch_size * 10),
+            adaptive_target_latency_ms: loader.get_with_default(
+                SINKER,
+                "adaptive_target_latency_ms",
+                100,
+            ),
+            max_packet_bytes: loader.get_optional(SINKER, "max_packet_bytes"),
+            auto_create_tables: loader.get_optional(SINKER, "auto_create_tables"),
+            defer_indexes: loader.get_optional(SINKER, "defer_indexes"),
+            defer_indexes_tbs: loader.get_with_default(
+                SINKER,
+                "defer_indexes_tbs",
+                "*.*".to_string(),
+            ),
         };
 
         let conflict_policy: ConflictPolicyEnum =
             loader.get_with_default(SINKER, "conflict_policy", ConflictPolicyEnum::Interrupt);
 
+        // for sink_type=check, rows are sampled by the hash of id_cols
+        let sample_percent: u8 = loader.get_with_default(SINKER, "sample_percent", 100);
+        if sample_percent == 0 || sample_percent > 100 {
+            bail! {Error::ConfigError(format!(
+                "config [sinker] sample_percent: {} should be in [1, 100]",
+                sample_percent
+            ))}
+        }
+
         let not_supported_err =
             Error::ConfigError(format!("sinker db type: {} not supported", db_type));
 
@@ -354,13 +531,26 @@ impl TaskConfig {
                 SinkType::Write => SinkerConfig::Mysql {
                     url,
                     batch_size,
-                    replace: loader.get_with_default(SINKER, REPLACE, true),
+                    // replace=false works as conflict_mode=error for compatibility
+                    conflict_mode: loader.get_with_default(
+                        SINKER,
+                        "conflict_mode",
+                        if loader.get_with_default(SINKER, REPLACE, true) {
+                            MysqlConflictMode::Replace
+                        } else {
+                            MysqlConflictMode::Error
+                        },
+                    ),
+                    json_merge: loader.get_optional(SINKER, "json_merge"),
                 },
 
+                SinkType::ServerSide => SinkerConfig::MysqlServerSide { url, batch_size },
+
                 SinkType::Check => SinkerConfig::MysqlCheck {
                     url,
                     batch_size,
                     check_log_dir: loader.get_optional(SINKER, CHECK_LOG_DIR),
+                    sample_percent,
                 },
 
                 SinkType::Struct => SinkerConfig::MysqlStruct {
@@ -372,6 +562,10 @@ impl TaskConfig {
                     reverse: loader.get_optional(SINKER, REVERSE),
                 },
 
+                SinkType::Csv => Self::load_csv_sinker_config(loader)?,
+
+                SinkType::Parquet => Self::load_parquet_sinker_config(loader, batch_size)?,
+
                 _ => bail! { not_supported_err },
             },
 
@@ -379,13 +573,42 @@ impl TaskConfig {
                 SinkType::Write => SinkerConfig::Pg {
                     url,
                     batch_size,
-                    replace: loader.get_with_default(SINKER, REPLACE, true),
+                    // replace=false works as conflict_mode=error for compatibility
+                    conflict_mode: loader.get_with_default(
+                        SINKER,
+                        "conflict_mode",
+                        if loader.get_with_default(SINKER, REPLACE, true) {
+                            PgConflictMode::DoUpdate
+                        } else {
+                            PgConflictMode::Error
+                        },
+                    ),
+                    warn_on_unlogged: loader.get_with_default(SINKER, "warn_on_unlogged", true),
+                    use_dml_functions: loader.get_optional(SINKER, "use_dml_functions"),
+                    dml_functions: loader.get_optional(SINKER, "dml_functions"),
+                    table_lock: loader.get_optional(SINKER, "table_lock"),
+                    lock_timeout_ms: loader.get_with_default(SINKER, "lock_timeout_ms", 10000),
+                    use_advisory_locks: loader.get_optional(SINKER, "use_advisory_locks"),
+                    lock_retry_count: loader.get_with_default(SINKER, "lock_retry_count", 3),
+                    lock_retry_delay_ms: loader.get_with_default(
+                        SINKER,
+                        "lock_retry_delay_ms",
+                        100,
+                    ),
+                    collation_mismatch_policy: loader
+                        .get_optional(SINKER, "collation_mismatch_policy"),
+                    partman_aware: loader.get_optional(SINKER, "partman_aware"),
+                    prepared_statement_cache_size: loader
+                        .get_optional(SINKER, "prepared_statement_cache_size"),
+                    exclude_conflict_policy: loader.get_optional(SINKER, "exclude_conflict_policy"),
+                    conflict_cols: loader.get_optional(SINKER, "conflict_cols"),
                 },
 
                 SinkType::Check => SinkerConfig::PgCheck {
                     url,
                     batch_size,
                     check_log_dir: loader.get_optional(SINKER, CHECK_LOG_DIR),
+                    sample_percent,
                 },
 
                 SinkType::Struct => SinkerConfig::PgStruct {
@@ -397,6 +620,10 @@ impl TaskConfig {
                     reverse: loader.get_optional(SINKER, REVERSE),
                 },
 
+                SinkType::Csv => Self::load_csv_sinker_config(loader)?,
+
+                SinkType::Parquet => Self::load_parquet_sinker_config(loader, batch_size)?,
+
                 _ => bail! { not_supported_err },
             },
 
@@ -415,6 +642,7 @@ impl TaskConfig {
                         app_name,
                         batch_size,
                         check_log_dir: loader.get_optional(SINKER, CHECK_LOG_DIR),
+                        sample_percent,
                     },
 
                     _ => bail! { not_supported_err },
@@ -427,6 +655,9 @@ impl TaskConfig {
                 ack_timeout_secs: loader.get_with_default(SINKER, "ack_timeout_secs", 5),
                 required_acks: loader.get_with_default(SINKER, "required_acks", "one".to_string()),
                 with_field_defs: loader.get_with_default(SINKER, "with_field_defs", true),
+                message_format: loader.get_optional(SINKER, "message_format"),
+                partition_key: loader.get_optional(SINKER, "partition_key"),
+                ddl_topic: loader.get_optional(SINKER, "ddl_topic"),
             },
 
             DbType::Redis => match sink_type {
@@ -435,6 +666,8 @@ impl TaskConfig {
                     batch_size,
                     method: loader.get_optional(SINKER, "method"),
                     is_cluster: loader.get_optional(SINKER, "is_cluster"),
+                    ignore_ttl: loader.get_optional(SINKER, "ignore_ttl"),
+                    expired_key_policy: loader.get_optional(SINKER, "expired_key_policy"),
                 },
 
                 SinkType::Statistic => SinkerConfig::RedisStatistic {
@@ -540,14 +773,67 @@ impl TaskConfig {
         Ok((basic, sinker))
     }
 
+    fn load_csv_sinker_config(loader: &IniLoader) -> anyhow::Result<SinkerConfig> {
+        let output_dir: String = loader.get_optional(SINKER, "output_dir");
+        if output_dir.is_empty() {
+            bail! {Error::ConfigError(
+                "[sinker] output_dir is required if sink_type=csv".into()
+            )}
+        }
+        let delimiter: String = loader.get_with_default(SINKER, "delimiter", ",".to_string());
+        // tab can not be written literally in ini
+        let delimiter = if delimiter == "\\t" {
+            "\t".to_string()
+        } else {
+            delimiter
+        };
+        if delimiter.is_empty() || delimiter.contains('"') {
+            bail! {Error::ConfigError(format!(
+                "invalid [sinker] delimiter: {}",
+                delimiter
+            ))}
+        }
+        Ok(SinkerConfig::Csv {
+            output_dir,
+            delimiter,
+            null_token: loader.get_with_default(SINKER, "null_token", "\\N".to_string()),
+            ignore_non_insert: loader.get_optional(SINKER, "ignore_non_insert"),
+        })
+    }
+
+    fn load_parquet_sinker_config(
+        loader: &IniLoader,
+        batch_size: usize,
+    ) -> anyhow::Result<SinkerConfig> {
+        let output_dir: String = loader.get_optional(SINKER, "output_dir");
+        if output_dir.is_empty() {
+            bail! {Error::ConfigError(
+                "[sinker] output_dir is required if sink_type=parquet".into()
+            )}
+        }
+        Ok(SinkerConfig::Parquet {
+            output_dir,
+            // rows of a row group
+            batch_size: batch_size.max(1),
+            ignore_non_insert: loader.get_optional(SINKER, "ignore_non_insert"),
+        })
+    }
+
     fn load_parallelizer_config(loader: &IniLoader) -> anyhow::Result<ParallelizerConfig> {
         Ok(ParallelizerConfig {
             parallel_size: loader.get_with_default(PARALLELIZER, PARALLEL_SIZE, 1),
+            tb_parallel_sizes: ParallelizerConfig::parse_tb_parallel_sizes(
+                &loader.get_optional::<String>(PARALLELIZER, "tb_parallel_sizes"),
+            )?,
             parallel_type: loader.get_with_default(
                 PARALLELIZER,
                 "parallel_type",
                 ParallelType::Serial,
             ),
+            skew_threshold: loader.get_optional(PARALLELIZER, "skew_threshold"),
+            skew_rebalance: loader.get_optional(PARALLELIZER, "skew_rebalance"),
+            chunk_transaction: loader.get_optional(PARALLELIZER, "chunk_transaction"),
+            snapshot_partition_mode: loader.get_optional(PARALLELIZER, "snapshot_partition_mode"),
         })
     }
 
@@ -568,6 +854,12 @@ impl TaskConfig {
             http_host: loader.get_with_default(PIPELINE, "http_host", "0.0.0.0".to_string()),
             http_port: loader.get_with_default(PIPELINE, "http_port", 10231),
             with_field_defs: loader.get_with_default(PIPELINE, "with_field_defs", true),
+            integrity_hash_chain: loader.get_optional(PIPELINE, "integrity_hash_chain"),
+            integrity_manifest_file: loader.get_optional(PIPELINE, "integrity_manifest_file"),
+            integrity_private_key_file: loader.get_optional(PIPELINE, "integrity_private_key_file"),
+            integrity_public_key: loader.get_optional(PIPELINE, "integrity_public_key"),
+            dead_letter_file: loader.get_optional(PIPELINE, "dead_letter_file"),
+            dead_letter_tb: loader.get_optional(PIPELINE, "dead_letter_tb"),
         };
 
         if config.counter_time_window_secs == 0 {
@@ -585,7 +877,18 @@ impl TaskConfig {
                 "log4rs_file",
                 "./log4rs.yaml".to_string(),
             ),
+            log_format: loader.get_with_default(RUNTIME, "log_format", LogFormat::Text),
             tb_parallel_size: loader.get_with_default(RUNTIME, "tb_parallel_size", 1),
+            hot_reload: loader.get_optional(RUNTIME, "hot_reload"),
+            admin_addr: loader.get_optional(RUNTIME, "admin_addr"),
+            admin_api_key: loader.get_optional(RUNTIME, "admin_api_key"),
+            metrics_addr: loader.get_optional(RUNTIME, "metrics_addr"),
+            credential_refresh_margin_secs: loader.get_with_default(
+                RUNTIME,
+                "credential_refresh_margin_secs",
+                60,
+            ),
+            shutdown_grace_secs: loader.get_with_default(RUNTIME, "shutdown_grace_secs", 60),
         })
     }
 
@@ -596,11 +899,16 @@ impl TaskConfig {
             do_tbs: loader.get_optional(FILTER, "do_tbs"),
             ignore_tbs: loader.get_optional(FILTER, "ignore_tbs"),
             ignore_cols: loader.get_optional(FILTER, "ignore_cols"),
+            include_cols: loader.get_optional(FILTER, "include_cols"),
             do_events: loader.get_optional(FILTER, "do_events"),
             do_ddls: loader.get_optional(FILTER, "do_ddls"),
             do_structures: loader.get_with_default(FILTER, "do_structures", ASTRISK.to_string()),
             ignore_cmds: loader.get_optional(FILTER, "ignore_cmds"),
+            do_keys: loader.get_optional(FILTER, "do_keys"),
+            ignore_keys: loader.get_optional(FILTER, "ignore_keys"),
             where_conditions: loader.get_optional(FILTER, "where_conditions"),
+            row_filters: loader.get_optional(FILTER, "row_filters"),
+            include_server_settings: loader.get_optional(FILTER, "include_server_settings"),
         })
     }
 
@@ -610,6 +918,8 @@ impl TaskConfig {
             tb_map: loader.get_optional(ROUTER, "tb_map"),
             col_map: loader.get_optional(ROUTER, "col_map"),
             topic_map: loader.get_optional(ROUTER, "topic_map"),
+            src_id_col: loader.get_optional(ROUTER, "src_id_col"),
+            shard_map: loader.get_optional(ROUTER, "shard_map"),
         })
     }
 
@@ -623,6 +933,8 @@ impl TaskConfig {
             resume_config_file: loader.get_optional(RESUMER, "resume_config_file"),
             resume_from_log: loader.get_optional(RESUMER, "resume_from_log"),
             resume_log_dir,
+            position_store_tb: loader.get_optional(RESUMER, "position_store_tb"),
+            position_store_channel: loader.get_optional(RESUMER, "position_store_channel"),
         })
     }
 
@@ -659,6 +971,14 @@ impl TaskConfig {
         Ok(Some(ProcessorConfig {
             lua_code_file,
             lua_code,
+            truncate_col_limits: loader.get_optional(PROCESSOR, "truncate_col_limits"),
+            truncate_auto_detect_limits: loader
+                .get_optional(PROCESSOR, "truncate_auto_detect_limits"),
+            truncation_log_path: loader.get_optional(PROCESSOR, "truncation_log_path"),
+            source_time_zone: loader.get_optional(PROCESSOR, "source_time_zone"),
+            target_time_zone: loader.get_optional(PROCESSOR, "target_time_zone"),
+            zero_date_value: loader.get_optional(PROCESSOR, "zero_date_value"),
+            transform_cols: loader.get_optional(PROCESSOR, "transform_cols"),
         }))
     }
 
@@ -0,0 +1,146 @@
+use std::{collections::HashMap, env};
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::error::Error;
+
+use super::credential_resolver::{CredentialProvider, Secret};
+
+const VAULT_ADDR: &str = "VAULT_ADDR";
+const VAULT_TOKEN: &str = "VAULT_TOKEN";
+
+// read secrets by vault http api: GET {VAULT_ADDR}/v1/{path}, supports kv (v1, v2)
+// and dynamic secrets engines, E.g. secret/data/dts/mysql, database/creds/dts
+pub struct VaultCredentialProvider {
+    addr: String,
+    token: String,
+    http_client: reqwest::Client,
+}
+
+impl VaultCredentialProvider {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let get_env = |key: &str| match env::var(key) {
+            Ok(value) if !value.is_empty() => Ok(value),
+            _ => bail! {Error::ConfigError(format!(
+                "env: {} is required to fetch credentials from vault",
+                key
+            ))},
+        };
+        Ok(Self {
+            addr: get_env(VAULT_ADDR)?.trim_end_matches('/').to_string(),
+            token: get_env(VAULT_TOKEN)?,
+            http_client: reqwest::Client::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for VaultCredentialProvider {
+    async fn fetch(&self, path: &str) -> anyhow::Result<Secret> {
+        let url = format!("{}/v1/{}", self.addr, path.trim_start_matches('/'));
+        let response = self
+            .http_client
+            .get(&url)
+            .header("X-Vault-Token", &self.token)
+            .send()
+            .await
+            .with_context(|| format!("failed to request vault: {}", url))?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            bail! {Error::ConfigError(format!(
+                "failed to read secret: {} from vault, status: {}, response: {}",
+                path, status, body
+            ))}
+        }
+        parse_response(&body)
+    }
+
+    // PUT {VAULT_ADDR}/v1/sys/leases/renew, E.g. leases of database/creds/dts
+    async fn renew(&self, lease_id: &str, increment_secs: u64) -> anyhow::Result<u64> {
+        let url = format!("{}/v1/sys/leases/renew", self.addr);
+        let response = self
+            .http_client
+            .put(&url)
+            .header("X-Vault-Token", &self.token)
+            .body(json!({ "lease_id": lease_id, "increment": increment_secs }).to_string())
+            .send()
+            .await
+            .with_context(|| format!("failed to request vault: {}", url))?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            bail! {Error::ConfigError(format!(
+                "failed to renew lease: {} in vault, status: {}, response: {}",
+                lease_id, status, body
+            ))}
+        }
+        Ok(parse_response(&body)?.lease_duration_secs)
+    }
+}
+
+fn parse_response(body: &str) -> anyhow::Result<Secret> {
+    let response: Value = serde_json::from_str(body)?;
+    let lease_duration_secs = response["lease_duration"].as_u64().unwrap_or(0);
+    let lease_id = response["lease_id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let renewable = response["renewable"].as_bool().unwrap_or(false);
+    // kv v2 wraps the secret in data.data
+    let data = match &response["data"]["data"] {
+        Value::Object(_) => &response["data"]["data"],
+        _ => &response["data"],
+    };
+
+    let mut values = HashMap::new();
+    if let Value::Object(map) = data {
+        for (key, value) in map {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                _ => value.to_string(),
+            };
+            values.insert(key.clone(), value);
+        }
+    }
+    Ok(Secret {
+        values,
+        lease_duration_secs,
+        lease_id,
+        renewable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_response;
+
+    #[test]
+    fn test_parse_response() {
+        // kv v2
+        let body = r#"{"lease_duration":0,"data":{"data":{"username":"dts","password":"123456"},"metadata":{"version":1}}}"#;
+        let secret = parse_response(body).unwrap();
+        assert_eq!(secret.values["username"], "dts");
+        assert_eq!(secret.values["password"], "123456");
+        assert_eq!(secret.lease_duration_secs, 0);
+        assert!(secret.lease_id.is_empty());
+
+        // database secrets engine
+        let body = r#"{"lease_id":"database/creds/dts/abc","lease_duration":3600,"renewable":true,"data":{"username":"v-dts-abc","password":"xyz"}}"#;
+        let secret = parse_response(body).unwrap();
+        assert_eq!(secret.values["username"], "v-dts-abc");
+        assert_eq!(secret.lease_duration_secs, 3600);
+        assert_eq!(secret.lease_id, "database/creds/dts/abc");
+        assert!(secret.renewable);
+
+        // lease renewal
+        let body = r#"{"lease_id":"database/creds/dts/abc","lease_duration":1800,"renewable":true,"data":null}"#;
+        let secret = parse_response(body).unwrap();
+        assert_eq!(secret.lease_duration_secs, 1800);
+        assert!(secret.values.is_empty());
+    }
+}
@@ -65,6 +65,12 @@ pub enum Error {
     #[error("data marker error: {0}")]
     DataMarkerError(String),
 
+    #[error("integrity error: {0}")]
+    IntegrityError(String),
+
     #[error("mlua error: {0}")]
     MluaError(#[from] mlua::Error),
+
+    #[error("extractor stopped")]
+    ExtractorStopped,
 }
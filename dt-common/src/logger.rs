@@ -1,3 +1,17 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// per row / per event debug logs, disabled with json log format to keep the volume of
+// collected logs under control
+static ROW_LOG_ENABLED: AtomicBool = AtomicBool::new(true);
+
+pub fn set_row_log_enabled(enabled: bool) {
+    ROW_LOG_ENABLED.store(enabled, Ordering::Release);
+}
+
+pub fn is_row_log_enabled() -> bool {
+    ROW_LOG_ENABLED.load(Ordering::Acquire)
+}
+
 #[macro_export(local_inner_macros)]
 macro_rules! log_miss {
     ($($arg:tt)+) => (log::log!(target: "miss_logger", log::Level::Info, $($arg)+));
@@ -57,3 +71,12 @@ macro_rules! log_warn {
 macro_rules! log_debug {
     ($($arg:tt)+) => (log::log!(target: "default_logger", log::Level::Debug, $($arg)+))
 }
+
+#[macro_export(local_inner_macros)]
+macro_rules! log_debug_row {
+    ($($arg:tt)+) => (
+        if $crate::logger::is_row_log_enabled() {
+            log::log!(target: "default_logger", log::Level::Debug, $($arg)+)
+        }
+    )
+}
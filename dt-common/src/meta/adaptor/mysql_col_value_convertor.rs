@@ -7,10 +7,12 @@ use chrono::{TimeZone, Utc};
 use mysql_binlog_connector_rust::column::{
     column_value::ColumnValue, json::json_binary::JsonBinary,
 };
-use sqlx::{mysql::MySqlRow, types::BigDecimal, Row};
+use sqlx::{mysql::MySqlRow, Row};
 
 use crate::meta::{col_value::ColValue, mysql::mysql_col_type::MysqlColType};
 
+const ZERO_TIMESTAMP: &str = "0000-00-00 00:00:00";
+
 pub struct MysqlColValueConvertor {}
 
 impl MysqlColValueConvertor {
@@ -63,6 +65,22 @@ impl MysqlColValueConvertor {
         Ok(ColValue::Timestamp(datetime))
     }
 
+    // json null is kept as the document: null, sql NULL is not passed here
+    pub fn parse_json(buf: Vec<u8>) -> anyhow::Result<ColValue> {
+        match String::from_utf8(buf) {
+            Ok(value) => Ok(ColValue::Json2(value)),
+            Err(e) => bail! {Error::Unexpected(format!("invalid json document: {}", e))},
+        }
+    }
+
+    // keep the text returned by mysql, BigDecimal may reformat it, e.g. 0.0000000001 -> 1E-10
+    pub fn parse_decimal(buf: Vec<u8>) -> anyhow::Result<ColValue> {
+        match String::from_utf8(buf) {
+            Ok(value) => Ok(ColValue::Decimal(value)),
+            Err(e) => bail! {Error::Unexpected(format!("invalid decimal: {}", e))},
+        }
+    }
+
     fn parse_date_time_fields(cursor: &mut Cursor<Vec<u8>>) -> anyhow::Result<String> {
         let length = cursor.read_u8()? as usize;
         let date = Self::parese_date_fields(cursor, length)?;
@@ -146,6 +164,10 @@ impl MysqlColValueConvertor {
             ColumnValue::DateTime(v) => ColValue::DateTime(v),
             ColumnValue::Year(v) => ColValue::Year(v),
 
+            // zero timestamp: 0000-00-00 00:00:00 is stored as 0 in binlog,
+            // keep it the same as extracted by query instead of 1970-01-01 00:00:00
+            ColumnValue::Timestamp(0) => ColValue::Timestamp(ZERO_TIMESTAMP.to_string()),
+
             ColumnValue::Timestamp(v) => {
                 if let MysqlColType::Timestamp {
                     timezone_offset, ..
@@ -233,8 +255,12 @@ impl MysqlColValueConvertor {
             },
 
             ColumnValue::Json(v) => {
-                let v = JsonBinary::parse_as_string(&v)?;
-                ColValue::Json2(v)
+                // an empty json value in binlog means json null, the same as mysqlbinlog prints
+                if v.is_empty() {
+                    ColValue::Json2("null".to_string())
+                } else {
+                    ColValue::Json2(JsonBinary::parse_as_string(&v)?)
+                }
             }
 
             ColumnValue::None => ColValue::None,
@@ -319,13 +345,21 @@ impl MysqlColValueConvertor {
 
                 MysqlColType::Json => ColValue::Json2(value_str),
 
+                // written by to_position_str
                 MysqlColType::Binary { .. }
                 | MysqlColType::VarBinary { .. }
                 | MysqlColType::TinyBlob
                 | MysqlColType::MediumBlob
                 | MysqlColType::Blob
-                | MysqlColType::LongBlob
-                | MysqlColType::Unknown => {
+                | MysqlColType::LongBlob => match hex::decode(&value_str) {
+                    Ok(value) => ColValue::Blob(value),
+                    Err(_) => bail! {Error::Unexpected(format!(
+                        "invalid hex value: {} for column type: {:?}",
+                        value_str, col_type
+                    ))},
+                },
+
+                MysqlColType::Unknown => {
                     bail! {Error::Unexpected(format!(
                         "unsupported column type: {:?}",
                         col_type
@@ -336,6 +370,15 @@ impl MysqlColValueConvertor {
         Ok(col_value)
     }
 
+    // serialize an order_col value for snapshot positions, from_str restores it exactly,
+    // binary values are always hex encoded since they may be valid utf8 or not
+    pub fn to_position_str(col_value: &ColValue) -> Option<String> {
+        match col_value {
+            ColValue::Blob(v) => Some(hex::encode(v)),
+            _ => col_value.to_option_string(),
+        }
+    }
+
     pub fn from_query(
         row: &MySqlRow,
         col: &str,
@@ -397,8 +440,8 @@ impl MysqlColValueConvertor {
                 Ok(ColValue::Double(value))
             }
             MysqlColType::Decimal { .. } => {
-                let value: BigDecimal = row.get_unchecked(col);
-                Ok(ColValue::Decimal(value.to_string()))
+                let value: Vec<u8> = row.get_unchecked(col);
+                Self::parse_decimal(value)
             }
             MysqlColType::Time { .. } => match db_type {
                 DbType::Foxlake => {
@@ -488,13 +531,15 @@ impl MysqlColValueConvertor {
                 Ok(ColValue::Enum2(value))
             }
             MysqlColType::Json => {
-                let value: serde_json::Value = row.try_get(col)?;
+                // read the document text as returned by mysql, same as parsed from binlog,
+                // serde_json::Value would reorder keys and lose precision of numbers
+                let buf: Vec<u8> = row.get_unchecked(col);
                 // TODO, decimal will lose precision when insert into target mysql as string.
                 // insert into json_table(id, json_col) values(1, "212765.700000000010000"); the result will be:
                 // +-----+--------------------------+
                 // | id | json_col                  |
                 // |  1 | 212765.7                  |
-                Ok(ColValue::Json2(value.to_string()))
+                Self::parse_json(buf)
             }
             MysqlColType::Unknown => Ok(ColValue::None),
         }
@@ -509,10 +554,10 @@ impl MysqlColValueConvertor {
         match col_type {
             MysqlColType::Timestamp {
                 is_nullable: false, ..
-            } => Ok(ColValue::Timestamp("0000-00-00 00:00:00".to_string())),
+            } => Ok(ColValue::Timestamp(ZERO_TIMESTAMP.to_string())),
             MysqlColType::DateTime {
                 is_nullable: false, ..
-            } => Ok(ColValue::DateTime("0000-00-00 00:00:00".to_string())),
+            } => Ok(ColValue::DateTime(ZERO_TIMESTAMP.to_string())),
             MysqlColType::Date { is_nullable: false } => {
                 Ok(ColValue::DateTime("0000-00-00".to_string()))
             }
@@ -520,3 +565,129 @@ impl MysqlColValueConvertor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_str_round_trip() {
+        let cases = [
+            (
+                MysqlColType::Varchar {
+                    length: 255,
+                    charset: "utf8mb4".into(),
+                },
+                ColValue::String("abc 中文 '\"\\ ".into()),
+            ),
+            (
+                MysqlColType::Char {
+                    length: 10,
+                    charset: "utf8mb4".into(),
+                },
+                ColValue::String("".into()),
+            ),
+            (
+                MysqlColType::VarBinary { length: 16 },
+                ColValue::Blob(vec![0, 1, 2, 255, 254]),
+            ),
+            // valid utf8 bytes should not be restored as text
+            (
+                MysqlColType::Blob,
+                ColValue::Blob("abcd".as_bytes().to_vec()),
+            ),
+            (
+                MysqlColType::Binary { length: 4 },
+                ColValue::Blob(vec![0x61, 0, 0, 0]),
+            ),
+            (MysqlColType::Blob, ColValue::Blob(vec![])),
+        ];
+
+        for (col_type, col_value) in cases {
+            let value_str = MysqlColValueConvertor::to_position_str(&col_value).unwrap();
+            let restored = MysqlColValueConvertor::from_str(&col_type, &value_str).unwrap();
+            assert_eq!(restored, col_value);
+        }
+    }
+
+    #[test]
+    fn test_from_binlog_zero_timestamp() {
+        let col_type = MysqlColType::Timestamp {
+            precision: 0,
+            timezone_offset: 0,
+            is_nullable: false,
+        };
+        assert_eq!(
+            MysqlColValueConvertor::from_binlog(&col_type, ColumnValue::Timestamp(0)).unwrap(),
+            ColValue::Timestamp("0000-00-00 00:00:00".into())
+        );
+        assert_eq!(
+            MysqlColValueConvertor::from_binlog(&col_type, ColumnValue::Timestamp(1_000_000))
+                .unwrap(),
+            ColValue::Timestamp("1970-01-01 00:00:01".into())
+        );
+    }
+
+    #[test]
+    fn test_unsigned_bigint() {
+        let col_type = MysqlColType::BigInt { unsigned: true };
+        let expected = ColValue::UnsignedLongLong(18446744073709551615);
+
+        // the value is u64::MAX, parsed from binlog as -1
+        assert_eq!(
+            MysqlColValueConvertor::from_binlog(&col_type, ColumnValue::LongLong(-1)).unwrap(),
+            expected
+        );
+        assert_eq!(
+            MysqlColValueConvertor::from_str(&col_type, "18446744073709551615").unwrap(),
+            expected
+        );
+        assert_eq!(
+            MysqlColValueConvertor::to_position_str(&expected).unwrap(),
+            "18446744073709551615"
+        );
+
+        let col_type = MysqlColType::BigInt { unsigned: false };
+        assert_eq!(
+            MysqlColValueConvertor::from_binlog(&col_type, ColumnValue::LongLong(-1)).unwrap(),
+            ColValue::LongLong(-1)
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        // key order and number literals are kept as returned by mysql
+        let doc = r#"{"b": 1, "a": 212765.700000000010000, "c": [true, null]}"#;
+        assert_eq!(
+            MysqlColValueConvertor::parse_json(doc.as_bytes().to_vec()).unwrap(),
+            ColValue::Json2(doc.into())
+        );
+
+        // json null is not sql NULL
+        assert_eq!(
+            MysqlColValueConvertor::parse_json(b"null".to_vec()).unwrap(),
+            ColValue::Json2("null".into())
+        );
+        assert_eq!(
+            MysqlColValueConvertor::from_binlog(&MysqlColType::Json, ColumnValue::Json(vec![]))
+                .unwrap(),
+            ColValue::Json2("null".into())
+        );
+        assert!(MysqlColValueConvertor::parse_json(vec![0xff, 0xfe]).is_err());
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        for value in [
+            "12345678901234567890.0123456789",
+            "-12345678901234567890.0123456789",
+            "0.0000000001",
+            "1000000000.0000000000",
+        ] {
+            assert_eq!(
+                MysqlColValueConvertor::parse_decimal(value.as_bytes().to_vec()).unwrap(),
+                ColValue::Decimal(value.into())
+            );
+        }
+    }
+}
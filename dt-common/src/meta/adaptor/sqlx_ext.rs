@@ -19,6 +19,13 @@ impl<'q> SqlxPgExt<'q> for Query<'q, Postgres, PgArguments> {
                 ColValue::Short(v) => self.bind(v),
                 ColValue::Long(v) => self.bind(v),
                 ColValue::LongLong(v) => self.bind(v),
+                // pg has no unsigned types, bind values from mysql unsigned columns by wider types,
+                // bigint unsigned may exceed i64, bind it as text and cast to the column type
+                ColValue::UnsignedTiny(v) => self.bind(*v as i16),
+                ColValue::UnsignedShort(v) => self.bind(*v as i32),
+                ColValue::UnsignedLong(v) => self.bind(*v as i64),
+                ColValue::UnsignedLongLong(v) => self.bind(v.to_string()),
+                ColValue::Year(v) => self.bind(*v as i32),
                 ColValue::Float(v) => self.bind(v),
                 ColValue::Double(v) => self.bind(v),
                 ColValue::Decimal(v) => self.bind(v),
@@ -41,6 +41,19 @@ impl DdlData {
         (schema, tb)
     }
 
+    // (schema, tb) of tables dropped by the ddl, tb is empty if the whole schema is dropped
+    pub fn get_dropped_schema_tbs(&self) -> Vec<(String, String)> {
+        match self.ddl_type {
+            DdlType::DropDatabase | DdlType::DropSchema | DdlType::DropTable => self
+                .clone()
+                .split_to_multi()
+                .iter()
+                .map(|i| i.get_schema_tb())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn split_to_multi(self) -> Vec<DdlData> {
         let mut res = Vec::new();
         for statement in self.statement.split_to_multi() {
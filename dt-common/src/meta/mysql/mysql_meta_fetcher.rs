@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{config::config_enums::DbType, error::Error, meta::ddl_meta::ddl_data::DdlData};
 use anyhow::{bail, Ok};
@@ -29,6 +29,7 @@ const CHARACTER_SET_NAME: &str = "CHARACTER_SET_NAME";
 const NUMERIC_PRECISION: &str = "NUMERIC_PRECISION";
 const NUMERIC_SCALE: &str = "NUMERIC_SCALE";
 const IS_NULLABLE: &str = "IS_NULLABLE";
+const EXTRA: &str = "EXTRA";
 
 impl MysqlMetaFetcher {
     pub async fn new(conn_pool: Pool<MySql>) -> anyhow::Result<Self> {
@@ -83,11 +84,13 @@ impl MysqlMetaFetcher {
     ) -> anyhow::Result<&'a MysqlTbMeta> {
         let full_name = format!("{}.{}", schema, tb);
         if !self.cache.contains_key(&full_name) {
-            let (cols, col_origin_type_map, col_type_map) =
+            let (cols, col_origin_type_map, col_type_map, auto_increment_col, generated_cols) =
                 Self::parse_cols(&self.conn_pool, &self.db_type, schema, tb).await?;
-            let key_map = Self::parse_keys(&self.conn_pool, schema, tb).await?;
+            let (key_map, not_null_keys) = Self::parse_keys(&self.conn_pool, schema, tb).await?;
             let (order_col, partition_col, id_cols) =
                 RdbMetaManager::parse_rdb_cols(&key_map, &cols)?;
+            let order_col =
+                RdbMetaManager::get_not_null_order_col(order_col, &key_map, &not_null_keys);
             // disable get_foreign_keys since we don't support foreign key check,
             // also quering them is very slow, which may casue terrible performance issue if there were many tables in a CDC task.
             let (foreign_keys, ref_by_foreign_keys) = (vec![], vec![]);
@@ -109,12 +112,15 @@ impl MysqlMetaFetcher {
             let tb_meta = MysqlTbMeta {
                 basic,
                 col_type_map,
+                auto_increment_col,
+                generated_cols,
             };
             self.cache.insert(full_name.clone(), tb_meta);
         }
         Ok(self.cache.get(&full_name).unwrap())
     }
 
+    #[allow(clippy::type_complexity)]
     async fn parse_cols(
         conn_pool: &Pool<MySql>,
         db_type: &DbType,
@@ -124,10 +130,14 @@ impl MysqlMetaFetcher {
         Vec<String>,
         HashMap<String, String>,
         HashMap<String, MysqlColType>,
+        Option<String>,
+        HashSet<String>,
     )> {
         let mut cols = Vec::new();
         let mut col_origin_type_map = HashMap::new();
         let mut col_type_map = HashMap::new();
+        let mut auto_increment_col = None;
+        let mut generated_cols = HashSet::new();
 
         let sql = if matches!(db_type, DbType::Mysql) {
             "SELECT * FROM information_schema.columns
@@ -153,6 +163,19 @@ impl MysqlMetaFetcher {
             // Column and index names are not case sensitive on any platform, nor are column aliases.
             col = col.to_lowercase();
             cols.push(col.clone());
+
+            // EXTRA: auto_increment, VIRTUAL GENERATED, STORED GENERATED, DEFAULT_GENERATED ...
+            let extra = row
+                .try_get::<String, _>(EXTRA)
+                .unwrap_or_default()
+                .to_lowercase();
+            if extra.contains("auto_increment") {
+                auto_increment_col = Some(col.clone());
+            }
+            if extra.contains("virtual generated") || extra.contains("stored generated") {
+                generated_cols.insert(col.clone());
+            }
+
             let (origin_type, col_type) = Self::get_col_type(&row).await?;
             col_origin_type_map.insert(col.clone(), origin_type);
             col_type_map.insert(col, col_type);
@@ -164,7 +187,13 @@ impl MysqlMetaFetcher {
                 schema, tb
             )) }
         }
-        Ok((cols, col_origin_type_map, col_type_map))
+        Ok((
+            cols,
+            col_origin_type_map,
+            col_type_map,
+            auto_increment_col,
+            generated_cols,
+        ))
     }
 
     async fn get_col_type(row: &MySqlRow) -> anyhow::Result<(String, MysqlColType)> {
@@ -314,12 +343,15 @@ impl MysqlMetaFetcher {
         row.try_get_unchecked::<u64, &str>(col).unwrap_or_default()
     }
 
+    // returns key_map and names of the keys without nullable cols, in the order of SHOW INDEXES
     async fn parse_keys(
         conn_pool: &Pool<MySql>,
         schema: &str,
         tb: &str,
-    ) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    ) -> anyhow::Result<(HashMap<String, Vec<String>>, Vec<String>)> {
         let mut key_map: HashMap<String, Vec<String>> = HashMap::new();
+        let mut key_names = Vec::new();
+        let mut nullable_keys = HashSet::new();
         let sql = format!("SHOW INDEXES FROM `{}`.`{}`", schema, tb);
         let mut rows = sqlx::query(&sql).disable_arguments().fetch(conn_pool);
         while let Some(row) = rows.try_next().await? {
@@ -344,13 +376,25 @@ impl MysqlMetaFetcher {
             let mut col_name: String = row.try_get("Column_name")?;
             key_name = key_name.to_lowercase();
             col_name = col_name.to_lowercase();
+            // Null: "YES" if the column may contain NULL values, otherwise ""
+            let null: String = row.try_get("Null").unwrap_or_default();
+            if null.to_lowercase() == "yes" {
+                nullable_keys.insert(key_name.clone());
+            }
+
             if let Some(key_cols) = key_map.get_mut(&key_name) {
                 key_cols.push(col_name);
             } else {
+                key_names.push(key_name.clone());
                 key_map.insert(key_name, vec![col_name]);
             }
         }
-        Ok(key_map)
+
+        let not_null_keys = key_names
+            .into_iter()
+            .filter(|i| !nullable_keys.contains(i))
+            .collect();
+        Ok((key_map, not_null_keys))
     }
 
     #[allow(dead_code)]
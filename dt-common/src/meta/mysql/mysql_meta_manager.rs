@@ -44,6 +44,11 @@ impl MysqlMetaManager {
     pub fn invalidate_cache_by_ddl_data(&mut self, ddl_data: &DdlData) {
         let (schema, tb) = ddl_data.get_schema_tb();
         self.invalidate_cache(&schema, &tb);
+        // a table may have been cached by the rename-to name before, E.g. dropped and renamed
+        let (new_schema, new_tb) = ddl_data.get_rename_to_schema_tb();
+        if !new_tb.is_empty() {
+            self.invalidate_cache(&new_schema, &new_tb);
+        }
     }
 
     pub async fn get_tb_meta_by_row_data<'a>(
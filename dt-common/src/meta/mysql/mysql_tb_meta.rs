@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::Context;
 use serde::Serialize;
@@ -12,6 +12,9 @@ use super::mysql_col_type::MysqlColType;
 pub struct MysqlTbMeta {
     pub basic: RdbTbMeta,
     pub col_type_map: HashMap<String, MysqlColType>,
+    pub auto_increment_col: Option<String>,
+    // VIRTUAL / STORED generated cols, computed by mysql and can not be inserted or updated
+    pub generated_cols: HashSet<String>,
 }
 
 impl std::fmt::Display for MysqlTbMeta {
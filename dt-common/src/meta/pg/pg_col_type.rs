@@ -9,6 +9,9 @@ pub struct PgColType {
     pub alias: String,
     pub oid: i32,
     pub parent_oid: i32,
+    // the underlying non domain type of domains, which may be defined over other domains
+    #[serde(default)]
+    pub base_oid: i32,
     pub element_oid: i32,
     pub category: String,
     pub enum_values: Option<Vec<String>>,
@@ -33,4 +36,17 @@ impl PgColType {
     pub fn is_user_defined(&self) -> bool {
         "U" == self.category
     }
+
+    pub fn is_domain(&self) -> bool {
+        self.parent_oid != 0
+    }
+
+    // value type of the base type for domains, used when target dbs have no domains
+    pub fn base_value_type(&self) -> PgValueType {
+        if self.is_domain() {
+            PgValueType::from_oid(self.base_oid)
+        } else {
+            self.value_type.clone()
+        }
+    }
 }
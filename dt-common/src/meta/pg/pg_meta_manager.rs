@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{error::Error, meta::ddl_meta::ddl_data::DdlData};
 use anyhow::{bail, Context};
@@ -10,7 +10,11 @@ use crate::meta::{
     row_data::RowData,
 };
 
-use super::{pg_col_type::PgColType, pg_tb_meta::PgTbMeta, type_registry::TypeRegistry};
+use super::{
+    pg_col_type::PgColType,
+    pg_tb_meta::{ExcludeConstraint, PgTbMeta},
+    type_registry::TypeRegistry,
+};
 
 #[derive(Clone)]
 pub struct PgMetaManager {
@@ -18,6 +22,8 @@ pub struct PgMetaManager {
     pub type_registry: TypeRegistry,
     pub name_to_tb_meta: HashMap<String, PgTbMeta>,
     pub oid_to_tb_meta: HashMap<i32, PgTbMeta>,
+    // generated columns are supported since pg 12
+    pub support_generated_cols: bool,
 }
 
 impl PgMetaManager {
@@ -28,8 +34,10 @@ impl PgMetaManager {
             type_registry,
             name_to_tb_meta: HashMap::new(),
             oid_to_tb_meta: HashMap::new(),
+            support_generated_cols: false,
         };
         me.type_registry = me.type_registry.init().await?;
+        me.support_generated_cols = Self::get_server_version_num(&me.conn_pool).await? >= 120000;
         Ok(me)
     }
 
@@ -54,6 +62,27 @@ impl PgMetaManager {
         Ok(())
     }
 
+    // max length of character columns, e.g. 255 for varchar(255), unlimited columns not included
+    pub async fn get_col_max_lengths(
+        &self,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<HashMap<String, usize>> {
+        let sql = format!(
+            "SELECT column_name, character_maximum_length FROM information_schema.columns
+            WHERE table_schema='{}' AND table_name = '{}' AND character_maximum_length IS NOT NULL",
+            schema, tb
+        );
+        let mut results = HashMap::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let col: String = row.try_get("column_name")?;
+            let max_length: i32 = row.try_get_unchecked("character_maximum_length")?;
+            results.insert(col, max_length as usize);
+        }
+        Ok(results)
+    }
+
     pub fn get_tb_meta_by_oid(&mut self, oid: i32) -> anyhow::Result<PgTbMeta> {
         Ok(self
             .oid_to_tb_meta
@@ -77,11 +106,20 @@ impl PgMetaManager {
         let full_name = format!(r#""{}"."{}""#, schema, tb);
         if !self.name_to_tb_meta.contains_key(&full_name) {
             let oid = Self::get_oid(&self.conn_pool, schema, tb).await?;
-            let (cols, col_origin_type_map, col_type_map) =
+            let is_unlogged = Self::get_is_unlogged(&self.conn_pool, oid).await?;
+            let (cols, col_origin_type_map, col_type_map, col_collations) =
                 Self::parse_cols(&self.conn_pool, &mut self.type_registry, schema, tb).await?;
-            let key_map = Self::parse_keys(&self.conn_pool, schema, tb).await?;
+            let (key_map, not_null_keys) = Self::parse_keys(&self.conn_pool, schema, tb).await?;
+            let generated_cols = if self.support_generated_cols {
+                Self::get_generated_cols(&self.conn_pool, oid).await?
+            } else {
+                HashMap::new()
+            };
+            let exclude_constraints = Self::get_exclude_constraints(&self.conn_pool, oid).await?;
             let (order_col, partition_col, id_cols) =
                 RdbMetaManager::parse_rdb_cols(&key_map, &cols)?;
+            let order_col =
+                RdbMetaManager::get_not_null_order_col(order_col, &key_map, &not_null_keys);
             // disable get_foreign_keys since we don't support foreign key check
             let (foreign_keys, ref_by_foreign_keys) = (vec![], vec![]);
             // let (foreign_keys, ref_by_foreign_keys) =
@@ -103,6 +141,10 @@ impl PgMetaManager {
                 oid,
                 col_type_map,
                 basic,
+                is_unlogged,
+                col_collations,
+                generated_cols,
+                exclude_constraints,
             };
             self.oid_to_tb_meta.insert(oid, tb_meta.clone());
             self.name_to_tb_meta.insert(full_name.clone(), tb_meta);
@@ -123,6 +165,11 @@ impl PgMetaManager {
     pub fn invalidate_cache_by_ddl_data(&mut self, ddl_data: &DdlData) {
         let (schema, tb) = ddl_data.get_schema_tb();
         self.invalidate_cache(&schema, &tb);
+        // a table may have been cached by the rename-to name before, E.g. dropped and renamed
+        let (new_schema, new_tb) = ddl_data.get_rename_to_schema_tb();
+        if !new_tb.is_empty() {
+            self.invalidate_cache(&new_schema, &new_tb);
+        }
     }
 
     async fn parse_cols(
@@ -134,10 +181,12 @@ impl PgMetaManager {
         Vec<String>,
         HashMap<String, String>,
         HashMap<String, PgColType>,
+        HashMap<String, String>,
     )> {
         let mut cols = Vec::new();
         let mut col_origin_type_map = HashMap::new();
         let mut col_type_map = HashMap::new();
+        let mut col_collations = HashMap::new();
 
         // get cols of the table
         let sql = format!(
@@ -152,12 +201,13 @@ impl PgMetaManager {
             cols.push(col);
         }
 
-        // get col_type_oid of the table
+        // get col_type_oid and non-default collation of the table
         let sql = format!(
-            "SELECT a.attname AS col_name, a.atttypid as col_type_oid
-            FROM pg_class t, pg_attribute a
-            WHERE a.attrelid = t.oid
-                AND t.relname = '{}'
+            "SELECT a.attname AS col_name, a.atttypid as col_type_oid, co.collname AS collation_name
+            FROM pg_class t
+            JOIN pg_attribute a ON a.attrelid = t.oid
+            LEFT JOIN pg_collation co ON co.oid = a.attcollation AND co.collname <> 'default'
+            WHERE t.relname = '{}'
                 AND t.relnamespace = (SELECT oid FROM pg_namespace WHERE nspname = '{}');",
             tb, schema
         );
@@ -175,22 +225,28 @@ impl PgMetaManager {
                 .get(&col_type_oid)
                 .unwrap()
                 .clone();
+            let collation: Option<String> = row.try_get("collation_name")?;
+            if let Some(collation) = collation {
+                col_collations.insert(col.clone(), collation);
+            }
             col_origin_type_map.insert(col.clone(), col_type.alias.clone());
             col_type_map.insert(col, col_type);
         }
 
-        Ok((cols, col_origin_type_map, col_type_map))
+        Ok((cols, col_origin_type_map, col_type_map, col_collations))
     }
 
+    // returns key_map and names of the keys without nullable cols, in the order of key cols
     async fn parse_keys(
         conn_pool: &Pool<Postgres>,
         schema: &str,
         tb: &str,
-    ) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    ) -> anyhow::Result<(HashMap<String, Vec<String>>, Vec<String>)> {
         let sql = format!(
             "SELECT kcu.column_name as col_name, 
                 kcu.constraint_name as constraint_name,
-                tc.constraint_type as constraint_type
+                tc.constraint_type as constraint_type,
+                c.is_nullable as is_nullable
             FROM 
                 information_schema.table_constraints AS tc
             JOIN 
@@ -199,16 +255,24 @@ impl PgMetaManager {
                 tc.constraint_name = kcu.constraint_name
                 AND tc.table_schema = kcu.table_schema
                 AND tc.table_name = kcu.table_name
+            JOIN 
+                information_schema.columns AS c
+            ON 
+                c.table_schema = kcu.table_schema
+                AND c.table_name = kcu.table_name
+                AND c.column_name = kcu.column_name
             WHERE 
                 tc.table_schema = '{}' 
                 AND tc.table_name = '{}'
                 AND tc.constraint_type IN ('PRIMARY KEY', 'UNIQUE')
             ORDER BY 
+                tc.constraint_type = 'PRIMARY KEY' DESC,
+                kcu.constraint_name,
                 kcu.ordinal_position;",
             schema, tb
         );
 
-        let mut key_map: HashMap<String, Vec<String>> = HashMap::new();
+        let mut key_cols = Vec::new();
         let mut rows = sqlx::query(&sql).fetch(conn_pool);
         while let Some(row) = rows.try_next().await? {
             let col_name: String = row.try_get("col_name")?;
@@ -217,15 +281,40 @@ impl PgMetaManager {
             if constraint_type == "PRIMARY KEY" {
                 key_name = "primary".to_string();
             }
+            let is_nullable: String = row.try_get("is_nullable")?;
+            key_cols.push((key_name, col_name, is_nullable.to_lowercase() == "yes"));
+        }
+        Ok(Self::build_keys(key_cols))
+    }
+
+    // key_cols: (key_name, col_name, is_nullable) ordered by key and col position,
+    // so the primary key comes first and the order of keys is deterministic
+    fn build_keys(
+        key_cols: Vec<(String, String, bool)>,
+    ) -> (HashMap<String, Vec<String>>, Vec<String>) {
+        let mut key_map: HashMap<String, Vec<String>> = HashMap::new();
+        let mut key_names = Vec::new();
+        // unique constraints allow NULL values in nullable cols
+        let mut nullable_keys = HashSet::new();
+        for (key_name, col_name, is_nullable) in key_cols {
+            if is_nullable {
+                nullable_keys.insert(key_name.clone());
+            }
 
             // key_map
             if let Some(key_cols) = key_map.get_mut(&key_name) {
                 key_cols.push(col_name);
             } else {
+                key_names.push(key_name.clone());
                 key_map.insert(key_name, vec![col_name]);
             }
         }
-        Ok(key_map)
+
+        let not_null_keys = key_names
+            .into_iter()
+            .filter(|i| !nullable_keys.contains(i))
+            .collect();
+        (key_map, not_null_keys)
     }
 
     async fn get_oid(conn_pool: &Pool<Postgres>, schema: &str, tb: &str) -> anyhow::Result<i32> {
@@ -242,6 +331,76 @@ impl PgMetaManager {
         ))}
     }
 
+    async fn get_server_version_num(conn_pool: &Pool<Postgres>) -> anyhow::Result<i32> {
+        let sql = "SELECT current_setting('server_version_num')::int AS version_num";
+        let row = sqlx::query(sql).fetch_one(conn_pool).await?;
+        Ok(row.try_get("version_num")?)
+    }
+
+    async fn get_generated_cols(
+        conn_pool: &Pool<Postgres>,
+        oid: i32,
+    ) -> anyhow::Result<HashMap<String, String>> {
+        let sql = format!(
+            "SELECT a.attname AS col_name, pg_get_expr(d.adbin, d.adrelid) AS generation_expr
+            FROM pg_attribute a
+            JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+            WHERE a.attrelid = {} AND a.attgenerated = 's' AND NOT a.attisdropped",
+            oid
+        );
+
+        let mut generated_cols = HashMap::new();
+        let mut rows = sqlx::query(&sql).fetch(conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let col: String = row.try_get("col_name")?;
+            let generation_expr: String = row.try_get("generation_expr")?;
+            generated_cols.insert(col, generation_expr);
+        }
+        Ok(generated_cols)
+    }
+
+    async fn get_exclude_constraints(
+        conn_pool: &Pool<Postgres>,
+        oid: i32,
+    ) -> anyhow::Result<Vec<ExcludeConstraint>> {
+        let sql = format!(
+            "SELECT c.conname AS constraint_name,
+                pg_get_constraintdef(c.oid) AS definition,
+                ARRAY(
+                    SELECT a.attname::text FROM unnest(c.conkey) AS k(attnum)
+                    JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                ) AS cols
+            FROM pg_constraint c
+            WHERE c.conrelid = {} AND c.contype = 'x'
+            ORDER BY c.conname",
+            oid
+        );
+
+        let mut exclude_constraints = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            exclude_constraints.push(ExcludeConstraint {
+                name: row.try_get("constraint_name")?,
+                cols: row.try_get("cols")?,
+                definition: row.try_get("definition")?,
+            });
+        }
+        Ok(exclude_constraints)
+    }
+
+    async fn get_is_unlogged(conn_pool: &Pool<Postgres>, oid: i32) -> anyhow::Result<bool> {
+        let sql = format!(
+            "SELECT relpersistence::text AS relpersistence FROM pg_class WHERE oid = {}",
+            oid
+        );
+        let mut rows = sqlx::query(&sql).fetch(conn_pool);
+        if let Some(row) = rows.try_next().await? {
+            let relpersistence: String = row.try_get("relpersistence")?;
+            return Ok(relpersistence == "u");
+        }
+        Ok(false)
+    }
+
     #[allow(dead_code)]
     async fn get_foreign_keys(
         conn_pool: &Pool<Postgres>,
@@ -303,3 +462,49 @@ impl PgMetaManager {
         Ok((foreign_keys, ref_by_foreign_keys))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_keys() {
+        let key_col = |key: &str, col: &str, is_nullable: bool| {
+            (key.to_string(), col.to_string(), is_nullable)
+        };
+        // uk_email is on a nullable col whose NULL values may be interleaved with others,
+        // uk_code_region mixes a NOT NULL col with a nullable one
+        let (key_map, not_null_keys) = PgMetaManager::build_keys(vec![
+            key_col("uk_code_region", "code", false),
+            key_col("uk_code_region", "region", true),
+            key_col("uk_email", "email", true),
+            key_col("uk_name", "name", false),
+        ]);
+        assert_eq!(
+            key_map.get("uk_code_region"),
+            Some(&vec!["code".to_string(), "region".to_string()])
+        );
+        assert_eq!(not_null_keys, vec!["uk_name".to_string()]);
+
+        // `email > $1` would skip rows with NULL email, so it is never the order_col
+        assert_eq!(
+            RdbMetaManager::get_not_null_order_col(
+                Some("email".to_string()),
+                &key_map,
+                &not_null_keys
+            ),
+            Some("name".to_string())
+        );
+
+        let (key_map, not_null_keys) =
+            PgMetaManager::build_keys(vec![key_col("uk_email", "email", true)]);
+        assert_eq!(
+            RdbMetaManager::get_not_null_order_col(
+                Some("email".to_string()),
+                &key_map,
+                &not_null_keys
+            ),
+            None
+        );
+    }
+}
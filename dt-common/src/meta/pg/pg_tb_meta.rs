@@ -13,6 +13,22 @@ pub struct PgTbMeta {
     pub basic: RdbTbMeta,
     pub oid: i32,
     pub col_type_map: HashMap<String, PgColType>,
+    // pg_class.relpersistence = 'u'
+    pub is_unlogged: bool,
+    // col -> collation name, only for cols with non-default collations
+    pub col_collations: HashMap<String, String>,
+    // col -> generation expression, for GENERATED ALWAYS AS (expr) STORED cols
+    pub generated_cols: HashMap<String, String>,
+    // pg_constraint.contype = 'x'
+    pub exclude_constraints: Vec<ExcludeConstraint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcludeConstraint {
+    pub name: String,
+    pub cols: Vec<String>,
+    // E.g. EXCLUDE USING gist (room_id WITH =, during WITH &&)
+    pub definition: String,
 }
 
 impl std::fmt::Display for PgTbMeta {
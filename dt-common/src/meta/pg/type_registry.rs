@@ -41,9 +41,35 @@ impl TypeRegistry {
             let col_type = self.parse_col_meta(&row)?;
             self.oid_to_type.insert(col_type.oid, col_type.clone());
         }
+        Self::resolve_base_oids(&mut self.oid_to_type);
         Ok(self)
     }
 
+    // for a domain over other domains, typbasetype is the parent domain,
+    // follow the chain until a non domain type
+    fn resolve_base_oids(oid_to_type: &mut HashMap<i32, PgColType>) {
+        let base_oids: Vec<(i32, i32)> = oid_to_type
+            .values()
+            .filter(|col_type| col_type.is_domain())
+            .map(|col_type| {
+                let mut base_oid = col_type.parent_oid;
+                while let Some(parent) = oid_to_type.get(&base_oid) {
+                    if !parent.is_domain() {
+                        break;
+                    }
+                    base_oid = parent.parent_oid;
+                }
+                (col_type.oid, base_oid)
+            })
+            .collect();
+
+        for (oid, base_oid) in base_oids {
+            if let Some(col_type) = oid_to_type.get_mut(&oid) {
+                col_type.base_oid = base_oid;
+            }
+        }
+    }
+
     fn parse_col_meta(&mut self, row: &PgRow) -> anyhow::Result<PgColType> {
         let oid: i32 = row.get_unchecked("oid");
         let value_type = PgValueType::from_oid(oid);
@@ -67,6 +93,7 @@ impl TypeRegistry {
             alias,
             element_oid,
             parent_oid,
+            base_oid: parent_oid,
             category,
             enum_values,
         })
@@ -98,3 +125,45 @@ impl TypeRegistry {
         .to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_col_type(oid: i32, parent_oid: i32) -> PgColType {
+        PgColType {
+            value_type: PgValueType::from_oid(oid),
+            name: String::new(),
+            alias: String::new(),
+            oid,
+            parent_oid,
+            base_oid: parent_oid,
+            element_oid: 0,
+            category: String::new(),
+            enum_values: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_base_oids() {
+        // int4 <- positive_int <- small_positive_int <- tiny_positive_int
+        let mut oid_to_type = HashMap::new();
+        for col_type in [
+            mock_col_type(23, 0),
+            mock_col_type(16384, 23),
+            mock_col_type(16385, 16384),
+            mock_col_type(16386, 16385),
+        ] {
+            oid_to_type.insert(col_type.oid, col_type);
+        }
+        TypeRegistry::resolve_base_oids(&mut oid_to_type);
+
+        assert_eq!(oid_to_type[&23].base_oid, 0);
+        for oid in [16384, 16385, 16386] {
+            assert_eq!(oid_to_type[&oid].base_oid, 23);
+            assert_eq!(oid_to_type[&oid].base_value_type(), PgValueType::Int32);
+        }
+        // parents are kept for value conversion
+        assert_eq!(oid_to_type[&16386].parent_oid, 16385);
+    }
+}
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use anyhow::Context;
-use chrono::DateTime;
+use chrono::{DateTime, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -67,6 +67,27 @@ impl Position {
             String::new()
         }
     }
+
+    // timestamp of the source event for cdc positions, formatted by format_timestamp_millis
+    pub fn get_timestamp(&self) -> Option<&str> {
+        match self {
+            Position::MysqlCdc { timestamp, .. }
+            | Position::PgCdc { timestamp, .. }
+            | Position::MongoCdc { timestamp, .. }
+            | Position::Redis { timestamp, .. } => Some(timestamp),
+            _ => None,
+        }
+    }
+
+    pub fn get_timestamp_millis(&self) -> Option<i64> {
+        Self::parse_timestamp_millis(self.get_timestamp()?)
+    }
+
+    pub fn parse_timestamp_millis(timestamp: &str) -> Option<i64> {
+        NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.3f")
+            .ok()
+            .map(|i| i.and_utc().timestamp_millis())
+    }
 }
 
 impl std::fmt::Display for Position {
@@ -123,6 +144,25 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_get_timestamp_millis() {
+        let position = Position::PgCdc {
+            lsn: "0/1".into(),
+            timestamp: Position::format_timestamp_millis(1679981627123),
+        };
+        assert_eq!(position.get_timestamp_millis(), Some(1679981627123));
+
+        let position = Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: "mysql-bin.000001".into(),
+            next_event_position: 4,
+            gtid_set: String::new(),
+            timestamp: String::new(),
+        };
+        assert_eq!(position.get_timestamp_millis(), None);
+        assert_eq!(Position::None.get_timestamp_millis(), None);
+    }
+
     #[test]
     fn test_from_str() {
         let strs = [
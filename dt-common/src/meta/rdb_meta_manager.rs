@@ -99,4 +99,89 @@ impl RdbMetaManager {
         let partition_col = id_cols[0].clone();
         Ok((order_col, partition_col, id_cols))
     }
+
+    // order_col is used by snapshot extractors to extract by batch with `order_col > ?`, so it must
+    // be a single col unique key without NULL values, otherwise rows with NULL values are missed.
+    // if the table has no such primary key, the first NOT NULL single col unique key is used
+    // shared by the mysql and pg meta managers, both extract snapshots by `order_col > ?`
+    pub fn get_not_null_order_col(
+        order_col: Option<String>,
+        key_map: &HashMap<String, Vec<String>>,
+        not_null_keys: &[String],
+    ) -> Option<String> {
+        let candidates: Vec<&String> = not_null_keys
+            .iter()
+            .filter_map(|i| key_map.get(i))
+            .filter(|cols| cols.len() == 1)
+            .map(|cols| &cols[0])
+            .collect();
+
+        if let Some(col) = &order_col {
+            if candidates.contains(&col) {
+                return order_col;
+            }
+        }
+        candidates.first().map(|col| col.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_not_null_order_col() {
+        let key_map: HashMap<String, Vec<String>> = HashMap::from([
+            (
+                "primary".to_string(),
+                vec!["id".to_string(), "code".to_string()],
+            ),
+            ("uk_nullable".to_string(), vec!["email".to_string()]),
+            ("uk_name".to_string(), vec!["name".to_string()]),
+            ("uk_phone".to_string(), vec!["phone".to_string()]),
+        ]);
+        let not_null_keys =
+            |keys: &[&str]| -> Vec<String> { keys.iter().map(|i| i.to_string()).collect() };
+
+        // composite primary key, use the first NOT NULL single col unique key
+        assert_eq!(
+            RdbMetaManager::get_not_null_order_col(
+                None,
+                &key_map,
+                &not_null_keys(&["primary", "uk_name", "uk_phone"])
+            ),
+            Some("name".to_string())
+        );
+
+        // nullable unique key is not used
+        assert_eq!(
+            RdbMetaManager::get_not_null_order_col(
+                Some("email".to_string()),
+                &key_map,
+                &not_null_keys(&["uk_phone"])
+            ),
+            Some("phone".to_string())
+        );
+        assert_eq!(
+            RdbMetaManager::get_not_null_order_col(
+                Some("email".to_string()),
+                &key_map,
+                &not_null_keys(&["primary"])
+            ),
+            None
+        );
+
+        assert_eq!(
+            RdbMetaManager::get_not_null_order_col(
+                Some("phone".to_string()),
+                &key_map,
+                &not_null_keys(&["uk_name", "uk_phone"])
+            ),
+            Some("phone".to_string())
+        );
+        assert_eq!(
+            RdbMetaManager::get_not_null_order_col(None, &HashMap::new(), &[]),
+            None
+        );
+    }
 }
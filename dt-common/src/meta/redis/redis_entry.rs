@@ -13,7 +13,8 @@ pub struct RedisEntry {
     pub db_id: i64,
     pub timestamp_ms: u64,
 
-    pub expire_ms: i64,
+    // absolute unix time in milliseconds, 0 if the key has no expiry
+    pub expire_at_ms: i64,
     pub key: RedisString,
     pub value: RedisObject,
     pub value_type_byte: u8,
@@ -33,7 +34,7 @@ impl RedisEntry {
             db_id: 0,
             timestamp_ms: 0,
 
-            expire_ms: 0,
+            expire_at_ms: 0,
             key: RedisString::new(),
             value: RedisObject::Unknown,
             raw_bytes: Vec::new(),
@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+use crate::error::Error;
+use strum::IntoStaticStr;
+
+// how to sink a key whose ttl has elapsed between extracting and sinking
+#[derive(Clone, IntoStaticStr, Debug, PartialEq)]
+pub enum RedisExpiredKeyPolicy {
+    // write the key with a 1ms ttl
+    #[strum(serialize = "min_ttl")]
+    MinTtl,
+
+    #[strum(serialize = "skip")]
+    Skip,
+}
+
+impl FromStr for RedisExpiredKeyPolicy {
+    type Err = Error;
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        match str {
+            "skip" => Ok(Self::Skip),
+            _ => Ok(Self::MinTtl),
+        }
+    }
+}
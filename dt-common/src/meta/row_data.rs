@@ -23,6 +23,10 @@ pub struct RowData {
     pub before: Option<HashMap<String, ColValue>>,
     pub after: Option<HashMap<String, ColValue>>,
     pub data_size: usize,
+    // position of the source event, only kept for sinkers writing it to the target,
+    // E.g. kafka json messages, empty otherwise
+    #[serde(skip)]
+    pub position: String,
 }
 
 impl std::fmt::Display for RowData {
@@ -46,6 +50,7 @@ impl RowData {
             before,
             after,
             data_size: 0,
+            position: String::new(),
         };
         me.data_size = me.get_data_malloc_size();
         me
@@ -65,11 +70,12 @@ impl RowData {
             before: self.after.clone(),
             after: self.before.clone(),
             data_size: self.data_size,
+            position: self.position.clone(),
         }
     }
 
     pub fn split_update_row_data(self) -> (RowData, RowData) {
-        let delete = RowData::new(
+        let mut delete = RowData::new(
             self.schema.clone(),
             self.tb.clone(),
             RowType::Delete,
@@ -77,7 +83,9 @@ impl RowData {
             None,
         );
 
-        let insert = RowData::new(self.schema, self.tb, RowType::Insert, None, self.after);
+        let mut insert = RowData::new(self.schema, self.tb, RowType::Insert, None, self.after);
+        delete.position = self.position.clone();
+        insert.position = self.position;
         (delete, insert)
     }
 
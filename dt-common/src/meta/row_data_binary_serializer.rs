@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use mongodb::bson::{self, Document};
+use serde::{Deserialize, Serialize};
+
+use super::{col_value::ColValue, row_data::RowData, row_type::RowType};
+
+// encode RowData into CBOR for persisting queued data and transferring rows between processes,
+// the Serialize impl of ColValue writes plain values (E.g. "abc" for both String and Decimal)
+// which can not be deserialized into the original variants, so values are tagged here
+pub struct RowDataBinarySerializer {}
+
+#[derive(Serialize, Deserialize)]
+struct BinaryRowData {
+    schema: String,
+    tb: String,
+    row_type: RowType,
+    before: Option<HashMap<String, BinaryColValue>>,
+    after: Option<HashMap<String, BinaryColValue>>,
+    data_size: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+enum BinaryColValue {
+    None,
+    Bool(bool),
+    Tiny(i8),
+    UnsignedTiny(u8),
+    Short(i16),
+    UnsignedShort(u16),
+    Long(i32),
+    UnsignedLong(u32),
+    LongLong(i64),
+    UnsignedLongLong(u64),
+    Float(f32),
+    Double(f64),
+    Decimal(String),
+    Time(String),
+    Date(String),
+    DateTime(String),
+    Timestamp(String),
+    Year(u16),
+    String(String),
+    RawString(#[serde(with = "serde_bytes")] Vec<u8>),
+    Blob(#[serde(with = "serde_bytes")] Vec<u8>),
+    Bit(u64),
+    Set(u64),
+    Enum(u32),
+    Set2(String),
+    Enum2(String),
+    Json(#[serde(with = "serde_bytes")] Vec<u8>),
+    Json2(String),
+    Json3(serde_json::Value),
+    // bson bytes, to keep bson types (ObjectId, DateTime, Decimal128...)
+    MongoDoc(#[serde(with = "serde_bytes")] Vec<u8>),
+}
+
+impl RowDataBinarySerializer {
+    pub fn serialize(row: &RowData) -> Vec<u8> {
+        let to_binary = |col_values: &Option<HashMap<String, ColValue>>| {
+            col_values.as_ref().map(|col_values| {
+                col_values
+                    .iter()
+                    .map(|(col, value)| (col.clone(), Self::to_binary_col_value(value)))
+                    .collect()
+            })
+        };
+
+        let binary_row = BinaryRowData {
+            schema: row.schema.clone(),
+            tb: row.tb.clone(),
+            row_type: row.row_type.clone(),
+            before: to_binary(&row.before),
+            after: to_binary(&row.after),
+            data_size: row.data_size,
+        };
+        let mut bytes = Vec::with_capacity(row.data_size);
+        // writing into Vec<u8> never fails
+        ciborium::into_writer(&binary_row, &mut bytes).unwrap();
+        bytes
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> anyhow::Result<RowData> {
+        let binary_row: BinaryRowData =
+            ciborium::from_reader(bytes).context("failed to decode row data from cbor")?;
+
+        let from_binary = |col_values: Option<HashMap<String, BinaryColValue>>| {
+            col_values
+                .map(|col_values| {
+                    col_values
+                        .into_iter()
+                        .map(|(col, value)| Ok((col, Self::from_binary_col_value(value)?)))
+                        .collect::<anyhow::Result<HashMap<_, _>>>()
+                })
+                .transpose()
+        };
+
+        Ok(RowData {
+            schema: binary_row.schema,
+            tb: binary_row.tb,
+            row_type: binary_row.row_type,
+            before: from_binary(binary_row.before)?,
+            after: from_binary(binary_row.after)?,
+            data_size: binary_row.data_size,
+            position: String::new(),
+        })
+    }
+
+    fn to_binary_col_value(value: &ColValue) -> BinaryColValue {
+        match value {
+            ColValue::None => BinaryColValue::None,
+            ColValue::Bool(v) => BinaryColValue::Bool(*v),
+            ColValue::Tiny(v) => BinaryColValue::Tiny(*v),
+            ColValue::UnsignedTiny(v) => BinaryColValue::UnsignedTiny(*v),
+            ColValue::Short(v) => BinaryColValue::Short(*v),
+            ColValue::UnsignedShort(v) => BinaryColValue::UnsignedShort(*v),
+            ColValue::Long(v) => BinaryColValue::Long(*v),
+            ColValue::UnsignedLong(v) => BinaryColValue::UnsignedLong(*v),
+            ColValue::LongLong(v) => BinaryColValue::LongLong(*v),
+            ColValue::UnsignedLongLong(v) => BinaryColValue::UnsignedLongLong(*v),
+            ColValue::Float(v) => BinaryColValue::Float(*v),
+            ColValue::Double(v) => BinaryColValue::Double(*v),
+            ColValue::Decimal(v) => BinaryColValue::Decimal(v.clone()),
+            ColValue::Time(v) => BinaryColValue::Time(v.clone()),
+            ColValue::Date(v) => BinaryColValue::Date(v.clone()),
+            ColValue::DateTime(v) => BinaryColValue::DateTime(v.clone()),
+            ColValue::Timestamp(v) => BinaryColValue::Timestamp(v.clone()),
+            ColValue::Year(v) => BinaryColValue::Year(*v),
+            ColValue::String(v) => BinaryColValue::String(v.clone()),
+            ColValue::RawString(v) => BinaryColValue::RawString(v.clone()),
+            ColValue::Blob(v) => BinaryColValue::Blob(v.clone()),
+            ColValue::Bit(v) => BinaryColValue::Bit(*v),
+            ColValue::Set(v) => BinaryColValue::Set(*v),
+            ColValue::Enum(v) => BinaryColValue::Enum(*v),
+            ColValue::Set2(v) => BinaryColValue::Set2(v.clone()),
+            ColValue::Enum2(v) => BinaryColValue::Enum2(v.clone()),
+            ColValue::Json(v) => BinaryColValue::Json(v.clone()),
+            ColValue::Json2(v) => BinaryColValue::Json2(v.clone()),
+            ColValue::Json3(v) => BinaryColValue::Json3(v.clone()),
+            // documents read from mongo are always valid bson
+            ColValue::MongoDoc(v) => BinaryColValue::MongoDoc(bson::to_vec(v).unwrap()),
+        }
+    }
+
+    fn from_binary_col_value(value: BinaryColValue) -> anyhow::Result<ColValue> {
+        let col_value = match value {
+            BinaryColValue::None => ColValue::None,
+            BinaryColValue::Bool(v) => ColValue::Bool(v),
+            BinaryColValue::Tiny(v) => ColValue::Tiny(v),
+            BinaryColValue::UnsignedTiny(v) => ColValue::UnsignedTiny(v),
+            BinaryColValue::Short(v) => ColValue::Short(v),
+            BinaryColValue::UnsignedShort(v) => ColValue::UnsignedShort(v),
+            BinaryColValue::Long(v) => ColValue::Long(v),
+            BinaryColValue::UnsignedLong(v) => ColValue::UnsignedLong(v),
+            BinaryColValue::LongLong(v) => ColValue::LongLong(v),
+            BinaryColValue::UnsignedLongLong(v) => ColValue::UnsignedLongLong(v),
+            BinaryColValue::Float(v) => ColValue::Float(v),
+            BinaryColValue::Double(v) => ColValue::Double(v),
+            BinaryColValue::Decimal(v) => ColValue::Decimal(v),
+            BinaryColValue::Time(v) => ColValue::Time(v),
+            BinaryColValue::Date(v) => ColValue::Date(v),
+            BinaryColValue::DateTime(v) => ColValue::DateTime(v),
+            BinaryColValue::Timestamp(v) => ColValue::Timestamp(v),
+            BinaryColValue::Year(v) => ColValue::Year(v),
+            BinaryColValue::String(v) => ColValue::String(v),
+            BinaryColValue::RawString(v) => ColValue::RawString(v),
+            BinaryColValue::Blob(v) => ColValue::Blob(v),
+            BinaryColValue::Bit(v) => ColValue::Bit(v),
+            BinaryColValue::Set(v) => ColValue::Set(v),
+            BinaryColValue::Enum(v) => ColValue::Enum(v),
+            BinaryColValue::Set2(v) => ColValue::Set2(v),
+            BinaryColValue::Enum2(v) => ColValue::Enum2(v),
+            BinaryColValue::Json(v) => ColValue::Json(v),
+            BinaryColValue::Json2(v) => ColValue::Json2(v),
+            BinaryColValue::Json3(v) => ColValue::Json3(v),
+            BinaryColValue::MongoDoc(v) => ColValue::MongoDoc(bson::from_slice::<Document>(&v)?),
+        };
+        Ok(col_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use mongodb::bson::{doc, oid::ObjectId};
+    use proptest::prelude::*;
+    use serde_json::json;
+
+    use super::RowDataBinarySerializer;
+    use crate::meta::{col_value::ColValue, row_data::RowData, row_type::RowType};
+
+    // NaN is excluded since NaN != NaN
+    fn f32_strategy() -> impl Strategy<Value = f32> {
+        prop::num::f32::NORMAL
+            | prop::num::f32::SUBNORMAL
+            | prop::num::f32::ZERO
+            | prop::num::f32::INFINITE
+    }
+
+    fn f64_strategy() -> impl Strategy<Value = f64> {
+        prop::num::f64::NORMAL
+            | prop::num::f64::SUBNORMAL
+            | prop::num::f64::ZERO
+            | prop::num::f64::INFINITE
+    }
+
+    fn col_value_strategy() -> impl Strategy<Value = ColValue> {
+        prop_oneof![
+            Just(ColValue::None),
+            any::<bool>().prop_map(ColValue::Bool),
+            any::<i8>().prop_map(ColValue::Tiny),
+            any::<u8>().prop_map(ColValue::UnsignedTiny),
+            any::<i16>().prop_map(ColValue::Short),
+            any::<u16>().prop_map(ColValue::UnsignedShort),
+            any::<i32>().prop_map(ColValue::Long),
+            any::<u32>().prop_map(ColValue::UnsignedLong),
+            any::<i64>().prop_map(ColValue::LongLong),
+            any::<u64>().prop_map(ColValue::UnsignedLongLong),
+            f32_strategy().prop_map(ColValue::Float),
+            f64_strategy().prop_map(ColValue::Double),
+            "-?[0-9]{1,30}(\\.[0-9]{1,30})?".prop_map(ColValue::Decimal),
+            "[0-9]{2}:[0-9]{2}:[0-9]{2}(\\.[0-9]{1,6})?".prop_map(ColValue::Time),
+            "[0-9]{4}-[0-9]{2}-[0-9]{2}".prop_map(ColValue::Date),
+            "[0-9]{4}-[0-9]{2}-[0-9]{2} [0-9]{2}:[0-9]{2}:[0-9]{2}".prop_map(ColValue::DateTime),
+            "[0-9]{4}-[0-9]{2}-[0-9]{2} [0-9]{2}:[0-9]{2}:[0-9]{2}".prop_map(ColValue::Timestamp),
+            any::<u16>().prop_map(ColValue::Year),
+            any::<String>().prop_map(ColValue::String),
+            any::<Vec<u8>>().prop_map(ColValue::RawString),
+            any::<Vec<u8>>().prop_map(ColValue::Blob),
+            any::<u64>().prop_map(ColValue::Bit),
+            any::<u64>().prop_map(ColValue::Set),
+            any::<u32>().prop_map(ColValue::Enum),
+            any::<String>().prop_map(ColValue::Set2),
+            any::<String>().prop_map(ColValue::Enum2),
+            any::<Vec<u8>>().prop_map(ColValue::Json),
+            any::<String>().prop_map(ColValue::Json2),
+            (
+                any::<String>(),
+                any::<i64>(),
+                f64_strategy().prop_filter("finite", |v| v.is_finite())
+            )
+                .prop_map(|(s, i, f)| ColValue::Json3(
+                    json!({"s": s, "i": i, "f": f, "a": [i, s]})
+                )),
+            (any::<String>(), any::<i64>()).prop_map(|(s, i)| ColValue::MongoDoc(
+                doc! {"_id": ObjectId::new(), "s": s, "i": i}
+            )),
+        ]
+    }
+
+    fn col_values_strategy() -> impl Strategy<Value = Option<HashMap<String, ColValue>>> {
+        prop::option::of(prop::collection::hash_map(
+            "[a-z_0-9]{1,16}",
+            col_value_strategy(),
+            0..16,
+        ))
+    }
+
+    fn row_data_strategy() -> impl Strategy<Value = RowData> {
+        (
+            any::<String>(),
+            any::<String>(),
+            prop_oneof![
+                Just(RowType::Insert),
+                Just(RowType::Update),
+                Just(RowType::Delete)
+            ],
+            col_values_strategy(),
+            col_values_strategy(),
+        )
+            .prop_map(|(schema, tb, row_type, before, after)| {
+                RowData::new(schema, tb, row_type, before, after)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(row_data in row_data_strategy()) {
+            let bytes = RowDataBinarySerializer::serialize(&row_data);
+            let decoded = RowDataBinarySerializer::deserialize(&bytes).unwrap();
+            prop_assert_eq!(decoded, row_data);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_invalid_bytes() {
+        assert!(RowDataBinarySerializer::deserialize(&[0xff, 0x00, 0x01]).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+pub mod mysql_to_pg_convertor;
+pub mod schema_snapshot;
 pub mod statement;
 pub mod struct_data;
 pub mod structure;
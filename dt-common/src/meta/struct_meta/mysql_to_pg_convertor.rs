@@ -0,0 +1,522 @@
+use anyhow::bail;
+
+use crate::{config::config_enums::DbType, error::Error, log_warn};
+
+use super::{
+    statement::{
+        mysql_create_database_statement::MysqlCreateDatabaseStatement,
+        mysql_create_table_statement::MysqlCreateTableStatement,
+        pg_add_constraint_statement::PgAddConstraintStatement,
+        pg_create_index_statement::PgCreateIndexStatement,
+        pg_create_schema_statement::PgCreateSchemaStatement,
+        pg_create_table_statement::PgCreateTableStatement, struct_statement::StructStatement,
+    },
+    structure::{
+        column::{Column, ColumnDefault},
+        comment::{Comment, CommentType},
+        constraint::{Constraint, ConstraintType},
+        index::{Index, IndexKind},
+        schema::Schema,
+        table::Table,
+    },
+};
+
+const PG_DEFAULT_TABLE_SPACE: &str = "pg_default";
+
+// rewrite mysql struct statements to pg ones for mysql -> pg struct migration,
+// column types are mapped to their closest pg equivalents
+pub struct MysqlToPgConvertor {}
+
+impl MysqlToPgConvertor {
+    pub fn convert(statement: StructStatement) -> anyhow::Result<StructStatement> {
+        match statement {
+            StructStatement::MysqlCreateDatabase(s) => {
+                Ok(StructStatement::PgCreateSchema(Self::convert_database(s)))
+            }
+            StructStatement::MysqlCreateTable(s) => {
+                Ok(StructStatement::PgCreateTable(Self::convert_table(s)?))
+            }
+            StructStatement::MysqlCreateIndex(s) => {
+                let (schema, tb) = (s.index.database_name.clone(), s.index.table_name.clone());
+                match Self::convert_index(&schema, &tb, s.index) {
+                    Some(index) => Ok(StructStatement::PgCreateIndex(PgCreateIndexStatement {
+                        index,
+                    })),
+                    None => Ok(StructStatement::Unknown),
+                }
+            }
+            StructStatement::MysqlAddConstraint(s) => {
+                let (schema, tb) = (
+                    s.constraint.database_name.clone(),
+                    s.constraint.table_name.clone(),
+                );
+                Ok(StructStatement::PgAddConstraint(PgAddConstraintStatement {
+                    constraint: Self::convert_constraint(&schema, &tb, s.constraint),
+                }))
+            }
+            _ => Ok(statement),
+        }
+    }
+
+    fn convert_database(statement: MysqlCreateDatabaseStatement) -> PgCreateSchemaStatement {
+        PgCreateSchemaStatement {
+            schema: Schema {
+                name: statement.database.name,
+            },
+        }
+    }
+
+    fn convert_table(
+        statement: MysqlCreateTableStatement,
+    ) -> anyhow::Result<PgCreateTableStatement> {
+        let mysql_table = statement.table;
+        let schema = mysql_table.database_name.clone();
+        let tb = mysql_table.table_name.clone();
+
+        let mut columns = Vec::new();
+        let mut pks = Vec::new();
+        let mut column_comments = Vec::new();
+        for column in mysql_table.columns.iter() {
+            columns.push(Self::convert_column(&schema, &tb, column)?);
+            if column.column_key == "PRI" {
+                pks.push(column.clone());
+            }
+            if !column.column_comment.is_empty() {
+                column_comments.push(Self::build_comment(
+                    &schema,
+                    &tb,
+                    &column.column_name,
+                    &column.column_comment,
+                ));
+            }
+        }
+
+        let mut table_comments = Vec::new();
+        if !mysql_table.table_comment.is_empty() {
+            table_comments.push(Self::build_comment(
+                &schema,
+                &tb,
+                "",
+                &mysql_table.table_comment,
+            ));
+        }
+
+        let mut constraints = Vec::new();
+        if !pks.is_empty() {
+            pks.sort_by(|a, b| a.ordinal_position.cmp(&b.ordinal_position));
+            let pk_cols: Vec<String> = pks
+                .iter()
+                .map(|i| Self::escape_col(&i.column_name))
+                .collect();
+            constraints.push(Constraint {
+                database_name: String::new(),
+                schema_name: schema.clone(),
+                table_name: tb.clone(),
+                constraint_name: format!("{}_pkey", tb),
+                constraint_type: ConstraintType::Primary,
+                definition: format!("PRIMARY KEY ({})", pk_cols.join(", ")),
+            });
+        }
+        for constraint in statement.constraints {
+            constraints.push(Self::convert_constraint(&schema, &tb, constraint));
+        }
+
+        let mut indexes = Vec::new();
+        for index in statement.indexes {
+            if let Some(index) = Self::convert_index(&schema, &tb, index) {
+                indexes.push(index);
+            }
+        }
+
+        Ok(PgCreateTableStatement {
+            table: Table {
+                schema_name: schema,
+                table_name: tb,
+                columns,
+                ..Default::default()
+            },
+            table_comments,
+            column_comments,
+            constraints,
+            indexes,
+            sequences: Vec::new(),
+            sequence_owners: Vec::new(),
+        })
+    }
+
+    fn convert_column(schema: &str, tb: &str, column: &Column) -> anyhow::Result<Column> {
+        let auto_increment = column.extra.to_lowercase().contains("auto_increment");
+        let column_type = match Self::get_pg_col_type(&column.column_type, auto_increment) {
+            Some(column_type) => column_type,
+            None => bail! {Error::StructError(format!(
+                "column: {}.{}.{} of type: {} can not be mapped to a pg type",
+                schema, tb, column.column_name, column.column_type
+            ))},
+        };
+
+        if column.extra.to_lowercase().contains("on update") {
+            log_warn!(
+                "column: {}.{}.{} has [{}] which is not supported by pg, ignored",
+                schema,
+                tb,
+                column.column_name,
+                column.extra
+            );
+        }
+
+        let column_default = match &column.column_default {
+            // bit literals are like b'1'
+            Some(ColumnDefault::Literal(v)) if !column_type.starts_with("bit") => Some(
+                ColumnDefault::Literal(format!("'{}'", v.replace('\'', "''"))),
+            ),
+            other => other.clone(),
+        };
+
+        Ok(Column {
+            column_name: column.column_name.clone(),
+            ordinal_position: column.ordinal_position,
+            column_default: if auto_increment { None } else { column_default },
+            is_nullable: column.is_nullable,
+            column_type,
+            generated: if auto_increment {
+                Some("BY DEFAULT".into())
+            } else {
+                None
+            },
+            ..Default::default()
+        })
+    }
+
+    // mysql_col_type is COLUMN_TYPE in information_schema.columns, E.g. int(10) unsigned
+    pub fn get_pg_col_type(mysql_col_type: &str, auto_increment: bool) -> Option<String> {
+        let mysql_col_type = mysql_col_type.trim().to_lowercase();
+        let base_end = mysql_col_type
+            .find(|c: char| c == '(' || c == ' ')
+            .unwrap_or(mysql_col_type.len());
+        let base_type = &mysql_col_type[..base_end];
+        let args = if mysql_col_type[base_end..].starts_with('(') {
+            mysql_col_type
+                .rfind(')')
+                .map(|end| &mysql_col_type[base_end + 1..end])
+        } else {
+            None
+        };
+        let unsigned = mysql_col_type.contains(" unsigned");
+        let with_args = |pg_type: &str| match args {
+            Some(args) => format!("{}({})", pg_type, args),
+            None => pg_type.to_string(),
+        };
+
+        let pg_col_type: String = match base_type {
+            "tinyint" => "smallint".into(),
+            "smallint" if unsigned => "integer".into(),
+            "smallint" => "smallint".into(),
+            "mediumint" => "integer".into(),
+            "int" | "integer" if unsigned => "bigint".into(),
+            "int" | "integer" => "integer".into(),
+            // identity columns must be of integer types
+            "bigint" if unsigned && !auto_increment => "numeric(20,0)".into(),
+            "bigint" => "bigint".into(),
+            "float" => "real".into(),
+            "double" | "real" => "double precision".into(),
+            "decimal" | "numeric" | "dec" | "fixed" => with_args("numeric"),
+            "bit" => with_args("bit"),
+            "bool" | "boolean" => "boolean".into(),
+
+            "date" => "date".into(),
+            "datetime" | "timestamp" => with_args("timestamp"),
+            "time" => with_args("time"),
+            "year" => "smallint".into(),
+
+            "char" => with_args("char"),
+            "varchar" => with_args("varchar"),
+            "tinytext" | "text" | "mediumtext" | "longtext" => "text".into(),
+            "enum" | "set" => "text".into(),
+            "json" => "json".into(),
+
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => {
+                "bytea".into()
+            }
+
+            _ => return None,
+        };
+        Some(pg_col_type)
+    }
+
+    fn convert_constraint(schema: &str, tb: &str, constraint: Constraint) -> Constraint {
+        let definition = format!(
+            "{} {}",
+            constraint.constraint_type.to_str(DbType::Mysql),
+            constraint.definition.replace('`', "\"")
+        );
+        Constraint {
+            database_name: String::new(),
+            schema_name: schema.to_string(),
+            table_name: tb.to_string(),
+            constraint_name: constraint.constraint_name,
+            constraint_type: constraint.constraint_type,
+            definition,
+        }
+    }
+
+    fn convert_index(schema: &str, tb: &str, mut index: Index) -> Option<Index> {
+        let unique = match index.index_kind {
+            IndexKind::Unique => "UNIQUE ",
+            IndexKind::Unknown => "",
+            _ => {
+                log_warn!(
+                    "{} index: {} of {}.{} is not supported by pg, ignored",
+                    index.index_kind,
+                    index.index_name,
+                    schema,
+                    tb
+                );
+                return None;
+            }
+        };
+
+        // functional key parts of mysql 8 have no column name, E.g. INDEX idx((col_1 + 1)),
+        // building the index without them would make it narrower, or even invalid
+        if index.columns.iter().any(|i| i.column_name.is_empty()) {
+            log_warn!(
+                "index: {} of {}.{} has functional key parts, not supported by pg, ignored",
+                index.index_name,
+                schema,
+                tb
+            );
+            return None;
+        }
+
+        index
+            .columns
+            .sort_by(|a, b| a.seq_in_index.cmp(&b.seq_in_index));
+        let cols: Vec<String> = index
+            .columns
+            .iter()
+            .map(|i| Self::escape_col(&i.column_name))
+            .collect();
+        // index names are unique in a pg schema but only in a mysql table
+        let index_name = format!("{}_{}", tb, index.index_name);
+        let definition = format!(
+            r#"CREATE {}INDEX "{}" ON "{}"."{}" ({})"#,
+            unique,
+            index_name,
+            schema,
+            tb,
+            cols.join(", ")
+        );
+
+        Some(Index {
+            database_name: String::new(),
+            schema_name: schema.to_string(),
+            table_name: tb.to_string(),
+            index_name,
+            table_space: PG_DEFAULT_TABLE_SPACE.into(),
+            definition,
+            ..index
+        })
+    }
+
+    fn build_comment(schema: &str, tb: &str, col: &str, comment: &str) -> Comment {
+        Comment {
+            comment_type: if col.is_empty() {
+                CommentType::Table
+            } else {
+                CommentType::Column
+            },
+            database_name: String::new(),
+            schema_name: schema.to_string(),
+            table_name: tb.to_string(),
+            column_name: col.to_string(),
+            comment: comment.replace('\'', "''"),
+        }
+    }
+
+    fn escape_col(col: &str) -> String {
+        format!(r#""{}""#, col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::filter_config::FilterConfig, meta::struct_meta::structure::index::IndexColumn,
+        rdb_filter::RdbFilter,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_pg_col_type() {
+        let cases = [
+            ("tinyint(1)", "smallint"),
+            ("tinyint(3) unsigned", "smallint"),
+            ("smallint unsigned", "integer"),
+            ("mediumint(8)", "integer"),
+            ("int(11)", "integer"),
+            ("int(10) unsigned", "bigint"),
+            ("int unsigned zerofill", "bigint"),
+            ("bigint(20) unsigned", "numeric(20,0)"),
+            ("decimal(10,2)", "numeric(10,2)"),
+            ("double", "double precision"),
+            ("bit(8)", "bit(8)"),
+            ("datetime", "timestamp"),
+            ("datetime(3)", "timestamp(3)"),
+            ("timestamp(6)", "timestamp(6)"),
+            ("year(4)", "smallint"),
+            ("varchar(255)", "varchar(255)"),
+            ("mediumtext", "text"),
+            ("enum('a','b c')", "text"),
+            ("longblob", "bytea"),
+            ("JSON", "json"),
+        ];
+        for (mysql_col_type, pg_col_type) in cases {
+            assert_eq!(
+                MysqlToPgConvertor::get_pg_col_type(mysql_col_type, false),
+                Some(pg_col_type.to_string()),
+                "{}",
+                mysql_col_type
+            );
+        }
+
+        assert_eq!(
+            MysqlToPgConvertor::get_pg_col_type("bigint unsigned", true),
+            Some("bigint".to_string())
+        );
+        assert_eq!(MysqlToPgConvertor::get_pg_col_type("geometry", false), None);
+    }
+
+    #[test]
+    fn test_convert_table() {
+        let column =
+            |name: &str, position, column_type: &str, column_key: &str, extra: &str| Column {
+                column_name: name.into(),
+                ordinal_position: position,
+                column_type: column_type.into(),
+                column_key: column_key.into(),
+                extra: extra.into(),
+                ..Default::default()
+            };
+        let statement = MysqlCreateTableStatement {
+            table: Table {
+                database_name: "db_1".into(),
+                table_name: "tb_1".into(),
+                columns: vec![
+                    column("id", 1, "int(10) unsigned", "PRI", "auto_increment"),
+                    Column {
+                        column_default: Some(ColumnDefault::Literal("it's".into())),
+                        column_comment: "the name".into(),
+                        ..column("name", 2, "varchar(64)", "", "")
+                    },
+                    Column {
+                        column_default: Some(ColumnDefault::Expression("CURRENT_TIMESTAMP".into())),
+                        ..column("created_at", 3, "datetime", "", "")
+                    },
+                ],
+                ..Default::default()
+            },
+            constraints: vec![],
+            indexes: vec![Index {
+                database_name: "db_1".into(),
+                table_name: "tb_1".into(),
+                index_name: "idx_name".into(),
+                index_kind: IndexKind::Unique,
+                columns: vec![IndexColumn {
+                    column_name: "name".into(),
+                    seq_in_index: 1,
+                }],
+                ..Default::default()
+            }],
+        };
+
+        let mut statement = match MysqlToPgConvertor::convert(StructStatement::MysqlCreateTable(
+            statement,
+        ))
+        .unwrap()
+        {
+            StructStatement::PgCreateTable(s) => s,
+            _ => panic!("not converted to pg statement"),
+        };
+
+        let filter = RdbFilter::from_config(
+            &FilterConfig {
+                do_structures: "*".into(),
+                ..Default::default()
+            },
+            &DbType::Pg,
+        )
+        .unwrap();
+        let sqls: Vec<String> = statement
+            .to_sqls(&filter)
+            .unwrap()
+            .into_iter()
+            .map(|(_, sql)| sql)
+            .collect();
+        assert_eq!(
+            sqls[0],
+            r#"CREATE TABLE IF NOT EXISTS "db_1"."tb_1" ("id" bigint NOT NULL GENERATED BY DEFAULT AS IDENTITY ,"name" varchar(64) NOT NULL DEFAULT 'it''s' ,"created_at" timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP )"#
+        );
+        assert_eq!(
+            sqls[1],
+            r#"COMMENT ON COLUMN "db_1"."tb_1"."name" IS 'the name'"#
+        );
+        assert_eq!(
+            sqls[2],
+            r#"ALTER TABLE "db_1"."tb_1" ADD CONSTRAINT "tb_1_pkey" PRIMARY KEY ("id")"#
+        );
+        assert_eq!(
+            statement.indexes[0].definition,
+            r#"CREATE UNIQUE INDEX "tb_1_idx_name" ON "db_1"."tb_1" ("name")"#
+        );
+    }
+
+    #[test]
+    fn test_convert_functional_index() {
+        let index = |columns: Vec<&str>| Index {
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            index_name: "idx_1".into(),
+            index_kind: IndexKind::Unknown,
+            columns: columns
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| IndexColumn {
+                    column_name: name.into(),
+                    seq_in_index: i as u32 + 1,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let converted = MysqlToPgConvertor::convert_index("db_1", "tb_1", index(vec!["a", "b"]));
+        assert_eq!(
+            converted.unwrap().definition,
+            r#"CREATE INDEX "tb_1_idx_1" ON "db_1"."tb_1" ("a", "b")"#
+        );
+        // E.g. INDEX idx_1(a, (b + 1)), the functional key part has no column name
+        assert!(MysqlToPgConvertor::convert_index("db_1", "tb_1", index(vec!["a", ""])).is_none());
+        assert!(MysqlToPgConvertor::convert_index("db_1", "tb_1", index(vec![""])).is_none());
+    }
+
+    #[test]
+    fn test_convert_unmappable_type() {
+        let statement = MysqlCreateTableStatement {
+            table: Table {
+                database_name: "db_1".into(),
+                table_name: "tb_1".into(),
+                columns: vec![Column {
+                    column_name: "location".into(),
+                    column_type: "point".into(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            constraints: vec![],
+            indexes: vec![],
+        };
+        let error = MysqlToPgConvertor::convert(StructStatement::MysqlCreateTable(statement))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("db_1.tb_1.location"), "{}", error);
+    }
+}
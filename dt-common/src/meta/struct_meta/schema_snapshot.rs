@@ -0,0 +1,89 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_enums::DbType;
+
+use super::statement::{
+    mysql_create_database_statement::MysqlCreateDatabaseStatement,
+    mysql_create_table_statement::MysqlCreateTableStatement,
+    pg_create_schema_statement::PgCreateSchemaStatement,
+    pg_create_table_statement::PgCreateTableStatement,
+};
+
+// self-contained dump of source structures, can be loaded for offline analysis
+// without connecting to the database
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    pub db_type: DbType,
+    #[serde(default)]
+    pub mysql_databases: Vec<MysqlCreateDatabaseStatement>,
+    #[serde(default)]
+    pub mysql_tables: Vec<MysqlCreateTableStatement>,
+    #[serde(default)]
+    pub pg_schemas: Vec<PgCreateSchemaStatement>,
+    #[serde(default)]
+    pub pg_tables: Vec<PgCreateTableStatement>,
+}
+
+impl SchemaSnapshot {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::config_enums::DbType,
+        meta::struct_meta::{
+            statement::mysql_create_table_statement::MysqlCreateTableStatement,
+            structure::{
+                column::{Column, ColumnDefault},
+                table::Table,
+            },
+        },
+    };
+
+    use super::SchemaSnapshot;
+
+    #[test]
+    fn test_serde() {
+        let table = Table {
+            database_name: "db_1".into(),
+            table_name: "tb_1".into(),
+            columns: vec![Column {
+                column_name: "id".into(),
+                ordinal_position: 1,
+                column_default: Some(ColumnDefault::Literal("0".into())),
+                column_type: "int".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let snapshot = SchemaSnapshot {
+            db_type: DbType::Mysql,
+            mysql_tables: vec![MysqlCreateTableStatement {
+                table,
+                constraints: vec![],
+                indexes: vec![],
+            }],
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let loaded: SchemaSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.db_type, DbType::Mysql);
+        assert!(loaded.pg_tables.is_empty());
+        assert_eq!(loaded.mysql_tables.len(), 1);
+        let column = &loaded.mysql_tables[0].table.columns[0];
+        assert_eq!(column, &snapshot.mysql_tables[0].table.columns[0]);
+    }
+}
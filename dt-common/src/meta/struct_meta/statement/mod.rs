@@ -1,5 +1,10 @@
+pub mod mysql_add_constraint_statement;
 pub mod mysql_create_database_statement;
+pub mod mysql_create_index_statement;
 pub mod mysql_create_table_statement;
+pub mod pg_add_constraint_statement;
+pub mod pg_create_domain_statement;
+pub mod pg_create_index_statement;
 pub mod pg_create_schema_statement;
 pub mod pg_create_table_statement;
 pub mod struct_statement;
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdb_filter::RdbFilter;
+
+use crate::meta::struct_meta::structure::{constraint::Constraint, structure_type::StructureType};
+
+use super::mysql_create_table_statement::MysqlCreateTableStatement;
+
+// a check / foreign key constraint added after all tables and indexes of the schema,
+// so foreign keys do not fail on tables not created yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MysqlAddConstraintStatement {
+    pub constraint: Constraint,
+}
+
+impl MysqlAddConstraintStatement {
+    pub fn route(&mut self, dst_db: &str, dst_tb: &str) {
+        self.constraint.database_name = dst_db.to_string();
+        self.constraint.table_name = dst_tb.to_string();
+    }
+
+    pub fn to_sqls(&self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
+        let mut sqls = Vec::new();
+        if filter.filter_structure(&StructureType::Constraint) {
+            return Ok(sqls);
+        }
+
+        let key = format!(
+            "constraint.{}.{}.{}",
+            self.constraint.database_name,
+            self.constraint.table_name,
+            self.constraint.constraint_name
+        );
+        sqls.push((
+            key,
+            MysqlCreateTableStatement::constraint_to_sql(&self.constraint),
+        ));
+        Ok(sqls)
+    }
+}
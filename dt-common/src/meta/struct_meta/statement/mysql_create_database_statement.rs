@@ -1,8 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::rdb_filter::RdbFilter;
 
 use crate::meta::struct_meta::structure::{database::Database, structure_type::StructureType};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MysqlCreateDatabaseStatement {
     pub database: Database,
 }
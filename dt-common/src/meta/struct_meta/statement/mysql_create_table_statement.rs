@@ -1,6 +1,13 @@
+use serde::{Deserialize, Serialize};
+
 use crate::meta::struct_meta::structure::column::ColumnDefault;
 use crate::{config::config_enums::DbType, rdb_filter::RdbFilter};
 
+use super::{
+    mysql_add_constraint_statement::MysqlAddConstraintStatement,
+    mysql_create_index_statement::MysqlCreateIndexStatement,
+};
+
 use crate::meta::struct_meta::structure::{
     column::Column,
     constraint::Constraint,
@@ -9,7 +16,7 @@ use crate::meta::struct_meta::structure::{
     table::Table,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MysqlCreateTableStatement {
     pub table: Table,
     pub constraints: Vec<Constraint>,
@@ -32,6 +39,26 @@ impl MysqlCreateTableStatement {
         }
     }
 
+    // take out indexes and constraints to be created after all tables
+    pub fn split(
+        &mut self,
+    ) -> (
+        Vec<MysqlCreateIndexStatement>,
+        Vec<MysqlAddConstraintStatement>,
+    ) {
+        let indexes = self
+            .indexes
+            .drain(..)
+            .map(|index| MysqlCreateIndexStatement { index })
+            .collect();
+        let constraints = self
+            .constraints
+            .drain(..)
+            .map(|constraint| MysqlAddConstraintStatement { constraint })
+            .collect();
+        (indexes, constraints)
+    }
+
     pub fn to_sqls(&mut self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
         let mut sqls = Vec::new();
 
@@ -176,7 +203,7 @@ impl MysqlCreateTableStatement {
         (sql_lines.join(", "), pks)
     }
 
-    fn index_to_sql(index: &mut Index) -> String {
+    pub(crate) fn index_to_sql(index: &mut Index) -> String {
         index
             .columns
             .sort_by(|a, b| a.seq_in_index.cmp(&b.seq_in_index));
@@ -203,7 +230,7 @@ impl MysqlCreateTableStatement {
         sql
     }
 
-    fn constraint_to_sql(constraint: &Constraint) -> String {
+    pub(crate) fn constraint_to_sql(constraint: &Constraint) -> String {
         // TODO, check for escapes
         format!(
             "ALTER TABLE `{}`.`{}` ADD CONSTRAINT `{}` {} {} ",
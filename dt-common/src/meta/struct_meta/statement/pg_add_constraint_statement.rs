@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdb_filter::RdbFilter;
+
+use crate::meta::struct_meta::structure::{
+    constraint::{Constraint, ConstraintType},
+    structure_type::StructureType,
+};
+
+use super::pg_create_table_statement::PgCreateTableStatement;
+
+// a check / foreign key constraint added after all tables and indexes of the schema,
+// primary / unique constraints are kept with tables
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgAddConstraintStatement {
+    pub constraint: Constraint,
+}
+
+impl PgAddConstraintStatement {
+    pub fn route(&mut self, dst_schema: &str, dst_tb: &str) {
+        self.constraint.schema_name = dst_schema.to_string();
+        self.constraint.table_name = dst_tb.to_string();
+    }
+
+    pub fn to_sqls(&self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
+        let mut sqls = Vec::new();
+        let structure_type = match self.constraint.constraint_type {
+            ConstraintType::Primary | ConstraintType::Unique => StructureType::Table,
+            _ => StructureType::Constraint,
+        };
+        if filter.filter_structure(&structure_type) {
+            return Ok(sqls);
+        }
+
+        let key = format!(
+            "constraint.{}.{}.{}",
+            self.constraint.schema_name,
+            self.constraint.table_name,
+            self.constraint.constraint_name
+        );
+        sqls.push((
+            key,
+            PgCreateTableStatement::constraint_to_sql(&self.constraint),
+        ));
+        Ok(sqls)
+    }
+}
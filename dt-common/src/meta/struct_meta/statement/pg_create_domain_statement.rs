@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdb_filter::RdbFilter;
+
+use crate::meta::struct_meta::structure::{domain::Domain, structure_type::StructureType};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgCreateDomainStatement {
+    pub domain: Domain,
+}
+
+impl PgCreateDomainStatement {
+    pub fn route(&mut self, dst_schema: &str) {
+        self.domain.schema_name = dst_schema.to_string();
+    }
+
+    pub fn to_sqls(&self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
+        let mut sqls = Vec::new();
+        // domains are column types of tables
+        if filter.filter_structure(&StructureType::Table) {
+            return Ok(sqls);
+        }
+
+        let key = format!(
+            "domain.{}.{}",
+            self.domain.schema_name, self.domain.domain_name
+        );
+        sqls.push((key, Self::domain_to_sql(&self.domain)));
+        Ok(sqls)
+    }
+
+    fn domain_to_sql(domain: &Domain) -> String {
+        let mut sql = format!(
+            r#"CREATE DOMAIN "{}"."{}" AS {}"#,
+            domain.schema_name, domain.domain_name, domain.base_type
+        );
+        if let Some(default_value) = &domain.default_value {
+            sql = format!("{} DEFAULT {}", sql, default_value);
+        }
+        if domain.not_null {
+            sql = format!("{} NOT NULL", sql);
+        }
+        for constraint in domain.constraints.iter() {
+            sql = format!(
+                r#"{} CONSTRAINT "{}" {}"#,
+                sql, constraint.constraint_name, constraint.definition
+            );
+        }
+        sql
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::meta::struct_meta::structure::domain::DomainConstraint;
+
+    use super::*;
+
+    #[test]
+    fn test_domain_to_sql() {
+        let mut domain = Domain {
+            schema_name: "public".into(),
+            domain_name: "positive_int".into(),
+            base_type: "integer".into(),
+            default_value: None,
+            not_null: false,
+            constraints: vec![],
+        };
+        assert_eq!(
+            PgCreateDomainStatement::domain_to_sql(&domain),
+            r#"CREATE DOMAIN "public"."positive_int" AS integer"#
+        );
+
+        domain.default_value = Some("1".into());
+        domain.not_null = true;
+        domain.constraints = vec![
+            DomainConstraint {
+                constraint_name: "positive_int_check".into(),
+                definition: "CHECK ((VALUE > 0))".into(),
+            },
+            DomainConstraint {
+                constraint_name: "positive_int_max".into(),
+                definition: "CHECK ((VALUE < 100))".into(),
+            },
+        ];
+        assert_eq!(
+            PgCreateDomainStatement::domain_to_sql(&domain),
+            r#"CREATE DOMAIN "public"."positive_int" AS integer DEFAULT 1 NOT NULL CONSTRAINT "positive_int_check" CHECK ((VALUE > 0)) CONSTRAINT "positive_int_max" CHECK ((VALUE < 100))"#
+        );
+
+        // nested domains are created over the parent domain
+        let nested = Domain {
+            domain_name: "small_positive_int".into(),
+            base_type: r#""public"."positive_int""#.into(),
+            default_value: None,
+            not_null: false,
+            constraints: vec![],
+            ..domain
+        };
+        assert_eq!(
+            PgCreateDomainStatement::domain_to_sql(&nested),
+            r#"CREATE DOMAIN "public"."small_positive_int" AS "public"."positive_int""#
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdb_filter::RdbFilter;
+
+use crate::meta::struct_meta::structure::{
+    index::{Index, IndexKind},
+    structure_type::StructureType,
+};
+
+use super::pg_create_table_statement::PgCreateTableStatement;
+
+// an index created after all tables of the schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgCreateIndexStatement {
+    pub index: Index,
+}
+
+impl PgCreateIndexStatement {
+    pub fn route(&mut self, dst_schema: &str, dst_tb: &str) {
+        self.index.schema_name = dst_schema.to_string();
+        self.index.table_name = dst_tb.to_string();
+    }
+
+    // unique indexes are necessities for data migration, they are kept with tables
+    pub fn is_deferrable(&self) -> bool {
+        self.index.index_kind != IndexKind::Unique
+    }
+
+    pub fn to_sqls(&self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
+        let mut sqls = Vec::new();
+        let structure_type = match self.index.index_kind {
+            IndexKind::Unique => StructureType::Table,
+            _ => StructureType::Index,
+        };
+        if filter.filter_structure(&structure_type) {
+            return Ok(sqls);
+        }
+
+        let key = format!(
+            "index.{}.{}.{}",
+            self.index.schema_name, self.index.table_name, self.index.index_name
+        );
+        sqls.push((key, PgCreateTableStatement::index_to_sql(&self.index)?));
+        Ok(sqls)
+    }
+}
@@ -1,8 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::rdb_filter::RdbFilter;
 
 use crate::meta::struct_meta::structure::{schema::Schema, structure_type::StructureType};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PgCreateSchemaStatement {
     pub schema: Schema,
 }
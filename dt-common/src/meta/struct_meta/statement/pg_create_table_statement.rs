@@ -1,4 +1,5 @@
 use anyhow::bail;
+use serde::{Deserialize, Serialize};
 
 use crate::config::config_enums::DbType;
 use crate::error::Error;
@@ -7,6 +8,11 @@ use crate::meta::ddl_meta::ddl_statement::DdlStatement;
 use crate::meta::struct_meta::structure::column::ColumnDefault;
 use crate::rdb_filter::RdbFilter;
 
+use super::{
+    pg_add_constraint_statement::PgAddConstraintStatement,
+    pg_create_index_statement::PgCreateIndexStatement,
+};
+
 use crate::meta::struct_meta::structure::{
     column::Column,
     comment::Comment,
@@ -18,7 +24,7 @@ use crate::meta::struct_meta::structure::{
     table::Table,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PgCreateTableStatement {
     pub table: Table,
     pub table_comments: Vec<Comment>,
@@ -63,6 +69,29 @@ impl PgCreateTableStatement {
         }
     }
 
+    // take out indexes and check / foreign key constraints to be created after all tables,
+    // primary / unique constraints are kept with the table
+    pub fn split(&mut self) -> (Vec<PgCreateIndexStatement>, Vec<PgAddConstraintStatement>) {
+        let indexes = self
+            .indexes
+            .drain(..)
+            .map(|index| PgCreateIndexStatement { index })
+            .collect();
+        let (kept, split): (Vec<Constraint>, Vec<Constraint>) =
+            self.constraints.drain(..).partition(|i| {
+                matches!(
+                    i.constraint_type,
+                    ConstraintType::Primary | ConstraintType::Unique
+                )
+            });
+        self.constraints = kept;
+        let constraints = split
+            .into_iter()
+            .map(|constraint| PgAddConstraintStatement { constraint })
+            .collect();
+        (indexes, constraints)
+    }
+
     pub fn to_sqls(&mut self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
         let mut sqls = Vec::new();
 
@@ -179,7 +208,7 @@ impl PgCreateTableStatement {
         sql
     }
 
-    fn index_to_sql(index: &Index) -> anyhow::Result<String> {
+    pub(crate) fn index_to_sql(index: &Index) -> anyhow::Result<String> {
         let parser = DdlParser::new(DbType::Pg);
         if let Ok(mut ddl_data) = parser.parse(&index.definition) {
             if let DdlStatement::PgCreateIndex(s) = &mut ddl_data.statement {
@@ -242,7 +271,7 @@ impl PgCreateTableStatement {
         )
     }
 
-    fn constraint_to_sql(constraint: &Constraint) -> String {
+    pub(crate) fn constraint_to_sql(constraint: &Constraint) -> String {
         format!(
             r#"ALTER TABLE "{}"."{}" ADD CONSTRAINT "{}" {}"#,
             constraint.schema_name,
@@ -1,8 +1,13 @@
 use crate::rdb_filter::RdbFilter;
 
 use super::{
+    mysql_add_constraint_statement::MysqlAddConstraintStatement,
     mysql_create_database_statement::MysqlCreateDatabaseStatement,
+    mysql_create_index_statement::MysqlCreateIndexStatement,
     mysql_create_table_statement::MysqlCreateTableStatement,
+    pg_add_constraint_statement::PgAddConstraintStatement,
+    pg_create_domain_statement::PgCreateDomainStatement,
+    pg_create_index_statement::PgCreateIndexStatement,
     pg_create_schema_statement::PgCreateSchemaStatement,
     pg_create_table_statement::PgCreateTableStatement,
 };
@@ -11,8 +16,20 @@ use super::{
 pub enum StructStatement {
     MysqlCreateDatabase(MysqlCreateDatabaseStatement),
     PgCreateSchema(PgCreateSchemaStatement),
+    // domains should be created before tables referencing them
+    PgCreateDomain(PgCreateDomainStatement),
     MysqlCreateTable(MysqlCreateTableStatement),
     PgCreateTable(PgCreateTableStatement),
+    // split from tables, created after all tables of the schema
+    MysqlCreateIndex(MysqlCreateIndexStatement),
+    PgCreateIndex(PgCreateIndexStatement),
+    MysqlAddConstraint(MysqlAddConstraintStatement),
+    PgAddConstraint(PgAddConstraintStatement),
+    // server-level setting which affects data representation, e.g. timezone, DateStyle
+    PgSetParameter {
+        name: String,
+        value: String,
+    },
     #[default]
     Unknown,
 }
@@ -22,9 +39,115 @@ impl StructStatement {
         match self {
             Self::MysqlCreateDatabase(s) => s.to_sqls(filter),
             Self::PgCreateSchema(s) => s.to_sqls(filter),
+            Self::PgCreateDomain(s) => s.to_sqls(filter),
             Self::MysqlCreateTable(s) => s.to_sqls(filter),
             Self::PgCreateTable(s) => s.to_sqls(filter),
+            Self::MysqlCreateIndex(s) => s.to_sqls(filter),
+            Self::PgCreateIndex(s) => s.to_sqls(filter),
+            Self::MysqlAddConstraint(s) => s.to_sqls(filter),
+            Self::PgAddConstraint(s) => s.to_sqls(filter),
+            Self::PgSetParameter { name, value } => Ok(vec![(
+                format!("parameter.{}", name),
+                format!("SET {} TO '{}'", name, value.replace('\'', "''")),
+            )]),
             _ => Ok(vec![]),
         }
     }
+
+    // take out indexes and constraints of a create table statement,
+    // they should be created after all tables
+    pub fn split(&mut self) -> (Vec<StructStatement>, Vec<StructStatement>) {
+        match self {
+            Self::MysqlCreateTable(s) => {
+                let (indexes, constraints) = s.split();
+                (
+                    indexes.into_iter().map(Self::MysqlCreateIndex).collect(),
+                    constraints
+                        .into_iter()
+                        .map(Self::MysqlAddConstraint)
+                        .collect(),
+                )
+            }
+            Self::PgCreateTable(s) => {
+                let (indexes, constraints) = s.split();
+                (
+                    indexes.into_iter().map(Self::PgCreateIndex).collect(),
+                    constraints.into_iter().map(Self::PgAddConstraint).collect(),
+                )
+            }
+            _ => (vec![], vec![]),
+        }
+    }
+
+    // secondary indexes and check / foreign key constraints are not needed by data
+    // migration, they can be created after the snapshot
+    pub fn is_deferrable(&self) -> bool {
+        match self {
+            Self::MysqlCreateIndex(s) => s.is_deferrable(),
+            Self::PgCreateIndex(s) => s.is_deferrable(),
+            Self::MysqlAddConstraint(_) | Self::PgAddConstraint(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::meta::struct_meta::structure::{
+        constraint::{Constraint, ConstraintType},
+        index::{Index, IndexKind},
+        table::Table,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let index = |index_name: &str, index_kind| Index {
+            index_name: index_name.into(),
+            index_kind,
+            ..Default::default()
+        };
+        let constraint = |constraint_name: &str, constraint_type| Constraint {
+            database_name: String::new(),
+            schema_name: String::new(),
+            table_name: String::new(),
+            constraint_name: constraint_name.into(),
+            constraint_type,
+            definition: String::new(),
+        };
+
+        let mut statement = StructStatement::PgCreateTable(PgCreateTableStatement {
+            table: Table::default(),
+            table_comments: vec![],
+            column_comments: vec![],
+            constraints: vec![
+                constraint("pk", ConstraintType::Primary),
+                constraint("fk", ConstraintType::Foregin),
+                constraint("chk", ConstraintType::Check),
+            ],
+            indexes: vec![
+                index("uk", IndexKind::Unique),
+                index("idx", IndexKind::Unknown),
+            ],
+            sequences: vec![],
+            sequence_owners: vec![],
+        });
+
+        let (indexes, constraints) = statement.split();
+        assert_eq!(indexes.len(), 2);
+        assert!(!indexes[0].is_deferrable());
+        assert!(indexes[1].is_deferrable());
+        assert_eq!(constraints.len(), 2);
+        assert!(constraints.iter().all(|i| i.is_deferrable()));
+        assert!(!statement.is_deferrable());
+        match statement {
+            StructStatement::PgCreateTable(s) => {
+                assert!(s.indexes.is_empty());
+                assert_eq!(s.constraints.len(), 1);
+                assert_eq!(s.constraints[0].constraint_name, "pk");
+            }
+            _ => panic!("unexpected statement"),
+        }
+    }
 }
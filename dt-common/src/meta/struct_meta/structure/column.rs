@@ -1,4 +1,6 @@
-#[derive(Clone, Debug, PartialEq, Default)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct Column {
     pub column_name: String,
     pub ordinal_position: u32,
@@ -13,7 +15,7 @@ pub struct Column {
     pub collation_name: String,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ColumnDefault {
     Literal(String),
     Expression(String),
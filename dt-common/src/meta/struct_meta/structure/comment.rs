@@ -1,4 +1,6 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
     pub comment_type: CommentType,
     pub database_name: String,
@@ -8,7 +10,7 @@ pub struct Comment {
     pub comment: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum CommentType {
     Table,
     Column,
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use crate::config::config_enums::DbType;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Constraint {
     pub database_name: String,
     pub schema_name: String,
@@ -10,7 +12,7 @@ pub struct Constraint {
     pub definition: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConstraintType {
     Primary,
     Unique,
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Domain {
+    pub schema_name: String,
+    pub domain_name: String,
+    // E.g. integer, character varying(64), numeric(10,2)
+    pub base_type: String,
+    pub default_value: Option<String>,
+    pub not_null: bool,
+    pub constraints: Vec<DomainConstraint>,
+}
+
+impl Domain {
+    pub fn has_constraints(&self) -> bool {
+        self.not_null || self.default_value.is_some() || !self.constraints.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainConstraint {
+    pub constraint_name: String,
+    // E.g. CHECK ((VALUE > 0))
+    pub definition: String,
+}
@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Index {
     pub database_name: String,
     pub schema_name: String,
@@ -14,7 +15,7 @@ pub struct Index {
     pub columns: Vec<IndexColumn>,
 }
 
-#[derive(Debug, Clone, PartialEq, Display, EnumString, Default)]
+#[derive(Debug, Clone, PartialEq, Display, EnumString, Default, Serialize, Deserialize)]
 pub enum IndexKind {
     #[strum(serialize = "UNIQUE")]
     Unique,
@@ -29,7 +30,7 @@ pub enum IndexKind {
 
 /// for mysql, IndexType corresponds to INDEX_TYPE in information_schema.statistics
 /// for posgres, this is ignored
-#[derive(Debug, Clone, PartialEq, Display, EnumString, Default)]
+#[derive(Debug, Clone, PartialEq, Display, EnumString, Default, Serialize, Deserialize)]
 pub enum IndexType {
     #[strum(serialize = "FULLTEXT")]
     FullText,
@@ -45,7 +46,7 @@ pub enum IndexType {
     Unknown,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct IndexColumn {
     pub column_name: String,
     pub seq_in_index: u32,
@@ -2,6 +2,7 @@ pub mod column;
 pub mod comment;
 pub mod constraint;
 pub mod database;
+pub mod domain;
 pub mod index;
 pub mod schema;
 pub mod sequence;
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use super::column::Column;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Table {
     pub database_name: String,
     pub schema_name: String,
@@ -7,6 +7,28 @@ pub enum CounterType {
     BatchWriteFailures,
     #[strum(serialize = "serial_writes")]
     SerialWrites,
+    #[strum(serialize = "advisory_lock_contentions")]
+    AdvisoryLockContentions,
+    #[strum(serialize = "deadlock_retries")]
+    DeadlockRetries,
+    // redis keys dropped by [filter] do_keys / ignore_keys
+    #[strum(serialize = "filtered_keys")]
+    FilteredKeys,
+    // rows written to [pipeline] dead_letter_file instead of the target
+    #[strum(serialize = "dead_letter_rows")]
+    DeadLetterRows,
+    // rows dropped by [filter] row_filters
+    #[strum(serialize = "filtered_rows")]
+    FilteredRows,
+    // time extractors slept for [extractor] max_rows_per_sec / max_mb_per_sec
+    #[strum(serialize = "throttled_ms")]
+    ThrottledMs,
+    // pings of mysql/pg sinkers waiting for the pool to recover from connection errors,
+    // and the time slept between them, reconnect_wait_ms > 0 means the sinker is reconnecting
+    #[strum(serialize = "reconnect_attempts")]
+    ReconnectAttempts,
+    #[strum(serialize = "reconnect_wait_ms")]
+    ReconnectWaitMs,
 
     // time window counter, aggregate by: avg by window
     #[strum(serialize = "record_count")]
@@ -25,10 +47,40 @@ pub enum CounterType {
     DataBytes,
     #[strum(serialize = "record_size")]
     RecordSize,
+    // max / min data size of partitions in a batch, in percent
+    #[strum(serialize = "skew_coefficient")]
+    SkewCoefficient,
+    // sampled when a connection is checked out from the sinker pool
+    #[strum(serialize = "pool_active_connections")]
+    PoolActiveConnections,
+    #[strum(serialize = "pool_idle_connections")]
+    PoolIdleConnections,
+    #[strum(serialize = "pool_wait_queue_depth")]
+    PoolWaitQueueDepth,
+    #[strum(serialize = "pool_checkout_ms")]
+    PoolCheckoutMs,
+    // now - timestamp of the latest extracted cdc event
+    #[strum(serialize = "cdc_lag_ms")]
+    CdcLagMs,
+    // batch size of mysql/pg sinkers after each sub batch, changes with adaptive_batch_size
+    #[strum(serialize = "effective_batch_size")]
+    EffectiveBatchSize,
+    // rows / bytes extracted per second between two flushes of the extractor monitor,
+    // only added if extraction is throttled
+    #[strum(serialize = "extract_rows_per_sec")]
+    ExtractRowsPerSec,
+    #[strum(serialize = "extract_bytes_per_sec")]
+    ExtractBytesPerSec,
 
     // no window counter
     #[strum(serialize = "sinked_count")]
     SinkedCount,
+    // rows compared with the target by checkers, after [sinker] sample_percent
+    #[strum(serialize = "checked_rows")]
+    CheckedRows,
+    // rows of miss.log and diff.log, mismatch rate = mismatched_rows / checked_rows
+    #[strum(serialize = "mismatched_rows")]
+    MismatchedRows,
 }
 
 #[derive(EnumString, IntoStaticStr, Display, PartialEq, Eq, Hash, Clone)]
@@ -59,14 +111,31 @@ impl CounterType {
         match self {
             Self::BatchWriteFailures
             | Self::SerialWrites
+            | Self::AdvisoryLockContentions
+            | Self::DeadlockRetries
+            | Self::FilteredKeys
+            | Self::DeadLetterRows
+            | Self::FilteredRows
+            | Self::ThrottledMs
+            | Self::ReconnectAttempts
+            | Self::ReconnectWaitMs
             | Self::RecordCount
             | Self::BytesPerQuery
             | Self::RecordsPerQuery
             | Self::RtPerQuery
             | Self::BufferSize
             | Self::DataBytes
-            | Self::RecordSize => WindowType::TimeWindow,
-            Self::SinkedCount => WindowType::NoWindow,
+            | Self::RecordSize
+            | Self::SkewCoefficient
+            | Self::PoolActiveConnections
+            | Self::PoolIdleConnections
+            | Self::PoolWaitQueueDepth
+            | Self::PoolCheckoutMs
+            | Self::CdcLagMs
+            | Self::EffectiveBatchSize
+            | Self::ExtractRowsPerSec
+            | Self::ExtractBytesPerSec => WindowType::TimeWindow,
+            Self::SinkedCount | Self::CheckedRows | Self::MismatchedRows => WindowType::NoWindow,
         }
     }
 
@@ -78,7 +147,8 @@ impl CounterType {
                 Self::BytesPerQuery
                 | Self::RecordsPerQuery
                 | Self::RtPerQuery
-                | Self::BufferSize => {
+                | Self::BufferSize
+                | Self::SkewCoefficient => {
                     vec![
                         AggregateType::AvgByCount,
                         AggregateType::Sum,
@@ -90,8 +160,27 @@ impl CounterType {
                     vec![AggregateType::AvgByCount]
                 }
 
+                Self::PoolActiveConnections
+                | Self::PoolIdleConnections
+                | Self::PoolWaitQueueDepth
+                | Self::PoolCheckoutMs
+                | Self::CdcLagMs
+                | Self::EffectiveBatchSize
+                | Self::ExtractRowsPerSec
+                | Self::ExtractBytesPerSec => {
+                    vec![AggregateType::AvgByCount, AggregateType::MaxByCount]
+                }
+
                 Self::BatchWriteFailures
                 | Self::SerialWrites
+                | Self::AdvisoryLockContentions
+                | Self::DeadlockRetries
+                | Self::FilteredKeys
+                | Self::DeadLetterRows
+                | Self::FilteredRows
+                | Self::ThrottledMs
+                | Self::ReconnectAttempts
+                | Self::ReconnectWaitMs
                 | Self::RecordCount
                 | Self::DataBytes => {
                     vec![
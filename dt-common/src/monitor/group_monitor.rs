@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex, MutexGuard};
 
 use crate::monitor::counter_type::AggregateType;
@@ -83,6 +83,8 @@ impl GroupMonitor {
         let mut window_counter_statistics_map: HashMap<CounterType, Vec<WindowCounterStatistics>> =
             HashMap::new();
         let mut no_window_counter_statistics_map = self.no_window_counter_statistics_map.clone();
+        let mut partition_counters = BTreeMap::new();
+        let mut snapshot_progresses = BTreeMap::new();
 
         for (id, monitor) in self.monitors.iter() {
             match monitor.lock().as_mut() {
@@ -99,6 +101,9 @@ impl GroupMonitor {
                         &mut no_window_counter_statistics_map,
                         guard,
                     );
+
+                    partition_counters.extend(guard.partition_counters.clone());
+                    snapshot_progresses.extend(guard.snapshot_progresses.clone());
                 }
 
                 Err(e) => {
@@ -136,5 +141,17 @@ impl GroupMonitor {
             }
             log_monitor!("{}", log);
         }
+
+        for (partition, count) in partition_counters.iter() {
+            log_monitor!(
+                "{} | {} | partition | {} | record_count={}",
+                self.name,
+                self.description,
+                partition,
+                count
+            );
+        }
+
+        Monitor::log_snapshot_progresses(&self.name, &self.description, &snapshot_progresses);
     }
 }
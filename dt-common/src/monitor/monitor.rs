@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::cmp;
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
 
 use crate::log_monitor;
 use crate::monitor::counter_type::AggregateType;
@@ -17,6 +20,30 @@ pub struct Monitor {
     pub time_window_secs: usize,
     pub max_sub_count: usize,
     pub count_window: usize,
+    // schema.tb.partition -> extracted records, for partition based snapshot extraction
+    pub partition_counters: BTreeMap<String, usize>,
+    // schema.tb -> (extracted records, total records) of tables being extracted by snapshot
+    pub snapshot_progresses: BTreeMap<String, (usize, usize)>,
+    // schema.tb -> extracted records and bytes in time window
+    pub table_counters: BTreeMap<String, TableCounters>,
+}
+
+#[derive(Clone)]
+pub struct TableCounters {
+    pub record_count: TimeWindowCounter,
+    pub data_bytes: TimeWindowCounter,
+    // since the monitor was created
+    pub record_total: usize,
+    pub data_bytes_total: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TableStatistics {
+    pub records_per_sec: usize,
+    pub bytes_per_sec: usize,
+    // in time window
+    pub record_count: usize,
+    pub data_bytes: usize,
 }
 
 impl FlushableMonitor for Monitor {
@@ -41,6 +68,9 @@ impl Monitor {
             time_window_secs,
             max_sub_count,
             count_window,
+            partition_counters: BTreeMap::new(),
+            snapshot_progresses: BTreeMap::new(),
+            table_counters: BTreeMap::new(),
         }
     }
 
@@ -75,6 +105,102 @@ impl Monitor {
             }
             log_monitor!("{}", log);
         }
+
+        for (partition, count) in self.partition_counters.iter() {
+            log_monitor!(
+                "{} | {} | partition | {} | record_count={}",
+                self.name,
+                self.description,
+                partition,
+                count
+            );
+        }
+
+        Self::log_snapshot_progresses(&self.name, &self.description, &self.snapshot_progresses);
+
+        for (tb, statistics) in self.get_table_statistics() {
+            log_monitor!(
+                "{} | {} | table | {} | records_per_sec={} | bytes_per_sec={} | record_count={} | data_bytes={}",
+                self.name,
+                self.description,
+                tb,
+                statistics.records_per_sec,
+                statistics.bytes_per_sec,
+                statistics.record_count,
+                statistics.data_bytes
+            );
+        }
+    }
+
+    // schema.tb -> throughput in time window, tables without records in time window are skipped
+    pub fn get_table_statistics(&mut self) -> BTreeMap<String, TableStatistics> {
+        let mut results = BTreeMap::new();
+        for (tb, counters) in self.table_counters.iter_mut() {
+            let record_count = counters.record_count.statistics();
+            let data_bytes = counters.data_bytes.statistics();
+            if record_count.sum == 0 {
+                continue;
+            }
+            results.insert(
+                tb.clone(),
+                TableStatistics {
+                    records_per_sec: record_count.avg_by_sec,
+                    bytes_per_sec: data_bytes.avg_by_sec,
+                    record_count: record_count.sum,
+                    data_bytes: data_bytes.sum,
+                },
+            );
+        }
+        results
+    }
+
+    // schema.tb -> (records, bytes) since the monitor was created
+    pub fn get_table_totals(&self) -> BTreeMap<String, (usize, usize)> {
+        self.table_counters
+            .iter()
+            .map(|(tb, counters)| {
+                (
+                    tb.clone(),
+                    (counters.record_total, counters.data_bytes_total),
+                )
+            })
+            .collect()
+    }
+
+    // counter_type -> aggregate_type -> value, same as logged by flush
+    pub fn get_statistics(&mut self) -> BTreeMap<String, BTreeMap<String, usize>> {
+        let mut results = BTreeMap::new();
+        for (counter_type, counter) in self.time_window_counters.iter_mut() {
+            let statistics = counter.statistics();
+            let mut values = BTreeMap::new();
+            for aggregate_type in counter_type.get_aggregate_types() {
+                let aggregate_value = match aggregate_type {
+                    AggregateType::AvgByCount => statistics.avg_by_count,
+                    AggregateType::AvgBySec => statistics.avg_by_sec,
+                    AggregateType::Sum => statistics.sum,
+                    AggregateType::MaxBySec => statistics.max_by_sec,
+                    AggregateType::MaxByCount => statistics.max,
+                    AggregateType::Count => statistics.count,
+                    _ => continue,
+                };
+                values.insert(aggregate_type.to_string(), aggregate_value);
+            }
+            results.insert(counter_type.to_string(), values);
+        }
+
+        for (counter_type, counter) in self.no_window_counters.iter() {
+            let mut values = BTreeMap::new();
+            for aggregate_type in counter_type.get_aggregate_types() {
+                let aggregate_value = match aggregate_type {
+                    AggregateType::Latest => counter.value,
+                    AggregateType::AvgByCount => counter.avg_by_count(),
+                    _ => continue,
+                };
+                values.insert(aggregate_type.to_string(), aggregate_value);
+            }
+            results.insert(counter_type.to_string(), values);
+        }
+        results
     }
 
     pub fn add_batch_counter(
@@ -89,6 +215,101 @@ impl Monitor {
         self.add_counter_internal(counter_type, value, count)
     }
 
+    pub fn add_partition_counter(&mut self, partition: &str, value: usize) -> &mut Self {
+        *self
+            .partition_counters
+            .entry(partition.to_string())
+            .or_default() += value;
+        self
+    }
+
+    pub fn set_snapshot_progress(
+        &mut self,
+        tb: &str,
+        extracted_count: usize,
+        total_count: usize,
+    ) -> &mut Self {
+        self.snapshot_progresses
+            .insert(tb.to_string(), (extracted_count, total_count));
+        self
+    }
+
+    // called once the table is extracted, so finished tables are not logged forever
+    pub fn remove_snapshot_progress(&mut self, tb: &str) -> &mut Self {
+        self.snapshot_progresses.remove(tb);
+        self
+    }
+
+    // partitions of a table are keyed by schema.tb.partition, removed once the table is extracted
+    pub fn remove_partition_counters(&mut self, tb: &str) -> &mut Self {
+        let prefix = format!("{}.", tb);
+        self.partition_counters
+            .retain(|partition, _| !partition.starts_with(&prefix));
+        self
+    }
+
+    // the total may be estimated by table statistics, so the percent is capped at 100
+    pub fn get_snapshot_percent(extracted_count: usize, total_count: usize) -> usize {
+        cmp::min(extracted_count * 100 / cmp::max(total_count, 1), 100)
+    }
+
+    pub fn log_snapshot_progresses(
+        name: &str,
+        description: &str,
+        snapshot_progresses: &BTreeMap<String, (usize, usize)>,
+    ) {
+        for (tb, (extracted_count, total_count)) in snapshot_progresses.iter() {
+            log_monitor!(
+                "{} | {} | snapshot_progress | {} | extracted_count={} | total_count={} | percent={}",
+                name,
+                description,
+                tb,
+                extracted_count,
+                total_count,
+                Self::get_snapshot_percent(*extracted_count, *total_count)
+            );
+        }
+    }
+
+    pub fn add_table_counter(
+        &mut self,
+        tb: &str,
+        record_count: usize,
+        data_bytes: usize,
+    ) -> &mut Self {
+        if record_count == 0 {
+            return self;
+        }
+        if !self.table_counters.contains_key(tb) {
+            let counters = TableCounters {
+                record_count: TimeWindowCounter::new(self.time_window_secs, self.max_sub_count),
+                data_bytes: TimeWindowCounter::new(self.time_window_secs, self.max_sub_count),
+                record_total: 0,
+                data_bytes_total: 0,
+            };
+            self.table_counters.insert(tb.to_string(), counters);
+        }
+        let counters = self.table_counters.get_mut(tb).unwrap();
+        counters.record_count.add(record_count, 1);
+        counters.data_bytes.add(data_bytes, 1);
+        counters.record_total += record_count;
+        counters.data_bytes_total += data_bytes;
+        self
+    }
+
+    // tables dropped in cdc, E.g. rotated log tables, won't have records any more,
+    // all tables of the schema are removed if tb is empty
+    pub fn remove_table_counters(&mut self, schema: &str, tb: &str) -> &mut Self {
+        if tb.is_empty() {
+            let prefix = format!("{}.", schema);
+            self.table_counters
+                .retain(|key, _| !key.starts_with(&prefix));
+        } else {
+            self.table_counters.remove(&format!("{}.{}", schema, tb));
+        }
+        self
+    }
+
     pub fn add_counter(&mut self, counter_type: CounterType, value: usize) -> &mut Self {
         self.add_counter_internal(counter_type, value, 1)
     }
@@ -123,3 +344,94 @@ impl Monitor {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_table_statistics() {
+        let mut monitor = Monitor::new("extractor", "", 10, 100, 10);
+        monitor
+            .add_table_counter("db_1.tb_1", 10, 1000)
+            .add_table_counter("db_1.tb_1", 5, 500)
+            .add_table_counter("db_1.tb_2", 1, 100)
+            .add_table_counter("db_1.tb_3", 0, 0);
+
+        let statistics = monitor.get_table_statistics();
+        assert_eq!(statistics.len(), 2);
+        assert_eq!(
+            statistics.get("db_1.tb_1"),
+            Some(&TableStatistics {
+                records_per_sec: 15,
+                bytes_per_sec: 1500,
+                record_count: 15,
+                data_bytes: 1500,
+            })
+        );
+        assert_eq!(statistics.get("db_1.tb_2").unwrap().record_count, 1);
+
+        let totals = monitor.get_table_totals();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals.get("db_1.tb_1"), Some(&(15, 1500)));
+    }
+
+    #[test]
+    fn test_remove_table_counters() {
+        let mut monitor = Monitor::new("pipeline", "", 10, 100, 10);
+        monitor
+            .add_table_counter("db_1.tb_1", 10, 1000)
+            .add_table_counter("db_1.tb_2", 10, 1000)
+            .add_table_counter("db_10.tb_1", 10, 1000)
+            .add_table_counter("db_2.tb_1", 10, 1000);
+
+        monitor.remove_table_counters("db_1", "tb_1");
+        assert_eq!(
+            monitor.get_table_totals().into_keys().collect::<Vec<_>>(),
+            vec!["db_1.tb_2", "db_10.tb_1", "db_2.tb_1"]
+        );
+
+        // dropped schema
+        monitor.remove_table_counters("db_1", "");
+        assert_eq!(
+            monitor.get_table_totals().into_keys().collect::<Vec<_>>(),
+            vec!["db_10.tb_1", "db_2.tb_1"]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_progress() {
+        let mut monitor = Monitor::new("extractor", "", 10, 100, 10);
+        monitor
+            .set_snapshot_progress("db_1.tb_1", 50, 100)
+            .set_snapshot_progress("db_1.tb_2", 10, 100);
+        assert_eq!(
+            monitor.snapshot_progresses.get("db_1.tb_1"),
+            Some(&(50, 100))
+        );
+
+        monitor.remove_snapshot_progress("db_1.tb_1");
+        assert_eq!(monitor.snapshot_progresses.len(), 1);
+
+        assert_eq!(Monitor::get_snapshot_percent(50, 100), 50);
+        // estimated total
+        assert_eq!(Monitor::get_snapshot_percent(150, 100), 100);
+        assert_eq!(Monitor::get_snapshot_percent(10, 0), 100);
+        assert_eq!(Monitor::get_snapshot_percent(0, 0), 0);
+    }
+
+    #[test]
+    fn test_remove_partition_counters() {
+        let mut monitor = Monitor::new("extractor", "", 10, 100, 10);
+        monitor
+            .add_partition_counter("db_1.tb_1.p0", 10)
+            .add_partition_counter("db_1.tb_1.p0", 5)
+            .add_partition_counter("db_1.tb_1.p1", 10)
+            .add_partition_counter("db_1.tb_10.p0", 10);
+        assert_eq!(monitor.partition_counters.get("db_1.tb_1.p0"), Some(&15));
+
+        monitor.remove_partition_counters("db_1.tb_1");
+        assert_eq!(monitor.partition_counters.len(), 1);
+        assert_eq!(monitor.partition_counters.get("db_1.tb_10.p0"), Some(&10));
+    }
+}
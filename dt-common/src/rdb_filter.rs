@@ -5,17 +5,17 @@ use crate::{
         config_enums::DbType, config_token_parser::ConfigTokenParser, filter_config::FilterConfig,
     },
     meta::{
-        ddl_meta::ddl_type::DdlType, row_type::RowType,
+        ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
+        row_type::RowType,
         struct_meta::structure::structure_type::StructureType,
     },
-    utils::sql_util::SqlUtil,
+    utils::{name_pattern::NamePattern, sql_util::SqlUtil},
 };
 
-use anyhow::Context;
-use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 type IgnoreCols = HashMap<(String, String), HashSet<String>>;
+type IncludeCols = HashMap<(String, String), HashSet<String>>;
 type WhereConditions = HashMap<(String, String), String>;
 
 const JSON_PREFIX: &str = "json:";
@@ -23,16 +23,20 @@ const JSON_PREFIX: &str = "json:";
 #[derive(Debug, Clone)]
 pub struct RdbFilter {
     pub db_type: DbType,
-    pub do_schemas: HashSet<String>,
-    pub ignore_schemas: HashSet<String>,
-    pub do_tbs: HashSet<(String, String)>,
-    pub ignore_tbs: HashSet<(String, String)>,
+    pub do_schemas: Vec<NamePattern>,
+    pub ignore_schemas: Vec<NamePattern>,
+    pub do_tbs: Vec<(NamePattern, NamePattern)>,
+    pub ignore_tbs: Vec<(NamePattern, NamePattern)>,
     pub ignore_cols: IgnoreCols,
+    pub include_cols: IncludeCols,
     pub do_events: HashSet<String>,
     pub do_structures: HashSet<String>,
     pub do_ddls: HashSet<String>,
     pub ignore_cmds: HashSet<String>,
+    pub do_keys: Vec<NamePattern>,
+    pub ignore_keys: Vec<NamePattern>,
     pub where_conditions: WhereConditions,
+    pub include_server_settings: bool,
     pub cache: HashMap<(String, String), bool>,
 }
 
@@ -40,33 +44,35 @@ impl RdbFilter {
     pub fn from_config(config: &FilterConfig, db_type: &DbType) -> anyhow::Result<Self> {
         Ok(Self {
             db_type: db_type.to_owned(),
-            do_schemas: Self::parse_single_tokens(&config.do_schemas, db_type)?,
-            ignore_schemas: Self::parse_single_tokens(&config.ignore_schemas, db_type)?,
-            do_tbs: Self::parse_pair_tokens(&config.do_tbs, db_type)?,
-            ignore_tbs: Self::parse_pair_tokens(&config.ignore_tbs, db_type)?,
+            do_schemas: Self::parse_schema_patterns(&config.do_schemas, db_type)?,
+            ignore_schemas: Self::parse_schema_patterns(&config.ignore_schemas, db_type)?,
+            do_tbs: Self::parse_tb_patterns(&config.do_tbs, db_type)?,
+            ignore_tbs: Self::parse_tb_patterns(&config.ignore_tbs, db_type)?,
             ignore_cols: Self::parse_ignore_cols(&config.ignore_cols)?,
+            include_cols: Self::parse_include_cols(&config.include_cols)?,
             do_events: Self::parse_single_tokens(&config.do_events, db_type)?,
             do_structures: Self::parse_single_tokens(&config.do_structures, db_type)?,
             do_ddls: Self::parse_single_tokens(&config.do_ddls, db_type)?,
             ignore_cmds: Self::parse_single_tokens(&config.ignore_cmds, db_type)?,
+            do_keys: Self::parse_key_patterns(&config.do_keys)?,
+            ignore_keys: Self::parse_key_patterns(&config.ignore_keys)?,
             where_conditions: Self::parse_where_conditions(&config.where_conditions)?,
+            include_server_settings: config.include_server_settings,
             cache: HashMap::new(),
         })
     }
 
     pub fn filter_schema(&mut self, schema: &str) -> bool {
         let tb = "*";
-        let escape_pairs = SqlUtil::get_escape_pairs(&self.db_type);
-        let filter = Self::contain_tb(&self.ignore_tbs, schema, tb, &escape_pairs)
-            || Self::contain_schema(&self.ignore_schemas, schema, &escape_pairs);
+        let filter = Self::contain_tb(&self.ignore_tbs, schema, tb)
+            || Self::contain_schema(&self.ignore_schemas, schema);
 
         if filter {
             return filter;
         }
 
-        let do_tb_schemas: HashSet<String> = self.do_tbs.iter().map(|(d, _)| d.clone()).collect();
-        let keep = Self::contain_schema(&self.do_schemas, schema, &escape_pairs)
-            || Self::contain_schema(&do_tb_schemas, schema, &escape_pairs);
+        let keep = Self::contain_schema(&self.do_schemas, schema)
+            || self.do_tbs.iter().any(|(d, _)| d.is_match(schema));
         !keep
     }
 
@@ -75,11 +81,11 @@ impl RdbFilter {
             return *cache;
         }
 
-        let escape_pairs = SqlUtil::get_escape_pairs(&self.db_type);
-        let filter = Self::contain_tb(&self.ignore_tbs, schema, tb, &escape_pairs)
-            || Self::contain_schema(&self.ignore_schemas, schema, &escape_pairs);
-        let keep = Self::contain_tb(&self.do_tbs, schema, tb, &escape_pairs)
-            || Self::contain_schema(&self.do_schemas, schema, &escape_pairs);
+        // ignore_* takes precedence over do_* if both matched
+        let filter = Self::contain_tb(&self.ignore_tbs, schema, tb)
+            || Self::contain_schema(&self.ignore_schemas, schema);
+        let keep = Self::contain_tb(&self.do_tbs, schema, tb)
+            || Self::contain_schema(&self.do_schemas, schema);
 
         let filter = filter || !keep;
         self.cache
@@ -111,6 +117,22 @@ impl RdbFilter {
         }
     }
 
+    // a rename is kept only if both the renamed table and the rename-to table are kept:
+    // renaming a filtered table can not be applied since it is missing in target,
+    // renaming to a filtered table would leave a target table that receives no more changes
+    pub fn filter_ddl_data(&mut self, ddl_data: &DdlData) -> bool {
+        let (schema, tb) = ddl_data.get_schema_tb();
+        if self.filter_ddl(&schema, &tb, &ddl_data.ddl_type) {
+            return true;
+        }
+
+        let (new_schema, new_tb) = ddl_data.get_rename_to_schema_tb();
+        if new_tb.is_empty() {
+            return false;
+        }
+        self.filter_ddl(&new_schema, &new_tb, &ddl_data.ddl_type)
+    }
+
     pub fn filter_structure(&self, structure_type: &StructureType) -> bool {
         !Self::match_all(&self.do_structures)
             && !self.do_structures.contains(&structure_type.to_string())
@@ -120,16 +142,32 @@ impl RdbFilter {
         self.ignore_cmds.contains(cmd)
     }
 
+    // for redis keys, all keys are kept if do_keys is empty
+    pub fn filter_key(&self, key: &str) -> bool {
+        (!self.do_keys.is_empty() && !Self::contain_schema(&self.do_keys, key))
+            || Self::contain_schema(&self.ignore_keys, key)
+    }
+
     pub fn get_ignore_cols(&self, schema: &str, tb: &str) -> Option<&HashSet<String>> {
         self.ignore_cols.get(&(schema.to_string(), tb.to_string()))
     }
 
+    pub fn get_include_cols(&self, schema: &str, tb: &str) -> Option<&HashSet<String>> {
+        self.include_cols.get(&(schema.to_string(), tb.to_string()))
+    }
+
     pub fn add_ignore_tb(&mut self, schema: &str, tb: &str) {
-        self.ignore_tbs.insert((schema.into(), tb.into()));
+        self.ignore_tbs.push((
+            NamePattern::Exact(schema.into()),
+            NamePattern::Exact(tb.into()),
+        ));
     }
 
     pub fn add_do_tb(&mut self, schema: &str, tb: &str) {
-        self.do_tbs.insert((schema.into(), tb.into()));
+        self.do_tbs.push((
+            NamePattern::Exact(schema.into()),
+            NamePattern::Exact(tb.into()),
+        ));
     }
 
     pub fn get_where_condition(&self, schema: &str, tb: &str) -> Option<&String> {
@@ -141,68 +179,105 @@ impl RdbFilter {
         set.len() == 1 && set.contains("*")
     }
 
-    fn contain_tb(
-        set: &HashSet<(String, String)>,
-        schema: &str,
-        tb: &str,
-        escape_pairs: &[(char, char)],
-    ) -> bool {
-        for i in set.iter() {
-            if Self::match_token(&i.0, schema, escape_pairs)
-                && Self::match_token(&i.1, tb, escape_pairs)
-            {
-                return true;
-            }
-        }
-        false
+    fn contain_tb(patterns: &[(NamePattern, NamePattern)], schema: &str, tb: &str) -> bool {
+        patterns.iter().any(|(schema_pattern, tb_pattern)| {
+            schema_pattern.is_match(schema) && tb_pattern.is_match(tb)
+        })
     }
 
-    fn contain_schema(set: &HashSet<String>, item: &str, escape_pairs: &[(char, char)]) -> bool {
-        for i in set.iter() {
-            if Self::match_token(i, item, escape_pairs) {
-                return true;
-            }
-        }
-        false
+    fn contain_schema(patterns: &[NamePattern], schema: &str) -> bool {
+        patterns.iter().any(|pattern| pattern.is_match(schema))
     }
 
+    // kept to test the semantics of config tokens, items may be escaped the same as patterns
+    #[cfg(test)]
     fn match_token(pattern: &str, item: &str, escape_pairs: &[(char, char)]) -> bool {
-        // if pattern is enclosed by escapes, it is considered as exactly match
-        // example: mysql table name : `aaa*`, it can only match the table `aaa*`, it won't match `aaa_bbb`
+        let mut item = item.to_string();
         for escape_pair in escape_pairs.iter() {
             if SqlUtil::is_escaped(pattern, escape_pair) {
-                return pattern == SqlUtil::escape(item, escape_pair);
+                item = SqlUtil::unescape(&item, escape_pair);
             }
         }
-        // only support 2 wildchars : '*' and '?', '.' is NOT supported
-        // * : matching mutiple chars
-        // ? : for matching 0-1 chars
-        let mut pattern = pattern
-            .replace('.', "\\.")
-            .replace('*', ".*")
-            .replace('?', ".?");
-        pattern = format!(r"^{}$", pattern);
-
-        Regex::new(&pattern)
-            .with_context(|| format!("invalid filter pattern: [{}]", pattern))
+        NamePattern::from_token(pattern, escape_pairs)
             .unwrap()
-            .is_match(item)
+            .is_match(&item)
     }
 
-    fn parse_pair_tokens(
+    // do_dbs=db_1,db_*
+    // do_dbs=json:["db_\\d+"]
+    fn parse_schema_patterns(
         config_str: &str,
         db_type: &DbType,
-    ) -> anyhow::Result<HashSet<(String, String)>> {
-        let mut results = HashSet::new();
+    ) -> anyhow::Result<Vec<NamePattern>> {
+        if config_str.trim().starts_with(JSON_PREFIX) {
+            let config: Vec<String> =
+                serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+            return config.iter().map(|i| NamePattern::from_regex(i)).collect();
+        }
+
+        let escape_pairs = SqlUtil::get_escape_pairs(db_type);
+        Self::parse_config(config_str, db_type)?
+            .iter()
+            .map(|token| NamePattern::from_token(token, &escape_pairs))
+            .collect()
+    }
+
+    // do_tbs=db_1.tb_1,db_1.orders_*
+    // do_tbs=json:[{"db":"db_1","tb":"orders_\\d+"}]
+    fn parse_tb_patterns(
+        config_str: &str,
+        db_type: &DbType,
+    ) -> anyhow::Result<Vec<(NamePattern, NamePattern)>> {
+        let mut results = Vec::new();
+        if config_str.trim().starts_with(JSON_PREFIX) {
+            #[derive(Serialize, Deserialize)]
+            struct TbPatternType {
+                db: String,
+                tb: String,
+            }
+            let config: Vec<TbPatternType> =
+                serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+            for i in config {
+                results.push((
+                    NamePattern::from_regex(&i.db)?,
+                    NamePattern::from_regex(&i.tb)?,
+                ));
+            }
+            return Ok(results);
+        }
+
+        let escape_pairs = SqlUtil::get_escape_pairs(db_type);
         let tokens = Self::parse_config(config_str, db_type)?;
         let mut i = 0;
         while i < tokens.len() {
-            results.insert((tokens[i].to_string(), tokens[i + 1].to_string()));
+            results.push((
+                NamePattern::from_token(&tokens[i], &escape_pairs)?,
+                NamePattern::from_token(&tokens[i + 1], &escape_pairs)?,
+            ));
             i += 2;
         }
         Ok(results)
     }
 
+    // redis keys may contain '.', only ',' is the delimiter and there are no escapes
+    // do_keys=session:*,user:?
+    // do_keys=json:["order:\\d+"]
+    fn parse_key_patterns(config_str: &str) -> anyhow::Result<Vec<NamePattern>> {
+        let config_str = config_str.trim();
+        if config_str.starts_with(JSON_PREFIX) {
+            let config: Vec<String> =
+                serde_json::from_str(config_str.trim_start_matches(JSON_PREFIX))?;
+            return config.iter().map(|i| NamePattern::from_regex(i)).collect();
+        }
+
+        config_str
+            .split(',')
+            .map(|token| token.trim())
+            .filter(|token| !token.is_empty())
+            .map(|token| NamePattern::from_token(token, &[]))
+            .collect()
+    }
+
     fn parse_single_tokens(config_str: &str, db_type: &DbType) -> anyhow::Result<HashSet<String>> {
         let tokens = Self::parse_config(config_str, db_type)?;
         let results: HashSet<String> = HashSet::from_iter(tokens);
@@ -234,6 +309,26 @@ impl RdbFilter {
         Ok(results)
     }
 
+    fn parse_include_cols(config_str: &str) -> anyhow::Result<IncludeCols> {
+        let mut results = IncludeCols::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+        // include_cols=json:[{"db":"test_db","tb":"tb_1","include_cols":["f_0","f_1"]}]
+        #[derive(Serialize, Deserialize)]
+        struct IncludeColsType {
+            db: String,
+            tb: String,
+            include_cols: HashSet<String>,
+        }
+        let config: Vec<IncludeColsType> =
+            serde_json::from_str(config_str.trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            results.insert((i.db, i.tb), i.include_cols);
+        }
+        Ok(results)
+    }
+
     fn parse_where_conditions(config_str: &str) -> anyhow::Result<WhereConditions> {
         let mut results = WhereConditions::new();
         if config_str.trim().is_empty() {
@@ -259,6 +354,9 @@ impl RdbFilter {
 mod tests {
 
     use super::*;
+    use crate::meta::ddl_meta::ddl_statement::{
+        DdlStatement, DropTableStatement, RenameTableStatement,
+    };
 
     #[test]
     fn test_parse_ignore_cols() {
@@ -278,6 +376,19 @@ mod tests {
         assert!(tb_2.contains(&"f_3".to_string()));
     }
 
+    #[test]
+    fn test_parse_include_cols() {
+        let config_str = r#"json:[{"db":"db_1","tb":"tb_1","include_cols":["id","f_1"]}]"#;
+        let include_cols = RdbFilter::parse_include_cols(config_str).unwrap();
+        let tb_1 = include_cols
+            .get(&("db_1".to_string(), "tb_1".to_string()))
+            .unwrap();
+        assert_eq!(tb_1.len(), 2);
+        assert!(tb_1.contains(&"id".to_string()));
+        assert!(tb_1.contains(&"f_1".to_string()));
+        assert!(!include_cols.contains_key(&("db_1".to_string(), "tb_2".to_string())));
+    }
+
     #[test]
     fn test_match_token_without_escape() {
         let escape_pairs = vec![];
@@ -603,6 +714,66 @@ mod tests {
         assert!(!rdb_fitler.filter_event("c*", "*", &RowType::Insert));
     }
 
+    #[test]
+    fn test_rdb_filter_do_tbs_with_regex() {
+        let db_type = DbType::Mysql;
+        let config = FilterConfig {
+            do_tbs: r#"json:[{"db":"db_\\d+","tb":"orders_\\d{4}"}]"#.to_string(),
+            do_events: "insert".to_string(),
+            ..Default::default()
+        };
+        let mut rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_fitler.filter_event("db_1", "orders_0001", &RowType::Insert));
+        assert!(!rdb_fitler.filter_event("db_12", "orders_2024", &RowType::Insert));
+        // the whole name should match
+        assert!(rdb_fitler.filter_event("db_1", "orders_0001_bak", &RowType::Insert));
+        assert!(rdb_fitler.filter_event("db_a", "orders_0001", &RowType::Insert));
+        assert!(!rdb_fitler.filter_schema("db_1"));
+        assert!(rdb_fitler.filter_schema("db_a"));
+
+        let config = FilterConfig {
+            do_schemas: r#"json:["db_\\d+"]"#.to_string(),
+            ..Default::default()
+        };
+        let mut rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_fitler.filter_tb("db_1", "tb_1"));
+        assert!(rdb_fitler.filter_tb("db_a", "tb_1"));
+
+        let config = FilterConfig {
+            do_tbs: r#"json:[{"db":"db_1","tb":"orders_("}]"#.to_string(),
+            ..Default::default()
+        };
+        assert!(RdbFilter::from_config(&config, &db_type).is_err());
+    }
+
+    #[test]
+    fn test_rdb_filter_overlapping_patterns() {
+        let db_type = DbType::Mysql;
+        // ignore_* takes precedence over do_* no matter how specific the patterns are
+        let config = FilterConfig {
+            do_tbs: "db_1.orders_0001,db_1.orders_*".to_string(),
+            ignore_tbs: "db_1.orders_00*".to_string(),
+            ..Default::default()
+        };
+        let mut rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(rdb_fitler.filter_tb("db_1", "orders_0001"));
+        assert!(rdb_fitler.filter_tb("db_1", "orders_0002"));
+        assert!(!rdb_fitler.filter_tb("db_1", "orders_1001"));
+        assert!(rdb_fitler.filter_tb("db_1", "users"));
+
+        // wildcard and regex patterns may be mixed in do_* and ignore_*
+        let config = FilterConfig {
+            do_schemas: "db_*".to_string(),
+            ignore_tbs: r#"json:[{"db":".*","tb":".*_(bak|tmp)"}]"#.to_string(),
+            ..Default::default()
+        };
+        let mut rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_fitler.filter_tb("db_1", "orders"));
+        assert!(rdb_fitler.filter_tb("db_1", "orders_bak"));
+        assert!(rdb_fitler.filter_tb("db_2", "orders_tmp"));
+        assert!(rdb_fitler.filter_tb("other", "orders"));
+    }
+
     #[test]
     fn test_rdb_filter_db_without_escapes() {
         let db_type = DbType::Mysql;
@@ -852,4 +1023,76 @@ mod tests {
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Update));
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Delete));
     }
+
+    #[test]
+    fn test_filter_ddl_data() {
+        let db_type = DbType::Mysql;
+        let config = FilterConfig {
+            do_tbs: "a.*".to_string(),
+            ignore_tbs: "a.ignored".to_string(),
+            do_ddls: "*".to_string(),
+            ..Default::default()
+        };
+        let mut rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+
+        let rename_ddl = |tb: &str, new_schema: &str, new_tb: &str| DdlData {
+            default_schema: "a".to_string(),
+            ddl_type: DdlType::RenameTable,
+            statement: DdlStatement::RenameTable(RenameTableStatement {
+                schema: "a".to_string(),
+                tb: tb.to_string(),
+                new_schema: new_schema.to_string(),
+                new_tb: new_tb.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(!rdb_filter.filter_ddl_data(&rename_ddl("tb_1", "a", "tb_2")));
+        // rename-to schema defaults to default_schema
+        assert!(!rdb_filter.filter_ddl_data(&rename_ddl("tb_1", "", "tb_2")));
+        assert!(rdb_filter.filter_ddl_data(&rename_ddl("tb_1", "a", "ignored")));
+        assert!(rdb_filter.filter_ddl_data(&rename_ddl("tb_1", "b", "tb_1")));
+        assert!(rdb_filter.filter_ddl_data(&rename_ddl("ignored", "a", "tb_1")));
+
+        let drop_ddl = DdlData {
+            default_schema: "a".to_string(),
+            ddl_type: DdlType::DropTable,
+            statement: DdlStatement::DropTable(DropTableStatement {
+                schema: "a".to_string(),
+                tb: "tb_1".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(!rdb_filter.filter_ddl_data(&drop_ddl));
+    }
+
+    #[test]
+    fn test_filter_key() {
+        let db_type = DbType::Redis;
+        let config = FilterConfig {
+            do_keys: "session:*,user.?,order:1".to_string(),
+            ignore_keys: "session:tmp:*".to_string(),
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_filter.filter_key("session:1"));
+        assert!(!rdb_filter.filter_key("user.1"));
+        assert!(!rdb_filter.filter_key("order:1"));
+        assert!(rdb_filter.filter_key("order:10"));
+        assert!(rdb_filter.filter_key("session:tmp:1"));
+        assert!(rdb_filter.filter_key("other"));
+
+        let config = FilterConfig {
+            do_keys: r#"json:["order:\\d+"]"#.to_string(),
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_filter.filter_key("order:10"));
+        assert!(rdb_filter.filter_key("order:a"));
+
+        // all keys are kept by default
+        let rdb_filter = RdbFilter::from_config(&FilterConfig::default(), &db_type).unwrap();
+        assert!(!rdb_filter.filter_key("any"));
+    }
 }
@@ -0,0 +1,38 @@
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+pub struct HashUtil {}
+
+impl HashUtil {
+    // FNV-1a, unlike DefaultHasher its output never changes across rust releases,
+    // so it can be used for results persisted or compared between runs
+    pub fn fnv1a(bytes: &[u8]) -> u64 {
+        Self::fnv1a_append(FNV_OFFSET_BASIS, bytes)
+    }
+
+    // continue hashing from a previous hash, e.g. over values of multiple columns
+    pub fn fnv1a_append(mut hash: u64, bytes: &[u8]) -> u64 {
+        for b in bytes {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashUtil;
+
+    #[test]
+    fn test_fnv1a() {
+        // reference values of 64-bit FNV-1a
+        assert_eq!(HashUtil::fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(HashUtil::fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(HashUtil::fnv1a(b"foobar"), 0x85944171f73967e8);
+        assert_eq!(
+            HashUtil::fnv1a_append(HashUtil::fnv1a(b"foo"), b"bar"),
+            HashUtil::fnv1a(b"foobar")
+        );
+    }
+}
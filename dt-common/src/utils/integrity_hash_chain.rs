@@ -0,0 +1,643 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::bail;
+use ring::{
+    digest::{Context, SHA256},
+    signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+};
+
+const INTEGRITY_HASH_TAG: &str = "integrity_hash | ";
+pub const DIGEST_LEN: usize = 32;
+
+// hash_n = SHA256(hash_{n-1} || serialize(batch_n)), any modification / reordering / loss of
+// sinked batches changes the final hash. A task may run several times with the same task_id
+// (e.g. restarted), each run starts a new chain identified by run_id.
+//
+// batch boundaries depend on timing, so the chain can not be rebuilt from data at rest,
+// instead a digest of each table is kept: the sum (mod 2^256) of SHA256 of inserted images
+// minus SHA256 of deleted images, an update counts as delete before + insert after. It only
+// depends on the final rows of the table, so it is rebuilt by re-reading the target
+pub struct IntegrityHashChain {
+    pub task_id: String,
+    pub run_id: String,
+    pub hash: Vec<u8>,
+    pub batch_count: u64,
+    pub row_count: u64,
+    // (schema, tb) -> (digest, net row count)
+    pub tb_digests: BTreeMap<(String, String), ([u8; DIGEST_LEN], i64)>,
+}
+
+// digest of the rows sinked into a table, see IntegrityHashChain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityTbDigest {
+    pub schema: String,
+    pub tb: String,
+    pub digest: String,
+    // inserted rows - deleted rows
+    pub row_count: i64,
+}
+
+// a line in the manifest file for each finished task, signed by ed25519
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityManifestEntry {
+    pub task_id: String,
+    // empty in manifests written before run_id was added
+    #[serde(default)]
+    pub run_id: String,
+    pub final_hash: String,
+    pub batch_count: u64,
+    pub row_count: u64,
+    // empty in manifests written before table digests were added
+    #[serde(default)]
+    pub tb_digests: Vec<IntegrityTbDigest>,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl IntegrityManifestEntry {
+    fn get_sign_message(&self) -> String {
+        let message = format!(
+            "{}|{}|{}|{}",
+            self.task_id, self.final_hash, self.batch_count, self.row_count
+        );
+        let message = if self.run_id.is_empty() {
+            message
+        } else {
+            format!("{}|{}", message, self.run_id)
+        };
+        if self.tb_digests.is_empty() {
+            return message;
+        }
+
+        let tb_digests: Vec<String> = self
+            .tb_digests
+            .iter()
+            .map(|i| format!("{}.{}={}:{}", i.schema, i.tb, i.digest, i.row_count))
+            .collect();
+        format!("{}|{}", message, tb_digests.join(","))
+    }
+}
+
+impl IntegrityHashChain {
+    pub fn new(task_id: &str) -> Self {
+        let run_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|i| i.as_micros())
+            .unwrap_or(0);
+        Self {
+            task_id: task_id.into(),
+            run_id: run_id.to_string(),
+            hash: vec![0; SHA256.output_len()],
+            batch_count: 0,
+            row_count: 0,
+            tb_digests: BTreeMap::new(),
+        }
+    }
+
+    pub fn update(&mut self, batch: &[RowData]) -> anyhow::Result<()> {
+        let mut context = Context::new(&SHA256);
+        context.update(&self.hash);
+        context.update(&Self::serialize_batch(batch)?);
+        self.hash = context.finish().as_ref().to_vec();
+        self.batch_count += 1;
+        self.row_count += batch.len() as u64;
+
+        for row_data in batch {
+            let (digest, row_count) = self
+                .tb_digests
+                .entry((row_data.schema.clone(), row_data.tb.clone()))
+                .or_insert(([0; DIGEST_LEN], 0));
+            if let Some(before) = &row_data.before {
+                if row_data.row_type != RowType::Insert {
+                    Self::sub_digest(digest, &Self::get_row_digest(before)?);
+                    *row_count -= 1;
+                }
+            }
+            if let Some(after) = &row_data.after {
+                if row_data.row_type != RowType::Delete {
+                    Self::add_digest(digest, &Self::get_row_digest(after)?);
+                    *row_count += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // SHA256 of a row image, values are compared as strings so that the same value
+    // read back from the target with a different ColValue variant has the same digest
+    pub fn get_row_digest(
+        col_values: &HashMap<String, ColValue>,
+    ) -> anyhow::Result<[u8; DIGEST_LEN]> {
+        let sorted: BTreeMap<&String, Option<String>> = col_values
+            .iter()
+            .map(|(col, value)| (col, value.to_option_string()))
+            .collect();
+        let digest = ring::digest::digest(&SHA256, &serde_json::to_vec(&sorted)?);
+        let mut result = [0; DIGEST_LEN];
+        result.copy_from_slice(digest.as_ref());
+        Ok(result)
+    }
+
+    // big endian, wrapping
+    pub fn add_digest(sum: &mut [u8; DIGEST_LEN], digest: &[u8; DIGEST_LEN]) {
+        let mut carry = 0u16;
+        for i in (0..DIGEST_LEN).rev() {
+            let v = sum[i] as u16 + digest[i] as u16 + carry;
+            sum[i] = v as u8;
+            carry = v >> 8;
+        }
+    }
+
+    pub fn sub_digest(sum: &mut [u8; DIGEST_LEN], digest: &[u8; DIGEST_LEN]) {
+        let mut borrow = 0i16;
+        for i in (0..DIGEST_LEN).rev() {
+            let mut v = sum[i] as i16 - digest[i] as i16 - borrow;
+            borrow = 0;
+            if v < 0 {
+                v += 256;
+                borrow = 1;
+            }
+            sum[i] = v as u8;
+        }
+    }
+
+    pub fn hash_hex(&self) -> String {
+        hex::encode(&self.hash)
+    }
+
+    // logged in position.log with each checkpoint, used by verification
+    pub fn to_checkpoint_log(&self) -> String {
+        format!(
+            "{}{} | run_id={} | {} | batch_count={}",
+            INTEGRITY_HASH_TAG,
+            self.task_id,
+            self.run_id,
+            self.hash_hex(),
+            self.batch_count
+        )
+    }
+
+    // private key: unencrypted pkcs8 der, e.g. openssl genpkey -algorithm ed25519 -outform DER
+    pub fn write_manifest(
+        &self,
+        manifest_file: &str,
+        private_key_file: &str,
+    ) -> anyhow::Result<()> {
+        let key_pair = Self::load_key_pair(private_key_file)?;
+        let mut entry = IntegrityManifestEntry {
+            task_id: self.task_id.clone(),
+            run_id: self.run_id.clone(),
+            final_hash: self.hash_hex(),
+            batch_count: self.batch_count,
+            row_count: self.row_count,
+            tb_digests: self
+                .tb_digests
+                .iter()
+                .map(|((schema, tb), (digest, row_count))| IntegrityTbDigest {
+                    schema: schema.clone(),
+                    tb: tb.clone(),
+                    digest: hex::encode(digest),
+                    row_count: *row_count,
+                })
+                .collect(),
+            public_key: hex::encode(key_pair.public_key().as_ref()),
+            signature: String::new(),
+        };
+        entry.signature = hex::encode(key_pair.sign(entry.get_sign_message().as_bytes()));
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(manifest_file)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    // verify the signature of each entry in manifest by the trusted public key (hex), and
+    // final hashes against the last checkpoints of the same runs in position.log, returns
+    // the verified entries. The public key embedded in an entry proves nothing since anyone
+    // can re-sign with their own key, so a trusted one is required
+    pub fn verify_manifest(
+        manifest_file: &str,
+        trusted_public_key: &str,
+        position_log_file: &str,
+    ) -> anyhow::Result<Vec<IntegrityManifestEntry>> {
+        if trusted_public_key.trim().is_empty() {
+            bail! {Error::ConfigError(
+                "a trusted public key is required to verify the integrity manifest".into()
+            )}
+        }
+        let checkpoints = Self::load_checkpoints(position_log_file)?;
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(File::open(manifest_file)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry: IntegrityManifestEntry = serde_json::from_str(&line)?;
+            if !entry
+                .public_key
+                .eq_ignore_ascii_case(trusted_public_key.trim())
+            {
+                bail! {Error::IntegrityError(format!(
+                    "untrusted public key, task_id: {}, public_key: {}",
+                    entry.task_id, entry.public_key
+                ))}
+            }
+
+            let public_key = UnparsedPublicKey::new(&ED25519, hex::decode(&entry.public_key)?);
+            let signature = hex::decode(&entry.signature)?;
+            if public_key
+                .verify(entry.get_sign_message().as_bytes(), &signature)
+                .is_err()
+            {
+                bail! {Error::IntegrityError(format!(
+                    "invalid signature, task_id: {}",
+                    entry.task_id
+                ))}
+            }
+
+            match checkpoints.get(&(entry.task_id.clone(), entry.run_id.clone())) {
+                Some((hash, batch_count))
+                    if *hash == entry.final_hash && *batch_count == entry.batch_count => {}
+                Some((hash, batch_count)) => bail! {Error::IntegrityError(format!(
+                    "hash chain mismatch, task_id: {}, manifest: {} (batch_count={}), checkpoint: {} (batch_count={})",
+                    entry.task_id, entry.final_hash, entry.batch_count, hash, batch_count
+                ))},
+                None => bail! {Error::IntegrityError(format!(
+                    "no checkpoint found, task_id: {}, run_id: {}",
+                    entry.task_id, entry.run_id
+                ))},
+            }
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    pub fn get_public_key_hex(private_key_file: &str) -> anyhow::Result<String> {
+        let key_pair = Self::load_key_pair(private_key_file)?;
+        Ok(hex::encode(key_pair.public_key().as_ref()))
+    }
+
+    fn load_key_pair(private_key_file: &str) -> anyhow::Result<Ed25519KeyPair> {
+        let pkcs8 = fs::read(private_key_file)?;
+        match Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pkcs8) {
+            Ok(key_pair) => Ok(key_pair),
+            Err(e) => bail! {Error::ConfigError(format!(
+                "invalid ed25519 private key: {}, error: {}",
+                private_key_file, e
+            ))},
+        }
+    }
+
+    // (task_id, run_id) -> (last hash, batch_count)
+    #[allow(clippy::type_complexity)]
+    fn load_checkpoints(
+        position_log_file: &str,
+    ) -> anyhow::Result<HashMap<(String, String), (String, u64)>> {
+        let mut checkpoints = HashMap::new();
+        for line in BufReader::new(File::open(position_log_file)?).lines() {
+            let line = line?;
+            if let Some((task_id, run_id, hash, batch_count)) = Self::parse_checkpoint_log(&line) {
+                checkpoints.insert((task_id, run_id), (hash, batch_count));
+            }
+        }
+        Ok(checkpoints)
+    }
+
+    // 2024-02-29 01:25:09.554271 | integrity_hash | test_db_1.tb_1 | run_id=1709169909554271 | 4f2a... | batch_count=10
+    // run_id is missing in logs written before it was added
+    fn parse_checkpoint_log(line: &str) -> Option<(String, String, String, u64)> {
+        let (_, log) = line.split_once(INTEGRITY_HASH_TAG)?;
+        let tokens: Vec<&str> = log.split(" | ").collect();
+        let (run_id, tokens) = match tokens.len() {
+            3 => ("", [tokens[0], tokens[1], tokens[2]]),
+            4 => (
+                tokens[1].strip_prefix("run_id=")?,
+                [tokens[0], tokens[2], tokens[3]],
+            ),
+            _ => return None,
+        };
+        let batch_count = tokens[2]
+            .trim()
+            .trim_start_matches("batch_count=")
+            .parse()
+            .ok()?;
+        Some((
+            tokens[0].into(),
+            run_id.into(),
+            tokens[1].into(),
+            batch_count,
+        ))
+    }
+
+    fn serialize_batch(batch: &[RowData]) -> anyhow::Result<Vec<u8>> {
+        // cols are sorted to make the result deterministic
+        #[derive(Serialize)]
+        struct SortedRowData<'a> {
+            schema: &'a str,
+            tb: &'a str,
+            row_type: &'a RowType,
+            before: Option<BTreeMap<&'a String, &'a ColValue>>,
+            after: Option<BTreeMap<&'a String, &'a ColValue>>,
+        }
+
+        let mut result = Vec::new();
+        for row_data in batch {
+            let sorted = SortedRowData {
+                schema: &row_data.schema,
+                tb: &row_data.tb,
+                row_type: &row_data.row_type,
+                before: Self::sort_cols(&row_data.before),
+                after: Self::sort_cols(&row_data.after),
+            };
+            serde_json::to_writer(&mut result, &sorted)?;
+            result.push(b'\n');
+        }
+        Ok(result)
+    }
+    fn sort_cols(
+        col_values: &Option<HashMap<String, ColValue>>,
+    ) -> Option<BTreeMap<&String, &ColValue>> {
+        col_values.as_ref().map(|v| v.iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, io::Write};
+
+    use ring::{
+        rand::SystemRandom,
+        signature::{Ed25519KeyPair, KeyPair},
+    };
+
+    use crate::meta::{col_value::ColValue, row_data::RowData, row_type::RowType};
+
+    use super::IntegrityHashChain;
+
+    fn mock_row_data(id: i32, name: &str) -> RowData {
+        let mut after = HashMap::new();
+        after.insert("id".to_string(), ColValue::Long(id));
+        after.insert("name".to_string(), ColValue::String(name.into()));
+        after.insert("age".to_string(), ColValue::Long(10));
+        RowData::new(
+            "test_db_1".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        )
+    }
+
+    #[test]
+    fn test_update() {
+        let batch_1 = vec![mock_row_data(1, "a"), mock_row_data(2, "b")];
+        let batch_2 = vec![mock_row_data(3, "c")];
+
+        let mut chain_1 = IntegrityHashChain::new("test_db_1.tb_1");
+        chain_1.update(&batch_1).unwrap();
+        chain_1.update(&batch_2).unwrap();
+        assert_eq!(chain_1.batch_count, 2);
+        assert_eq!(chain_1.row_count, 3);
+
+        // deterministic regardless of the iteration order of cols
+        let mut chain_2 = IntegrityHashChain::new("test_db_1.tb_1");
+        chain_2
+            .update(&[mock_row_data(1, "a"), mock_row_data(2, "b")])
+            .unwrap();
+        chain_2.update(&[mock_row_data(3, "c")]).unwrap();
+        assert_eq!(chain_1.hash_hex(), chain_2.hash_hex());
+
+        // batch order matters
+        let mut chain_3 = IntegrityHashChain::new("test_db_1.tb_1");
+        chain_3.update(&batch_2).unwrap();
+        chain_3.update(&batch_1).unwrap();
+        assert_ne!(chain_1.hash_hex(), chain_3.hash_hex());
+
+        // tampered data
+        let mut chain_4 = IntegrityHashChain::new("test_db_1.tb_1");
+        chain_4.update(&batch_1).unwrap();
+        chain_4.update(&[mock_row_data(3, "d")]).unwrap();
+        assert_ne!(chain_1.hash_hex(), chain_4.hash_hex());
+    }
+
+    #[test]
+    fn test_tb_digests() {
+        let get_digest = |chain: &IntegrityHashChain| {
+            chain.tb_digests[&("test_db_1".to_string(), "tb_1".to_string())]
+        };
+
+        // independent of batch boundaries and row order
+        let mut chain_1 = IntegrityHashChain::new("test_db_1.tb_1");
+        chain_1
+            .update(&[mock_row_data(1, "a"), mock_row_data(2, "b")])
+            .unwrap();
+        chain_1.update(&[mock_row_data(3, "c")]).unwrap();
+        let mut chain_2 = IntegrityHashChain::new("test_db_1.tb_1");
+        chain_2.update(&[mock_row_data(3, "c")]).unwrap();
+        chain_2
+            .update(&[mock_row_data(2, "b"), mock_row_data(1, "a")])
+            .unwrap();
+        assert_ne!(chain_1.hash_hex(), chain_2.hash_hex());
+        assert_eq!(get_digest(&chain_1), get_digest(&chain_2));
+        assert_eq!(get_digest(&chain_1).1, 3);
+
+        // rebuilt from the final rows, E.g. re-read from the target
+        let mut digest = [0; 32];
+        for row_data in [
+            mock_row_data(2, "b"),
+            mock_row_data(3, "c"),
+            mock_row_data(1, "a"),
+        ] {
+            let after = row_data.after.as_ref().unwrap();
+            IntegrityHashChain::add_digest(
+                &mut digest,
+                &IntegrityHashChain::get_row_digest(after).unwrap(),
+            );
+        }
+        assert_eq!(get_digest(&chain_1).0, digest);
+
+        // the same value of another ColValue variant
+        let mut row_data = mock_row_data(1, "a");
+        row_data
+            .after
+            .as_mut()
+            .unwrap()
+            .insert("id".into(), ColValue::LongLong(1));
+        assert_eq!(
+            IntegrityHashChain::get_row_digest(row_data.after.as_ref().unwrap()).unwrap(),
+            IntegrityHashChain::get_row_digest(mock_row_data(1, "a").after.as_ref().unwrap())
+                .unwrap()
+        );
+
+        // update 3 -> "d" then delete 2 equals the final rows [1, 3 -> "d"]
+        let update = RowData::new(
+            "test_db_1".into(),
+            "tb_1".into(),
+            RowType::Update,
+            mock_row_data(3, "c").after,
+            mock_row_data(3, "d").after,
+        );
+        let delete = RowData::new(
+            "test_db_1".into(),
+            "tb_1".into(),
+            RowType::Delete,
+            mock_row_data(2, "b").after,
+            None,
+        );
+        chain_1.update(&[update, delete]).unwrap();
+        let mut chain_3 = IntegrityHashChain::new("test_db_1.tb_1");
+        chain_3
+            .update(&[mock_row_data(3, "d"), mock_row_data(1, "a")])
+            .unwrap();
+        assert_eq!(get_digest(&chain_1), get_digest(&chain_3));
+        assert_eq!(get_digest(&chain_1).1, 2);
+
+        // tampered data
+        let mut chain_4 = IntegrityHashChain::new("test_db_1.tb_1");
+        chain_4
+            .update(&[mock_row_data(3, "c"), mock_row_data(1, "a")])
+            .unwrap();
+        assert_ne!(get_digest(&chain_1), get_digest(&chain_4));
+    }
+
+    #[test]
+    fn test_add_sub_digest() {
+        let mut sum = [0xff; 32];
+        let mut one = [0; 32];
+        one[31] = 1;
+        IntegrityHashChain::add_digest(&mut sum, &one);
+        assert_eq!(sum, [0; 32]);
+        IntegrityHashChain::sub_digest(&mut sum, &one);
+        assert_eq!(sum, [0xff; 32]);
+
+        let a = IntegrityHashChain::get_row_digest(mock_row_data(1, "a").after.as_ref().unwrap())
+            .unwrap();
+        let b = IntegrityHashChain::get_row_digest(mock_row_data(2, "b").after.as_ref().unwrap())
+            .unwrap();
+        let mut sum = [0; 32];
+        IntegrityHashChain::add_digest(&mut sum, &a);
+        IntegrityHashChain::add_digest(&mut sum, &b);
+        IntegrityHashChain::sub_digest(&mut sum, &a);
+        assert_eq!(sum, b);
+    }
+
+    #[test]
+    fn test_parse_checkpoint_log() {
+        let chain = IntegrityHashChain::new("test_db_1.tb_1");
+        let line = format!("2024-02-29 01:25:09.554271 | {}", chain.to_checkpoint_log());
+        assert_eq!(
+            IntegrityHashChain::parse_checkpoint_log(&line),
+            Some((
+                "test_db_1.tb_1".into(),
+                chain.run_id.clone(),
+                chain.hash_hex(),
+                0
+            ))
+        );
+        // written before run_id was added
+        let line = format!(
+            "2024-02-29 01:25:09.554271 | integrity_hash | test_db_1.tb_1 | {} | batch_count=3",
+            chain.hash_hex()
+        );
+        assert_eq!(
+            IntegrityHashChain::parse_checkpoint_log(&line),
+            Some(("test_db_1.tb_1".into(), String::new(), chain.hash_hex(), 3))
+        );
+        assert_eq!(
+            IntegrityHashChain::parse_checkpoint_log(
+                "2024-02-29 01:25:09.554271 | current_position | None"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_verify_manifest() {
+        let dir = std::env::temp_dir().join("ape_dts_test_verify_manifest");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        let (key_file, manifest_file, position_log_file) = (
+            path("key.pk8"),
+            path("manifest.jsonl"),
+            path("position.log"),
+        );
+
+        let rng = SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        fs::write(&key_file, pkcs8.as_ref()).unwrap();
+        let public_key = IntegrityHashChain::get_public_key_hex(&key_file).unwrap();
+
+        // two runs of the same task, the first one is longer
+        let mut run_1 = IntegrityHashChain::new("test_db_1.tb_1");
+        run_1.run_id = "1".into();
+        let mut run_2 = IntegrityHashChain::new("test_db_1.tb_1");
+        run_2.run_id = "2".into();
+        let mut position_log = fs::File::create(&position_log_file).unwrap();
+        for chain in [&mut run_1, &mut run_2] {
+            chain.update(&[mock_row_data(1, "a")]).unwrap();
+            writeln!(position_log, "2024-02-29 | {}", chain.to_checkpoint_log()).unwrap();
+        }
+        run_1.update(&[mock_row_data(2, "b")]).unwrap();
+        writeln!(position_log, "2024-02-29 | {}", run_1.to_checkpoint_log()).unwrap();
+        run_1.write_manifest(&manifest_file, &key_file).unwrap();
+        run_2.write_manifest(&manifest_file, &key_file).unwrap();
+
+        // verified by the trusted public key, without the private key
+        let entries =
+            IntegrityHashChain::verify_manifest(&manifest_file, &public_key, &position_log_file)
+                .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].batch_count, 2);
+        assert_eq!(entries[1].batch_count, 1);
+        assert_eq!(entries[0].tb_digests.len(), 1);
+        assert_eq!(entries[0].tb_digests[0].row_count, 2);
+
+        // the public keys in manifest are not trusted by themselves
+        assert!(
+            IntegrityHashChain::verify_manifest(&manifest_file, "", &position_log_file).is_err()
+        );
+
+        // signed by another key
+        let other_pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let other_key_pair = Ed25519KeyPair::from_pkcs8(other_pkcs8.as_ref()).unwrap();
+        let other_public_key = hex::encode(other_key_pair.public_key().as_ref());
+        assert!(IntegrityHashChain::verify_manifest(
+            &manifest_file,
+            &other_public_key,
+            &position_log_file
+        )
+        .is_err());
+
+        // tampered manifest
+        let manifest = fs::read_to_string(&manifest_file).unwrap();
+        fs::write(
+            &manifest_file,
+            manifest.replacen("\"row_count\":2", "\"row_count\":3", 1),
+        )
+        .unwrap();
+        assert!(IntegrityHashChain::verify_manifest(
+            &manifest_file,
+            &public_key,
+            &position_log_file
+        )
+        .is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
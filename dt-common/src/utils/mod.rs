@@ -1,4 +1,8 @@
 pub mod file_util;
+pub mod hash_util;
+pub mod integrity_hash_chain;
+pub mod name_pattern;
 pub mod redis_util;
+pub mod rpo_calculator;
 pub mod sql_util;
 pub mod time_util;
@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+
+use anyhow::bail;
+use regex::Regex;
+
+use crate::{error::Error, utils::sql_util::SqlUtil};
+
+// database / table name pattern in filter and router configs, compiled once when parsing config
+#[derive(Debug, Clone)]
+pub enum NamePattern {
+    Exact(String),
+    // '*' matches any chars, '?' matches 0-1 char
+    Wildcard(Regex),
+    Regex(Regex),
+}
+
+impl NamePattern {
+    // a token enclosed by escapes is an exact name,
+    // example: mysql table name : `aaa*`, it can only match the table `aaa*`, it won't match `aaa_bbb`
+    pub fn from_token(token: &str, escape_pairs: &[(char, char)]) -> anyhow::Result<Self> {
+        for escape_pair in escape_pairs.iter() {
+            if SqlUtil::is_escaped(token, escape_pair) {
+                return Ok(Self::Exact(SqlUtil::unescape(token, escape_pair)));
+            }
+        }
+
+        if !token.contains('*') && !token.contains('?') {
+            return Ok(Self::Exact(token.to_string()));
+        }
+        let pattern = regex::escape(token)
+            .replace(r"\*", ".*")
+            .replace(r"\?", ".?");
+        Ok(Self::Wildcard(Self::compile(&pattern)?))
+    }
+
+    // the whole name should match the regex
+    pub fn from_regex(pattern: &str) -> anyhow::Result<Self> {
+        Ok(Self::Regex(Self::compile(pattern)?))
+    }
+
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Exact(_))
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Exact(exact) => exact == name,
+            Self::Wildcard(regex) | Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+
+    // target name of a matched name, capture groups of a regex pattern
+    // are substituted in target, E.g. orders_(\d+) + orders_bak_${1}
+    pub fn replace<'a>(&self, name: &str, target: &'a str) -> Cow<'a, str> {
+        if let Self::Regex(regex) = self {
+            if let Some(captures) = regex.captures(name) {
+                let mut result = String::new();
+                captures.expand(target, &mut result);
+                return Cow::Owned(result);
+            }
+        }
+        Cow::Borrowed(target)
+    }
+
+    fn compile(pattern: &str) -> anyhow::Result<Regex> {
+        match Regex::new(&format!("^(?:{})$", pattern)) {
+            Ok(regex) => Ok(regex),
+            Err(e) => bail! {Error::ConfigError(format!(
+                "invalid name pattern: [{}], error: {}",
+                pattern, e
+            ))},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config::config_enums::DbType, utils::sql_util::SqlUtil};
+
+    use super::NamePattern;
+
+    #[test]
+    fn test_from_token() {
+        let escape_pairs = SqlUtil::get_escape_pairs(&DbType::Mysql);
+
+        let pattern = NamePattern::from_token("orders", &escape_pairs).unwrap();
+        assert!(pattern.is_exact());
+        assert!(pattern.is_match("orders"));
+        assert!(!pattern.is_match("orders_1"));
+
+        let pattern = NamePattern::from_token("orders_*", &escape_pairs).unwrap();
+        assert!(!pattern.is_exact());
+        assert!(pattern.is_match("orders_"));
+        assert!(pattern.is_match("orders_0001"));
+        assert!(!pattern.is_match("order_0001"));
+
+        let pattern = NamePattern::from_token("orders_?", &escape_pairs).unwrap();
+        assert!(pattern.is_match("orders_1"));
+        assert!(!pattern.is_match("orders_12"));
+
+        // escaped, exactly match
+        let pattern = NamePattern::from_token("`orders_*`", &escape_pairs).unwrap();
+        assert!(pattern.is_exact());
+        assert!(pattern.is_match("orders_*"));
+        assert!(!pattern.is_match("orders_0001"));
+
+        // regex chars other than wildcards are literal
+        let pattern = NamePattern::from_token("a+b_*", &[]).unwrap();
+        assert!(pattern.is_match("a+b_1"));
+        assert!(!pattern.is_match("aab_1"));
+    }
+
+    #[test]
+    fn test_from_regex() {
+        let pattern = NamePattern::from_regex(r"orders_(\d+)").unwrap();
+        assert!(pattern.is_match("orders_0001"));
+        // the whole name should match
+        assert!(!pattern.is_match("orders_0001_bak"));
+        assert!(!pattern.is_match("x_orders_0001"));
+
+        assert_eq!(pattern.replace("orders_0001", "orders"), "orders");
+        assert_eq!(
+            pattern.replace("orders_0001", "orders_bak_${1}"),
+            "orders_bak_0001"
+        );
+
+        assert!(NamePattern::from_regex("orders_(").is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::log_warn;
+
+// rows replayed on restart should not exceed this size even if the rpo allows
+const MAX_REPLAY_BYTES: u64 = 1024 * 1024 * 1024;
+const MAX_CHECKPOINT_OVERHEAD_PERCENT: f64 = 1.0;
+
+// recovery point objective: tolerated data loss (in seconds of work) on restart
+pub struct RpoCalculator {}
+
+impl RpoCalculator {
+    // returns the recommended checkpoint interval in rows, so that at most rpo_secs of work
+    // is lost on restart
+    pub fn recommend_checkpoint_interval(
+        rows_per_sec: f64,
+        rpo_secs: u64,
+        avg_row_bytes: usize,
+    ) -> u64 {
+        if !rows_per_sec.is_finite() || rows_per_sec <= 0.0 {
+            return 1;
+        }
+
+        let mut interval = (rows_per_sec * rpo_secs as f64).floor() as u64;
+        if avg_row_bytes > 0 {
+            interval = interval.min(MAX_REPLAY_BYTES / avg_row_bytes as u64);
+        }
+        interval.max(1)
+    }
+
+    // percentage of time spent on writing checkpoints
+    pub fn get_checkpoint_overhead(
+        checkpoint_write_time_ms: u64,
+        checkpoint_interval_ms: u64,
+    ) -> f64 {
+        if checkpoint_interval_ms == 0 {
+            return 0.0;
+        }
+        checkpoint_write_time_ms as f64 / checkpoint_interval_ms as f64 * 100.0
+    }
+
+    // returns true if the overhead exceeds 1%
+    pub fn check_checkpoint_overhead(
+        checkpoint_write_time_ms: u64,
+        checkpoint_interval_ms: u64,
+    ) -> bool {
+        let overhead =
+            Self::get_checkpoint_overhead(checkpoint_write_time_ms, checkpoint_interval_ms);
+        if overhead > MAX_CHECKPOINT_OVERHEAD_PERCENT {
+            log_warn!(
+                "checkpoint write overhead: {:.2}%, write time: {} ms, interval: {} ms, consider increasing checkpoint_interval_secs",
+                overhead,
+                checkpoint_write_time_ms,
+                checkpoint_interval_ms
+            );
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RpoCalculator;
+
+    #[test]
+    fn test_recommend_checkpoint_interval() {
+        assert_eq!(
+            RpoCalculator::recommend_checkpoint_interval(1000.0, 300, 100),
+            300000
+        );
+        // limited by replay bytes
+        assert_eq!(
+            RpoCalculator::recommend_checkpoint_interval(1000.0, 300, 1024 * 1024),
+            1024
+        );
+        assert_eq!(RpoCalculator::recommend_checkpoint_interval(0.5, 1, 100), 1);
+        assert_eq!(
+            RpoCalculator::recommend_checkpoint_interval(0.0, 300, 100),
+            1
+        );
+    }
+
+    #[test]
+    fn test_check_checkpoint_overhead() {
+        assert_eq!(RpoCalculator::get_checkpoint_overhead(50, 10000), 0.5);
+        assert!(!RpoCalculator::check_checkpoint_overhead(100, 10000));
+        assert!(RpoCalculator::check_checkpoint_overhead(101, 10000));
+        assert!(!RpoCalculator::check_checkpoint_overhead(100, 0));
+    }
+}
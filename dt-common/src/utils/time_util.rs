@@ -1,11 +1,18 @@
 use std::time::Duration;
 
 use anyhow::{bail, Context};
-use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::error::Error;
 
 pub struct TimeUtil {}
 
 const UTC_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+// %#z also accepts offsets of hours only, e.g. +08
+const DATETIME_WITH_OFFSET_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%#z";
+const DATETIME_WITH_OFFSET_OUTPUT_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%:z";
+const ZERO_DATE: &str = "0000-00-00";
 
 impl TimeUtil {
     #[inline(always)]
@@ -31,6 +38,73 @@ impl TimeUtil {
         }
     }
 
+    // time zone in config: +08:00, -05:30, UTC, empty means UTC
+    pub fn parse_time_zone(str: &str) -> anyhow::Result<FixedOffset> {
+        let str = str.trim();
+        if str.is_empty() || str == "Z" || str.eq_ignore_ascii_case("UTC") {
+            return Ok(FixedOffset::east_opt(0).unwrap());
+        }
+
+        let sign = if str.starts_with('+') {
+            1
+        } else if str.starts_with('-') {
+            -1
+        } else {
+            0
+        };
+        if sign != 0 {
+            if let Some((hours, minutes)) = str[1..].split_once(':') {
+                if let (Ok(hours), Ok(minutes)) = (hours.parse::<i32>(), minutes.parse::<i32>()) {
+                    if hours <= 14 && minutes < 60 {
+                        if let Some(offset) =
+                            FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+                        {
+                            return Ok(offset);
+                        }
+                    }
+                }
+            }
+        }
+        bail! {Error::ConfigError(format!(
+            "invalid time zone: [{}], should be like: +08:00, -05:30, UTC",
+            str
+        ))}
+    }
+
+    // mysql zero dates: 0000-00-00, 0000-00-00 00:00:00
+    #[inline(always)]
+    pub fn is_zero_date(str: &str) -> bool {
+        str.starts_with(ZERO_DATE)
+    }
+
+    // convert a wall-clock datetime in time zone: from into time zone: to,
+    // values with an offset (pg timestamptz: 2024-01-01 20:00:00+08) are converted from their
+    // own offset instead of from, and keep the offset of to
+    pub fn convert_time_zone(
+        str: &str,
+        from: &FixedOffset,
+        to: &FixedOffset,
+    ) -> anyhow::Result<String> {
+        let naive = match NaiveDateTime::parse_from_str(str, DATETIME_FORMAT) {
+            Ok(naive) => naive,
+            Err(_) => {
+                let datetime = DateTime::parse_from_str(str, DATETIME_WITH_OFFSET_FORMAT)
+                    .with_context(|| format!("convert_time_zone failed, input: [{}]", str))?;
+                return Ok(datetime
+                    .with_timezone(to)
+                    .format(DATETIME_WITH_OFFSET_OUTPUT_FORMAT)
+                    .to_string());
+            }
+        };
+        // a fixed offset has no ambiguous local times
+        let datetime = from.from_local_datetime(&naive).unwrap();
+        Ok(datetime
+            .with_timezone(to)
+            .naive_local()
+            .format(DATETIME_FORMAT)
+            .to_string())
+    }
+
     #[inline(always)]
     pub fn timestamp_to_str(timestamp: u32) -> anyhow::Result<String> {
         if let Some(datetime) = DateTime::from_timestamp(timestamp as i64, 0) {
@@ -75,4 +149,43 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn test_parse_time_zone() {
+        for (str, secs) in [
+            ("", 0),
+            ("UTC", 0),
+            ("Z", 0),
+            ("+08:00", 8 * 3600),
+            ("-05:30", -(5 * 3600 + 30 * 60)),
+            ("+00:00", 0),
+        ] {
+            assert_eq!(
+                TimeUtil::parse_time_zone(str).unwrap().local_minus_utc(),
+                secs
+            );
+        }
+
+        for str in ["08:00", "+8", "+15:00", "+08:60", "Asia/Shanghai", "中"] {
+            assert!(TimeUtil::parse_time_zone(str).is_err());
+        }
+    }
+
+    #[test]
+    fn test_convert_time_zone() {
+        let utc = TimeUtil::parse_time_zone("UTC").unwrap();
+        let east_8 = TimeUtil::parse_time_zone("+08:00").unwrap();
+        assert_eq!(
+            TimeUtil::convert_time_zone("2024-01-01 20:00:00", &utc, &east_8).unwrap(),
+            "2024-01-02 04:00:00"
+        );
+        assert_eq!(
+            TimeUtil::convert_time_zone("2024-01-02 04:00:00.123456", &east_8, &utc).unwrap(),
+            "2024-01-01 20:00:00.123456"
+        );
+        assert!(TimeUtil::convert_time_zone("0000-00-00 00:00:00", &utc, &east_8).is_err());
+        assert!(TimeUtil::is_zero_date("0000-00-00 00:00:00"));
+        assert!(TimeUtil::is_zero_date("0000-00-00"));
+        assert!(!TimeUtil::is_zero_date("2024-01-01"));
+    }
 }
@@ -1,7 +1,9 @@
 use std::collections::HashSet;
 
+use anyhow::bail;
 use dt_common::{
     config::{config_enums::DbType, data_marker_config::DataMarkerConfig},
+    error::Error,
     meta::{dt_data::DtData, redis::redis_entry::RedisEntry},
 };
 
@@ -55,6 +57,12 @@ impl DataMarker {
         match *db_type {
             DbType::Mysql | DbType::Pg | DbType::Mongo => {
                 let marker_info: Vec<&str> = config.marker.split('.').collect();
+                if marker_info.len() != 2 || marker_info.iter().any(|i| i.is_empty()) {
+                    bail! {Error::ConfigError(format!(
+                        "[data_marker] marker should be schema.tb for {}, got: {}",
+                        db_type, config.marker
+                    ))}
+                }
                 me.marker_schema = marker_info[0].to_string();
                 me.marker_tb = marker_info[1].to_string();
             }
@@ -126,3 +134,37 @@ impl DataMarker {
         self.filter
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dt_common::config::{config_enums::DbType, data_marker_config::DataMarkerConfig};
+
+    use super::DataMarker;
+
+    #[test]
+    fn test_from_config() {
+        let mut config = DataMarkerConfig {
+            topo_name: "topo1".into(),
+            src_node: "node1".into(),
+            dst_node: "node2".into(),
+            do_nodes: "node1".into(),
+            ignore_nodes: "node2".into(),
+            marker: "ape_trans_mysql.topo1".into(),
+            ..Default::default()
+        };
+        let data_marker = DataMarker::from_config(&config, &DbType::Mysql).unwrap();
+        assert_eq!(data_marker.marker_schema, "ape_trans_mysql");
+        assert_eq!(data_marker.marker_tb, "topo1");
+        assert!(data_marker.is_rdb_marker_info("ape_trans_mysql", "topo1"));
+
+        for marker in ["topo1", "ape_trans_mysql.", "a.b.c"] {
+            config.marker = marker.into();
+            assert!(DataMarker::from_config(&config, &DbType::Pg).is_err());
+        }
+
+        // redis marker is a key
+        config.marker = "data_marker_topo1".into();
+        let data_marker = DataMarker::from_config(&config, &DbType::Redis).unwrap();
+        assert_eq!(data_marker.marker, "data_marker_topo1");
+    }
+}
@@ -1,6 +1,9 @@
-use std::str::FromStr;
+use std::{collections::HashSet, str::FromStr};
 
-use dt_common::log_info;
+use dt_common::{
+    log_info, log_warn,
+    meta::{rdb_tb_meta::RdbTbMeta, row_data::RowData, row_type::RowType},
+};
 
 use crate::{
     check_log::{check_log::CheckLog, log_reader::LogReader},
@@ -62,6 +65,61 @@ impl BaseCheckExtractor {
         Ok(())
     }
 
+    // rows in check logs but no longer in the source are extra rows of the target,
+    // build deletes for them by id_col_values in check logs. The batch query matches keys by
+    // collations of the source, e.g. 'abc' returns 'ABC ' in case insensitive / PAD SPACE
+    // collations, so keys are compared loosely and only keys matching none of the returned
+    // rows are deleted
+    pub fn build_delete_row_datas(
+        check_row_datas: &[RowData],
+        src_keys: &HashSet<Vec<String>>,
+        tb_meta: &RdbTbMeta,
+    ) -> Vec<RowData> {
+        let mut delete_row_datas = Vec::new();
+        for check_row_data in check_row_datas {
+            let key = match Self::get_loose_key(check_row_data, tb_meta) {
+                Some(key) => key,
+                // NULL in id cols, the row can not be located exactly
+                None => {
+                    log_warn!(
+                        "row not found in source, but id cols have NULL, not deleted: {}",
+                        check_row_data
+                    );
+                    continue;
+                }
+            };
+            if src_keys.contains(&key) {
+                continue;
+            }
+            delete_row_datas.push(RowData::new(
+                check_row_data.schema.clone(),
+                check_row_data.tb.clone(),
+                RowType::Delete,
+                check_row_data.after.clone(),
+                None,
+            ));
+        }
+        delete_row_datas
+    }
+
+    // values of id cols, lowercased and without trailing spaces, None if any of them is NULL
+    pub fn get_loose_key(row_data: &RowData, tb_meta: &RdbTbMeta) -> Option<Vec<String>> {
+        let col_values = match row_data.row_type {
+            RowType::Insert => row_data.after.as_ref()?,
+            _ => row_data.before.as_ref()?,
+        };
+        tb_meta
+            .id_cols
+            .iter()
+            .map(|col| {
+                col_values
+                    .get(col)?
+                    .to_option_string()
+                    .map(|v| v.trim_end_matches(' ').to_lowercase())
+            })
+            .collect()
+    }
+
     fn can_in_same_batch(exist_items: &[CheckLog], new_item: &CheckLog) -> bool {
         if exist_items.is_empty() {
             return true;
@@ -82,3 +140,74 @@ impl BaseCheckExtractor {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::col_value::ColValue;
+
+    use super::*;
+
+    #[test]
+    fn test_build_delete_row_datas() {
+        let tb_meta = RdbTbMeta {
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            id_cols: vec!["id".into()],
+            ..Default::default()
+        };
+        let build_row_data = |id: ColValue| {
+            let after = HashMap::from([("id".to_string(), id)]);
+            RowData::build_insert_row_data(after, &tb_meta)
+        };
+
+        let src_row_data = build_row_data(ColValue::Long(1));
+        let src_keys =
+            HashSet::from([BaseCheckExtractor::get_loose_key(&src_row_data, &tb_meta).unwrap()]);
+        let check_row_datas = vec![
+            build_row_data(ColValue::Long(1)),
+            build_row_data(ColValue::Long(2)),
+            build_row_data(ColValue::None),
+        ];
+
+        let delete_row_datas =
+            BaseCheckExtractor::build_delete_row_datas(&check_row_datas, &src_keys, &tb_meta);
+        assert_eq!(delete_row_datas.len(), 1);
+        assert_eq!(delete_row_datas[0].row_type, RowType::Delete);
+        assert_eq!(
+            delete_row_datas[0].before.as_ref().unwrap()["id"],
+            ColValue::Long(2)
+        );
+        assert!(delete_row_datas[0].after.is_none());
+    }
+
+    #[test]
+    fn test_build_delete_row_datas_with_collation() {
+        let tb_meta = RdbTbMeta {
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            id_cols: vec!["code".into()],
+            ..Default::default()
+        };
+        let build_row_data = |code: &str| {
+            let after = HashMap::from([("code".to_string(), ColValue::String(code.into()))]);
+            RowData::build_insert_row_data(after, &tb_meta)
+        };
+
+        // returned by `code IN ('abc', 'def')` in a case insensitive, PAD SPACE collation
+        let src_keys =
+            HashSet::from([
+                BaseCheckExtractor::get_loose_key(&build_row_data("ABC "), &tb_meta).unwrap(),
+            ]);
+        let check_row_datas = vec![build_row_data("abc"), build_row_data("def")];
+
+        let delete_row_datas =
+            BaseCheckExtractor::build_delete_row_datas(&check_row_datas, &src_keys, &tb_meta);
+        assert_eq!(delete_row_datas.len(), 1);
+        assert_eq!(
+            delete_row_datas[0].before.as_ref().unwrap()["code"],
+            ColValue::String("def".into())
+        );
+    }
+}
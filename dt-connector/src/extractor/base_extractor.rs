@@ -6,9 +6,11 @@ use std::sync::{
 use anyhow::bail;
 
 use dt_common::{
-    config::{config_enums::DbType, config_token_parser::ConfigTokenParser},
+    config::{
+        config_enums::DbType, config_token_parser::ConfigTokenParser, task_config::TaskConfig,
+    },
     error::Error,
-    log_debug, log_error, log_info, log_warn,
+    log_debug_row, log_error, log_info, log_warn,
     meta::{ddl_meta::ddl_data::DdlData, dt_queue::DtQueue, struct_meta::struct_data::StructData},
     rdb_filter::RdbFilter,
     utils::{sql_util::SqlUtil, time_util::TimeUtil},
@@ -24,17 +26,31 @@ use dt_common::{
 };
 use serde_json::json;
 
-use crate::{data_marker::DataMarker, rdb_router::RdbRouter};
+use crate::{data_marker::DataMarker, rdb_router::RdbRouter, Configurable};
 
-use super::extractor_monitor::ExtractorMonitor;
+use super::{extract_rate_limiter::ExtractRateLimiter, extractor_monitor::ExtractorMonitor};
 
 pub struct BaseExtractor {
     pub buffer: Arc<DtQueue>,
     pub router: RdbRouter,
     pub shut_down: Arc<AtomicBool>,
+    // set by admin api or shutdown signal, the extractor returns at the next push
+    pub stopped: Arc<AtomicBool>,
     pub monitor: ExtractorMonitor,
     pub data_marker: Option<DataMarker>,
     pub time_filter: TimeFilter,
+    pub rate_limiter: Option<Arc<ExtractRateLimiter>>,
+}
+
+// the rate limiter is also registered to the config watcher, since extractors are owned by
+// their running tasks
+impl Configurable for BaseExtractor {
+    fn update_config(&mut self, config: &TaskConfig) -> anyhow::Result<()> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.update_config(config);
+        }
+        Ok(())
+    }
 }
 
 impl BaseExtractor {
@@ -50,6 +66,11 @@ impl BaseExtractor {
         dt_data: DtData,
         position: Position,
     ) -> anyhow::Result<()> {
+        // data pushed before is complete in buffer, the pipeline sinks it and exits
+        if self.stopped.load(Ordering::Acquire) {
+            bail! {Error::ExtractorStopped}
+        }
+
         if !self.time_filter.started {
             return Ok(());
         }
@@ -58,8 +79,26 @@ impl BaseExtractor {
             return Ok(());
         }
 
+        let data_size = dt_data.get_data_size();
+        if let (Some(rate_limiter), DtData::Dml { .. }) = (&self.rate_limiter, &dt_data) {
+            let throttled = rate_limiter.acquire(1, data_size as u64).await;
+            self.monitor.counters.throttled_ms += throttled.as_millis() as usize;
+        }
         self.monitor.counters.record_count += 1;
-        self.monitor.counters.data_size += dt_data.get_data_size();
+        self.monitor.counters.data_size += data_size;
+        match &dt_data {
+            DtData::Dml { row_data } => {
+                self.monitor
+                    .add_table_counter(&row_data.schema, &row_data.tb, data_size);
+            }
+            DtData::Ddl { ddl_data } => {
+                for (schema, tb) in ddl_data.get_dropped_schema_tbs() {
+                    self.monitor.remove_table_counters(&schema, &tb);
+                }
+            }
+            _ => {}
+        }
+        self.monitor.refresh_latest_event_timestamp(&position);
         self.monitor.try_flush(false);
 
         let data_origin_node = if let Some(data_marker) = &mut self.data_marker {
@@ -73,7 +112,7 @@ impl BaseExtractor {
             position,
             data_origin_node,
         };
-        log_debug!("extracted item: {}", json!(item));
+        log_debug_row!("extracted item: {}", json!(item));
         self.buffer.push(item).await
     }
 
@@ -107,8 +146,11 @@ impl BaseExtractor {
     }
 
     pub async fn push_row(&mut self, row_data: RowData, position: Position) -> anyhow::Result<()> {
-        let row_data = self.router.route_row(row_data);
-        self.push_dt_data(DtData::Dml { row_data }, position).await
+        for row_data in self.router.route_dml(row_data)? {
+            self.push_dt_data(DtData::Dml { row_data }, position.clone())
+                .await?;
+        }
+        Ok(())
     }
 
     pub async fn push_ddl(&mut self, ddl_data: DdlData, position: Position) -> anyhow::Result<()> {
@@ -152,19 +194,17 @@ impl BaseExtractor {
     }
 
     pub fn get_where_sql(filter: &RdbFilter, schema: &str, tb: &str, condition: &str) -> String {
-        let mut res: String = String::new();
-        if let Some(where_condition) = filter.get_where_condition(schema, tb) {
-            res = format!("WHERE {}", where_condition);
-        }
-
         if condition.is_empty() {
-            return res;
+            return match filter.get_where_condition(schema, tb) {
+                Some(where_condition) => format!("WHERE {}", where_condition),
+                None => String::new(),
+            };
         }
 
-        if res.is_empty() {
-            format!("WHERE {}", condition)
-        } else {
-            format!("{} AND {}", res, condition)
+        // the where condition may contain OR, wrap it so pagination conditions still apply
+        match filter.get_where_condition(schema, tb) {
+            Some(where_condition) => format!("WHERE ({}) AND {}", where_condition, condition),
+            None => format!("WHERE {}", condition),
         }
     }
 
@@ -222,3 +262,29 @@ impl BaseExtractor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dt_common::config::{config_enums::DbType, filter_config::FilterConfig};
+
+    use super::*;
+
+    #[test]
+    fn test_get_where_sql() {
+        let mut filter = RdbFilter::from_config(&FilterConfig::default(), &DbType::Mysql).unwrap();
+        filter
+            .where_conditions
+            .insert(("db_1".into(), "tb_1".into()), "f_1 = 1 OR f_1 = 2".into());
+
+        let where_sql = |tb: &str, condition: &str| {
+            BaseExtractor::get_where_sql(&filter, "db_1", tb, condition)
+        };
+        assert_eq!(where_sql("tb_1", ""), "WHERE f_1 = 1 OR f_1 = 2");
+        assert_eq!(
+            where_sql("tb_1", "`f_0` > ?"),
+            "WHERE (f_1 = 1 OR f_1 = 2) AND `f_0` > ?"
+        );
+        assert_eq!(where_sql("tb_2", ""), "");
+        assert_eq!(where_sql("tb_2", "`f_0` > ?"), "WHERE `f_0` > ?");
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use dt_common::{config::task_config::TaskConfig, log_info};
+
+// paces rows pushed by extractors with token buckets of rows and bytes, shared by
+// all tables of a task or owned by a single table depending on [extractor] rate_limit_scope.
+// each bucket holds tokens of one second at most, tokens are taken even if not enough
+// and the caller sleeps until the debt is refilled, so a row larger than the bucket
+// is delayed instead of blocking forever.
+// limits can be changed by the config watcher while extracting, a limit set to 0 is removed,
+// but a limiter is only created if any limit is set when the task starts
+pub struct ExtractRateLimiter {
+    buckets: Mutex<(Option<TokenBucket>, Option<TokenBucket>)>,
+}
+
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        Self {
+            rate: rate as f64,
+            tokens: rate as f64,
+            last_refill: now,
+        }
+    }
+
+    // take tokens and return how long the caller should wait
+    fn take(&mut self, amount: u64, now: Instant) -> Duration {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+        self.tokens -= amount as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    fn update(bucket: &mut Option<TokenBucket>, rate: u64, now: Instant) {
+        match bucket {
+            _ if rate == 0 => *bucket = None,
+            Some(bucket) => {
+                bucket.rate = rate as f64;
+                bucket.tokens = bucket.tokens.min(bucket.rate);
+            }
+            None => *bucket = Some(TokenBucket::new(rate, now)),
+        }
+    }
+}
+
+impl ExtractRateLimiter {
+    pub fn new(max_rows_per_sec: u64, max_mb_per_sec: u64) -> Option<Self> {
+        if max_rows_per_sec == 0 && max_mb_per_sec == 0 {
+            return None;
+        }
+
+        let now = Instant::now();
+        let max_bytes_per_sec = max_mb_per_sec * 1024 * 1024;
+        let new_bucket = |rate: u64| (rate > 0).then(|| TokenBucket::new(rate, now));
+        Some(Self {
+            buckets: Mutex::new((new_bucket(max_rows_per_sec), new_bucket(max_bytes_per_sec))),
+        })
+    }
+
+    // shared by extractors and the config watcher through Arc, so limits are updated in place
+    pub fn update_config(&self, config: &TaskConfig) {
+        let basic = &config.extractor_basic;
+        let old_limits = self.get_limits();
+        self.update(basic.max_rows_per_sec, basic.max_mb_per_sec);
+        let new_limits = self.get_limits();
+        if old_limits != new_limits {
+            log_info!(
+                "extract rate limits (rows/s, bytes/s) updated: {:?} -> {:?}",
+                old_limits,
+                new_limits
+            );
+        }
+    }
+
+    pub fn update(&self, max_rows_per_sec: u64, max_mb_per_sec: u64) {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let (row_bucket, byte_bucket) = &mut *buckets;
+        TokenBucket::update(row_bucket, max_rows_per_sec, now);
+        TokenBucket::update(byte_bucket, max_mb_per_sec * 1024 * 1024, now);
+    }
+
+    // 0 means unlimited
+    pub fn get_limits(&self) -> (u64, u64) {
+        let buckets = self.buckets.lock().unwrap();
+        let rate = |bucket: &Option<TokenBucket>| bucket.as_ref().map_or(0, |i| i.rate as u64);
+        (rate(&buckets.0), rate(&buckets.1))
+    }
+
+    // sleep rather than spin if throttled, return the time slept
+    pub async fn acquire(&self, rows: u64, bytes: u64) -> Duration {
+        let wait = self.take(rows, bytes, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        wait
+    }
+
+    fn take(&self, rows: u64, bytes: u64, now: Instant) -> Duration {
+        let mut buckets = self.buckets.lock().unwrap();
+        let (row_bucket, byte_bucket) = &mut *buckets;
+        let row_wait = row_bucket
+            .as_mut()
+            .map_or(Duration::ZERO, |bucket| bucket.take(rows, now));
+        let byte_wait = byte_bucket
+            .as_mut()
+            .map_or(Duration::ZERO, |bucket| bucket.take(bytes, now));
+        row_wait.max(byte_wait)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take() {
+        assert!(ExtractRateLimiter::new(0, 0).is_none());
+
+        let limiter = ExtractRateLimiter::new(100, 0).unwrap();
+        let now = Instant::now();
+        // the bucket starts full
+        assert_eq!(limiter.take(100, 0, now), Duration::ZERO);
+        // 50 rows more should wait for half a second
+        assert_eq!(limiter.take(50, 0, now), Duration::from_millis(500));
+        // refilled, but never beyond the tokens of one second
+        let later = now + Duration::from_secs(10);
+        assert_eq!(limiter.take(100, 0, later), Duration::ZERO);
+        assert_eq!(limiter.take(10, 0, later), Duration::from_millis(100));
+
+        // the longer wait of rows and bytes
+        let limiter = ExtractRateLimiter::new(1000, 1).unwrap();
+        assert_eq!(limiter.get_limits(), (1000, 1024 * 1024));
+        let wait = limiter.take(1, 2 * 1024 * 1024, now);
+        assert_eq!(wait, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_update() {
+        let limiter = ExtractRateLimiter::new(100, 0).unwrap();
+        let now = Instant::now();
+        assert_eq!(limiter.take(100, 0, now), Duration::ZERO);
+
+        // lowered, the debt is paid at the new rate
+        limiter.update(50, 0);
+        assert_eq!(limiter.get_limits(), (50, 0));
+        assert_eq!(limiter.take(25, 0, now), Duration::from_millis(500));
+
+        // raised, tokens are still capped by one second
+        limiter.update(200, 1);
+        assert_eq!(limiter.get_limits(), (200, 1024 * 1024));
+        let later = now + Duration::from_secs(10);
+        assert_eq!(limiter.take(200, 0, later), Duration::ZERO);
+        assert_eq!(limiter.take(20, 0, later), Duration::from_millis(100));
+
+        // removed
+        limiter.update(0, 0);
+        assert_eq!(limiter.get_limits(), (0, 0));
+        assert_eq!(limiter.take(10000, 0, later), Duration::ZERO);
+    }
+}
@@ -1,14 +1,21 @@
 use std::{
+    collections::HashMap,
     sync::{Arc, Mutex},
-    time::Instant,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
-use dt_common::monitor::{counter_type::CounterType, monitor::Monitor};
+use dt_common::{
+    meta::position::Position,
+    monitor::{counter_type::CounterType, monitor::Monitor},
+};
 
 #[derive(Clone, Default)]
 pub struct ExtractorCounters {
     pub record_count: usize,
     pub data_size: usize,
+    pub filtered_count: usize,
+    // time slept by [extractor] max_rows_per_sec / max_mb_per_sec
+    pub throttled_ms: usize,
 }
 
 impl ExtractorCounters {
@@ -16,6 +23,8 @@ impl ExtractorCounters {
         Self {
             record_count: 0,
             data_size: 0,
+            filtered_count: 0,
+            throttled_ms: 0,
         }
     }
 }
@@ -27,6 +36,12 @@ pub struct ExtractorMonitor {
     pub last_flush_time: Instant,
     pub flushed_counters: ExtractorCounters,
     pub counters: ExtractorCounters,
+    // schema.tb -> (records, bytes) since the last flush
+    pub table_counters: HashMap<String, (usize, usize)>,
+    // timestamp of the latest extracted cdc event, for cdc_lag_ms
+    pub latest_event_timestamp: String,
+    // add extract_rows_per_sec / extract_bytes_per_sec for each flush, set if extraction is throttled
+    pub report_extract_rate: bool,
 }
 
 impl ExtractorMonitor {
@@ -40,22 +55,86 @@ impl ExtractorMonitor {
             time_window_secs,
             flushed_counters: ExtractorCounters::new(),
             counters: ExtractorCounters::new(),
+            table_counters: HashMap::new(),
+            latest_event_timestamp: String::new(),
+            report_extract_rate: false,
+        }
+    }
+
+    pub fn add_table_counter(&mut self, schema: &str, tb: &str, data_size: usize) {
+        let counter = self
+            .table_counters
+            .entry(format!("{}.{}", schema, tb))
+            .or_default();
+        counter.0 += 1;
+        counter.1 += data_size;
+    }
+
+    pub fn remove_table_counters(&mut self, schema: &str, tb: &str) {
+        if tb.is_empty() {
+            let prefix = format!("{}.", schema);
+            self.table_counters
+                .retain(|key, _| !key.starts_with(&prefix));
+        } else {
+            self.table_counters.remove(&format!("{}.{}", schema, tb));
+        }
+        self.monitor
+            .lock()
+            .unwrap()
+            .remove_table_counters(schema, tb);
+    }
+
+    pub fn refresh_latest_event_timestamp(&mut self, position: &Position) {
+        if let Some(timestamp) = position.get_timestamp() {
+            if !timestamp.is_empty() && timestamp != self.latest_event_timestamp {
+                self.latest_event_timestamp = timestamp.to_string();
+            }
         }
     }
 
     pub fn try_flush(&mut self, force: bool) {
         let record_count = self.counters.record_count - self.flushed_counters.record_count;
         let record_size = self.counters.data_size - self.flushed_counters.data_size;
+        let filtered_count = self.counters.filtered_count - self.flushed_counters.filtered_count;
+        let throttled_ms = self.counters.throttled_ms - self.flushed_counters.throttled_ms;
         // to avoid too many sub counters, add counter by batch
         if force
             || record_count >= self.count_window
             || self.last_flush_time.elapsed().as_secs() >= self.time_window_secs as u64
         {
-            self.monitor
-                .lock()
-                .unwrap()
+            let mut monitor = self.monitor.lock().unwrap();
+            monitor
                 .add_counter(CounterType::RecordCount, record_count)
                 .add_counter(CounterType::DataBytes, record_size);
+            if filtered_count > 0 {
+                monitor.add_counter(CounterType::FilteredKeys, filtered_count);
+            }
+            if self.report_extract_rate {
+                // the effective rate since the last flush, capped by the rate limiter
+                let elapsed_ms = self.last_flush_time.elapsed().as_millis().max(1) as usize;
+                monitor
+                    .add_counter(
+                        CounterType::ExtractRowsPerSec,
+                        record_count * 1000 / elapsed_ms,
+                    )
+                    .add_counter(
+                        CounterType::ExtractBytesPerSec,
+                        record_size * 1000 / elapsed_ms,
+                    )
+                    .add_counter(CounterType::ThrottledMs, throttled_ms);
+            }
+            for (tb, (record_count, data_size)) in self.table_counters.drain() {
+                monitor.add_table_counter(&tb, record_count, data_size);
+            }
+            if let Some(timestamp) = Position::parse_timestamp_millis(&self.latest_event_timestamp)
+            {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as i64;
+                monitor.add_counter(CounterType::CdcLagMs, (now - timestamp).max(0) as usize);
+            }
+            drop(monitor);
             self.last_flush_time = Instant::now();
             self.flushed_counters = self.counters.clone();
         }
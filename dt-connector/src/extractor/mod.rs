@@ -1,5 +1,6 @@
 pub mod base_check_extractor;
 pub mod base_extractor;
+pub mod extract_rate_limiter;
 pub mod extractor_monitor;
 pub mod foxlake;
 pub mod kafka;
@@ -8,3 +9,4 @@ pub mod mysql;
 pub mod pg;
 pub mod redis;
 pub mod resumer;
+pub mod slice_checksum;
@@ -0,0 +1,91 @@
+use std::time::Instant;
+
+use dt_common::{log_monitor, log_warn};
+use hdrhistogram::Histogram;
+use mysql_binlog_connector_rust::event::table_map_event::TableMapEvent;
+
+const LOG_INTERVAL_SECS: u64 = 60;
+// mysql max_allowed_packet is at most 1G
+const MAX_TRACKABLE_SIZE: u64 = 1 << 30;
+
+// size distribution of received binlog events, large events (e.g. updates to rows with
+// big BLOB columns) are a common bottleneck of cdc tasks
+pub struct BinlogEventSizer {
+    histogram: Histogram<u64>,
+    large_event_threshold_bytes: u64,
+    last_log_time: Instant,
+}
+
+impl BinlogEventSizer {
+    pub fn new(large_event_threshold_bytes: u64) -> anyhow::Result<Self> {
+        Ok(Self {
+            histogram: Histogram::new_with_bounds(1, MAX_TRACKABLE_SIZE, 3)?,
+            large_event_threshold_bytes,
+            last_log_time: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, event_size: u64) {
+        self.histogram
+            .saturating_record(event_size.clamp(1, MAX_TRACKABLE_SIZE));
+
+        if self.last_log_time.elapsed().as_secs() >= LOG_INTERVAL_SECS {
+            log_monitor!(
+                "binlog event size, count: {}, p50: {}, p99: {}, max: {}",
+                self.histogram.len(),
+                self.value_at_quantile(0.5),
+                self.value_at_quantile(0.99),
+                self.histogram.max()
+            );
+            self.histogram.reset();
+            self.last_log_time = Instant::now();
+        }
+    }
+
+    pub fn value_at_quantile(&self, quantile: f64) -> u64 {
+        self.histogram.value_at_quantile(quantile)
+    }
+
+    pub fn is_large_event(&self, event_size: u64) -> bool {
+        self.large_event_threshold_bytes > 0 && event_size > self.large_event_threshold_bytes
+    }
+
+    pub fn check_large_event(&self, event_size: u64, table_map_event: &TableMapEvent) {
+        if !self.is_large_event(event_size) {
+            return;
+        }
+        log_warn!(
+            "large binlog event: {} bytes, threshold: {}, schema: {}, tb: {}, table_id: {}, column_types: {:?}",
+            event_size,
+            self.large_event_threshold_bytes,
+            table_map_event.database_name,
+            table_map_event.table_name,
+            table_map_event.table_id,
+            table_map_event.column_types
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinlogEventSizer;
+
+    #[test]
+    fn test_binlog_event_sizer() {
+        let mut sizer = BinlogEventSizer::new(1024).unwrap();
+        for _ in 0..99 {
+            sizer.record(100);
+        }
+        sizer.record(10 * 1024 * 1024);
+        // histogram keeps 3 significant digits
+        assert!(sizer.value_at_quantile(0.5).abs_diff(100) <= 1);
+        assert!(sizer.value_at_quantile(0.99).abs_diff(100) <= 1);
+        assert!(sizer.value_at_quantile(1.0) >= 10 * 1024 * 1024);
+
+        assert!(!sizer.is_large_event(1024));
+        assert!(sizer.is_large_event(1025));
+
+        let sizer = BinlogEventSizer::new(0).unwrap();
+        assert!(!sizer.is_large_event(u64::MAX));
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use dt_common::{meta::row_type::RowType, rdb_filter::RdbFilter};
+
+// evaluate RdbFilter once for each TABLE_MAP_EVENT instead of for each row, rows events of
+// filtered tables are dropped by table_id before any row is converted
+#[derive(Default)]
+pub struct MysqlBinlogFilterPushdown {
+    // table_id -> filtered results of (insert, update, delete)
+    filtered_tbs: HashMap<u64, (bool, bool, bool)>,
+    pub skipped_events: u64,
+}
+
+impl MysqlBinlogFilterPushdown {
+    // table_id may be reused by another table after ddl, results are refreshed by every TABLE_MAP_EVENT
+    pub fn on_table_map(
+        &mut self,
+        table_id: u64,
+        db: &str,
+        tb: &str,
+        filter: &mut RdbFilter,
+        is_data_marker_info: bool,
+    ) {
+        // events of the data marker table are always kept
+        let mut filtered =
+            |row_type: RowType| !is_data_marker_info && filter.filter_event(db, tb, &row_type);
+        let results = (
+            filtered(RowType::Insert),
+            filtered(RowType::Update),
+            filtered(RowType::Delete),
+        );
+        self.filtered_tbs.insert(table_id, results);
+    }
+
+    pub fn is_filtered(&mut self, table_id: u64, row_type: &RowType) -> bool {
+        let filtered = match self.filtered_tbs.get(&table_id) {
+            Some((insert, update, delete)) => match row_type {
+                RowType::Insert => *insert,
+                RowType::Update => *update,
+                RowType::Delete => *delete,
+            },
+            None => false,
+        };
+        if filtered {
+            self.skipped_events += 1;
+        }
+        filtered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::{
+        config::{config_enums::DbType, filter_config::FilterConfig},
+        meta::row_type::RowType,
+        rdb_filter::RdbFilter,
+    };
+
+    use super::MysqlBinlogFilterPushdown;
+
+    #[test]
+    fn test_is_filtered() {
+        let config = FilterConfig {
+            do_tbs: "db_1.tb_1".into(),
+            do_events: "insert,delete".into(),
+            ..Default::default()
+        };
+        let mut filter = RdbFilter::from_config(&config, &DbType::Mysql).unwrap();
+        let mut pushdown = MysqlBinlogFilterPushdown::default();
+        pushdown.on_table_map(1, "db_1", "tb_1", &mut filter, false);
+        pushdown.on_table_map(2, "db_1", "tb_2", &mut filter, false);
+        pushdown.on_table_map(3, "db_1", "marker_tb", &mut filter, true);
+
+        assert!(!pushdown.is_filtered(1, &RowType::Insert));
+        assert!(pushdown.is_filtered(1, &RowType::Update));
+        assert!(!pushdown.is_filtered(1, &RowType::Delete));
+        assert!(pushdown.is_filtered(2, &RowType::Insert));
+        assert!(!pushdown.is_filtered(3, &RowType::Update));
+        // unknown table_id
+        assert!(!pushdown.is_filtered(4, &RowType::Insert));
+        assert_eq!(pushdown.skipped_events, 2);
+
+        // table_id reused by another table
+        pushdown.on_table_map(2, "db_1", "tb_1", &mut filter, false);
+        assert!(!pushdown.is_filtered(2, &RowType::Insert));
+    }
+}
@@ -1,8 +1,17 @@
-use dt_common::{log_info, utils::time_util::TimeUtil};
+use std::collections::BTreeMap;
+
+use anyhow::bail;
+use dt_common::{error::Error, log_info, utils::time_util::TimeUtil};
 use futures::TryStreamExt;
 use mysql_binlog_connector_rust::{binlog_client::BinlogClient, event::event_data::EventData};
 use sqlx::{MySql, Pool, Row};
 
+pub const QUERY_BEGIN: &str = "BEGIN";
+
+// https://dev.mysql.com/doc/dev/mysql-server/latest/namespacemysql_1_1binlog_1_1event.html
+const GTID_LOG_EVENT: u8 = 33;
+const ANONYMOUS_GTID_LOG_EVENT: u8 = 34;
+
 pub struct BinlogUtil {}
 
 impl BinlogUtil {
@@ -74,14 +83,188 @@ impl BinlogUtil {
         }
     }
 
+    // scan the binlog forward and get the position of the first transaction which starts
+    // at or after start_timestamp, None if no such transaction in the binlog
+    pub async fn find_first_position_after_timestamp(
+        start_timestamp: u32,
+        url: &str,
+        server_id: u64,
+        binlog: &str,
+        conn_pool: &Pool<MySql>,
+    ) -> anyhow::Result<Option<u32>> {
+        // the binlog may still be written, only scan to its size when we start
+        let binlog_size = match Self::get_binary_logs_with_size(conn_pool)
+            .await?
+            .into_iter()
+            .find(|(log_name, _)| log_name == binlog)
+        {
+            Some((_, file_size)) => file_size,
+            None => return Ok(None),
+        };
+
+        let mut client = BinlogClient {
+            url: url.into(),
+            binlog_filename: binlog.into(),
+            binlog_position: 0,
+            server_id,
+            ..Default::default()
+        };
+        let mut stream = client.connect().await?;
+
+        let mut position = None;
+        // position of the gtid event (anonymous if gtid disabled) of the current transaction
+        let mut gtid_position = None;
+        loop {
+            let (header, data) = stream.read().await?;
+            // artificial events sent when binlog_client connected
+            if header.next_event_position == 0 {
+                continue;
+            }
+
+            let event_position = header.next_event_position - header.event_length;
+            if header.event_type == GTID_LOG_EVENT || header.event_type == ANONYMOUS_GTID_LOG_EVENT
+            {
+                gtid_position = Some(event_position);
+            }
+
+            let is_transaction_start = match &data {
+                EventData::Query(query) => query.query == QUERY_BEGIN,
+                EventData::TransactionPayload(_) => true,
+                _ => false,
+            };
+            if is_transaction_start {
+                if header.timestamp >= start_timestamp {
+                    position = Some(gtid_position.unwrap_or(event_position));
+                    break;
+                }
+                gtid_position = None;
+            }
+
+            if header.next_event_position as u64 >= binlog_size {
+                break;
+            }
+        }
+        stream.close().await?;
+
+        match position {
+            Some(position) => log_info!(
+                "found first transaction after start_time in binlog: {}, position: {}",
+                binlog,
+                position
+            ),
+            None => log_info!(
+                "no transaction after start_time found in binlog: {}",
+                binlog
+            ),
+        }
+        Ok(position)
+    }
+
+    // the server can only send transactions after gtid_set if binlogs containing them
+    // are not purged, which means gtid_purged should be a subset of gtid_set
+    pub async fn check_gtid_purged(gtid_set: &str, conn_pool: &Pool<MySql>) -> anyhow::Result<()> {
+        let sql = "SELECT CAST(@@GLOBAL.gtid_purged AS CHAR) AS gtid_purged";
+        let row = sqlx::query(sql).fetch_one(conn_pool).await?;
+        let gtid_purged: String = row.try_get("gtid_purged").unwrap_or_default();
+        let missing_gtids = Self::subtract_gtid_set(&gtid_purged, gtid_set)?;
+        if !missing_gtids.is_empty() {
+            bail! {Error::ExtractorError(format!(
+                "binlogs containing gtids: {} have been purged, they are neither in gtid_set: {} nor available on the server, gtid_purged: {}",
+                missing_gtids, gtid_set, gtid_purged
+            ))}
+        }
+        Ok(())
+    }
+
+    // gtids in gtid_set_1 but not in gtid_set_2,
+    // E.g. "uuid_1:1-10:20,uuid_2:1-5" - "uuid_1:1-5" = "uuid_1:6-10:20,uuid_2:1-5"
+    pub fn subtract_gtid_set(gtid_set_1: &str, gtid_set_2: &str) -> anyhow::Result<String> {
+        let set_1 = Self::parse_gtid_set(gtid_set_1)?;
+        let set_2 = Self::parse_gtid_set(gtid_set_2)?;
+
+        let mut result = Vec::new();
+        for (sid, intervals) in set_1 {
+            let mut remaining = intervals;
+            for (sub_start, sub_end) in set_2.get(&sid).into_iter().flatten() {
+                remaining = remaining
+                    .into_iter()
+                    .flat_map(|(start, end)| {
+                        if end < *sub_start || start > *sub_end {
+                            return vec![(start, end)];
+                        }
+                        let mut parts = Vec::new();
+                        if start < *sub_start {
+                            parts.push((start, sub_start - 1));
+                        }
+                        if end > *sub_end {
+                            parts.push((sub_end + 1, end));
+                        }
+                        parts
+                    })
+                    .collect();
+            }
+
+            if !remaining.is_empty() {
+                let intervals: Vec<String> = remaining
+                    .iter()
+                    .map(|(start, end)| {
+                        if start == end {
+                            start.to_string()
+                        } else {
+                            format!("{}-{}", start, end)
+                        }
+                    })
+                    .collect();
+                result.push(format!("{}:{}", sid, intervals.join(":")));
+            }
+        }
+        Ok(result.join(","))
+    }
+
+    // "uuid_1:1-10:20,uuid_2:1-5" -> {uuid_1: [(1, 10), (20, 20)], uuid_2: [(1, 5)]},
+    // tagged gtids (uuid:tag:1-5) are keyed by uuid:tag
+    fn parse_gtid_set(gtid_set: &str) -> anyhow::Result<BTreeMap<String, Vec<(u64, u64)>>> {
+        let mut result: BTreeMap<String, Vec<(u64, u64)>> = BTreeMap::new();
+        // gtid sets returned by mysql contain line breaks
+        let gtid_set = gtid_set.replace(['\n', '\r', ' '], "");
+        for item in gtid_set.split(',').filter(|i| !i.is_empty()) {
+            let mut parts = item.split(':');
+            let uuid = parts.next().unwrap_or_default().to_lowercase();
+            let mut sid = uuid.clone();
+            for part in parts {
+                if !part.starts_with(|c: char| c.is_ascii_digit()) {
+                    sid = format!("{}:{}", uuid, part.to_lowercase());
+                    continue;
+                }
+
+                let (start, end) = part.split_once('-').unwrap_or((part, part));
+                match (start.parse::<u64>(), end.parse::<u64>()) {
+                    (Ok(start), Ok(end)) => {
+                        result.entry(sid.clone()).or_default().push((start, end))
+                    }
+                    _ => bail! {Error::ExtractorError(format!("invalid gtid set: {}", gtid_set))},
+                }
+            }
+        }
+        Ok(result)
+    }
+
     async fn get_binary_logs(conn_pool: &Pool<MySql>) -> anyhow::Result<Vec<String>> {
+        let binlogs = Self::get_binary_logs_with_size(conn_pool).await?;
+        Ok(binlogs.into_iter().map(|(log_name, _)| log_name).collect())
+    }
+
+    async fn get_binary_logs_with_size(
+        conn_pool: &Pool<MySql>,
+    ) -> anyhow::Result<Vec<(String, u64)>> {
         let mut binlogs = Vec::new();
         let sql = "SHOW BINARY LOGS";
 
         let mut rows = sqlx::query(sql).fetch(conn_pool);
         while let Some(row) = rows.try_next().await.unwrap() {
             let log_name: String = row.try_get(0)?;
-            binlogs.push(log_name)
+            let file_size: u64 = row.try_get(1)?;
+            binlogs.push((log_name, file_size))
         }
         Ok(binlogs)
     }
@@ -115,3 +298,48 @@ impl BinlogUtil {
         Ok(timestamp)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BinlogUtil;
+
+    const UUID_1: &str = "3e11fa47-71ca-11e1-9e33-c80aa9429562";
+    const UUID_2: &str = "5a5b8b6e-0b4a-11ef-a3f1-0242ac110002";
+
+    #[test]
+    fn test_subtract_gtid_set() {
+        let subtract =
+            |set_1: &str, set_2: &str| BinlogUtil::subtract_gtid_set(set_1, set_2).unwrap();
+
+        // purged gtids are all in the resumed gtid set
+        assert_eq!(
+            subtract(&format!("{}:1-100", UUID_1), &format!("{}:1-200", UUID_1)),
+            ""
+        );
+        assert_eq!(subtract("", &format!("{}:1-200", UUID_1)), "");
+
+        // part of purged gtids are not in the resumed gtid set
+        assert_eq!(
+            subtract(&format!("{}:1-100", UUID_1), &format!("{}:1-50", UUID_1)),
+            format!("{}:51-100", UUID_1)
+        );
+        assert_eq!(
+            subtract(
+                &format!("{}:1-100:120,\n{}:1-5", UUID_1, UUID_2.to_uppercase()),
+                &format!("{}:10-20:30-40", UUID_1)
+            ),
+            format!("{}:1-9:21-29:41-100:120,{}:1-5", UUID_1, UUID_2)
+        );
+
+        // tagged gtids
+        assert_eq!(
+            subtract(
+                &format!("{}:1-10:tag_1:1-3", UUID_1),
+                &format!("{}:1-10", UUID_1)
+            ),
+            format!("{}:tag_1:1-3", UUID_1)
+        );
+
+        assert!(BinlogUtil::subtract_gtid_set(&format!("{}:1-x", UUID_1), "").is_err());
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    log_info,
+    meta::{
+        mysql::{mysql_meta_manager::MysqlMetaManager, mysql_tb_meta::MysqlTbMeta},
+        position::Position,
+        rdb_tb_meta::RdbTbMeta,
+        row_data::RowData,
+        row_type::RowType,
+    },
+    rdb_filter::RdbFilter,
+    utils::sql_util::SqlUtil,
+};
+use futures::TryStreamExt;
+use regex::Regex;
+use sqlx::{MySql, Pool};
+
+use crate::{close_conn_pool, extractor::base_extractor::BaseExtractor, Extractor};
+
+const TOKEN: &str = r"(?:`[^`]+`|\w+)";
+// words that may follow a table reference if its alias is missing
+const RESERVED_ALIASES: [&str; 12] = [
+    "on", "using", "where", "join", "inner", "left", "right", "cross", "natural", "group", "order",
+    "limit",
+];
+
+// extract rows of a SELECT with JOINs (tables may be in different databases of the same
+// server) as rows of a virtual table, snapshot only since there is no binlog for JOIN results
+pub struct CrossDatabaseJoinExtractor {
+    pub base_extractor: BaseExtractor,
+    pub conn_pool: Pool<MySql>,
+    pub meta_manager: MysqlMetaManager,
+    pub filter: RdbFilter,
+    pub join_sql: JoinSql,
+    pub virtual_db: String,
+    pub virtual_tb: String,
+}
+
+// a validated join_sql, every selected column is referenced as alias.col and renamed
+// to alias_col in the virtual table to avoid collisions
+#[derive(Debug, Clone, PartialEq)]
+pub struct JoinSql {
+    pub sql: String,
+    pub cols: Vec<JoinCol>,
+    // alias -> (db, tb)
+    pub tbs: HashMap<String, (String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JoinCol {
+    pub alias: String,
+    pub col: String,
+    pub name: String,
+}
+
+#[async_trait]
+impl Extractor for CrossDatabaseJoinExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!(
+            "CrossDatabaseJoinExtractor starts, virtual table: `{}`.`{}`, sql: {}",
+            self.virtual_db,
+            self.virtual_tb,
+            self.join_sql.sql
+        );
+        self.extract_internal().await?;
+        self.base_extractor.wait_task_finish().await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.meta_manager.close().await?;
+        close_conn_pool!(self)
+    }
+}
+
+impl CrossDatabaseJoinExtractor {
+    async fn extract_internal(&mut self) -> anyhow::Result<()> {
+        let (db, tb) = (self.virtual_db.clone(), self.virtual_tb.clone());
+        if self.filter.filter_event(&db, &tb, &RowType::Insert) {
+            log_info!("schema: {}, tb: {}, insert events filtered", db, tb);
+            return Ok(());
+        }
+
+        let tb_meta = self.build_virtual_tb_meta().await?;
+        let ignore_cols = self.filter.get_ignore_cols(&db, &tb);
+
+        let mut extracted_count = 0;
+        let mut rows = sqlx::query(&self.join_sql.sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let row_data = RowData::from_mysql_row(&row, &tb_meta, &ignore_cols);
+            self.base_extractor
+                .push_row(row_data, Position::None)
+                .await?;
+            extracted_count += 1;
+        }
+
+        log_info!(
+            "end extracting data from virtual table `{}`.`{}`, all count: {}",
+            db,
+            tb,
+            extracted_count
+        );
+        Ok(())
+    }
+
+    // column types of the virtual table come from the joined tables
+    async fn build_virtual_tb_meta(&mut self) -> anyhow::Result<MysqlTbMeta> {
+        let mut basic = RdbTbMeta {
+            schema: self.virtual_db.clone(),
+            tb: self.virtual_tb.clone(),
+            ..Default::default()
+        };
+        let mut col_type_map = HashMap::new();
+
+        for join_col in self.join_sql.cols.iter() {
+            let (db, tb) = &self.join_sql.tbs[&join_col.alias];
+            let src_tb_meta = self.meta_manager.get_tb_meta(db, tb).await?;
+            let src_col = join_col.col.to_lowercase();
+            let col_type = match src_tb_meta.col_type_map.get(&src_col) {
+                Some(col_type) => col_type,
+                None => bail! {Error::ConfigError(format!(
+                    "invalid join_sql, col: `{}` not exists in `{}`.`{}`",
+                    join_col.col, db, tb
+                ))},
+            };
+
+            col_type_map.insert(join_col.name.clone(), col_type.clone());
+            if let Some(origin_type) = src_tb_meta.basic.col_origin_type_map.get(&src_col) {
+                basic
+                    .col_origin_type_map
+                    .insert(join_col.name.clone(), origin_type.clone());
+            }
+            basic.cols.push(join_col.name.clone());
+        }
+
+        Ok(MysqlTbMeta {
+            basic,
+            col_type_map,
+            auto_increment_col: None,
+            generated_cols: HashSet::new(),
+        })
+    }
+}
+
+impl JoinSql {
+    // supported: SELECT a.c1, b.c2, ... FROM db1.tb1 a JOIN db2.tb2 b ON ... [WHERE ...],
+    // every table must be referenced as db.tb with an alias
+    pub fn parse(join_sql: &str) -> anyhow::Result<Self> {
+        let join_sql = join_sql.trim().trim_end_matches(';').trim();
+        if join_sql.contains(';') {
+            bail! {Self::invalid_err("multiple statements are not supported")}
+        }
+
+        let statement_re = Regex::new(r"(?is)^SELECT\s+(?P<cols>.+?)\s+FROM\s+(?P<from>.+)$")?;
+        let caps = match statement_re.captures(join_sql) {
+            Some(caps) => caps,
+            None => bail! {Self::invalid_err("should be like: SELECT ... FROM ... JOIN ...")},
+        };
+        let (cols_str, from_str) = (&caps["cols"], &caps["from"]);
+
+        let tbs = Self::parse_tbs(from_str)?;
+        if tbs.len() < 2 {
+            bail! {Self::invalid_err("at least 2 tables should be joined")}
+        }
+
+        let col_re = Regex::new(&format!(r"^(?P<alias>{TOKEN})\s*\.\s*(?P<col>{TOKEN})$"))?;
+        let mut cols = Vec::new();
+        let mut names = HashSet::new();
+        for item in cols_str.split(',') {
+            let col_caps = match col_re.captures(item.trim()) {
+                Some(col_caps) => col_caps,
+                None => bail! {Self::invalid_err(&format!(
+                    "selected column should be like alias.col, got: {}",
+                    item.trim()
+                ))},
+            };
+            let alias = Self::unescape(&col_caps["alias"]);
+            let col = Self::unescape(&col_caps["col"]);
+            if !tbs.contains_key(&alias) {
+                bail! {Self::invalid_err(&format!("unknown table alias: {}", alias))}
+            }
+
+            let name = format!("{}_{}", alias, col).to_lowercase();
+            if !names.insert(name.clone()) {
+                bail! {Self::invalid_err(&format!("duplicate column: {}", name))}
+            }
+            cols.push(JoinCol { alias, col, name });
+        }
+
+        let db_type = DbType::Mysql;
+        let select_cols: Vec<String> = cols
+            .iter()
+            .map(|i| {
+                format!(
+                    "{}.{} AS {}",
+                    SqlUtil::escape_by_db_type(&i.alias, &db_type),
+                    SqlUtil::escape_by_db_type(&i.col, &db_type),
+                    SqlUtil::escape_by_db_type(&i.name, &db_type)
+                )
+            })
+            .collect();
+        let sql = format!("SELECT {} FROM {}", select_cols.join(","), from_str);
+        Ok(Self { sql, cols, tbs })
+    }
+
+    fn parse_tbs(from_str: &str) -> anyhow::Result<HashMap<String, (String, String)>> {
+        let keyword_re = Regex::new(r"(?i)\b(?:FROM|JOIN)\s+")?;
+        let tb_re = Regex::new(&format!(
+            r"(?i)\b(?:FROM|JOIN)\s+(?P<db>{TOKEN})\s*\.\s*(?P<tb>{TOKEN})(?:\s+AS)?\s+(?P<alias>{TOKEN})"
+        ))?;
+
+        // the leading FROM is consumed by the statement regex
+        let from_str = format!("FROM {}", from_str);
+        let mut tbs = HashMap::new();
+        for caps in tb_re.captures_iter(&from_str) {
+            let alias = Self::unescape(&caps["alias"]);
+            if RESERVED_ALIASES.contains(&alias.to_lowercase().as_str()) {
+                bail! {Self::invalid_err(&format!(
+                    "alias is required for table: {}.{}",
+                    &caps["db"], &caps["tb"]
+                ))}
+            }
+            let tb = (Self::unescape(&caps["db"]), Self::unescape(&caps["tb"]));
+            if tbs.insert(alias.clone(), tb).is_some() {
+                bail! {Self::invalid_err(&format!("duplicate table alias: {}", alias))}
+            }
+        }
+
+        // subqueries or tables without db are not matched
+        if tbs.len() != keyword_re.find_iter(&from_str).count() {
+            bail! {Self::invalid_err(
+                "tables should be referenced as db.tb alias, subqueries are not supported"
+            )}
+        }
+        Ok(tbs)
+    }
+
+    fn unescape(token: &str) -> String {
+        SqlUtil::unescape_by_db_type(token, &DbType::Mysql)
+    }
+
+    fn invalid_err(reason: &str) -> Error {
+        Error::ConfigError(format!("invalid join_sql, {}", reason))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_join_sql() {
+        let join_sql = JoinSql::parse(
+            "SELECT o.id, o.amount, `u`.`name` FROM db1.orders o INNER JOIN `db2`.`users` AS u ON o.user_id = u.id WHERE o.amount > 0;",
+        )
+        .unwrap();
+
+        assert_eq!(
+            join_sql.sql,
+            "SELECT `o`.`id` AS `o_id`,`o`.`amount` AS `o_amount`,`u`.`name` AS `u_name` FROM db1.orders o INNER JOIN `db2`.`users` AS u ON o.user_id = u.id WHERE o.amount > 0"
+        );
+        assert_eq!(
+            join_sql
+                .cols
+                .iter()
+                .map(|i| i.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["o_id", "o_amount", "u_name"]
+        );
+        assert_eq!(join_sql.tbs["o"], ("db1".into(), "orders".into()));
+        assert_eq!(join_sql.tbs["u"], ("db2".into(), "users".into()));
+    }
+
+    #[test]
+    fn test_parse_invalid_join_sql() {
+        let invalid_sqls = [
+            // no join
+            "SELECT o.id FROM db1.orders o",
+            // select *
+            "SELECT * FROM db1.orders o JOIN db2.users u ON o.user_id = u.id",
+            // col without alias
+            "SELECT id FROM db1.orders o JOIN db2.users u ON o.user_id = u.id",
+            // unknown alias
+            "SELECT x.id FROM db1.orders o JOIN db2.users u ON o.user_id = u.id",
+            // table without db
+            "SELECT o.id FROM db1.orders o JOIN users u ON o.user_id = u.id",
+            // table without alias
+            "SELECT o.id FROM db1.orders o JOIN db2.users ON o.user_id = users.id",
+            // subquery
+            "SELECT o.id FROM db1.orders o JOIN (SELECT id FROM db2.users) u ON o.user_id = u.id",
+            // duplicate column names
+            "SELECT o.id, o.ID FROM db1.orders o JOIN db2.users u ON o.user_id = u.id",
+            // multiple statements
+            "SELECT o.id FROM db1.orders o JOIN db2.users u ON o.user_id = u.id; DELETE FROM db1.orders",
+            "UPDATE db1.orders o JOIN db2.users u ON o.user_id = u.id SET o.amount = 0",
+        ];
+        for sql in invalid_sqls {
+            assert!(JoinSql::parse(sql).is_err(), "{}", sql);
+        }
+    }
+}
@@ -1,5 +1,10 @@
+pub mod binlog_event_sizer;
+pub mod binlog_filter_pushdown;
 pub mod binlog_util;
+pub mod cross_database_join_extractor;
 pub mod mysql_cdc_extractor;
 pub mod mysql_check_extractor;
+pub mod mysql_consistent_snapshot;
+pub mod mysql_rds_extractor;
 pub mod mysql_snapshot_extractor;
 pub mod mysql_struct_extractor;
@@ -13,7 +13,7 @@ use std::{
 };
 
 use dt_common::{
-    log_debug,
+    log_debug_row,
     meta::{
         adaptor::mysql_col_value_convertor::MysqlColValueConvertor, col_value::ColValue,
         dt_data::DtData, mysql::mysql_meta_manager::MysqlMetaManager, position::Position,
@@ -30,14 +30,19 @@ use mysql_binlog_connector_rust::{
 };
 
 use dt_common::{
-    config::config_enums::DbType, error::Error, log_error, log_info, rdb_filter::RdbFilter,
-    utils::time_util::TimeUtil,
+    config::config_enums::DbType, error::Error, log_error, log_info, log_warn,
+    rdb_filter::RdbFilter, utils::time_util::TimeUtil,
 };
 
 use crate::{
     close_conn_pool,
     extractor::{
-        base_extractor::BaseExtractor, mysql::binlog_util::BinlogUtil,
+        base_extractor::BaseExtractor,
+        mysql::{
+            binlog_event_sizer::BinlogEventSizer,
+            binlog_filter_pushdown::MysqlBinlogFilterPushdown,
+            binlog_util::{BinlogUtil, QUERY_BEGIN},
+        },
         resumer::cdc_resumer::CdcResumer,
     },
     Extractor,
@@ -60,48 +65,72 @@ pub struct MysqlCdcExtractor {
     pub heartbeat_tb: String,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
+    pub event_sizer: BinlogEventSizer,
 }
 
 struct Context {
     binlog_filename: String,
     table_map_event_map: HashMap<u64, TableMapEvent>,
+    filter_pushdown: MysqlBinlogFilterPushdown,
     gtid_set: Option<GtidSet>,
 }
 
-const QUERY_BEGIN: &str = "BEGIN";
-
 #[async_trait]
 impl Extractor for MysqlCdcExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
-        if self.base_extractor.time_filter.start_timestamp > 0 {
-            self.binlog_filename = BinlogUtil::find_last_binlog_before_timestamp(
-                self.base_extractor.time_filter.start_timestamp,
-                &self.url,
-                self.server_id,
-                &self.conn_pool,
-            )
-            .await?;
+        // with gtid_enabled=true, the binlog stream starts after gtid_set and binlog_filename is ignored
+        if self.gtid_enabled && !self.binlog_filename.is_empty() {
+            bail! {Error::ConfigError(
+                "[extractor] binlog_filename can not be used with gtid_enabled=true, use gtid_set instead".into()
+            )}
         }
 
-        if let Position::MysqlCdc {
-            binlog_filename,
-            next_event_position,
-            gtid_set,
-            ..
-        } = &self.resumer.checkpoint_position
-        {
-            self.binlog_filename = binlog_filename.to_owned();
-            self.binlog_position = next_event_position.to_owned();
-            self.gtid_set = gtid_set.to_owned();
-            log_info!("resume from: {}", self.resumer.checkpoint_position);
-            self.base_extractor
-                .push_dt_data(
-                    DtData::Heartbeat {},
-                    self.resumer.checkpoint_position.clone(),
-                )
+        if self.base_extractor.time_filter.start_timestamp > 0 {
+            self.skip_events_before(self.base_extractor.time_filter.start_timestamp)
                 .await?;
         }
 
+        match &self.resumer.checkpoint_position {
+            // a gtid based checkpoint should not be resumed by binlog file/position
+            Position::MysqlCdc { gtid_set, .. } if !self.gtid_enabled && !gtid_set.is_empty() => {
+                bail! {Error::ExtractorError(format!(
+                    "checkpoint position: {} was recorded with gtid_enabled=true, can not resume from it",
+                    self.resumer.checkpoint_position
+                ))}
+            }
+
+            // in gtid mode, binlog file/position is not valid after failovers, a checkpoint without
+            // gtid_set was recorded before any gtid was received, E.g. the task started with an empty
+            // gtid_set and no transactions came, so it starts from the configured position
+            Position::MysqlCdc { gtid_set, .. } if self.gtid_enabled && gtid_set.is_empty() => {
+                log_warn!(
+                    "checkpoint position: {} has no gtid_set, start from the configured gtid_set: {}",
+                    self.resumer.checkpoint_position,
+                    self.gtid_set
+                );
+            }
+
+            Position::MysqlCdc {
+                binlog_filename,
+                next_event_position,
+                gtid_set,
+                ..
+            } => {
+                self.binlog_filename = binlog_filename.to_owned();
+                self.binlog_position = next_event_position.to_owned();
+                self.gtid_set = gtid_set.to_owned();
+                log_info!("resume from: {}", self.resumer.checkpoint_position);
+                self.base_extractor
+                    .push_dt_data(
+                        DtData::Heartbeat {},
+                        self.resumer.checkpoint_position.clone(),
+                    )
+                    .await?;
+            }
+
+            _ => {}
+        }
+
         log_info!(
             "MysqlCdcExtractor starts, binlog_filename: {}, binlog_position: {}, gtid_enabled: {}, gtid_set: {}, heartbeat_interval_secs: {}, heartbeat_tb: {}",
             self.binlog_filename,
@@ -122,7 +151,48 @@ impl Extractor for MysqlCdcExtractor {
 }
 
 impl MysqlCdcExtractor {
+    // set binlog_filename and binlog_position to the first transaction at or after start_timestamp,
+    // events before start_timestamp which are still received are dropped by time_filter
+    async fn skip_events_before(&mut self, start_timestamp: u32) -> anyhow::Result<()> {
+        if !self.binlog_filename.is_empty() {
+            log_info!(
+                "binlog_filename: {} is specified, start_time_utc is only used to filter events",
+                self.binlog_filename
+            );
+            return Ok(());
+        }
+
+        self.binlog_filename = BinlogUtil::find_last_binlog_before_timestamp(
+            start_timestamp,
+            &self.url,
+            self.server_id,
+            &self.conn_pool,
+        )
+        .await?;
+        if self.binlog_filename.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(position) = BinlogUtil::find_first_position_after_timestamp(
+            start_timestamp,
+            &self.url,
+            self.server_id,
+            &self.binlog_filename,
+            &self.conn_pool,
+        )
+        .await?
+        {
+            self.binlog_position = position;
+        }
+        Ok(())
+    }
+
     async fn extract_internal(&mut self) -> anyhow::Result<()> {
+        // if gtid_set is empty, the binlog stream starts after gtid_executed of the server
+        if self.gtid_enabled && !self.gtid_set.is_empty() {
+            BinlogUtil::check_gtid_purged(&self.gtid_set, &self.conn_pool).await?;
+        }
+
         let mut client = BinlogClient {
             url: self.url.clone(),
             binlog_filename: self.binlog_filename.clone(),
@@ -138,6 +208,7 @@ impl MysqlCdcExtractor {
         let mut ctx = Context {
             binlog_filename: self.binlog_filename.clone(),
             table_map_event_map: HashMap::new(),
+            filter_pushdown: MysqlBinlogFilterPushdown::default(),
             gtid_set: None,
         };
         if self.gtid_enabled {
@@ -154,6 +225,7 @@ impl MysqlCdcExtractor {
             }
 
             let (header, data) = stream.read().await?;
+            self.event_sizer.record(header.event_length as u64);
             match data {
                 EventData::Rotate(r) => {
                     ctx.binlog_filename = r.binlog_filename;
@@ -171,7 +243,7 @@ impl MysqlCdcExtractor {
         data: EventData,
         ctx: &mut Context,
     ) -> anyhow::Result<()> {
-        log_debug!(
+        log_debug_row!(
             "received binlog, event header: {:?}, event data: {:?}",
             header,
             data
@@ -200,10 +272,23 @@ impl MysqlCdcExtractor {
             }
 
             EventData::TableMap(d) => {
+                let is_data_marker_info = self
+                    .base_extractor
+                    .is_data_marker_info(&d.database_name, &d.table_name);
+                ctx.filter_pushdown.on_table_map(
+                    d.table_id,
+                    &d.database_name,
+                    &d.table_name,
+                    &mut self.filter,
+                    is_data_marker_info,
+                );
                 ctx.table_map_event_map.insert(d.table_id, d);
             }
 
             EventData::TransactionPayload(event) => {
+                // binlog_transaction_compression=ON (mysql 8.0.20+), payloads are decompressed
+                // by zstd in mysql-binlog-connector-rust, inner events have the same layout
+                // as uncompressed binlog
                 for (mut inner_header, data) in event.uncompressed_events {
                     // headers of uncompressed events have no next_event_position,
                     // use header of TransactionPayload instead
@@ -213,12 +298,18 @@ impl MysqlCdcExtractor {
             }
 
             EventData::WriteRows(mut w) => {
+                if ctx
+                    .filter_pushdown
+                    .is_filtered(w.table_id, &RowType::Insert)
+                {
+                    return Ok(());
+                }
+                if let Some(table_map_event) = ctx.table_map_event_map.get(&w.table_id) {
+                    self.event_sizer
+                        .check_large_event(header.event_length as u64, table_map_event);
+                }
                 for event in w.rows.iter_mut() {
                     let table_map_event = ctx.table_map_event_map.get(&w.table_id).unwrap();
-                    if self.filter_event(table_map_event, RowType::Insert) {
-                        continue;
-                    }
-
                     let col_values = self
                         .parse_row_data(table_map_event, &w.included_columns, event)
                         .await?;
@@ -234,12 +325,18 @@ impl MysqlCdcExtractor {
             }
 
             EventData::UpdateRows(mut u) => {
+                if ctx
+                    .filter_pushdown
+                    .is_filtered(u.table_id, &RowType::Update)
+                {
+                    return Ok(());
+                }
+                if let Some(table_map_event) = ctx.table_map_event_map.get(&u.table_id) {
+                    self.event_sizer
+                        .check_large_event(header.event_length as u64, table_map_event);
+                }
                 for event in u.rows.iter_mut() {
                     let table_map_event = ctx.table_map_event_map.get(&u.table_id).unwrap();
-                    if self.filter_event(table_map_event, RowType::Update) {
-                        continue;
-                    }
-
                     let col_values_before = self
                         .parse_row_data(table_map_event, &u.included_columns_before, &mut event.0)
                         .await?;
@@ -258,12 +355,18 @@ impl MysqlCdcExtractor {
             }
 
             EventData::DeleteRows(mut d) => {
+                if ctx
+                    .filter_pushdown
+                    .is_filtered(d.table_id, &RowType::Delete)
+                {
+                    return Ok(());
+                }
+                if let Some(table_map_event) = ctx.table_map_event_map.get(&d.table_id) {
+                    self.event_sizer
+                        .check_large_event(header.event_length as u64, table_map_event);
+                }
                 for event in d.rows.iter_mut() {
                     let table_map_event = ctx.table_map_event_map.get(&d.table_id).unwrap();
-                    if self.filter_event(table_map_event, RowType::Delete) {
-                        continue;
-                    }
-
                     let col_values = self
                         .parse_row_data(table_map_event, &d.included_columns, event)
                         .await?;
@@ -377,10 +480,10 @@ impl MysqlCdcExtractor {
             .await
         {
             for sub_ddl_data in ddl_data.clone().split_to_multi() {
-                let (db, tb) = sub_ddl_data.get_schema_tb();
                 // invalidate metadata cache
-                self.meta_manager.invalidate_cache(&db, &tb);
-                if !self.filter.filter_ddl(&db, &tb, &sub_ddl_data.ddl_type) {
+                self.meta_manager
+                    .invalidate_cache_by_ddl_data(&sub_ddl_data);
+                if !self.filter.filter_ddl_data(&sub_ddl_data) {
                     self.base_extractor
                         .push_ddl(sub_ddl_data.clone(), position.clone())
                         .await?;
@@ -394,16 +497,6 @@ impl MysqlCdcExtractor {
         Ok(())
     }
 
-    fn filter_event(&mut self, table_map_event: &TableMapEvent, row_type: RowType) -> bool {
-        let db = &table_map_event.database_name;
-        let tb = &table_map_event.table_name;
-        let filtered = self.filter.filter_event(db, tb, &row_type);
-        if filtered {
-            return !self.base_extractor.is_data_marker_info(db, tb);
-        }
-        filtered
-    }
-
     fn start_heartbeat(&mut self, shut_down: Arc<AtomicBool>) -> anyhow::Result<()> {
         let db_tb = self.base_extractor.precheck_heartbeat(
             self.heartbeat_interval_secs,
@@ -11,7 +11,7 @@ use dt_common::meta::{
 use dt_common::rdb_filter::RdbFilter;
 use futures::TryStreamExt;
 use sqlx::{MySql, Pool};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     check_log::{check_log::CheckLog, log_type::LogType},
@@ -28,6 +28,8 @@ pub struct MysqlCheckExtractor {
     pub filter: RdbFilter,
     pub check_log_dir: String,
     pub batch_size: usize,
+    // delete rows of check logs from the target if they are not found in the source
+    pub delete_extra: bool,
 }
 
 #[async_trait]
@@ -65,9 +67,15 @@ impl BatchCheckExtractor for MysqlCheckExtractor {
         };
         let query = query_builder.create_mysql_query(&query_info);
 
+        let mut src_keys = HashSet::new();
         let mut rows = query.fetch(&self.conn_pool);
         while let Some(row) = rows.try_next().await.unwrap() {
             let mut row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols);
+            if self.delete_extra {
+                if let Some(key) = BaseCheckExtractor::get_loose_key(&row_data, &tb_meta.basic) {
+                    src_keys.insert(key);
+                }
+            }
 
             if log_type == &LogType::Diff {
                 row_data.row_type = RowType::Update;
@@ -78,6 +86,19 @@ impl BatchCheckExtractor for MysqlCheckExtractor {
                 .push_row(row_data, Position::None)
                 .await?;
         }
+
+        if self.delete_extra {
+            let delete_row_datas = BaseCheckExtractor::build_delete_row_datas(
+                &check_row_datas,
+                &src_keys,
+                &tb_meta.basic,
+            );
+            for row_data in delete_row_datas {
+                self.base_extractor
+                    .push_row(row_data, Position::None)
+                    .await?;
+            }
+        }
         Ok(())
     }
 }
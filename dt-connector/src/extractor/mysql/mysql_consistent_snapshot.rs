@@ -0,0 +1,264 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+use async_mutex::{Mutex, MutexGuard};
+use dt_common::{log_info, log_position, log_warn, meta::position::Position};
+use sqlx::{mysql::MySqlConnection, Connection, Executor, Row};
+
+// warn every interval while the snapshot transaction is open, since it blocks DDL
+// (metadata locks) on extracted tables
+const LONG_SNAPSHOT_WARN_INTERVAL_SECS: u64 = 600;
+
+// a transaction started by START TRANSACTION WITH CONSISTENT SNAPSHOT on a dedicated connection,
+// shared by snapshot extractors of all tables and kept open until all tables are extracted.
+// With FLUSH TABLES WITH READ LOCK held, more connections can start their transactions at the
+// same point, so slices of a table are extracted in parallel from the same view
+pub struct MysqlConsistentSnapshot {
+    conns: Vec<Mutex<MySqlConnection>>,
+    // binlog position of the snapshot, cdc tasks can start from it
+    pub position: Position,
+    start_time: Instant,
+    next_warn_secs: AtomicU64,
+}
+
+impl MysqlConsistentSnapshot {
+    // with read_committed there is no shared transaction, tables are extracted by the normal
+    // connection pool in autocommit queries, so neither metadata locks nor undo logs are held
+    // during the snapshot. The position is read before extracting, rows changed after it may
+    // be seen and are converged by cdc from it
+    pub async fn read_start_position(url: &str) -> anyhow::Result<Position> {
+        let mut conn = MySqlConnection::connect(url).await?;
+        Self::check_replica_lag(&mut conn).await;
+        let position = Self::get_binlog_position(&mut conn).await?;
+        conn.close().await?;
+
+        log_info!(
+            "mysql snapshot started without a shared transaction, isolation: read_committed, position: {}",
+            position
+        );
+        log_position!("consistent_snapshot_position | {}", position);
+        Ok(position)
+    }
+
+    // a REPEATABLE READ transaction shared by all tables until finish
+    pub async fn start(url: &str, conn_count: usize) -> anyhow::Result<Self> {
+        let mut conn = MySqlConnection::connect(url).await?;
+        Self::check_replica_lag(&mut conn).await;
+
+        // like mysqldump --single-transaction --source-data: with FLUSH TABLES WITH READ LOCK held,
+        // the position read in the transaction is exactly the snapshot point. Without the privilege,
+        // read the position before starting the transaction, events in between are replayed by cdc
+        let locked = match conn.execute("FLUSH TABLES WITH READ LOCK").await {
+            Ok(_) => true,
+            Err(e) => {
+                log_warn!(
+                    "FLUSH TABLES WITH READ LOCK failed, binlog position is read before the snapshot starts, cdc from it may replay some rows, error: {}",
+                    e
+                );
+                false
+            }
+        };
+
+        let position_before = if locked {
+            None
+        } else {
+            Some(Self::get_binlog_position(&mut conn).await?)
+        };
+        Self::start_transaction(&mut conn).await?;
+        let position = match position_before {
+            Some(position) => position,
+            None => Self::get_binlog_position(&mut conn).await?,
+        };
+
+        let actual_conn_count = Self::get_conn_count(conn_count, locked);
+        if actual_conn_count < conn_count {
+            log_warn!(
+                "consistent_snapshot_conns: {} needs FLUSH TABLES WITH READ LOCK (RELOAD privilege) to start transactions at the same point, only {} connection is used and slices of a table are extracted one by one",
+                conn_count,
+                actual_conn_count
+            );
+        }
+        let mut conns = vec![Mutex::new(conn)];
+        for _ in 1..actual_conn_count {
+            let mut conn = MySqlConnection::connect(url).await?;
+            Self::start_transaction(&mut conn).await?;
+            conns.push(Mutex::new(conn));
+        }
+
+        if locked {
+            let mut conn = conns[0].lock().await;
+            // UNLOCK TABLES does not commit the transaction if locks are from FLUSH TABLES
+            conn.execute("UNLOCK TABLES").await?;
+        }
+
+        log_info!(
+            "mysql consistent snapshot started, isolation: repeatable_read, connections: {}, position: {}",
+            conns.len(),
+            position
+        );
+        log_position!("consistent_snapshot_position | {}", position);
+        Ok(Self {
+            conns,
+            position,
+            start_time: Instant::now(),
+            next_warn_secs: AtomicU64::new(LONG_SNAPSHOT_WARN_INTERVAL_SECS),
+        })
+    }
+
+    pub fn conn_count(&self) -> usize {
+        self.conns.len()
+    }
+
+    pub async fn lock_conn(&self) -> MutexGuard<'_, MySqlConnection> {
+        self.lock_nth_conn(0).await
+    }
+
+    // connections share the same view, any of them can be used by a slice
+    pub async fn lock_nth_conn(&self, n: usize) -> MutexGuard<'_, MySqlConnection> {
+        let elapsed_secs = self.start_time.elapsed().as_secs();
+        let next_warn_secs = self.next_warn_secs.load(Ordering::Relaxed);
+        if elapsed_secs >= next_warn_secs
+            && self
+                .next_warn_secs
+                .compare_exchange(
+                    next_warn_secs,
+                    elapsed_secs + LONG_SNAPSHOT_WARN_INTERVAL_SECS,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+        {
+            log_warn!(
+                "consistent snapshot transaction has been open for {}s, DDL on extracted tables is blocked until all tables are extracted",
+                elapsed_secs
+            );
+        }
+        self.conns[n % self.conns.len()].lock().await
+    }
+
+    pub async fn finish(&self) -> anyhow::Result<()> {
+        for conn in self.conns.iter() {
+            conn.lock().await.execute("COMMIT").await?;
+        }
+        log_info!(
+            "mysql consistent snapshot finished, held for {}s, position: {}",
+            self.start_time.elapsed().as_secs(),
+            self.position
+        );
+        Ok(())
+    }
+
+    // transactions of extra connections start at the same point only while the lock is held
+    fn get_conn_count(conn_count: usize, locked: bool) -> usize {
+        if locked {
+            conn_count.max(1)
+        } else {
+            1
+        }
+    }
+
+    async fn start_transaction(conn: &mut MySqlConnection) -> anyhow::Result<()> {
+        // WITH CONSISTENT SNAPSHOT only takes effect in REPEATABLE READ
+        conn.execute("SET SESSION TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .await?;
+        conn.execute("START TRANSACTION WITH CONSISTENT SNAPSHOT")
+            .await?;
+        Ok(())
+    }
+
+    // the snapshot of a replica is as stale as its replication lag, and its binlog position
+    // belongs to the replica, so cdc tasks starting from it should also extract from the replica
+    async fn check_replica_lag(conn: &mut MySqlConnection) {
+        // SHOW SLAVE STATUS is removed in mysql 8.4, SHOW REPLICA STATUS is added in 8.0.22
+        let result = match conn.fetch_optional("SHOW REPLICA STATUS").await {
+            Err(e) if !Self::is_access_denied(&e.to_string()) => {
+                conn.fetch_optional("SHOW SLAVE STATUS").await
+            }
+            result => result,
+        };
+        let row = match result {
+            Ok(row) => row,
+            // a primary usually grants no REPLICATION CLIENT to the extracting user
+            Err(e) if Self::is_access_denied(&e.to_string()) => {
+                log_info!(
+                    "skip checking replica lag without REPLICATION CLIENT privilege, error: {}",
+                    e
+                );
+                return;
+            }
+            Err(e) => {
+                log_warn!("failed to check replica status, error: {}", e);
+                return;
+            }
+        };
+        let row = match row {
+            Some(row) => row,
+            None => return,
+        };
+
+        let lag: Option<u64> = match row
+            .try_get("Seconds_Behind_Source")
+            .or_else(|_| row.try_get("Seconds_Behind_Master"))
+        {
+            Ok(lag) => lag,
+            Err(_) => return,
+        };
+        match lag {
+            Some(lag) => log_warn!(
+                "consistent snapshot is taken from a replica which is {}s behind its source, cdc tasks starting from the snapshot position should extract from the same replica",
+                lag
+            ),
+            None => log_warn!(
+                "consistent snapshot is taken from a replica whose replication is not running, the snapshot may be stale"
+            ),
+        }
+    }
+
+    fn is_access_denied(error: &str) -> bool {
+        // ER_SPECIFIC_ACCESS_DENIED_ERROR, ER_ACCESS_DENIED_ERROR
+        error.contains("1227") || error.contains("1045") || error.contains("Access denied")
+    }
+
+    async fn get_binlog_position(conn: &mut MySqlConnection) -> anyhow::Result<Position> {
+        // SHOW MASTER STATUS is removed in mysql 8.4
+        let row = match conn.fetch_one("SHOW MASTER STATUS").await {
+            Ok(row) => row,
+            Err(_) => conn.fetch_one("SHOW BINARY LOG STATUS").await?,
+        };
+        let binlog_filename: String = row.try_get("File")?;
+        let next_event_position: u64 = row.try_get("Position")?;
+        let gtid_set: String = row.try_get("Executed_Gtid_Set").unwrap_or_default();
+        Ok(Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename,
+            next_event_position: next_event_position as u32,
+            gtid_set,
+            timestamp: String::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_conn_count() {
+        assert_eq!(MysqlConsistentSnapshot::get_conn_count(4, true), 4);
+        assert_eq!(MysqlConsistentSnapshot::get_conn_count(0, true), 1);
+        // without FLUSH TABLES WITH READ LOCK
+        assert_eq!(MysqlConsistentSnapshot::get_conn_count(4, false), 1);
+    }
+
+    #[test]
+    fn test_is_access_denied() {
+        assert!(MysqlConsistentSnapshot::is_access_denied(
+            "error returned from database: 1227 (42000): Access denied; you need (at least one of) the SUPER, REPLICATION CLIENT privilege(s) for this operation"
+        ));
+        assert!(!MysqlConsistentSnapshot::is_access_denied(
+            "error returned from database: 1064 (42000): You have an error in your SQL syntax"
+        ));
+    }
+}
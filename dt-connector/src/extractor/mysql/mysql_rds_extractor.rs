@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use aws_sdk_rdsdata::{
+    error::SdkError,
+    operation::execute_statement::ExecuteStatementOutput,
+    types::{Field, SqlParameter},
+    Client,
+};
+use dt_common::{
+    error::Error,
+    log_info, log_warn,
+    meta::{col_value::ColValue, position::Position, row_data::RowData, row_type::RowType},
+    rdb_filter::RdbFilter,
+    utils::time_util::TimeUtil,
+};
+
+use crate::{extractor::base_extractor::BaseExtractor, Extractor};
+
+const MAX_RETRY_TIMES: u32 = 10;
+const RETRY_BASE_MILLIS: u64 = 200;
+const RETRY_MAX_MILLIS: u64 = 10_000;
+
+// extract snapshot data through AWS RDS Data API (HTTP), for servers that do not support
+// persistent connections, such as Aurora Serverless v1. Binlog is not accessible by Data API,
+// so cdc is not supported.
+pub struct MysqlRdsExtractor {
+    pub base_extractor: BaseExtractor,
+    pub client: Client,
+    pub filter: RdbFilter,
+    pub resource_arn: String,
+    pub secret_arn: String,
+    pub batch_size: usize,
+}
+
+// each sql statement is sent as a single execute_statement call
+pub struct StatementRequest {
+    pub database: String,
+    pub sql: String,
+    pub parameters: Vec<SqlParameter>,
+}
+
+#[async_trait]
+impl Extractor for MysqlRdsExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!(
+            "MysqlRdsExtractor starts, resource_arn: {}, batch_size: {}",
+            self.resource_arn,
+            self.batch_size
+        );
+        self.extract_internal().await?;
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+impl MysqlRdsExtractor {
+    async fn extract_internal(&mut self) -> anyhow::Result<()> {
+        for db in self.list_dbs().await? {
+            if self.filter.filter_schema(&db) {
+                log_info!("schema: {} filtered", db);
+                continue;
+            }
+
+            for tb in self.list_tbs(&db).await? {
+                if self.filter.filter_event(&db, &tb, &RowType::Insert) {
+                    log_info!("schema: {}, tb: {}, insert events filtered", db, tb);
+                    continue;
+                }
+                self.extract_tb(&db, &tb).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn extract_tb(&mut self, db: &str, tb: &str) -> anyhow::Result<()> {
+        let key_cols = self.get_primary_key_cols(db, tb).await?;
+        if key_cols.is_empty() {
+            log_warn!(
+                "`{}`.`{}` has no primary key, it will be paginated by LIMIT/OFFSET, rows changed during extraction may be skipped or extracted twice",
+                db,
+                tb
+            );
+        }
+        log_info!(
+            "start extracting data from `{}`.`{}` by batch, key cols: [{}]",
+            db,
+            tb,
+            key_cols.join(",")
+        );
+
+        let mut extracted_count = 0;
+        let mut last_key_fields: Vec<Field> = Vec::new();
+        loop {
+            // Data API has no server side cursor, paginate by the primary key
+            let condition = if last_key_fields.is_empty() {
+                String::new()
+            } else {
+                Self::build_key_condition(&key_cols)
+            };
+            let where_sql = BaseExtractor::get_where_sql(&self.filter, db, tb, &condition);
+            let offset = if key_cols.is_empty() {
+                extracted_count
+            } else {
+                0
+            };
+            let request = StatementRequest {
+                database: db.into(),
+                sql: Self::build_extract_sql(
+                    db,
+                    tb,
+                    &where_sql,
+                    &key_cols,
+                    self.batch_size,
+                    offset,
+                ),
+                parameters: last_key_fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        SqlParameter::builder()
+                            .name(format!("k{}", i))
+                            .value(field.clone())
+                            .build()
+                    })
+                    .collect(),
+            };
+            let output = self.execute_statement(&request).await?;
+
+            let col_names: Vec<String> = output
+                .column_metadata()
+                .iter()
+                .map(|i| i.name().unwrap_or_default().to_string())
+                .collect();
+            let ignore_cols = self.filter.get_ignore_cols(db, tb).cloned();
+
+            let key_indexes: Vec<usize> = key_cols
+                .iter()
+                .filter_map(|key_col| col_names.iter().position(|col| col == key_col))
+                .collect();
+
+            let mut slice_count = 0;
+            for record in output.records() {
+                if key_indexes.len() == key_cols.len() && !key_cols.is_empty() {
+                    last_key_fields = key_indexes.iter().map(|i| record[*i].clone()).collect();
+                }
+                let mut after = HashMap::new();
+                for (col, field) in col_names.iter().zip(record.iter()) {
+                    if ignore_cols.as_ref().is_some_and(|cols| cols.contains(col)) {
+                        continue;
+                    }
+                    after.insert(col.clone(), Self::field_to_col_value(field));
+                }
+                let row_data =
+                    RowData::new(db.into(), tb.into(), RowType::Insert, None, Some(after));
+                self.base_extractor
+                    .push_row(row_data, Position::None)
+                    .await?;
+                slice_count += 1;
+            }
+
+            extracted_count += slice_count;
+            if slice_count < self.batch_size {
+                break;
+            }
+        }
+
+        log_info!(
+            "end extracting data from `{}`.`{}`, all count: {}",
+            db,
+            tb,
+            extracted_count
+        );
+        Ok(())
+    }
+
+    // `(k0, k1) > (:k0, :k1)`, compared in the ORDER BY order of build_extract_sql
+    fn build_key_condition(key_cols: &[String]) -> String {
+        let cols: Vec<String> = key_cols.iter().map(|col| format!("`{}`", col)).collect();
+        let params: Vec<String> = (0..key_cols.len()).map(|i| format!(":k{}", i)).collect();
+        format!("({}) > ({})", cols.join(", "), params.join(", "))
+    }
+
+    fn build_extract_sql(
+        db: &str,
+        tb: &str,
+        where_sql: &str,
+        key_cols: &[String],
+        batch_size: usize,
+        offset: usize,
+    ) -> String {
+        if key_cols.is_empty() {
+            return format!(
+                "SELECT * FROM `{}`.`{}` {} LIMIT {} OFFSET {}",
+                db, tb, where_sql, batch_size, offset
+            );
+        }
+        let order_by: Vec<String> = key_cols
+            .iter()
+            .map(|col| format!("`{}` ASC", col))
+            .collect();
+        format!(
+            "SELECT * FROM `{}`.`{}` {} ORDER BY {} LIMIT {}",
+            db,
+            tb,
+            where_sql,
+            order_by.join(", "),
+            batch_size
+        )
+    }
+
+    async fn get_primary_key_cols(&self, db: &str, tb: &str) -> anyhow::Result<Vec<String>> {
+        let request = StatementRequest {
+            database: db.into(),
+            sql: format!(
+                "SELECT COLUMN_NAME FROM information_schema.KEY_COLUMN_USAGE WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME = '{}' AND CONSTRAINT_NAME = 'PRIMARY' ORDER BY ORDINAL_POSITION",
+                db, tb
+            ),
+            parameters: Vec::new(),
+        };
+        self.query_first_col(&request).await
+    }
+
+    async fn list_dbs(&self) -> anyhow::Result<Vec<String>> {
+        let request = StatementRequest {
+            database: String::new(),
+            sql: "SHOW DATABASES".into(),
+            parameters: Vec::new(),
+        };
+        self.query_first_col(&request).await
+    }
+
+    async fn list_tbs(&self, db: &str) -> anyhow::Result<Vec<String>> {
+        let request = StatementRequest {
+            database: db.into(),
+            sql: format!(
+                "SHOW FULL TABLES FROM `{}` WHERE Table_type = 'BASE TABLE'",
+                db
+            ),
+            parameters: Vec::new(),
+        };
+        self.query_first_col(&request).await
+    }
+
+    async fn query_first_col(&self, request: &StatementRequest) -> anyhow::Result<Vec<String>> {
+        let output = self.execute_statement(request).await?;
+        let mut values = Vec::new();
+        for record in output.records() {
+            if let Some(value) = record.first().and_then(|f| f.as_string_value().ok()) {
+                values.push(value.clone());
+            }
+        }
+        Ok(values)
+    }
+
+    async fn execute_statement(
+        &self,
+        request: &StatementRequest,
+    ) -> anyhow::Result<ExecuteStatementOutput> {
+        let mut retry_times = 0;
+        loop {
+            let mut builder = self
+                .client
+                .execute_statement()
+                .resource_arn(&self.resource_arn)
+                .secret_arn(&self.secret_arn)
+                .sql(&request.sql)
+                .set_parameters(Some(request.parameters.clone()))
+                .include_result_metadata(true);
+            if !request.database.is_empty() {
+                builder = builder.database(&request.database);
+            }
+
+            match builder.send().await {
+                Ok(output) => return Ok(output),
+                Err(err) => {
+                    let status = err.raw_response().map(|r| r.status().as_u16());
+                    let throttled = status == Some(429)
+                        || matches!(&err, SdkError::ServiceError(e) if e.err().is_service_unavailable_error());
+                    if !throttled || retry_times >= MAX_RETRY_TIMES {
+                        bail! {Error::ExtractorError(format!(
+                            "rds data api execute_statement failed, sql: {}, error: {}",
+                            request.sql, err
+                        ))}
+                    }
+
+                    let wait_millis =
+                        std::cmp::min(RETRY_BASE_MILLIS << retry_times, RETRY_MAX_MILLIS);
+                    retry_times += 1;
+                    log_warn!(
+                        "rds data api throttled, retry: {}, wait {} ms",
+                        retry_times,
+                        wait_millis
+                    );
+                    TimeUtil::sleep_millis(wait_millis).await;
+                }
+            }
+        }
+    }
+
+    pub fn field_to_col_value(field: &Field) -> ColValue {
+        match field {
+            Field::IsNull(_) => ColValue::None,
+            Field::BooleanValue(v) => ColValue::Bool(*v),
+            Field::LongValue(v) => ColValue::LongLong(*v),
+            Field::DoubleValue(v) => ColValue::Double(*v),
+            Field::StringValue(v) => ColValue::String(v.clone()),
+            Field::BlobValue(v) => ColValue::Blob(v.clone().into_inner()),
+            // array values are only returned for pg
+            _ => ColValue::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_extract_sql() {
+        let key_cols = vec!["id".to_string(), "name".to_string()];
+        assert_eq!(
+            MysqlRdsExtractor::build_key_condition(&key_cols),
+            "(`id`, `name`) > (:k0, :k1)"
+        );
+        assert_eq!(
+            MysqlRdsExtractor::build_extract_sql(
+                "db_1",
+                "tb_1",
+                "WHERE (`id`, `name`) > (:k0, :k1)",
+                &key_cols,
+                100,
+                0
+            ),
+            "SELECT * FROM `db_1`.`tb_1` WHERE (`id`, `name`) > (:k0, :k1) ORDER BY `id` ASC, `name` ASC LIMIT 100"
+        );
+        // tables without primary key
+        assert_eq!(
+            MysqlRdsExtractor::build_extract_sql("db_1", "tb_1", "", &[], 100, 200),
+            "SELECT * FROM `db_1`.`tb_1`  LIMIT 100 OFFSET 200"
+        );
+    }
+
+    #[test]
+    fn test_field_to_col_value() {
+        assert_eq!(
+            MysqlRdsExtractor::field_to_col_value(&Field::IsNull(true)),
+            ColValue::None
+        );
+        assert_eq!(
+            MysqlRdsExtractor::field_to_col_value(&Field::LongValue(1)),
+            ColValue::LongLong(1)
+        );
+        assert_eq!(
+            MysqlRdsExtractor::field_to_col_value(&Field::StringValue("a".into())),
+            ColValue::String("a".into())
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     cmp,
+    collections::{HashMap, HashSet},
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc, Mutex,
@@ -8,7 +9,7 @@ use std::{
 
 use async_trait::async_trait;
 use dt_common::{
-    log_debug,
+    log_debug_row, log_warn,
     meta::{
         adaptor::{mysql_col_value_convertor::MysqlColValueConvertor, sqlx_ext::SqlxMysqlExt},
         col_value::ColValue,
@@ -21,19 +22,26 @@ use dt_common::{
         position::Position,
         row_data::RowData,
     },
+    monitor::monitor::Monitor,
     rdb_filter::RdbFilter,
 };
 use futures::TryStreamExt;
 
 use serde_json::json;
-use sqlx::{MySql, Pool};
+use sqlx::{MySql, Pool, Row};
 
 use dt_common::{config::config_enums::DbType, log_info};
-use tokio::task::JoinHandle;
+use tokio::task::{JoinHandle, JoinSet};
 
 use crate::{
     close_conn_pool,
-    extractor::{base_extractor::BaseExtractor, resumer::snapshot_resumer::SnapshotResumer},
+    extractor::{
+        base_extractor::BaseExtractor,
+        extract_rate_limiter::ExtractRateLimiter,
+        mysql::mysql_consistent_snapshot::MysqlConsistentSnapshot,
+        resumer::snapshot_resumer::SnapshotResumer,
+        slice_checksum::{SliceChecksum, SliceChecksumSql, SliceChecksumStats},
+    },
     rdb_query_builder::RdbQueryBuilder,
     rdb_router::RdbRouter,
     Extractor,
@@ -50,12 +58,43 @@ pub struct MysqlSnapshotExtractor {
     pub sample_interval: usize,
     pub db: String,
     pub tb: String,
+    pub force_index: Option<String>,
+    pub auto_detect_force_index: bool,
+    // from [extractor] tb_query_hints, an index hint replaces force_index,
+    // an optimizer hint (/*+ ... */) is added right after SELECT
+    pub query_hint: Option<String>,
+    // extract each partition by SELECT ... PARTITION (pN), for partitioned tables
+    pub use_partition_based_extraction: bool,
+    pub partition_concurrency: usize,
+    // extract by the dedicated connection of a snapshot shared by all tables,
+    // parallel and partition based extraction are disabled
+    pub consistent_snapshot: Option<Arc<MysqlConsistentSnapshot>>,
+    // split the order_col range into sub ranges and extract them concurrently, for integer order_col
+    pub parallel_slices: usize,
+    // log progress of the snapshot every slice_progress_interval batches
+    pub slice_progress_interval: usize,
+    // total records of the table being extracted, estimated by table statistics,
+    // or counted exactly when extracting by slices
+    pub progress_total_count: usize,
+    // extract by order_col DESC, parallel and slice based extraction are disabled
+    pub snapshot_desc: bool,
+    // target of sink_type=check, compare slices of batch_size rows by checksums and
+    // only extract the rows of mismatched slices
+    pub checksum_target_conn_pool: Option<Pool<MySql>>,
 }
 
 struct ExtractColValue {
     value: ColValue,
 }
 
+#[derive(Clone)]
+struct SliceProgress {
+    // the last extracted value, or the start of the slice
+    value: ColValue,
+    end: ColValue,
+    finished: bool,
+}
+
 #[async_trait]
 impl Extractor for MysqlSnapshotExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
@@ -71,6 +110,9 @@ impl Extractor for MysqlSnapshotExtractor {
     }
 
     async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(conn_pool) = &self.checksum_target_conn_pool {
+            conn_pool.close().await;
+        }
         close_conn_pool!(self)
     }
 }
@@ -84,20 +126,53 @@ impl MysqlSnapshotExtractor {
             .await?
             .to_owned();
 
-        if let Some(order_col) = &tb_meta.basic.order_col {
+        let progress_key = format!("{}.{}", self.db, self.tb);
+        self.progress_total_count = self.get_estimated_count().await?;
+        self.base_extractor
+            .monitor
+            .monitor
+            .lock()
+            .unwrap()
+            .set_snapshot_progress(&progress_key, 0, self.progress_total_count);
+
+        let partitions =
+            if self.use_partition_based_extraction && self.consistent_snapshot.is_none() {
+                self.get_partitions().await?
+            } else {
+                Vec::new()
+            };
+
+        if !partitions.is_empty() {
+            extracted_count = self.extract_by_partitions(&tb_meta, partitions).await?;
+        } else if let Some(order_col) = &tb_meta.basic.order_col {
             let order_col_type = tb_meta.get_col_type(order_col)?;
-            let parallel_extract = self.parallel_size > 1
-                && matches!(
-                    order_col_type,
-                    MysqlColType::Int { .. }
-                        | MysqlColType::BigInt { .. }
-                        | MysqlColType::MediumInt { .. }
-                );
+            let parallel_supported = self.consistent_snapshot.is_none() && !self.snapshot_desc;
+            let integer_order_col = matches!(
+                order_col_type,
+                MysqlColType::Int { .. }
+                    | MysqlColType::BigInt { .. }
+                    | MysqlColType::MediumInt { .. }
+            );
+            let binary_order_col = matches!(
+                order_col_type,
+                MysqlColType::Binary { .. } | MysqlColType::VarBinary { .. }
+            );
+            // slices of a consistent snapshot are extracted by its connections sharing one view
+            let slice_extract = !self.snapshot_desc
+                && (integer_order_col || binary_order_col)
+                && self.parallel_slices > 1;
+            let parallel_extract =
+                parallel_supported && integer_order_col && !slice_extract && self.parallel_size > 1;
 
-            let resume_value = if let Some(value) =
-                self.resumer
-                    .get_resume_value(&self.db, &self.tb, order_col, parallel_extract)
-            {
+            self.try_detect_force_index(order_col, &tb_meta).await?;
+
+            let checksum_extract = self.checksum_target_conn_pool.is_some();
+            let resume_value = if let Some(value) = self.resumer.get_resume_value(
+                &self.db,
+                &self.tb,
+                order_col,
+                parallel_extract || slice_extract || checksum_extract,
+            ) {
                 MysqlColValueConvertor::from_str(order_col_type, &value)?
             } else {
                 ColValue::None
@@ -112,7 +187,13 @@ impl MysqlSnapshotExtractor {
                 resume_value.to_string()
             );
 
-            extracted_count = if parallel_extract {
+            extracted_count = if checksum_extract {
+                self.extract_by_checksum(&tb_meta, order_col, order_col_type, resume_value)
+                    .await?
+            } else if slice_extract {
+                self.extract_by_slices(&tb_meta, order_col, order_col_type, resume_value)
+                    .await?
+            } else if parallel_extract {
                 log_info!("parallel extracting, parallel_size: {}", self.parallel_size);
                 self.parallel_extract_by_batch(&tb_meta, order_col, order_col_type, resume_value)
                     .await?
@@ -120,7 +201,16 @@ impl MysqlSnapshotExtractor {
                 self.extract_by_batch(&tb_meta, order_col, order_col_type, resume_value)
                     .await?
             };
+        } else if let Some(order_cols) = Self::get_composite_order_cols(&tb_meta) {
+            extracted_count = self
+                .extract_by_composite_batch(&tb_meta, &order_cols)
+                .await?;
         } else {
+            log_warn!(
+                "`{}`.`{}` has no primary key or NOT NULL unique key, it will be extracted without batch, which may be slow and use much memory",
+                self.db,
+                self.tb
+            );
             extracted_count = self.extract_all(&tb_meta).await?;
         }
 
@@ -130,6 +220,13 @@ impl MysqlSnapshotExtractor {
             self.tb,
             extracted_count
         );
+        self.base_extractor
+            .monitor
+            .monitor
+            .lock()
+            .unwrap()
+            .remove_snapshot_progress(&progress_key)
+            .remove_partition_counters(&progress_key);
         Ok(())
     }
 
@@ -140,7 +237,7 @@ impl MysqlSnapshotExtractor {
             self.tb
         );
 
-        let ignore_cols = self.filter.get_ignore_cols(&self.db, &self.tb);
+        let ignore_cols = self.get_ignore_cols(tb_meta);
         let cols_str = self.build_extract_cols_str(tb_meta)?;
         let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, "");
         let sql = format!(
@@ -148,9 +245,18 @@ impl MysqlSnapshotExtractor {
             cols_str, self.db, self.tb, where_sql
         );
 
-        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        let consistent_snapshot = self.consistent_snapshot.clone();
+        let mut snapshot_conn = match &consistent_snapshot {
+            Some(snapshot) => Some(snapshot.lock_conn().await),
+            None => None,
+        };
+        let query = sqlx::query(&sql);
+        let mut rows = match snapshot_conn.as_mut() {
+            Some(conn) => query.fetch(&mut **conn),
+            None => query.fetch(&self.conn_pool),
+        };
         while let Some(row) = rows.try_next().await.unwrap() {
-            let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols);
+            let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols.as_ref());
             self.base_extractor
                 .push_row(row_data, Position::None)
                 .await?;
@@ -166,22 +272,25 @@ impl MysqlSnapshotExtractor {
         resume_value: ColValue,
     ) -> anyhow::Result<usize> {
         let mut extracted_count = 0;
+        let mut batch_count = 0;
         let mut start_value = resume_value;
-        let ignore_cols = self.filter.get_ignore_cols(&self.db, &self.tb);
+        let ignore_cols = self.get_ignore_cols(tb_meta);
         let cols_str = self.build_extract_cols_str(tb_meta)?;
 
+        let (order, operator) = self.get_order_and_operator();
+        let index_hint = self.get_index_hint_str();
         let where_sql_1 = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, "");
         let sql_1 = format!(
-            "SELECT {} FROM `{}`.`{}` {} ORDER BY `{}` ASC LIMIT {}",
-            cols_str, self.db, self.tb, where_sql_1, order_col, self.batch_size
+            "SELECT {} FROM `{}`.`{}` {} {} ORDER BY `{}` {} LIMIT {}",
+            cols_str, self.db, self.tb, index_hint, where_sql_1, order_col, order, self.batch_size
         );
 
-        let condition_2 = format!("`{}` > ?", order_col);
+        let condition_2 = format!("`{}` {} ?", order_col, operator);
         let where_sql_2 =
             BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition_2);
         let sql_2 = format!(
-            "SELECT {} FROM `{}`.`{}` {} ORDER BY `{}` ASC LIMIT {}",
-            cols_str, self.db, self.tb, where_sql_2, order_col, self.batch_size
+            "SELECT {} FROM `{}`.`{}` {} {} ORDER BY `{}` {} LIMIT {}",
+            cols_str, self.db, self.tb, index_hint, where_sql_2, order_col, order, self.batch_size
         );
 
         loop {
@@ -192,7 +301,15 @@ impl MysqlSnapshotExtractor {
                 sqlx::query(&sql_2).bind_col_value(Some(&start_value_for_bind), order_col_type)
             };
 
-            let mut rows = query.fetch(&self.conn_pool);
+            let consistent_snapshot = self.consistent_snapshot.clone();
+            let mut snapshot_conn = match &consistent_snapshot {
+                Some(snapshot) => Some(snapshot.lock_conn().await),
+                None => None,
+            };
+            let mut rows = match snapshot_conn.as_mut() {
+                Some(conn) => query.fetch(&mut **conn),
+                None => query.fetch(&self.conn_pool),
+            };
             let mut slice_count = 0usize;
 
             while let Some(row) = rows.try_next().await.unwrap() {
@@ -204,22 +321,120 @@ impl MysqlSnapshotExtractor {
                     continue;
                 }
 
-                let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols);
-                let position = if let Some(value) = start_value.to_option_string() {
-                    Position::RdbSnapshot {
-                        db_type: DbType::Mysql.to_string(),
-                        schema: self.db.clone(),
-                        tb: self.tb.clone(),
-                        order_col: order_col.into(),
-                        value,
-                    }
-                } else {
-                    Position::None
-                };
+                let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols.as_ref());
+                let position = Self::build_position(&self.db, &self.tb, order_col, &start_value);
+
+                self.base_extractor.push_row(row_data, position).await?;
+            }
+
+            batch_count += 1;
+            self.report_progress(extracted_count, batch_count);
+            // all data extracted
+            if slice_count < self.batch_size {
+                break;
+            }
+        }
+
+        Ok(extracted_count)
+    }
+
+    // for tables with composite primary keys, extract by batch with a row value comparison:
+    // WHERE (a, b) > (?, ?) ORDER BY a, b
+    async fn extract_by_composite_batch(
+        &mut self,
+        tb_meta: &MysqlTbMeta,
+        order_cols: &[String],
+    ) -> anyhow::Result<usize> {
+        let mut order_col_types = Vec::new();
+        for order_col in order_cols {
+            order_col_types.push(tb_meta.get_col_type(order_col)?.clone());
+        }
+
+        let position_order_col = json!(order_cols).to_string();
+        let mut start_values = vec![ColValue::None; order_cols.len()];
+        if let Some(value) =
+            self.resumer
+                .get_resume_value(&self.db, &self.tb, &position_order_col, false)
+        {
+            let values: Vec<String> = serde_json::from_str(&value)?;
+            for (i, value) in values.iter().enumerate() {
+                start_values[i] = MysqlColValueConvertor::from_str(&order_col_types[i], value)?;
+            }
+        }
+
+        log_info!(
+            "start extracting data from `{}`.`{}` by batch, order_cols: {}, start_values: {:?}",
+            self.db,
+            self.tb,
+            position_order_col,
+            start_values
+        );
+
+        let mut extracted_count = 0;
+        let mut batch_count = 0;
+        let ignore_cols = self.get_ignore_cols(tb_meta);
+        let cols_str = self.build_extract_cols_str(tb_meta)?;
+        let (order, operator) = self.get_order_and_operator();
+        let (condition_2, order_by_str) =
+            Self::build_composite_batch_condition(order_cols, order, operator);
+
+        let index_hint = self.get_index_hint_str();
+        let where_sql_1 = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, "");
+        let sql_1 = format!(
+            "SELECT {} FROM `{}`.`{}` {} {} ORDER BY {} LIMIT {}",
+            cols_str, self.db, self.tb, index_hint, where_sql_1, order_by_str, self.batch_size
+        );
+
+        let where_sql_2 =
+            BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition_2);
+        let sql_2 = format!(
+            "SELECT {} FROM `{}`.`{}` {} {} ORDER BY {} LIMIT {}",
+            cols_str, self.db, self.tb, index_hint, where_sql_2, order_by_str, self.batch_size
+        );
+
+        loop {
+            let start_values_for_bind = start_values.clone();
+            let query = if start_values[0] == ColValue::None {
+                sqlx::query(&sql_1)
+            } else {
+                let mut query = sqlx::query(&sql_2);
+                for (value, col_type) in start_values_for_bind.iter().zip(order_col_types.iter()) {
+                    query = query.bind_col_value(Some(value), col_type);
+                }
+                query
+            };
+
+            let consistent_snapshot = self.consistent_snapshot.clone();
+            let mut snapshot_conn = match &consistent_snapshot {
+                Some(snapshot) => Some(snapshot.lock_conn().await),
+                None => None,
+            };
+            let mut rows = match snapshot_conn.as_mut() {
+                Some(conn) => query.fetch(&mut **conn),
+                None => query.fetch(&self.conn_pool),
+            };
+            let mut slice_count = 0usize;
+
+            while let Some(row) = rows.try_next().await? {
+                for (i, order_col) in order_cols.iter().enumerate() {
+                    start_values[i] =
+                        MysqlColValueConvertor::from_query(&row, order_col, &order_col_types[i])?;
+                }
+                extracted_count += 1;
+                slice_count += 1;
+                // sampling may be used in check scenario
+                if extracted_count % self.sample_interval != 0 {
+                    continue;
+                }
 
+                let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols.as_ref());
+                let position =
+                    Self::build_composite_position(&self.db, &self.tb, order_cols, &start_values);
                 self.base_extractor.push_row(row_data, position).await?;
             }
 
+            batch_count += 1;
+            self.report_progress(extracted_count, batch_count);
             // all data extracted
             if slice_count < self.batch_size {
                 break;
@@ -240,31 +455,35 @@ impl MysqlSnapshotExtractor {
         let parallel_size = self.parallel_size;
         let batch_size = cmp::max(self.batch_size / parallel_size, 1);
         let router = Arc::new(self.base_extractor.router.clone());
-        let ignore_cols = self.filter.get_ignore_cols(&self.db, &self.tb).cloned();
+        let rate_limiter = self.base_extractor.rate_limiter.clone();
+        let throttled_ms = Arc::new(AtomicUsize::new(0));
+        let ignore_cols = self.get_ignore_cols(tb_meta);
 
         let mut start_value = resume_value;
+        let mut batch_count = 0;
         let cols_str = self.build_extract_cols_str(tb_meta)?;
 
+        let index_hint = self.get_index_hint_str();
         let where_sql_1 = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, "");
         let sql_1 = format!(
-            "SELECT {} FROM `{}`.`{}` {} ORDER BY `{}` ASC LIMIT {}",
-            cols_str, self.db, self.tb, where_sql_1, order_col, batch_size
+            "SELECT {} FROM `{}`.`{}` {} {} ORDER BY `{}` ASC LIMIT {}",
+            cols_str, self.db, self.tb, index_hint, where_sql_1, order_col, batch_size
         );
 
         let condition_2 = format!("`{}` > ? AND `{}` <= ?", order_col, order_col);
         let where_sql_2 =
             BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition_2);
         let sql_2 = format!(
-            "SELECT {} FROM `{}`.`{}` {} ORDER BY `{}` ASC LIMIT {}",
-            cols_str, self.db, self.tb, where_sql_2, order_col, batch_size
+            "SELECT {} FROM `{}`.`{}` {} {} ORDER BY `{}` ASC LIMIT {}",
+            cols_str, self.db, self.tb, index_hint, where_sql_2, order_col, batch_size
         );
 
         let condition_3 = format!("`{}` > ?", order_col);
         let where_sql_3 =
             BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition_3);
         let sql_3 = format!(
-            "SELECT {} FROM `{}`.`{}` {} ORDER BY `{}` ASC LIMIT {}",
-            cols_str, self.db, self.tb, where_sql_3, order_col, batch_size
+            "SELECT {} FROM `{}`.`{}` {} {} ORDER BY `{}` ASC LIMIT {}",
+            cols_str, self.db, self.tb, index_hint, where_sql_3, order_col, batch_size
         );
 
         loop {
@@ -287,8 +506,15 @@ impl MysqlSnapshotExtractor {
                     let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols.as_ref());
                     let position =
                         Self::build_position(&self.db, &self.tb, order_col, &start_value);
-                    Self::push_row(&self.base_extractor.buffer, &router, row_data, position)
-                        .await?;
+                    Self::push_row(
+                        &self.base_extractor.buffer,
+                        &router,
+                        &rate_limiter,
+                        &throttled_ms,
+                        row_data,
+                        position,
+                    )
+                    .await?;
                     slice_count += 1;
                 }
 
@@ -299,6 +525,8 @@ impl MysqlSnapshotExtractor {
                 for i in 0..parallel_size {
                     let buffer = self.base_extractor.buffer.clone();
                     let router = router.clone();
+                    let rate_limiter = rate_limiter.clone();
+                    let throttled_ms = throttled_ms.clone();
                     let conn_pool = self.conn_pool.clone();
                     let db = self.db.clone();
                     let tb = self.tb.clone();
@@ -341,7 +569,15 @@ impl MysqlSnapshotExtractor {
                                 RowData::from_mysql_row(&row, &tb_meta, &ignore_cols.as_ref());
                             let position =
                                 Self::build_position(&db, &tb, &order_col, &order_col_value);
-                            Self::push_row(&buffer, &router, row_data, position).await?;
+                            Self::push_row(
+                                &buffer,
+                                &router,
+                                &rate_limiter,
+                                &throttled_ms,
+                                row_data,
+                                position,
+                            )
+                            .await?;
                             slice_count += 1;
                         }
 
@@ -360,100 +596,1648 @@ impl MysqlSnapshotExtractor {
                 }
 
                 start_value = last_order_col_value.lock().unwrap().value.clone();
-                if all_finished.load(Ordering::Acquire) {
-                    break;
-                }
+            }
+
+            batch_count += 1;
+            self.report_progress(all_extracted_count.load(Ordering::Acquire), batch_count);
+            if all_finished.load(Ordering::Acquire) {
+                break;
             }
         }
 
+        self.base_extractor.monitor.counters.throttled_ms += throttled_ms.load(Ordering::Acquire);
         Ok(all_extracted_count.load(Ordering::Acquire))
     }
 
-    pub async fn push_row(
-        buffer: &Arc<DtQueue>,
-        router: &Arc<RdbRouter>,
-        row_data: RowData,
-        position: Position,
-    ) -> anyhow::Result<()> {
-        let row_data = router.route_row(row_data);
-        let dt_data = DtData::Dml { row_data };
-        let item = DtItem {
-            dt_data,
-            position,
-            data_origin_node: String::new(),
+    // split the order_col range into parallel_slices sub ranges and extract them concurrently,
+    // each sub range is extracted by batch on its own connection of the pool, or on a connection
+    // of the consistent snapshot so all slices read the same view
+    async fn extract_by_slices(
+        &mut self,
+        tb_meta: &MysqlTbMeta,
+        order_col: &str,
+        order_col_type: &MysqlColType,
+        resume_value: ColValue,
+    ) -> anyhow::Result<usize> {
+        let total_count = self
+            .get_total_count(order_col, order_col_type, &resume_value)
+            .await?;
+        if total_count == 0 {
+            log_info!(
+                "`{}`.`{}` is an empty table, skip extracting by slices",
+                self.db,
+                self.tb
+            );
+            return Ok(0);
+        }
+
+        let boundaries = if matches!(
+            order_col_type,
+            MysqlColType::Binary { .. } | MysqlColType::VarBinary { .. }
+        ) {
+            self.get_sampled_slice_boundaries(order_col, order_col_type, &resume_value, total_count)
+                .await?
+        } else {
+            self.get_slice_boundaries(order_col, order_col_type, &resume_value)
+                .await?
         };
-        log_debug!("extracted item: {}", json!(item));
-        buffer.push(item).await
+        let boundaries = match boundaries {
+            Some(boundaries) => boundaries,
+            None => return Ok(0),
+        };
+        log_info!(
+            "extracting by slices, slice count: {}, total count: {}, boundaries: [{}]",
+            boundaries.len() + 1,
+            total_count,
+            boundaries
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        // slice i extracts (start, end], ColValue::None means unbounded
+        let mut starts = vec![resume_value];
+        starts.extend(boundaries.iter().cloned());
+        let mut ends = boundaries;
+        ends.push(ColValue::None);
+        let slices: Vec<SliceProgress> = starts
+            .into_iter()
+            .zip(ends)
+            .map(|(start, end)| SliceProgress {
+                value: start,
+                end,
+                finished: false,
+            })
+            .collect();
+        if let Some(snapshot) = &self.consistent_snapshot {
+            if snapshot.conn_count() < slices.len() {
+                log_warn!(
+                    "`{}`.`{}` has {} slices but the consistent snapshot has {} connections, slices on the same connection are extracted one by one",
+                    self.db,
+                    self.tb,
+                    slices.len(),
+                    snapshot.conn_count()
+                );
+            }
+        }
+
+        let all_extracted_count = Arc::new(AtomicUsize::new(0));
+        let all_batch_count = Arc::new(AtomicUsize::new(0));
+        let slice_progresses = Arc::new(Mutex::new(slices.clone()));
+        let monitor = self.base_extractor.monitor.monitor.clone();
+        self.progress_total_count = total_count;
+        monitor.lock().unwrap().set_snapshot_progress(
+            &format!("{}.{}", self.db, self.tb),
+            0,
+            total_count,
+        );
+        let router = Arc::new(self.base_extractor.router.clone());
+        let rate_limiter = self.base_extractor.rate_limiter.clone();
+        let throttled_ms = Arc::new(AtomicUsize::new(0));
+        let ignore_cols = self.get_ignore_cols(tb_meta);
+        let cols_str = self.build_extract_cols_str(tb_meta)?;
+        let index_hint = self.get_index_hint_str();
+
+        let mut join_set: JoinSet<anyhow::Result<()>> = JoinSet::new();
+        for (i, slice) in slices.into_iter().enumerate() {
+            let mut conditions = vec![format!("`{}` > ?", order_col)];
+            if slice.end != ColValue::None {
+                conditions.push(format!("`{}` <= ?", order_col));
+            }
+            let build_sql = |conditions: &[String]| {
+                let where_sql = BaseExtractor::get_where_sql(
+                    &self.filter,
+                    &self.db,
+                    &self.tb,
+                    &conditions.join(" AND "),
+                );
+                format!(
+                    "SELECT {} FROM `{}`.`{}` {} {} ORDER BY `{}` ASC LIMIT {}",
+                    cols_str, self.db, self.tb, index_hint, where_sql, order_col, self.batch_size
+                )
+            };
+            // without lower bound, for the first batch of the first slice if not resumed
+            let sql_1 = build_sql(&conditions[1..]);
+            let sql_2 = build_sql(&conditions);
+
+            let buffer = self.base_extractor.buffer.clone();
+            let router = router.clone();
+            let rate_limiter = rate_limiter.clone();
+            let throttled_ms = throttled_ms.clone();
+            let conn_pool = self.conn_pool.clone();
+            let consistent_snapshot = self.consistent_snapshot.clone();
+            let db = self.db.clone();
+            let tb = self.tb.clone();
+            let tb_meta = tb_meta.clone();
+            let order_col = order_col.to_string();
+            let order_col_type = order_col_type.clone();
+            let ignore_cols = ignore_cols.clone();
+            let batch_size = self.batch_size;
+            let all_extracted_count = all_extracted_count.clone();
+            let all_batch_count = all_batch_count.clone();
+            let slice_progresses = slice_progresses.clone();
+            let monitor = monitor.clone();
+            let progress_interval = self.slice_progress_interval;
+
+            join_set.spawn(async move {
+                let mut start_value = slice.value;
+                loop {
+                    let mut query = if start_value == ColValue::None {
+                        sqlx::query(&sql_1)
+                    } else {
+                        sqlx::query(&sql_2).bind_col_value(Some(&start_value), &order_col_type)
+                    };
+                    if slice.end != ColValue::None {
+                        query = query.bind_col_value(Some(&slice.end), &order_col_type);
+                    }
+
+                    let mut snapshot_conn = match &consistent_snapshot {
+                        Some(snapshot) => Some(snapshot.lock_nth_conn(i).await),
+                        None => None,
+                    };
+                    let mut rows = match snapshot_conn.as_mut() {
+                        Some(conn) => query.fetch(&mut **conn),
+                        None => query.fetch(&conn_pool),
+                    };
+                    let mut slice_count = 0;
+                    while let Some(row) = rows.try_next().await? {
+                        start_value =
+                            MysqlColValueConvertor::from_query(&row, &order_col, &order_col_type)?;
+                        let row_data =
+                            RowData::from_mysql_row(&row, &tb_meta, &ignore_cols.as_ref());
+                        Self::push_row(
+                            &buffer,
+                            &router,
+                            &rate_limiter,
+                            &throttled_ms,
+                            row_data,
+                            Position::None,
+                        )
+                        .await?;
+                        slice_count += 1;
+                    }
+                    let extracted_count =
+                        all_extracted_count.fetch_add(slice_count, Ordering::AcqRel) + slice_count;
+                    let batch_count = all_batch_count.fetch_add(1, Ordering::AcqRel) + 1;
+                    Self::report_progress_to(
+                        &monitor,
+                        &db,
+                        &tb,
+                        (extracted_count, total_count),
+                        batch_count,
+                        progress_interval,
+                    );
+
+                    // rows of this batch are already pushed, so a checkpoint after them is safe
+                    let finished = slice_count < batch_size;
+                    let checkpoint = {
+                        let mut slice_progresses = slice_progresses.lock().unwrap();
+                        slice_progresses[i].value = start_value.clone();
+                        slice_progresses[i].finished = finished;
+                        Self::get_slices_checkpoint(&slice_progresses)
+                    };
+                    if checkpoint != ColValue::None {
+                        let item = DtItem {
+                            dt_data: DtData::Commit { xid: String::new() },
+                            position: Self::build_position(&db, &tb, &order_col, &checkpoint),
+                            data_origin_node: String::new(),
+                        };
+                        buffer.push(item).await?;
+                    }
+
+                    if finished {
+                        return Ok(());
+                    }
+                }
+            });
+        }
+
+        // an error in any slice cancels the others
+        while let Some(result) = join_set.join_next().await {
+            if let Err(err) = result.map_err(anyhow::Error::from).and_then(|r| r) {
+                join_set.abort_all();
+                return Err(err);
+            }
+        }
+
+        self.base_extractor.monitor.counters.throttled_ms += throttled_ms.load(Ordering::Acquire);
+        Ok(all_extracted_count.load(Ordering::Acquire))
     }
 
-    fn get_sub_extractor_range(
-        start_value: &ColValue,
-        extractor_index: usize,
-        batch_size: usize,
-    ) -> (ColValue, ColValue) {
-        let i = extractor_index;
-        let v = match start_value {
-            ColValue::Long(v) => *v as i128,
-            ColValue::UnsignedLong(v) => *v as i128,
-            ColValue::LongLong(v) => *v as i128,
-            ColValue::UnsignedLongLong(v) => *v as i128,
-            _ => 0,
+    // slices are (start, end] of order_col with batch_size rows, the end of each slice is
+    // found by offset in the source, then the same range is checksummed in both dbs
+    async fn extract_by_checksum(
+        &mut self,
+        tb_meta: &MysqlTbMeta,
+        order_col: &str,
+        order_col_type: &MysqlColType,
+        resume_value: ColValue,
+    ) -> anyhow::Result<usize> {
+        let target_conn_pool = match &self.checksum_target_conn_pool {
+            Some(conn_pool) => conn_pool.clone(),
+            None => return Ok(0),
         };
+        log_info!(
+            "start checking `{}`.`{}` by checksum, order_col: {}, slice size: {}",
+            self.db,
+            self.tb,
+            order_col,
+            self.batch_size
+        );
 
-        let start = v + i as i128 * batch_size as i128;
-        let end = start + batch_size as i128;
-        match start_value {
-            ColValue::Long(_) => (
-                ColValue::Long(cmp::min(start, i32::MAX as i128) as i32),
-                ColValue::Long(cmp::min(end, i32::MAX as i128) as i32),
-            ),
-            ColValue::UnsignedLong(_) => (
-                ColValue::UnsignedLong(cmp::min(start, u32::MAX as i128) as u32),
-                ColValue::UnsignedLong(cmp::min(end, u32::MAX as i128) as u32),
-            ),
-            ColValue::LongLong(_) => (
-                ColValue::LongLong(cmp::min(start, i64::MAX as i128) as i64),
-                ColValue::LongLong(cmp::min(end, i64::MAX as i128) as i64),
-            ),
-            ColValue::UnsignedLongLong(_) => (
-                ColValue::UnsignedLongLong(cmp::min(start, u64::MAX as i128) as u64),
-                ColValue::UnsignedLongLong(cmp::min(end, u64::MAX as i128) as u64),
-            ),
-            _ => (ColValue::None, ColValue::None),
+        let ignore_cols = self.get_ignore_cols(tb_meta);
+        let cols = SliceChecksumSql::get_cols(&tb_meta.basic.cols, ignore_cols.as_ref());
+        let src_sql = SliceChecksumSql {
+            schema: self.db.clone(),
+            tb: self.tb.clone(),
+            cols: cols.clone(),
+        };
+        let dst_sql = SliceChecksumSql::new_for_target(
+            &self.base_extractor.router,
+            &self.db,
+            &self.tb,
+            &cols,
+        );
+        let dst_order_col = match self
+            .base_extractor
+            .router
+            .get_col_map(&self.db, &self.tb)
+            .and_then(|col_map| col_map.get(order_col))
+        {
+            Some(dst_order_col) => dst_order_col.clone(),
+            None => order_col.to_string(),
+        };
+
+        let mut stats = SliceChecksumStats::default();
+        let mut start_value = resume_value;
+        loop {
+            let end_value = self
+                .get_checksum_slice_end(order_col, order_col_type, &start_value)
+                .await?;
+            let src_checksum = self
+                .query_slice_checksum(
+                    &self.conn_pool,
+                    &src_sql,
+                    order_col,
+                    order_col_type,
+                    &start_value,
+                    &end_value,
+                )
+                .await?;
+            let dst_checksum = self
+                .query_slice_checksum(
+                    &target_conn_pool,
+                    &dst_sql,
+                    &dst_order_col,
+                    order_col_type,
+                    &start_value,
+                    &end_value,
+                )
+                .await?;
+
+            if !stats.add_slice(&src_checksum, &dst_checksum) {
+                log_info!(
+                    "checksum mismatched, `{}`.`{}` slice: ({}, {}], source: {:?}, target: {:?}",
+                    self.db,
+                    self.tb,
+                    start_value.to_string(),
+                    end_value.to_string(),
+                    src_checksum,
+                    dst_checksum
+                );
+                stats.drilled_count += self
+                    .extract_checksum_slice(
+                        tb_meta,
+                        order_col,
+                        order_col_type,
+                        &start_value,
+                        &end_value,
+                    )
+                    .await?;
+            }
+
+            // the last slice has no upper bound
+            if end_value == ColValue::None {
+                break;
+            }
+            self.send_checkpoint_position(order_col, &end_value).await?;
+            start_value = end_value;
         }
+
+        stats.log(&self.db, &self.tb);
+        Ok(stats.row_count)
     }
 
-    fn build_position(db: &str, tb: &str, order_col: &str, order_col_value: &ColValue) -> Position {
-        if let Some(value) = order_col_value.to_option_string() {
-            Position::RdbSnapshot {
-                db_type: DbType::Mysql.to_string(),
-                schema: db.into(),
-                tb: tb.into(),
-                order_col: order_col.into(),
-                value,
+    // the batch_size th row after start_value, None if less rows left
+    async fn get_checksum_slice_end(
+        &self,
+        order_col: &str,
+        order_col_type: &MysqlColType,
+        start_value: &ColValue,
+    ) -> anyhow::Result<ColValue> {
+        let condition =
+            Self::build_checksum_slice_condition(order_col, start_value, &ColValue::None);
+        let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition);
+        let sql = format!(
+            "SELECT `{}` FROM `{}`.`{}` {} {} ORDER BY `{}` ASC LIMIT 1 OFFSET {}",
+            order_col,
+            self.db,
+            self.tb,
+            self.get_index_hint_str(),
+            where_sql,
+            order_col,
+            self.batch_size - 1
+        );
+        let mut query = sqlx::query(&sql);
+        if *start_value != ColValue::None {
+            query = query.bind_col_value(Some(start_value), order_col_type);
+        }
+        match query.fetch_optional(&self.conn_pool).await? {
+            Some(row) => MysqlColValueConvertor::from_query(&row, order_col, order_col_type),
+            None => Ok(ColValue::None),
+        }
+    }
+
+    async fn query_slice_checksum(
+        &self,
+        conn_pool: &Pool<MySql>,
+        checksum_sql: &SliceChecksumSql,
+        order_col: &str,
+        order_col_type: &MysqlColType,
+        start_value: &ColValue,
+        end_value: &ColValue,
+    ) -> anyhow::Result<SliceChecksum> {
+        let condition = Self::build_checksum_slice_condition(order_col, start_value, end_value);
+        // the where condition of [filter] is applied to the target as is
+        let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition);
+        let sql = checksum_sql.build_mysql_sql(&where_sql);
+        let mut query = sqlx::query(&sql);
+        for value in [start_value, end_value] {
+            if *value != ColValue::None {
+                query = query.bind_col_value(Some(value), order_col_type);
             }
-        } else {
-            Position::None
         }
+        let row = query.fetch_one(conn_pool).await?;
+        Ok(SliceChecksum {
+            row_count: row.try_get("row_count")?,
+            checksum: row.try_get("checksum")?,
+        })
     }
 
-    async fn send_checkpoint_position(
+    // push all rows of the slice to be checked row by row
+    async fn extract_checksum_slice(
         &mut self,
+        tb_meta: &MysqlTbMeta,
         order_col: &str,
-        order_col_value: &ColValue,
-    ) -> anyhow::Result<()> {
-        if *order_col_value == ColValue::None {
-            return Ok(());
+        order_col_type: &MysqlColType,
+        start_value: &ColValue,
+        end_value: &ColValue,
+    ) -> anyhow::Result<usize> {
+        let ignore_cols = self.get_ignore_cols(tb_meta);
+        let cols_str = self.build_extract_cols_str(tb_meta)?;
+        let condition = Self::build_checksum_slice_condition(order_col, start_value, end_value);
+        let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition);
+        let sql = format!(
+            "SELECT {} FROM `{}`.`{}` {} {} ORDER BY `{}` ASC",
+            cols_str,
+            self.db,
+            self.tb,
+            self.get_index_hint_str(),
+            where_sql,
+            order_col
+        );
+
+        let mut query = sqlx::query(&sql);
+        for value in [start_value, end_value] {
+            if *value != ColValue::None {
+                query = query.bind_col_value(Some(value), order_col_type);
+            }
+        }
+        let mut extracted_count = 0;
+        let mut rows = query.fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols.as_ref());
+            self.base_extractor
+                .push_row(row_data, Position::None)
+                .await?;
+            extracted_count += 1;
         }
+        Ok(extracted_count)
+    }
 
-        let position = Self::build_position(&self.db, &self.tb, order_col, order_col_value);
-        let commit = DtData::Commit { xid: String::new() };
+    fn build_checksum_slice_condition(
+        order_col: &str,
+        start_value: &ColValue,
+        end_value: &ColValue,
+    ) -> String {
+        let mut conditions = Vec::new();
+        if *start_value != ColValue::None {
+            conditions.push(format!("`{}` > ?", order_col));
+        }
+        if *end_value != ColValue::None {
+            conditions.push(format!("`{}` <= ?", order_col));
+        }
+        conditions.join(" AND ")
+    }
+
+    // rows to be extracted, rows before the resume value are excluded
+    // TABLE_ROWS of innodb is estimated by statistics, 0 if unavailable
+    async fn get_estimated_count(&self) -> anyhow::Result<usize> {
+        let sql = format!(
+            "SELECT TABLE_ROWS AS table_rows FROM information_schema.tables
+            WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME = '{}'",
+            self.db, self.tb
+        );
+        let row = sqlx::query(&sql).fetch_optional(&self.conn_pool).await?;
+        let count = match row {
+            Some(row) => row.try_get::<Option<u64>, _>("table_rows")?.unwrap_or(0),
+            None => 0,
+        };
+        Ok(count as usize)
+    }
+
+    fn report_progress(&self, extracted_count: usize, batch_count: usize) {
+        Self::report_progress_to(
+            &self.base_extractor.monitor.monitor,
+            &self.db,
+            &self.tb,
+            (extracted_count, self.progress_total_count),
+            batch_count,
+            self.slice_progress_interval,
+        );
+    }
+
+    // log the progress every progress_interval batches and keep it in the monitor
+    fn report_progress_to(
+        monitor: &Arc<Mutex<Monitor>>,
+        db: &str,
+        tb: &str,
+        (extracted_count, total_count): (usize, usize),
+        batch_count: usize,
+        progress_interval: usize,
+    ) {
+        if progress_interval == 0 || batch_count % progress_interval != 0 {
+            return;
+        }
+        log_info!(
+            "extracting `{}`.`{}`, progress: {}%, extracted count: {}, total count: {}",
+            db,
+            tb,
+            Monitor::get_snapshot_percent(extracted_count, total_count),
+            extracted_count,
+            total_count
+        );
+        monitor.lock().unwrap().set_snapshot_progress(
+            &format!("{}.{}", db, tb),
+            extracted_count,
+            total_count,
+        );
+    }
+
+    async fn get_total_count(
+        &self,
+        order_col: &str,
+        order_col_type: &MysqlColType,
+        resume_value: &ColValue,
+    ) -> anyhow::Result<usize> {
+        let condition = if *resume_value == ColValue::None {
+            String::new()
+        } else {
+            format!("`{}` > ?", order_col)
+        };
+        let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition);
+        let sql = format!(
+            "SELECT COUNT(*) AS `total_count` FROM `{}`.`{}` {}",
+            self.db, self.tb, where_sql
+        );
+        let mut query = sqlx::query(&sql);
+        if *resume_value != ColValue::None {
+            query = query.bind_col_value(Some(resume_value), order_col_type);
+        }
+        // counted in the same view as the slices are extracted from
+        let mut snapshot_conn = match &self.consistent_snapshot {
+            Some(snapshot) => Some(snapshot.lock_conn().await),
+            None => None,
+        };
+        let row = match snapshot_conn.as_mut() {
+            Some(conn) => query.fetch_one(&mut **conn).await?,
+            None => query.fetch_one(&self.conn_pool).await?,
+        };
+        let total_count: i64 = row.try_get("total_count")?;
+        Ok(total_count as usize)
+    }
+
+    // returns None if there is nothing to extract, the slice count may be less than
+    // parallel_slices if the range is too narrow
+    async fn get_slice_boundaries(
+        &self,
+        order_col: &str,
+        order_col_type: &MysqlColType,
+        resume_value: &ColValue,
+    ) -> anyhow::Result<Option<Vec<ColValue>>> {
+        let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, "");
+        let sql = format!(
+            "SELECT MIN(`{}`) AS `min_value`, MAX(`{}`) AS `max_value` FROM `{}`.`{}` {}",
+            order_col, order_col, self.db, self.tb, where_sql
+        );
+        // bounded in the same view as the slices are extracted from
+        let mut snapshot_conn = match &self.consistent_snapshot {
+            Some(snapshot) => Some(snapshot.lock_conn().await),
+            None => None,
+        };
+        let query = sqlx::query(&sql);
+        let row = match snapshot_conn.as_mut() {
+            Some(conn) => query.fetch_one(&mut **conn).await?,
+            None => query.fetch_one(&self.conn_pool).await?,
+        };
+        let min_value = MysqlColValueConvertor::from_query(&row, "min_value", order_col_type)?;
+        let max_value = MysqlColValueConvertor::from_query(&row, "max_value", order_col_type)?;
+
+        let start_value = if *resume_value == ColValue::None {
+            &min_value
+        } else {
+            resume_value
+        };
+        let (start, max) = match (
+            Self::col_value_to_i128(start_value),
+            Self::col_value_to_i128(&max_value),
+        ) {
+            (Some(start), Some(max)) => (start, max),
+            // empty table
+            _ => return Ok(None),
+        };
+        if start > max || (start == max && *resume_value != ColValue::None) {
+            return Ok(None);
+        }
+        Ok(Some(Self::split_range(
+            start,
+            max,
+            self.parallel_slices,
+            &max_value,
+        )))
+    }
+
+    // binary values can not be split arithmetically, pick the boundaries from the rows by offset
+    // instead, they are read and bound back as raw bytes, so `>` and `<=` follow the binary
+    // collation of ORDER BY and no row is skipped or extracted twice
+    async fn get_sampled_slice_boundaries(
+        &self,
+        order_col: &str,
+        order_col_type: &MysqlColType,
+        resume_value: &ColValue,
+        total_count: usize,
+    ) -> anyhow::Result<Option<Vec<ColValue>>> {
+        let condition = if *resume_value == ColValue::None {
+            String::new()
+        } else {
+            format!("`{}` > ?", order_col)
+        };
+        let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition);
+
+        // sampled in the same view as the slices are extracted from
+        let mut snapshot_conn = match &self.consistent_snapshot {
+            Some(snapshot) => Some(snapshot.lock_conn().await),
+            None => None,
+        };
+        let mut boundaries = Vec::new();
+        for offset in Self::get_sample_offsets(total_count, self.parallel_slices) {
+            let sql = format!(
+                "SELECT `{}` FROM `{}`.`{}` {} ORDER BY `{}` ASC LIMIT 1 OFFSET {}",
+                order_col, self.db, self.tb, where_sql, order_col, offset
+            );
+            let mut query = sqlx::query(&sql);
+            if *resume_value != ColValue::None {
+                query = query.bind_col_value(Some(resume_value), order_col_type);
+            }
+            // rows may be deleted since counted
+            let row = match snapshot_conn.as_mut() {
+                Some(conn) => query.fetch_optional(&mut **conn).await?,
+                None => query.fetch_optional(&self.conn_pool).await?,
+            };
+            if let Some(row) = row {
+                let value = MysqlColValueConvertor::from_query(&row, order_col, order_col_type)?;
+                if boundaries.last() != Some(&value) {
+                    boundaries.push(value);
+                }
+            }
+        }
+        Ok(Some(boundaries))
+    }
+
+    // offsets of the last rows of all slices except the last one
+    fn get_sample_offsets(total_count: usize, slice_count: usize) -> Vec<usize> {
+        let slice_count = cmp::min(slice_count, total_count).max(1);
+        (1..slice_count)
+            .map(|i| total_count * i / slice_count - 1)
+            .collect()
+    }
+
+    // inner boundaries which split [start, max] into at most slice_count ranges
+    fn split_range(start: i128, max: i128, slice_count: usize, like: &ColValue) -> Vec<ColValue> {
+        let slice_count = cmp::min(slice_count as i128, max - start + 1).max(1);
+        let step = (max - start + 1) / slice_count;
+        (1..slice_count)
+            .map(|i| Self::i128_to_col_value(start + step * i - 1, like))
+            .collect()
+    }
+
+    // all rows <= checkpoint are extracted: finished slices as a prefix, then the progress of the
+    // first unfinished slice
+    fn get_slices_checkpoint(slice_progresses: &[SliceProgress]) -> ColValue {
+        let mut checkpoint = ColValue::None;
+        for slice in slice_progresses {
+            if !slice.finished {
+                return slice.value.clone();
+            }
+            checkpoint = if slice.end == ColValue::None {
+                slice.value.clone()
+            } else {
+                slice.end.clone()
+            };
+        }
+        checkpoint
+    }
+
+    fn col_value_to_i128(value: &ColValue) -> Option<i128> {
+        match value {
+            ColValue::Long(v) => Some(*v as i128),
+            ColValue::UnsignedLong(v) => Some(*v as i128),
+            ColValue::LongLong(v) => Some(*v as i128),
+            ColValue::UnsignedLongLong(v) => Some(*v as i128),
+            _ => None,
+        }
+    }
+
+    fn i128_to_col_value(value: i128, like: &ColValue) -> ColValue {
+        match like {
+            ColValue::Long(_) => ColValue::Long(value as i32),
+            ColValue::UnsignedLong(_) => ColValue::UnsignedLong(value as u32),
+            ColValue::LongLong(_) => ColValue::LongLong(value as i64),
+            ColValue::UnsignedLongLong(_) => ColValue::UnsignedLongLong(value as u64),
+            _ => ColValue::None,
+        }
+    }
+
+    // each partition is extracted by batch of order_col with its own position,
+    // so an interrupted extraction resumes every partition from where it stopped
+    async fn extract_by_partitions(
+        &mut self,
+        tb_meta: &MysqlTbMeta,
+        partitions: Vec<String>,
+    ) -> anyhow::Result<usize> {
+        let partition_concurrency = cmp::max(self.partition_concurrency, 1);
+        log_info!(
+            "start extracting data from `{}`.`{}` by partitions, partitions: [{}], partition_concurrency: {}",
+            self.db,
+            self.tb,
+            partitions.join(","),
+            partition_concurrency
+        );
+
+        let order_col = tb_meta.basic.order_col.clone();
+        let order_col_type = match &order_col {
+            Some(order_col) => {
+                self.try_detect_force_index(order_col, tb_meta).await?;
+                Some(tb_meta.get_col_type(order_col)?.clone())
+            }
+            None => {
+                log_warn!(
+                    "`{}`.`{}` has no primary key or NOT NULL unique key, partitions will be extracted without batch and can not be resumed",
+                    self.db,
+                    self.tb
+                );
+                None
+            }
+        };
+
+        let all_extracted_count = Arc::new(AtomicUsize::new(0));
+        let router = Arc::new(self.base_extractor.router.clone());
+        let rate_limiter = self.base_extractor.rate_limiter.clone();
+        let throttled_ms = Arc::new(AtomicUsize::new(0));
+        let ignore_cols = self.get_ignore_cols(tb_meta);
+        let cols_str = self.build_extract_cols_str(tb_meta)?;
+        let index_hint = self.get_index_hint_str();
+        let count_window = cmp::max(self.base_extractor.monitor.count_window, 1);
+
+        for (i, sub_partitions) in partitions.chunks(partition_concurrency).enumerate() {
+            let mut futures = Vec::new();
+            for partition in sub_partitions {
+                // sql_1 for the first batch, sql_2 for batches after the start value
+                let (sql_1, sql_2) = (
+                    self.build_partition_sql(&cols_str, partition, &index_hint, &order_col, false),
+                    self.build_partition_sql(&cols_str, partition, &index_hint, &order_col, true),
+                );
+                let partition_order_col = order_col
+                    .as_ref()
+                    .map(|order_col| Self::get_partition_order_col(partition, order_col));
+                let mut start_value = match (&order_col, &order_col_type) {
+                    (Some(order_col), Some(order_col_type)) => Self::get_partition_resume_value(
+                        &self.resumer,
+                        &self.db,
+                        &self.tb,
+                        partition,
+                        order_col,
+                        order_col_type,
+                    )?,
+                    _ => ColValue::None,
+                };
+
+                let partition_key = format!("{}.{}.{}", self.db, self.tb, partition);
+                let db = self.db.clone();
+                let tb = self.tb.clone();
+                let order_col = order_col.clone();
+                let order_col_type = order_col_type.clone();
+                let batch_size = self.batch_size;
+                let buffer = self.base_extractor.buffer.clone();
+                let router = router.clone();
+                let rate_limiter = rate_limiter.clone();
+                let throttled_ms = throttled_ms.clone();
+                let conn_pool = self.conn_pool.clone();
+                let monitor = self.base_extractor.monitor.monitor.clone();
+                let tb_meta = tb_meta.clone();
+                let ignore_cols = ignore_cols.clone();
+                let all_extracted_count = all_extracted_count.clone();
+
+                let future: JoinHandle<anyhow::Result<()>> = tokio::spawn(async move {
+                    let mut partition_count = 0;
+                    loop {
+                        let query = match &order_col_type {
+                            Some(order_col_type) if start_value != ColValue::None => {
+                                sqlx::query(&sql_2)
+                                    .bind_col_value(Some(&start_value), order_col_type)
+                            }
+                            _ => sqlx::query(&sql_1),
+                        };
+                        let mut rows = query.fetch(&conn_pool);
+                        let mut batch_count = 0;
+                        while let Some(row) = rows.try_next().await? {
+                            let mut position = Position::None;
+                            if let (
+                                Some(order_col),
+                                Some(order_col_type),
+                                Some(partition_order_col),
+                            ) = (&order_col, &order_col_type, &partition_order_col)
+                            {
+                                start_value = MysqlColValueConvertor::from_query(
+                                    &row,
+                                    order_col,
+                                    order_col_type,
+                                )?;
+                                position = Self::build_position(
+                                    &db,
+                                    &tb,
+                                    partition_order_col,
+                                    &start_value,
+                                );
+                            }
+                            let row_data =
+                                RowData::from_mysql_row(&row, &tb_meta, &ignore_cols.as_ref());
+                            Self::push_row(
+                                &buffer,
+                                &router,
+                                &rate_limiter,
+                                &throttled_ms,
+                                row_data,
+                                position,
+                            )
+                            .await?;
+                            batch_count += 1;
+                            partition_count += 1;
+                            if partition_count % count_window == 0 {
+                                monitor
+                                    .lock()
+                                    .unwrap()
+                                    .add_partition_counter(&partition_key, count_window);
+                            }
+                        }
+
+                        // all data of the partition extracted
+                        if order_col_type.is_none() || batch_count < batch_size {
+                            break;
+                        }
+                    }
+
+                    monitor
+                        .lock()
+                        .unwrap()
+                        .add_partition_counter(&partition_key, partition_count % count_window);
+                    all_extracted_count.fetch_add(partition_count, Ordering::Release);
+                    log_info!(
+                        "end extracting partition: {}, count: {}",
+                        partition_key,
+                        partition_count
+                    );
+                    Ok(())
+                });
+                futures.push(future);
+            }
+
+            for future in futures {
+                future.await??;
+            }
+            self.report_progress(all_extracted_count.load(Ordering::Acquire), i + 1);
+        }
+
+        self.base_extractor.monitor.counters.throttled_ms += throttled_ms.load(Ordering::Acquire);
+        Ok(all_extracted_count.load(Ordering::Acquire))
+    }
+
+    // the partition clause goes before index hints: FROM tb PARTITION (p0) FORCE INDEX (...)
+    fn build_partition_sql(
+        &self,
+        cols_str: &str,
+        partition: &str,
+        index_hint: &str,
+        order_col: &Option<String>,
+        has_start_value: bool,
+    ) -> String {
+        let order_col = match order_col {
+            Some(order_col) => order_col,
+            None => {
+                let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, "");
+                return format!(
+                    "SELECT {} FROM `{}`.`{}` PARTITION (`{}`) {} {}",
+                    cols_str, self.db, self.tb, partition, index_hint, where_sql
+                );
+            }
+        };
+
+        let condition = if has_start_value {
+            format!("`{}` > ?", order_col)
+        } else {
+            String::new()
+        };
+        let where_sql = BaseExtractor::get_where_sql(&self.filter, &self.db, &self.tb, &condition);
+        format!(
+            "SELECT {} FROM `{}`.`{}` PARTITION (`{}`) {} {} ORDER BY `{}` ASC LIMIT {}",
+            cols_str,
+            self.db,
+            self.tb,
+            partition,
+            index_hint,
+            where_sql,
+            order_col,
+            self.batch_size
+        )
+    }
+
+    fn get_partition_resume_value(
+        resumer: &SnapshotResumer,
+        db: &str,
+        tb: &str,
+        partition: &str,
+        order_col: &str,
+        order_col_type: &MysqlColType,
+    ) -> anyhow::Result<ColValue> {
+        let partition_order_col = Self::get_partition_order_col(partition, order_col);
+        match resumer.get_resume_value(db, tb, &partition_order_col, false) {
+            Some(value) => {
+                log_info!(
+                    "resume extracting partition: {} of `{}`.`{}` from {}: {}",
+                    partition,
+                    db,
+                    tb,
+                    order_col,
+                    value
+                );
+                MysqlColValueConvertor::from_str(order_col_type, &value)
+            }
+            None => Ok(ColValue::None),
+        }
+    }
+
+    // order_col of partition positions, a json object to tell it from the order_col of
+    // the whole table and the json array of composite order cols
+    fn get_partition_order_col(partition: &str, order_col: &str) -> String {
+        json!({"partition": partition, "order_col": order_col}).to_string()
+    }
+
+    async fn get_partitions(&self) -> anyhow::Result<Vec<String>> {
+        // a partition has multiple rows if it has subpartitions
+        let sql = format!(
+            "SELECT PARTITION_NAME AS partition_name FROM information_schema.partitions
+            WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME = '{}' AND PARTITION_NAME IS NOT NULL
+            GROUP BY PARTITION_NAME
+            ORDER BY MIN(PARTITION_ORDINAL_POSITION)",
+            self.db, self.tb
+        );
+
+        let mut partitions = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let partition: String = row.try_get("partition_name")?;
+            partitions.push(partition);
+        }
+        Ok(partitions)
+    }
+
+    // rows extracted by spawned tasks bypass BaseExtractor::push_dt_data, so they are
+    // throttled here, the time slept is added to throttled_ms
+    pub async fn push_row(
+        buffer: &Arc<DtQueue>,
+        router: &Arc<RdbRouter>,
+        rate_limiter: &Option<Arc<ExtractRateLimiter>>,
+        throttled_ms: &AtomicUsize,
+        row_data: RowData,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        for row_data in router.route_dml(row_data)? {
+            if let Some(rate_limiter) = rate_limiter {
+                let throttled = rate_limiter.acquire(1, row_data.data_size as u64).await;
+                throttled_ms.fetch_add(throttled.as_millis() as usize, Ordering::Relaxed);
+            }
+            let dt_data = DtData::Dml { row_data };
+            let item = DtItem {
+                dt_data,
+                position: position.clone(),
+                data_origin_node: String::new(),
+            };
+            log_debug_row!("extracted item: {}", json!(item));
+            buffer.push(item).await?;
+        }
+        Ok(())
+    }
+
+    fn get_sub_extractor_range(
+        start_value: &ColValue,
+        extractor_index: usize,
+        batch_size: usize,
+    ) -> (ColValue, ColValue) {
+        let i = extractor_index;
+        let v = match start_value {
+            ColValue::Long(v) => *v as i128,
+            ColValue::UnsignedLong(v) => *v as i128,
+            ColValue::LongLong(v) => *v as i128,
+            ColValue::UnsignedLongLong(v) => *v as i128,
+            _ => 0,
+        };
+
+        let start = v + i as i128 * batch_size as i128;
+        let end = start + batch_size as i128;
+        match start_value {
+            ColValue::Long(_) => (
+                ColValue::Long(cmp::min(start, i32::MAX as i128) as i32),
+                ColValue::Long(cmp::min(end, i32::MAX as i128) as i32),
+            ),
+            ColValue::UnsignedLong(_) => (
+                ColValue::UnsignedLong(cmp::min(start, u32::MAX as i128) as u32),
+                ColValue::UnsignedLong(cmp::min(end, u32::MAX as i128) as u32),
+            ),
+            ColValue::LongLong(_) => (
+                ColValue::LongLong(cmp::min(start, i64::MAX as i128) as i64),
+                ColValue::LongLong(cmp::min(end, i64::MAX as i128) as i64),
+            ),
+            ColValue::UnsignedLongLong(_) => (
+                ColValue::UnsignedLongLong(cmp::min(start, u64::MAX as i128) as u64),
+                ColValue::UnsignedLongLong(cmp::min(end, u64::MAX as i128) as u64),
+            ),
+            _ => (ColValue::None, ColValue::None),
+        }
+    }
+
+    // newest rows first if snapshot_desc
+    fn get_order_and_operator(&self) -> (&'static str, &'static str) {
+        if self.snapshot_desc {
+            ("DESC", "<")
+        } else {
+            ("ASC", ">")
+        }
+    }
+
+    // compare all order_cols as a row value, rows sharing leading values across
+    // batch edges are neither skipped nor extracted twice.
+    // E.g. (`a`, `b`) > (?, ?) and `a` ASC, `b` ASC
+    fn build_composite_batch_condition(
+        order_cols: &[String],
+        order: &str,
+        operator: &str,
+    ) -> (String, String) {
+        let escaped_order_cols: Vec<String> =
+            order_cols.iter().map(|col| format!("`{}`", col)).collect();
+        let condition = format!(
+            "({}) {} ({})",
+            escaped_order_cols.join(", "),
+            operator,
+            vec!["?"; order_cols.len()].join(", ")
+        );
+        let order_by_str = escaped_order_cols
+            .iter()
+            .map(|col| format!("{} {}", col, order))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (condition, order_by_str)
+    }
+
+    // NULL is not comparable in row values, so only primary keys are used
+    fn get_composite_order_cols(tb_meta: &MysqlTbMeta) -> Option<Vec<String>> {
+        tb_meta
+            .basic
+            .key_map
+            .get("primary")
+            .filter(|cols| cols.len() > 1)
+            .cloned()
+    }
+
+    fn build_position(db: &str, tb: &str, order_col: &str, order_col_value: &ColValue) -> Position {
+        if let Some(value) = MysqlColValueConvertor::to_position_str(order_col_value) {
+            Position::RdbSnapshot {
+                db_type: DbType::Mysql.to_string(),
+                schema: db.into(),
+                tb: tb.into(),
+                order_col: order_col.into(),
+                value,
+            }
+        } else {
+            Position::None
+        }
+    }
+
+    // order_col and value are json arrays for composite order cols
+    fn build_composite_position(
+        db: &str,
+        tb: &str,
+        order_cols: &[String],
+        order_col_values: &[ColValue],
+    ) -> Position {
+        let mut values = Vec::new();
+        for order_col_value in order_col_values {
+            match MysqlColValueConvertor::to_position_str(order_col_value) {
+                Some(value) => values.push(value),
+                None => return Position::None,
+            }
+        }
+        Position::RdbSnapshot {
+            db_type: DbType::Mysql.to_string(),
+            schema: db.into(),
+            tb: tb.into(),
+            order_col: json!(order_cols).to_string(),
+            value: json!(values).to_string(),
+        }
+    }
+
+    async fn send_checkpoint_position(
+        &mut self,
+        order_col: &str,
+        order_col_value: &ColValue,
+    ) -> anyhow::Result<()> {
+        if *order_col_value == ColValue::None {
+            return Ok(());
+        }
+
+        let position = Self::build_position(&self.db, &self.tb, order_col, order_col_value);
+        let commit = DtData::Commit { xid: String::new() };
         self.base_extractor.push_dt_data(commit, position).await
     }
 
+    async fn try_detect_force_index(
+        &mut self,
+        order_col: &str,
+        tb_meta: &MysqlTbMeta,
+    ) -> anyhow::Result<()> {
+        let (_, index_hint) = Self::split_query_hint(self.query_hint.as_deref());
+        if self.force_index.is_none() && self.auto_detect_force_index && index_hint.is_none() {
+            self.detect_force_index(order_col, &tb_meta.basic.key_map)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn detect_force_index(
+        &mut self,
+        order_col: &str,
+        key_map: &HashMap<String, Vec<String>>,
+    ) -> anyhow::Result<()> {
+        let sql = format!(
+            "EXPLAIN SELECT * FROM `{}`.`{}` ORDER BY `{}` LIMIT 1",
+            self.db, self.tb, order_col
+        );
+        let mut extras = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let extra: Option<String> = row.try_get("Extra")?;
+            extras.push(extra.unwrap_or_default());
+        }
+
+        if !Self::explain_has_filesort(&extras) {
+            return Ok(());
+        }
+        match Self::get_order_col_index(order_col, key_map) {
+            Some(index) => {
+                log_info!(
+                    "filesort found in plan of `{}`.`{}` ORDER BY `{}`, use FORCE INDEX (`{}`)",
+                    self.db,
+                    self.tb,
+                    order_col,
+                    index
+                );
+                self.force_index = Some(index);
+            }
+            None => log_warn!(
+                "filesort found in plan of `{}`.`{}` ORDER BY `{}`, but no key starts with it to force",
+                self.db,
+                self.tb,
+                order_col
+            ),
+        }
+        Ok(())
+    }
+
+    // the key starting with order_col, which serves ORDER BY order_col without filesort,
+    // primary key first, then the key with the fewest cols
+    fn get_order_col_index(
+        order_col: &str,
+        key_map: &HashMap<String, Vec<String>>,
+    ) -> Option<String> {
+        key_map
+            .iter()
+            .filter(|(_, cols)| {
+                cols.first()
+                    .is_some_and(|col| col.eq_ignore_ascii_case(order_col))
+            })
+            .min_by_key(|(name, cols)| (name.as_str() != "primary", cols.len(), name.as_str()))
+            // key names are lowercased in key_map
+            .map(|(name, _)| {
+                if name == "primary" {
+                    "PRIMARY".to_string()
+                } else {
+                    name.clone()
+                }
+            })
+    }
+
+    // extras: the Extra column of each EXPLAIN row, e.g. "Using index; Using filesort"
+    fn explain_has_filesort(extras: &[String]) -> bool {
+        extras.iter().any(|extra| {
+            extra
+                .split(';')
+                .any(|i| i.trim().eq_ignore_ascii_case("Using filesort"))
+        })
+    }
+
+    // placed between the table name and WHERE
+    fn get_index_hint_str(&self) -> String {
+        if let (_, Some(index_hint)) = Self::split_query_hint(self.query_hint.as_deref()) {
+            return index_hint.to_string();
+        }
+        match &self.force_index {
+            Some(index) if !index.is_empty() => format!("FORCE INDEX (`{}`)", index),
+            _ => String::new(),
+        }
+    }
+
+    // mysql ignores optimizer hints unless they follow SELECT, so they are returned as
+    // (optimizer hint, None), other hints as (None, index hint), both are kept verbatim
+    fn split_query_hint(query_hint: Option<&str>) -> (Option<&str>, Option<&str>) {
+        match query_hint {
+            Some(hint) if hint.trim().is_empty() => (None, None),
+            Some(hint) if hint.trim_start().starts_with("/*+") => (Some(hint), None),
+            Some(hint) => (None, Some(hint)),
+            None => (None, None),
+        }
+    }
+
+    // the extracted cols always follow SELECT, so an optimizer hint is put before them
     fn build_extract_cols_str(&self, tb_meta: &MysqlTbMeta) -> anyhow::Result<String> {
-        let ignore_cols = self.filter.get_ignore_cols(&self.db, &self.tb);
-        let query_builder = RdbQueryBuilder::new_for_mysql(tb_meta, ignore_cols);
-        query_builder.build_extract_cols_str()
+        let ignore_cols = self.get_ignore_cols(tb_meta);
+        let query_builder = RdbQueryBuilder::new_for_mysql(tb_meta, ignore_cols.as_ref());
+        let cols_str = query_builder.build_extract_cols_str()?;
+        match Self::split_query_hint(self.query_hint.as_deref()) {
+            (Some(optimizer_hint), _) => Ok(format!("{} {}", optimizer_hint, cols_str)),
+            _ => Ok(cols_str),
+        }
+    }
+
+    fn get_ignore_cols(&self, tb_meta: &MysqlTbMeta) -> Option<HashSet<String>> {
+        Self::build_ignore_cols(
+            tb_meta,
+            self.filter.get_ignore_cols(&self.db, &self.tb),
+            self.filter.get_include_cols(&self.db, &self.tb),
+        )
+    }
+
+    // columns not in include_cols are ignored too, but order_col and primary key
+    // columns are always extracted since batch / slice positions are built from them
+    fn build_ignore_cols(
+        tb_meta: &MysqlTbMeta,
+        ignore_cols: Option<&HashSet<String>>,
+        include_cols: Option<&HashSet<String>>,
+    ) -> Option<HashSet<String>> {
+        let mut results = ignore_cols.cloned().unwrap_or_default();
+        if let Some(include_cols) = include_cols {
+            for col in tb_meta.basic.cols.iter() {
+                if !include_cols.contains(col) {
+                    results.insert(col.clone());
+                }
+            }
+        }
+
+        if let Some(order_col) = &tb_meta.basic.order_col {
+            results.remove(order_col);
+        }
+        if let Some(key_cols) = tb_meta.basic.key_map.get("primary") {
+            for col in key_cols.iter() {
+                results.remove(col);
+            }
+        }
+
+        if results.is_empty() {
+            None
+        } else {
+            Some(results)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Instant,
+    };
+
+    use dt_common::meta::{
+        col_value::ColValue,
+        dt_queue::DtQueue,
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+        position::Position,
+        rdb_tb_meta::RdbTbMeta,
+        row_data::RowData,
+        row_type::RowType,
+    };
+
+    use super::{MysqlSnapshotExtractor, SliceProgress};
+    use crate::{
+        extractor::{
+            extract_rate_limiter::ExtractRateLimiter, resumer::snapshot_resumer::SnapshotResumer,
+        },
+        rdb_router::RdbRouter,
+    };
+
+    #[tokio::test]
+    async fn test_push_row_throttled() {
+        let buffer = Arc::new(DtQueue::new(100, 0));
+        let router = Arc::new(RdbRouter::default());
+        let rate_limiter = ExtractRateLimiter::new(10, 0).map(Arc::new);
+        let throttled_ms = AtomicUsize::new(0);
+
+        // 10 rows are taken from the full bucket, 5 more wait for 0.5 second
+        let start_time = Instant::now();
+        for i in 0..15 {
+            let after = HashMap::from([("id".to_string(), ColValue::Long(i))]);
+            let row_data = RowData::new(
+                "db_1".into(),
+                "tb_1".into(),
+                RowType::Insert,
+                None,
+                Some(after),
+            );
+            MysqlSnapshotExtractor::push_row(
+                &buffer,
+                &router,
+                &rate_limiter,
+                &throttled_ms,
+                row_data,
+                Position::None,
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(buffer.len(), 15);
+        assert!(start_time.elapsed().as_millis() >= 400);
+        assert!(throttled_ms.load(Ordering::Acquire) >= 400);
+
+        // not throttled without limiter
+        let throttled_ms = AtomicUsize::new(0);
+        let row_data = RowData::new("db_1".into(), "tb_1".into(), RowType::Insert, None, None);
+        MysqlSnapshotExtractor::push_row(
+            &buffer,
+            &router,
+            &None,
+            &throttled_ms,
+            row_data,
+            Position::None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(throttled_ms.load(Ordering::Acquire), 0);
+    }
+
+    #[test]
+    fn test_get_order_col_index() {
+        let key_map: HashMap<String, Vec<String>> = HashMap::from([
+            ("primary".into(), vec!["id".into()]),
+            (
+                "uk_code_region".into(),
+                vec!["code".into(), "region".into()],
+            ),
+            ("uk_code".into(), vec!["code".into()]),
+            (
+                "uk_region_code".into(),
+                vec!["region".into(), "code".into()],
+            ),
+        ]);
+        assert_eq!(
+            MysqlSnapshotExtractor::get_order_col_index("id", &key_map),
+            Some("PRIMARY".to_string())
+        );
+        // a unique key is forced when order_col is not the primary key
+        assert_eq!(
+            MysqlSnapshotExtractor::get_order_col_index("code", &key_map),
+            Some("uk_code".to_string())
+        );
+        assert_eq!(
+            MysqlSnapshotExtractor::get_order_col_index("region", &key_map),
+            Some("uk_region_code".to_string())
+        );
+        assert_eq!(
+            MysqlSnapshotExtractor::get_order_col_index("name", &key_map),
+            None
+        );
+    }
+
+    #[test]
+    fn test_explain_has_filesort() {
+        let check = |extras: Vec<&str>| {
+            let extras: Vec<String> = extras.into_iter().map(String::from).collect();
+            MysqlSnapshotExtractor::explain_has_filesort(&extras)
+        };
+
+        assert!(check(vec!["Using filesort"]));
+        assert!(check(vec!["Using where; Using filesort"]));
+        assert!(check(vec![
+            "Using index",
+            "Using temporary; Using filesort"
+        ]));
+        assert!(!check(vec![""]));
+        assert!(!check(vec!["Using index"]));
+        assert!(!check(vec!["Using where; Using index"]));
+        assert!(!check(vec![]));
+    }
+
+    #[test]
+    fn test_split_range() {
+        let like = ColValue::LongLong(0);
+        let split = |start, max, slice_count| {
+            MysqlSnapshotExtractor::split_range(start, max, slice_count, &like)
+        };
+
+        assert_eq!(
+            split(1, 100, 4),
+            vec![
+                ColValue::LongLong(25),
+                ColValue::LongLong(50),
+                ColValue::LongLong(75)
+            ]
+        );
+        // range narrower than slice count
+        assert_eq!(split(1, 2, 4), vec![ColValue::LongLong(1)]);
+        assert!(split(5, 5, 4).is_empty());
+        assert!(split(1, 100, 1).is_empty());
+    }
+
+    #[test]
+    fn test_get_sample_offsets() {
+        let offsets = MysqlSnapshotExtractor::get_sample_offsets;
+        assert_eq!(offsets(100, 4), vec![24, 49, 74]);
+        assert_eq!(offsets(10, 3), vec![2, 5]);
+        // fewer rows than slices
+        assert_eq!(offsets(2, 4), vec![0]);
+        assert!(offsets(1, 4).is_empty());
+        assert!(offsets(100, 1).is_empty());
+    }
+
+    #[test]
+    fn test_get_slices_checkpoint() {
+        let slice = |value: i64, end: Option<i64>, finished| SliceProgress {
+            value: ColValue::LongLong(value),
+            end: end.map_or(ColValue::None, ColValue::LongLong),
+            finished,
+        };
+        let checkpoint = MysqlSnapshotExtractor::get_slices_checkpoint;
+
+        // the first slice is in progress
+        let slices = vec![slice(10, Some(25), false), slice(40, Some(50), true)];
+        assert_eq!(checkpoint(&slices), ColValue::LongLong(10));
+        // finished slices as a prefix
+        let slices = vec![
+            slice(20, Some(25), true),
+            slice(30, Some(50), false),
+            slice(70, None, false),
+        ];
+        assert_eq!(checkpoint(&slices), ColValue::LongLong(30));
+        // all finished
+        let slices = vec![slice(25, Some(25), true), slice(99, None, true)];
+        assert_eq!(checkpoint(&slices), ColValue::LongLong(99));
+    }
+
+    #[test]
+    fn test_split_query_hint() {
+        let split = MysqlSnapshotExtractor::split_query_hint;
+        assert_eq!(
+            split(Some("FORCE INDEX (idx_created)")),
+            (None, Some("FORCE INDEX (idx_created)"))
+        );
+        assert_eq!(
+            split(Some(" /*+ INDEX(tb_1 idx_created) */")),
+            (Some(" /*+ INDEX(tb_1 idx_created) */"), None)
+        );
+        // a plain comment is not an optimizer hint
+        assert_eq!(split(Some("/* tag */")), (None, Some("/* tag */")));
+        assert_eq!(split(Some("  ")), (None, None));
+        assert_eq!(split(None), (None, None));
+    }
+
+    #[test]
+    fn test_build_checksum_slice_condition() {
+        let condition = MysqlSnapshotExtractor::build_checksum_slice_condition;
+        assert_eq!(
+            condition("id", &ColValue::LongLong(1), &ColValue::LongLong(200)),
+            "`id` > ? AND `id` <= ?"
+        );
+        // the first slice
+        assert_eq!(
+            condition("id", &ColValue::None, &ColValue::LongLong(200)),
+            "`id` <= ?"
+        );
+        // the last slice
+        assert_eq!(
+            condition("id", &ColValue::LongLong(200), &ColValue::None),
+            "`id` > ?"
+        );
+        assert_eq!(condition("id", &ColValue::None, &ColValue::None), "");
+    }
+
+    #[test]
+    fn test_build_composite_position() {
+        let order_cols = vec!["tenant_id".to_string(), "id".to_string()];
+        let position = MysqlSnapshotExtractor::build_composite_position(
+            "db_1",
+            "tb_1",
+            &order_cols,
+            &[ColValue::String("t\"1".into()), ColValue::LongLong(10)],
+        );
+        assert_eq!(
+            position.to_string(),
+            r#"{"type":"RdbSnapshot","db_type":"mysql","schema":"db_1","tb":"tb_1","order_col":"[\"tenant_id\",\"id\"]","value":"[\"t\\\"1\",\"10\"]"}"#
+        );
+
+        let position = MysqlSnapshotExtractor::build_composite_position(
+            "db_1",
+            "tb_1",
+            &order_cols,
+            &[ColValue::None, ColValue::None],
+        );
+        assert_eq!(position.to_string(), r#"{"type":"None"}"#);
+    }
+
+    #[test]
+    fn test_composite_batch_with_duplicate_leading_values() {
+        let order_cols = vec!["tenant_id".to_string(), "id".to_string()];
+        let batch_size = 3;
+        // tenant 1 and tenant 3 span several batch edges
+        let mut rows: Vec<(i32, i32)> = (1..=7).map(|id| (1, id)).collect();
+        rows.extend([(2, 1), (2, 2)]);
+        rows.extend((1..=5).map(|id| (3, id)));
+
+        for (order, operator) in [("ASC", ">"), ("DESC", "<")] {
+            let (condition, order_by) = MysqlSnapshotExtractor::build_composite_batch_condition(
+                &order_cols,
+                order,
+                operator,
+            );
+            assert_eq!(
+                condition,
+                format!("(`tenant_id`, `id`) {} (?, ?)", operator)
+            );
+            assert_eq!(order_by, format!("`tenant_id` {0}, `id` {0}", order));
+
+            // tuples compare the same way as row values in mysql
+            let mut sorted = rows.clone();
+            sorted.sort();
+            if order == "DESC" {
+                sorted.reverse();
+            }
+
+            // extract by batch as extract_by_composite_batch does
+            let mut extracted = Vec::new();
+            let mut start_values: Option<(i32, i32)> = None;
+            loop {
+                let batch: Vec<(i32, i32)> = sorted
+                    .iter()
+                    .filter(|row| match start_values {
+                        Some(start) if operator == ">" => **row > start,
+                        Some(start) => **row < start,
+                        None => true,
+                    })
+                    .take(batch_size)
+                    .cloned()
+                    .collect();
+                if let Some(last) = batch.last() {
+                    start_values = Some(*last);
+                }
+                extracted.extend(batch.iter().cloned());
+                if batch.len() < batch_size {
+                    break;
+                }
+            }
+            // no row skipped or extracted twice
+            assert_eq!(extracted, sorted);
+        }
+    }
+
+    #[test]
+    fn test_partition_resume() {
+        let order_col_type = MysqlColType::Int { unsigned: false };
+        let partition_order_col = MysqlSnapshotExtractor::get_partition_order_col("p0", "id");
+        assert_eq!(
+            partition_order_col,
+            r#"{"order_col":"id","partition":"p0"}"#
+        );
+
+        // the position of the last row of p0 pushed before the task was interrupted
+        let position = MysqlSnapshotExtractor::build_position(
+            "db_1",
+            "tb_1",
+            &partition_order_col,
+            &ColValue::Long(100),
+        );
+        let log = format!(
+            "2024-03-29 07:02:24.463776 | current_position | {}",
+            position
+        );
+        let mut resumer = SnapshotResumer::default();
+        resumer.load_stored_position(Position::from_log(&log));
+
+        let get_resume_value = |partition: &str| {
+            MysqlSnapshotExtractor::get_partition_resume_value(
+                &resumer,
+                "db_1",
+                "tb_1",
+                partition,
+                "id",
+                &order_col_type,
+            )
+            .unwrap()
+        };
+        // p0 resumes after 100, p1 was not started and is extracted from the beginning
+        assert_eq!(get_resume_value("p0"), ColValue::Long(100));
+        assert_eq!(get_resume_value("p1"), ColValue::None);
+        // positions of the whole table are not mistaken for those of partitions
+        assert_eq!(resumer.get_resume_value("db_1", "tb_1", "id", false), None);
+    }
+
+    #[test]
+    fn test_build_ignore_cols() {
+        let cols: Vec<String> = ["id", "name", "content", "remark"]
+            .iter()
+            .map(|i| i.to_string())
+            .collect();
+        let tb_meta = MysqlTbMeta {
+            basic: RdbTbMeta {
+                cols,
+                key_map: HashMap::from([("primary".to_string(), vec!["id".to_string()])]),
+                order_col: Some("id".into()),
+                ..Default::default()
+            },
+            col_type_map: HashMap::new(),
+            auto_increment_col: None,
+            generated_cols: HashSet::new(),
+        };
+        let to_set =
+            |cols: &[&str]| -> HashSet<String> { cols.iter().map(|i| i.to_string()).collect() };
+
+        assert_eq!(
+            MysqlSnapshotExtractor::build_ignore_cols(&tb_meta, None, None),
+            None
+        );
+
+        // order_col is extracted even if excluded
+        let ignore_cols = to_set(&["id", "content"]);
+        assert_eq!(
+            MysqlSnapshotExtractor::build_ignore_cols(&tb_meta, Some(&ignore_cols), None),
+            Some(to_set(&["content"]))
+        );
+
+        let include_cols = to_set(&["name"]);
+        assert_eq!(
+            MysqlSnapshotExtractor::build_ignore_cols(&tb_meta, None, Some(&include_cols)),
+            Some(to_set(&["content", "remark"]))
+        );
+
+        // both include_cols and ignore_cols are applied
+        let include_cols = to_set(&["name", "content"]);
+        assert_eq!(
+            MysqlSnapshotExtractor::build_ignore_cols(
+                &tb_meta,
+                Some(&ignore_cols),
+                Some(&include_cols)
+            ),
+            Some(to_set(&["content", "remark"]))
+        );
     }
 }
@@ -19,6 +19,9 @@ pub struct MysqlStructExtractor {
     pub conn_pool: Pool<MySql>,
     pub db: String,
     pub filter: RdbFilter,
+    // push indexes and constraints after all tables, so foreign keys do not fail
+    // on tables not created yet
+    pub split_indexes: bool,
 }
 
 #[async_trait]
@@ -50,9 +53,21 @@ impl MysqlStructExtractor {
             .await?;
 
         // tables
+        let mut indexes = Vec::new();
+        let mut constraints = Vec::new();
         for table_statement in fetcher.get_create_table_statements("").await? {
-            self.push_dt_data(StructStatement::MysqlCreateTable(table_statement))
-                .await?;
+            let mut statement = StructStatement::MysqlCreateTable(table_statement);
+            if self.split_indexes {
+                let (tb_indexes, tb_constraints) = statement.split();
+                indexes.extend(tb_indexes);
+                constraints.extend(tb_constraints);
+            }
+            self.push_dt_data(statement).await?;
+        }
+
+        // indexes and constraints
+        for statement in indexes.into_iter().chain(constraints) {
+            self.push_dt_data(statement).await?;
         }
         Ok(())
     }
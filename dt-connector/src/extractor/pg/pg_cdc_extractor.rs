@@ -446,9 +446,8 @@ impl PgCdcExtractor {
             for ddl_data in ddl_data.split_to_multi() {
                 // invalidate metadata cache
                 self.meta_manager.invalidate_cache_by_ddl_data(&ddl_data);
-                let (schema, tb) = ddl_data.get_schema_tb();
 
-                if !self.filter.filter_ddl(&schema, &tb, &ddl_data.ddl_type) {
+                if !self.filter.filter_ddl_data(&ddl_data) {
                     self.base_extractor
                         .push_ddl(ddl_data, position.clone())
                         .await?;
@@ -523,6 +522,10 @@ impl PgCdcExtractor {
             },
             oid,
             col_type_map: HashMap::new(),
+            is_unlogged: false,
+            col_collations: HashMap::new(),
+            generated_cols: HashMap::new(),
+            exclude_constraints: Vec::new(),
         }
     }
 
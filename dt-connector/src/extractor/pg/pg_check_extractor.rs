@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
 
@@ -34,6 +34,8 @@ pub struct PgCheckExtractor {
     pub filter: RdbFilter,
     pub check_log_dir: String,
     pub batch_size: usize,
+    // delete rows of check logs from the target if they are not found in the source
+    pub delete_extra: bool,
 }
 
 #[async_trait]
@@ -71,9 +73,15 @@ impl BatchCheckExtractor for PgCheckExtractor {
         };
         let query = query_builder.create_pg_query(&query_info);
 
+        let mut src_keys = HashSet::new();
         let mut rows = query.fetch(&self.conn_pool);
         while let Some(row) = rows.try_next().await.unwrap() {
             let mut row_data = RowData::from_pg_row(&row, &tb_meta, &ignore_cols);
+            if self.delete_extra {
+                if let Some(key) = BaseCheckExtractor::get_loose_key(&row_data, &tb_meta.basic) {
+                    src_keys.insert(key);
+                }
+            }
 
             if log_type == &LogType::Diff {
                 row_data.row_type = RowType::Update;
@@ -85,6 +93,19 @@ impl BatchCheckExtractor for PgCheckExtractor {
                 .await?;
         }
 
+        if self.delete_extra {
+            let delete_row_datas = BaseCheckExtractor::build_delete_row_datas(
+                &check_row_datas,
+                &src_keys,
+                &tb_meta.basic,
+            );
+            for row_data in delete_row_datas {
+                self.base_extractor
+                    .push_row(row_data, Position::None)
+                    .await?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -2,13 +2,14 @@ use async_trait::async_trait;
 use dt_common::rdb_filter::RdbFilter;
 use futures::TryStreamExt;
 
-use sqlx::{Pool, Postgres};
+use sqlx::{Pool, Postgres, Row, Transaction};
 
-use dt_common::{config::config_enums::DbType, log_info};
+use dt_common::{config::config_enums::DbType, log_info, log_warn};
 
 use dt_common::meta::{
     adaptor::{pg_col_value_convertor::PgColValueConvertor, sqlx_ext::SqlxPgExt},
     col_value::ColValue,
+    dt_data::DtData,
     pg::{pg_col_type::PgColType, pg_meta_manager::PgMetaManager, pg_tb_meta::PgTbMeta},
     position::Position,
     row_data::RowData,
@@ -16,11 +17,18 @@ use dt_common::meta::{
 
 use crate::close_conn_pool;
 use crate::{
-    extractor::{base_extractor::BaseExtractor, resumer::snapshot_resumer::SnapshotResumer},
+    extractor::{
+        base_extractor::BaseExtractor,
+        resumer::snapshot_resumer::SnapshotResumer,
+        slice_checksum::{SliceChecksum, SliceChecksumSql, SliceChecksumStats},
+    },
     rdb_query_builder::RdbQueryBuilder,
     Extractor,
 };
 
+// rows of a heap block if the table has no statistics
+const CTID_DEFAULT_ROWS_PER_BLOCK: f64 = 100.0;
+
 pub struct PgSnapshotExtractor {
     pub base_extractor: BaseExtractor,
     pub conn_pool: Pool<Postgres>,
@@ -31,6 +39,13 @@ pub struct PgSnapshotExtractor {
     pub sample_interval: usize,
     pub schema: String,
     pub tb: String,
+    // exported by pg_export_snapshot(), tables extracted by multiple sessions share the same snapshot
+    pub shared_snapshot_id: Option<String>,
+    // extract tables without order_col in batches by ctid instead of a single query
+    pub ctid_slicing: bool,
+    // target of sink_type=check, compare slices of batch_size rows by checksums and
+    // only extract the rows of mismatched slices
+    pub checksum_target_conn_pool: Option<Pool<Postgres>>,
 }
 
 #[async_trait]
@@ -47,6 +62,9 @@ impl Extractor for PgSnapshotExtractor {
     }
 
     async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(conn_pool) = &self.checksum_target_conn_pool {
+            conn_pool.close().await;
+        }
         close_conn_pool!(self)
     }
 }
@@ -59,6 +77,26 @@ impl PgSnapshotExtractor {
             .await?
             .to_owned();
 
+        // the target is not in the shared snapshot, so it makes no sense for the source
+        if let (Some(order_col), true) = (
+            &tb_meta.basic.order_col,
+            self.checksum_target_conn_pool.is_some(),
+        ) {
+            let order_col_type = tb_meta.get_col_type(order_col)?;
+            let resume_value = if let Some(value) =
+                self.resumer
+                    .get_resume_value(&self.schema, &self.tb, order_col, true)
+            {
+                PgColValueConvertor::from_str(order_col_type, &value, &mut self.meta_manager)?
+            } else {
+                ColValue::None
+            };
+            return self
+                .extract_by_checksum(&tb_meta, order_col, order_col_type, resume_value)
+                .await;
+        }
+
+        let mut tx = self.begin_shared_snapshot().await?;
         if let Some(order_col) = &tb_meta.basic.order_col {
             let order_col_type = tb_meta.get_col_type(order_col)?;
 
@@ -71,15 +109,53 @@ impl PgSnapshotExtractor {
                 ColValue::None
             };
 
-            self.extract_by_batch(&tb_meta, order_col, order_col_type, resume_value)
+            self.extract_by_batch(&tb_meta, order_col, order_col_type, resume_value, &mut tx)
                 .await?;
+        } else if self.ctid_slicing {
+            self.extract_by_ctid(&tb_meta, &mut tx).await?;
         } else {
-            self.extract_all(&tb_meta).await?;
+            self.extract_all(&tb_meta, &mut tx).await?;
+        }
+
+        if let Some(tx) = tx {
+            tx.commit().await?;
         }
         Ok(())
     }
 
-    async fn extract_all(&mut self, tb_meta: &PgTbMeta) -> anyhow::Result<()> {
+    async fn begin_shared_snapshot(
+        &self,
+    ) -> anyhow::Result<Option<Transaction<'static, Postgres>>> {
+        let snapshot_id = match &self.shared_snapshot_id {
+            Some(snapshot_id) => snapshot_id,
+            None => return Ok(None),
+        };
+
+        let mut tx = self.conn_pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut tx)
+            .await?;
+        let sql = format!("SET TRANSACTION SNAPSHOT '{}'", snapshot_id);
+        if let Err(err) = sqlx::query(&sql).execute(&mut tx).await {
+            // the exporting transaction may have ended
+            log_warn!(
+                r#"failed to import shared snapshot: {}, "{}"."{}" uses individual snapshot, error: {}"#,
+                snapshot_id,
+                self.schema,
+                self.tb,
+                err
+            );
+            tx.rollback().await?;
+            return Ok(None);
+        }
+        Ok(Some(tx))
+    }
+
+    async fn extract_all(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        tx: &mut Option<Transaction<'static, Postgres>>,
+    ) -> anyhow::Result<()> {
         log_info!(
             r#"start extracting data from "{}"."{}" without batch"#,
             self.schema,
@@ -88,7 +164,11 @@ impl PgSnapshotExtractor {
 
         let sql = self.build_extract_sql(tb_meta, false)?;
         let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
-        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        let query = sqlx::query(&sql);
+        let mut rows = match tx.as_mut() {
+            Some(tx) => query.fetch(tx),
+            None => query.fetch(&self.conn_pool),
+        };
         while let Some(row) = rows.try_next().await? {
             let row_data = RowData::from_pg_row(&row, tb_meta, &ignore_cols);
             self.base_extractor
@@ -111,6 +191,7 @@ impl PgSnapshotExtractor {
         order_col: &str,
         order_col_type: &PgColType,
         resume_value: ColValue,
+        tx: &mut Option<Transaction<'static, Postgres>>,
     ) -> anyhow::Result<()> {
         log_info!(
             r#"start extracting data from "{}"."{}" by batch, order_col: {}, start_value: {}"#,
@@ -133,7 +214,10 @@ impl PgSnapshotExtractor {
                 sqlx::query(&sql_2).bind_col_value(Some(&start_value_for_bind), order_col_type)
             };
 
-            let mut rows = query.fetch(&self.conn_pool);
+            let mut rows = match tx.as_mut() {
+                Some(tx) => query.fetch(tx),
+                None => query.fetch(&self.conn_pool),
+            };
             let mut slice_count = 0usize;
             while let Some(row) = rows.try_next().await? {
                 start_value = PgColValueConvertor::from_query(&row, order_col, order_col_type)?;
@@ -175,6 +259,371 @@ impl PgSnapshotExtractor {
         Ok(())
     }
 
+    // slices are (start, end] of order_col with batch_size rows, the end of each slice is
+    // found by offset in the source, then the same range is checksummed in both dbs
+    async fn extract_by_checksum(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        order_col: &str,
+        order_col_type: &PgColType,
+        resume_value: ColValue,
+    ) -> anyhow::Result<()> {
+        let target_conn_pool = match &self.checksum_target_conn_pool {
+            Some(conn_pool) => conn_pool.clone(),
+            None => return Ok(()),
+        };
+        log_info!(
+            r#"start checking "{}"."{}" by checksum, order_col: {}, slice size: {}"#,
+            self.schema,
+            self.tb,
+            order_col,
+            self.batch_size
+        );
+
+        let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
+        let cols = SliceChecksumSql::get_cols(&tb_meta.basic.cols, ignore_cols);
+        let src_sql = SliceChecksumSql {
+            schema: self.schema.clone(),
+            tb: self.tb.clone(),
+            cols: cols.clone(),
+        };
+        let dst_sql = SliceChecksumSql::new_for_target(
+            &self.base_extractor.router,
+            &self.schema,
+            &self.tb,
+            &cols,
+        );
+        let dst_order_col = match self
+            .base_extractor
+            .router
+            .get_col_map(&self.schema, &self.tb)
+            .and_then(|col_map| col_map.get(order_col))
+        {
+            Some(dst_order_col) => dst_order_col.clone(),
+            None => order_col.to_string(),
+        };
+
+        let mut stats = SliceChecksumStats::default();
+        let mut start_value = resume_value;
+        loop {
+            let end_value = self
+                .get_checksum_slice_end(order_col, order_col_type, &start_value)
+                .await?;
+            let src_checksum = self
+                .query_slice_checksum(
+                    &self.conn_pool,
+                    &src_sql,
+                    order_col,
+                    order_col_type,
+                    &start_value,
+                    &end_value,
+                )
+                .await?;
+            let dst_checksum = self
+                .query_slice_checksum(
+                    &target_conn_pool,
+                    &dst_sql,
+                    &dst_order_col,
+                    order_col_type,
+                    &start_value,
+                    &end_value,
+                )
+                .await?;
+
+            if !stats.add_slice(&src_checksum, &dst_checksum) {
+                log_info!(
+                    r#"checksum mismatched, "{}"."{}" slice: ({}, {}], source: {:?}, target: {:?}"#,
+                    self.schema,
+                    self.tb,
+                    start_value.to_string(),
+                    end_value.to_string(),
+                    src_checksum,
+                    dst_checksum
+                );
+                stats.drilled_count += self
+                    .extract_checksum_slice(
+                        tb_meta,
+                        order_col,
+                        order_col_type,
+                        &start_value,
+                        &end_value,
+                    )
+                    .await?;
+            }
+
+            // the last slice has no upper bound
+            let value = match end_value.to_option_string() {
+                Some(value) => value,
+                None => break,
+            };
+            let position = Position::RdbSnapshot {
+                db_type: DbType::Pg.to_string(),
+                schema: self.schema.clone(),
+                tb: self.tb.clone(),
+                order_col: order_col.into(),
+                value,
+            };
+            let commit = DtData::Commit { xid: String::new() };
+            self.base_extractor.push_dt_data(commit, position).await?;
+            start_value = end_value;
+        }
+
+        stats.log(&self.schema, &self.tb);
+        Ok(())
+    }
+
+    // the batch_size th row after start_value, None if less rows left
+    async fn get_checksum_slice_end(
+        &self,
+        order_col: &str,
+        order_col_type: &PgColType,
+        start_value: &ColValue,
+    ) -> anyhow::Result<ColValue> {
+        let condition = Self::build_checksum_slice_condition(
+            order_col,
+            order_col_type,
+            start_value,
+            &ColValue::None,
+        );
+        let where_sql =
+            BaseExtractor::get_where_sql(&self.filter, &self.schema, &self.tb, &condition);
+        let sql = format!(
+            r#"SELECT "{}" FROM "{}"."{}" {} ORDER BY "{}" ASC LIMIT 1 OFFSET {}"#,
+            order_col,
+            self.schema,
+            self.tb,
+            where_sql,
+            order_col,
+            self.batch_size - 1
+        );
+        let mut query = sqlx::query(&sql);
+        if *start_value != ColValue::None {
+            query = query.bind_col_value(Some(start_value), order_col_type);
+        }
+        match query.fetch_optional(&self.conn_pool).await? {
+            Some(row) => PgColValueConvertor::from_query(&row, order_col, order_col_type),
+            None => Ok(ColValue::None),
+        }
+    }
+
+    async fn query_slice_checksum(
+        &self,
+        conn_pool: &Pool<Postgres>,
+        checksum_sql: &SliceChecksumSql,
+        order_col: &str,
+        order_col_type: &PgColType,
+        start_value: &ColValue,
+        end_value: &ColValue,
+    ) -> anyhow::Result<SliceChecksum> {
+        let condition =
+            Self::build_checksum_slice_condition(order_col, order_col_type, start_value, end_value);
+        // the where condition of [filter] is applied to the target as is
+        let where_sql =
+            BaseExtractor::get_where_sql(&self.filter, &self.schema, &self.tb, &condition);
+        let sql = checksum_sql.build_pg_sql(&where_sql);
+        let mut query = sqlx::query(&sql);
+        for value in [start_value, end_value] {
+            if *value != ColValue::None {
+                query = query.bind_col_value(Some(value), order_col_type);
+            }
+        }
+        let row = query.fetch_one(conn_pool).await?;
+        Ok(SliceChecksum {
+            row_count: row.try_get("row_count")?,
+            checksum: row.try_get("checksum")?,
+        })
+    }
+
+    // push all rows of the slice to be checked row by row
+    async fn extract_checksum_slice(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        order_col: &str,
+        order_col_type: &PgColType,
+        start_value: &ColValue,
+        end_value: &ColValue,
+    ) -> anyhow::Result<usize> {
+        let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
+        let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, ignore_cols);
+        let cols_str = query_builder.build_extract_cols_str()?;
+        let condition =
+            Self::build_checksum_slice_condition(order_col, order_col_type, start_value, end_value);
+        let where_sql =
+            BaseExtractor::get_where_sql(&self.filter, &self.schema, &self.tb, &condition);
+        let sql = format!(
+            r#"SELECT {} FROM "{}"."{}" {} ORDER BY "{}" ASC"#,
+            cols_str, self.schema, self.tb, where_sql, order_col
+        );
+
+        let mut query = sqlx::query(&sql);
+        for value in [start_value, end_value] {
+            if *value != ColValue::None {
+                query = query.bind_col_value(Some(value), order_col_type);
+            }
+        }
+        let mut extracted_count = 0;
+        let mut rows = query.fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let row_data = RowData::from_pg_row(&row, tb_meta, &ignore_cols);
+            self.base_extractor
+                .push_row(row_data, Position::None)
+                .await?;
+            extracted_count += 1;
+        }
+        Ok(extracted_count)
+    }
+
+    fn build_checksum_slice_condition(
+        order_col: &str,
+        order_col_type: &PgColType,
+        start_value: &ColValue,
+        end_value: &ColValue,
+    ) -> String {
+        let mut conditions = Vec::new();
+        if *start_value != ColValue::None {
+            conditions.push(format!(
+                r#""{}" > ${}::{}"#,
+                order_col,
+                conditions.len() + 1,
+                order_col_type.alias
+            ));
+        }
+        if *end_value != ColValue::None {
+            conditions.push(format!(
+                r#""{}" <= ${}::{}"#,
+                order_col,
+                conditions.len() + 1,
+                order_col_type.alias
+            ));
+        }
+        conditions.join(" AND ")
+    }
+
+    // rows are extracted by ranges of heap blocks: ctid >= '(b,0)' AND ctid < '(b+k,0)', each
+    // range is a TID range scan (pg 14+) without sorting. ctid is only stable for rows visible
+    // in a snapshot, so all ranges are extracted in one repeatable read transaction if there is
+    // no shared snapshot. ctid values are not resumable since VACUUM FULL / CLUSTER rewrites them
+    async fn extract_by_ctid(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        tx: &mut Option<Transaction<'static, Postgres>>,
+    ) -> anyhow::Result<()> {
+        let mut own_tx = None;
+        let tx = match tx.as_mut() {
+            Some(tx) => tx,
+            None => {
+                let mut new_tx = self.conn_pool.begin().await?;
+                sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+                    .execute(&mut new_tx)
+                    .await?;
+                own_tx.insert(new_tx)
+            }
+        };
+
+        let sql = "SELECT (pg_relation_size(c.oid) / current_setting('block_size')::bigint)::bigint AS blocks,
+            c.reltuples::float8 AS reltuples, c.relpages::bigint AS relpages
+            FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2";
+        let row = sqlx::query(sql)
+            .bind(&self.schema)
+            .bind(&self.tb)
+            .fetch_one(&mut *tx)
+            .await?;
+        let total_blocks: i64 = row.try_get("blocks")?;
+        let blocks_per_range = Self::get_ctid_blocks_per_range(
+            self.batch_size,
+            row.try_get("reltuples")?,
+            row.try_get("relpages")?,
+        );
+        log_info!(
+            r#"start extracting data from "{}"."{}" by ctid ranges, blocks: {}, blocks per range: {}"#,
+            self.schema,
+            self.tb,
+            total_blocks,
+            blocks_per_range
+        );
+
+        let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
+        let mut extracted_count = 0;
+        let mut start_block = 0;
+        loop {
+            // the last range is open, rows may be appended after the size was read
+            let end_block = if start_block + blocks_per_range < total_blocks as u64 {
+                Some(start_block + blocks_per_range)
+            } else {
+                None
+            };
+            let sql = self.build_extract_by_ctid_sql(tb_meta, start_block, end_block)?;
+            let mut rows = sqlx::query(&sql).fetch(&mut *tx);
+            while let Some(row) = rows.try_next().await? {
+                extracted_count += 1;
+                if extracted_count % self.sample_interval != 0 {
+                    continue;
+                }
+
+                let row_data = RowData::from_pg_row(&row, tb_meta, &ignore_cols);
+                self.base_extractor
+                    .push_row(row_data, Position::None)
+                    .await?;
+            }
+
+            match end_block {
+                Some(end_block) => start_block = end_block,
+                None => break,
+            }
+        }
+
+        if let Some(own_tx) = own_tx {
+            own_tx.commit().await?;
+        }
+        log_info!(
+            r#"end extracting data from "{}"."{}", all count: {}"#,
+            self.schema,
+            self.tb,
+            extracted_count
+        );
+        Ok(())
+    }
+
+    // about batch_size rows in each range by the average rows per block in statistics
+    fn get_ctid_blocks_per_range(batch_size: usize, reltuples: f64, relpages: i64) -> u64 {
+        let rows_per_block = if reltuples <= 0.0 || relpages <= 0 {
+            // never analyzed
+            CTID_DEFAULT_ROWS_PER_BLOCK
+        } else {
+            (reltuples / relpages as f64).max(1.0)
+        };
+        ((batch_size as f64 / rows_per_block) as u64).max(1)
+    }
+
+    fn get_ctid_condition(start_block: u64, end_block: Option<u64>) -> String {
+        let mut condition = format!("ctid >= '({},0)'::tid", start_block);
+        if let Some(end_block) = end_block {
+            condition = format!("{} AND ctid < '({},0)'::tid", condition, end_block);
+        }
+        condition
+    }
+
+    fn build_extract_by_ctid_sql(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        start_block: u64,
+        end_block: Option<u64>,
+    ) -> anyhow::Result<String> {
+        let ignore_cols = self.filter.get_ignore_cols(&self.schema, &self.tb);
+        let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, ignore_cols);
+        let cols_str = query_builder.build_extract_cols_str()?;
+        let condition = Self::get_ctid_condition(start_block, end_block);
+        let where_sql =
+            BaseExtractor::get_where_sql(&self.filter, &self.schema, &self.tb, &condition);
+
+        // SELECT col_1, col_2::text FROM tb_1 WHERE ctid >= '(0,0)'::tid AND ctid < '(10,0)'::tid;
+        Ok(format!(
+            r#"SELECT {} FROM "{}"."{}" {}"#,
+            cols_str, self.schema, self.tb, where_sql
+        ))
+    }
+
     fn build_extract_sql(
         &mut self,
         tb_meta: &PgTbMeta,
@@ -210,3 +659,36 @@ impl PgSnapshotExtractor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PgSnapshotExtractor;
+
+    #[test]
+    fn test_get_ctid_condition() {
+        assert_eq!(
+            PgSnapshotExtractor::get_ctid_condition(0, Some(10)),
+            "ctid >= '(0,0)'::tid AND ctid < '(10,0)'::tid"
+        );
+        assert_eq!(
+            PgSnapshotExtractor::get_ctid_condition(10, None),
+            "ctid >= '(10,0)'::tid"
+        );
+
+        // 100 rows per block
+        assert_eq!(
+            PgSnapshotExtractor::get_ctid_blocks_per_range(10000, 100000.0, 1000),
+            100
+        );
+        // larger rows than batch_size in a block
+        assert_eq!(
+            PgSnapshotExtractor::get_ctid_blocks_per_range(10, 100000.0, 1000),
+            1
+        );
+        // never analyzed
+        assert_eq!(
+            PgSnapshotExtractor::get_ctid_blocks_per_range(10000, -1.0, 0),
+            100
+        );
+    }
+}
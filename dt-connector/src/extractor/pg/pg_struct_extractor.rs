@@ -17,6 +17,9 @@ pub struct PgStructExtractor {
     pub conn_pool: Pool<Postgres>,
     pub schema: String,
     pub filter: RdbFilter,
+    // push indexes and check / foreign key constraints after all tables, so foreign keys
+    // do not fail on tables not created yet
+    pub split_indexes: bool,
 }
 
 #[async_trait]
@@ -40,15 +43,41 @@ impl PgStructExtractor {
             filter: Some(self.filter.to_owned()),
         };
 
+        // server settings
+        if self.filter.include_server_settings {
+            for (name, value) in pg_fetcher.get_relevant_server_settings().await? {
+                self.push_dt_data(StructStatement::PgSetParameter { name, value })
+                    .await?;
+            }
+        }
+
         // schema
         let schema_statement = pg_fetcher.get_create_schema_statement().await?;
         self.push_dt_data(StructStatement::PgCreateSchema(schema_statement))
             .await?;
 
+        // domains
+        for domain_statement in pg_fetcher.get_create_domain_statements().await? {
+            self.push_dt_data(StructStatement::PgCreateDomain(domain_statement))
+                .await?;
+        }
+
         // tables
+        let mut indexes = Vec::new();
+        let mut constraints = Vec::new();
         for table_statement in pg_fetcher.get_create_table_statements("").await? {
-            self.push_dt_data(StructStatement::PgCreateTable(table_statement))
-                .await?;
+            let mut statement = StructStatement::PgCreateTable(table_statement);
+            if self.split_indexes {
+                let (tb_indexes, tb_constraints) = statement.split();
+                indexes.extend(tb_indexes);
+                constraints.extend(tb_constraints);
+            }
+            self.push_dt_data(statement).await?;
+        }
+
+        // indexes and constraints
+        for statement in indexes.into_iter().chain(constraints) {
+            self.push_dt_data(statement).await?;
         }
         Ok(())
     }
@@ -1,7 +1,6 @@
 use anyhow::bail;
 use dt_common::meta::redis::{redis_entry::RedisEntry, redis_object::RedisCmd};
-use dt_common::{error::Error, log_debug, log_info};
-use sqlx::types::chrono;
+use dt_common::{error::Error, log_debug_row, log_info};
 
 use crate::extractor::redis::{rdb::entry_parser::module2_parser::ModuleParser, StreamReader};
 
@@ -30,7 +29,7 @@ pub struct RdbParser<'a> {
     pub reader: RdbReader<'a>,
     pub repl_stream_db_id: i64,
     pub now_db_id: i64,
-    pub expire_ms: i64,
+    pub expire_at_ms: i64,
     pub idle: i64,
     pub freq: i64,
 
@@ -54,7 +53,7 @@ impl RdbParser<'_> {
 
     pub fn load_entry(&mut self) -> anyhow::Result<Option<RedisEntry>> {
         let type_byte = self.reader.read_byte()?;
-        log_debug!("rdb type_byte: {}", type_byte);
+        log_debug_row!("rdb type_byte: {}", type_byte);
 
         match type_byte {
             K_FLAG_MODULE_AUX => {
@@ -144,22 +143,13 @@ impl RdbParser<'_> {
                 )
             }
 
+            // keep the absolute expiry, keys may be sinked long after parsed
             K_FLAG_EXPIRE_MS => {
-                let mut expire_ms = self.reader.read_u64()? as i64;
-                expire_ms -= chrono::Utc::now().timestamp_millis();
-                if expire_ms < 0 {
-                    expire_ms = 1
-                }
-                self.expire_ms = expire_ms;
+                self.expire_at_ms = self.reader.read_u64()? as i64;
             }
 
             K_FLAG_EXPIRE => {
-                let mut expire_ms = self.reader.read_u32()? as i64 * 1000;
-                expire_ms -= chrono::Utc::now().timestamp_millis();
-                if expire_ms < 0 {
-                    expire_ms = 1
-                }
-                self.expire_ms = expire_ms;
+                self.expire_at_ms = self.reader.read_u32()? as i64 * 1000;
             }
 
             K_FLAG_SELECT => {
@@ -193,9 +183,9 @@ impl RdbParser<'_> {
                     entry.key = key;
                     entry.value = value.unwrap();
                     entry.value_type_byte = type_byte;
-                    entry.expire_ms = self.expire_ms;
-                    // reset expire_ms
-                    self.expire_ms = 0;
+                    entry.expire_at_ms = self.expire_at_ms;
+                    // reset expire_at_ms
+                    self.expire_at_ms = 0;
                     return Ok(Some(entry));
                 }
             }
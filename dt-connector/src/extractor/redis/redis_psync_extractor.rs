@@ -11,7 +11,7 @@ use dt_common::rdb_filter::RdbFilter;
 use dt_common::utils::sql_util::SqlUtil;
 use dt_common::utils::time_util::TimeUtil;
 use dt_common::{error::Error, log_info};
-use dt_common::{log_debug, log_error, log_position, log_warn};
+use dt_common::{log_debug_row, log_error, log_position, log_warn};
 
 use crate::extractor::base_extractor::BaseExtractor;
 use crate::extractor::redis::rdb::rdb_parser::RdbParser;
@@ -27,6 +27,18 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
+// consecutive reconnect attempts after the replication connection is broken
+const MAX_RECONNECT_TIMES: usize = 3;
+// used if CONFIG GET databases is not allowed by the source
+const DEFAULT_DATABASES: i64 = 16;
+
+#[derive(Debug, PartialEq)]
+enum PsyncResponse {
+    FullResync { repl_id: String, repl_offset: u64 },
+    // new_repl_id is returned since redis 4.0
+    Continue { repl_id: Option<String> },
+}
+
 pub struct RedisPsyncExtractor {
     pub base_extractor: BaseExtractor,
     pub conn: RedisClient,
@@ -46,6 +58,27 @@ pub struct RedisPsyncExtractor {
 #[async_trait]
 impl Extractor for RedisPsyncExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
+        if let Position::Redis {
+            repl_id,
+            repl_offset,
+            now_db_id,
+            ..
+        } = &self.resumer.checkpoint_position
+        {
+            if !repl_id.is_empty() {
+                self.repl_id = repl_id.to_owned();
+                self.repl_offset = *repl_offset;
+                self.now_db_id = *now_db_id;
+                log_info!("resume from: {}", self.resumer.checkpoint_position);
+                self.base_extractor
+                    .push_dt_data(
+                        DtData::Heartbeat {},
+                        self.resumer.checkpoint_position.clone(),
+                    )
+                    .await?;
+            }
+        }
+
         log_info!(
             "RedisPsyncExtractor starts, repl_id: {}, repl_offset: {}, now_db_id: {}, 
              keepalive_interval_secs: {}, heartbeat_interval_secs: {}, heartbeat_key: {}",
@@ -67,7 +100,7 @@ impl Extractor for RedisPsyncExtractor {
             self.extract_type,
             ExtractType::Cdc | ExtractType::SnapshotAndCdc
         ) {
-            self.receive_aof().await?;
+            self.receive_aof_with_reconnect().await?;
         }
 
         self.base_extractor.wait_task_finish().await
@@ -93,11 +126,18 @@ impl RedisPsyncExtractor {
             )}
         }
 
-        let full_sync = self.repl_id.is_empty() && self.repl_offset == 0;
-        let (repl_id, repl_offset) = if full_sync {
-            ("?".to_string(), "-1".to_string())
+        // repl_offset is the offset of the last received byte, ask for the next one
+        let partial_sync = !self.repl_id.is_empty() || self.repl_offset != 0;
+        // asked before PSYNC, the connection streams the rdb and commands after it
+        let databases = if partial_sync {
+            self.get_databases().await
         } else {
-            (self.repl_id.clone(), self.repl_offset.to_string())
+            DEFAULT_DATABASES
+        };
+        let (repl_id, repl_offset) = if partial_sync {
+            (self.repl_id.clone(), (self.repl_offset + 1).to_string())
+        } else {
+            ("?".to_string(), "-1".to_string())
         };
 
         // PSYNC [repl_id] [offset]
@@ -106,23 +146,135 @@ impl RedisPsyncExtractor {
         self.conn.send(&psync_cmd).await?;
         let value = self.conn.read().await?;
 
-        if let Value::Status(s) = value {
-            log_info!("PSYNC command response status: {:?}", s);
-            if full_sync {
-                let tokens: Vec<&str> = s.split_whitespace().collect();
-                self.repl_id = tokens[1].to_string();
-                self.repl_offset = tokens[2].parse::<u64>()?;
-            } else if s != "CONTINUE" {
-                bail! {Error::ExtractorError(
-                    "PSYNC command response is NOT CONTINUE".into(),
-                )}
-            }
+        let s = if let Value::Status(s) = value {
+            s
         } else {
             bail! {Error::ExtractorError(
                 "PSYNC command response is NOT status".into(),
             )}
         };
-        Ok(full_sync)
+        log_info!("PSYNC command response status: {:?}", s);
+
+        match Self::parse_psync_response(&s, partial_sync)? {
+            PsyncResponse::FullResync {
+                repl_id,
+                repl_offset,
+            } => {
+                self.repl_id = repl_id;
+                self.repl_offset = repl_offset;
+                if partial_sync {
+                    log_warn!(
+                        "partial resync rejected by master, fall back to full resync, repl_id: {}, repl_offset: {}",
+                        self.repl_id,
+                        self.repl_offset
+                    );
+                    // commands from the checkpoint are lost, the whole rdb must be synced
+                    self.now_db_id = 0;
+                    if matches!(self.extract_type, ExtractType::Cdc) {
+                        self.extract_type = ExtractType::SnapshotAndCdc;
+                    }
+                    self.push_flush_dbs(databases).await?;
+                }
+                Ok(true)
+            }
+
+            PsyncResponse::Continue { repl_id } => {
+                if let Some(repl_id) = repl_id {
+                    self.repl_id = repl_id;
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    // FULLRESYNC [repl_id] [offset]
+    // CONTINUE [new_repl_id]
+    fn parse_psync_response(s: &str, partial_sync: bool) -> anyhow::Result<PsyncResponse> {
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        match tokens.first() {
+            Some(&"FULLRESYNC") if tokens.len() == 3 => {
+                if let Ok(repl_offset) = tokens[2].parse::<u64>() {
+                    return Ok(PsyncResponse::FullResync {
+                        repl_id: tokens[1].to_string(),
+                        repl_offset,
+                    });
+                }
+            }
+
+            Some(&"CONTINUE") if partial_sync && tokens.len() <= 2 => {
+                return Ok(PsyncResponse::Continue {
+                    repl_id: tokens.get(1).map(|i| i.to_string()),
+                });
+            }
+
+            _ => {}
+        }
+        bail! {Error::ExtractorError(format!(
+            "unexpected PSYNC command response: {}",
+            s
+        ))}
+    }
+
+    async fn get_databases(&mut self) -> i64 {
+        let cmd = RedisCmd::from_str_args(&["CONFIG", "GET", "databases"]);
+        let databases = match self.conn.send(&cmd).await {
+            Ok(_) => match self.conn.read().await {
+                Ok(Value::Bulk(values)) => match values.get(1) {
+                    Some(Value::Data(data)) => String::from_utf8_lossy(data).parse().ok(),
+                    _ => None,
+                },
+                _ => None,
+            },
+            Err(_) => None,
+        };
+        databases.unwrap_or_else(|| {
+            log_warn!(
+                "failed to get databases of source, use default: {}",
+                DEFAULT_DATABASES
+            );
+            DEFAULT_DATABASES
+        })
+    }
+
+    // the rdb of a full resync only has keys existing now, keys deleted since the checkpoint
+    // would stay in target, so target dbs are flushed before the rdb as a replica does
+    async fn push_flush_dbs(&mut self, databases: i64) -> anyhow::Result<()> {
+        let db_ids = match Self::get_flush_db_ids(&mut self.filter, databases) {
+            Some(db_ids) => db_ids,
+            None => {
+                log_warn!(
+                    "keys are filtered by do_keys / ignore_keys, target dbs are not flushed before full resync, keys deleted since the checkpoint may stay in target"
+                );
+                return Ok(());
+            }
+        };
+
+        for db_id in db_ids {
+            log_warn!("flush target db before full resync, db_id: {}", db_id);
+            let mut entry = RedisEntry::new();
+            entry.cmd = RedisCmd::from_str_args(&["FLUSHDB"]);
+            entry.db_id = db_id;
+            Self::push_to_buf(
+                &mut self.base_extractor,
+                &mut self.filter,
+                entry,
+                Position::None,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    // None if keys are filtered, flushing a db would remove target keys not synced by the task
+    fn get_flush_db_ids(filter: &mut RdbFilter, databases: i64) -> Option<Vec<i64>> {
+        if !filter.do_keys.is_empty() || !filter.ignore_keys.is_empty() {
+            return None;
+        }
+        Some(
+            (0..databases)
+                .filter(|db_id| !filter.filter_schema(&db_id.to_string()))
+                .collect(),
+        )
     }
 
     async fn receive_rdb(&mut self) -> anyhow::Result<()> {
@@ -164,7 +316,7 @@ impl RedisPsyncExtractor {
             reader,
             repl_stream_db_id: 0,
             now_db_id: self.now_db_id,
-            expire_ms: 0,
+            expire_at_ms: 0,
             idle: 0,
             freq: 0,
             is_end: false,
@@ -198,8 +350,9 @@ impl RedisPsyncExtractor {
 
             if parser.is_end {
                 log_info!(
-                    "end extracting data from rdb, all count: {}",
-                    self.base_extractor.monitor.counters.record_count
+                    "end extracting data from rdb, all count: {}, filtered key count: {}",
+                    self.base_extractor.monitor.counters.record_count,
+                    self.base_extractor.monitor.counters.filtered_count
                 );
                 break;
             }
@@ -217,7 +370,60 @@ impl RedisPsyncExtractor {
         Ok(())
     }
 
-    async fn receive_aof(&mut self) -> anyhow::Result<()> {
+    // a broken replication connection is reconnected and resumed by PSYNC from the last
+    // received offset, commands already received are not replayed since the master sends
+    // only the bytes after the offset, and now_db_id stays the same as no SELECT is resent
+    async fn receive_aof_with_reconnect(&mut self) -> anyhow::Result<()> {
+        let mut reconnect_times = 0;
+        let mut failed_repl_offset = self.repl_offset;
+        let mut start_heartbeat = true;
+        loop {
+            let err = match self.receive_aof(start_heartbeat).await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            start_heartbeat = false;
+            // reconnect times are reset only if commands were received since the last failure,
+            // an error that always happens at the same offset is raised
+            if self.repl_offset > failed_repl_offset {
+                reconnect_times = 0;
+            }
+            failed_repl_offset = self.repl_offset;
+
+            loop {
+                if self.base_extractor.shut_down.load(Ordering::Acquire)
+                    || reconnect_times >= MAX_RECONNECT_TIMES
+                {
+                    return Err(err);
+                }
+                reconnect_times += 1;
+                log_warn!(
+                    "replication connection broken, reconnecting: {}/{}, repl_id: {}, repl_offset: {}, error: {:?}",
+                    reconnect_times,
+                    MAX_RECONNECT_TIMES,
+                    self.repl_id,
+                    self.repl_offset,
+                    err
+                );
+                TimeUtil::sleep_millis(1000 * reconnect_times as u64).await;
+
+                match self.reconnect().await {
+                    Ok(()) => break,
+                    Err(err) => log_error!("reconnect failed, error: {:?}", err),
+                }
+            }
+        }
+    }
+
+    async fn reconnect(&mut self) -> anyhow::Result<()> {
+        self.conn = RedisClient::new(&self.conn.url).await?;
+        if self.start_psync().await? {
+            self.receive_rdb().await?;
+        }
+        Ok(())
+    }
+
+    async fn receive_aof(&mut self, start_heartbeat: bool) -> anyhow::Result<()> {
         let heartbeat_db_key = ConfigTokenParser::parse(
             &self.heartbeat_key,
             &['.'],
@@ -229,16 +435,18 @@ impl RedisPsyncExtractor {
             i64::MIN
         };
 
-        // start hearbeat
-        if heartbeat_db_key.len() == 2 {
-            self.start_heartbeat(
-                heartbeat_db_id,
-                &heartbeat_db_key[1],
-                self.base_extractor.shut_down.clone(),
-            )
-            .await?;
-        } else {
-            log_warn!("heartbeat disabled, heartbeat_tb should be like db.key");
+        // start hearbeat, only once if reconnected
+        if start_heartbeat {
+            if heartbeat_db_key.len() == 2 {
+                self.start_heartbeat(
+                    heartbeat_db_id,
+                    &heartbeat_db_key[1],
+                    self.base_extractor.shut_down.clone(),
+                )
+                .await?;
+            } else {
+                log_warn!("heartbeat disabled, heartbeat_tb should be like db.key");
+            }
         }
 
         let mut heartbeat_timestamp = String::new();
@@ -251,6 +459,12 @@ impl RedisPsyncExtractor {
             }
 
             let (value, n) = self.conn.read_with_len().await?;
+            // nothing read means EOF, a newline (n == 1) is sent by master to keep alive
+            if n == 0 {
+                bail! {Error::ExtractorError(
+                    "replication connection closed by master".into(),
+                )}
+            }
             if Value::Nil == value {
                 TimeUtil::sleep_millis(1).await;
                 continue;
@@ -258,7 +472,7 @@ impl RedisPsyncExtractor {
 
             self.repl_offset += n as u64;
             let cmd = self.handle_redis_value(value).await?;
-            log_debug!("received cmd: [{}]", cmd);
+            log_debug_row!("received cmd: [{}]", cmd);
 
             if !cmd.args.is_empty() {
                 let cmd_name = cmd.get_name().to_ascii_lowercase();
@@ -445,14 +659,103 @@ impl RedisPsyncExtractor {
         mut entry: RedisEntry,
         position: Position,
     ) -> anyhow::Result<()> {
-        // currently only support db filter
         if filter.filter_schema(&entry.db_id.to_string()) {
             return Ok(());
         }
 
+        // keys of all value types parsed from rdb, before reaching the sinker
+        if entry.is_raw() && filter.filter_key(&entry.key.to_string()) {
+            base_extractor.monitor.counters.filtered_count += 1;
+            base_extractor.monitor.try_flush(false);
+            return Ok(());
+        }
+
         entry.data_size = entry.get_data_malloc_size();
         base_extractor
             .push_dt_data(DtData::Redis { entry }, position)
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dt_common::config::filter_config::FilterConfig;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_psync_response() {
+        let parse = RedisPsyncExtractor::parse_psync_response;
+        assert_eq!(
+            parse(
+                "FULLRESYNC 8de1787ba490483314a4d30f1c628bc5025eb761 2443808505",
+                false
+            )
+            .unwrap(),
+            PsyncResponse::FullResync {
+                repl_id: "8de1787ba490483314a4d30f1c628bc5025eb761".into(),
+                repl_offset: 2443808505,
+            }
+        );
+        // master rejects partial resync
+        assert_eq!(
+            parse("FULLRESYNC repl_id_1 100", true).unwrap(),
+            PsyncResponse::FullResync {
+                repl_id: "repl_id_1".into(),
+                repl_offset: 100,
+            }
+        );
+        assert_eq!(
+            parse("CONTINUE", true).unwrap(),
+            PsyncResponse::Continue { repl_id: None }
+        );
+        assert_eq!(
+            parse("CONTINUE repl_id_2", true).unwrap(),
+            PsyncResponse::Continue {
+                repl_id: Some("repl_id_2".into())
+            }
+        );
+
+        // CONTINUE is only expected if partial resync was asked
+        assert!(parse("CONTINUE repl_id_2", false).is_err());
+        assert!(parse("FULLRESYNC repl_id_1", false).is_err());
+        assert!(parse("FULLRESYNC repl_id_1 -1", false).is_err());
+        assert!(parse("NOMASTERLINK", true).is_err());
+        assert!(parse("", true).is_err());
+    }
+
+    #[test]
+    fn test_get_flush_db_ids() {
+        let get_filter = |do_schemas: &str, ignore_schemas: &str, do_keys: &str| {
+            let config = FilterConfig {
+                do_schemas: do_schemas.into(),
+                ignore_schemas: ignore_schemas.into(),
+                do_keys: do_keys.into(),
+                ..Default::default()
+            };
+            RdbFilter::from_config(&config, &DbType::Redis).unwrap()
+        };
+
+        let mut filter = get_filter("*", "", "");
+        assert_eq!(
+            RedisPsyncExtractor::get_flush_db_ids(&mut filter, 4),
+            Some(vec![0, 1, 2, 3])
+        );
+
+        // only dbs synced by the task are flushed
+        let mut filter = get_filter("*", "1,3", "");
+        assert_eq!(
+            RedisPsyncExtractor::get_flush_db_ids(&mut filter, 4),
+            Some(vec![0, 2])
+        );
+        let mut filter = get_filter("2", "", "");
+        assert_eq!(
+            RedisPsyncExtractor::get_flush_db_ids(&mut filter, 16),
+            Some(vec![2])
+        );
+
+        // keys not synced by the task may be in the same db
+        let mut filter = get_filter("*", "", "user:*");
+        assert_eq!(RedisPsyncExtractor::get_flush_db_ids(&mut filter, 16), None);
+    }
+}
@@ -2,7 +2,7 @@ use std::{cmp, collections::HashMap};
 
 use async_trait::async_trait;
 use dt_common::{
-    log_debug, log_info,
+    log_debug_row, log_info,
     meta::redis::{
         cluster_node::ClusterNode, command::cmd_encoder::CmdEncoder, redis_object::RedisCmd,
     },
@@ -147,7 +147,7 @@ impl RedisReshardExtractor {
 
         // migrate
         for key in keys.iter() {
-            log_debug!(
+            log_debug_row!(
                 "migrating key: [{}] in slot {} from {} to {}",
                 key,
                 slot,
@@ -42,7 +42,7 @@ impl Extractor for RedisSnapshotFileExtractor {
             reader,
             repl_stream_db_id: 0,
             now_db_id: 0,
-            expire_ms: 0,
+            expire_at_ms: 0,
             idle: 0,
             freq: 0,
             is_end: false,
@@ -64,8 +64,9 @@ impl Extractor for RedisSnapshotFileExtractor {
 
             if parser.is_end {
                 log_info!(
-                    "end extracting data from rdb, all count: {}",
-                    self.base_extractor.monitor.counters.record_count
+                    "end extracting data from rdb, all count: {}, filtered key count: {}",
+                    self.base_extractor.monitor.counters.record_count,
+                    self.base_extractor.monitor.counters.filtered_count
                 );
                 break;
             }
@@ -72,6 +72,20 @@ impl CdcResumer {
         Ok(me)
     }
 
+    // positions from DbPositionStore are committed, extractors resume from them exactly
+    pub fn load_stored_position(&mut self, position: Position) {
+        match position {
+            Position::None
+            | Position::RdbSnapshot { .. }
+            | Position::RdbSnapshotFinished { .. }
+            | Position::FoxlakeS3 { .. } => {}
+            _ => {
+                self.current_position = position.clone();
+                self.checkpoint_position = position;
+            }
+        }
+    }
+
     fn load_resume_line(&mut self, line: &str) {
         let position = Position::from_log(line);
         // ignore position log lines like:
@@ -98,3 +112,33 @@ impl std::fmt::Display for CdcResumer {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_stored_position() {
+        let mut resumer = CdcResumer::default();
+        let position = Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: "mysql-bin.000003".into(),
+            next_event_position: 1200,
+            gtid_set: String::new(),
+            timestamp: String::new(),
+        };
+        resumer.load_stored_position(position.clone());
+        assert_eq!(resumer.current_position, position);
+        assert_eq!(resumer.checkpoint_position, position);
+
+        // snapshot positions are not cdc positions
+        resumer.load_stored_position(Position::RdbSnapshotFinished {
+            db_type: "mysql".into(),
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+        });
+        resumer.load_stored_position(Position::None);
+        assert_eq!(resumer.current_position, position);
+        assert_eq!(resumer.checkpoint_position, position);
+    }
+}
@@ -138,15 +138,36 @@ impl SnapshotResumer {
         res
     }
 
+    // positions from DbPositionStore are committed, so they are checkpoint positions,
+    // and they override positions of the same table from logs
+    pub fn load_stored_position(&mut self, position: Position) {
+        if let Position::RdbSnapshot {
+            schema,
+            tb,
+            order_col,
+            ..
+        } = &position
+        {
+            self.current_tb_positions
+                .remove(&(schema.clone(), tb.clone(), order_col.clone()));
+        }
+        self.load_position(position, true)
+    }
+
     fn load_resume_line(&mut self, line: &str) {
         // by default, all positions in resumer.config are checkpoint positions
-        let tb_positions = if line.contains(CURRENT_POSITION_LOG_FLAG) {
-            &mut self.current_tb_positions
-        } else {
+        let checkpoint = !line.contains(CURRENT_POSITION_LOG_FLAG);
+        self.load_position(Position::from_log(line), checkpoint)
+    }
+
+    fn load_position(&mut self, position: Position, checkpoint: bool) {
+        let tb_positions = if checkpoint {
             &mut self.checkpoint_tb_positions
+        } else {
+            &mut self.current_tb_positions
         };
 
-        match Position::from_log(line) {
+        match position {
             Position::RdbSnapshot {
                 schema,
                 tb,
@@ -173,3 +194,56 @@ impl SnapshotResumer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot_position(tb: &str, value: &str) -> Position {
+        Position::RdbSnapshot {
+            db_type: "mysql".into(),
+            schema: "db_1".into(),
+            tb: tb.into(),
+            order_col: "id".into(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn test_load_stored_position() {
+        let mut resumer = SnapshotResumer::default();
+        resumer.load_resume_line(&format!(
+            "2025-02-18 04:13:04.655541 {} {}",
+            CURRENT_POSITION_LOG_FLAG,
+            serde_json::to_string(&snapshot_position("tb_1", "100")).unwrap()
+        ));
+        resumer.load_position(snapshot_position("tb_2", "10"), true);
+        assert_eq!(
+            resumer.get_resume_value("db_1", "tb_1", "id", false),
+            Some("100".to_string())
+        );
+
+        // stored positions are committed and override those of the same table from logs
+        resumer.load_stored_position(snapshot_position("tb_1", "80"));
+        assert_eq!(
+            resumer.get_resume_value("db_1", "tb_1", "id", false),
+            Some("80".to_string())
+        );
+        assert_eq!(
+            resumer.get_resume_value("db_1", "tb_1", "id", true),
+            Some("80".to_string())
+        );
+        assert_eq!(
+            resumer.get_resume_value("db_1", "tb_2", "id", false),
+            Some("10".to_string())
+        );
+
+        resumer.load_stored_position(Position::RdbSnapshotFinished {
+            db_type: "mysql".into(),
+            schema: "db_1".into(),
+            tb: "tb_3".into(),
+        });
+        assert!(resumer.check_finished("db_1", "tb_3"));
+        assert!(!resumer.check_finished("db_1", "tb_1"));
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+
+use dt_common::log_info;
+
+use crate::rdb_router::RdbRouter;
+
+// aggregate of the rows in a slice of order_col, computed by the source and the target
+// on their own so only the aggregates are transferred. the row hashes are summed, so
+// the checksum does not depend on the order rows are scanned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SliceChecksum {
+    pub row_count: i64,
+    pub checksum: String,
+}
+
+#[derive(Default)]
+pub struct SliceChecksumStats {
+    pub slice_count: usize,
+    pub mismatched_slice_count: usize,
+    // rows of mismatched slices pushed to the checker
+    pub drilled_count: usize,
+    // rows in all slices of the source
+    pub row_count: usize,
+}
+
+pub struct SliceChecksumSql {
+    pub schema: String,
+    pub tb: String,
+    pub cols: Vec<String>,
+}
+
+impl SliceChecksumSql {
+    // cols are the extracted cols of the source, the target tb and cols are routed
+    pub fn new_for_target(router: &RdbRouter, schema: &str, tb: &str, cols: &[String]) -> Self {
+        let (dst_schema, dst_tb) = router.get_tb_map(schema, tb);
+        let col_map = router.get_col_map(schema, tb);
+        let cols = cols
+            .iter()
+            .map(|col| match col_map.and_then(|m| m.get(col)) {
+                Some(dst_col) => dst_col.clone(),
+                None => col.clone(),
+            })
+            .collect();
+        Self {
+            schema: dst_schema.to_string(),
+            tb: dst_tb.to_string(),
+            cols,
+        }
+    }
+
+    // cols to be checksummed in table order, ignored cols are excluded
+    pub fn get_cols(cols: &[String], ignore_cols: Option<&HashSet<String>>) -> Vec<String> {
+        cols.iter()
+            .filter(|col| ignore_cols.map_or(true, |ignore_cols| !ignore_cols.contains(*col)))
+            .cloned()
+            .collect()
+    }
+
+    // CONCAT_WS skips NULLs, so the NULL flags of all cols are appended to tell
+    // NULL from empty strings
+    pub fn build_mysql_sql(&self, where_sql: &str) -> String {
+        let escaped_cols: Vec<String> = self.cols.iter().map(|col| format!("`{}`", col)).collect();
+        let null_flags: Vec<String> = escaped_cols
+            .iter()
+            .map(|col| format!("ISNULL({})", col))
+            .collect();
+        format!(
+            "SELECT COUNT(*) AS `row_count`, CAST(COALESCE(SUM(CRC32(CONCAT_WS('#', {}, CONCAT({})))), 0) AS CHAR) AS `checksum` FROM `{}`.`{}` {}",
+            escaped_cols.join(", "),
+            null_flags.join(", "),
+            self.schema,
+            self.tb,
+            where_sql
+        )
+    }
+
+    // the text of a row value quotes empty strings and leaves NULLs empty,
+    // the first 32 bits of its md5 are used as the row hash
+    pub fn build_pg_sql(&self, where_sql: &str) -> String {
+        let escaped_cols: Vec<String> = self
+            .cols
+            .iter()
+            .map(|col| format!(r#""{}""#, col))
+            .collect();
+        format!(
+            r#"SELECT COUNT(*) AS "row_count", COALESCE(SUM(('x' || substr(md5(ROW({})::text), 1, 8))::bit(32)::bigint), 0)::text AS "checksum" FROM "{}"."{}" {}"#,
+            escaped_cols.join(", "),
+            self.schema,
+            self.tb,
+            where_sql
+        )
+    }
+}
+
+impl SliceChecksumStats {
+    pub fn add_slice(&mut self, src: &SliceChecksum, dst: &SliceChecksum) -> bool {
+        self.slice_count += 1;
+        self.row_count += src.row_count as usize;
+        let matched = src == dst;
+        if !matched {
+            self.mismatched_slice_count += 1;
+        }
+        matched
+    }
+
+    pub fn log(&self, schema: &str, tb: &str) {
+        log_info!(
+            "end checking {}.{} by checksum, slice count: {}, mismatched slice count: {}, row count: {}, drilled row count: {}",
+            schema,
+            tb,
+            self.slice_count,
+            self.mismatched_slice_count,
+            self.row_count,
+            self.drilled_count
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sql() {
+        let sql = SliceChecksumSql {
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            cols: vec!["id".into(), "name".into()],
+        };
+        assert_eq!(
+            sql.build_mysql_sql("WHERE `id` > ?"),
+            "SELECT COUNT(*) AS `row_count`, CAST(COALESCE(SUM(CRC32(CONCAT_WS('#', `id`, `name`, CONCAT(ISNULL(`id`), ISNULL(`name`))))), 0) AS CHAR) AS `checksum` FROM `db_1`.`tb_1` WHERE `id` > ?"
+        );
+        assert_eq!(
+            sql.build_pg_sql(""),
+            r#"SELECT COUNT(*) AS "row_count", COALESCE(SUM(('x' || substr(md5(ROW("id", "name")::text), 1, 8))::bit(32)::bigint), 0)::text AS "checksum" FROM "db_1"."tb_1" "#
+        );
+    }
+
+    #[test]
+    fn test_get_cols() {
+        let cols: Vec<String> = vec!["id".into(), "name".into(), "content".into()];
+        assert_eq!(SliceChecksumSql::get_cols(&cols, None), cols);
+        let ignore_cols = HashSet::from(["content".to_string()]);
+        assert_eq!(
+            SliceChecksumSql::get_cols(&cols, Some(&ignore_cols)),
+            vec!["id".to_string(), "name".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_add_slice() {
+        let checksum = |row_count, checksum: &str| SliceChecksum {
+            row_count,
+            checksum: checksum.into(),
+        };
+        let mut stats = SliceChecksumStats::default();
+        assert!(stats.add_slice(&checksum(10, "123"), &checksum(10, "123")));
+        assert!(!stats.add_slice(&checksum(10, "123"), &checksum(9, "123")));
+        assert!(!stats.add_slice(&checksum(10, "123"), &checksum(10, "124")));
+        assert_eq!(stats.slice_count, 3);
+        assert_eq!(stats.mismatched_slice_count, 2);
+        assert_eq!(stats.row_count, 30);
+    }
+}
@@ -7,23 +7,53 @@ pub mod conn_util;
 pub mod data_marker;
 pub mod extractor;
 pub mod meta_fetcher;
+pub mod position_store;
 pub mod rdb_query_builder;
 pub mod rdb_router;
 pub mod sinker;
 
 use async_trait::async_trait;
 use check_log::check_log::CheckLog;
-use dt_common::meta::{
-    ddl_meta::ddl_data::DdlData, dt_data::DtItem, row_data::RowData,
-    struct_meta::struct_data::StructData,
+use dt_common::{
+    config::task_config::TaskConfig,
+    meta::{
+        ddl_meta::ddl_data::DdlData, dt_data::DtItem, row_data::RowData,
+        struct_meta::struct_data::StructData,
+    },
 };
 
+// apply config changes without restart, called by the config watcher with the reloaded config,
+// changes not supported by the implementor are ignored
+pub trait Configurable {
+    fn update_config(&mut self, _config: &TaskConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
 #[async_trait]
-pub trait Sinker {
+pub trait Sinker: Configurable {
     async fn sink_dml(&mut self, mut _data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
         Ok(())
     }
 
+    // sink all sub batches of data in one transaction, committed only if all succeed,
+    // sinkers without transaction support sink data as sink_dml
+    async fn sink_dml_in_tx(&mut self, data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml(data, batch).await
+    }
+
+    // sink sub batches in order in one transaction, e.g. the delete and insert halves
+    // of merged rows, sinkers without transaction support sink them one by one
+    async fn sink_dml_batches_in_tx(
+        &mut self,
+        batches: Vec<(Vec<RowData>, bool)>,
+    ) -> anyhow::Result<()> {
+        for (data, batch) in batches {
+            self.sink_dml(data, batch).await?;
+        }
+        Ok(())
+    }
+
     async fn sink_ddl(&mut self, mut _data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
         Ok(())
     }
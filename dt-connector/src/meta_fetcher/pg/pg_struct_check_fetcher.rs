@@ -261,6 +261,7 @@ impl PgStructCheckFetcher {
             value_type: PgValueType::String,
             oid: 0,
             parent_oid: 0,
+            base_oid: 0,
             element_oid: 0,
             category: String::new(),
             enum_values: None,
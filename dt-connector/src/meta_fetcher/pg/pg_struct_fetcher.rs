@@ -3,6 +3,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use anyhow::bail;
 use dt_common::meta::struct_meta::{
     statement::{
+        pg_create_domain_statement::PgCreateDomainStatement,
         pg_create_schema_statement::PgCreateSchemaStatement,
         pg_create_table_statement::PgCreateTableStatement,
     },
@@ -10,6 +11,7 @@ use dt_common::meta::struct_meta::{
         column::{Column, ColumnDefault},
         comment::{Comment, CommentType},
         constraint::{Constraint, ConstraintType},
+        domain::{Domain, DomainConstraint},
         index::{Index, IndexKind},
         schema::Schema,
         sequence::Sequence,
@@ -35,6 +37,16 @@ pub struct PgStructFetcher {
     pub filter: Option<RdbFilter>,
 }
 
+// settings which affect data representation and should match between source and target
+pub const RELEVANT_SERVER_SETTINGS: [&str; 6] = [
+    "default_toast_compression",
+    "TimeZone",
+    "DateStyle",
+    "IntervalStyle",
+    "extra_float_digits",
+    "bytea_output",
+];
+
 enum ColType {
     Text,
     Char,
@@ -46,6 +58,63 @@ impl PgStructFetcher {
         Ok(PgCreateSchemaStatement { schema })
     }
 
+    pub async fn get_create_domain_statements(
+        &mut self,
+    ) -> anyhow::Result<Vec<PgCreateDomainStatement>> {
+        // ordered by oid, base domains are created before domains derived from them
+        let sql = format!(
+            "SELECT t.typname AS domain_name,
+                format_type(t.typbasetype, t.typtypmod) AS base_type,
+                t.typdefault AS default_value,
+                t.typnotnull AS not_null,
+                c.conname AS constraint_name,
+                pg_get_constraintdef(c.oid) AS constraint_definition
+            FROM pg_type t
+            JOIN pg_namespace n
+                ON t.typnamespace = n.oid
+            LEFT JOIN pg_constraint c
+                ON c.contypid = t.oid AND c.contype = 'c'
+            WHERE t.typtype = 'd' AND n.nspname = '{}'
+            ORDER BY t.oid, c.conname",
+            self.schema
+        );
+
+        let mut results: Vec<PgCreateDomainStatement> = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let domain_name = Self::get_str_with_null(&row, "domain_name")?;
+            if results
+                .last()
+                .map_or(true, |i| i.domain.domain_name != domain_name)
+            {
+                results.push(PgCreateDomainStatement {
+                    domain: Domain {
+                        schema_name: self.schema.clone(),
+                        domain_name,
+                        base_type: Self::get_str_with_null(&row, "base_type")?,
+                        default_value: row.try_get("default_value")?,
+                        not_null: row.try_get("not_null")?,
+                        constraints: Vec::new(),
+                    },
+                });
+            }
+
+            let constraint_name: Option<String> = row.try_get("constraint_name")?;
+            if let Some(constraint_name) = constraint_name {
+                results
+                    .last_mut()
+                    .unwrap()
+                    .domain
+                    .constraints
+                    .push(DomainConstraint {
+                        constraint_name,
+                        definition: Self::get_str_with_null(&row, "constraint_definition")?,
+                    });
+            }
+        }
+        Ok(results)
+    }
+
     pub async fn get_create_table_statements(
         &mut self,
         tb: &str,
@@ -76,6 +145,27 @@ impl PgStructFetcher {
         Ok(results)
     }
 
+    pub async fn get_relevant_server_settings(&mut self) -> anyhow::Result<Vec<(String, String)>> {
+        let names = RELEVANT_SERVER_SETTINGS
+            .iter()
+            .map(|i| format!("'{}'", i))
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!(
+            "SELECT name, setting FROM pg_settings WHERE name IN ({}) ORDER BY name",
+            names
+        );
+
+        let mut results = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let name = Self::get_str_with_null(&row, "name")?;
+            let setting = Self::get_str_with_null(&row, "setting")?;
+            results.push((name, setting));
+        }
+        Ok(results)
+    }
+
     async fn get_schema(&mut self) -> anyhow::Result<Schema> {
         let sql = format!(
             "SELECT schema_name 
@@ -0,0 +1,241 @@
+use std::str::FromStr;
+
+use dt_common::{log_info, log_warn, meta::position::Position};
+use sqlx::{MySql, Pool, Postgres, Row};
+
+#[derive(Clone)]
+enum PositionStoreConnPool {
+    Mysql(Pool<MySql>),
+    Pg(Pool<Postgres>),
+}
+
+// keep checkpoint positions in a table of the target db, one row for each (channel, db, tb):
+// cdc positions are stored with empty db / tb, snapshot positions with the table they belong to.
+// positions are only saved after the data before them has been committed by sinkers,
+// so a restarted task resumes from a position that never skips uncommitted data
+#[derive(Clone)]
+pub struct DbPositionStore {
+    pub channel: String,
+    pub schema: String,
+    pub tb: String,
+    conn_pool: PositionStoreConnPool,
+}
+
+impl DbPositionStore {
+    pub async fn new_mysql(
+        conn_pool: Pool<MySql>,
+        channel: &str,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<Self> {
+        let me = Self {
+            channel: channel.into(),
+            schema: schema.into(),
+            tb: tb.into(),
+            conn_pool: PositionStoreConnPool::Mysql(conn_pool),
+        };
+        me.init().await?;
+        Ok(me)
+    }
+
+    pub async fn new_pg(
+        conn_pool: Pool<Postgres>,
+        channel: &str,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<Self> {
+        let me = Self {
+            channel: channel.into(),
+            schema: schema.into(),
+            tb: tb.into(),
+            conn_pool: PositionStoreConnPool::Pg(conn_pool),
+        };
+        me.init().await?;
+        Ok(me)
+    }
+
+    async fn init(&self) -> anyhow::Result<()> {
+        log_info!(
+            "position store: {}.{}, channel: {}",
+            self.schema,
+            self.tb,
+            self.channel
+        );
+        match &self.conn_pool {
+            PositionStoreConnPool::Mysql(conn_pool) => {
+                let db_sql = format!("CREATE DATABASE IF NOT EXISTS `{}`", self.schema);
+                let tb_sql = format!(
+                    "CREATE TABLE IF NOT EXISTS `{}`.`{}` (
+                        channel varchar(255) NOT NULL,
+                        db varchar(255) NOT NULL,
+                        tb varchar(255) NOT NULL,
+                        position_json text NOT NULL,
+                        updated_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                        PRIMARY KEY (channel, db, tb)
+                    )",
+                    self.schema, self.tb
+                );
+                sqlx::query(&db_sql).execute(conn_pool).await?;
+                sqlx::query(&tb_sql).execute(conn_pool).await?;
+            }
+
+            PositionStoreConnPool::Pg(conn_pool) => {
+                let schema_sql = format!(r#"CREATE SCHEMA IF NOT EXISTS "{}""#, self.schema);
+                let tb_sql = format!(
+                    r#"CREATE TABLE IF NOT EXISTS "{}"."{}" (
+                        channel varchar(255) NOT NULL,
+                        db varchar(255) NOT NULL,
+                        tb varchar(255) NOT NULL,
+                        position_json text NOT NULL,
+                        updated_at timestamp NOT NULL DEFAULT (now() at time zone 'utc'),
+                        PRIMARY KEY (channel, db, tb)
+                    )"#,
+                    self.schema, self.tb
+                );
+                sqlx::query(&schema_sql).execute(conn_pool).await?;
+                sqlx::query(&tb_sql).execute(conn_pool).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // called after sinkers committed all data before the position
+    pub async fn save(&self, position: &Position) -> anyhow::Result<()> {
+        if matches!(position, Position::None) {
+            return Ok(());
+        }
+
+        let (db, tb) = Self::get_db_tb(position);
+        let position_json = position.to_string();
+        match &self.conn_pool {
+            PositionStoreConnPool::Mysql(conn_pool) => {
+                let sql = format!(
+                    "INSERT INTO `{}`.`{}` (channel, db, tb, position_json, updated_at)
+                    VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)
+                    ON DUPLICATE KEY UPDATE position_json = VALUES(position_json), updated_at = CURRENT_TIMESTAMP",
+                    self.schema, self.tb
+                );
+                sqlx::query(&sql)
+                    .bind(&self.channel)
+                    .bind(&db)
+                    .bind(&tb)
+                    .bind(&position_json)
+                    .execute(conn_pool)
+                    .await?;
+            }
+
+            PositionStoreConnPool::Pg(conn_pool) => {
+                let sql = format!(
+                    r#"INSERT INTO "{}"."{}" (channel, db, tb, position_json, updated_at)
+                    VALUES ($1, $2, $3, $4, now() at time zone 'utc')
+                    ON CONFLICT (channel, db, tb)
+                    DO UPDATE SET position_json = EXCLUDED.position_json, updated_at = EXCLUDED.updated_at"#,
+                    self.schema, self.tb
+                );
+                sqlx::query(&sql)
+                    .bind(&self.channel)
+                    .bind(&db)
+                    .bind(&tb)
+                    .bind(&position_json)
+                    .execute(conn_pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn load(&self) -> anyhow::Result<Vec<Position>> {
+        let mut position_jsons = Vec::new();
+        match &self.conn_pool {
+            PositionStoreConnPool::Mysql(conn_pool) => {
+                let sql = format!(
+                    "SELECT position_json FROM `{}`.`{}` WHERE channel = ?",
+                    self.schema, self.tb
+                );
+                let rows = sqlx::query(&sql)
+                    .bind(&self.channel)
+                    .fetch_all(conn_pool)
+                    .await?;
+                for row in rows {
+                    position_jsons.push(row.try_get::<String, _>("position_json")?);
+                }
+            }
+
+            PositionStoreConnPool::Pg(conn_pool) => {
+                let sql = format!(
+                    r#"SELECT position_json FROM "{}"."{}" WHERE channel = $1"#,
+                    self.schema, self.tb
+                );
+                let rows = sqlx::query(&sql)
+                    .bind(&self.channel)
+                    .fetch_all(conn_pool)
+                    .await?;
+                for row in rows {
+                    position_jsons.push(row.try_get::<String, _>("position_json")?);
+                }
+            }
+        }
+
+        let mut positions = Vec::new();
+        for position_json in position_jsons {
+            match Position::from_str(&position_json) {
+                Ok(position) => {
+                    log_info!("position store, loaded position: {}", position_json);
+                    positions.push(position)
+                }
+                Err(_) => log_warn!("position store, invalid position: {}", position_json),
+            }
+        }
+        Ok(positions)
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        match &self.conn_pool {
+            PositionStoreConnPool::Mysql(conn_pool) => conn_pool.close().await,
+            PositionStoreConnPool::Pg(conn_pool) => conn_pool.close().await,
+        }
+        Ok(())
+    }
+
+    // snapshot positions are stored for each table, others share the row with empty db / tb
+    pub fn get_db_tb(position: &Position) -> (String, String) {
+        match position {
+            Position::RdbSnapshot { schema, tb, .. }
+            | Position::RdbSnapshotFinished { schema, tb, .. }
+            | Position::FoxlakeS3 { schema, tb, .. } => (schema.clone(), tb.clone()),
+            _ => (String::new(), String::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_db_tb() {
+        let position = Position::RdbSnapshot {
+            db_type: "mysql".into(),
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            order_col: "id".into(),
+            value: "1".into(),
+        };
+        assert_eq!(
+            DbPositionStore::get_db_tb(&position),
+            ("db_1".to_string(), "tb_1".to_string())
+        );
+
+        let position = Position::MysqlCdc {
+            server_id: "1".into(),
+            binlog_filename: "mysql-bin.000001".into(),
+            next_event_position: 4,
+            gtid_set: String::new(),
+            timestamp: String::new(),
+        };
+        assert_eq!(
+            DbPositionStore::get_db_tb(&position),
+            (String::new(), String::new())
+        );
+    }
+}
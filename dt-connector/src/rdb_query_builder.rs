@@ -28,6 +28,14 @@ pub struct RdbQueryBuilder<'a> {
     ignore_cols: Option<&'a HashSet<String>>,
     pg_tb_meta: Option<&'a PgTbMeta>,
     mysql_tb_meta: Option<&'a MysqlTbMeta>,
+    // append COLLATE of target columns in ON CONFLICT ... DO UPDATE SET
+    apply_collations: bool,
+    // append ON CONFLICT ON CONSTRAINT ... DO NOTHING in inserts for pg tables with EXCLUDE constraints
+    ignore_exclude_conflicts: bool,
+    // target of ON CONFLICT (...) DO UPDATE in pg upserts, id_cols if None
+    conflict_cols: Option<&'a Vec<String>>,
+    // append ON CONFLICT (...) DO NOTHING in pg inserts
+    conflict_do_nothing: bool,
 }
 
 impl RdbQueryBuilder<'_> {
@@ -42,6 +50,10 @@ impl RdbQueryBuilder<'_> {
             mysql_tb_meta: Some(tb_meta),
             db_type: DbType::Mysql,
             ignore_cols,
+            apply_collations: false,
+            ignore_exclude_conflicts: false,
+            conflict_cols: None,
+            conflict_do_nothing: false,
         }
     }
 
@@ -56,9 +68,31 @@ impl RdbQueryBuilder<'_> {
             mysql_tb_meta: None,
             db_type: DbType::Pg,
             ignore_cols,
+            apply_collations: false,
+            ignore_exclude_conflicts: false,
+            conflict_cols: None,
+            conflict_do_nothing: false,
         }
     }
 
+    #[inline(always)]
+    pub fn with_collations(mut self, apply_collations: bool) -> Self {
+        self.apply_collations = apply_collations;
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_exclude_conflicts_ignored(mut self, ignore_exclude_conflicts: bool) -> Self {
+        self.ignore_exclude_conflicts = ignore_exclude_conflicts;
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_conflict_do_nothing(mut self, conflict_do_nothing: bool) -> Self {
+        self.conflict_do_nothing = conflict_do_nothing;
+        self
+    }
+
     #[inline(always)]
     pub fn create_mysql_query<'a>(
         &self,
@@ -111,7 +145,9 @@ impl RdbQueryBuilder<'_> {
                 if replace {
                     self.get_replace_query(row_data, placeholder)
                 } else {
-                    self.get_insert_query(row_data, placeholder)
+                    let mut query_info = self.get_insert_query(row_data, placeholder)?;
+                    query_info.sql = self.append_conflict_clause(query_info.sql);
+                    Ok(query_info)
                 }
             }
             RowType::Update => self.get_update_query(row_data, placeholder),
@@ -175,9 +211,10 @@ impl RdbQueryBuilder<'_> {
         let mut malloc_size = 0;
         let mut placeholder_index = 1;
         let mut row_values = Vec::new();
+        let insert_cols = self.get_insert_cols();
         for _ in 0..batch_size {
             let mut col_values = Vec::new();
-            for col in self.rdb_tb_meta.cols.iter() {
+            for col in insert_cols.iter() {
                 col_values.push(self.get_placeholder(placeholder_index, col)?);
                 placeholder_index += 1;
             }
@@ -188,7 +225,7 @@ impl RdbQueryBuilder<'_> {
             "INSERT INTO {}.{}({}) VALUES{}",
             self.escape(&self.rdb_tb_meta.schema),
             self.escape(&self.rdb_tb_meta.tb),
-            self.escape_cols(&self.rdb_tb_meta.cols).join(","),
+            self.escape_cols(&insert_cols).join(","),
             row_values.join(",")
         );
 
@@ -197,7 +234,7 @@ impl RdbQueryBuilder<'_> {
         for row_data in data.iter().skip(start_index).take(batch_size) {
             malloc_size += row_data.data_size;
             let after = row_data.after.as_ref().unwrap();
-            for col_name in self.rdb_tb_meta.cols.iter() {
+            for col_name in insert_cols.iter() {
                 cols.push(col_name.clone());
                 binds.push(after.get(col_name));
             }
@@ -206,9 +243,137 @@ impl RdbQueryBuilder<'_> {
         if replace && self.mysql_tb_meta.is_some() {
             sql = format!("REPLACE{}", sql.trim_start_matches("INSERT"));
         }
+        if !replace {
+            sql = self.append_conflict_clause(sql);
+        }
         Ok((RdbQueryInfo { sql, cols, binds }, malloc_size))
     }
 
+    // INSERT ... ON DUPLICATE KEY UPDATE for mysql, if json_merge, JSON columns are merged by
+    // json_col=JSON_MERGE_PATCH(json_col, VALUES(json_col)), otherwise replaced by json_col=VALUES(json_col),
+    // AUTO_INCREMENT and generated columns are kept unchanged
+    pub fn get_batch_upsert_query<'a>(
+        &self,
+        data: &'a [RowData],
+        start_index: usize,
+        batch_size: usize,
+        json_merge: bool,
+    ) -> anyhow::Result<(RdbQueryInfo<'a>, usize)> {
+        let (mut query_info, malloc_size) =
+            self.get_batch_insert_query(data, start_index, batch_size, false)?;
+
+        let mut set_pairs = Vec::new();
+        for col in self.rdb_tb_meta.cols.iter() {
+            if self.rdb_tb_meta.id_cols.contains(col) || self.is_mysql_auto_col(col) {
+                continue;
+            }
+            let escaped_col = self.escape(col);
+            if json_merge && self.is_mysql_json_col(col) {
+                set_pairs.push(format!(
+                    "{0}=JSON_MERGE_PATCH({0},VALUES({0}))",
+                    escaped_col
+                ));
+            } else {
+                set_pairs.push(format!("{0}=VALUES({0})", escaped_col));
+            }
+        }
+
+        // all columns are keys
+        if set_pairs.is_empty() {
+            for col in self.rdb_tb_meta.id_cols.iter() {
+                set_pairs.push(format!("{0}=VALUES({0})", self.escape(col)));
+            }
+        }
+
+        query_info.sql = format!(
+            "{} ON DUPLICATE KEY UPDATE {}",
+            query_info.sql,
+            set_pairs.join(",")
+        );
+        Ok((query_info, malloc_size))
+    }
+
+    pub fn is_mysql_json_col(&self, col: &str) -> bool {
+        self.mysql_tb_meta.is_some_and(|tb_meta| {
+            matches!(tb_meta.col_type_map.get(col), Some(MysqlColType::Json))
+        })
+    }
+
+    fn is_mysql_auto_col(&self, col: &str) -> bool {
+        self.mysql_tb_meta.is_some_and(|tb_meta| {
+            tb_meta.auto_increment_col.as_deref() == Some(col)
+                || tb_meta.generated_cols.contains(col)
+        })
+    }
+
+    // generated columns are computed by the database, they can not be inserted or updated
+    fn get_insert_cols(&self) -> Vec<String> {
+        self.rdb_tb_meta
+            .cols
+            .iter()
+            .filter(|col| !self.is_pg_generated_col(col) && !self.is_mysql_generated_col(col))
+            .cloned()
+            .collect()
+    }
+
+    fn is_mysql_generated_col(&self, col: &str) -> bool {
+        self.mysql_tb_meta
+            .is_some_and(|tb_meta| tb_meta.generated_cols.contains(col))
+    }
+
+    fn is_pg_generated_col(&self, col: &str) -> bool {
+        self.pg_tb_meta
+            .is_some_and(|tb_meta| tb_meta.generated_cols.contains_key(col))
+    }
+
+    fn get_pg_collation(&self, col: &str) -> Option<&String> {
+        if !self.apply_collations {
+            return None;
+        }
+        self.pg_tb_meta
+            .and_then(|tb_meta| tb_meta.col_collations.get(col))
+    }
+
+    fn append_conflict_clause(&self, sql: String) -> String {
+        if !self.conflict_do_nothing {
+            return self.append_exclude_conflict_clause(sql);
+        }
+
+        let has_exclude_constraints = self.ignore_exclude_conflicts
+            && self
+                .pg_tb_meta
+                .is_some_and(|tb_meta| !tb_meta.exclude_constraints.is_empty());
+        // without conflict target, conflicts on any constraint are ignored
+        if has_exclude_constraints || self.rdb_tb_meta.key_map.is_empty() {
+            return format!("{} ON CONFLICT DO NOTHING", sql);
+        }
+        let conflict_cols = self.conflict_cols.unwrap_or(&self.rdb_tb_meta.id_cols);
+        format!(
+            "{} ON CONFLICT ({}) DO NOTHING",
+            sql,
+            self.escape_cols(conflict_cols).join(",")
+        )
+    }
+
+    // EXCLUDE constraints can only be arbiters of ON CONFLICT ... DO NOTHING, if there are
+    // multiple, conflicts on any constraint (including primary/unique keys) are ignored
+    fn append_exclude_conflict_clause(&self, sql: String) -> String {
+        if !self.ignore_exclude_conflicts {
+            return sql;
+        }
+        match self.pg_tb_meta.map(|tb_meta| &tb_meta.exclude_constraints) {
+            Some(constraints) if constraints.len() == 1 => format!(
+                "{} ON CONFLICT ON CONSTRAINT {} DO NOTHING",
+                sql,
+                self.escape(&constraints[0].name)
+            ),
+            Some(constraints) if constraints.len() > 1 => {
+                format!("{} ON CONFLICT DO NOTHING", sql)
+            }
+            _ => sql,
+        }
+    }
+
     fn get_replace_query<'a>(
         &self,
         row_data: &'a RowData,
@@ -216,14 +381,18 @@ impl RdbQueryBuilder<'_> {
     ) -> anyhow::Result<RdbQueryInfo<'a>> {
         let mut query_info = self.get_insert_query(row_data, placeholder)?;
         if self.db_type == DbType::Pg {
+            let conflict_cols = self.conflict_cols.unwrap_or(&self.rdb_tb_meta.id_cols);
             let mut index = query_info.cols.len() + 1;
             let after = row_data.after.as_ref().unwrap();
             let mut set_pairs = Vec::new();
             for col in self.rdb_tb_meta.cols.iter() {
-                if self.rdb_tb_meta.id_cols.contains(col) {
+                if conflict_cols.contains(col) || self.is_pg_generated_col(col) {
                     continue;
                 }
-                let sql_value = self.get_sql_value(index, col, &after.get(col), placeholder)?;
+                let mut sql_value = self.get_sql_value(index, col, &after.get(col), placeholder)?;
+                if let Some(collation) = self.get_pg_collation(col) {
+                    sql_value = format!(r#"{} COLLATE "{}""#, sql_value, collation);
+                }
                 let set_pair = format!(r#""{}"={}"#, col, sql_value);
                 set_pairs.push(set_pair);
                 query_info.cols.push(col.clone());
@@ -234,7 +403,7 @@ impl RdbQueryBuilder<'_> {
             query_info.sql = format!(
                 "{} ON CONFLICT ({}) DO UPDATE SET {}",
                 query_info.sql,
-                SqlUtil::escape_cols(&self.rdb_tb_meta.id_cols, &self.db_type).join(","),
+                SqlUtil::escape_cols(conflict_cols, &self.db_type).join(","),
                 set_pairs.join(",")
             );
             return Ok(query_info);
@@ -252,15 +421,14 @@ impl RdbQueryBuilder<'_> {
         let mut cols = Vec::new();
         let mut binds = Vec::new();
         let after = row_data.after.as_ref().unwrap();
-        for col_name in self.rdb_tb_meta.cols.iter() {
-            cols.push(col_name.clone());
-            binds.push(after.get(col_name));
+        for col_name in self.get_insert_cols() {
+            binds.push(after.get(&col_name));
+            cols.push(col_name);
         }
 
         let mut col_values = Vec::new();
-        for i in 0..self.rdb_tb_meta.cols.len() {
-            let sql_value =
-                self.get_sql_value(i + 1, &self.rdb_tb_meta.cols[i], &binds[i], placeholder)?;
+        for i in 0..cols.len() {
+            let sql_value = self.get_sql_value(i + 1, &cols[i], &binds[i], placeholder)?;
             col_values.push(sql_value);
         }
 
@@ -268,7 +436,7 @@ impl RdbQueryBuilder<'_> {
             "INSERT INTO {}.{}({}) VALUES({})",
             self.escape(&self.rdb_tb_meta.schema),
             self.escape(&self.rdb_tb_meta.tb),
-            self.escape_cols(&self.rdb_tb_meta.cols).join(","),
+            self.escape_cols(&cols).join(","),
             col_values.join(",")
         );
 
@@ -313,6 +481,9 @@ impl RdbQueryBuilder<'_> {
         let mut set_cols = Vec::new();
         let mut set_pairs = Vec::new();
         for (col, _) in after.iter() {
+            if self.is_pg_generated_col(col) {
+                continue;
+            }
             set_cols.push(col.clone());
             let sql_value = self.get_sql_value(index, col, &after.get(col), placeholder)?;
             set_pairs.push(format!("{}={}", self.escape(col), sql_value));
@@ -350,6 +521,111 @@ impl RdbQueryBuilder<'_> {
         Ok(RdbQueryInfo { sql, cols, binds })
     }
 
+    // cols to be updated by UPDATE ... FROM (VALUES ...), id_cols and generated cols are excluded
+    pub fn get_batch_update_set_cols(&self, row_data: &RowData) -> Vec<String> {
+        let mut set_cols: Vec<String> = row_data
+            .after
+            .as_ref()
+            .map(|after| {
+                after
+                    .keys()
+                    .filter(|col| {
+                        !self.rdb_tb_meta.id_cols.contains(col) && !self.is_pg_generated_col(col)
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        set_cols.sort();
+        set_cols
+    }
+
+    // UPDATE t SET c=v.c FROM (VALUES (...),(...)) AS v(k,c) WHERE t.k=v.k, for pg,
+    // rows should have the same set cols and no key changes, keys are taken from before
+    pub fn get_batch_update_query<'a>(
+        &self,
+        data: &[&'a RowData],
+    ) -> anyhow::Result<(RdbQueryInfo<'a>, usize)> {
+        let id_cols = &self.rdb_tb_meta.id_cols;
+        let set_cols = self.get_batch_update_set_cols(data[0]);
+        if set_cols.is_empty() {
+            bail! {Error::Unexpected(format!(
+                "schema: {}, tb: {}, no cols to be updated in batch update",
+                self.rdb_tb_meta.schema, self.rdb_tb_meta.tb
+            ))}
+        }
+
+        let mut value_cols = id_cols.clone();
+        value_cols.extend(set_cols.iter().cloned());
+
+        let mut malloc_size = 0;
+        let mut placeholder_index = 1;
+        let mut row_values = Vec::new();
+        let mut cols = Vec::new();
+        let mut binds = Vec::new();
+        for row_data in data.iter() {
+            malloc_size += row_data.data_size;
+            let before = row_data.before.as_ref().unwrap();
+            let after = row_data.after.as_ref().unwrap();
+            let mut placeholders = Vec::new();
+            for col in value_cols.iter() {
+                placeholders.push(self.get_placeholder(placeholder_index, col)?);
+                placeholder_index += 1;
+                cols.push(col.clone());
+                if id_cols.contains(col) {
+                    binds.push(before.get(col));
+                } else {
+                    binds.push(after.get(col));
+                }
+            }
+            row_values.push(format!("({})", placeholders.join(",")));
+        }
+
+        let set_pairs: Vec<String> = set_cols
+            .iter()
+            .map(|col| format!("{0}=v.{0}", self.escape(col)))
+            .collect();
+        let where_pairs: Vec<String> = id_cols
+            .iter()
+            .map(|col| format!("t.{0}=v.{0}", self.escape(col)))
+            .collect();
+        let sql = format!(
+            "UPDATE {}.{} AS t SET {} FROM (VALUES{}) AS v({}) WHERE {}",
+            self.escape(&self.rdb_tb_meta.schema),
+            self.escape(&self.rdb_tb_meta.tb),
+            set_pairs.join(","),
+            row_values.join(","),
+            self.escape_cols(&value_cols).join(","),
+            where_pairs.join(" AND ")
+        );
+        Ok((RdbQueryInfo { sql, cols, binds }, malloc_size))
+    }
+
+    // SELECT fn($1::type, $2::type, ...), all columns are passed in column order,
+    // values are taken from after for insert/update, and from before for delete
+    pub fn get_pg_function_query<'a>(
+        &self,
+        row_data: &'a RowData,
+        function_name: &str,
+    ) -> anyhow::Result<RdbQueryInfo<'a>> {
+        let col_values = match row_data.row_type {
+            RowType::Delete => row_data.before.as_ref().unwrap(),
+            _ => row_data.after.as_ref().unwrap(),
+        };
+
+        let mut cols = Vec::new();
+        let mut binds = Vec::new();
+        let mut placeholders = Vec::new();
+        for (i, col_name) in self.rdb_tb_meta.cols.iter().enumerate() {
+            cols.push(col_name.clone());
+            binds.push(col_values.get(col_name));
+            placeholders.push(self.get_placeholder(i + 1, col_name)?);
+        }
+
+        let sql = format!("SELECT {}({})", function_name, placeholders.join(","));
+        Ok(RdbQueryInfo { sql, cols, binds })
+    }
+
     pub fn get_select_query<'a>(&self, row_data: &'a RowData) -> anyhow::Result<RdbQueryInfo<'a>> {
         let after = row_data.after.as_ref().unwrap();
         let (where_sql, not_null_cols) = self.get_where_info(1, after, true)?;
@@ -586,3 +862,11 @@ impl RdbQueryBuilder<'_> {
         SqlUtil::escape_cols(cols, &self.db_type)
     }
 }
+
+impl<'a> RdbQueryBuilder<'a> {
+    #[inline(always)]
+    pub fn with_conflict_cols(mut self, conflict_cols: Option<&'a Vec<String>>) -> Self {
+        self.conflict_cols = conflict_cols;
+        self
+    }
+}
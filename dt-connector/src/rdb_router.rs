@@ -1,35 +1,56 @@
-use anyhow::Ok;
+use anyhow::{bail, Ok};
 use dt_common::{
     config::{
         config_enums::DbType, config_token_parser::ConfigTokenParser, router_config::RouterConfig,
     },
+    error::Error,
     meta::{
         ddl_meta::{ddl_data::DdlData, ddl_statement::DdlStatement},
         struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
     },
-    utils::sql_util::SqlUtil,
+    utils::{hash_util::HashUtil, name_pattern::NamePattern, sql_util::SqlUtil},
+};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
 };
-use std::collections::HashMap;
 
-use dt_common::meta::{col_value::ColValue, row_data::RowData};
+use dt_common::meta::{col_value::ColValue, row_data::RowData, row_type::RowType};
 use serde::{Deserialize, Serialize};
 
 type SchemaMap = HashMap<String, String>;
 type TbMap = HashMap<(String, String), (String, String)>;
 type TbColMap = HashMap<(String, String), HashMap<String, String>>;
+type TbPatternMap = Vec<TbPatternRoute>;
+type TbShardMap = HashMap<(String, String), ColumnHashRouter>;
 
 const JSON_PREFIX: &str = "json:";
+const SHARD_PLACEHOLDER: &str = "{shard}";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RdbRouter {
     // HashMap<src_schema, dst_schema>
     pub schema_map: SchemaMap,
     // HashMap<(src_schema, src_tb), (dst_schema, dst_tb)>
     pub tb_map: TbMap,
+    // tb_map entries with wildcard / regex source, checked in config order,
+    // compiled patterns are not serialized
+    #[serde(skip)]
+    pub tb_pattern_map: TbPatternMap,
     // HashMap<(src_schema, src_tb), HashMap<src_col, dst_col>>
     pub col_map: TbColMap,
     // HashMap<(src_schema, src_tb), String>
     pub topic_map: HashMap<(String, String), String>,
+    // if not empty, rows routed by tb_map or merged by schema_map get this column with
+    // value: src_schema.src_tb
+    #[serde(default)]
+    pub src_id_col: String,
+    // dst schemas mapped from many src schemas by schema_map
+    #[serde(skip)]
+    pub merged_schemas: HashSet<String>,
+    // HashMap<(src_schema, src_tb), ColumnHashRouter>
+    #[serde(default)]
+    pub shard_map: TbShardMap,
 }
 
 impl RdbRouter {
@@ -40,21 +61,50 @@ impl RdbRouter {
                 tb_map,
                 col_map,
                 topic_map,
+                src_id_col,
+                shard_map,
             } => {
                 let schema_map = Self::parse_schema_map(schema_map, db_type)?;
-                let tb_map = Self::parse_tb_map(tb_map, db_type)?;
+                let (tb_map, tb_pattern_map) = Self::parse_tb_map(tb_map, db_type)?;
                 let col_map = Self::parse_col_map(col_map)?;
                 let topic_map = Self::parse_topic_map(topic_map, db_type)?;
+                let merged_schemas = Self::get_merged_schemas(&schema_map);
+                let shard_map = Self::parse_shard_map(shard_map)?;
                 Ok(Self {
                     schema_map,
                     tb_map,
+                    tb_pattern_map,
                     col_map,
                     topic_map,
+                    src_id_col: src_id_col.trim().to_string(),
+                    merged_schemas,
+                    shard_map,
                 })
             }
         }
     }
 
+    fn get_merged_schemas(schema_map: &SchemaMap) -> HashSet<String> {
+        let mut dst_schemas = HashSet::new();
+        let mut merged_schemas = HashSet::new();
+        for dst_schema in schema_map.values() {
+            if !dst_schemas.insert(dst_schema) {
+                merged_schemas.insert(dst_schema.to_owned());
+            }
+        }
+        merged_schemas
+    }
+
+    // rows of many source tables may be merged into one target table by tb_map,
+    // or by schema_map if many source schemas are mapped to the same target schema
+    fn is_merged(&self, schema: &str, tb: &str) -> bool {
+        self.get_tb_map_entry(schema, tb).is_some()
+            || self
+                .schema_map
+                .get(schema)
+                .is_some_and(|dst_schema| self.merged_schemas.contains(dst_schema))
+    }
+
     pub fn get_schema_map<'a>(&'a self, schema: &'a str) -> &'a str {
         if let Some(dst_schema) = self.schema_map.get(schema) {
             return dst_schema;
@@ -62,14 +112,35 @@ impl RdbRouter {
         schema
     }
 
-    pub fn get_tb_map<'a>(&'a self, schema: &'a str, tb: &'a str) -> (&'a str, &'a str) {
-        if let Some((dst_schema, dst_tb)) = self.tb_map.get(&(schema.into(), tb.into())) {
-            return (dst_schema, dst_tb);
+    // precedence: exact tb_map > wildcard / regex tb_map in config order > db_map
+    pub fn get_tb_map<'a>(&'a self, schema: &'a str, tb: &'a str) -> (Cow<'a, str>, Cow<'a, str>) {
+        if let Some(dst) = self.get_tb_map_entry(schema, tb) {
+            return dst;
         }
         if let Some(dst_schema) = self.schema_map.get(schema) {
-            return (dst_schema, tb);
+            return (Cow::Borrowed(dst_schema), Cow::Borrowed(tb));
+        }
+        (Cow::Borrowed(schema), Cow::Borrowed(tb))
+    }
+
+    // target of the table if it matches an entry of tb_map, exact or pattern
+    fn get_tb_map_entry<'a>(
+        &'a self,
+        schema: &str,
+        tb: &str,
+    ) -> Option<(Cow<'a, str>, Cow<'a, str>)> {
+        if let Some((dst_schema, dst_tb)) = self.tb_map.get(&(schema.into(), tb.into())) {
+            return Some((Cow::Borrowed(dst_schema), Cow::Borrowed(dst_tb)));
+        }
+        for route in self.tb_pattern_map.iter() {
+            if route.schema.is_match(schema) && route.tb.is_match(tb) {
+                return Some((
+                    route.schema.replace(schema, &route.dst_schema),
+                    route.tb.replace(tb, &route.dst_tb),
+                ));
+            }
         }
-        (schema, tb)
+        None
     }
 
     pub fn get_col_map(&self, schema: &str, tb: &str) -> Option<&HashMap<String, String>> {
@@ -88,7 +159,20 @@ impl RdbRouter {
         self.topic_map.get(&("*".into(), "*".into())).unwrap()
     }
 
-    pub fn reverse(&self) -> Self {
+    // maps routed names back to the source names, tables routed by wildcard / regex
+    // tb_map entries can not be mapped back by their names, so such routers are rejected
+    pub fn reverse(&self) -> anyhow::Result<Self> {
+        if !self.tb_pattern_map.is_empty() {
+            bail! {Error::ConfigError(
+                "config [router] tb_map with wildcard / regex sources is not supported by this task, since routed tables can not be mapped back to the source tables".into()
+            )}
+        }
+        if !self.shard_map.is_empty() {
+            bail! {Error::ConfigError(
+                "config [router] shard_map is not supported by this task, since shard tables can not be mapped back to the source tables".into()
+            )}
+        }
+
         let mut reverse_schema_map = HashMap::new();
         let mut reverse_tb_map = HashMap::new();
         let mut reverse_tb_col_map = HashMap::new();
@@ -98,10 +182,12 @@ impl RdbRouter {
             for (src_col, dst_col) in col_map.iter() {
                 reverse_col_map.insert(dst_col.into(), src_col.into());
             }
-            let dst_tb = self.tb_map.get(src_schema_tb).unwrap();
-            reverse_tb_col_map.insert(dst_tb.clone(), reverse_col_map);
+            // columns may be renamed without renaming the table
+            let (dst_schema, dst_tb) = self.get_tb_map(&src_schema_tb.0, &src_schema_tb.1);
+            reverse_tb_col_map.insert((dst_schema.into(), dst_tb.into()), reverse_col_map);
         }
 
+        // if many tables are merged into one target, the target is reversed to any one of them
         for (src_tb, dst_tb) in self.tb_map.iter() {
             reverse_tb_map.insert(dst_tb.to_owned(), src_tb.to_owned());
         }
@@ -110,13 +196,36 @@ impl RdbRouter {
             reverse_schema_map.insert(dst_db.to_owned(), src_schema.to_owned());
         }
 
-        Self {
+        Ok(Self {
             schema_map: reverse_schema_map,
             tb_map: reverse_tb_map,
+            tb_pattern_map: Vec::new(),
             col_map: reverse_tb_col_map,
             // topic_map should not be reversed
             topic_map: self.topic_map.clone(),
+            src_id_col: String::new(),
+            merged_schemas: HashSet::new(),
+            shard_map: HashMap::new(),
+        })
+    }
+
+    // route_row + shard routing, an update changing the shard of a row is routed as
+    // a delete in the old shard table and an insert in the new one
+    pub fn route_dml(&self, row_data: RowData) -> anyhow::Result<Vec<RowData>> {
+        let shard_router = self
+            .shard_map
+            .get(&(row_data.schema.clone(), row_data.tb.clone()));
+        if shard_router.is_none() {
+            return Ok(vec![self.route_row(row_data)]);
         }
+
+        let mut results = Vec::new();
+        for (row_data, shard_tb) in shard_router.unwrap().route(row_data)? {
+            let mut row_data = self.route_row(row_data);
+            row_data.tb = shard_tb;
+            results.push(row_data);
+        }
+        Ok(results)
     }
 
     pub fn route_row(&self, mut row_data: RowData) -> RowData {
@@ -126,6 +235,17 @@ impl RdbRouter {
         row_data.schema = dst_schema.to_string();
         row_data.tb = dst_tb.to_string();
 
+        // source id, rows of many source tables may be merged into one target table
+        if !self.src_id_col.is_empty() && self.is_merged(&schema, &tb) {
+            let src_id = ColValue::String(format!("{}.{}", schema, tb));
+            for col_values in [row_data.before.as_mut(), row_data.after.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                col_values.insert(self.src_id_col.clone(), src_id.clone());
+            }
+        }
+
         // col map
         let col_map = self.get_col_map(&schema, &tb);
         if col_map.is_none() {
@@ -191,7 +311,7 @@ impl RdbRouter {
             StructStatement::MysqlCreateTable(s) => {
                 let (schema, tb) = (s.table.database_name.clone(), s.table.table_name.clone());
                 let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
-                s.route(dst_schema, dst_tb)
+                s.route(&dst_schema, &dst_tb)
             }
 
             StructStatement::MysqlCreateDatabase(s) => {
@@ -202,7 +322,7 @@ impl RdbRouter {
             StructStatement::PgCreateTable(s) => {
                 let (schema, tb) = (s.table.schema_name.clone(), s.table.table_name.clone());
                 let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
-                s.route(dst_schema, dst_tb)
+                s.route(&dst_schema, &dst_tb)
             }
 
             StructStatement::PgCreateSchema(s) => {
@@ -210,6 +330,41 @@ impl RdbRouter {
                 s.route(&dst_schema)
             }
 
+            StructStatement::PgCreateDomain(s) => {
+                let dst_schema = self.get_schema_map(&s.domain.schema_name).to_string();
+                s.route(&dst_schema)
+            }
+
+            StructStatement::MysqlCreateIndex(s) => {
+                let (schema, tb) = (s.index.database_name.clone(), s.index.table_name.clone());
+                let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
+                s.route(&dst_schema, &dst_tb)
+            }
+
+            StructStatement::PgCreateIndex(s) => {
+                let (schema, tb) = (s.index.schema_name.clone(), s.index.table_name.clone());
+                let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
+                s.route(&dst_schema, &dst_tb)
+            }
+
+            StructStatement::MysqlAddConstraint(s) => {
+                let (schema, tb) = (
+                    s.constraint.database_name.clone(),
+                    s.constraint.table_name.clone(),
+                );
+                let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
+                s.route(&dst_schema, &dst_tb)
+            }
+
+            StructStatement::PgAddConstraint(s) => {
+                let (schema, tb) = (
+                    s.constraint.schema_name.clone(),
+                    s.constraint.table_name.clone(),
+                );
+                let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
+                s.route(&dst_schema, &dst_tb)
+            }
+
             _ => {}
         }
 
@@ -229,19 +384,64 @@ impl RdbRouter {
         Ok(schema_map)
     }
 
-    fn parse_tb_map(config_str: &str, db_type: &DbType) -> anyhow::Result<TbMap> {
-        // tb_map=src_db_1.src_tb_1:dst_db_1.dst_tb_1,src_db_2.src_tb_2:dst_db_2.dst_tb_2
+    fn parse_tb_map(config_str: &str, db_type: &DbType) -> anyhow::Result<(TbMap, TbPatternMap)> {
         let mut tb_map = HashMap::new();
-        let tokens = Self::parse_config(config_str, db_type)?;
+        let mut tb_pattern_map = Vec::new();
+
+        // tb_map=json:[{"db":"db_1","tb":"orders_(\\d+)","dst_db":"db_1","dst_tb":"orders_bak_${1}"}]
+        if config_str.trim().starts_with(JSON_PREFIX) {
+            #[derive(Serialize, Deserialize)]
+            struct TbPatternRouteType {
+                db: String,
+                tb: String,
+                dst_db: String,
+                dst_tb: String,
+            }
+            let config: Vec<TbPatternRouteType> =
+                serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+            for i in config {
+                tb_pattern_map.push(TbPatternRoute {
+                    schema: NamePattern::from_regex(&i.db)?,
+                    tb: NamePattern::from_regex(&i.tb)?,
+                    dst_schema: i.dst_db,
+                    dst_tb: i.dst_tb,
+                });
+            }
+            return Ok((tb_map, tb_pattern_map));
+        }
+
+        // tb_map=src_db_1.src_tb_1:dst_db_1.dst_tb_1,src_db_2.orders_*:dst_db_2.orders
+        let escape_pairs = SqlUtil::get_escape_pairs(db_type);
+        let delimiters = vec![',', '.', ':'];
+        let tokens = ConfigTokenParser::parse_config(config_str, db_type, &delimiters)?;
+        let unescape = |token: &str| {
+            let mut token = token.to_string();
+            for escape_pair in escape_pairs.iter() {
+                token = SqlUtil::unescape(&token, escape_pair);
+            }
+            token
+        };
         let mut i = 0;
         while i < tokens.len() {
-            tb_map.insert(
-                (tokens[i].to_string(), tokens[i + 1].to_string()),
-                (tokens[i + 2].to_string(), tokens[i + 3].to_string()),
-            );
+            let schema = NamePattern::from_token(&tokens[i], &escape_pairs)?;
+            let tb = NamePattern::from_token(&tokens[i + 1], &escape_pairs)?;
+            let (dst_schema, dst_tb) = (unescape(&tokens[i + 2]), unescape(&tokens[i + 3]));
+            if schema.is_exact() && tb.is_exact() {
+                tb_map.insert(
+                    (unescape(&tokens[i]), unescape(&tokens[i + 1])),
+                    (dst_schema, dst_tb),
+                );
+            } else {
+                tb_pattern_map.push(TbPatternRoute {
+                    schema,
+                    tb,
+                    dst_schema,
+                    dst_tb,
+                });
+            }
             i += 4;
         }
-        Ok(tb_map)
+        Ok((tb_map, tb_pattern_map))
     }
 
     fn parse_topic_map(
@@ -283,6 +483,30 @@ impl RdbRouter {
         Ok(results)
     }
 
+    fn parse_shard_map(config_str: &str) -> anyhow::Result<TbShardMap> {
+        let mut results = TbShardMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct TbShardMapType {
+            db: String,
+            tb: String,
+            shard_col: String,
+            shard_count: u32,
+            dst_tb: String,
+        }
+        // shard_map=json:[{"db":"db_1","tb":"events","shard_col":"user_id","shard_count":4,"dst_tb":"events_{shard}"}]
+        let config: Vec<TbShardMapType> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        for i in config {
+            let router = ColumnHashRouter::new(&i.shard_col, i.shard_count, &i.dst_tb)?;
+            results.insert((i.db, i.tb), router);
+        }
+        Ok(results)
+    }
+
     fn parse_config(config_str: &str, db_type: &DbType) -> anyhow::Result<Vec<String>> {
         let delimiters = vec![',', '.', ':'];
         let tokens = ConfigTokenParser::parse_config(config_str, db_type, &delimiters)?;
@@ -299,13 +523,137 @@ impl RdbRouter {
     }
 }
 
+// a tb_map entry whose source database / table is a wildcard or regex pattern
+#[derive(Debug, Clone)]
+pub struct TbPatternRoute {
+    pub schema: NamePattern,
+    pub tb: NamePattern,
+    pub dst_schema: String,
+    pub dst_tb: String,
+}
+
+// route rows to shard tables by hash of a column value, e.g.
+// shard_col: user_id, shard_count: 4, target_table_template: events_{shard}
+// -> events_0, events_1, events_2, events_3
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnHashRouter {
+    pub shard_col: String,
+    pub shard_count: u32,
+    pub target_table_template: String,
+}
+
+impl ColumnHashRouter {
+    pub fn new(
+        shard_col: &str,
+        shard_count: u32,
+        target_table_template: &str,
+    ) -> anyhow::Result<Self> {
+        if shard_count == 0 {
+            bail! {Error::ConfigError("shard_count should be greater than 0".into())}
+        }
+        if !target_table_template.contains(SHARD_PLACEHOLDER) {
+            bail! {Error::ConfigError(format!(
+                "target_table_template: {} should contain {}",
+                target_table_template, SHARD_PLACEHOLDER
+            ))}
+        }
+
+        Ok(Self {
+            shard_col: shard_col.into(),
+            shard_count,
+            target_table_template: target_table_template.into(),
+        })
+    }
+
+    // Vec<(row_data, shard_tb)>, an update moving the row to another shard is split
+    // into a delete in the old shard table and an insert in the new one
+    pub fn route(&self, row_data: RowData) -> anyhow::Result<Vec<(RowData, String)>> {
+        match row_data.row_type {
+            RowType::Insert => {
+                let shard_tb = self.get_shard_tb(&row_data, &row_data.after)?;
+                Ok(vec![(row_data, shard_tb)])
+            }
+
+            RowType::Delete => {
+                let shard_tb = self.get_shard_tb(&row_data, &row_data.before)?;
+                Ok(vec![(row_data, shard_tb)])
+            }
+
+            RowType::Update => {
+                let shard_tb = self.get_shard_tb(&row_data, &row_data.after)?;
+                // the before image may only contain key cols, E.g. pg tables with
+                // replica identity default, then the shard col is regarded as unchanged
+                let before_has_shard_col = row_data
+                    .before
+                    .as_ref()
+                    .is_some_and(|before| before.contains_key(&self.shard_col));
+                if !before_has_shard_col {
+                    return Ok(vec![(row_data, shard_tb)]);
+                }
+
+                let before_shard_tb = self.get_shard_tb(&row_data, &row_data.before)?;
+                if before_shard_tb == shard_tb {
+                    return Ok(vec![(row_data, shard_tb)]);
+                }
+                let (delete, insert) = row_data.split_update_row_data();
+                Ok(vec![(delete, before_shard_tb), (insert, shard_tb)])
+            }
+        }
+    }
+
+    fn get_shard_tb(
+        &self,
+        row_data: &RowData,
+        col_values: &Option<HashMap<String, ColValue>>,
+    ) -> anyhow::Result<String> {
+        let value = col_values
+            .as_ref()
+            .and_then(|col_values| col_values.get(&self.shard_col))
+            .and_then(|col_value| col_value.to_option_string());
+        let value = match value {
+            Some(value) => value,
+            None => bail! {Error::Unexpected(format!(
+                "shard col: {} is NULL or missing, schema: {}, tb: {}",
+                self.shard_col, row_data.schema, row_data.tb
+            ))},
+        };
+
+        let shard = (HashUtil::fnv1a(value.as_bytes()) % self.shard_count as u64) as u32;
+        Ok(self
+            .target_table_template
+            .replace(SHARD_PLACEHOLDER, &shard.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use dt_common::config::{config_enums::DbType, router_config::RouterConfig};
+    use dt_common::{
+        config::{config_enums::DbType, router_config::RouterConfig},
+        meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+    };
 
-    use super::{RdbRouter, TbColMap, TbMap};
+    use super::{ColumnHashRouter, RdbRouter, TbColMap, TbMap};
+
+    // [router] configs not set here keep their defaults
+    fn mock_config(schema_map: &str, tb_map: &str, col_map: &str) -> RouterConfig {
+        let mut config = RouterConfig::default();
+        let RouterConfig::Rdb {
+            schema_map: config_schema_map,
+            tb_map: config_tb_map,
+            col_map: config_col_map,
+            ..
+        } = &mut config;
+        *config_schema_map = schema_map.into();
+        *config_tb_map = tb_map.into();
+        *config_col_map = col_map.into();
+        config
+    }
+
+    fn mock_router(schema_map: &str, tb_map: &str, col_map: &str) -> RdbRouter {
+        RdbRouter::from_config(&mock_config(schema_map, tb_map, col_map), &DbType::Mysql).unwrap()
+    }
 
     #[test]
     fn test_parse_ignore_cols() {
@@ -353,7 +701,7 @@ mod tests {
         let config_str = "src_db_1.src_tb_1:dst_db_1.dst_tb_1,".to_string()
             + "`src_db,2'`.`src_tb,2'`:dst_db_2.dst_tb_2,"
             + "`src_db:3,`.`src_tb:3,`:`dst_db:3,`.`dst_tb:3,`";
-        let tb_map = RdbRouter::parse_tb_map(&config_str, &DbType::Mysql).unwrap();
+        let (tb_map, _) = RdbRouter::parse_tb_map(&config_str, &DbType::Mysql).unwrap();
 
         assert_exists(&tb_map, "src_db_1", "src_tb_1", "dst_db_1", "dst_tb_1");
         assert_exists(&tb_map, "src_db,2'", "src_tb,2'", "dst_db_2", "dst_tb_2");
@@ -364,7 +712,7 @@ mod tests {
         let config_str = r#"src_db_1.src_tb_1:dst_db_1.dst_tb_1,"#.to_string()
             + r#""src_db,2'"."src_tb,2'":dst_db_2.dst_tb_2,"#
             + r#""src_db:3,"."src_tb:3,":"dst_db:3,"."dst_tb:3,""#;
-        let tb_map = RdbRouter::parse_tb_map(&config_str, &DbType::Pg).unwrap();
+        let (tb_map, _) = RdbRouter::parse_tb_map(&config_str, &DbType::Pg).unwrap();
 
         assert_exists(&tb_map, "src_db_1", "src_tb_1", "dst_db_1", "dst_tb_1");
         assert_exists(&tb_map, "src_db,2'", "src_tb,2'", "dst_db_2", "dst_tb_2");
@@ -372,6 +720,79 @@ mod tests {
         assert_eq!(tb_map.get(&("src_db_4".into(), "src_tb_4".into())), None);
     }
 
+    #[test]
+    fn test_parse_tb_pattern_map() {
+        let config_str =
+            "db_1.orders_*:db_1.orders,`db_1`.`orders_*`:db_1.orders_star,db_?.tb_1:dst_db.tb_1";
+        let (tb_map, tb_pattern_map) = RdbRouter::parse_tb_map(config_str, &DbType::Mysql).unwrap();
+        // escaped names are exact
+        assert_eq!(
+            tb_map.get(&("db_1".into(), "orders_*".into())).unwrap(),
+            &("db_1".into(), "orders_star".into())
+        );
+        assert_eq!(tb_pattern_map.len(), 2);
+        assert!(tb_pattern_map[0].schema.is_exact());
+        assert!(tb_pattern_map[0].tb.is_match("orders_0001"));
+        assert!(tb_pattern_map[1].schema.is_match("db_2"));
+
+        let config_str = r#"json:[{"db":"db_1","tb":"orders_(\d+)","dst_db":"db_1","dst_tb":"orders_bak_${1}"}]"#;
+        let (tb_map, tb_pattern_map) = RdbRouter::parse_tb_map(config_str, &DbType::Mysql).unwrap();
+        assert!(tb_map.is_empty());
+        assert_eq!(tb_pattern_map.len(), 1);
+
+        let config_str =
+            r#"json:[{"db":"db_1","tb":"orders_(","dst_db":"db_1","dst_tb":"orders"}]"#;
+        assert!(RdbRouter::parse_tb_map(config_str, &DbType::Mysql).is_err());
+    }
+
+    #[test]
+    fn test_reverse() {
+        let reversed = mock_router("db_1:dst_db_1", "db_2.tb_1:dst_db_2.dst_tb_1", "")
+            .reverse()
+            .unwrap();
+        assert_eq!(reversed.get_schema_map("dst_db_1"), "db_1");
+        let (schema, tb) = reversed.get_tb_map("dst_db_2", "dst_tb_1");
+        assert_eq!((schema.as_ref(), tb.as_ref()), ("db_2", "tb_1"));
+
+        // tables routed by patterns can not be mapped back
+        let router = mock_router("", "db_2.orders_*:dst_db_2.orders", "");
+        assert!(router.reverse().is_err());
+    }
+
+    #[test]
+    fn test_get_tb_map_with_patterns() {
+        let build_router = |schema_map: &str, tb_map: &str| mock_router(schema_map, tb_map, "");
+        let assert_tb_map =
+            |router: &RdbRouter, src_db: &str, src_tb: &str, dst_db: &str, dst_tb: &str| {
+                let (schema, tb) = router.get_tb_map(src_db, src_tb);
+                assert_eq!((schema.as_ref(), tb.as_ref()), (dst_db, dst_tb));
+            };
+
+        // overlapping patterns: exact tb_map first, then patterns in config order, then db_map
+        let router = build_router(
+            "db_1:dst_db_1",
+            "db_1.orders_0001:db_1.orders_special,db_1.orders_*:db_1.orders,db_1.orders_00*:db_1.orders_00,db_*.*:dst_db.merged",
+        );
+        assert_tb_map(&router, "db_1", "orders_0001", "db_1", "orders_special");
+        assert_tb_map(&router, "db_1", "orders_0002", "db_1", "orders");
+        assert_tb_map(&router, "db_1", "users", "dst_db", "merged");
+        assert_tb_map(&router, "db_2", "orders_0001", "dst_db", "merged");
+        assert_tb_map(&router, "other", "orders_0001", "other", "orders_0001");
+
+        // db_map applies only when no tb_map entry matches
+        let router = build_router("db_1:dst_db_1", "db_1.orders_*:db_1.orders");
+        assert_tb_map(&router, "db_1", "orders_0001", "db_1", "orders");
+        assert_tb_map(&router, "db_1", "users", "dst_db_1", "users");
+
+        // capture group substitution
+        let router = build_router(
+            "",
+            r#"json:[{"db":"(db)_(\d+)","tb":"orders_(\d+)","dst_db":"${1}_all","dst_tb":"orders_bak_${1}"}]"#,
+        );
+        assert_tb_map(&router, "db_1", "orders_0001", "db_all", "orders_bak_0001");
+        assert_tb_map(&router, "db_1", "orders_x", "db_1", "orders_x");
+    }
+
     #[test]
     fn test_parse_tb_col_map() {
         let assert_col_map =
@@ -419,16 +840,17 @@ mod tests {
         let col_map_str = r#"[{"db":"src_db:3,","tb":"src_tb:3,","col_map":{"src_col:1,":"dst_col:1,","src_col:2,":"dst_col:2,"}}]"#;
         let topic_map = "*.*:test,`db:1`.*:test2,`db:1`.`tb:1`:test3";
 
-        let config = RouterConfig::Rdb {
-            schema_map: db_map_str.into(),
-            tb_map: tb_map_str.into(),
-            col_map: col_map_str.into(),
-            topic_map: topic_map.into(),
-        };
+        let mut config = mock_config(db_map_str, tb_map_str, col_map_str);
+        let RouterConfig::Rdb {
+            topic_map: config_topic_map,
+            ..
+        } = &mut config;
+        *config_topic_map = topic_map.into();
         let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
 
         let assert_tb_map = |src_db: &str, src_tb: &str, dst_db: &str, dst_tb: &str| {
-            assert_eq!(router.get_tb_map(src_db, src_tb), (dst_db, dst_tb));
+            let (schema, tb) = router.get_tb_map(src_db, src_tb);
+            assert_eq!((schema.as_ref(), tb.as_ref()), (dst_db, dst_tb));
         };
         let assert_col_map = |src_db: &str, src_tb: &str, col_map: &HashMap<String, String>| {
             assert_eq!(router.get_col_map(src_db, src_tb).unwrap(), col_map)
@@ -450,4 +872,223 @@ mod tests {
         assert_eq!(router.get_topic("db:1", "tb:2"), "test2");
         assert_eq!(router.get_topic("db:2", "tb:1"), "test");
     }
+
+    #[test]
+    fn test_route_row_with_col_map() {
+        // ts renamed to created_at and id renamed to dst_id, table not renamed
+        let router = mock_router(
+            "db_1:dst_db_1",
+            "",
+            r#"json:[{"db":"db_1","tb":"tb_1","col_map":{"id":"dst_id","ts":"created_at"}}]"#,
+        );
+
+        let build_col_values = |id_col: &str, ts_col: &str| {
+            HashMap::from([
+                (id_col.to_string(), ColValue::Long(1)),
+                (ts_col.to_string(), ColValue::String("2024-01-01".into())),
+                ("value".to_string(), ColValue::Long(2)),
+            ])
+        };
+        let row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Update,
+            Some(build_col_values("id", "ts")),
+            Some(build_col_values("id", "ts")),
+        );
+
+        let routed = router.route_row(row_data.clone());
+        assert_eq!(routed.schema, "dst_db_1");
+        assert_eq!(routed.tb, "tb_1");
+        let expected = build_col_values("dst_id", "created_at");
+        assert_eq!(routed.before.as_ref().unwrap(), &expected);
+        assert_eq!(routed.after.as_ref().unwrap(), &expected);
+
+        let reversed = router.reverse().unwrap().route_row(routed);
+        assert_eq!(reversed.schema, "db_1");
+        assert_eq!(reversed.tb, "tb_1");
+        assert_eq!(reversed.before, row_data.before);
+        assert_eq!(reversed.after, row_data.after);
+    }
+
+    #[test]
+    fn test_route_row_merged_with_src_id_col() {
+        // db_0.orders, db_1.orders ... merged into warehouse.orders
+        let build_router = |schema_map: &str, tb_map: &str| {
+            let mut config = mock_config(schema_map, tb_map, "");
+            let RouterConfig::Rdb { src_id_col, .. } = &mut config;
+            *src_id_col = "src_id".into();
+            RdbRouter::from_config(&config, &DbType::Mysql).unwrap()
+        };
+        let router = build_router(
+            "db_x:dst_db_x",
+            "db_*.orders:warehouse.orders,db_x.users:warehouse.users",
+        );
+
+        let build_row = |schema: &str, tb: &str| {
+            let col_values = HashMap::from([("id".to_string(), ColValue::Long(1))]);
+            RowData::new(
+                schema.into(),
+                tb.into(),
+                RowType::Update,
+                Some(col_values.clone()),
+                Some(col_values),
+            )
+        };
+
+        for (schema, tb, dst_tb) in [
+            ("db_0", "orders", "orders"),
+            ("db_1", "orders", "orders"),
+            ("db_x", "users", "users"),
+        ] {
+            let routed = router.route_row(build_row(schema, tb));
+            assert_eq!(routed.schema, "warehouse");
+            assert_eq!(routed.tb, dst_tb);
+            let src_id = ColValue::String(format!("{}.{}", schema, tb));
+            assert_eq!(routed.before.unwrap().get("src_id"), Some(&src_id));
+            assert_eq!(routed.after.unwrap().get("src_id"), Some(&src_id));
+        }
+
+        // not routed by tb_map, no source id
+        let routed = router.route_row(build_row("db_x", "tb_1"));
+        assert_eq!(routed.schema, "dst_db_x");
+        assert!(routed.after.unwrap().get("src_id").is_none());
+
+        // many schemas merged into one by schema_map
+        let router = build_router("db_0:warehouse,db_1:warehouse,db_x:dst_db_x", "");
+        for schema in ["db_0", "db_1"] {
+            let routed = router.route_row(build_row(schema, "orders"));
+            assert_eq!(routed.schema, "warehouse");
+            assert_eq!(
+                routed.after.unwrap().get("src_id"),
+                Some(&ColValue::String(format!("{}.orders", schema)))
+            );
+        }
+        // 1:1 schema_map, no source id
+        let routed = router.route_row(build_row("db_x", "orders"));
+        assert_eq!(routed.schema, "dst_db_x");
+        assert!(routed.after.unwrap().get("src_id").is_none());
+
+        // no source id in reversed rows
+        assert!(router.reverse().unwrap().src_id_col.is_empty());
+    }
+
+    #[test]
+    fn test_column_hash_route() {
+        let build_row = |user_id: ColValue| {
+            let mut after = HashMap::new();
+            after.insert("user_id".to_string(), user_id);
+            RowData::new(
+                "db_1".into(),
+                "events".into(),
+                RowType::Insert,
+                None,
+                Some(after),
+            )
+        };
+
+        let router = ColumnHashRouter::new("user_id", 4, "events_{shard}").unwrap();
+        // fnv1a("1") = 0xaf63ac4c86019afc
+        let routed = router.route(build_row(ColValue::Long(1))).unwrap();
+        assert_eq!(routed.len(), 1);
+        assert_eq!(routed[0].1, "events_0");
+
+        for i in 0..100 {
+            let routed = router.route(build_row(ColValue::Long(i))).unwrap();
+            let shard: u32 = routed[0].1.trim_start_matches("events_").parse().unwrap();
+            assert!(shard < 4);
+        }
+
+        assert!(router.route(build_row(ColValue::None)).is_err());
+        assert!(ColumnHashRouter::new("user_id", 0, "events_{shard}").is_err());
+        assert!(ColumnHashRouter::new("user_id", 4, "events").is_err());
+    }
+
+    #[test]
+    fn test_route_dml_by_shard() {
+        let mut config = mock_config(
+            "db_1:dst_db_1",
+            "",
+            r#"json:[{"db":"db_1","tb":"events","col_map":{"user_id":"dst_user_id"}}]"#,
+        );
+        let RouterConfig::Rdb { shard_map, .. } = &mut config;
+        *shard_map = r#"json:[{"db":"db_1","tb":"events","shard_col":"user_id","shard_count":4,"dst_tb":"events_{shard}"}]"#.into();
+        let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
+
+        let build_col_values = |id: i32, user_id: i32| {
+            let mut col_values = HashMap::new();
+            col_values.insert("id".to_string(), ColValue::Long(id));
+            col_values.insert("user_id".to_string(), ColValue::Long(user_id));
+            col_values
+        };
+        let build_row =
+            |row_type: RowType, before: Option<(i32, i32)>, after: Option<(i32, i32)>| {
+                RowData::new(
+                    "db_1".into(),
+                    "events".into(),
+                    row_type,
+                    before.map(|(id, user_id)| build_col_values(id, user_id)),
+                    after.map(|(id, user_id)| build_col_values(id, user_id)),
+                )
+            };
+
+        // fnv1a("1") % 4 = 0, fnv1a("2") % 4 = 1
+        let routed = router
+            .route_dml(build_row(RowType::Insert, None, Some((1, 2))))
+            .unwrap();
+        assert_eq!(routed.len(), 1);
+        assert_eq!(routed[0].schema, "dst_db_1");
+        assert_eq!(routed[0].tb, "events_1");
+        assert!(routed[0]
+            .after
+            .as_ref()
+            .unwrap()
+            .contains_key("dst_user_id"));
+
+        let routed = router
+            .route_dml(build_row(RowType::Delete, Some((1, 1)), None))
+            .unwrap();
+        assert_eq!(routed.len(), 1);
+        assert_eq!(routed[0].tb, "events_0");
+
+        // shard col unchanged
+        let routed = router
+            .route_dml(build_row(RowType::Update, Some((1, 1)), Some((2, 1))))
+            .unwrap();
+        assert_eq!(routed.len(), 1);
+        assert_eq!(routed[0].row_type, RowType::Update);
+        assert_eq!(routed[0].tb, "events_0");
+
+        // shard col changed: delete in the old shard + insert in the new shard
+        let routed = router
+            .route_dml(build_row(RowType::Update, Some((1, 1)), Some((1, 2))))
+            .unwrap();
+        assert_eq!(routed.len(), 2);
+        assert_eq!(routed[0].row_type, RowType::Delete);
+        assert_eq!(routed[0].schema, "dst_db_1");
+        assert_eq!(routed[0].tb, "events_0");
+        assert_eq!(
+            routed[0].before.as_ref().unwrap()["dst_user_id"],
+            ColValue::Long(1)
+        );
+        assert!(routed[0].after.is_none());
+        assert_eq!(routed[1].row_type, RowType::Insert);
+        assert_eq!(routed[1].schema, "dst_db_1");
+        assert_eq!(routed[1].tb, "events_1");
+        assert_eq!(
+            routed[1].after.as_ref().unwrap()["dst_user_id"],
+            ColValue::Long(2)
+        );
+        assert!(routed[1].before.is_none());
+
+        // tables not in shard_map are routed as before
+        let mut row_data = build_row(RowType::Insert, None, Some((1, 2)));
+        row_data.tb = "tb_1".into();
+        let routed = router.route_dml(row_data).unwrap();
+        assert_eq!(routed.len(), 1);
+        assert_eq!(routed[0].schema, "dst_db_1");
+        assert_eq!(routed[0].tb, "tb_1");
+
+        assert!(router.reverse().is_err());
+    }
 }
@@ -1,10 +1,14 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use dt_common::meta::{
-    rdb_meta_manager::RdbMetaManager, rdb_tb_meta::RdbTbMeta, row_data::RowData,
+    rdb_meta_manager::RdbMetaManager, rdb_tb_meta::RdbTbMeta, row_data::RowData, row_type::RowType,
     struct_meta::statement::struct_statement::StructStatement,
 };
-use dt_common::{log_diff, log_extra, log_miss, rdb_filter::RdbFilter};
+use dt_common::monitor::{counter_type::CounterType, monitor::Monitor};
+use dt_common::{log_diff, log_extra, log_miss, rdb_filter::RdbFilter, utils::hash_util::HashUtil};
 
 use crate::{
     check_log::{
@@ -82,6 +86,54 @@ impl BaseChecker {
         diff_col_values
     }
 
+    // sample rows by the hash of id_cols instead of their positions, so the same rows are
+    // checked however they are extracted, rows with NULL in id_cols are always sampled.
+    // Rows are sampled after extraction, only reads of the target are reduced
+    pub fn sample_row_datas(data: &mut Vec<RowData>, tb_meta: &RdbTbMeta, sample_percent: u8) {
+        if sample_percent >= 100 {
+            return;
+        }
+        data.retain(|row_data| {
+            Self::is_sampled(Self::get_sample_hash(row_data, tb_meta), sample_percent)
+        });
+    }
+
+    // a fixed hash of the id_cols values, None if any of them is NULL
+    pub fn get_sample_hash(row_data: &RowData, tb_meta: &RdbTbMeta) -> Option<u64> {
+        let col_values = match row_data.row_type {
+            RowType::Insert => row_data.after.as_ref()?,
+            _ => row_data.before.as_ref()?,
+        };
+        let mut hash = HashUtil::fnv1a(&[]);
+        for col in tb_meta.id_cols.iter() {
+            let value = col_values.get(col)?.to_option_string()?;
+            hash = HashUtil::fnv1a_append(hash, value.as_bytes());
+            // separates values, so ("ab", "c") and ("a", "bc") differ
+            hash = HashUtil::fnv1a_append(hash, &[0]);
+        }
+        Some(hash)
+    }
+
+    pub fn is_sampled(hash: Option<u64>, sample_percent: u8) -> bool {
+        match hash {
+            Some(hash) => hash % 100 < sample_percent as u64,
+            None => true,
+        }
+    }
+
+    pub fn update_check_monitor(
+        monitor: &Arc<Mutex<Monitor>>,
+        checked_count: usize,
+        miss: &[CheckLog],
+        diff: &[CheckLog],
+    ) {
+        monitor
+            .lock()
+            .unwrap()
+            .add_counter(CounterType::CheckedRows, checked_count)
+            .add_counter(CounterType::MismatchedRows, miss.len() + diff.len());
+    }
+
     pub fn log_dml(miss: Vec<CheckLog>, diff: Vec<CheckLog>) {
         for log in miss {
             log_miss!("{}", log.to_string());
@@ -168,8 +220,9 @@ impl BaseChecker {
         {
             let mut reverse_diff_col_values = HashMap::new();
             for (col, diff_col_value) in diff_col_values {
-                let reverse_col = col_map.get(&col).unwrap();
-                reverse_diff_col_values.insert(reverse_col.to_owned(), diff_col_value);
+                // only renamed cols are in col_map
+                let reverse_col = col_map.get(&col).cloned().unwrap_or(col);
+                reverse_diff_col_values.insert(reverse_col, diff_col_value);
             }
             reverse_diff_col_values
         } else {
@@ -227,3 +280,54 @@ impl BaseChecker {
         id_col_values
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::col_value::ColValue;
+
+    use super::*;
+
+    #[test]
+    fn test_sample_row_datas() {
+        let tb_meta = RdbTbMeta {
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            id_cols: vec!["id".into()],
+            ..Default::default()
+        };
+        let build_row_datas = || -> Vec<RowData> {
+            (0..1000)
+                .map(|i| {
+                    let after = HashMap::from([("id".to_string(), ColValue::Long(i))]);
+                    RowData::build_insert_row_data(after, &tb_meta)
+                })
+                .collect()
+        };
+
+        let mut data = build_row_datas();
+        BaseChecker::sample_row_datas(&mut data, &tb_meta, 100);
+        assert_eq!(data.len(), 1000);
+
+        let mut data = build_row_datas();
+        BaseChecker::sample_row_datas(&mut data, &tb_meta, 10);
+        assert!(data.len() > 50 && data.len() < 150);
+
+        // deterministic, the same rows are sampled again
+        let mut data_2 = build_row_datas();
+        BaseChecker::sample_row_datas(&mut data_2, &tb_meta, 10);
+        assert_eq!(data, data_2);
+
+        // NULL in id_cols
+        assert!(BaseChecker::is_sampled(None, 1));
+
+        // the hash is fixed across builds
+        let row_data = RowData::build_insert_row_data(
+            HashMap::from([("id".to_string(), ColValue::Long(1))]),
+            &tb_meta,
+        );
+        assert_eq!(
+            BaseChecker::get_sample_hash(&row_data, &tb_meta),
+            Some(HashUtil::fnv1a(b"1\0"))
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use anyhow::bail;
-use dt_common::log_error;
 use dt_common::meta::struct_meta::struct_data::StructData;
+use dt_common::meta::struct_meta::structure::domain::Domain;
 use dt_common::{
     config::config_enums::ConflictPolicyEnum, error::Error, log_info, rdb_filter::RdbFilter,
 };
+use dt_common::{log_error, log_warn};
 use sqlx::{query, MySql, Pool, Postgres};
 
 pub struct BaseStructSinker {}
@@ -41,7 +42,20 @@ impl BaseStructSinker {
         Ok(())
     }
 
-    async fn execute(pool: &DBConnPool, sql: &str) -> anyhow::Result<()> {
+    // for targets without domains, domain columns are mapped to the underlying non domain type,
+    // which may be several levels down for domains over other domains
+    pub fn warn_unreplicated_domain(domain: &Domain) {
+        if domain.has_constraints() {
+            log_warn!(
+                "domain: {}.{} (AS {}) is mapped to its underlying base type, its constraints are not replicated",
+                domain.schema_name,
+                domain.domain_name,
+                domain.base_type
+            );
+        }
+    }
+
+    pub async fn execute(pool: &DBConnPool, sql: &str) -> anyhow::Result<()> {
         match pool {
             DBConnPool::MySQL(pool) => match query(sql).execute(pool).await {
                 Ok(_) => Ok(()),
@@ -0,0 +1,248 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use dt_common::{
+    config::sinker_config::BasicSinkerConfig,
+    meta::row_data::RowData,
+    monitor::{counter_type::CounterType, monitor::Monitor},
+};
+
+// statement prefix: INSERT INTO `db`.`tb`(`col_1`,...) VALUES, ON DUPLICATE KEY UPDATE ...
+const STATEMENT_OVERHEAD_BYTES: usize = 4096;
+// placeholder, separator and bind type header of a column value
+const COL_OVERHEAD_BYTES: usize = 8;
+// both mysql and pg take at most 65535 bind parameters in a statement
+const MAX_BIND_PARAMS: usize = 65535;
+
+// decides the size of each sub batch of a sinker:
+// - adaptive: starts at batch_size, grows while batches are faster than target_latency_ms,
+//   shrinks by half once slower, capped by max_batch_size,
+// - estimated sql text + bind payload of a sub batch never exceeds max_packet_bytes,
+// - bind parameters of a sub batch never exceed 65535, E.g. 65535 / column count rows for inserts
+#[derive(Clone)]
+pub struct BatchSizer {
+    // effective batch size
+    pub batch_size: usize,
+    pub adaptive: bool,
+    pub max_batch_size: usize,
+    // 0 means unlimited
+    pub max_packet_bytes: usize,
+    pub target_latency_ms: u64,
+}
+
+impl BatchSizer {
+    pub fn new(batch_size: usize, config: &BasicSinkerConfig) -> Self {
+        let batch_size = batch_size.max(1);
+        Self {
+            batch_size,
+            adaptive: config.adaptive_batch_size,
+            max_batch_size: config.max_batch_size.max(batch_size),
+            max_packet_bytes: config.max_packet_bytes,
+            target_latency_ms: config.adaptive_target_latency_ms,
+        }
+    }
+
+    pub fn reset(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.max(1);
+        self.max_batch_size = self.max_batch_size.max(self.batch_size);
+    }
+
+    // size of the next sub batch starting from data[sinked_count], 0 if all sinked
+    pub fn get_batch_size(&self, data: &[RowData], sinked_count: usize) -> usize {
+        let remaining = data.len().saturating_sub(sinked_count);
+        let batch_size = self.batch_size.min(remaining);
+
+        let mut packet_bytes = STATEMENT_OVERHEAD_BYTES;
+        let mut bind_count = 0;
+        for (i, row_data) in data[sinked_count..sinked_count + batch_size]
+            .iter()
+            .enumerate()
+        {
+            let col_count = Self::get_col_count(row_data);
+            packet_bytes += row_data.data_size + col_count * COL_OVERHEAD_BYTES;
+            bind_count += col_count;
+            let exceeded = bind_count > MAX_BIND_PARAMS
+                || (self.max_packet_bytes > 0 && packet_bytes > self.max_packet_bytes);
+            // a single row exceeding the limits is still sinked and fails in target
+            if exceeded && i > 0 {
+                return i;
+            }
+        }
+        batch_size
+    }
+
+    // max rows of a statement binding bind_count_per_row parameters for each row,
+    // E.g. id cols + set cols of UPDATE ... FROM (VALUES ...)
+    pub fn get_max_rows(&self, bind_count_per_row: usize) -> usize {
+        let max_rows = MAX_BIND_PARAMS / bind_count_per_row.max(1);
+        self.batch_size.min(max_rows).max(1)
+    }
+
+    pub fn on_batch_done(
+        &mut self,
+        batch_size: usize,
+        elapsed: Duration,
+        monitor: &Arc<Mutex<Monitor>>,
+    ) {
+        if self.adaptive {
+            let elapsed_ms = elapsed.as_millis() as u64;
+            if elapsed_ms > self.target_latency_ms {
+                self.batch_size = (self.batch_size / 2).max(1);
+            } else if elapsed_ms < self.target_latency_ms / 2 && batch_size >= self.batch_size {
+                // only full batches prove that a larger one is affordable
+                self.batch_size =
+                    (self.batch_size + (self.batch_size / 4).max(1)).min(self.max_batch_size);
+            }
+        }
+
+        monitor
+            .lock()
+            .unwrap()
+            .add_counter(CounterType::EffectiveBatchSize, self.batch_size);
+    }
+
+    // estimated sql text + bind payload of a batch of rows with the same width
+    pub fn estimate_batch_bytes(batch_size: usize, row_bytes: usize, col_count: usize) -> usize {
+        STATEMENT_OVERHEAD_BYTES + batch_size * (row_bytes + col_count * COL_OVERHEAD_BYTES)
+    }
+
+    // upper bound of bind parameters of a row, E.g. updates bind both before and after
+    fn get_col_count(row_data: &RowData) -> usize {
+        row_data.after.as_ref().map_or(0, |after| after.len())
+            + row_data.before.as_ref().map_or(0, |before| before.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use dt_common::{
+        config::sinker_config::BasicSinkerConfig,
+        meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+        monitor::monitor::Monitor,
+    };
+
+    use super::BatchSizer;
+
+    fn mock_row_data(data_size: usize) -> RowData {
+        mock_wide_row_data(data_size, 1)
+    }
+
+    fn mock_wide_row_data(data_size: usize, col_count: usize) -> RowData {
+        let after = (0..col_count)
+            .map(|i| (format!("col_{}", i), ColValue::Long(i as i32)))
+            .collect::<HashMap<_, _>>();
+        let mut row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        row_data.data_size = data_size;
+        row_data
+    }
+
+    #[test]
+    fn test_get_batch_size() {
+        let data: Vec<RowData> = (0..10).map(|_| mock_row_data(1000)).collect();
+
+        let sizer = BatchSizer::new(4, &BasicSinkerConfig::default());
+        assert_eq!(sizer.get_batch_size(&data, 0), 4);
+        assert_eq!(sizer.get_batch_size(&data, 8), 2);
+        assert_eq!(sizer.get_batch_size(&data, 10), 0);
+
+        // 4096 + 3 * 1008 <= 8000 < 4096 + 4 * 1008
+        let mut sizer = BatchSizer::new(10, &BasicSinkerConfig::default());
+        sizer.max_packet_bytes = 8000;
+        assert_eq!(sizer.get_batch_size(&data, 0), 3);
+        assert_eq!(sizer.get_batch_size(&data, 9), 1);
+
+        // a row larger than the limit goes alone
+        sizer.max_packet_bytes = 100;
+        assert_eq!(sizer.get_batch_size(&data, 0), 1);
+
+        assert_eq!(
+            BatchSizer::estimate_batch_bytes(3, 1000, 1),
+            4096 + 3 * 1008
+        );
+    }
+
+    #[test]
+    fn test_get_batch_size_by_bind_params() {
+        // 65535 / 1000 = 65 rows of 1000 columns in a statement
+        let data: Vec<RowData> = (0..100).map(|_| mock_wide_row_data(10, 1000)).collect();
+        let config = BasicSinkerConfig {
+            adaptive_batch_size: true,
+            max_batch_size: 2000,
+            adaptive_target_latency_ms: 100,
+            ..Default::default()
+        };
+        let mut sizer = BatchSizer::new(1000, &config);
+        assert_eq!(sizer.get_batch_size(&data, 0), 65);
+        assert_eq!(sizer.get_batch_size(&data, 65), 35);
+
+        // capped batches are not full, so the size does not grow
+        sizer.on_batch_done(65, Duration::from_millis(1), &mock_monitor());
+        assert_eq!(sizer.batch_size, 1000);
+        assert_eq!(sizer.get_batch_size(&data, 0), 65);
+
+        // a row with more columns than the limit goes alone
+        let data: Vec<RowData> = (0..2).map(|_| mock_wide_row_data(10, 70000)).collect();
+        assert_eq!(sizer.get_batch_size(&data, 0), 1);
+    }
+
+    #[test]
+    fn test_get_max_rows() {
+        let sizer = BatchSizer::new(16000, &BasicSinkerConfig::default());
+        // 65535 / 10
+        assert_eq!(sizer.get_max_rows(10), 6553);
+        assert_eq!(sizer.get_max_rows(1), 16000);
+        assert_eq!(sizer.get_max_rows(0), 16000);
+        // a row with more columns than the limit goes alone
+        assert_eq!(sizer.get_max_rows(70000), 1);
+
+        let sizer = BatchSizer::new(100, &BasicSinkerConfig::default());
+        assert_eq!(sizer.get_max_rows(10), 100);
+    }
+
+    fn mock_monitor() -> Arc<Mutex<Monitor>> {
+        Arc::new(Mutex::new(Monitor::new("sinker", "", 10, 100, 4)))
+    }
+
+    #[test]
+    fn test_on_batch_done() {
+        let monitor = Arc::new(Mutex::new(Monitor::new("sinker", "", 10, 100, 4)));
+        let config = BasicSinkerConfig {
+            adaptive_batch_size: true,
+            max_batch_size: 12,
+            adaptive_target_latency_ms: 100,
+            ..Default::default()
+        };
+        let mut sizer = BatchSizer::new(8, &config);
+
+        sizer.on_batch_done(8, Duration::from_millis(10), &monitor);
+        assert_eq!(sizer.batch_size, 10);
+        // partial batches do not grow
+        sizer.on_batch_done(5, Duration::from_millis(10), &monitor);
+        assert_eq!(sizer.batch_size, 10);
+        sizer.on_batch_done(10, Duration::from_millis(10), &monitor);
+        assert_eq!(sizer.batch_size, 12);
+        sizer.on_batch_done(12, Duration::from_millis(80), &monitor);
+        assert_eq!(sizer.batch_size, 12);
+        sizer.on_batch_done(12, Duration::from_millis(200), &monitor);
+        assert_eq!(sizer.batch_size, 6);
+
+        // fixed
+        let mut sizer = BatchSizer::new(8, &BasicSinkerConfig::default());
+        sizer.on_batch_done(8, Duration::from_millis(500), &monitor);
+        assert_eq!(sizer.batch_size, 8);
+    }
+}
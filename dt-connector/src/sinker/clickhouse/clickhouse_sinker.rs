@@ -18,7 +18,7 @@ use dt_common::{
 use reqwest::{Client, Method, Response, StatusCode};
 use serde_json::json;
 
-use crate::{call_batch_fn, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{call_batch_fn, sinker::base_sinker::BaseSinker, Configurable, Sinker};
 
 const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
 const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
@@ -35,6 +35,8 @@ pub struct ClickhouseSinker {
     pub sync_timestamp: i64,
 }
 
+impl Configurable for ClickhouseSinker {}
+
 #[async_trait]
 impl Sinker for ClickhouseSinker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
@@ -1,4 +1,6 @@
-use crate::{rdb_router::RdbRouter, Sinker};
+use crate::{
+    rdb_router::RdbRouter, sinker::base_struct_sinker::BaseStructSinker, Configurable, Sinker,
+};
 
 use anyhow::bail;
 use clickhouse::Client;
@@ -35,10 +37,12 @@ pub struct ClickhouseStructSinker {
     pub extractor_meta_manager: RdbMetaManager,
 }
 
+impl Configurable for ClickhouseStructSinker {}
+
 #[async_trait]
 impl Sinker for ClickhouseStructSinker {
     async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
-        let reverse_router = self.router.reverse();
+        let reverse_router = self.router.reverse()?;
         for i in data {
             match i.statement {
                 StructStatement::MysqlCreateDatabase(statement) => {
@@ -55,7 +59,7 @@ impl Sinker for ClickhouseStructSinker {
                     if let Some(meta_manager) =
                         self.extractor_meta_manager.mysql_meta_manager.as_mut()
                     {
-                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?;
+                        let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?;
                         let sql =
                             Self::get_create_table_sql(&statement.table, Some(tb_meta), None)?;
                         self.execute_sql(&sql).await?;
@@ -67,12 +71,16 @@ impl Sinker for ClickhouseStructSinker {
                     self.execute_sql(&sql).await?;
                 }
 
+                StructStatement::PgCreateDomain(statement) => {
+                    BaseStructSinker::warn_unreplicated_domain(&statement.domain);
+                }
+
                 StructStatement::PgCreateTable(statement) => {
                     let (schema, tb) = reverse_router
                         .get_tb_map(&statement.table.schema_name, &statement.table.table_name);
                     if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
                     {
-                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?.to_owned();
                         let sql =
                             Self::get_create_table_sql(&statement.table, None, Some(&tb_meta))?;
                         self.execute_sql(&sql).await?;
@@ -211,7 +219,7 @@ impl ClickhouseStructSinker {
 
     fn get_dst_col_type_from_pg(col: &str, tb_meta: &PgTbMeta) -> anyhow::Result<String> {
         let pg_col_type = tb_meta.get_col_type(col)?;
-        let dst_col = match pg_col_type.value_type {
+        let dst_col = match pg_col_type.base_value_type() {
             PgValueType::Boolean => "Bool",
             PgValueType::Int16 => "Int16",
             PgValueType::Int32 => "Int32",
@@ -0,0 +1,99 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use dt_common::{
+    log_warn,
+    monitor::{counter_type::CounterType, monitor::Monitor},
+};
+use sqlx::{pool::PoolConnection, Database, Pool, Transaction};
+
+// wrap the sinker pool to record utilization and checkout time of connections,
+// long checkouts mean all connections are busy and the pool is undersized
+pub struct ConnectionPoolMetrics<DB: Database> {
+    pub conn_pool: Pool<DB>,
+    // 0 means no warning
+    pub max_acceptable_wait_ms: u64,
+    pub monitor: Arc<Mutex<Monitor>>,
+    // shared by all sinkers of the pool
+    waiting: Arc<AtomicUsize>,
+}
+
+impl<DB: Database> Clone for ConnectionPoolMetrics<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            conn_pool: self.conn_pool.clone(),
+            max_acceptable_wait_ms: self.max_acceptable_wait_ms,
+            monitor: self.monitor.clone(),
+            waiting: self.waiting.clone(),
+        }
+    }
+}
+
+impl<DB: Database> ConnectionPoolMetrics<DB> {
+    pub fn new(
+        conn_pool: Pool<DB>,
+        max_acceptable_wait_ms: u64,
+        monitor: Arc<Mutex<Monitor>>,
+    ) -> Self {
+        Self {
+            conn_pool,
+            max_acceptable_wait_ms,
+            monitor,
+            waiting: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub async fn acquire(&self) -> anyhow::Result<PoolConnection<DB>> {
+        let start_time = self.before_checkout();
+        let conn = self.conn_pool.acquire().await;
+        self.after_checkout(start_time);
+        Ok(conn?)
+    }
+
+    // checkout time includes the round trip of BEGIN
+    pub async fn begin(&self) -> anyhow::Result<Transaction<'static, DB>> {
+        let start_time = self.before_checkout();
+        let tx = self.conn_pool.begin().await;
+        self.after_checkout(start_time);
+        Ok(tx?)
+    }
+
+    fn before_checkout(&self) -> Instant {
+        self.waiting.fetch_add(1, Ordering::Relaxed);
+        Instant::now()
+    }
+
+    fn after_checkout(&self, start_time: Instant) {
+        let checkout_ms = start_time.elapsed().as_millis() as u64;
+        let wait_queue_depth = self.waiting.fetch_sub(1, Ordering::Relaxed) - 1;
+        let size = self.conn_pool.size() as usize;
+        let idle = self.conn_pool.num_idle();
+
+        self.monitor
+            .lock()
+            .unwrap()
+            .add_counter(
+                CounterType::PoolActiveConnections,
+                size.saturating_sub(idle),
+            )
+            .add_counter(CounterType::PoolIdleConnections, idle)
+            .add_counter(CounterType::PoolWaitQueueDepth, wait_queue_depth)
+            .add_counter(CounterType::PoolCheckoutMs, checkout_ms as usize);
+
+        if self.max_acceptable_wait_ms > 0 && checkout_ms > self.max_acceptable_wait_ms {
+            log_warn!(
+                "connection checkout took {} ms, exceeds max_acceptable_wait_ms: {}, pool size: {}, idle: {}, waiting: {}, the pool may be undersized",
+                checkout_ms,
+                self.max_acceptable_wait_ms,
+                size,
+                idle,
+                wait_queue_depth
+            );
+        }
+    }
+}
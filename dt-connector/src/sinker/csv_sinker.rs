@@ -0,0 +1,219 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use dt_common::{
+    error::Error,
+    log_info,
+    meta::{
+        col_value::ColValue, rdb_meta_manager::RdbMetaManager, row_data::RowData, row_type::RowType,
+    },
+    monitor::monitor::Monitor,
+};
+
+use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Configurable, Sinker};
+
+pub struct CsvWriter {
+    pub writer: BufWriter<File>,
+    // routed cols in the order of tb_meta
+    pub cols: Vec<String>,
+}
+
+// writes inserted rows of each table to {output_dir}/{db}.{tb}.csv, writers are shared by
+// all sinkers since rows of a table may be sunk by any of them
+pub struct CsvSinker {
+    // meta_manager of the extractor, tables are looked up by source names
+    pub meta_manager: RdbMetaManager,
+    pub router: RdbRouter,
+    pub reverse_router: RdbRouter,
+    pub output_dir: String,
+    pub delimiter: String,
+    pub null_token: String,
+    pub ignore_non_insert: bool,
+    // HashMap<(db, tb), CsvWriter>
+    pub writers: Arc<Mutex<HashMap<(String, String), CsvWriter>>>,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+impl Configurable for CsvSinker {}
+
+#[async_trait]
+impl Sinker for CsvSinker {
+    async fn sink_dml(&mut self, data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let start_time = Instant::now();
+        let mut data_size = 0;
+        let mut record_count = 0;
+        for row_data in data.iter() {
+            if row_data.row_type != RowType::Insert {
+                if self.ignore_non_insert {
+                    continue;
+                }
+                bail! {Error::SinkerError(format!(
+                    "csv sinker only supports insert rows, got: {}, schema: {}, tb: {}, set [sinker] ignore_non_insert=true to skip them",
+                    row_data.row_type, row_data.schema, row_data.tb
+                ))}
+            }
+
+            let key = (row_data.schema.clone(), row_data.tb.clone());
+            if !self.writers.lock().unwrap().contains_key(&key) {
+                let cols = self.get_routed_cols(&key.0, &key.1).await?;
+                self.open_writer(&key, cols)?;
+            }
+
+            let mut writers = self.writers.lock().unwrap();
+            let csv_writer = writers.get_mut(&key).unwrap();
+            let line = Self::format_row(
+                &csv_writer.cols,
+                row_data.after.as_ref().unwrap(),
+                &self.delimiter,
+                &self.null_token,
+            );
+            csv_writer
+                .writer
+                .write_all(line.as_bytes())
+                .with_context(|| format!("failed to write csv file of `{}`.`{}`", key.0, key.1))?;
+
+            data_size += row_data.data_size;
+            record_count += 1;
+        }
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, record_count, data_size, start_time)
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        let mut writers = self.writers.lock().unwrap();
+        for ((schema, tb), csv_writer) in writers.iter_mut() {
+            csv_writer
+                .writer
+                .flush()
+                .with_context(|| format!("failed to flush csv file of `{}`.`{}`", schema, tb))?;
+        }
+        writers.clear();
+        drop(writers);
+        self.meta_manager.close().await
+    }
+}
+
+impl CsvSinker {
+    // rows are routed by the extractor, so tb_meta is fetched by the source names and
+    // its cols are mapped to the routed names
+    async fn get_routed_cols(&mut self, schema: &str, tb: &str) -> anyhow::Result<Vec<String>> {
+        let (src_schema, src_tb) = self.reverse_router.get_tb_map(schema, tb);
+        let (src_schema, src_tb) = (src_schema.to_string(), src_tb.to_string());
+        let tb_meta = self.meta_manager.get_tb_meta(&src_schema, &src_tb).await?;
+        let col_map = self.router.get_col_map(&src_schema, &src_tb);
+        let cols = tb_meta
+            .cols
+            .iter()
+            .map(|col| match col_map.and_then(|col_map| col_map.get(col)) {
+                Some(dst_col) => dst_col.clone(),
+                None => col.clone(),
+            })
+            .collect();
+        Ok(cols)
+    }
+
+    fn open_writer(&self, key: &(String, String), cols: Vec<String>) -> anyhow::Result<()> {
+        let mut writers = self.writers.lock().unwrap();
+        // may be opened by another sinker
+        if writers.contains_key(key) {
+            return Ok(());
+        }
+
+        let file_path = Path::new(&self.output_dir).join(format!("{}.{}.csv", key.0, key.1));
+        let file = File::create(&file_path)
+            .with_context(|| format!("failed to create csv file: {}", file_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        let header: Vec<String> = cols
+            .iter()
+            .map(|col| Self::escape_cell(col, &self.delimiter))
+            .collect();
+        writer.write_all(format!("{}\n", header.join(&self.delimiter)).as_bytes())?;
+        log_info!("csv file created: {}", file_path.display());
+
+        writers.insert(key.clone(), CsvWriter { writer, cols });
+        Ok(())
+    }
+
+    fn format_row(
+        cols: &[String],
+        col_values: &HashMap<String, ColValue>,
+        delimiter: &str,
+        null_token: &str,
+    ) -> String {
+        let cells: Vec<String> = cols
+            .iter()
+            .map(
+                |col| match col_values.get(col).and_then(|i| i.to_option_string()) {
+                    Some(value) => Self::escape_cell(&value, delimiter),
+                    None => null_token.to_string(),
+                },
+            )
+            .collect();
+        format!("{}\n", cells.join(delimiter))
+    }
+
+    // RFC 4180: cells containing the delimiter, quotes or line breaks are quoted,
+    // and quotes inside are doubled
+    fn escape_cell(value: &str, delimiter: &str) -> String {
+        if value.contains(delimiter)
+            || value.contains('"')
+            || value.contains('\n')
+            || value.contains('\r')
+        {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::col_value::ColValue;
+
+    use super::CsvSinker;
+
+    #[test]
+    fn test_format_row() {
+        let cols: Vec<String> = ["id", "name", "remark", "score"]
+            .iter()
+            .map(|i| i.to_string())
+            .collect();
+        let col_values = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("name".to_string(), ColValue::String("a,\"b\"".into())),
+            ("remark".to_string(), ColValue::None),
+        ]);
+
+        assert_eq!(
+            CsvSinker::format_row(&cols, &col_values, ",", "\\N"),
+            "1,\"a,\"\"b\"\"\",\\N,\\N\n"
+        );
+        assert_eq!(
+            CsvSinker::format_row(&cols, &col_values, "\t", ""),
+            "1\t\"a,\"\"b\"\"\"\t\t\n"
+        );
+    }
+
+    #[test]
+    fn test_escape_cell() {
+        assert_eq!(CsvSinker::escape_cell("abc", ","), "abc");
+        assert_eq!(CsvSinker::escape_cell("a,b", ","), "\"a,b\"");
+        assert_eq!(CsvSinker::escape_cell("a,b", "|"), "a,b");
+        assert_eq!(CsvSinker::escape_cell("a\nb", ","), "\"a\nb\"");
+    }
+}
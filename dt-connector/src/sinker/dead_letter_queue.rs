@@ -0,0 +1,253 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+use dt_common::{
+    config::config_enums::DbType,
+    meta::{position::Position, row_data::RowData},
+};
+use serde_json::{json, Value};
+use sqlx::{MySql, Pool, Postgres};
+
+// rows rejected by the target (constraint violations, invalid values, etc.) are moved here
+// instead of failing the task, either appended to a file as json lines, one line for each row:
+// {"schema":..,"tb":..,"row_type":..,"before":..,"after":..,"error":..,"position":..}
+// or inserted into a side table of the target
+pub struct DeadLetterQueue {
+    pub row_count: u64,
+    // the last received position of the batch being sunk, set by the pipeline
+    pub position: Option<Position>,
+    target: DeadLetterTarget,
+}
+
+enum DeadLetterTarget {
+    File(File),
+    MysqlTb {
+        conn_pool: Pool<MySql>,
+        sql: String,
+    },
+    PgTb {
+        conn_pool: Pool<Postgres>,
+        sql: String,
+    },
+}
+
+impl DeadLetterQueue {
+    pub fn new_file(file_path: &str) -> anyhow::Result<Self> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)?;
+        Ok(Self::new(DeadLetterTarget::File(file)))
+    }
+
+    pub async fn new_mysql_tb(
+        conn_pool: Pool<MySql>,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<Self> {
+        let sql = Self::get_create_tb_sql(&DbType::Mysql, schema, tb);
+        sqlx::query(&sql).execute(&conn_pool).await?;
+        let sql = Self::get_insert_sql(&DbType::Mysql, schema, tb);
+        Ok(Self::new(DeadLetterTarget::MysqlTb { conn_pool, sql }))
+    }
+
+    pub async fn new_pg_tb(
+        conn_pool: Pool<Postgres>,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<Self> {
+        let sql = Self::get_create_tb_sql(&DbType::Pg, schema, tb);
+        sqlx::query(&sql).execute(&conn_pool).await?;
+        let sql = Self::get_insert_sql(&DbType::Pg, schema, tb);
+        Ok(Self::new(DeadLetterTarget::PgTb { conn_pool, sql }))
+    }
+
+    fn new(target: DeadLetterTarget) -> Self {
+        Self {
+            row_count: 0,
+            position: None,
+            target,
+        }
+    }
+
+    pub async fn push(&mut self, row_data: &RowData, error: &anyhow::Error) -> anyhow::Result<()> {
+        let line = self.to_json(row_data, error);
+        let row_type = row_data.row_type.to_string();
+        let error = format!("{:#}", error);
+        let position = json!(self.position).to_string();
+        match &mut self.target {
+            DeadLetterTarget::File(file) => {
+                // one write for each line, so lines from pipelines sharing the file are not interleaved
+                file.write_all(format!("{}\n", line).as_bytes())?;
+            }
+            DeadLetterTarget::MysqlTb { conn_pool, sql } => {
+                sqlx::query(sql)
+                    .bind(&row_data.schema)
+                    .bind(&row_data.tb)
+                    .bind(&row_type)
+                    .bind(line.to_string())
+                    .bind(&error)
+                    .bind(&position)
+                    .execute(&*conn_pool)
+                    .await?;
+            }
+            DeadLetterTarget::PgTb { conn_pool, sql } => {
+                sqlx::query(sql)
+                    .bind(&row_data.schema)
+                    .bind(&row_data.tb)
+                    .bind(&row_type)
+                    .bind(line.to_string())
+                    .bind(&error)
+                    .bind(&position)
+                    .execute(&*conn_pool)
+                    .await?;
+            }
+        }
+        self.row_count += 1;
+        Ok(())
+    }
+
+    fn to_json(&self, row_data: &RowData, error: &anyhow::Error) -> Value {
+        json!({
+            "schema": row_data.schema,
+            "tb": row_data.tb,
+            "row_type": row_data.row_type,
+            "before": row_data.before,
+            "after": row_data.after,
+            "error": format!("{:#}", error),
+            "position": self.position,
+        })
+    }
+
+    fn get_create_tb_sql(db_type: &DbType, schema: &str, tb: &str) -> String {
+        match db_type {
+            DbType::Mysql => format!(
+                "CREATE TABLE IF NOT EXISTS `{}`.`{}` (
+                    `id` BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+                    `schema_name` VARCHAR(255) NOT NULL,
+                    `tb_name` VARCHAR(255) NOT NULL,
+                    `row_type` VARCHAR(32) NOT NULL,
+                    `row_data` LONGTEXT NOT NULL,
+                    `error` TEXT NOT NULL,
+                    `position` TEXT NOT NULL,
+                    `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )",
+                schema, tb
+            ),
+            _ => format!(
+                r#"CREATE TABLE IF NOT EXISTS "{}"."{}" (
+                    "id" BIGSERIAL PRIMARY KEY,
+                    "schema_name" VARCHAR(255) NOT NULL,
+                    "tb_name" VARCHAR(255) NOT NULL,
+                    "row_type" VARCHAR(32) NOT NULL,
+                    "row_data" TEXT NOT NULL,
+                    "error" TEXT NOT NULL,
+                    "position" TEXT NOT NULL,
+                    "created_at" TIMESTAMP NOT NULL DEFAULT now()
+                )"#,
+                schema, tb
+            ),
+        }
+    }
+
+    fn get_insert_sql(db_type: &DbType, schema: &str, tb: &str) -> String {
+        match db_type {
+            DbType::Mysql => format!(
+                "INSERT INTO `{}`.`{}` (`schema_name`, `tb_name`, `row_type`, `row_data`, `error`, `position`) VALUES (?, ?, ?, ?, ?, ?)",
+                schema, tb
+            ),
+            _ => format!(
+                r#"INSERT INTO "{}"."{}" ("schema_name", "tb_name", "row_type", "row_data", "error", "position") VALUES ($1, $2, $3, $4, $5, $6)"#,
+                schema, tb
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{col_value::ColValue, row_type::RowType};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_push() {
+        let file_path = std::env::temp_dir().join("ape_dts_test_dead_letter_queue.log");
+        let file_path = file_path.to_str().unwrap();
+        let _ = fs::remove_file(file_path);
+
+        let after = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("name".to_string(), ColValue::String("a".into())),
+        ]);
+        let row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        let position = Some(Position::RdbSnapshot {
+            db_type: "mysql".into(),
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            order_col: "id".into(),
+            value: "1".into(),
+        });
+
+        let mut queue = DeadLetterQueue::new_file(file_path).unwrap();
+        queue.position = position;
+        queue
+            .push(&row_data, &anyhow::anyhow!("duplicate entry"))
+            .await
+            .unwrap();
+        queue.position = None;
+        queue
+            .push(&row_data, &anyhow::anyhow!("too long"))
+            .await
+            .unwrap();
+        assert_eq!(queue.row_count, 2);
+
+        let lines: Vec<Value> = fs::read_to_string(file_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["schema"], "db_1");
+        assert_eq!(lines[0]["tb"], "tb_1");
+        assert_eq!(lines[0]["error"], "duplicate entry");
+        assert!(!lines[0]["position"].is_null());
+        assert_eq!(lines[1]["error"], "too long");
+        assert!(lines[1]["position"].is_null());
+
+        fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn test_get_insert_sql() {
+        assert_eq!(
+            DeadLetterQueue::get_insert_sql(&DbType::Mysql, "db_1", "dead_letters"),
+            "INSERT INTO `db_1`.`dead_letters` (`schema_name`, `tb_name`, `row_type`, `row_data`, `error`, `position`) VALUES (?, ?, ?, ?, ?, ?)"
+        );
+        assert_eq!(
+            DeadLetterQueue::get_insert_sql(&DbType::Pg, "public", "dead_letters"),
+            r#"INSERT INTO "public"."dead_letters" ("schema_name", "tb_name", "row_type", "row_data", "error", "position") VALUES ($1, $2, $3, $4, $5, $6)"#
+        );
+        assert!(
+            DeadLetterQueue::get_create_tb_sql(&DbType::Pg, "public", "dead_letters")
+                .starts_with(r#"CREATE TABLE IF NOT EXISTS "public"."dead_letters""#)
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use async_trait::async_trait;
 
-use crate::Sinker;
+use crate::{Configurable, Sinker};
 
 pub struct DummySinker {}
 
+impl Configurable for DummySinker {}
+
 #[async_trait]
 impl Sinker for DummySinker {}
@@ -14,7 +14,7 @@ use dt_common::{
 use rusoto_s3::S3Client;
 use sqlx::{MySql, Pool};
 
-use crate::{close_conn_pool, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{close_conn_pool, sinker::base_sinker::BaseSinker, Configurable, Sinker};
 
 pub struct FoxlakeMerger {
     pub batch_size: usize,
@@ -25,6 +25,8 @@ pub struct FoxlakeMerger {
     pub extract_type: ExtractType,
 }
 
+impl Configurable for FoxlakeMerger {}
+
 #[async_trait]
 impl Sinker for FoxlakeMerger {
     async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
@@ -34,7 +34,7 @@ use std::{
 };
 use uuid::Uuid;
 
-use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Configurable, Sinker};
 
 use super::{
     decimal_util::DecimalUtil,
@@ -60,6 +60,8 @@ pub struct FoxlakePusher {
 const CDC_ACTION: &str = "cdc_action";
 const CDC_LOG_SEQUENCE: &str = "cdc_log_sequence";
 
+impl Configurable for FoxlakePusher {}
+
 #[async_trait]
 impl Sinker for FoxlakePusher {
     async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
@@ -168,7 +170,7 @@ impl FoxlakePusher {
                 .reverse_router
                 .get_tb_map(&tb_meta.basic.schema, &tb_meta.basic.tb);
             let (data_file_name, meta_file_name, sequence_info) =
-                self.get_s3_file_info(src_schema, src_tb);
+                self.get_s3_file_info(&src_schema, &src_tb);
 
             let s3_file_meta = S3FileMeta {
                 schema: tb_meta.basic.schema.clone(),
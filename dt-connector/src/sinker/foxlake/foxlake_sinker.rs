@@ -20,7 +20,9 @@ use std::{
     time::Instant,
 };
 
-use crate::{close_conn_pool, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{
+    close_conn_pool, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Configurable, Sinker,
+};
 
 use super::{foxlake_merger::FoxlakeMerger, foxlake_pusher::FoxlakePusher};
 
@@ -36,6 +38,8 @@ pub struct FoxlakeSinker {
     pub engine: String,
 }
 
+impl Configurable for FoxlakeSinker {}
+
 #[async_trait]
 impl Sinker for FoxlakeSinker {
     async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
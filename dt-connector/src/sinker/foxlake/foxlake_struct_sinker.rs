@@ -2,7 +2,7 @@ use crate::{
     close_conn_pool,
     rdb_router::RdbRouter,
     sinker::base_struct_sinker::{BaseStructSinker, DBConnPool},
-    Sinker,
+    Configurable, Sinker,
 };
 
 use dt_common::{
@@ -24,6 +24,8 @@ pub struct FoxlakeStructSinker {
     pub engine: String,
 }
 
+impl Configurable for FoxlakeStructSinker {}
+
 #[async_trait]
 impl Sinker for FoxlakeStructSinker {
     async fn sink_struct(&mut self, mut data: Vec<StructData>) -> anyhow::Result<()> {
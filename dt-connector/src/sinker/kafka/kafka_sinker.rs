@@ -1,13 +1,21 @@
 use std::{
+    collections::HashMap,
     sync::{Arc, Mutex},
-    time::Instant,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
 use async_trait::async_trait;
+use serde_json::json;
 
-use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{
+    call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Configurable, Sinker,
+};
 
-use dt_common::{meta::ddl_meta::ddl_data::DdlData, monitor::monitor::Monitor};
+use dt_common::{
+    config::config_enums::{KafkaMessageFormat, KafkaPartitionKey},
+    meta::{col_value::ColValue, ddl_meta::ddl_data::DdlData, row_type::RowType},
+    monitor::monitor::Monitor,
+};
 
 use dt_common::meta::{avro::avro_converter::AvroConverter, row_data::RowData};
 
@@ -19,8 +27,15 @@ pub struct KafkaSinker {
     pub producer: Producer,
     pub avro_converter: AvroConverter,
     pub monitor: Arc<Mutex<Monitor>>,
+    pub message_format: KafkaMessageFormat,
+    // only for json messages, avro messages are keyed by order_col
+    pub partition_key: KafkaPartitionKey,
+    // topic of ddl events, topic_map of [router] is used if empty
+    pub ddl_topic: String,
 }
 
+impl Configurable for KafkaSinker {}
+
 #[async_trait]
 impl Sinker for KafkaSinker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
@@ -28,17 +43,39 @@ impl Sinker for KafkaSinker {
             return Ok(());
         }
 
-        call_batch_fn!(self, data, Self::send_avro);
+        match self.message_format {
+            KafkaMessageFormat::Avro => {
+                call_batch_fn!(self, data, Self::send_avro);
+            }
+            KafkaMessageFormat::Json => {
+                call_batch_fn!(self, data, Self::send_json);
+            }
+        }
         Ok(())
     }
 
     async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
         let mut messages = Vec::new();
         for ddl_data in data {
-            let topic = self.router.get_topic(&ddl_data.default_schema, "");
-            let payload = self.avro_converter.ddl_data_to_avro_value(ddl_data).await?;
+            let topic = if self.ddl_topic.is_empty() {
+                self.router.get_topic(&ddl_data.default_schema, "")
+            } else {
+                &self.ddl_topic
+            };
+            let (key, payload) = match self.message_format {
+                KafkaMessageFormat::Avro => (
+                    String::new(),
+                    self.avro_converter.ddl_data_to_avro_value(ddl_data).await?,
+                ),
+                KafkaMessageFormat::Json => (
+                    ddl_data.default_schema.clone(),
+                    Self::ddl_data_to_json(&ddl_data, Self::now_ms())
+                        .to_string()
+                        .into_bytes(),
+                ),
+            };
             messages.push(Record {
-                key: String::new(),
+                key,
                 value: payload,
                 topic,
                 partition: -1,
@@ -52,6 +89,15 @@ impl Sinker for KafkaSinker {
         self.avro_converter.refresh_meta(&data);
         Ok(())
     }
+
+    // send_all returns after all messages are acked by brokers (by required_acks),
+    // the producer buffers nothing, so there is nothing to flush here
+    async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(meta_manager) = &self.avro_converter.meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
 }
 
 impl KafkaSinker {
@@ -87,4 +133,163 @@ impl KafkaSinker {
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
     }
+
+    async fn send_json(
+        &mut self,
+        data: &mut [RowData],
+        sinked_count: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        let mut data_size = 0;
+        let ts_ms = Self::now_ms();
+
+        let mut keys = Vec::new();
+        let mut payloads = Vec::new();
+        for row_data in data.iter_mut().skip(sinked_count).take(batch_size) {
+            data_size += row_data.data_size;
+
+            row_data.convert_raw_string();
+            keys.push(self.get_json_key(row_data).await?);
+            payloads.push(Self::row_data_to_json(row_data, ts_ms).to_string());
+        }
+
+        let messages: Vec<Record<String, Vec<u8>>> = data
+            .iter()
+            .skip(sinked_count)
+            .take(batch_size)
+            .zip(keys.into_iter().zip(payloads))
+            .map(|(row_data, (key, payload))| Record {
+                key,
+                value: payload.into_bytes(),
+                topic: self.router.get_topic(&row_data.schema, &row_data.tb),
+                partition: -1,
+            })
+            .collect();
+        self.producer.send_all(&messages)?;
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    async fn get_json_key(&mut self, row_data: &RowData) -> anyhow::Result<String> {
+        let db_tb = format!("{}.{}", row_data.schema, row_data.tb);
+        if self.partition_key == KafkaPartitionKey::DbTb {
+            return Ok(db_tb);
+        }
+
+        // tables without meta (E.g. non-rdb sources) are keyed by db.tb
+        let meta_manager = match self.avro_converter.meta_manager.as_mut() {
+            Some(meta_manager) => meta_manager,
+            None => return Ok(db_tb),
+        };
+        let tb_meta = meta_manager
+            .get_tb_meta(&row_data.schema, &row_data.tb)
+            .await?;
+        // keyed by the after-image for updates, so if the key is changed, the update and
+        // later events of the new key are in the same partition
+        let col_values = match row_data.row_type {
+            RowType::Insert | RowType::Update => row_data.after.as_ref(),
+            RowType::Delete => row_data.before.as_ref(),
+        };
+        match col_values {
+            Some(col_values) => Ok(Self::get_id_key(&db_tb, &tb_meta.id_cols, col_values)),
+            None => Ok(db_tb),
+        }
+    }
+
+    // db.tb:value_1,value_2, NULL values are empty
+    fn get_id_key(
+        db_tb: &str,
+        id_cols: &[String],
+        col_values: &HashMap<String, ColValue>,
+    ) -> String {
+        let values: Vec<String> = id_cols
+            .iter()
+            .map(|col| {
+                col_values
+                    .get(col)
+                    .and_then(|value| value.to_option_string())
+                    .unwrap_or_default()
+            })
+            .collect();
+        format!("{}:{}", db_tb, values.join(","))
+    }
+
+    fn row_data_to_json(row_data: &RowData, ts_ms: u64) -> serde_json::Value {
+        json!({
+            "op": row_data.row_type.to_string(),
+            "db": row_data.schema,
+            "tb": row_data.tb,
+            "before": row_data.before,
+            "after": row_data.after,
+            "position": row_data.position,
+            "ts_ms": ts_ms,
+        })
+    }
+
+    fn ddl_data_to_json(ddl_data: &DdlData, ts_ms: u64) -> serde_json::Value {
+        let (db, tb) = ddl_data.get_schema_tb();
+        json!({
+            "op": "ddl",
+            "db": db,
+            "tb": tb,
+            "ddl_type": ddl_data.ddl_type.to_string(),
+            "query": ddl_data.to_sql(),
+            "ts_ms": ts_ms,
+        })
+    }
+
+    fn now_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{col_value::ColValue, row_data::RowData, row_type::RowType};
+    use serde_json::json;
+
+    use super::KafkaSinker;
+
+    #[test]
+    fn test_get_id_key() {
+        let id_cols = vec!["id".to_string(), "code".to_string()];
+        let col_values = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("name".to_string(), ColValue::String("a".into())),
+        ]);
+        assert_eq!(
+            KafkaSinker::get_id_key("db_1.tb_1", &id_cols, &col_values),
+            "db_1.tb_1:1,"
+        );
+    }
+
+    #[test]
+    fn test_row_data_to_json() {
+        let mut row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Update,
+            Some(HashMap::from([("id".to_string(), ColValue::Long(1))])),
+            Some(HashMap::from([("id".to_string(), ColValue::Long(2))])),
+        );
+        row_data.position = "binlog_filename:mysql-bin.000001,next_event_position:100".into();
+        assert_eq!(
+            KafkaSinker::row_data_to_json(&row_data, 100),
+            json!({
+                "op": "update",
+                "db": "db_1",
+                "tb": "tb_1",
+                "before": {"id": 1},
+                "after": {"id": 2},
+                "position": "binlog_filename:mysql-bin.000001,next_event_position:100",
+                "ts_ms": 100,
+            })
+        );
+    }
 }
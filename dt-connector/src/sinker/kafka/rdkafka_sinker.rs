@@ -7,7 +7,7 @@ use anyhow::bail;
 use async_trait::async_trait;
 use rdkafka::producer::{FutureProducer, FutureRecord};
 
-use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Configurable, Sinker};
 
 use dt_common::monitor::monitor::Monitor;
 
@@ -22,6 +22,8 @@ pub struct RdkafkaSinker {
     pub queue_timeout_secs: u64,
 }
 
+impl Configurable for RdkafkaSinker {}
+
 #[async_trait]
 impl Sinker for RdkafkaSinker {
     async fn sink_dml(&mut self, data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
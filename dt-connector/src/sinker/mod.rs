@@ -1,12 +1,17 @@
 pub mod base_checker;
 pub mod base_sinker;
 pub mod base_struct_sinker;
+pub mod batch_sizer;
 pub mod clickhouse;
+pub mod connection_pool_metrics;
+pub mod csv_sinker;
+pub mod dead_letter_queue;
 pub mod dummy_sinker;
 pub mod foxlake;
 pub mod kafka;
 pub mod mongo;
 pub mod mysql;
+pub mod parquet_sinker;
 pub mod pg;
 pub mod redis;
 pub mod sql_sinker;
@@ -10,7 +10,7 @@ use mongodb::{
     Client,
 };
 
-use dt_common::{log_error, monitor::monitor::Monitor};
+use dt_common::{log_error, monitor::monitor::Monitor, utils::hash_util::HashUtil};
 
 use dt_common::meta::{
     col_value::ColValue,
@@ -24,7 +24,7 @@ use crate::{
     call_batch_fn,
     rdb_router::RdbRouter,
     sinker::{base_checker::BaseChecker, base_sinker::BaseSinker},
-    Sinker,
+    Configurable, Sinker,
 };
 
 #[derive(Clone)]
@@ -33,8 +33,11 @@ pub struct MongoChecker {
     pub batch_size: usize,
     pub mongo_client: Client,
     pub monitor: Arc<Mutex<Monitor>>,
+    pub sample_percent: u8,
 }
 
+impl Configurable for MongoChecker {}
+
 #[async_trait]
 impl Sinker for MongoChecker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
@@ -77,6 +80,11 @@ impl MongoChecker {
             if let Some(ColValue::MongoDoc(doc)) = after.get(MongoConstants::DOC) {
                 let id = doc.get(MongoConstants::ID).unwrap();
                 if let Some(key) = MongoKey::from_doc(doc) {
+                    // sampled by the hash of _id, the same as rdb checkers
+                    let hash = HashUtil::fnv1a(key.to_string().as_bytes());
+                    if !BaseChecker::is_sampled(Some(hash), self.sample_percent) {
+                        continue;
+                    }
                     src_row_data_map.insert(key, row_data.clone());
                     ids.push(id);
                 } else {
@@ -104,6 +112,7 @@ impl MongoChecker {
         }
 
         // batch check
+        let checked_count = src_row_data_map.len();
         let mut miss = Vec::new();
         let mut diff = Vec::new();
         for (key, src_row_data) in src_row_data_map {
@@ -124,6 +133,7 @@ impl MongoChecker {
                 miss.push(miss_log);
             };
         }
+        BaseChecker::update_check_monitor(&self.monitor, checked_count, &miss, &diff);
         BaseChecker::log_dml(miss, diff);
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, 0, start_time)
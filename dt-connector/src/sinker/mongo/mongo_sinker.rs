@@ -17,7 +17,9 @@ use dt_common::meta::{
     row_type::RowType,
 };
 
-use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{
+    call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Configurable, Sinker,
+};
 
 #[derive(Clone)]
 pub struct MongoSinker {
@@ -27,6 +29,8 @@ pub struct MongoSinker {
     pub monitor: Arc<Mutex<Monitor>>,
 }
 
+impl Configurable for MongoSinker {}
+
 #[async_trait]
 impl Sinker for MongoSinker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
@@ -1,3 +1,4 @@
 pub mod mysql_checker;
 pub mod mysql_sinker;
 pub mod mysql_struct_sinker;
+pub mod server_side_migration_sinker;
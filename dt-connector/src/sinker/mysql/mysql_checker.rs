@@ -14,7 +14,7 @@ use crate::{
     rdb_query_builder::RdbQueryBuilder,
     rdb_router::RdbRouter,
     sinker::{base_checker::BaseChecker, base_sinker::BaseSinker},
-    Sinker,
+    Configurable, Sinker,
 };
 
 use dt_common::{
@@ -35,8 +35,11 @@ pub struct MysqlChecker {
     pub batch_size: usize,
     pub monitor: Arc<Mutex<Monitor>>,
     pub filter: RdbFilter,
+    pub sample_percent: u8,
 }
 
+impl Configurable for MysqlChecker {}
+
 #[async_trait]
 impl Sinker for MysqlChecker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
@@ -44,6 +47,14 @@ impl Sinker for MysqlChecker {
             return Ok(());
         }
 
+        if self.sample_percent < 100 {
+            let tb_meta = self.meta_manager.get_tb_meta_by_row_data(&data[0]).await?;
+            BaseChecker::sample_row_datas(&mut data, &tb_meta.basic, self.sample_percent);
+            if data.is_empty() {
+                return Ok(());
+            }
+        }
+
         if !batch {
             self.serial_check(data).await?;
         } else {
@@ -108,6 +119,7 @@ impl MysqlChecker {
                 miss.push(miss_log);
             }
         }
+        BaseChecker::update_check_monitor(&self.monitor, data.len(), &miss, &diff);
         BaseChecker::log_dml(miss, diff);
 
         BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), 0, start_time)
@@ -147,6 +159,7 @@ impl MysqlChecker {
             &self.reverse_router,
         )
         .await?;
+        BaseChecker::update_check_monitor(&self.monitor, batch_size, &miss, &diff);
         BaseChecker::log_dml(miss, diff);
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, 0, start_time)
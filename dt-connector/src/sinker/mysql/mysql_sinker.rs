@@ -5,12 +5,22 @@ use std::{
 };
 
 use crate::{
-    call_batch_fn, close_conn_pool, data_marker::DataMarker, rdb_query_builder::RdbQueryBuilder,
-    rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker,
+    call_batch_fn_with_retry, call_serial_fn_with_retry, close_conn_pool,
+    data_marker::DataMarker,
+    rdb_query_builder::RdbQueryBuilder,
+    rdb_router::RdbRouter,
+    sinker::{
+        base_sinker::BaseSinker, batch_sizer::BatchSizer,
+        connection_pool_metrics::ConnectionPoolMetrics, dead_letter_queue::DeadLetterQueue,
+    },
+    Configurable, Sinker,
 };
 
 use anyhow::Context;
 use dt_common::{
+    config::{
+        config_enums::MysqlConflictMode, sinker_config::SinkerConfig, task_config::TaskConfig,
+    },
     log_error, log_info,
     meta::ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
     monitor::monitor::Monitor,
@@ -21,7 +31,8 @@ use dt_common::meta::{
 };
 
 use sqlx::{
-    mysql::{MySqlConnectOptions, MySqlPoolOptions},
+    mysql::{MySqlArguments, MySqlConnectOptions, MySqlPoolOptions},
+    query::Query,
     MySql, Pool,
 };
 
@@ -33,10 +44,35 @@ pub struct MysqlSinker {
     pub conn_pool: Pool<MySql>,
     pub meta_manager: MysqlMetaManager,
     pub router: RdbRouter,
-    pub batch_size: usize,
+    pub batch_sizer: BatchSizer,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
-    pub replace: bool,
+    pub conflict_mode: MysqlConflictMode,
+    pub json_merge: bool,
+    pub pool_metrics: ConnectionPoolMetrics<MySql>,
+    pub deadlock_max_retries: u32,
+    pub deadlock_retry_interval_ms: u64,
+    // replay a sub batch failed by connection errors once the pool recovers, 0 means disabled
+    pub reconnect_max_wait_secs: u64,
+    pub reconnect_interval_ms: u64,
+    // rows rejected by the target are moved here instead of failing the task
+    pub dead_letter_queue: Option<Arc<async_mutex::Mutex<DeadLetterQueue>>>,
+}
+
+impl Configurable for MysqlSinker {
+    fn update_config(&mut self, config: &TaskConfig) -> anyhow::Result<()> {
+        if let SinkerConfig::Mysql { batch_size, .. } = &config.sinker {
+            if *batch_size != self.batch_sizer.batch_size {
+                log_info!(
+                    "MysqlSinker batch_size updated: {} -> {}",
+                    self.batch_sizer.batch_size,
+                    batch_size
+                );
+                self.batch_sizer.reset(*batch_size);
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -46,17 +82,20 @@ impl Sinker for MysqlSinker {
             return Ok(());
         }
 
+        let max_retries = self.deadlock_max_retries;
         if !batch {
-            self.serial_sink(&data).await?;
+            call_serial_fn_with_retry!(self, &data, Self::serial_sink, max_retries, true);
         } else {
             match data[0].row_type {
                 RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
+                    call_batch_fn_with_retry!(self, data, Self::batch_insert, max_retries, true);
                 }
                 RowType::Delete => {
-                    call_batch_fn!(self, data, Self::batch_delete);
+                    call_batch_fn_with_retry!(self, data, Self::batch_delete, max_retries, true);
+                }
+                _ => {
+                    call_serial_fn_with_retry!(self, &data, Self::serial_sink, max_retries, true);
                 }
-                _ => self.serial_sink(&data).await?,
             }
         }
 
@@ -109,7 +148,7 @@ impl MysqlSinker {
         let start_time = Instant::now();
         let mut data_size = 0;
 
-        let mut tx = self.conn_pool.begin().await?;
+        let mut tx = self.pool_metrics.begin().await?;
         if let Some(sql) = self.get_data_marker_sql() {
             sqlx::query(&sql)
                 .execute(&mut tx)
@@ -121,7 +160,20 @@ impl MysqlSinker {
             let tb_meta = self.meta_manager.get_tb_meta_by_row_data(row_data).await?;
             let query_builder = RdbQueryBuilder::new_for_mysql(tb_meta, None);
 
-            let query_info = query_builder.get_query_info(row_data, self.replace)?;
+            let query_info = if row_data.row_type == RowType::Insert
+                && self.conflict_mode == MysqlConflictMode::DoUpdate
+            {
+                let (query_info, _) = query_builder.get_batch_upsert_query(
+                    std::slice::from_ref(row_data),
+                    0,
+                    1,
+                    self.json_merge,
+                )?;
+                query_info
+            } else {
+                let replace = self.conflict_mode == MysqlConflictMode::Replace;
+                query_builder.get_query_info(row_data, replace)?
+            };
             let query = query_builder.create_mysql_query(&query_info);
             query
                 .execute(&mut tx)
@@ -152,12 +204,13 @@ impl MysqlSinker {
         let query = query_builder.create_mysql_query(&query_info);
 
         if let Some(sql) = self.get_data_marker_sql() {
-            let mut tx = self.conn_pool.begin().await?;
+            let mut tx = self.pool_metrics.begin().await?;
             sqlx::query(&sql).execute(&mut tx).await?;
             query.execute(&mut tx).await?;
             tx.commit().await?;
         } else {
-            query.execute(&self.conn_pool).await?;
+            let mut conn = self.pool_metrics.acquire().await?;
+            query.execute(&mut *conn).await?;
         }
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
@@ -178,26 +231,30 @@ impl MysqlSinker {
             .to_owned();
         let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
 
+        let upsert = match self.conflict_mode {
+            MysqlConflictMode::DoUpdate => true,
+            MysqlConflictMode::Replace => tb_meta
+                .basic
+                .cols
+                .iter()
+                .any(|col| query_builder.is_mysql_json_col(col)),
+            MysqlConflictMode::Error => false,
+        };
+        if upsert {
+            return self.batch_upsert(data, start_index, batch_size).await;
+        }
+
+        let replace = self.conflict_mode == MysqlConflictMode::Replace;
         let (query_info, data_size) =
-            query_builder.get_batch_insert_query(data, start_index, batch_size, self.replace)?;
+            query_builder.get_batch_insert_query(data, start_index, batch_size, replace)?;
         let query = query_builder.create_mysql_query(&query_info);
 
-        let exec_error = if let Some(sql) = self.get_data_marker_sql() {
-            let mut tx = self.conn_pool.begin().await?;
-            sqlx::query(&sql).execute(&mut tx).await?;
-            query.execute(&mut tx).await?;
-            match tx.commit().await {
-                Err(e) => Some(e),
-                _ => None,
-            }
-        } else {
-            match query.execute(&self.conn_pool).await {
-                Err(e) => Some(e),
-                _ => None,
+        if let Some(error) = self.execute_batch(query).await? {
+            let error = anyhow::Error::from(error);
+            // the batch is retried as a whole by call_batch_fn_with_retry
+            if self.is_retried_as_batch(&error) {
+                return Err(error);
             }
-        };
-
-        if let Some(error) = exec_error {
             log_error!(
                 "batch insert failed, will insert one by one, schema: {}, tb: {}, error: {}",
                 tb_meta.basic.schema,
@@ -212,6 +269,81 @@ impl MysqlSinker {
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
     }
 
+    // INSERT ... ON DUPLICATE KEY UPDATE, used if conflict_mode=do_update, or instead of REPLACE
+    // for tables with JSON columns, so JSON columns can be merged with existing values if json_merge=true
+    async fn batch_upsert(
+        &mut self,
+        data: &mut [RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+
+        let tb_meta = self
+            .meta_manager
+            .get_tb_meta_by_row_data(&data[0])
+            .await?
+            .to_owned();
+        let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
+
+        let (query_info, data_size) =
+            query_builder.get_batch_upsert_query(data, start_index, batch_size, self.json_merge)?;
+        let query = query_builder.create_mysql_query(&query_info);
+
+        if let Some(error) = self.execute_batch(query).await? {
+            let error = anyhow::Error::from(error);
+            // the batch is retried as a whole by call_batch_fn_with_retry
+            if self.is_retried_as_batch(&error) {
+                return Err(error);
+            }
+            log_error!(
+                "batch upsert failed, will upsert one by one, schema: {}, tb: {}, error: {}",
+                tb_meta.basic.schema,
+                tb_meta.basic.tb,
+                error.to_string()
+            );
+            // upsert one by one, keep the same semantics for JSON columns
+            for i in start_index..start_index + batch_size {
+                let (query_info, _) =
+                    query_builder.get_batch_upsert_query(data, i, 1, self.json_merge)?;
+                let query = query_builder.create_mysql_query(&query_info);
+                if let Some(error) = self.execute_batch(query).await? {
+                    return Err(error.into());
+                }
+            }
+        }
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    fn is_retried_as_batch(&self, error: &anyhow::Error) -> bool {
+        (self.deadlock_max_retries > 0 && BaseSinker::is_lock_conflict_error(error))
+            || (self.reconnect_max_wait_secs > 0 && BaseSinker::is_connection_error(error))
+    }
+
+    // returns the error of the query instead of failing, so callers can retry one by one
+    async fn execute_batch(
+        &self,
+        query: Query<'_, MySql, MySqlArguments>,
+    ) -> anyhow::Result<Option<sqlx::Error>> {
+        let exec_error = if let Some(sql) = self.get_data_marker_sql() {
+            let mut tx = self.pool_metrics.begin().await?;
+            sqlx::query(&sql).execute(&mut tx).await?;
+            query.execute(&mut tx).await?;
+            match tx.commit().await {
+                Err(e) => Some(e),
+                _ => None,
+            }
+        } else {
+            let mut conn = self.pool_metrics.acquire().await?;
+            match query.execute(&mut *conn).await {
+                Err(e) => Some(e),
+                _ => None,
+            }
+        };
+        Ok(exec_error)
+    }
+
     fn get_data_marker_sql(&self) -> Option<String> {
         if let Some(data_marker) = &self.data_marker {
             let data_marker = data_marker.read().unwrap();
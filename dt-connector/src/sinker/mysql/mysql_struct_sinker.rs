@@ -2,7 +2,7 @@ use crate::{
     close_conn_pool,
     rdb_router::RdbRouter,
     sinker::base_struct_sinker::{BaseStructSinker, DBConnPool},
-    Sinker,
+    Configurable, Sinker,
 };
 
 use dt_common::{
@@ -22,6 +22,8 @@ pub struct MysqlStructSinker {
     pub router: RdbRouter,
 }
 
+impl Configurable for MysqlStructSinker {}
+
 #[async_trait]
 impl Sinker for MysqlStructSinker {
     async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
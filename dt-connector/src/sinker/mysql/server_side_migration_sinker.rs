@@ -0,0 +1,364 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    log_info, log_warn,
+    meta::{
+        adaptor::{mysql_col_value_convertor::MysqlColValueConvertor, sqlx_ext::SqlxMysqlExt},
+        col_value::ColValue,
+        mysql::{
+            mysql_col_type::MysqlColType, mysql_meta_manager::MysqlMetaManager,
+            mysql_tb_meta::MysqlTbMeta,
+        },
+    },
+    monitor::monitor::Monitor,
+    rdb_filter::RdbFilter,
+    utils::sql_util::SqlUtil,
+};
+use sqlx::{MySql, Pool, Row};
+
+use crate::{
+    close_conn_pool, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Configurable, Sinker,
+};
+
+// migrate tables inside the same mysql server by INSERT INTO ... SELECT, rows are copied by
+// the server without being transferred through the task, the connection should have access
+// to both the source and target databases
+pub struct ServerSideMigrationSinker {
+    pub conn_pool: Pool<MySql>,
+    pub meta_manager: MysqlMetaManager,
+    pub router: RdbRouter,
+    pub filter: RdbFilter,
+    pub batch_size: usize,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+impl Configurable for ServerSideMigrationSinker {}
+
+#[async_trait]
+impl Sinker for ServerSideMigrationSinker {
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.meta_manager.close().await?;
+        return close_conn_pool!(self);
+    }
+}
+
+impl ServerSideMigrationSinker {
+    pub async fn migrate_tb(&mut self, src_db: &str, src_tb: &str) -> anyhow::Result<()> {
+        let (dst_db, dst_tb) = self.router.get_tb_map(src_db, src_tb);
+        let (dst_db, dst_tb) = (dst_db.to_string(), dst_tb.to_string());
+        if src_db == dst_db && src_tb == dst_tb {
+            bail! {Error::SinkerError(format!(
+                "source and target of server side migration are the same table: `{}`.`{}`",
+                src_db, src_tb
+            ))}
+        }
+
+        let src_tb_meta = self.meta_manager.get_tb_meta(src_db, src_tb).await?.clone();
+        let dst_tb_meta = self
+            .meta_manager
+            .get_tb_meta(&dst_db, &dst_tb)
+            .await?
+            .clone();
+        let (src_cols, dst_cols) = self.check_schema(&src_tb_meta, &dst_tb_meta)?;
+
+        let db_type = DbType::Mysql;
+        let insert_prefix = format!(
+            "INSERT INTO {}.{}({}) SELECT {} FROM {}.{}",
+            SqlUtil::escape_by_db_type(&dst_db, &db_type),
+            SqlUtil::escape_by_db_type(&dst_tb, &db_type),
+            SqlUtil::escape_cols(&dst_cols, &db_type).join(","),
+            SqlUtil::escape_cols(&src_cols, &db_type).join(","),
+            SqlUtil::escape_by_db_type(src_db, &db_type),
+            SqlUtil::escape_by_db_type(src_tb, &db_type),
+        );
+        let mut conditions = Vec::new();
+        if let Some(condition) = self.filter.get_where_condition(src_db, src_tb) {
+            conditions.push(format!("({})", condition));
+        }
+
+        // pages are split by the key: `key > last AND key <= end`, rows with NULL keys would
+        // be missed, so only the primary key or a NOT NULL unique key is used
+        let not_null_cols = self.get_not_null_cols(src_db, src_tb).await?;
+        let key_cols = Self::get_page_key_cols(&src_tb_meta.basic.key_map, &not_null_cols);
+        let key_cols = match key_cols {
+            Some(key_cols) if self.batch_size > 0 => key_cols,
+            key_cols => {
+                if key_cols.is_none() {
+                    log_warn!(
+                        "table `{}`.`{}` has no primary key or NOT NULL unique key, it is migrated in a single statement",
+                        src_db,
+                        src_tb
+                    );
+                } else {
+                    log_info!(
+                        "server side migration without batching, table: `{}`.`{}`",
+                        src_db,
+                        src_tb
+                    );
+                }
+                let start_time = Instant::now();
+                let sql = format!("{}{}", insert_prefix, Self::build_where_sql(&conditions));
+                let rows_affected = sqlx::query(&sql)
+                    .execute(&self.conn_pool)
+                    .await?
+                    .rows_affected();
+                return BaseSinker::update_batch_monitor(
+                    &mut self.monitor,
+                    rows_affected as usize,
+                    0,
+                    start_time,
+                );
+            }
+        };
+        let key_col_types = key_cols
+            .iter()
+            .map(|col| src_tb_meta.get_col_type(col).cloned())
+            .collect::<anyhow::Result<Vec<MysqlColType>>>()?;
+        let escaped_key_cols = SqlUtil::escape_cols(&key_cols, &db_type).join(",");
+
+        let mut start_key: Option<Vec<ColValue>> = None;
+        let mut migrated_count = 0;
+        loop {
+            let start_time = Instant::now();
+            let mut page_conditions = conditions.clone();
+            if start_key.is_some() {
+                page_conditions.push(Self::build_key_condition(&key_cols, ">"));
+            }
+
+            // the key of the last row in this page
+            let end_key_sql = format!(
+                "SELECT {} FROM {}.{}{} ORDER BY {} LIMIT 1 OFFSET {}",
+                escaped_key_cols,
+                SqlUtil::escape_by_db_type(src_db, &db_type),
+                SqlUtil::escape_by_db_type(src_tb, &db_type),
+                Self::build_where_sql(&page_conditions),
+                escaped_key_cols,
+                self.batch_size - 1
+            );
+            let mut query = sqlx::query(&end_key_sql);
+            for (value, col_type) in start_key.iter().flatten().zip(key_col_types.iter()) {
+                query = query.bind_col_value(Some(value), col_type);
+            }
+            let end_key = match query.fetch_optional(&self.conn_pool).await? {
+                Some(row) => Some(
+                    key_cols
+                        .iter()
+                        .zip(key_col_types.iter())
+                        .map(|(col, col_type)| {
+                            MysqlColValueConvertor::from_query(&row, col, col_type)
+                        })
+                        .collect::<anyhow::Result<Vec<ColValue>>>()?,
+                ),
+                None => None,
+            };
+
+            // the last page has no end
+            if end_key.is_some() {
+                page_conditions.push(Self::build_key_condition(&key_cols, "<="));
+            }
+            let sql = format!(
+                "{}{}",
+                insert_prefix,
+                Self::build_where_sql(&page_conditions)
+            );
+            let mut query = sqlx::query(&sql);
+            for (value, col_type) in start_key
+                .iter()
+                .flatten()
+                .chain(end_key.iter().flatten())
+                .zip(key_col_types.iter().cycle())
+            {
+                query = query.bind_col_value(Some(value), col_type);
+            }
+            let rows_affected = query.execute(&self.conn_pool).await?.rows_affected();
+            BaseSinker::update_batch_monitor(
+                &mut self.monitor,
+                rows_affected as usize,
+                0,
+                start_time,
+            )?;
+            migrated_count += rows_affected;
+
+            match end_key {
+                Some(end_key) => start_key = Some(end_key),
+                None => break,
+            }
+        }
+        log_info!(
+            "server side migration finished, table: `{}`.`{}`, rows: {}",
+            src_db,
+            src_tb,
+            migrated_count
+        );
+        Ok(())
+    }
+
+    async fn get_not_null_cols(&self, db: &str, tb: &str) -> anyhow::Result<HashSet<String>> {
+        let sql = "SELECT COLUMN_NAME FROM information_schema.COLUMNS
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? AND IS_NULLABLE = 'NO'";
+        let rows = sqlx::query(sql)
+            .bind(db)
+            .bind(tb)
+            .fetch_all(&self.conn_pool)
+            .await?;
+        let mut cols = HashSet::new();
+        for row in rows {
+            let col: String = row.try_get(0)?;
+            cols.insert(col.to_lowercase());
+        }
+        Ok(cols)
+    }
+
+    // the primary key, or the NOT NULL unique key with the least cols
+    fn get_page_key_cols(
+        key_map: &HashMap<String, Vec<String>>,
+        not_null_cols: &HashSet<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(key_cols) = key_map.get("primary") {
+            return Some(key_cols.clone());
+        }
+        let mut keys: Vec<(&String, &Vec<String>)> = key_map
+            .iter()
+            .filter(|(_, cols)| {
+                cols.iter()
+                    .all(|col| not_null_cols.contains(&col.to_lowercase()))
+            })
+            .collect();
+        // sorted by name for ties to be deterministic
+        keys.sort_by_key(|(name, cols)| (cols.len(), name.to_string()));
+        keys.first().map(|(_, cols)| cols.to_vec())
+    }
+
+    // (`a`,`b`) > (?,?)
+    fn build_key_condition(key_cols: &[String], op: &str) -> String {
+        let placeholders = vec!["?"; key_cols.len()].join(",");
+        format!(
+            "({}) {} ({})",
+            SqlUtil::escape_cols(&key_cols.to_vec(), &DbType::Mysql).join(","),
+            op,
+            placeholders
+        )
+    }
+
+    fn build_where_sql(conditions: &[String]) -> String {
+        if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        }
+    }
+
+    // the target table should have the same columns (after col_map of router) and column types,
+    // returns the columns to select from source and insert into target
+    fn check_schema(
+        &self,
+        src_tb_meta: &MysqlTbMeta,
+        dst_tb_meta: &MysqlTbMeta,
+    ) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+        let (src_db, src_tb) = (&src_tb_meta.basic.schema, &src_tb_meta.basic.tb);
+        let col_map = self.router.get_col_map(src_db, src_tb);
+
+        let src_cols = src_tb_meta.basic.cols.clone();
+        let mut dst_cols = Vec::new();
+        for src_col in src_cols.iter() {
+            let dst_col = col_map
+                .and_then(|col_map| col_map.get(src_col))
+                .unwrap_or(src_col);
+            let src_type = src_tb_meta.basic.col_origin_type_map.get(src_col);
+            let dst_type = dst_tb_meta.basic.col_origin_type_map.get(dst_col);
+            if dst_type.is_none() || src_type != dst_type {
+                bail! {Error::SinkerError(format!(
+                    "schema mismatch for server side migration, `{}`.`{}`.`{}`: {:?}, `{}`.`{}`.`{}`: {:?}",
+                    src_db, src_tb, src_col, src_type,
+                    dst_tb_meta.basic.schema, dst_tb_meta.basic.tb, dst_col, dst_type
+                ))}
+            }
+            dst_cols.push(dst_col.clone());
+        }
+
+        if dst_cols.len() != dst_tb_meta.basic.cols.len() {
+            bail! {Error::SinkerError(format!(
+                "schema mismatch for server side migration, `{}`.`{}` has {} columns, `{}`.`{}` has {}",
+                src_db, src_tb, src_cols.len(),
+                dst_tb_meta.basic.schema, dst_tb_meta.basic.tb, dst_tb_meta.basic.cols.len()
+            ))}
+        }
+        Ok((src_cols, dst_cols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::ServerSideMigrationSinker;
+
+    #[test]
+    fn test_get_page_key_cols() {
+        let key_map = |keys: &[(&str, &[&str])]| -> HashMap<String, Vec<String>> {
+            keys.iter()
+                .map(|(name, cols)| {
+                    (
+                        name.to_string(),
+                        cols.iter().map(|c| c.to_string()).collect(),
+                    )
+                })
+                .collect()
+        };
+        let not_null_cols: HashSet<String> = ["id", "code", "a", "b"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        let get = |keys: &[(&str, &[&str])]| {
+            ServerSideMigrationSinker::get_page_key_cols(&key_map(keys), &not_null_cols)
+        };
+
+        assert_eq!(
+            get(&[("primary", &["id"]), ("uk_code", &["code"])]),
+            Some(vec!["id".to_string()])
+        );
+        // the NOT NULL unique key with the least cols
+        assert_eq!(
+            get(&[("uk_ab", &["a", "b"]), ("uk_code", &["code"])]),
+            Some(vec!["code".to_string()])
+        );
+        // unique keys with nullable cols are skipped
+        assert_eq!(
+            get(&[("uk_name", &["name"]), ("uk_ab", &["a", "b"])]),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(get(&[("uk_name", &["name"])]), None);
+        assert_eq!(get(&[]), None);
+    }
+
+    #[test]
+    fn test_build_key_condition() {
+        let key_cols = vec!["id".to_string()];
+        assert_eq!(
+            ServerSideMigrationSinker::build_key_condition(&key_cols, ">"),
+            "(`id`) > (?)"
+        );
+        let key_cols = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(
+            ServerSideMigrationSinker::build_key_condition(&key_cols, "<="),
+            "(`a`,`b`) <= (?,?)"
+        );
+
+        assert_eq!(ServerSideMigrationSinker::build_where_sql(&[]), "");
+        assert_eq!(
+            ServerSideMigrationSinker::build_where_sql(&[
+                "(id > 1 OR id < 0)".to_string(),
+                "(`id`) > (?)".to_string()
+            ]),
+            " WHERE (id > 1 OR id < 0) AND (`id`) > (?)"
+        );
+    }
+}
@@ -0,0 +1,652 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::{bail, Context};
+use arrow::{
+    array::{
+        ArrayRef, BinaryBuilder, BooleanBuilder, Date32Builder, Decimal128Builder, Float32Builder,
+        Float64Builder, Int32Builder, Int64Builder, StringBuilder, TimestampMicrosecondBuilder,
+        UInt64Builder,
+    },
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use dt_common::{
+    error::Error,
+    log_info,
+    meta::{
+        col_value::ColValue,
+        mysql::mysql_col_type::MysqlColType,
+        pg::{pg_col_type::PgColType, pg_value_type::PgValueType},
+        rdb_meta_manager::RdbMetaManager,
+        row_data::RowData,
+        row_type::RowType,
+    },
+    monitor::monitor::Monitor,
+};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use rust_decimal::Decimal;
+
+use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Configurable, Sinker};
+
+// max precision of rust_decimal, wider decimals are written as strings
+const MAX_DECIMAL_PRECISION: u32 = 28;
+
+// arrow type of a column in parquet files:
+// - decimal(p, s) of mysql: Decimal128(p, s) if p <= 28, otherwise Utf8,
+//   numeric of pg: Utf8 since precision and scale are unknown for unconstrained numeric
+// - date: Date32, zero dates of mysql are written as NULL
+// - datetime / timestamp of mysql, timestamp of pg: Timestamp(Microsecond) without timezone,
+//   the wall-clock value as extracted, timestamptz of pg: Utf8 with the offset kept
+// - time: Utf8 since mysql time may be negative or exceed 24 hours
+// - binary / varbinary / blob of mysql, bytea of pg: Binary
+// - others (strings, json, enum, set, arrays ...): Utf8
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParquetColType {
+    Boolean,
+    Int32,
+    Int64,
+    UInt64,
+    Float32,
+    Float64,
+    Decimal { precision: u8, scale: i8 },
+    Date,
+    Timestamp,
+    Binary,
+    String,
+}
+
+// buffered rows of a table, flushed as a row group every batch_size rows
+pub struct ParquetTable {
+    pub writer: ArrowWriter<File>,
+    pub schema: SchemaRef,
+    // routed cols in the order of tb_meta
+    pub cols: Vec<String>,
+    pub col_types: Vec<ParquetColType>,
+    pub rows: Vec<HashMap<String, ColValue>>,
+}
+
+// writes inserted rows of each table to {output_dir}/{db}.{tb}.parquet, files are shared by
+// all sinkers since rows of a table may be sunk by any of them
+pub struct ParquetSinker {
+    // meta_manager of the extractor, tables are looked up by source names
+    pub meta_manager: RdbMetaManager,
+    pub router: RdbRouter,
+    pub reverse_router: RdbRouter,
+    pub output_dir: String,
+    pub batch_size: usize,
+    pub ignore_non_insert: bool,
+    // HashMap<(db, tb), ParquetTable>
+    pub tables: Arc<Mutex<HashMap<(String, String), ParquetTable>>>,
+    pub monitor: Arc<Mutex<Monitor>>,
+}
+
+impl Configurable for ParquetSinker {}
+
+#[async_trait]
+impl Sinker for ParquetSinker {
+    async fn sink_dml(&mut self, data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let start_time = Instant::now();
+        let mut data_size = 0;
+        let mut record_count = 0;
+        for row_data in data {
+            if row_data.row_type != RowType::Insert {
+                if self.ignore_non_insert {
+                    continue;
+                }
+                bail! {Error::SinkerError(format!(
+                    "parquet sinker only supports insert rows, got: {}, schema: {}, tb: {}, set [sinker] ignore_non_insert=true to skip them",
+                    row_data.row_type, row_data.schema, row_data.tb
+                ))}
+            }
+
+            let key = (row_data.schema.clone(), row_data.tb.clone());
+            if !self.tables.lock().unwrap().contains_key(&key) {
+                let (cols, col_types) = self.get_routed_cols(&key.0, &key.1).await?;
+                self.open_table(&key, cols, col_types)?;
+            }
+
+            data_size += row_data.data_size;
+            record_count += 1;
+
+            let mut tables = self.tables.lock().unwrap();
+            let table = tables.get_mut(&key).unwrap();
+            table.rows.push(row_data.after.unwrap_or_default());
+            if table.rows.len() >= self.batch_size {
+                table.flush().with_context(|| {
+                    format!("failed to write parquet file of `{}`.`{}`", key.0, key.1)
+                })?;
+            }
+        }
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, record_count, data_size, start_time)
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        let tables: Vec<((String, String), ParquetTable)> =
+            self.tables.lock().unwrap().drain().collect();
+        for ((schema, tb), table) in tables {
+            table.close().with_context(|| {
+                format!("failed to close parquet file of `{}`.`{}`", schema, tb)
+            })?;
+        }
+        self.meta_manager.close().await
+    }
+}
+
+impl ParquetSinker {
+    // rows are routed by the extractor, so tb_meta is fetched by the source names and
+    // its cols are mapped to the routed names
+    async fn get_routed_cols(
+        &mut self,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<(Vec<String>, Vec<ParquetColType>)> {
+        let (src_schema, src_tb) = self.reverse_router.get_tb_map(schema, tb);
+        let (src_schema, src_tb) = (src_schema.to_string(), src_tb.to_string());
+
+        let (src_cols, col_types) =
+            if let Some(meta_manager) = self.meta_manager.mysql_meta_manager.as_mut() {
+                let tb_meta = meta_manager.get_tb_meta(&src_schema, &src_tb).await?;
+                let col_types = tb_meta
+                    .basic
+                    .cols
+                    .iter()
+                    .map(|col| ParquetColType::from_mysql(tb_meta.col_type_map.get(col)))
+                    .collect();
+                (tb_meta.basic.cols.clone(), col_types)
+            } else if let Some(meta_manager) = self.meta_manager.pg_meta_manager.as_mut() {
+                let tb_meta = meta_manager.get_tb_meta(&src_schema, &src_tb).await?;
+                let col_types = tb_meta
+                    .basic
+                    .cols
+                    .iter()
+                    .map(|col| ParquetColType::from_pg(tb_meta.col_type_map.get(col)))
+                    .collect();
+                (tb_meta.basic.cols.clone(), col_types)
+            } else {
+                bail! {Error::Unexpected(
+                    "no available meta_manager in parquet sinker".into()
+                )}
+            };
+
+        let col_map = self.router.get_col_map(&src_schema, &src_tb);
+        let cols = src_cols
+            .into_iter()
+            .map(|col| match col_map.and_then(|col_map| col_map.get(&col)) {
+                Some(dst_col) => dst_col.clone(),
+                None => col,
+            })
+            .collect();
+        Ok((cols, col_types))
+    }
+
+    fn open_table(
+        &self,
+        key: &(String, String),
+        cols: Vec<String>,
+        col_types: Vec<ParquetColType>,
+    ) -> anyhow::Result<()> {
+        let mut tables = self.tables.lock().unwrap();
+        // may be opened by another sinker
+        if tables.contains_key(key) {
+            return Ok(());
+        }
+
+        let file_path = Path::new(&self.output_dir).join(format!("{}.{}.parquet", key.0, key.1));
+        let table = ParquetTable::new(&file_path, cols, col_types)?;
+        log_info!("parquet file created: {}", file_path.display());
+        tables.insert(key.clone(), table);
+        Ok(())
+    }
+}
+
+impl ParquetTable {
+    pub fn new(
+        file_path: &Path,
+        cols: Vec<String>,
+        col_types: Vec<ParquetColType>,
+    ) -> anyhow::Result<Self> {
+        let fields: Vec<Field> = cols
+            .iter()
+            .zip(col_types.iter())
+            .map(|(col, col_type)| Field::new(col, col_type.to_arrow_type(), true))
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+
+        let file = File::create(file_path)
+            .with_context(|| format!("failed to create parquet file: {}", file_path.display()))?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(props))?;
+
+        Ok(Self {
+            writer,
+            schema,
+            cols,
+            col_types,
+            rows: Vec::new(),
+        })
+    }
+
+    // write buffered rows as a row group
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut columns = Vec::with_capacity(self.cols.len());
+        for (col, col_type) in self.cols.iter().zip(self.col_types.iter()) {
+            let values: Vec<Option<&ColValue>> = self
+                .rows
+                .iter()
+                .map(|row| match row.get(col) {
+                    Some(ColValue::None) | None => None,
+                    Some(col_value) => Some(col_value),
+                })
+                .collect();
+            let column = col_type
+                .build_array(&values)
+                .with_context(|| format!("invalid value of col: {}", col))?;
+            columns.push(column);
+        }
+
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+        self.writer.write(&batch)?;
+        self.writer.flush()?;
+        self.rows.clear();
+        Ok(())
+    }
+
+    // the footer is written by close, files without it are unreadable
+    pub fn close(mut self) -> anyhow::Result<()> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+impl ParquetColType {
+    pub fn from_mysql(col_type: Option<&MysqlColType>) -> Self {
+        let col_type = match col_type {
+            Some(col_type) => col_type,
+            None => return Self::String,
+        };
+
+        match col_type {
+            MysqlColType::TinyInt { .. }
+            | MysqlColType::SmallInt { .. }
+            | MysqlColType::MediumInt { .. }
+            | MysqlColType::Int { unsigned: false }
+            | MysqlColType::Year => Self::Int32,
+            MysqlColType::Int { unsigned: true } | MysqlColType::BigInt { unsigned: false } => {
+                Self::Int64
+            }
+            MysqlColType::BigInt { unsigned: true } | MysqlColType::Bit => Self::UInt64,
+            MysqlColType::Float => Self::Float32,
+            MysqlColType::Double => Self::Float64,
+            MysqlColType::Decimal { precision, scale } => {
+                if *precision <= MAX_DECIMAL_PRECISION {
+                    Self::Decimal {
+                        precision: *precision as u8,
+                        scale: *scale as i8,
+                    }
+                } else {
+                    Self::String
+                }
+            }
+            MysqlColType::Date { .. } => Self::Date,
+            MysqlColType::DateTime { .. } | MysqlColType::Timestamp { .. } => Self::Timestamp,
+            MysqlColType::Binary { .. }
+            | MysqlColType::VarBinary { .. }
+            | MysqlColType::TinyBlob
+            | MysqlColType::MediumBlob
+            | MysqlColType::LongBlob
+            | MysqlColType::Blob => Self::Binary,
+            _ => Self::String,
+        }
+    }
+
+    pub fn from_pg(col_type: Option<&PgColType>) -> Self {
+        let col_type = match col_type {
+            Some(col_type) => col_type,
+            None => return Self::String,
+        };
+
+        match col_type.base_value_type() {
+            PgValueType::Boolean => Self::Boolean,
+            PgValueType::Int16 | PgValueType::Int32 => Self::Int32,
+            PgValueType::Int64 => Self::Int64,
+            PgValueType::Float32 => Self::Float32,
+            PgValueType::Float64 => Self::Float64,
+            PgValueType::Date => Self::Date,
+            PgValueType::Timestamp => Self::Timestamp,
+            PgValueType::Bytes => Self::Binary,
+            _ => Self::String,
+        }
+    }
+
+    pub fn to_arrow_type(&self) -> DataType {
+        match self {
+            Self::Boolean => DataType::Boolean,
+            Self::Int32 => DataType::Int32,
+            Self::Int64 => DataType::Int64,
+            Self::UInt64 => DataType::UInt64,
+            Self::Float32 => DataType::Float32,
+            Self::Float64 => DataType::Float64,
+            Self::Decimal { precision, scale } => DataType::Decimal128(*precision, *scale),
+            Self::Date => DataType::Date32,
+            Self::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+            Self::Binary => DataType::Binary,
+            Self::String => DataType::Utf8,
+        }
+    }
+
+    pub fn build_array(&self, values: &[Option<&ColValue>]) -> anyhow::Result<ArrayRef> {
+        let array: ArrayRef = match self {
+            Self::Boolean => {
+                let mut builder = BooleanBuilder::with_capacity(values.len());
+                for &value in values {
+                    builder.append_option(value.map(Self::to_bool).transpose()?);
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Int32 => {
+                let mut builder = Int32Builder::with_capacity(values.len());
+                for &value in values {
+                    let v = value.map(Self::to_i64).transpose()?;
+                    builder.append_option(v.map(i32::try_from).transpose()?);
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Int64 => {
+                let mut builder = Int64Builder::with_capacity(values.len());
+                for &value in values {
+                    builder.append_option(value.map(Self::to_i64).transpose()?);
+                }
+                Arc::new(builder.finish())
+            }
+            Self::UInt64 => {
+                let mut builder = UInt64Builder::with_capacity(values.len());
+                for &value in values {
+                    builder.append_option(value.map(Self::to_u64).transpose()?);
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Float32 => {
+                let mut builder = Float32Builder::with_capacity(values.len());
+                for &value in values {
+                    let v = value.map(Self::to_f64).transpose()?;
+                    builder.append_option(v.map(|v| v as f32));
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Float64 => {
+                let mut builder = Float64Builder::with_capacity(values.len());
+                for &value in values {
+                    builder.append_option(value.map(Self::to_f64).transpose()?);
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Decimal { precision, scale } => {
+                let mut builder = Decimal128Builder::with_capacity(values.len())
+                    .with_precision_and_scale(*precision, *scale)?;
+                for &value in values {
+                    let v = value.map(|v| Self::to_decimal(v, *scale)).transpose()?;
+                    builder.append_option(v);
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Date => {
+                let mut builder = Date32Builder::with_capacity(values.len());
+                for &value in values {
+                    builder.append_option(value.map(Self::to_days).transpose()?.flatten());
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Timestamp => {
+                let mut builder = TimestampMicrosecondBuilder::with_capacity(values.len());
+                for &value in values {
+                    builder.append_option(value.map(Self::to_micros).transpose()?.flatten());
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Binary => {
+                let mut builder = BinaryBuilder::new();
+                for &value in values {
+                    match value {
+                        Some(ColValue::Blob(v)) | Some(ColValue::RawString(v)) => {
+                            builder.append_value(v)
+                        }
+                        Some(v) => builder.append_option(v.to_option_string()),
+                        None => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Self::String => {
+                let mut builder = StringBuilder::new();
+                for &value in values {
+                    builder.append_option(value.and_then(|v| v.to_option_string()));
+                }
+                Arc::new(builder.finish())
+            }
+        };
+        Ok(array)
+    }
+
+    fn to_bool(value: &ColValue) -> anyhow::Result<bool> {
+        match value {
+            ColValue::Bool(v) => Ok(*v),
+            _ => Ok(Self::to_i64(value)? != 0),
+        }
+    }
+
+    fn to_i64(value: &ColValue) -> anyhow::Result<i64> {
+        let v = match value {
+            ColValue::Tiny(v) => *v as i64,
+            ColValue::UnsignedTiny(v) => *v as i64,
+            ColValue::Short(v) => *v as i64,
+            ColValue::UnsignedShort(v) => *v as i64,
+            ColValue::Long(v) => *v as i64,
+            ColValue::UnsignedLong(v) => *v as i64,
+            ColValue::LongLong(v) => *v,
+            ColValue::Year(v) => *v as i64,
+            ColValue::Bool(v) => *v as i64,
+            _ => Self::parse(value)?,
+        };
+        Ok(v)
+    }
+
+    fn to_u64(value: &ColValue) -> anyhow::Result<u64> {
+        match value {
+            ColValue::UnsignedLongLong(v) | ColValue::Bit(v) => Ok(*v),
+            _ => Self::parse(value),
+        }
+    }
+
+    fn to_f64(value: &ColValue) -> anyhow::Result<f64> {
+        match value {
+            ColValue::Float(v) => Ok(*v as f64),
+            ColValue::Double(v) => Ok(*v),
+            _ => Self::parse(value),
+        }
+    }
+
+    fn to_decimal(value: &ColValue, scale: i8) -> anyhow::Result<i128> {
+        let mut decimal: Decimal = Self::parse(value)?;
+        decimal.rescale(scale as u32);
+        Ok(decimal.mantissa())
+    }
+
+    // days since 1970-01-01, None for zero dates
+    fn to_days(value: &ColValue) -> anyhow::Result<Option<i32>> {
+        let str = value.to_option_string().unwrap_or_default();
+        if str.starts_with("0000-00-00") {
+            return Ok(None);
+        }
+        let date = NaiveDate::parse_from_str(&str, "%Y-%m-%d")
+            .with_context(|| format!("invalid date: {}", str))?;
+        Ok(Some(
+            date.signed_duration_since(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
+                .num_days() as i32,
+        ))
+    }
+
+    // micros since 1970-01-01 00:00:00, None for zero datetimes
+    fn to_micros(value: &ColValue) -> anyhow::Result<Option<i64>> {
+        let str = value.to_option_string().unwrap_or_default();
+        if str.starts_with("0000-00-00") {
+            return Ok(None);
+        }
+        let date_time = NaiveDateTime::parse_from_str(&str, "%Y-%m-%d %H:%M:%S%.f")
+            .with_context(|| format!("invalid datetime: {}", str))?;
+        Ok(Some(date_time.and_utc().timestamp_micros()))
+    }
+
+    fn parse<T: FromStr>(value: &ColValue) -> anyhow::Result<T> {
+        let str = value.to_option_string().unwrap_or_default();
+        match str.parse::<T>() {
+            Ok(v) => Ok(v),
+            Err(_) => bail! {Error::SinkerError(format!(
+                "can not convert value: {} to {}",
+                str,
+                std::any::type_name::<T>()
+            ))},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs::File};
+
+    use arrow::{
+        array::{
+            Array, BinaryArray, Date32Array, Decimal128Array, Int64Array, StringArray,
+            TimestampMicrosecondArray,
+        },
+        datatypes::DataType,
+    };
+    use dt_common::meta::{col_value::ColValue, mysql::mysql_col_type::MysqlColType};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::{ParquetColType, ParquetTable};
+
+    #[test]
+    fn test_from_mysql() {
+        let decimal = MysqlColType::Decimal {
+            precision: 10,
+            scale: 2,
+        };
+        assert_eq!(
+            ParquetColType::from_mysql(Some(&decimal)).to_arrow_type(),
+            DataType::Decimal128(10, 2)
+        );
+        let wide_decimal = MysqlColType::Decimal {
+            precision: 65,
+            scale: 2,
+        };
+        assert_eq!(
+            ParquetColType::from_mysql(Some(&wide_decimal)),
+            ParquetColType::String
+        );
+        assert_eq!(
+            ParquetColType::from_mysql(Some(&MysqlColType::BigInt { unsigned: true })),
+            ParquetColType::UInt64
+        );
+        assert_eq!(
+            ParquetColType::from_mysql(Some(&MysqlColType::Time { precision: 0 })),
+            ParquetColType::String
+        );
+        assert_eq!(ParquetColType::from_mysql(None), ParquetColType::String);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let file_path = std::env::temp_dir().join("ape_dts_test_parquet_round_trip.parquet");
+        let cols: Vec<String> = ["id", "price", "created_at", "birthday", "content", "name"]
+            .iter()
+            .map(|i| i.to_string())
+            .collect();
+        let col_types = vec![
+            ParquetColType::Int64,
+            ParquetColType::Decimal {
+                precision: 10,
+                scale: 2,
+            },
+            ParquetColType::Timestamp,
+            ParquetColType::Date,
+            ParquetColType::Binary,
+            ParquetColType::String,
+        ];
+
+        let mut table = ParquetTable::new(&file_path, cols, col_types).unwrap();
+        table.rows.push(HashMap::from([
+            ("id".to_string(), ColValue::LongLong(1)),
+            ("price".to_string(), ColValue::Decimal("12.5".into())),
+            (
+                "created_at".to_string(),
+                ColValue::DateTime("1970-01-01 00:00:01.000002".into()),
+            ),
+            ("birthday".to_string(), ColValue::Date("1970-01-11".into())),
+            ("content".to_string(), ColValue::Blob(vec![1, 2, 3])),
+            ("name".to_string(), ColValue::String("a".into())),
+        ]));
+        // flushed as the first row group
+        table.flush().unwrap();
+        table.rows.push(HashMap::from([
+            ("id".to_string(), ColValue::LongLong(2)),
+            ("price".to_string(), ColValue::None),
+            (
+                "created_at".to_string(),
+                ColValue::DateTime("0000-00-00 00:00:00".into()),
+            ),
+        ]));
+        table.close().unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&file_path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(|i| i.unwrap()).collect();
+        assert_eq!(batches.iter().map(|i| i.num_rows()).sum::<usize>(), 2);
+
+        let batch = &batches[0];
+        let column = |i: usize| batch.column(i).as_any();
+        let ids = column(0).downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(ids.value(0), 1);
+        let prices = column(1).downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(prices.value(0), 1250);
+        let created_ats = column(2)
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(created_ats.value(0), 1_000_002);
+        let birthdays = column(3).downcast_ref::<Date32Array>().unwrap();
+        assert_eq!(birthdays.value(0), 10);
+        let contents = column(4).downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(contents.value(0), &[1, 2, 3]);
+        let names = column(5).downcast_ref::<StringArray>().unwrap();
+        assert_eq!(names.value(0), "a");
+
+        let batch = batches.last().unwrap();
+        assert!(batch.column(1).is_null(batch.num_rows() - 1));
+        assert!(batch.column(2).is_null(batch.num_rows() - 1));
+        assert!(batch.column(5).is_null(batch.num_rows() - 1));
+
+        std::fs::remove_file(&file_path).unwrap();
+    }
+}
@@ -1,3 +1,4 @@
 pub mod pg_checker;
+pub mod pg_partman_router;
 pub mod pg_sinker;
 pub mod pg_struct_sinker;
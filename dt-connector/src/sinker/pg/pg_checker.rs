@@ -21,7 +21,7 @@ use crate::{
     rdb_query_builder::RdbQueryBuilder,
     rdb_router::RdbRouter,
     sinker::{base_checker::BaseChecker, base_sinker::BaseSinker},
-    Sinker,
+    Configurable, Sinker,
 };
 
 #[derive(Clone)]
@@ -33,8 +33,11 @@ pub struct PgChecker {
     pub batch_size: usize,
     pub monitor: Arc<Mutex<Monitor>>,
     pub filter: RdbFilter,
+    pub sample_percent: u8,
 }
 
+impl Configurable for PgChecker {}
+
 #[async_trait]
 impl Sinker for PgChecker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
@@ -42,6 +45,14 @@ impl Sinker for PgChecker {
             return Ok(());
         }
 
+        if self.sample_percent < 100 {
+            let tb_meta = self.meta_manager.get_tb_meta_by_row_data(&data[0]).await?;
+            BaseChecker::sample_row_datas(&mut data, &tb_meta.basic, self.sample_percent);
+            if data.is_empty() {
+                return Ok(());
+            }
+        }
+
         if !batch {
             self.serial_check(data).await?;
         } else {
@@ -106,6 +117,7 @@ impl PgChecker {
                 miss.push(miss_log);
             }
         }
+        BaseChecker::update_check_monitor(&self.monitor, data.len(), &miss, &diff);
         BaseChecker::log_dml(miss, diff);
 
         BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), 0, start_time)
@@ -145,6 +157,7 @@ impl PgChecker {
             &self.reverse_router,
         )
         .await?;
+        BaseChecker::update_check_monitor(&self.monitor, batch_size, &miss, &diff);
         BaseChecker::log_dml(miss, diff);
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, 0, start_time)
@@ -155,6 +168,7 @@ impl PgChecker {
             let src_statement = &mut src_data.statement;
             let schema = match src_statement {
                 StructStatement::PgCreateSchema(s) => s.schema.name.clone(),
+                StructStatement::PgCreateDomain(s) => s.domain.schema_name.clone(),
                 StructStatement::PgCreateTable(s) => s.table.schema_name.clone(),
                 _ => String::new(),
             };
@@ -171,6 +185,13 @@ impl PgChecker {
                     StructStatement::PgCreateSchema(dst_statement)
                 }
 
+                StructStatement::PgCreateDomain(statement) => struct_fetcher
+                    .get_create_domain_statements()
+                    .await?
+                    .into_iter()
+                    .find(|i| i.domain.domain_name == statement.domain.domain_name)
+                    .map_or(StructStatement::Unknown, StructStatement::PgCreateDomain),
+
                 StructStatement::PgCreateTable(statement) => {
                     let mut dst_statement = struct_fetcher
                         .get_create_table_statements(&statement.table.table_name)
@@ -0,0 +1,448 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use dt_common::{
+    log_info, log_warn,
+    meta::{
+        pg::{pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
+        row_data::RowData,
+    },
+};
+use sqlx::{Pool, Postgres, Row};
+
+// max length of identifiers in pg, pg_partman truncates the parent table name to fit the suffix
+const MAX_IDENTIFIER_LEN: usize = 63;
+
+#[derive(Debug, Clone, PartialEq)]
+enum PartmanInterval {
+    Id(i64),
+    Yearly,
+    Quarterly,
+    Monthly,
+    Weekly,
+    Daily,
+    Hourly,
+}
+
+#[derive(Debug, Clone)]
+struct PartmanConfig {
+    control: String,
+    interval: PartmanInterval,
+    datetime_string: String,
+}
+
+// route inserts of pg_partman managed tables to child tables directly, the child table name is
+// computed from the control column value and the interval in part_config,
+// e.g. control: created_at, interval: 1 day, value: 2024-01-01 10:00:00 -> tb_p20240101
+#[derive(Clone)]
+pub struct PgPartmanRouter {
+    conn_pool: Pool<Postgres>,
+    partman_schema: Option<String>,
+    partman_schema_fetched: bool,
+    // key: schema.tb, value: None if not managed by pg_partman or the interval is not supported
+    configs: HashMap<String, Option<PartmanConfig>>,
+    // key: schema.tb, value: existing child tables
+    children: HashMap<String, HashSet<String>>,
+    // schema.child_tb, not found even after run_maintenance
+    missing_children: HashSet<String>,
+}
+
+impl PgPartmanRouter {
+    pub fn new(conn_pool: Pool<Postgres>) -> Self {
+        Self {
+            conn_pool,
+            partman_schema: None,
+            partman_schema_fetched: false,
+            configs: HashMap::new(),
+            children: HashMap::new(),
+            missing_children: HashSet::new(),
+        }
+    }
+
+    // returns the tb_meta of the child table to insert into, None to insert into the parent
+    pub async fn route(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        row_data: &RowData,
+    ) -> anyhow::Result<Option<PgTbMeta>> {
+        let schema = &tb_meta.basic.schema;
+        let tb = &tb_meta.basic.tb;
+        let config = match self.get_config(schema, tb, tb_meta).await? {
+            Some(config) => config,
+            None => return Ok(None),
+        };
+
+        let value = row_data
+            .after
+            .as_ref()
+            .and_then(|after| after.get(&config.control))
+            .and_then(|v| v.to_option_string());
+        let suffix = match value.and_then(|v| Self::get_suffix(&config, &v)) {
+            Some(suffix) => suffix,
+            None => return Ok(None),
+        };
+
+        let child_tb = Self::get_child_tb(tb, &suffix);
+        if !self.child_exists(schema, tb, &child_tb).await? {
+            return Ok(None);
+        }
+
+        let mut child_tb_meta = tb_meta.clone();
+        child_tb_meta.basic.tb = child_tb;
+        Ok(Some(child_tb_meta))
+    }
+
+    async fn get_config(
+        &mut self,
+        schema: &str,
+        tb: &str,
+        tb_meta: &PgTbMeta,
+    ) -> anyhow::Result<Option<PartmanConfig>> {
+        let full_tb = format!("{}.{}", schema, tb);
+        if let Some(config) = self.configs.get(&full_tb) {
+            return Ok(config.clone());
+        }
+
+        let partman_schema = match self.get_partman_schema().await? {
+            Some(partman_schema) => partman_schema,
+            None => {
+                self.configs.insert(full_tb, None);
+                return Ok(None);
+            }
+        };
+
+        let sql = format!(
+            r#"SELECT control, partition_interval, datetime_string, epoch
+            FROM "{}".part_config WHERE parent_table = $1"#,
+            partman_schema
+        );
+        let row = sqlx::query(&sql)
+            .bind(&full_tb)
+            .fetch_optional(&self.conn_pool)
+            .await?;
+
+        let mut config = None;
+        if let Some(row) = row {
+            let control: String = row.try_get("control")?;
+            let partition_interval: String = row.try_get("partition_interval")?;
+            let datetime_string: Option<String> = row.try_get("datetime_string")?;
+            let epoch: String = row.try_get("epoch")?;
+
+            let is_time_col = match tb_meta.col_type_map.get(&control) {
+                Some(col_type) => {
+                    col_type.value_type == PgValueType::Timestamp
+                        || col_type.value_type == PgValueType::Date
+                }
+                None => false,
+            };
+            // timestamptz partitions depend on the session timezone, they are not routed
+            match Self::parse_interval(&partition_interval, is_time_col) {
+                Some(interval) if epoch == "none" => {
+                    log_info!(
+                        "table: {} managed by pg_partman, control: {}, interval: {}, inserts will be routed to child tables",
+                        full_tb,
+                        control,
+                        partition_interval
+                    );
+                    config = Some(PartmanConfig {
+                        control,
+                        interval,
+                        datetime_string: datetime_string.unwrap_or_default(),
+                    });
+                }
+                _ => log_warn!(
+                    "table: {} managed by pg_partman, but control: {}, interval: {}, epoch: {} not supported, inserts will go to the parent table",
+                    full_tb,
+                    control,
+                    partition_interval,
+                    epoch
+                ),
+            }
+        }
+        self.configs.insert(full_tb, config.clone());
+        Ok(config)
+    }
+
+    async fn get_partman_schema(&mut self) -> anyhow::Result<Option<String>> {
+        if self.partman_schema_fetched {
+            return Ok(self.partman_schema.clone());
+        }
+
+        let sql = "SELECT n.nspname FROM pg_extension e
+            JOIN pg_namespace n ON e.extnamespace = n.oid
+            WHERE e.extname = 'pg_partman'";
+        let row = sqlx::query(sql).fetch_optional(&self.conn_pool).await?;
+        self.partman_schema = match row {
+            Some(row) => Some(row.try_get("nspname")?),
+            None => {
+                log_warn!("pg_partman not installed in target, inserts will go to parent tables");
+                None
+            }
+        };
+        self.partman_schema_fetched = true;
+        Ok(self.partman_schema.clone())
+    }
+
+    async fn child_exists(
+        &mut self,
+        schema: &str,
+        tb: &str,
+        child_tb: &str,
+    ) -> anyhow::Result<bool> {
+        let full_tb = format!("{}.{}", schema, tb);
+        if !self.children.contains_key(&full_tb) {
+            self.load_children(schema, tb).await?;
+        }
+        if self.children[&full_tb].contains(child_tb) {
+            return Ok(true);
+        }
+
+        // the child may be created since loaded, or not be premade yet, run maintenance
+        // once for each missing child to avoid calling it for every row
+        let full_child_tb = format!("{}.{}", schema, child_tb);
+        if self.missing_children.contains(&full_child_tb) {
+            return Ok(false);
+        }
+        self.run_maintenance(&full_tb).await?;
+        self.load_children(schema, tb).await?;
+
+        let exists = self.children[&full_tb].contains(child_tb);
+        if !exists {
+            self.missing_children.insert(full_child_tb);
+            log_warn!(
+                "child table: {}.{} of pg_partman table: {} not exists, inserts will go to the parent table",
+                schema,
+                child_tb,
+                full_tb
+            );
+        }
+        Ok(exists)
+    }
+
+    async fn load_children(&mut self, schema: &str, tb: &str) -> anyhow::Result<()> {
+        let sql = "SELECT c.relname FROM pg_inherits i
+            JOIN pg_class c ON i.inhrelid = c.oid
+            JOIN pg_class p ON i.inhparent = p.oid
+            JOIN pg_namespace n ON p.relnamespace = n.oid
+            WHERE n.nspname = $1 AND p.relname = $2";
+        let rows = sqlx::query(sql)
+            .bind(schema)
+            .bind(tb)
+            .fetch_all(&self.conn_pool)
+            .await?;
+
+        let mut children = HashSet::new();
+        for row in rows {
+            children.insert(row.try_get("relname")?);
+        }
+        self.children.insert(format!("{}.{}", schema, tb), children);
+        Ok(())
+    }
+
+    async fn run_maintenance(&self, full_tb: &str) -> anyhow::Result<()> {
+        if let Some(partman_schema) = &self.partman_schema {
+            let sql = format!(r#"SELECT "{}".run_maintenance($1)"#, partman_schema);
+            log_info!(
+                "run pg_partman maintenance, sql: {}, table: {}",
+                sql,
+                full_tb
+            );
+            sqlx::query(&sql)
+                .bind(full_tb)
+                .execute(&self.conn_pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    // partition_interval in part_config is the text of the interval,
+    // e.g. 1 day, 7 days, 1 mon, 3 mons, 1 year, 01:00:00, or a number for id based partitions
+    fn parse_interval(partition_interval: &str, is_time_col: bool) -> Option<PartmanInterval> {
+        if !is_time_col {
+            return match partition_interval.trim().parse::<i64>() {
+                Ok(id_interval) if id_interval > 0 => Some(PartmanInterval::Id(id_interval)),
+                _ => None,
+            };
+        }
+
+        match partition_interval.trim() {
+            "1 year" => Some(PartmanInterval::Yearly),
+            "3 mons" => Some(PartmanInterval::Quarterly),
+            "1 mon" => Some(PartmanInterval::Monthly),
+            "7 days" => Some(PartmanInterval::Weekly),
+            "1 day" => Some(PartmanInterval::Daily),
+            "01:00:00" => Some(PartmanInterval::Hourly),
+            _ => None,
+        }
+    }
+
+    fn get_suffix(config: &PartmanConfig, value: &str) -> Option<String> {
+        if let PartmanInterval::Id(id_interval) = config.interval {
+            let id: i64 = value.parse().ok()?;
+            return Some((id.div_euclid(id_interval) * id_interval).to_string());
+        }
+
+        let datetime = Self::parse_datetime(value)?;
+        let date = datetime.date();
+        let start = match config.interval {
+            PartmanInterval::Yearly => {
+                NaiveDate::from_ymd_opt(date.year(), 1, 1)?.and_hms_opt(0, 0, 0)?
+            }
+            PartmanInterval::Quarterly => {
+                let month = (date.month() - 1) / 3 * 3 + 1;
+                NaiveDate::from_ymd_opt(date.year(), month, 1)?.and_hms_opt(0, 0, 0)?
+            }
+            PartmanInterval::Monthly => date.with_day(1)?.and_hms_opt(0, 0, 0)?,
+            PartmanInterval::Weekly => {
+                let days = date.weekday().num_days_from_monday() as i64;
+                (date - Duration::days(days)).and_hms_opt(0, 0, 0)?
+            }
+            PartmanInterval::Daily => date.and_hms_opt(0, 0, 0)?,
+            PartmanInterval::Hourly => date.and_hms_opt(datetime.hour(), 0, 0)?,
+            PartmanInterval::Id(_) => return None,
+        };
+        Some(Self::format_datetime(&start, &config.datetime_string))
+    }
+
+    // values of timestamp/date cols: 2024-01-01 10:00:00.123456, 2024-01-01
+    fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+        if value.len() >= 19 {
+            if let Ok(datetime) = NaiveDateTime::parse_from_str(&value[..19], "%Y-%m-%d %H:%M:%S") {
+                return Some(datetime);
+            }
+        }
+        if value.len() >= 10 {
+            if let Ok(date) = NaiveDate::parse_from_str(&value[..10], "%Y-%m-%d") {
+                return date.and_hms_opt(0, 0, 0);
+            }
+        }
+        None
+    }
+
+    // datetime_string is a pg to_char template, e.g. YYYYMMDD, YYYY_MM_DD, IYYY"w"IW, YYYY"q"Q
+    fn format_datetime(datetime: &NaiveDateTime, datetime_string: &str) -> String {
+        let mut result = String::new();
+        let mut rest = datetime_string;
+        while !rest.is_empty() {
+            if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                result.push_str(&quoted[..end]);
+                rest = quoted.get(end + 1..).unwrap_or("");
+                continue;
+            }
+
+            let (token, value) = if rest.starts_with("HH24") {
+                ("HH24", format!("{:02}", datetime.hour()))
+            } else if rest.starts_with("IYYY") {
+                ("IYYY", format!("{:04}", datetime.iso_week().year()))
+            } else if rest.starts_with("YYYY") {
+                ("YYYY", format!("{:04}", datetime.year()))
+            } else if rest.starts_with("IW") {
+                ("IW", format!("{:02}", datetime.iso_week().week()))
+            } else if rest.starts_with("MM") {
+                ("MM", format!("{:02}", datetime.month()))
+            } else if rest.starts_with("MI") {
+                ("MI", format!("{:02}", datetime.minute()))
+            } else if rest.starts_with("DD") {
+                ("DD", format!("{:02}", datetime.day()))
+            } else if rest.starts_with("SS") {
+                ("SS", format!("{:02}", datetime.second()))
+            } else if rest.starts_with('Q') {
+                ("Q", ((datetime.month() - 1) / 3 + 1).to_string())
+            } else {
+                let c = rest.chars().next().unwrap();
+                (&rest[..c.len_utf8()], c.to_string())
+            };
+            result.push_str(&value);
+            rest = &rest[token.len()..];
+        }
+        result
+    }
+
+    fn get_child_tb(tb: &str, suffix: &str) -> String {
+        let suffix = format!("_p{}", suffix);
+        let mut end = MAX_IDENTIFIER_LEN
+            .saturating_sub(suffix.len())
+            .min(tb.len());
+        while !tb.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}{}", &tb[..end], suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_suffix(interval: PartmanInterval, datetime_string: &str, value: &str) -> String {
+        let config = PartmanConfig {
+            control: "col_1".into(),
+            interval,
+            datetime_string: datetime_string.into(),
+        };
+        PgPartmanRouter::get_suffix(&config, value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(
+            PgPartmanRouter::parse_interval("1 day", true),
+            Some(PartmanInterval::Daily)
+        );
+        assert_eq!(
+            PgPartmanRouter::parse_interval("01:00:00", true),
+            Some(PartmanInterval::Hourly)
+        );
+        assert_eq!(PgPartmanRouter::parse_interval("2 days", true), None);
+        assert_eq!(
+            PgPartmanRouter::parse_interval("100000", false),
+            Some(PartmanInterval::Id(100000))
+        );
+        assert_eq!(PgPartmanRouter::parse_interval("1 day", false), None);
+    }
+
+    #[test]
+    fn test_get_suffix() {
+        let value = "2024-02-29 10:20:30.123456";
+        assert_eq!(
+            get_suffix(PartmanInterval::Daily, "YYYYMMDD", value),
+            "20240229"
+        );
+        assert_eq!(
+            get_suffix(PartmanInterval::Daily, "YYYY_MM_DD", "2024-02-29"),
+            "2024_02_29"
+        );
+        assert_eq!(
+            get_suffix(PartmanInterval::Hourly, "YYYYMMDD_HH24MISS", value),
+            "20240229_100000"
+        );
+        assert_eq!(
+            get_suffix(PartmanInterval::Monthly, "YYYYMM", value),
+            "202402"
+        );
+        assert_eq!(get_suffix(PartmanInterval::Yearly, "YYYY", value), "2024");
+        assert_eq!(
+            get_suffix(PartmanInterval::Quarterly, r#"YYYY"q"Q"#, value),
+            "2024q1"
+        );
+        assert_eq!(
+            get_suffix(PartmanInterval::Weekly, r#"IYYY"w"IW"#, value),
+            "2024w09"
+        );
+        assert_eq!(get_suffix(PartmanInterval::Id(1000), "", "12345"), "12000");
+    }
+
+    #[test]
+    fn test_get_child_tb() {
+        assert_eq!(
+            PgPartmanRouter::get_child_tb("tb_1", "20240101"),
+            "tb_1_p20240101"
+        );
+        let tb = "a".repeat(60);
+        assert_eq!(
+            PgPartmanRouter::get_child_tb(&tb, "20240101"),
+            format!("{}_p20240101", "a".repeat(53))
+        );
+    }
+}
@@ -1,39 +1,114 @@
 use std::{
+    collections::{BTreeSet, HashMap, HashSet},
     str::FromStr,
     sync::{Arc, Mutex, RwLock},
     time::Instant,
 };
 
 use crate::{
-    call_batch_fn, close_conn_pool, data_marker::DataMarker, rdb_query_builder::RdbQueryBuilder,
-    rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker,
+    call_batch_fn_with_retry, call_serial_fn_with_retry, close_conn_pool,
+    data_marker::DataMarker,
+    rdb_query_builder::RdbQueryBuilder,
+    rdb_router::RdbRouter,
+    sinker::{
+        base_sinker::BaseSinker, batch_sizer::BatchSizer,
+        connection_pool_metrics::ConnectionPoolMetrics, dead_letter_queue::DeadLetterQueue,
+        pg::pg_partman_router::PgPartmanRouter,
+    },
+    Configurable, Sinker,
 };
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use dt_common::{
-    log_error, log_info,
-    meta::ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
-    monitor::monitor::Monitor,
+    config::{
+        config_enums::{CollationPolicy, PgConflictMode, TableLockMode},
+        dml_function_config::DmlFunctionConfig,
+        sinker_config::SinkerConfig,
+        task_config::TaskConfig,
+    },
+    error::Error,
+    log_error, log_info, log_warn,
+    meta::{
+        col_value::ColValue,
+        ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
+        pg::pg_tb_meta::PgTbMeta,
+    },
+    monitor::{counter_type::CounterType, monitor::Monitor},
+    utils::time_util::TimeUtil,
 };
 use sqlx::{
     postgres::{PgConnectOptions, PgPoolOptions},
-    Executor, Pool, Postgres,
+    Executor, Pool, Postgres, Row, Transaction,
 };
 
 use dt_common::meta::{pg::pg_meta_manager::PgMetaManager, row_data::RowData, row_type::RowType};
 
 use async_trait::async_trait;
 
-#[derive(Clone)]
 pub struct PgSinker {
     pub url: String,
     pub conn_pool: Pool<Postgres>,
     pub meta_manager: PgMetaManager,
     pub router: RdbRouter,
-    pub batch_size: usize,
+    pub batch_sizer: BatchSizer,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
-    pub replace: bool,
+    pub conflict_mode: PgConflictMode,
+    pub warn_on_unlogged: bool,
+    pub reported_unlogged_tbs: HashSet<String>,
+    pub use_dml_functions: bool,
+    // HashMap<(schema, tb), DmlFunctionConfig>, configured + auto-detected
+    pub dml_functions: HashMap<(String, String), DmlFunctionConfig>,
+    pub table_lock: TableLockMode,
+    pub lock_timeout_ms: u64,
+    // lock rows by pg_try_advisory_xact_lock before UPDATE/DELETE, for multiple workers
+    // writing the same table concurrently
+    pub use_advisory_locks: bool,
+    pub lock_retry_count: u32,
+    pub lock_retry_delay_ms: u64,
+    // source is not pg, append COLLATE of target columns in upserts
+    pub apply_collations: bool,
+    pub collation_mismatch_policy: CollationPolicy,
+    // used to compare collations of source and target columns, only if source is pg
+    pub src_meta_manager: Option<PgMetaManager>,
+    pub reverse_router: RdbRouter,
+    pub checked_collation_tbs: HashSet<String>,
+    // insert into pg_partman child tables directly instead of the parent
+    pub partman_aware: bool,
+    pub partman_router: PgPartmanRouter,
+    // for tables with EXCLUDE constraints, skip inserts violating them by
+    // ON CONFLICT ON CONSTRAINT ... DO NOTHING, only if conflict_mode is not do_update
+    pub ignore_exclude_conflicts: bool,
+    // HashMap<(schema, tb), conflict cols>, targets of ON CONFLICT in upserts instead of id_cols
+    pub conflict_cols: HashMap<(String, String), Vec<String>>,
+    pub pool_metrics: ConnectionPoolMetrics<Postgres>,
+    // transaction of the chunk being sunk by sink_dml_in_tx, taken by each sub batch and
+    // put back after it succeeds
+    pub chunk_tx: Option<Transaction<'static, Postgres>>,
+    pub in_chunk_tx: bool,
+    pub deadlock_max_retries: u32,
+    pub deadlock_retry_interval_ms: u64,
+    // replay a sub batch failed by connection errors once the pool recovers, 0 means disabled
+    pub reconnect_max_wait_secs: u64,
+    pub reconnect_interval_ms: u64,
+    // rows rejected by the target are moved here instead of failing the task
+    pub dead_letter_queue: Option<Arc<async_mutex::Mutex<DeadLetterQueue>>>,
+}
+
+impl Configurable for PgSinker {
+    fn update_config(&mut self, config: &TaskConfig) -> anyhow::Result<()> {
+        if let SinkerConfig::Pg { batch_size, .. } = &config.sinker {
+            if *batch_size != self.batch_sizer.batch_size {
+                log_info!(
+                    "PgSinker batch_size updated: {} -> {}",
+                    self.batch_sizer.batch_size,
+                    batch_size
+                );
+                self.batch_sizer.reset(*batch_size);
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -43,23 +118,106 @@ impl Sinker for PgSinker {
             return Ok(());
         }
 
-        if !batch {
-            self.serial_sink(&data).await?;
+        if self.collation_mismatch_policy != CollationPolicy::Ignore {
+            for row_data in data.iter() {
+                self.check_collations(row_data).await?;
+            }
+        }
+
+        // the chunk transaction is aborted by the conflict or lost with the connection,
+        // it can not be retried by sub batch
+        let max_retries = if self.in_chunk_tx {
+            0
+        } else {
+            self.deadlock_max_retries
+        };
+        // dml functions are called row by row, updates are batched by serial_sink
+        if !batch || self.use_dml_functions || data[0].row_type == RowType::Update {
+            call_serial_fn_with_retry!(
+                self,
+                &data,
+                Self::serial_sink,
+                max_retries,
+                !self.in_chunk_tx
+            );
         } else {
             match data[0].row_type {
                 RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
+                    call_batch_fn_with_retry!(
+                        self,
+                        data,
+                        Self::batch_insert,
+                        max_retries,
+                        !self.in_chunk_tx
+                    );
                 }
                 RowType::Delete => {
-                    call_batch_fn!(self, data, Self::batch_delete);
+                    call_batch_fn_with_retry!(
+                        self,
+                        data,
+                        Self::batch_delete,
+                        max_retries,
+                        !self.in_chunk_tx
+                    );
                 }
-                _ => self.serial_sink(&data).await?,
+                // sunk by serial_sink
+                RowType::Update => {}
             }
         }
         Ok(())
     }
 
+    async fn sink_dml_in_tx(&mut self, data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_batches_in_tx(vec![(data, batch)]).await
+    }
+
+    async fn sink_dml_batches_in_tx(
+        &mut self,
+        batches: Vec<(Vec<RowData>, bool)>,
+    ) -> anyhow::Result<()> {
+        if batches.iter().all(|(data, _)| data.is_empty()) {
+            return Ok(());
+        }
+
+        self.chunk_tx = Some(self.pool_metrics.begin().await?);
+        self.in_chunk_tx = true;
+        let mut result = Ok(());
+        for (data, batch) in batches {
+            result = self.sink_dml(data, batch).await;
+            // a failed sub batch may have dropped the transaction, later ones must not
+            // begin another
+            if result.is_err() {
+                break;
+            }
+        }
+        self.in_chunk_tx = false;
+
+        match self.chunk_tx.take() {
+            Some(tx) => match result {
+                Ok(_) => tx
+                    .commit()
+                    .await
+                    .context("failed to commit chunk transaction"),
+                Err(error) => {
+                    if let Err(rollback_error) = tx.rollback().await {
+                        log_error!(
+                            "failed to rollback chunk transaction, error: {}",
+                            rollback_error
+                        );
+                    }
+                    Err(error)
+                }
+            },
+            // the sub batch failed has dropped the transaction, which rolls it back
+            None => result,
+        }
+    }
+
     async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        if self.table_lock != TableLockMode::None {
+            return self.sink_ddl_with_table_lock(data).await;
+        }
+
         for ddl_data in data {
             let (schema, _tb) = ddl_data.get_schema_tb();
             let conn_options = PgConnectOptions::from_str(&self.url)?;
@@ -95,42 +253,243 @@ impl Sinker for PgSinker {
     async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
         for ddl_data in data.iter() {
             self.meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+
+            let (schema, tb) = ddl_data.get_schema_tb();
+            self.checked_collation_tbs
+                .remove(&format!(r#""{}"."{}""#, schema, tb));
+            // ddl_data has been routed, map it back to the source table
+            if let Some(src_meta_manager) = self.src_meta_manager.as_mut() {
+                let (src_schema, src_tb) = self.reverse_router.get_tb_map(&schema, &tb);
+                src_meta_manager.invalidate_cache(&src_schema, &src_tb);
+            }
         }
         Ok(())
     }
 
     async fn close(&mut self) -> anyhow::Result<()> {
         self.meta_manager.close().await?;
+        if let Some(src_meta_manager) = &self.src_meta_manager {
+            src_meta_manager.close().await?;
+        }
         return close_conn_pool!(self);
     }
 }
 
 impl PgSinker {
+    // conflict_cols of each table should be the cols of a primary key or unique constraint
+    pub async fn check_conflict_cols(
+        meta_manager: &mut PgMetaManager,
+        conflict_cols: &HashMap<(String, String), Vec<String>>,
+    ) -> anyhow::Result<()> {
+        for ((schema, tb), cols) in conflict_cols.iter() {
+            let tb_meta = meta_manager.get_tb_meta(schema, tb).await?;
+            if !Self::is_key_cols(&tb_meta.basic.key_map, cols) {
+                bail! {Error::ConfigError(format!(
+                    "conflict_cols: [{}] of \"{}\".\"{}\" are not cols of a primary key or unique constraint",
+                    cols.join(","),
+                    schema,
+                    tb
+                ))}
+            }
+        }
+        Ok(())
+    }
+
+    fn is_key_cols(key_map: &HashMap<String, Vec<String>>, cols: &[String]) -> bool {
+        let cols: HashSet<&String> = cols.iter().collect();
+        key_map.values().any(|key_cols| {
+            key_cols.len() == cols.len() && key_cols.iter().all(|i| cols.contains(i))
+        })
+    }
+
+    // sub batches in sink_dml_in_tx run in the chunk transaction instead of their own
+    async fn begin_tx(&mut self) -> anyhow::Result<Transaction<'static, Postgres>> {
+        match self.chunk_tx.take() {
+            Some(tx) => Ok(tx),
+            None => self.pool_metrics.begin().await,
+        }
+    }
+
+    async fn commit_tx(&mut self, tx: Transaction<'static, Postgres>) -> anyhow::Result<()> {
+        if self.in_chunk_tx {
+            self.chunk_tx = Some(tx);
+        } else {
+            tx.commit().await?;
+        }
+        Ok(())
+    }
+
+    // execute ddls of the batch in a transaction, target tables are locked before any ddl
+    // and released by COMMIT
+    async fn sink_ddl_with_table_lock(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        let mut lock_tbs = Vec::new();
+        for ddl_data in data.iter() {
+            match ddl_data.ddl_type {
+                DdlType::AlterTable
+                | DdlType::DropTable
+                | DdlType::TruncateTable
+                | DdlType::RenameTable
+                | DdlType::CreateIndex
+                | DdlType::DropIndex => {
+                    let (schema, tb) = ddl_data.get_schema_tb();
+                    if !tb.is_empty() && !lock_tbs.contains(&(schema.clone(), tb.clone())) {
+                        lock_tbs.push((schema, tb));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut tx = self.conn_pool.begin().await?;
+        let sql = format!("SET LOCAL lock_timeout = {}", self.lock_timeout_ms);
+        sqlx::query(&sql).execute(&mut tx).await?;
+
+        let lock_mode = self.table_lock.to_pg_lock_mode();
+        let start_time = Instant::now();
+        for (schema, tb) in lock_tbs.iter() {
+            let sql = format!(r#"LOCK TABLE "{}"."{}" IN {} MODE"#, schema, tb, lock_mode);
+            log_info!("lock table, sql: {}", sql);
+            sqlx::query(&sql)
+                .execute(&mut tx)
+                .await
+                .with_context(|| format!("failed to lock table, sql: {}", sql))?;
+        }
+
+        for ddl_data in data {
+            let (schema, _tb) = ddl_data.get_schema_tb();
+            if !schema.is_empty() {
+                match ddl_data.ddl_type {
+                    DdlType::CreateSchema | DdlType::DropSchema | DdlType::AlterSchema => {}
+                    _ => {
+                        let sql = format!("SET LOCAL search_path = '{}'", schema);
+                        sqlx::query(&sql).execute(&mut tx).await?;
+                    }
+                }
+            }
+
+            let sql = ddl_data.to_sql();
+            log_info!("sink ddl, schema: {}, sql: {}", schema, sql);
+            sqlx::query(&sql).execute(&mut tx).await?;
+        }
+        tx.commit().await?;
+
+        if !lock_tbs.is_empty() {
+            log_info!(
+                "table lock released, mode: {}, tables: {:?}, held: {} ms",
+                lock_mode,
+                lock_tbs,
+                start_time.elapsed().as_millis()
+            );
+        }
+        Ok(())
+    }
+
     async fn serial_sink(&mut self, data: &[RowData]) -> anyhow::Result<()> {
         let start_time = Instant::now();
         let mut data_size = 0;
 
-        let mut tx = self.conn_pool.begin().await?;
+        let mut tx = self.begin_tx().await?;
         if let Some(sql) = self.get_data_marker_sql() {
             sqlx::query(&sql)
                 .execute(&mut tx)
                 .await
                 .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
         }
+        if self.use_advisory_locks {
+            self.acquire_advisory_locks(&mut tx, data).await?;
+        }
+        // consecutive updates of a table without key changes are executed by
+        // UPDATE ... FROM (VALUES ...), a key updated multiple times flushes them first
+        let batch_sizer = self.batch_sizer.clone();
+        let mut update_segment: Vec<&RowData> = Vec::new();
+        let mut update_segment_keys = HashSet::new();
+        let mut update_tb_meta: Option<PgTbMeta> = None;
         for row_data in data.iter() {
             data_size += row_data.data_size;
 
+            if row_data.row_type == RowType::Update && !self.use_dml_functions {
+                let tb_meta = self.meta_manager.get_tb_meta_by_row_data(row_data).await?;
+                if Self::is_batch_updatable(row_data, tb_meta) {
+                    Self::report_unlogged(
+                        &mut self.reported_unlogged_tbs,
+                        self.warn_on_unlogged,
+                        tb_meta,
+                    );
+                    let (_, key) = Self::get_advisory_lock_key(row_data, &tb_meta.basic.id_cols);
+                    let same_tb = update_tb_meta.as_ref().is_some_and(|i| {
+                        i.basic.schema == tb_meta.basic.schema && i.basic.tb == tb_meta.basic.tb
+                    });
+                    if !same_tb || update_segment_keys.contains(&key) {
+                        if let Some(segment_tb_meta) = &update_tb_meta {
+                            Self::batch_update(
+                                &mut tx,
+                                segment_tb_meta,
+                                &update_segment,
+                                &batch_sizer,
+                            )
+                            .await?;
+                        }
+                        update_segment.clear();
+                        update_segment_keys.clear();
+                        update_tb_meta = Some(tb_meta.clone());
+                    }
+                    update_segment_keys.insert(key);
+                    update_segment.push(row_data);
+                    continue;
+                }
+            }
+            if let Some(segment_tb_meta) = update_tb_meta.take() {
+                Self::batch_update(&mut tx, &segment_tb_meta, &update_segment, &batch_sizer)
+                    .await?;
+                update_segment.clear();
+                update_segment_keys.clear();
+            }
+
+            let dml_function = if self.use_dml_functions {
+                self.get_dml_function(row_data).await?
+            } else {
+                None
+            };
+
             let tb_meta = self.meta_manager.get_tb_meta_by_row_data(row_data).await?;
-            let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, None);
+            Self::report_unlogged(
+                &mut self.reported_unlogged_tbs,
+                self.warn_on_unlogged,
+                tb_meta,
+            );
+            let child_tb_meta = if self.partman_aware && row_data.row_type == RowType::Insert {
+                self.partman_router.route(tb_meta, row_data).await?
+            } else {
+                None
+            };
+            let tb_meta = child_tb_meta.as_ref().unwrap_or(tb_meta);
+            let conflict_cols = self
+                .conflict_cols
+                .get(&(row_data.schema.clone(), row_data.tb.clone()));
+            let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, None)
+                .with_collations(self.apply_collations)
+                .with_exclude_conflicts_ignored(self.ignore_exclude_conflicts)
+                .with_conflict_cols(conflict_cols)
+                .with_conflict_do_nothing(self.conflict_mode == PgConflictMode::DoNothing);
 
-            let query_info = query_builder.get_query_info(row_data, self.replace)?;
+            let query_info = if let Some(function_name) = &dml_function {
+                query_builder.get_pg_function_query(row_data, function_name)?
+            } else {
+                let replace = self.conflict_mode == PgConflictMode::DoUpdate;
+                query_builder.get_query_info(row_data, replace)?
+            };
             let query = query_builder.create_pg_query(&query_info);
-            query
-                .execute(&mut tx)
-                .await
-                .with_context(|| format!("serial sink failed, row_data: [{}]", row_data))?;
+            query.execute(&mut tx).await.with_context(|| {
+                format!(
+                    "serial sink failed, schema: {}, tb: {}, sql: [{}], row_data: [{}]",
+                    tb_meta.basic.schema, tb_meta.basic.tb, query_info.sql, row_data
+                )
+            })?;
         }
-        tx.commit().await?;
+        if let Some(segment_tb_meta) = &update_tb_meta {
+            Self::batch_update(&mut tx, segment_tb_meta, &update_segment, &batch_sizer).await?;
+        }
+        self.commit_tx(tx).await?;
 
         BaseSinker::update_serial_monitor(&mut self.monitor, data.len(), data_size, start_time)
     }
@@ -143,25 +502,135 @@ impl PgSinker {
     ) -> anyhow::Result<()> {
         let start_time = Instant::now();
 
-        let tb_meta = self.meta_manager.get_tb_meta_by_row_data(&data[0]).await?;
-        let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, None);
+        let tb_meta = self
+            .meta_manager
+            .get_tb_meta_by_row_data(&data[0])
+            .await?
+            .to_owned();
+        Self::report_unlogged(
+            &mut self.reported_unlogged_tbs,
+            self.warn_on_unlogged,
+            &tb_meta,
+        );
+        let query_builder = RdbQueryBuilder::new_for_pg(&tb_meta, None);
 
         let (query_info, data_size) =
             query_builder.get_batch_delete_query(data, start_index, batch_size)?;
         let query = query_builder.create_pg_query(&query_info);
+        let error_context = || {
+            format!(
+                "batch delete failed, schema: {}, tb: {}, sql: [{}]",
+                tb_meta.basic.schema, tb_meta.basic.tb, query_info.sql
+            )
+        };
 
-        if let Some(sql) = self.get_data_marker_sql() {
-            let mut tx = self.conn_pool.begin().await?;
-            sqlx::query(&sql).execute(&mut tx).await?;
-            query.execute(&mut tx).await?;
-            tx.commit().await?;
+        let data_marker_sql = self.get_data_marker_sql();
+        if data_marker_sql.is_some() || self.use_advisory_locks || self.in_chunk_tx {
+            let mut tx = self.begin_tx().await?;
+            if let Some(sql) = data_marker_sql {
+                sqlx::query(&sql)
+                    .execute(&mut tx)
+                    .await
+                    .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
+            }
+            if self.use_advisory_locks {
+                let sub_data = &data[start_index..start_index + batch_size];
+                self.acquire_advisory_locks(&mut tx, sub_data).await?;
+            }
+            query.execute(&mut tx).await.with_context(error_context)?;
+            self.commit_tx(tx).await.with_context(error_context)?;
         } else {
-            query.execute(&self.conn_pool).await?;
+            let mut conn = self.pool_metrics.acquire().await?;
+            query
+                .execute(&mut *conn)
+                .await
+                .with_context(error_context)?;
         }
 
         BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
     }
 
+    // updates without key changes, grouped by their set cols, in the transaction of serial_sink
+    async fn batch_update(
+        tx: &mut Transaction<'static, Postgres>,
+        tb_meta: &PgTbMeta,
+        data: &[&RowData],
+        batch_sizer: &BatchSizer,
+    ) -> anyhow::Result<()> {
+        let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, None);
+        let id_col_count = tb_meta.basic.id_cols.len();
+        for sub_data in Self::split_update_segment(&query_builder, id_col_count, data, batch_sizer)
+        {
+            let (query_info, _) = query_builder.get_batch_update_query(&sub_data)?;
+            query_builder
+                .create_pg_query(&query_info)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| {
+                    format!(
+                        "batch update failed, schema: {}, tb: {}, sql: [{}]",
+                        tb_meta.basic.schema, tb_meta.basic.tb, query_info.sql
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
+    // groups of the same set cols, each capped by batch_size and the bind parameter limit
+    fn split_update_segment<'b>(
+        query_builder: &RdbQueryBuilder,
+        id_col_count: usize,
+        data: &[&'b RowData],
+        batch_sizer: &BatchSizer,
+    ) -> Vec<Vec<&'b RowData>> {
+        let mut sub_datas = Vec::new();
+        for group in Self::group_by_set_cols(query_builder, data) {
+            let set_col_count = query_builder.get_batch_update_set_cols(group[0]).len();
+            let max_rows = batch_sizer.get_max_rows(id_col_count + set_col_count);
+            sub_datas.extend(group.chunks(max_rows).map(|chunk| chunk.to_vec()));
+        }
+        sub_datas
+    }
+
+    fn group_by_set_cols<'b>(
+        query_builder: &RdbQueryBuilder,
+        data: &[&'b RowData],
+    ) -> Vec<Vec<&'b RowData>> {
+        let mut groups: Vec<(Vec<String>, Vec<&RowData>)> = Vec::new();
+        for &row_data in data.iter() {
+            let set_cols = query_builder.get_batch_update_set_cols(row_data);
+            match groups.iter_mut().find(|(cols, _)| *cols == set_cols) {
+                Some((_, sub_data)) => sub_data.push(row_data),
+                None => groups.push((set_cols, vec![row_data])),
+            }
+        }
+        groups.into_iter().map(|(_, sub_data)| sub_data).collect()
+    }
+
+    // rows without keys, with NULL keys, or with key changes can not be matched by t.k=v.k
+    fn is_batch_updatable(row_data: &RowData, tb_meta: &PgTbMeta) -> bool {
+        let (before, after) = match (&row_data.before, &row_data.after) {
+            (Some(before), Some(after)) => (before, after),
+            _ => return false,
+        };
+        if tb_meta.basic.key_map.is_empty() {
+            return false;
+        }
+
+        tb_meta
+            .basic
+            .id_cols
+            .iter()
+            .all(|col| match before.get(col) {
+                None | Some(ColValue::None) => false,
+                // key cols may be absent in after if unchanged
+                Some(value) => match after.get(col) {
+                    Some(after_value) => after_value == value,
+                    None => true,
+                },
+            })
+    }
+
     async fn batch_insert(
         &mut self,
         data: &mut [RowData],
@@ -175,25 +644,128 @@ impl PgSinker {
             .get_tb_meta_by_row_data(&data[0])
             .await?
             .to_owned();
-        let query_builder = RdbQueryBuilder::new_for_pg(&tb_meta, None);
+        Self::report_unlogged(
+            &mut self.reported_unlogged_tbs,
+            self.warn_on_unlogged,
+            &tb_meta,
+        );
 
-        let (query_info, data_size) =
-            query_builder.get_batch_insert_query(data, start_index, batch_size, self.replace)?;
-        let query = query_builder.create_pg_query(&query_info);
+        let data_size = if self.partman_aware {
+            // rows of a batch may belong to different child tables
+            let mut groups: Vec<(PgTbMeta, Vec<RowData>)> = Vec::new();
+            for row_data in data[start_index..start_index + batch_size].iter() {
+                let child_tb_meta = self
+                    .partman_router
+                    .route(&tb_meta, row_data)
+                    .await?
+                    .unwrap_or_else(|| tb_meta.clone());
+                match groups
+                    .iter_mut()
+                    .find(|(i, _)| i.basic.tb == child_tb_meta.basic.tb)
+                {
+                    Some((_, sub_data)) => sub_data.push(row_data.clone()),
+                    None => groups.push((child_tb_meta, vec![row_data.clone()])),
+                }
+            }
 
-        let exec_error = if let Some(sql) = self.get_data_marker_sql() {
-            let mut tx = self.conn_pool.begin().await?;
-            sqlx::query(&sql).execute(&mut tx).await?;
-            query.execute(&mut tx).await?;
-            tx.commit().await
+            let mut data_size = 0;
+            for (child_tb_meta, sub_data) in groups {
+                data_size += self
+                    .batch_insert_into(&child_tb_meta, &sub_data, 0, sub_data.len())
+                    .await?;
+            }
+            data_size
+        } else {
+            self.batch_insert_into(&tb_meta, data, start_index, batch_size)
+                .await?
+        };
+
+        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    }
+
+    // returns the data size of the batch
+    async fn batch_insert_into(
+        &mut self,
+        tb_meta: &PgTbMeta,
+        data: &[RowData],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<usize> {
+        let data_marker_sql = self.get_data_marker_sql();
+        let tx = if data_marker_sql.is_some() || self.in_chunk_tx {
+            Some(self.begin_tx().await?)
         } else {
-            match query.execute(&self.conn_pool).await {
-                Err(e) => Err(e),
+            None
+        };
+
+        let conflict_cols = self.conflict_cols.get(&(
+            data[start_index].schema.clone(),
+            data[start_index].tb.clone(),
+        ));
+        let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, None)
+            .with_exclude_conflicts_ignored(self.ignore_exclude_conflicts)
+            .with_conflict_cols(conflict_cols)
+            .with_conflict_do_nothing(self.conflict_mode == PgConflictMode::DoNothing);
+
+        let (query_info, data_size) = query_builder.get_batch_insert_query(
+            data,
+            start_index,
+            batch_size,
+            self.conflict_mode == PgConflictMode::DoUpdate,
+        )?;
+
+        let exec_error = if let Some(mut tx) = tx {
+            // a failed statement aborts the chunk transaction, roll back to the savepoint
+            // so that rows can still be inserted one by one in it
+            if self.in_chunk_tx {
+                sqlx::query("SAVEPOINT batch_insert")
+                    .execute(&mut tx)
+                    .await?;
+            }
+            if let Some(sql) = data_marker_sql {
+                sqlx::query(&sql)
+                    .execute(&mut tx)
+                    .await
+                    .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
+            }
+            let query = query_builder.create_pg_query(&query_info);
+            match query.execute(&mut tx).await {
+                Err(e) => {
+                    if self.in_chunk_tx {
+                        sqlx::query("ROLLBACK TO SAVEPOINT batch_insert")
+                            .execute(&mut tx)
+                            .await?;
+                        self.chunk_tx = Some(tx);
+                    }
+                    Err(e.into())
+                }
+                _ => {
+                    if self.in_chunk_tx {
+                        sqlx::query("RELEASE SAVEPOINT batch_insert")
+                            .execute(&mut tx)
+                            .await?;
+                    }
+                    self.commit_tx(tx).await
+                }
+            }
+        } else {
+            let mut conn = self.pool_metrics.acquire().await?;
+            let query = query_builder.create_pg_query(&query_info);
+            match query.execute(&mut *conn).await {
+                Err(e) => Err(e.into()),
                 _ => Ok(()),
             }
         };
 
         if let Err(error) = exec_error {
+            // the batch is retried as a whole by call_batch_fn_with_retry
+            if !self.in_chunk_tx
+                && ((self.deadlock_max_retries > 0 && BaseSinker::is_lock_conflict_error(&error))
+                    || (self.reconnect_max_wait_secs > 0
+                        && BaseSinker::is_connection_error(&error)))
+            {
+                return Err(error);
+            }
             log_error!(
                 "batch insert failed, will insert one by one, schema: {}, tb: {}, error: {}",
                 tb_meta.basic.schema,
@@ -201,10 +773,219 @@ impl PgSinker {
                 error.to_string()
             );
             let sub_data = &data[start_index..start_index + batch_size];
-            self.serial_sink(sub_data).await?;
+            self.serial_sink(sub_data).await.with_context(|| {
+                format!(
+                    "batch insert failed and inserting one by one also failed, schema: {}, tb: {}",
+                    tb_meta.basic.schema, tb_meta.basic.tb
+                )
+            })?;
         }
+        Ok(data_size)
+    }
 
-        BaseSinker::update_batch_monitor(&mut self.monitor, batch_size, data_size, start_time)
+    // all locks of the batch are acquired before executing any statement, and released when
+    // the transaction ends
+    async fn acquire_advisory_locks(
+        &mut self,
+        tx: &mut Transaction<'_, Postgres>,
+        data: &[RowData],
+    ) -> anyhow::Result<()> {
+        // sorted to acquire locks in the same order among workers
+        let mut lock_keys = BTreeSet::new();
+        for row_data in data.iter() {
+            if row_data.row_type == RowType::Insert {
+                continue;
+            }
+            let tb_meta = self.meta_manager.get_tb_meta_by_row_data(row_data).await?;
+            lock_keys.insert(Self::get_advisory_lock_key(
+                row_data,
+                &tb_meta.basic.id_cols,
+            ));
+        }
+
+        let sql = "SELECT pg_try_advisory_xact_lock(hashtext($1), hashtext($2)) AS locked";
+        for (tb_key, pk_key) in lock_keys.iter() {
+            let mut retry_times = 0;
+            loop {
+                let row = sqlx::query(sql)
+                    .bind(tb_key)
+                    .bind(pk_key)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                let locked: bool = row.try_get("locked")?;
+                if locked {
+                    break;
+                }
+
+                self.monitor
+                    .lock()
+                    .unwrap()
+                    .add_counter(CounterType::AdvisoryLockContentions, 1);
+                if retry_times >= self.lock_retry_count {
+                    bail! {Error::SinkerError(format!(
+                        "failed to acquire advisory lock, tb: {}, pk: {}, retry times: {}",
+                        tb_key, pk_key, retry_times
+                    ))}
+                }
+                retry_times += 1;
+                TimeUtil::sleep_millis(self.lock_retry_delay_ms).await;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_advisory_lock_key(row_data: &RowData, id_cols: &[String]) -> (String, String) {
+        let tb_key = format!(r#""{}"."{}""#, row_data.schema, row_data.tb);
+        let col_values = match row_data.row_type {
+            RowType::Insert => &row_data.after,
+            _ => &row_data.before,
+        };
+        let pk_values: Vec<String> = id_cols
+            .iter()
+            .map(|col| {
+                col_values
+                    .as_ref()
+                    .and_then(|values| values.get(col))
+                    .and_then(|value| value.to_option_string())
+                    .unwrap_or("NULL".to_string())
+            })
+            .collect();
+        (tb_key, pk_values.join(","))
+    }
+
+    async fn get_dml_function(&mut self, row_data: &RowData) -> anyhow::Result<Option<String>> {
+        let key = (row_data.schema.clone(), row_data.tb.clone());
+        if !self.dml_functions.contains_key(&key) {
+            let functions = self.detect_dml_functions(&key.0, &key.1).await?;
+            self.dml_functions.insert(key.clone(), functions);
+        }
+
+        let functions = self.dml_functions.get(&key).unwrap();
+        let function_name = match row_data.row_type {
+            RowType::Insert => &functions.insert_fn,
+            RowType::Update => &functions.update_fn,
+            RowType::Delete => &functions.delete_fn,
+        };
+
+        if function_name.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(function_name.clone()))
+        }
+    }
+
+    // tables without configured functions: use upsert_{tb} for insert/update if it exists
+    async fn detect_dml_functions(
+        &self,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<DmlFunctionConfig> {
+        let proname = format!("upsert_{}", tb);
+        let sql = "SELECT p.proname FROM pg_proc p
+            JOIN pg_namespace n ON p.pronamespace = n.oid
+            WHERE n.nspname = $1 AND p.proname = $2";
+        let row = sqlx::query(sql)
+            .bind(schema)
+            .bind(&proname)
+            .fetch_optional(&self.conn_pool)
+            .await?;
+
+        let mut functions = DmlFunctionConfig::default();
+        if let Some(row) = row {
+            let proname: String = row.try_get("proname")?;
+            let function_name = format!(r#""{}"."{}""#, schema, proname);
+            log_info!(
+                "dml function: {} detected for \"{}\".\"{}\"",
+                function_name,
+                schema,
+                tb
+            );
+            functions.insert_fn = function_name.clone();
+            functions.update_fn = function_name;
+        }
+        Ok(functions)
+    }
+
+    async fn check_collations(&mut self, row_data: &RowData) -> anyhow::Result<()> {
+        let src_meta_manager = match self.src_meta_manager.as_mut() {
+            Some(src_meta_manager) => src_meta_manager,
+            None => return Ok(()),
+        };
+
+        let full_name = format!(r#""{}"."{}""#, row_data.schema, row_data.tb);
+        if self.checked_collation_tbs.contains(&full_name) {
+            return Ok(());
+        }
+
+        let (src_schema, src_tb) = self
+            .reverse_router
+            .get_tb_map(&row_data.schema, &row_data.tb);
+        let col_map = self
+            .reverse_router
+            .get_col_map(&row_data.schema, &row_data.tb);
+        let src_tb_meta = src_meta_manager.get_tb_meta(&src_schema, &src_tb).await?;
+        let tb_meta = self.meta_manager.get_tb_meta_by_row_data(row_data).await?;
+
+        let mut mismatches = Vec::new();
+        for col in tb_meta.basic.cols.iter() {
+            let src_col = col_map.and_then(|m| m.get(col)).unwrap_or(col);
+            let src_collation = src_tb_meta.col_collations.get(src_col);
+            let collation = tb_meta.col_collations.get(col);
+            if src_collation != collation {
+                mismatches.push(format!(
+                    "{}: {} -> {}",
+                    col,
+                    src_collation.map_or("default", |v| v),
+                    collation.map_or("default", |v| v)
+                ));
+            }
+        }
+        self.checked_collation_tbs.insert(full_name.clone());
+
+        if mismatches.is_empty() {
+            return Ok(());
+        }
+        let msg = format!(
+            "collations of source and target columns differ, target table: {}, cols: [{}]",
+            full_name,
+            mismatches.join(", ")
+        );
+        match self.collation_mismatch_policy {
+            CollationPolicy::Error => bail! {Error::SinkerError(msg)},
+            CollationPolicy::Warn => log_warn!("{}", msg),
+            CollationPolicy::Ignore => {}
+        }
+        Ok(())
+    }
+
+    fn report_unlogged(
+        reported_unlogged_tbs: &mut HashSet<String>,
+        warn_on_unlogged: bool,
+        tb_meta: &PgTbMeta,
+    ) {
+        if !tb_meta.is_unlogged {
+            return;
+        }
+
+        let full_name = format!(r#""{}"."{}""#, tb_meta.basic.schema, tb_meta.basic.tb);
+        if reported_unlogged_tbs.contains(&full_name) {
+            return;
+        }
+
+        // UNLOGGED tables skip WAL: writes are faster, but the table is truncated after
+        // a server crash and its data is never replicated to standby servers
+        if warn_on_unlogged {
+            log_warn!(
+                "target table: {} is UNLOGGED, data may be lost on server crash and it is not replicated to standby, set [sinker] warn_on_unlogged=false or start with --allow-unlogged if this is intended",
+                full_name
+            );
+        } else {
+            log_info!(
+                "target table: {} is UNLOGGED, data may be lost on server crash and it is not replicated to standby",
+                full_name
+            );
+        }
+        reported_unlogged_tbs.insert(full_name);
     }
 
     fn get_data_marker_sql(&self) -> Option<String> {
@@ -236,3 +1017,208 @@ impl PgSinker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::{
+        config::sinker_config::BasicSinkerConfig,
+        meta::{
+            col_value::ColValue,
+            pg::{pg_col_type::PgColType, pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
+            rdb_tb_meta::RdbTbMeta,
+            row_data::RowData,
+            row_type::RowType,
+        },
+    };
+
+    use super::PgSinker;
+    use crate::{rdb_query_builder::RdbQueryBuilder, sinker::batch_sizer::BatchSizer};
+
+    // public.tb_1 with primary key id, cols: [(col, type alias)]
+    fn mock_tb_meta(cols: &[(&str, &str)]) -> PgTbMeta {
+        let col_type = |alias: &str| PgColType {
+            value_type: PgValueType::String,
+            name: alias.into(),
+            alias: alias.into(),
+            oid: 0,
+            parent_oid: 0,
+            base_oid: 0,
+            element_oid: 0,
+            category: String::new(),
+            enum_values: None,
+        };
+        let id_cols = vec!["id".to_string()];
+        PgTbMeta {
+            basic: RdbTbMeta {
+                schema: "public".into(),
+                tb: "tb_1".into(),
+                cols: cols.iter().map(|(col, _)| col.to_string()).collect(),
+                key_map: HashMap::from([("primary".to_string(), id_cols.clone())]),
+                id_cols,
+                ..Default::default()
+            },
+            oid: 0,
+            col_type_map: cols
+                .iter()
+                .map(|(col, alias)| (col.to_string(), col_type(alias)))
+                .collect(),
+            is_unlogged: false,
+            col_collations: HashMap::new(),
+            generated_cols: HashMap::new(),
+            exclude_constraints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_is_key_cols() {
+        let key_map: HashMap<String, Vec<String>> = HashMap::from([
+            ("primary".to_string(), vec!["id".to_string()]),
+            (
+                "uk_tenant_code".to_string(),
+                vec!["tenant_id".to_string(), "code".to_string()],
+            ),
+        ]);
+        let is_key_cols = |cols: &[&str]| {
+            let cols: Vec<String> = cols.iter().map(|i| i.to_string()).collect();
+            PgSinker::is_key_cols(&key_map, &cols)
+        };
+
+        assert!(is_key_cols(&["id"]));
+        assert!(is_key_cols(&["tenant_id", "code"]));
+        assert!(is_key_cols(&["code", "tenant_id"]));
+        assert!(!is_key_cols(&["code"]));
+        assert!(!is_key_cols(&["id", "code"]));
+        assert!(!is_key_cols(&["tenant_id", "code", "id"]));
+    }
+
+    #[test]
+    fn test_batch_update_query() {
+        let tb_meta = mock_tb_meta(&[("id", "int8"), ("name", "text"), ("age", "int4")]);
+        let update = |id: i32, after_cols: &[(&str, ColValue)]| {
+            let before = HashMap::from([("id".to_string(), ColValue::Long(id))]);
+            let after = after_cols
+                .iter()
+                .map(|(col, value)| (col.to_string(), value.clone()))
+                .collect();
+            RowData::new(
+                "public".into(),
+                "tb_1".into(),
+                RowType::Update,
+                Some(before),
+                Some(after),
+            )
+        };
+        let data = [
+            update(1, &[("name", ColValue::String("a".into()))]),
+            update(
+                2,
+                &[
+                    ("id", ColValue::Long(2)),
+                    ("name", ColValue::String("b".into())),
+                    ("age", ColValue::Long(20)),
+                ],
+            ),
+            update(3, &[("name", ColValue::String("c".into()))]),
+        ];
+        let data: Vec<&RowData> = data.iter().collect();
+
+        let query_builder = RdbQueryBuilder::new_for_pg(&tb_meta, None);
+        let groups = PgSinker::group_by_set_cols(&query_builder, &data);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), 2);
+        assert_eq!(groups[1].len(), 1);
+
+        let (query_info, _) = query_builder.get_batch_update_query(&groups[0]).unwrap();
+        assert_eq!(
+            query_info.sql,
+            r#"UPDATE "public"."tb_1" AS t SET "name"=v."name" FROM (VALUES($1::int8,$2::text),($3::int8,$4::text)) AS v("id","name") WHERE t."id"=v."id""#
+        );
+        assert_eq!(query_info.binds.len(), 4);
+        assert_eq!(query_info.binds[0], Some(&ColValue::Long(1)));
+        assert_eq!(query_info.binds[3], Some(&ColValue::String("c".into())));
+
+        // the key in after is not updated, set cols are sorted
+        let (query_info, _) = query_builder.get_batch_update_query(&groups[1]).unwrap();
+        assert_eq!(
+            query_info.sql,
+            r#"UPDATE "public"."tb_1" AS t SET "age"=v."age","name"=v."name" FROM (VALUES($1::int8,$2::int4,$3::text)) AS v("id","age","name") WHERE t."id"=v."id""#
+        );
+    }
+
+    #[test]
+    fn test_split_update_segment() {
+        let set_cols: Vec<String> = (0..9).map(|i| format!("col_{}", i)).collect();
+        let mut cols = vec![("id", "int8")];
+        cols.extend(set_cols.iter().map(|col| (col.as_str(), "text")));
+        let tb_meta = mock_tb_meta(&cols);
+        let data: Vec<RowData> = (0..16000)
+            .map(|id| {
+                let before = HashMap::from([("id".to_string(), ColValue::LongLong(id))]);
+                let after = set_cols
+                    .iter()
+                    .map(|col| (col.clone(), ColValue::String("a".into())))
+                    .collect();
+                RowData::new(
+                    "public".into(),
+                    "tb_1".into(),
+                    RowType::Update,
+                    Some(before),
+                    Some(after),
+                )
+            })
+            .collect();
+        let data: Vec<&RowData> = data.iter().collect();
+
+        // 16000 rows of 10 bind parameters exceed 65535, so they are split by 65535 / 10 rows
+        let query_builder = RdbQueryBuilder::new_for_pg(&tb_meta, None);
+        let batch_sizer = BatchSizer::new(16000, &BasicSinkerConfig::default());
+        let sub_datas = PgSinker::split_update_segment(&query_builder, 1, &data, &batch_sizer);
+        let sizes: Vec<usize> = sub_datas.iter().map(|sub_data| sub_data.len()).collect();
+        assert_eq!(sizes, vec![6553, 6553, 2894]);
+        for sub_data in sub_datas.iter() {
+            let (query_info, _) = query_builder.get_batch_update_query(sub_data).unwrap();
+            assert!(query_info.binds.len() <= 65535);
+        }
+        // rows keep their order
+        assert!(std::ptr::eq(sub_datas[1][0], data[6553]));
+        assert!(std::ptr::eq(sub_datas[2][2893], data[15999]));
+
+        // also capped by batch_size
+        let batch_sizer = BatchSizer::new(5000, &BasicSinkerConfig::default());
+        let sub_datas = PgSinker::split_update_segment(&query_builder, 1, &data, &batch_sizer);
+        let sizes: Vec<usize> = sub_datas.iter().map(|sub_data| sub_data.len()).collect();
+        assert_eq!(sizes, vec![5000, 5000, 5000, 1000]);
+    }
+
+    #[test]
+    fn test_is_batch_updatable() {
+        let tb_meta = mock_tb_meta(&[("id", "int8"), ("name", "text")]);
+        let update = |before_id: ColValue, after_id: Option<ColValue>| {
+            let before = HashMap::from([
+                ("id".to_string(), before_id),
+                ("name".to_string(), ColValue::String("a".into())),
+            ]);
+            let mut after = HashMap::from([("name".to_string(), ColValue::String("b".into()))]);
+            if let Some(after_id) = after_id {
+                after.insert("id".to_string(), after_id);
+            }
+            let row_data = RowData::new(
+                "public".into(),
+                "tb_1".into(),
+                RowType::Update,
+                Some(before),
+                Some(after),
+            );
+            PgSinker::is_batch_updatable(&row_data, &tb_meta)
+        };
+
+        assert!(update(ColValue::Long(1), Some(ColValue::Long(1))));
+        assert!(update(ColValue::Long(1), None));
+        // key changed
+        assert!(!update(ColValue::Long(1), Some(ColValue::Long(2))));
+        // NULL key
+        assert!(!update(ColValue::None, None));
+    }
+}
@@ -2,16 +2,21 @@ use crate::{
     close_conn_pool,
     rdb_router::RdbRouter,
     sinker::base_struct_sinker::{BaseStructSinker, DBConnPool},
-    Sinker,
+    Configurable, Sinker,
 };
 
 use dt_common::{
-    config::config_enums::ConflictPolicyEnum, meta::struct_meta::struct_data::StructData,
+    config::config_enums::ConflictPolicyEnum,
+    log_info, log_warn,
+    meta::struct_meta::{
+        mysql_to_pg_convertor::MysqlToPgConvertor, statement::struct_statement::StructStatement,
+        struct_data::StructData,
+    },
     rdb_filter::RdbFilter,
 };
 
 use async_trait::async_trait;
-use sqlx::{Pool, Postgres};
+use sqlx::{postgres::PgPoolOptions, Executor, Pool, Postgres, Row};
 
 #[derive(Clone)]
 pub struct PgStructSinker {
@@ -19,11 +24,47 @@ pub struct PgStructSinker {
     pub conflict_policy: ConflictPolicyEnum,
     pub filter: RdbFilter,
     pub router: RdbRouter,
+    // SET sqls of source server settings, executed on every connection of conn_pool
+    pub session_sqls: Vec<String>,
 }
 
+impl Configurable for PgStructSinker {}
+
 #[async_trait]
 impl Sinker for PgStructSinker {
     async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
+        // statements from mysql are rewritten for mysql -> pg struct migration
+        let mut pg_data = Vec::with_capacity(data.len());
+        for mut struct_data in data {
+            struct_data.statement = MysqlToPgConvertor::convert(struct_data.statement)?;
+            pg_data.push(struct_data);
+        }
+        let data = pg_data;
+
+        // a SET only affects the connection executing it, so settings are not sunk as
+        // structs but applied to all connections by after_connect
+        let mut other_data = Vec::with_capacity(data.len());
+        let mut session_sqls = Vec::new();
+        for mut struct_data in data {
+            if let StructStatement::PgSetParameter { name, value } = &struct_data.statement {
+                self.check_server_setting(name, value).await?;
+            }
+            if matches!(
+                struct_data.statement,
+                StructStatement::PgSetParameter { .. }
+            ) {
+                for (_, sql) in struct_data.statement.to_sqls(&self.filter)? {
+                    session_sqls.push(sql);
+                }
+            } else {
+                other_data.push(struct_data);
+            }
+        }
+        if !session_sqls.is_empty() {
+            self.apply_session_sqls(session_sqls).await?;
+        }
+        let data = other_data;
+
         BaseStructSinker::sink_structs(
             &DBConnPool::PostgreSQL(self.conn_pool.clone()),
             &self.conflict_policy,
@@ -38,4 +79,54 @@ impl Sinker for PgStructSinker {
     }
 }
 
-impl PgStructSinker {}
+impl PgStructSinker {
+    // connections of the current pool can not be changed, a new pool is created
+    async fn apply_session_sqls(&mut self, session_sqls: Vec<String>) -> anyhow::Result<()> {
+        for sql in session_sqls {
+            if !self.session_sqls.contains(&sql) {
+                log_info!("apply server setting to target connections: {}", sql);
+                self.session_sqls.push(sql);
+            }
+        }
+
+        let session_sqls = self.session_sqls.clone();
+        let conn_pool = PgPoolOptions::new()
+            .max_connections(self.conn_pool.options().get_max_connections())
+            .after_connect(move |conn, _meta| {
+                let session_sqls = session_sqls.clone();
+                Box::pin(async move {
+                    // disable foreign key checks, same as other pools of the sinker
+                    conn.execute("SET session_replication_role = 'replica';")
+                        .await?;
+                    for sql in session_sqls.iter() {
+                        conn.execute(sql.as_str()).await?;
+                    }
+                    Ok(())
+                })
+            })
+            .connect_with(self.conn_pool.connect_options().clone())
+            .await?;
+
+        let old_conn_pool = std::mem::replace(&mut self.conn_pool, conn_pool);
+        old_conn_pool.close().await;
+        Ok(())
+    }
+
+    async fn check_server_setting(&self, name: &str, src_value: &str) -> anyhow::Result<()> {
+        let row = sqlx::query("SELECT current_setting($1, true) AS setting")
+            .bind(name)
+            .fetch_one(&self.conn_pool)
+            .await?;
+        let dst_value: Option<String> = row.try_get("setting")?;
+        let dst_value = dst_value.unwrap_or_default();
+        if dst_value != src_value {
+            log_warn!(
+                "server setting: {} differs, source: {}, target: {}, data representation may differ",
+                name,
+                src_value,
+                dst_value
+            );
+        }
+        Ok(())
+    }
+}
@@ -338,7 +338,8 @@ impl EntryRewriter {
         let mut cmd = RedisCmd::new();
         cmd.add_str_arg("restore");
         cmd.add_redis_arg(&entry.key);
-        cmd.add_str_arg(&entry.expire_ms.to_string());
+        // ttl is applied by rewrite_expire
+        cmd.add_str_arg("0");
         cmd.add_arg(value);
         if version >= 3.0 {
             cmd.add_str_arg("replace");
@@ -346,14 +347,26 @@ impl EntryRewriter {
         Ok(cmd)
     }
 
-    pub fn rewrite_expire(entry: &RedisEntry) -> anyhow::Result<Option<RedisCmd>> {
-        if entry.expire_ms == 0 {
+    // expire_at_ms: absolute unix time in milliseconds, 0 for persistent keys,
+    // a key already expired at now_ms gets the minimum ttl: 1ms
+    pub fn rewrite_expire(
+        entry: &RedisEntry,
+        expire_at_ms: i64,
+        now_ms: i64,
+    ) -> anyhow::Result<Option<RedisCmd>> {
+        if expire_at_ms <= 0 {
             return Ok(None);
         }
         let mut cmd = RedisCmd::new();
-        cmd.add_str_arg("pexpire");
-        cmd.add_redis_arg(&entry.key);
-        cmd.add_str_arg(&entry.expire_ms.to_string());
+        if expire_at_ms > now_ms {
+            cmd.add_str_arg("pexpireat");
+            cmd.add_redis_arg(&entry.key);
+            cmd.add_str_arg(&expire_at_ms.to_string());
+        } else {
+            cmd.add_str_arg("pexpire");
+            cmd.add_redis_arg(&entry.key);
+            cmd.add_str_arg("1");
+        }
         Ok(Some(cmd))
     }
 
@@ -376,3 +389,33 @@ impl EntryRewriter {
         crc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::redis::redis_entry::RedisEntry;
+
+    use super::EntryRewriter;
+
+    #[test]
+    fn test_rewrite_expire() {
+        let mut entry = RedisEntry::new();
+        entry.key = String::from("key_1").into();
+        let rewrite = |expire_at_ms, now_ms| {
+            EntryRewriter::rewrite_expire(&entry, expire_at_ms, now_ms)
+                .unwrap()
+                .map(|cmd| cmd.to_string())
+        };
+
+        assert_eq!(
+            rewrite(1700000001000, 1700000000000),
+            Some("pexpireat key_1 1700000001000".to_string())
+        );
+        // ttl elapsed between extracting and sinking
+        assert_eq!(
+            rewrite(1700000000000, 1700000000000),
+            Some("pexpire key_1 1".to_string())
+        );
+        // persistent
+        assert_eq!(rewrite(0, 1700000000000), None);
+    }
+}
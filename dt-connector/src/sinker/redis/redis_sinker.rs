@@ -5,14 +5,16 @@ use std::time::Instant;
 
 use anyhow::bail;
 use async_trait::async_trait;
+use chrono::Utc;
 use dt_common::error::Error;
-use dt_common::log_debug;
+use dt_common::log_debug_row;
 use dt_common::meta::dt_data::DtData;
 use dt_common::meta::dt_data::DtItem;
 use dt_common::meta::rdb_meta_manager::RdbMetaManager;
 use dt_common::meta::redis::cluster_node::ClusterNode;
 use dt_common::meta::redis::command::cmd_encoder::CmdEncoder;
 use dt_common::meta::redis::command::key_parser::KeyParser;
+use dt_common::meta::redis::redis_expired_key_policy::RedisExpiredKeyPolicy;
 use dt_common::meta::redis::redis_object::RedisCmd;
 use dt_common::meta::redis::redis_object::RedisObject;
 use dt_common::meta::redis::redis_write_method::RedisWriteMethod;
@@ -26,7 +28,7 @@ use redis::Value;
 use crate::call_batch_fn;
 use crate::data_marker::DataMarker;
 use crate::sinker::base_sinker::BaseSinker;
-use crate::Sinker;
+use crate::{Configurable, Sinker};
 
 use super::entry_rewriter::EntryRewriter;
 
@@ -37,12 +39,17 @@ pub struct RedisSinker {
     pub now_db_id: i64,
     pub version: f32,
     pub method: RedisWriteMethod,
+    // replicate keys from rdb as persistent
+    pub ignore_ttl: bool,
+    pub expired_key_policy: RedisExpiredKeyPolicy,
     pub meta_manager: Option<RdbMetaManager>,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub key_parser: KeyParser,
 }
 
+impl Configurable for RedisSinker {}
+
 #[async_trait]
 impl Sinker for RedisSinker {
     async fn sink_raw(&mut self, mut data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
@@ -129,11 +136,30 @@ impl RedisSinker {
                 self.now_db_id = entry.db_id;
             }
 
+            let now_ms = Utc::now().timestamp_millis();
+            let expire_at_ms = if self.ignore_ttl {
+                0
+            } else {
+                entry.expire_at_ms
+            };
+            if expire_at_ms > 0
+                && expire_at_ms <= now_ms
+                && self.expired_key_policy == RedisExpiredKeyPolicy::Skip
+            {
+                log_debug_row!("skip expired key: {}", entry.key);
+                return Ok(cmds);
+            }
+
             match self.method {
                 RedisWriteMethod::Restore => {
                     if entry.is_raw() {
                         let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
                         cmds.push(cmd);
+                        if let Some(expire_cmd) =
+                            EntryRewriter::rewrite_expire(entry, expire_at_ms, now_ms)?
+                        {
+                            cmds.push(expire_cmd)
+                        }
                     } else {
                         cmds.push(entry.cmd.clone());
                     }
@@ -153,7 +179,9 @@ impl RedisSinker {
                         }
                         _ => bail! {Error::SinkerError("rewrite not implemented".into())},
                     }?;
-                    if let Some(expire_cmd) = EntryRewriter::rewrite_expire(entry)? {
+                    if let Some(expire_cmd) =
+                        EntryRewriter::rewrite_expire(entry, expire_at_ms, now_ms)?
+                    {
                         rewrite_cmds.push(expire_cmd)
                     }
                     cmds.extend(rewrite_cmds);
@@ -323,7 +351,7 @@ impl RedisSinker {
                             ))}
                         }
                         _ => {
-                            log_debug!("sink result: [{:?}], cmd: [{}]", v, cmd)
+                            log_debug_row!("sink result: [{:?}], cmd: [{}]", v, cmd)
                         }
                     }
                 }
@@ -355,7 +383,7 @@ impl RedisSinker {
 
             let data_marker_cmd =
                 RedisCmd::from_str_args(&["SET", key, &data_marker.data_origin_node]);
-            log_debug!("data_marker_cmd: [{}] by cmd: [{}]", data_marker_cmd, cmd);
+            log_debug_row!("data_marker_cmd: [{}] by cmd: [{}]", data_marker_cmd, cmd);
             return Ok(Some(data_marker_cmd));
         }
         Ok(None)
@@ -10,7 +10,7 @@ use dt_common::monitor::monitor::Monitor;
 use serde::Serialize;
 use serde_json::json;
 
-use crate::Sinker;
+use crate::{Configurable, Sinker};
 
 pub struct RedisStatisticSinker {
     pub statistic_type: RedisStatisticType,
@@ -34,6 +34,8 @@ struct HotKeyInfo {
     freq: i64,
 }
 
+impl Configurable for RedisStatisticSinker {}
+
 #[async_trait]
 impl Sinker for RedisStatisticSinker {
     async fn sink_raw(&mut self, mut data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
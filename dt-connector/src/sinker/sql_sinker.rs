@@ -7,7 +7,7 @@ use dt_common::{
     monitor::monitor::Monitor,
 };
 
-use crate::{rdb_query_builder::RdbQueryBuilder, rdb_router::RdbRouter, Sinker};
+use crate::{rdb_query_builder::RdbQueryBuilder, rdb_router::RdbRouter, Configurable, Sinker};
 
 #[derive(Clone)]
 pub struct SqlSinker {
@@ -17,6 +17,8 @@ pub struct SqlSinker {
     pub monitor: Arc<Mutex<Monitor>>,
 }
 
+impl Configurable for SqlSinker {}
+
 #[async_trait]
 impl Sinker for SqlSinker {
     async fn sink_dml(&mut self, data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
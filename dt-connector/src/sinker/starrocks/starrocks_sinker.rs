@@ -6,7 +6,7 @@ use std::{
     time::Instant,
 };
 
-use crate::{call_batch_fn, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{call_batch_fn, sinker::base_sinker::BaseSinker, Configurable, Sinker};
 use anyhow::bail;
 use async_trait::async_trait;
 use chrono::Utc;
@@ -45,6 +45,8 @@ pub struct StarRocksSinker {
     pub hard_delete: bool,
 }
 
+impl Configurable for StarRocksSinker {}
+
 #[async_trait]
 impl Sinker for StarRocksSinker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
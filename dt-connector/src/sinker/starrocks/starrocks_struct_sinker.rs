@@ -1,4 +1,6 @@
-use crate::{rdb_router::RdbRouter, Sinker};
+use crate::{
+    rdb_router::RdbRouter, sinker::base_struct_sinker::BaseStructSinker, Configurable, Sinker,
+};
 
 use anyhow::bail;
 use dt_common::{
@@ -38,6 +40,8 @@ pub struct StarrocksStructSinker {
     pub backend_count: i32,
 }
 
+impl Configurable for StarrocksStructSinker {}
+
 #[async_trait]
 impl Sinker for StarrocksStructSinker {
     async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
@@ -45,7 +49,7 @@ impl Sinker for StarrocksStructSinker {
             self.backend_count = self.get_backend_count().await?;
         }
 
-        let reverse_router = self.router.reverse();
+        let reverse_router = self.router.reverse()?;
         for i in data {
             match i.statement {
                 StructStatement::MysqlCreateDatabase(statement) => {
@@ -62,7 +66,7 @@ impl Sinker for StarrocksStructSinker {
                     if let Some(meta_manager) =
                         self.extractor_meta_manager.mysql_meta_manager.as_mut()
                     {
-                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?.to_owned();
                         let sql =
                             self.get_create_table_sql(&statement.table, Some(&tb_meta), None)?;
                         self.execute_sql(&sql).await?;
@@ -74,12 +78,16 @@ impl Sinker for StarrocksStructSinker {
                     self.execute_sql(&sql).await?;
                 }
 
+                StructStatement::PgCreateDomain(statement) => {
+                    BaseStructSinker::warn_unreplicated_domain(&statement.domain);
+                }
+
                 StructStatement::PgCreateTable(statement) => {
                     let (schema, tb) = reverse_router
                         .get_tb_map(&statement.table.schema_name, &statement.table.table_name);
                     if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
                     {
-                        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+                        let tb_meta = meta_manager.get_tb_meta(&schema, &tb).await?.to_owned();
                         let sql =
                             self.get_create_table_sql(&statement.table, None, Some(&tb_meta))?;
                         self.execute_sql(&sql).await?;
@@ -290,7 +298,7 @@ impl StarrocksStructSinker {
 
     fn get_dst_col_type_from_pg(&self, col: &str, tb_meta: &PgTbMeta) -> anyhow::Result<String> {
         let pg_col_type = tb_meta.get_col_type(col)?;
-        let dst_col = match pg_col_type.value_type {
+        let dst_col = match pg_col_type.base_value_type() {
             // boolean == tinyint(1)
             PgValueType::Boolean => "BOOLEAN",
             PgValueType::Int16 => "SMALLINT",
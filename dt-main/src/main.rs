@@ -1,7 +1,16 @@
 use std::{env, panic};
 
 use dt_precheck::{config::task_config::PrecheckTaskConfig, do_precheck};
-use dt_task::task_runner::TaskRunner;
+use dt_task::{
+    integrity_verifier::IntegrityVerifier, schema_snapshot_exporter::SchemaSnapshotExporter,
+    task_runner::TaskRunner,
+};
+
+const ALLOW_UNLOGGED: &str = "--allow-unlogged";
+// dt-main task_config.ini --schema-export schema.json
+const SCHEMA_EXPORT: &str = "--schema-export";
+// dt-main task_config.ini --verify-integrity
+const VERIFY_INTEGRITY: &str = "--verify-integrity";
 
 #[tokio::main]
 async fn main() {
@@ -14,10 +23,23 @@ async fn main() {
 
     let task_config = args[1].clone();
 
-    if PrecheckTaskConfig::new(&task_config).is_ok() {
+    if let Some(i) = args.iter().position(|arg| arg == SCHEMA_EXPORT) {
+        let output_file = args
+            .get(i + 1)
+            .expect("no output file provided for --schema-export");
+        let exporter = SchemaSnapshotExporter::new(&task_config).await.unwrap();
+        exporter.export(output_file).await.unwrap();
+    } else if args.iter().any(|arg| arg == VERIFY_INTEGRITY) {
+        let verifier = IntegrityVerifier::new(&task_config).await.unwrap();
+        verifier.verify().await.unwrap();
+    } else if PrecheckTaskConfig::new(&task_config).is_ok() {
         do_precheck(&task_config).await;
     } else {
-        let runner = TaskRunner::new(&task_config).unwrap();
+        let mut runner = TaskRunner::new(&task_config).unwrap();
+        if args.iter().any(|arg| arg == ALLOW_UNLOGGED) {
+            runner.allow_unlogged();
+        }
+        runner.handle_shutdown_signals();
         runner.start_task(true).await.unwrap()
     }
 }
@@ -13,7 +13,8 @@ use std::sync::{Arc, Mutex};
 pub struct BaseParallelizer {
     pub poped_data: VecDeque<DtItem>,
     pub monitor: Arc<Mutex<Monitor>>,
-    pub rps_limiter: Option<Ratelimiter>,
+    pub rps_limiter: Option<Arc<Ratelimiter>>,
+    pub chunk_transaction: bool,
 }
 
 impl BaseParallelizer {
@@ -103,10 +104,51 @@ impl BaseParallelizer {
         batch: bool,
     ) -> anyhow::Result<()> {
         let mut join_set = tokio::task::JoinSet::new();
+        let chunk_transaction = self.chunk_transaction;
         for i in 0..sub_datas.len() {
             let data = sub_datas.remove(0);
             let sinker = sinkers[i % parallel_size].clone();
-            join_set.spawn(async move { sinker.lock().await.sink_dml(data, batch).await });
+            join_set.spawn(async move {
+                let mut sinker = sinker.lock().await;
+                if chunk_transaction {
+                    sinker.sink_dml_in_tx(data, batch).await
+                } else {
+                    sinker.sink_dml(data, batch).await
+                }
+            });
+        }
+        while let Some(result) = join_set.join_next().await {
+            result??;
+        }
+        Ok(())
+    }
+
+    // each sub data is a list of batches sunk by one sinker in order,
+    // in one transaction if chunk_transaction is enabled
+    pub async fn sink_dml_batches(
+        &self,
+        mut sub_batches: Vec<Vec<(Vec<RowData>, bool)>>,
+        sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
+        parallel_size: usize,
+    ) -> anyhow::Result<()> {
+        let mut join_set = tokio::task::JoinSet::new();
+        let chunk_transaction = self.chunk_transaction;
+        for i in 0..sub_batches.len() {
+            let batches = sub_batches.remove(0);
+            if batches.is_empty() {
+                continue;
+            }
+            let sinker = sinkers[i % parallel_size].clone();
+            join_set.spawn(async move {
+                let mut sinker = sinker.lock().await;
+                if chunk_transaction {
+                    return sinker.sink_dml_batches_in_tx(batches).await;
+                }
+                for (data, batch) in batches {
+                    sinker.sink_dml(data, batch).await?;
+                }
+                Ok(())
+            });
         }
         while let Some(result) = join_set.join_next().await {
             result??;
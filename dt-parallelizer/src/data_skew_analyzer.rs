@@ -0,0 +1,175 @@
+use std::sync::{Arc, Mutex};
+
+use dt_common::{
+    log_info, log_warn,
+    monitor::{counter_type::CounterType, monitor::Monitor},
+};
+
+// warn with the suggestion if data skew is detected in this many consecutive batches
+const CONSISTENT_SKEW_BATCHES: usize = 10;
+
+// detect data skew in partitions of a batch by the coefficient of variation (stddev / mean)
+// of partition sizes, and optionally rebalance the rows among partitions
+#[derive(Default)]
+pub struct DataSkewAnalyzer {
+    // 0 means disabled
+    pub skew_threshold: f64,
+    pub rebalance: bool,
+    pub monitor: Arc<Mutex<Monitor>>,
+    consecutive_skews: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SkewStats {
+    pub mean: f64,
+    pub stddev: f64,
+    // max_size / min_size
+    pub skew_coefficient: f64,
+}
+
+impl DataSkewAnalyzer {
+    pub fn new(skew_threshold: f64, rebalance: bool, monitor: Arc<Mutex<Monitor>>) -> Self {
+        Self {
+            skew_threshold,
+            rebalance,
+            monitor,
+            consecutive_skews: 0,
+        }
+    }
+
+    pub fn analyze<T>(
+        &mut self,
+        sub_datas: Vec<Vec<T>>,
+        get_size: impl Fn(&T) -> usize,
+    ) -> Vec<Vec<T>> {
+        if self.skew_threshold <= 0.0 || sub_datas.len() <= 1 {
+            return sub_datas;
+        }
+
+        let sizes: Vec<usize> = sub_datas
+            .iter()
+            .map(|sub_data| sub_data.iter().map(&get_size).sum())
+            .collect();
+        let stats = match Self::compute_stats(&sizes) {
+            Some(stats) => stats,
+            None => return sub_datas,
+        };
+        self.monitor.lock().unwrap().add_counter(
+            CounterType::SkewCoefficient,
+            (stats.skew_coefficient * 100.0) as usize,
+        );
+
+        if stats.stddev / stats.mean <= self.skew_threshold {
+            self.consecutive_skews = 0;
+            return sub_datas;
+        }
+
+        log_warn!(
+            "data skew detected in partitions, stddev / mean: {:.2}, skew_coefficient: {:.2}, partition sizes: {:?}",
+            stats.stddev / stats.mean,
+            stats.skew_coefficient,
+            sizes
+        );
+        self.consecutive_skews += 1;
+        if self.consecutive_skews == CONSISTENT_SKEW_BATCHES {
+            log_info!(
+                "data skew detected in {} consecutive batches, consider [parallelizer] parallel_type=rdb_partition to partition rows by column hash",
+                CONSISTENT_SKEW_BATCHES
+            );
+        }
+
+        if self.rebalance {
+            Self::rebalance(sub_datas, get_size)
+        } else {
+            sub_datas
+        }
+    }
+
+    // returns None if any partition is empty, which happens for small batches
+    fn compute_stats(sizes: &[usize]) -> Option<SkewStats> {
+        let (min, max) = (*sizes.iter().min()?, *sizes.iter().max()?);
+        if min == 0 {
+            return None;
+        }
+
+        let count = sizes.len() as f64;
+        let mean = sizes.iter().sum::<usize>() as f64 / count;
+        let variance = sizes
+            .iter()
+            .map(|size| (*size as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        Some(SkewStats {
+            mean,
+            stddev: variance.sqrt(),
+            skew_coefficient: max as f64 / min as f64,
+        })
+    }
+
+    // re-partition rows by size descending, each row goes to the partition with the least size,
+    // only for data without ordering requirements (E.g. snapshot rows)
+    fn rebalance<T>(sub_datas: Vec<Vec<T>>, get_size: impl Fn(&T) -> usize) -> Vec<Vec<T>> {
+        let partition_count = sub_datas.len();
+        let mut items: Vec<(usize, T)> = sub_datas
+            .into_iter()
+            .flatten()
+            .map(|item| (get_size(&item), item))
+            .collect();
+        items.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let mut sizes = vec![0; partition_count];
+        let mut rebalanced: Vec<Vec<T>> = (0..partition_count).map(|_| Vec::new()).collect();
+        for (size, item) in items {
+            let i = (0..partition_count).min_by_key(|i| sizes[*i]).unwrap();
+            sizes[i] += size;
+            rebalanced[i].push(item);
+        }
+        rebalanced
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use dt_common::monitor::monitor::Monitor;
+
+    use super::DataSkewAnalyzer;
+
+    #[test]
+    fn test_compute_stats() {
+        let stats = DataSkewAnalyzer::compute_stats(&[10, 10, 10, 10]).unwrap();
+        assert_eq!(stats.stddev, 0.0);
+        assert_eq!(stats.skew_coefficient, 1.0);
+
+        let stats = DataSkewAnalyzer::compute_stats(&[10, 30]).unwrap();
+        assert_eq!(stats.mean, 20.0);
+        assert_eq!(stats.stddev, 10.0);
+        assert_eq!(stats.skew_coefficient, 3.0);
+
+        assert!(DataSkewAnalyzer::compute_stats(&[10, 0]).is_none());
+    }
+
+    #[test]
+    fn test_analyze() {
+        let monitor = Arc::new(Mutex::new(Monitor::new("pipeline", "", 10, 100, 4)));
+        let sub_datas = vec![vec![100, 100], vec![1, 1], vec![1, 1]];
+
+        // not rebalanced
+        let mut analyzer = DataSkewAnalyzer::new(0.5, false, monitor.clone());
+        let result = analyzer.analyze(sub_datas.clone(), |size| *size);
+        assert_eq!(result, sub_datas);
+        assert_eq!(analyzer.consecutive_skews, 1);
+
+        // rebalanced
+        let mut analyzer = DataSkewAnalyzer::new(0.5, true, monitor.clone());
+        let result = analyzer.analyze(sub_datas.clone(), |size| *size);
+        assert_eq!(result, vec![vec![100], vec![100], vec![1, 1, 1, 1]]);
+
+        // below threshold
+        let mut analyzer = DataSkewAnalyzer::new(2.0, true, monitor);
+        let result = analyzer.analyze(sub_datas.clone(), |size| *size);
+        assert_eq!(result, sub_datas);
+        assert_eq!(analyzer.consecutive_skews, 0);
+    }
+}
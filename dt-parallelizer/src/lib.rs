@@ -1,5 +1,6 @@
 pub mod base_parallelizer;
 pub mod check_parallelizer;
+pub mod data_skew_analyzer;
 pub mod foxlake_parallelizer;
 pub mod merge_parallelizer;
 pub mod mongo_merger;
@@ -10,6 +11,10 @@ pub mod redis_parallelizer;
 pub mod serial_parallelizer;
 pub mod snapshot_parallelizer;
 pub mod table_parallelizer;
+pub mod transaction_parallelizer;
+
+#[cfg(test)]
+mod test_util;
 
 use std::sync::Arc;
 
@@ -58,6 +58,9 @@ impl Parallelizer for MergeParallelizer {
     ) -> anyhow::Result<()> {
         // no need to check foreign key since foreign key checks were disabled in MySQL/Postgres connections
         let mut tb_merged_datas = self.merger.merge(data).await?;
+        if self.base_parallelizer.chunk_transaction {
+            return self.sink_dml_in_tx_by_tb(tb_merged_datas, sinkers).await;
+        }
         self.sink_dml_internal(&mut tb_merged_datas, sinkers, MergeType::Delete)
             .await?;
         self.sink_dml_internal(&mut tb_merged_datas, sinkers, MergeType::Insert)
@@ -134,23 +137,109 @@ impl MergeParallelizer {
         Ok(())
     }
 
-    async fn sink_unmerged_rows(
-        sinker: Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>,
-        data: Vec<RowData>,
+    // the delete and insert halves of merged rows, and unmerged rows of a table are sunk
+    // by one sinker in one transaction, so an update merged into delete + insert is never
+    // torn, tables are sunk in parallel
+    async fn sink_dml_in_tx_by_tb(
+        &self,
+        tb_merged_datas: Vec<TbMergedData>,
+        sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
     ) -> anyhow::Result<()> {
+        let mut join_set = tokio::task::JoinSet::new();
+        for tb_merged_data in tb_merged_datas {
+            let batches = Self::get_tb_batches(tb_merged_data);
+            if batches.is_empty() {
+                continue;
+            }
+            let sinker = sinkers[join_set.len() % self.parallel_size].clone();
+            join_set
+                .spawn(async move { sinker.lock().await.sink_dml_batches_in_tx(batches).await });
+        }
+        while let Some(result) = join_set.join_next().await {
+            result??;
+        }
+        Ok(())
+    }
+
+    fn get_tb_batches(tb_merged_data: TbMergedData) -> Vec<(Vec<RowData>, bool)> {
+        let mut batches = Vec::new();
+        if !tb_merged_data.delete_rows.is_empty() {
+            batches.push((tb_merged_data.delete_rows, true));
+        }
+        if !tb_merged_data.insert_rows.is_empty() {
+            batches.push((tb_merged_data.insert_rows, true));
+        }
+        batches.extend(Self::split_unmerged_rows(tb_merged_data.unmerged_rows));
+        batches
+    }
+
+    // consecutive rows of the same row type, inserts are sunk in batch
+    fn split_unmerged_rows(data: Vec<RowData>) -> Vec<(Vec<RowData>, bool)> {
+        let mut batches = Vec::new();
         let mut start = 0;
         for i in 1..=data.len() {
             if i == data.len() || data[i].row_type != data[start].row_type {
-                let sub_data = data[start..i].to_vec();
-                if data[start].row_type == RowType::Insert {
-                    sinker.lock().await.sink_dml(sub_data, true).await?;
-                } else {
-                    // for Delete / Update, the safest way is serial
-                    sinker.lock().await.sink_dml(sub_data, false).await?;
-                }
+                // for Delete / Update, the safest way is serial
+                let batch = data[start].row_type == RowType::Insert;
+                batches.push((data[start..i].to_vec(), batch));
                 start = i;
             }
         }
+        batches
+    }
+
+    async fn sink_unmerged_rows(
+        sinker: Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>,
+        data: Vec<RowData>,
+    ) -> anyhow::Result<()> {
+        for (sub_data, batch) in Self::split_unmerged_rows(data) {
+            sinker.lock().await.sink_dml(sub_data, batch).await?;
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::row;
+
+    #[test]
+    fn test_get_tb_batches() {
+        let tb_merged_data = TbMergedData {
+            tb: "db_1.tb_1".into(),
+            delete_rows: vec![row(RowType::Delete, 1), row(RowType::Delete, 2)],
+            insert_rows: vec![row(RowType::Insert, 2)],
+            unmerged_rows: vec![
+                row(RowType::Insert, 3),
+                row(RowType::Insert, 4),
+                row(RowType::Update, 3),
+                row(RowType::Delete, 4),
+            ],
+        };
+        let batches = MergeParallelizer::get_tb_batches(tb_merged_data);
+        let summary: Vec<(RowType, usize, bool)> = batches
+            .iter()
+            .map(|(data, batch)| (data[0].row_type.clone(), data.len(), *batch))
+            .collect();
+        // the delete and insert halves of the table go to one transaction in order
+        assert_eq!(
+            summary,
+            vec![
+                (RowType::Delete, 2, true),
+                (RowType::Insert, 1, true),
+                (RowType::Insert, 2, true),
+                (RowType::Update, 1, false),
+                (RowType::Delete, 1, false),
+            ]
+        );
+
+        let tb_merged_data = TbMergedData {
+            tb: "db_1.tb_1".into(),
+            delete_rows: Vec::new(),
+            insert_rows: Vec::new(),
+            unmerged_rows: Vec::new(),
+        };
+        assert!(MergeParallelizer::get_tb_batches(tb_merged_data).is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use async_trait::async_trait;
-use dt_common::log_debug;
+use dt_common::log_debug_row;
 use dt_common::meta::{
     rdb_meta_manager::RdbMetaManager, rdb_tb_meta::RdbTbMeta, row_data::RowData, row_type::RowType,
 };
@@ -126,7 +126,7 @@ impl RdbMerger {
         let after = row_data.after.as_ref().unwrap();
         for col in tb_meta.id_cols.iter() {
             if before.get(col) != after.get(col) {
-                log_debug!("rdb_merger, uk change found, row_data: {:?}", row_data);
+                log_debug_row!("rdb_merger, uk change found, row_data: {:?}", row_data);
                 return true;
             }
         }
@@ -152,7 +152,7 @@ impl RdbMerger {
 
             for col in tb_meta.id_cols.iter() {
                 if col_values.get(col) != exist_col_values.get(col) {
-                    log_debug!("rdb_merger, collision found, row_data: {:?}", row_data);
+                    log_debug_row!("rdb_merger, collision found, row_data: {:?}", row_data);
                     return true;
                 }
             }
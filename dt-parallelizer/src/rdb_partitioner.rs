@@ -1,4 +1,4 @@
-use dt_common::log_debug;
+use dt_common::log_debug_row;
 use dt_common::meta::{rdb_meta_manager::RdbMetaManager, row_data::RowData, row_type::RowType};
 
 pub struct RdbPartitioner {
@@ -58,7 +58,7 @@ impl RdbPartitioner {
                 let col_value_before = before.get(col);
                 let col_value_after = after.get(col);
                 if col_value_before != col_value_after {
-                    log_debug!(
+                    log_debug_row!(
                         "{}.{}.{} changed from {:?} to {:?}",
                         &row_data.schema,
                         &row_data.tb,
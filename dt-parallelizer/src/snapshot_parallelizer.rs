@@ -1,16 +1,28 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use dt_common::meta::{dt_data::DtItem, dt_queue::DtQueue, row_data::RowData};
+use dt_common::{
+    config::config_enums::SnapshotPartitionMode,
+    meta::{
+        dt_data::{DtData, DtItem},
+        dt_queue::DtQueue,
+        rdb_meta_manager::RdbMetaManager,
+        row_data::RowData,
+    },
+};
 use dt_connector::Sinker;
 
 use crate::Parallelizer;
 
-use super::base_parallelizer::BaseParallelizer;
+use super::{base_parallelizer::BaseParallelizer, data_skew_analyzer::DataSkewAnalyzer};
 
 pub struct SnapshotParallelizer {
     pub base_parallelizer: BaseParallelizer,
     pub parallel_size: usize,
+    pub skew_analyzer: DataSkewAnalyzer,
+    pub partition_mode: SnapshotPartitionMode,
+    // required by SnapshotPartitionMode::IdCols
+    pub meta_manager: Option<RdbMetaManager>,
 }
 
 #[async_trait]
@@ -19,6 +31,13 @@ impl Parallelizer for SnapshotParallelizer {
         "SnapshotParallelizer".to_string()
     }
 
+    async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(meta_manager) = &self.meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
+
     async fn drain(&mut self, buffer: &DtQueue) -> anyhow::Result<Vec<DtItem>> {
         self.base_parallelizer.drain(buffer).await
     }
@@ -28,10 +47,32 @@ impl Parallelizer for SnapshotParallelizer {
         data: Vec<RowData>,
         sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
     ) -> anyhow::Result<()> {
-        let sub_datas = Self::partition(data, self.parallel_size)?;
-        self.base_parallelizer
-            .sink_dml(sub_datas, sinkers, self.parallel_size, true)
-            .await
+        let sub_datas = match self.partition_mode {
+            SnapshotPartitionMode::Position => Self::partition(data, self.parallel_size)?,
+            SnapshotPartitionMode::IdCols => {
+                self.partition_by_id_cols(data, |row_data| Some(row_data))
+                    .await?
+            }
+        };
+        let sub_datas = self
+            .skew_analyzer
+            .analyze(sub_datas, |row_data| row_data.data_size);
+
+        match self.partition_mode {
+            SnapshotPartitionMode::Position => {
+                self.base_parallelizer
+                    .sink_dml(sub_datas, sinkers, self.parallel_size, true)
+                    .await
+            }
+            // a partition may mix inserts / updates / deletes, while sinkers choose the
+            // batch statement by the first row, so each partition is sunk as runs of one row type
+            SnapshotPartitionMode::IdCols => {
+                let sub_batches = sub_datas.into_iter().map(Self::split_by_row_type).collect();
+                self.base_parallelizer
+                    .sink_dml_batches(sub_batches, sinkers, self.parallel_size)
+                    .await
+            }
+        }
     }
 
     async fn sink_raw(
@@ -39,7 +80,19 @@ impl Parallelizer for SnapshotParallelizer {
         data: Vec<DtItem>,
         sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
     ) -> anyhow::Result<()> {
-        let sub_datas = Self::partition(data, self.parallel_size)?;
+        let sub_datas = match self.partition_mode {
+            SnapshotPartitionMode::Position => Self::partition(data, self.parallel_size)?,
+            SnapshotPartitionMode::IdCols => {
+                self.partition_by_id_cols(data, |item| match &item.dt_data {
+                    DtData::Dml { row_data } => Some(row_data),
+                    _ => None,
+                })
+                .await?
+            }
+        };
+        let sub_datas = self
+            .skew_analyzer
+            .analyze(sub_datas, |item| item.dt_data.get_data_size());
         self.base_parallelizer
             .sink_raw(sub_datas, sinkers, self.parallel_size, true)
             .await
@@ -47,6 +100,8 @@ impl Parallelizer for SnapshotParallelizer {
 }
 
 impl SnapshotParallelizer {
+    // split data into parallele_size contiguous slices whose sizes differ by at most 1,
+    // contiguous slices keep rows of adjacent keys in the same batch of a sinker
     pub fn partition<T>(data: Vec<T>, parallele_size: usize) -> anyhow::Result<Vec<Vec<T>>> {
         let mut sub_datas = Vec::new();
         if parallele_size <= 1 {
@@ -54,18 +109,168 @@ impl SnapshotParallelizer {
             return Ok(sub_datas);
         }
 
-        let avg_size = data.len() / parallele_size + 1;
-        for _ in 0..parallele_size {
-            sub_datas.push(Vec::with_capacity(avg_size));
+        // the first (data.len() % parallele_size) slices take one more item
+        let min_size = data.len() / parallele_size;
+        let larger_count = data.len() % parallele_size;
+        let mut iter = data.into_iter();
+        for i in 0..parallele_size {
+            let size = if i < larger_count {
+                min_size + 1
+            } else {
+                min_size
+            };
+            sub_datas.push(iter.by_ref().take(size).collect());
         }
+        Ok(sub_datas)
+    }
+
+    // rows of the same id_cols values always go to the same partition in their original order,
+    // items without row data (E.g. raw data of non-rdb sources) go to the first partition
+    async fn partition_by_id_cols<T>(
+        &mut self,
+        data: Vec<T>,
+        get_row_data: impl Fn(&T) -> Option<&RowData> + Send,
+    ) -> anyhow::Result<Vec<Vec<T>>>
+    where
+        T: Send + Sync,
+    {
+        if self.parallel_size <= 1 {
+            return Ok(vec![data]);
+        }
+
+        let meta_manager = self.meta_manager.as_mut().unwrap();
+        let mut hash_codes = Vec::with_capacity(data.len());
+        for item in data.iter() {
+            let hash_code = match get_row_data(item) {
+                Some(row_data) => {
+                    let tb_meta = meta_manager
+                        .get_tb_meta(&row_data.schema, &row_data.tb)
+                        .await?;
+                    row_data.get_hash_code(tb_meta)
+                }
+                None => 0,
+            };
+            hash_codes.push(hash_code);
+        }
+        Ok(Self::partition_by_hash_codes(
+            data,
+            &hash_codes,
+            self.parallel_size,
+        ))
+    }
 
-        let mut i = 0;
-        for item in data {
-            sub_datas[i].push(item);
-            if sub_datas[i].len() >= avg_size {
-                i += 1;
+    // consecutive rows of the same row type in their original order
+    pub fn split_by_row_type(data: Vec<RowData>) -> Vec<(Vec<RowData>, bool)> {
+        let mut batches: Vec<(Vec<RowData>, bool)> = Vec::new();
+        for row_data in data {
+            match batches.last_mut() {
+                Some((batch_data, _)) if batch_data[0].row_type == row_data.row_type => {
+                    batch_data.push(row_data)
+                }
+                _ => batches.push((vec![row_data], true)),
             }
         }
-        Ok(sub_datas)
+        batches
+    }
+
+    pub fn partition_by_hash_codes<T>(
+        data: Vec<T>,
+        hash_codes: &[u128],
+        parallel_size: usize,
+    ) -> Vec<Vec<T>> {
+        let mut sub_datas: Vec<Vec<T>> = (0..parallel_size.max(1)).map(|_| Vec::new()).collect();
+        let partition_count = sub_datas.len() as u128;
+        for (item, hash_code) in data.into_iter().zip(hash_codes) {
+            sub_datas[(hash_code % partition_count) as usize].push(item);
+        }
+        sub_datas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::{col_value::ColValue, row_type::RowType};
+
+    use super::SnapshotParallelizer;
+    use crate::test_util::row;
+
+    #[test]
+    fn test_partition() {
+        let sub_datas = SnapshotParallelizer::partition((0..10).collect(), 4).unwrap();
+        assert_eq!(
+            sub_datas,
+            vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7], vec![8, 9]]
+        );
+
+        for (count, parallel_size) in [(0, 3), (2, 3), (7, 7), (100, 8), (1001, 16)] {
+            let sub_datas =
+                SnapshotParallelizer::partition((0..count).collect(), parallel_size).unwrap();
+            assert_eq!(sub_datas.len(), parallel_size);
+            let sizes: Vec<usize> = sub_datas.iter().map(|sub_data| sub_data.len()).collect();
+            let (min, max) = (sizes.iter().min().unwrap(), sizes.iter().max().unwrap());
+            assert!(max - min <= 1);
+            assert_eq!(sub_datas.concat(), (0..count).collect::<Vec<i32>>());
+        }
+
+        let sub_datas = SnapshotParallelizer::partition(vec![1, 2], 1).unwrap();
+        assert_eq!(sub_datas, vec![vec![1, 2]]);
+    }
+
+    #[test]
+    fn test_partition_by_hash_codes() {
+        let data = vec!["a1", "b1", "a2", "c1", "b2"];
+        let hash_codes = vec![10, 11, 10, 12, 11];
+        let sub_datas = SnapshotParallelizer::partition_by_hash_codes(data, &hash_codes, 2);
+        assert_eq!(sub_datas, vec![vec!["a1", "a2", "c1"], vec!["b1", "b2"]]);
+
+        let sub_datas = SnapshotParallelizer::partition_by_hash_codes(vec!["a1"], &[7], 1);
+        assert_eq!(sub_datas, vec![vec!["a1"]]);
+    }
+
+    #[test]
+    fn test_split_by_row_type() {
+        // an interleaved partition of one id_cols bucket
+        let data = vec![
+            row(RowType::Insert, 1),
+            row(RowType::Insert, 2),
+            row(RowType::Delete, 1),
+            row(RowType::Update, 2),
+            row(RowType::Update, 3),
+            row(RowType::Insert, 1),
+            row(RowType::Delete, 2),
+        ];
+        let batches = SnapshotParallelizer::split_by_row_type(data);
+        let summary: Vec<(RowType, Vec<i32>, bool)> = batches
+            .iter()
+            .map(|(data, batch)| {
+                let ids = data
+                    .iter()
+                    .map(|row_data| {
+                        let image = row_data.after.as_ref().or(row_data.before.as_ref());
+                        match image.unwrap().get("id") {
+                            Some(ColValue::Long(id)) => *id,
+                            _ => unreachable!(),
+                        }
+                    })
+                    .collect();
+                (data[0].row_type.clone(), ids, *batch)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (RowType::Insert, vec![1, 2], true),
+                (RowType::Delete, vec![1], true),
+                (RowType::Update, vec![2, 3], true),
+                (RowType::Insert, vec![1], true),
+                (RowType::Delete, vec![2], true),
+            ]
+        );
+        // no run mixes row types
+        assert!(batches
+            .iter()
+            .all(|(data, _)| data.iter().all(|r| r.row_type == data[0].row_type)));
+
+        assert!(SnapshotParallelizer::split_by_row_type(Vec::new()).is_empty());
     }
 }
@@ -0,0 +1,14 @@
+use std::collections::HashMap;
+
+use dt_common::meta::{col_value::ColValue, row_data::RowData, row_type::RowType};
+
+// a row of db_1.tb_1 with a single col: id
+pub fn row(row_type: RowType, id: i32) -> RowData {
+    let image = Some(HashMap::from([("id".to_string(), ColValue::Long(id))]));
+    let (before, after) = match row_type {
+        RowType::Insert => (None, image),
+        RowType::Delete => (image, None),
+        RowType::Update => (image.clone(), image),
+    };
+    RowData::new("db_1".into(), "tb_1".into(), row_type, before, after)
+}
@@ -0,0 +1,495 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use dt_common::{
+    log_debug,
+    meta::{
+        dt_data::{DtData, DtItem},
+        dt_queue::DtQueue,
+        rdb_meta_manager::RdbMetaManager,
+        rdb_tb_meta::RdbTbMeta,
+        row_data::RowData,
+    },
+    monitor::counter::Counter,
+};
+use dt_connector::Sinker;
+
+use crate::Parallelizer;
+
+use super::base_parallelizer::BaseParallelizer;
+
+// dispatch whole source transactions to sinkers, a transaction is never split among sinkers
+// and is written in one target transaction. transactions are detected by DtData::Commit
+// pushed by cdc extractors (mysql: XID event, pg: COMMIT message), all dmls since the
+// previous Commit belong to the transaction.
+// transactions touching the same keys (id cols or any unique key) are written by the same
+// sinker in commit order, others are written in parallel.
+pub struct TransactionParallelizer {
+    pub base_parallelizer: BaseParallelizer,
+    pub parallel_size: usize,
+    pub meta_manager: RdbMetaManager,
+    // dml counts of transactions in the last drained batch, in commit order
+    pub tx_row_counts: Vec<usize>,
+}
+
+// keys touched by transactions
+#[derive(Default)]
+pub struct TxKeys {
+    pub row_keys: HashSet<u64>,
+    pub tbs: HashSet<(String, String)>,
+    // tables without id cols, or referenced by foreign keys, all rows are considered touched
+    pub locked_tbs: HashSet<(String, String)>,
+}
+
+#[derive(Default)]
+struct Lane {
+    txs: Vec<Vec<RowData>>,
+    keys: TxKeys,
+    row_count: usize,
+}
+
+#[async_trait]
+impl Parallelizer for TransactionParallelizer {
+    fn get_name(&self) -> String {
+        "TransactionParallelizer".to_string()
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.meta_manager.close().await
+    }
+
+    async fn drain(&mut self, buffer: &DtQueue) -> anyhow::Result<Vec<DtItem>> {
+        let mut data = Vec::new();
+        let mut record_size_counter = Counter::new(0, 0);
+        // ddls and dmls are drained separately, a ddl also ends the pending transaction
+        let mut boundary_reached = false;
+        loop {
+            let item = match self.base_parallelizer.poped_data.pop_front() {
+                Some(item) => item,
+                None => match self
+                    .base_parallelizer
+                    .pop(buffer, &mut record_size_counter)
+                    .await
+                {
+                    Ok(item) => item,
+                    Err(_) => break,
+                },
+            };
+
+            if !data.is_empty() && data[0].is_ddl() != item.is_ddl() {
+                self.base_parallelizer.poped_data.push_front(item);
+                boundary_reached = true;
+                break;
+            }
+            data.push(item);
+        }
+        self.base_parallelizer
+            .update_monitor(&record_size_counter)
+            .await;
+
+        // items of the uncommitted transaction are kept for the next drain, so positions
+        // of the returned items never go beyond the last complete transaction
+        let complete_count = if boundary_reached || data.first().is_some_and(|i| i.is_ddl()) {
+            data.len()
+        } else {
+            Self::get_complete_count(&data)
+        };
+        let pending = data.split_off(complete_count);
+        for item in pending.into_iter().rev() {
+            self.base_parallelizer.poped_data.push_front(item);
+        }
+
+        self.tx_row_counts = Self::get_tx_row_counts(&data);
+        Ok(data)
+    }
+
+    async fn sink_dml(
+        &mut self,
+        data: Vec<RowData>,
+        sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
+    ) -> anyhow::Result<()> {
+        let txs = self.split_txs(data);
+        let mut lanes: Vec<Lane> = (0..self.parallel_size).map(|_| Lane::default()).collect();
+        for tx in txs {
+            let keys = self.get_tx_keys(&tx).await?;
+            let lane_index = match Self::get_lane_index(&lanes, &keys) {
+                Some(lane_index) => lane_index,
+                // the transaction depends on transactions of multiple lanes,
+                // wait for all of them to finish
+                None => {
+                    log_debug!("transaction conflicts with multiple lanes");
+                    Self::sink_lanes(&mut lanes, sinkers).await?;
+                    0
+                }
+            };
+
+            let lane = &mut lanes[lane_index];
+            lane.row_count += tx.len();
+            lane.keys.merge(keys);
+            lane.txs.push(tx);
+        }
+        Self::sink_lanes(&mut lanes, sinkers).await
+    }
+}
+
+impl TransactionParallelizer {
+    // count of leading items of complete transactions
+    pub fn get_complete_count(data: &[DtItem]) -> usize {
+        let mut complete_count = 0;
+        let mut pending_dml = false;
+        for (i, item) in data.iter().enumerate() {
+            match &item.dt_data {
+                DtData::Dml { .. } => pending_dml = true,
+                DtData::Commit { .. } => {
+                    pending_dml = false;
+                    complete_count = i + 1;
+                }
+                _ => {
+                    if !pending_dml {
+                        complete_count = i + 1;
+                    }
+                }
+            }
+        }
+        complete_count
+    }
+
+    pub fn get_tx_row_counts(data: &[DtItem]) -> Vec<usize> {
+        let mut tx_row_counts = Vec::new();
+        let mut row_count = 0;
+        for item in data.iter() {
+            match &item.dt_data {
+                DtData::Dml { .. } => row_count += 1,
+                DtData::Commit { .. } if row_count > 0 => {
+                    tx_row_counts.push(row_count);
+                    row_count = 0;
+                }
+                _ => {}
+            }
+        }
+        if row_count > 0 {
+            tx_row_counts.push(row_count);
+        }
+        tx_row_counts
+    }
+
+    // rows may be dropped / added by pipeline processors (lua, row_filters, etc.) after drain,
+    // then boundaries are unknown and all rows are written as one transaction
+    fn split_txs(&mut self, mut data: Vec<RowData>) -> Vec<Vec<RowData>> {
+        let tx_row_counts = std::mem::take(&mut self.tx_row_counts);
+        if data.is_empty() {
+            return Vec::new();
+        }
+        if tx_row_counts.iter().sum::<usize>() != data.len() {
+            log_debug!(
+                "rows changed after drain, write {} rows as one transaction",
+                data.len()
+            );
+            return vec![data];
+        }
+
+        let mut txs = Vec::with_capacity(tx_row_counts.len());
+        for row_count in tx_row_counts {
+            let rest = data.split_off(row_count);
+            txs.push(data);
+            data = rest;
+        }
+        txs
+    }
+
+    // None if the transaction conflicts with multiple lanes
+    fn get_lane_index(lanes: &[Lane], keys: &TxKeys) -> Option<usize> {
+        let conflict_lanes: Vec<usize> = (0..lanes.len())
+            .filter(|i| lanes[*i].keys.conflicts(keys))
+            .collect();
+        match conflict_lanes.len() {
+            // the least loaded lane
+            0 => Some(
+                (0..lanes.len())
+                    .min_by_key(|i| lanes[*i].row_count)
+                    .unwrap_or(0),
+            ),
+            1 => Some(conflict_lanes[0]),
+            _ => None,
+        }
+    }
+
+    async fn get_tx_keys(&mut self, tx: &[RowData]) -> anyhow::Result<TxKeys> {
+        let mut keys = TxKeys::default();
+        for row_data in tx {
+            let tb_meta = self
+                .meta_manager
+                .get_tb_meta(&row_data.schema, &row_data.tb)
+                .await?;
+            let full_tb = (row_data.schema.clone(), row_data.tb.clone());
+            for fk in tb_meta.foreign_keys.iter() {
+                keys.locked_tbs
+                    .insert((fk.ref_schema.clone(), fk.ref_tb.clone()));
+            }
+
+            if tb_meta.id_cols.is_empty() {
+                keys.locked_tbs.insert(full_tb.clone());
+            } else {
+                Self::add_row_keys(&mut keys, tb_meta, row_data);
+            }
+            keys.tbs.insert(full_tb);
+        }
+        Ok(keys)
+    }
+
+    // rows may conflict on id cols or any unique key, e.g. a row deleted and another row
+    // inserted with the same unique key value, keys with NULL values never conflict
+    fn add_row_keys(keys: &mut TxKeys, tb_meta: &RdbTbMeta, row_data: &RowData) {
+        let full_tb = (&row_data.schema, &row_data.tb);
+        // both before and after keys, in case keys are updated
+        for col_values in [&row_data.before, &row_data.after].into_iter().flatten() {
+            let mut hasher = DefaultHasher::new();
+            full_tb.hash(&mut hasher);
+            tb_meta.id_cols.hash(&mut hasher);
+            for col in tb_meta.id_cols.iter() {
+                let hash_code = col_values.get(col).map_or(0, |v| v.hash_code());
+                hash_code.hash(&mut hasher);
+            }
+            keys.row_keys.insert(hasher.finish());
+
+            for key_cols in tb_meta.key_map.values() {
+                let is_null = key_cols
+                    .iter()
+                    .any(|col| col_values.get(col).map_or(true, |v| v.hash_code() == 0));
+                if is_null {
+                    continue;
+                }
+                let mut hasher = DefaultHasher::new();
+                full_tb.hash(&mut hasher);
+                key_cols.hash(&mut hasher);
+                for col in key_cols.iter() {
+                    col_values[col].hash_code().hash(&mut hasher);
+                }
+                keys.row_keys.insert(hasher.finish());
+            }
+        }
+    }
+
+    // transactions of a lane are written in order by the same sinker, lanes in parallel
+    async fn sink_lanes(
+        lanes: &mut [Lane],
+        sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
+    ) -> anyhow::Result<()> {
+        let mut join_set = tokio::task::JoinSet::new();
+        for (i, lane) in lanes.iter_mut().enumerate() {
+            let lane = std::mem::take(lane);
+            if lane.txs.is_empty() {
+                continue;
+            }
+
+            let sinker = sinkers[i % sinkers.len()].clone();
+            join_set.spawn(async move {
+                let mut sinker = sinker.lock().await;
+                for tx in lane.txs {
+                    // rows of a transaction are written one by one in the source order
+                    sinker.sink_dml_in_tx(tx, false).await?;
+                }
+                Ok::<(), anyhow::Error>(())
+            });
+        }
+        while let Some(result) = join_set.join_next().await {
+            result??;
+        }
+        Ok(())
+    }
+}
+
+impl TxKeys {
+    pub fn conflicts(&self, other: &TxKeys) -> bool {
+        !self.row_keys.is_disjoint(&other.row_keys)
+            || !self.locked_tbs.is_disjoint(&other.tbs)
+            || !self.tbs.is_disjoint(&other.locked_tbs)
+            || !self.locked_tbs.is_disjoint(&other.locked_tbs)
+    }
+
+    pub fn merge(&mut self, other: TxKeys) {
+        self.row_keys.extend(other.row_keys);
+        self.tbs.extend(other.tbs);
+        self.locked_tbs.extend(other.locked_tbs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{
+        col_value::ColValue, ddl_meta::ddl_data::DdlData, position::Position, row_type::RowType,
+    };
+
+    use super::*;
+
+    fn item(dt_data: DtData) -> DtItem {
+        DtItem {
+            dt_data,
+            position: Position::None,
+            data_origin_node: String::new(),
+        }
+    }
+
+    fn dml() -> DtItem {
+        item(DtData::Dml {
+            row_data: RowData::new("db_1".into(), "tb_1".into(), RowType::Insert, None, None),
+        })
+    }
+
+    fn commit() -> DtItem {
+        item(DtData::Commit { xid: "1".into() })
+    }
+
+    #[test]
+    fn test_get_complete_count() {
+        let heartbeat = || item(DtData::Heartbeat {});
+        let begin = || item(DtData::Begin {});
+        let get = |data: Vec<DtItem>| TransactionParallelizer::get_complete_count(&data);
+
+        assert_eq!(get(vec![]), 0);
+        assert_eq!(get(vec![begin(), dml(), dml(), commit()]), 4);
+        assert_eq!(get(vec![begin(), dml(), commit(), begin(), dml()]), 4);
+        // heartbeats after uncommitted dmls are kept with them
+        assert_eq!(get(vec![dml(), commit(), dml(), heartbeat()]), 2);
+        assert_eq!(get(vec![dml(), commit(), heartbeat()]), 3);
+        assert_eq!(get(vec![heartbeat(), dml()]), 1);
+        assert_eq!(get(vec![dml()]), 0);
+    }
+
+    #[test]
+    fn test_get_tx_row_counts() {
+        let get = |data: Vec<DtItem>| TransactionParallelizer::get_tx_row_counts(&data);
+        assert!(get(vec![]).is_empty());
+        assert_eq!(
+            get(vec![dml(), dml(), commit(), commit(), dml(), commit()]),
+            vec![2, 1]
+        );
+        assert_eq!(get(vec![dml(), commit(), dml()]), vec![1, 1]);
+        let ddl = item(DtData::Ddl {
+            ddl_data: DdlData::default(),
+        });
+        assert!(get(vec![ddl]).is_empty());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let keys = |row_keys: Vec<u64>, tbs: Vec<&str>, locked_tbs: Vec<&str>| TxKeys {
+            row_keys: row_keys.into_iter().collect(),
+            tbs: tbs
+                .into_iter()
+                .map(|tb| ("db_1".to_string(), tb.to_string()))
+                .collect(),
+            locked_tbs: locked_tbs
+                .into_iter()
+                .map(|tb| ("db_1".to_string(), tb.to_string()))
+                .collect(),
+        };
+
+        let a = keys(vec![1, 2], vec!["tb_1"], vec![]);
+        assert!(a.conflicts(&keys(vec![2, 3], vec!["tb_1"], vec![])));
+        assert!(!a.conflicts(&keys(vec![3], vec!["tb_1"], vec![])));
+        // tables without keys, or referenced by foreign keys, conflict with any row of them
+        assert!(a.conflicts(&keys(vec![], vec!["tb_1"], vec!["tb_1"])));
+        assert!(keys(vec![], vec!["tb_2"], vec!["tb_1"]).conflicts(&a));
+        assert!(!a.conflicts(&keys(vec![], vec!["tb_2"], vec!["tb_2"])));
+
+        let mut merged = TxKeys::default();
+        merged.merge(keys(vec![1], vec!["tb_1"], vec![]));
+        merged.merge(keys(vec![], vec!["tb_2"], vec!["tb_2"]));
+        assert!(merged.conflicts(&keys(vec![9], vec!["tb_2"], vec![])));
+        assert!(merged.conflicts(&keys(vec![1], vec!["tb_1"], vec![])));
+        assert!(!merged.conflicts(&keys(vec![9], vec!["tb_1"], vec![])));
+    }
+
+    #[test]
+    fn test_add_row_keys() {
+        let tb_meta = RdbTbMeta {
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            id_cols: vec!["id".into()],
+            key_map: HashMap::from([
+                ("primary".to_string(), vec!["id".to_string()]),
+                ("uk_1".to_string(), vec!["name".to_string()]),
+            ]),
+            ..Default::default()
+        };
+        let get_keys = |row_type: RowType, id: i32, name: Option<&str>| {
+            let col_values = HashMap::from([
+                ("id".to_string(), ColValue::Long(id)),
+                (
+                    "name".to_string(),
+                    name.map_or(ColValue::None, |v| ColValue::String(v.into())),
+                ),
+            ]);
+            let (before, after) = match row_type {
+                RowType::Delete => (Some(col_values), None),
+                _ => (None, Some(col_values)),
+            };
+            let row_data = RowData::new("db_1".into(), "tb_1".into(), row_type, before, after);
+            let mut keys = TxKeys::default();
+            TransactionParallelizer::add_row_keys(&mut keys, &tb_meta, &row_data);
+            keys
+        };
+
+        // a row deleted and another row inserted with the same unique key conflict
+        let delete = get_keys(RowType::Delete, 1, Some("a"));
+        assert!(delete.conflicts(&get_keys(RowType::Insert, 2, Some("a"))));
+        assert!(delete.conflicts(&get_keys(RowType::Insert, 1, Some("b"))));
+        assert!(!delete.conflicts(&get_keys(RowType::Insert, 2, Some("b"))));
+        // NULL unique key values never conflict
+        let insert = get_keys(RowType::Insert, 3, None);
+        assert!(!insert.conflicts(&get_keys(RowType::Insert, 4, None)));
+        // the same values in another table do not conflict
+        let mut other_tb_keys = TxKeys::default();
+        let row_data = RowData::new(
+            "db_1".into(),
+            "tb_2".into(),
+            RowType::Insert,
+            None,
+            Some(HashMap::from([
+                ("id".to_string(), ColValue::Long(1)),
+                ("name".to_string(), ColValue::String("a".into())),
+            ])),
+        );
+        TransactionParallelizer::add_row_keys(&mut other_tb_keys, &tb_meta, &row_data);
+        assert!(!delete.conflicts(&other_tb_keys));
+    }
+
+    #[test]
+    fn test_get_lane_index() {
+        let lane = |row_keys: Vec<u64>, row_count: usize| Lane {
+            txs: Vec::new(),
+            keys: TxKeys {
+                row_keys: row_keys.into_iter().collect(),
+                ..Default::default()
+            },
+            row_count,
+        };
+        let keys = |row_keys: Vec<u64>| TxKeys {
+            row_keys: row_keys.into_iter().collect(),
+            ..Default::default()
+        };
+        let lanes = vec![lane(vec![1, 2], 5), lane(vec![3], 2), lane(vec![4], 9)];
+        let get = |keys: &TxKeys| TransactionParallelizer::get_lane_index(&lanes, keys);
+
+        // no conflicts, the least loaded lane
+        assert_eq!(get(&keys(vec![10])), Some(1));
+        // conflicts with one lane, even if it is not the least loaded
+        assert_eq!(get(&keys(vec![2, 10])), Some(0));
+        assert_eq!(get(&keys(vec![4])), Some(2));
+        // conflicts with multiple lanes
+        assert_eq!(get(&keys(vec![1, 3])), None);
+
+        let empty_lanes: Vec<Lane> = (0..3).map(|_| Lane::default()).collect();
+        assert_eq!(
+            TransactionParallelizer::get_lane_index(&empty_lanes, &keys(vec![1])),
+            Some(0)
+        );
+    }
+}
@@ -1,14 +1,15 @@
 use std::{
+    collections::BTreeMap,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc, Mutex, RwLock,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use async_trait::async_trait;
 use dt_common::{
-    config::sinker_config::SinkerConfig,
+    config::{config_enums::KafkaMessageFormat, sinker_config::SinkerConfig},
     meta::{
         ddl_meta::ddl_data::DdlData,
         dt_data::{DtData, DtItem},
@@ -21,12 +22,22 @@ use dt_common::{
 use dt_common::{
     log_info, log_position,
     monitor::{counter_type::CounterType, monitor::Monitor},
-    utils::time_util::TimeUtil,
+    utils::{
+        integrity_hash_chain::IntegrityHashChain, rpo_calculator::RpoCalculator,
+        time_util::TimeUtil,
+    },
+};
+use dt_connector::{
+    data_marker::DataMarker, position_store::DbPositionStore,
+    sinker::dead_letter_queue::DeadLetterQueue, Sinker,
 };
-use dt_connector::{data_marker::DataMarker, Sinker};
 use dt_parallelizer::Parallelizer;
 
-use crate::{lua_processor::LuaProcessor, Pipeline};
+use crate::{
+    column_value_transformer::ColumnValueTransformer, column_value_truncator::ColumnValueTruncator,
+    lua_processor::LuaProcessor, row_filter::RowFilter, time_zone_convertor::TimeZoneConvertor,
+    Pipeline,
+};
 
 pub struct BasePipeline {
     pub buffer: Arc<DtQueue>,
@@ -34,12 +45,24 @@ pub struct BasePipeline {
     pub sinker_config: SinkerConfig,
     pub sinkers: Vec<Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>>,
     pub shut_down: Arc<AtomicBool>,
+    // set by admin api, no new batches are fetched from buffer while paused
+    pub paused: Arc<AtomicBool>,
     pub checkpoint_interval_secs: u64,
     pub batch_sink_interval_secs: u64,
     pub syncer: Arc<Mutex<Syncer>>,
     pub monitor: Arc<Mutex<Monitor>>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
+    pub row_filter: Option<RowFilter>,
     pub lua_processor: Option<LuaProcessor>,
+    pub column_value_transformer: Option<ColumnValueTransformer>,
+    pub column_value_truncator: Option<ColumnValueTruncator>,
+    pub time_zone_convertor: Option<TimeZoneConvertor>,
+    pub integrity_hash_chain: Option<IntegrityHashChain>,
+    pub integrity_manifest_file: String,
+    pub integrity_private_key_file: String,
+    // shared with sinkers, which move rows rejected by the target to it
+    pub dead_letter_queue: Option<Arc<async_mutex::Mutex<DeadLetterQueue>>>,
+    pub position_store: Option<DbPositionStore>,
 }
 
 enum SinkMethod {
@@ -55,6 +78,9 @@ impl Pipeline for BasePipeline {
         for sinker in self.sinkers.iter_mut() {
             sinker.lock().await.close().await?;
         }
+        if let Some(position_store) = &self.position_store {
+            position_store.close().await?;
+        }
         self.parallelizer.close().await
     }
 
@@ -68,6 +94,8 @@ impl Pipeline for BasePipeline {
 
         let mut last_sink_time = Instant::now();
         let mut last_checkpoint_time = Instant::now();
+        // time spent on tracking positions since the last checkpoint
+        let mut position_tracking_time = Duration::ZERO;
         let mut last_received_position = Position::None;
         let mut last_commit_position = Position::None;
 
@@ -80,6 +108,18 @@ impl Pipeline for BasePipeline {
                     .add_counter(CounterType::BufferSize, self.buffer.len());
             }
 
+            // the extractor is blocked once buffer is full
+            if self.paused.load(Ordering::Acquire) {
+                last_checkpoint_time = self.record_checkpoint(
+                    Some(last_checkpoint_time),
+                    &last_received_position,
+                    &last_commit_position,
+                    &mut position_tracking_time,
+                );
+                TimeUtil::sleep_millis(100).await;
+                continue;
+            }
+
             // some sinkers (foxlake) need to accumulate data to a big batch and sink
             let data = if last_sink_time.elapsed().as_secs() < self.batch_sink_interval_secs
                 && !self.buffer.is_full()
@@ -105,18 +145,25 @@ impl Pipeline for BasePipeline {
                 SinkMethod::Struct => self.sink_struct(data).await?,
             };
 
+            let tracking_start_time = Instant::now();
             if let Some(position) = &last_received {
                 self.syncer.lock().unwrap().received_position = position.to_owned();
                 last_received_position = position.to_owned();
             }
             if let Some(position) = &last_commit {
                 last_commit_position = position.to_owned();
+                // sink returns after sinkers committed, so data before the position is in target
+                if let Some(position_store) = &self.position_store {
+                    position_store.save(position).await?;
+                }
             }
 
+            position_tracking_time += tracking_start_time.elapsed();
             last_checkpoint_time = self.record_checkpoint(
                 Some(last_checkpoint_time),
                 &last_received_position,
                 &last_commit_position,
+                &mut position_tracking_time,
             );
 
             self.monitor
@@ -128,7 +175,24 @@ impl Pipeline for BasePipeline {
             TimeUtil::sleep_millis(1).await;
         }
 
-        self.record_checkpoint(None, &last_received_position, &last_commit_position);
+        self.record_checkpoint(
+            None,
+            &last_received_position,
+            &last_commit_position,
+            &mut position_tracking_time,
+        );
+
+        if let Some(integrity_hash_chain) = &self.integrity_hash_chain {
+            integrity_hash_chain.write_manifest(
+                &self.integrity_manifest_file,
+                &self.integrity_private_key_file,
+            )?;
+            log_info!(
+                "integrity manifest written, task: {}, final hash: {}",
+                integrity_hash_chain.task_id,
+                integrity_hash_chain.hash_hex()
+            );
+        }
         Ok(())
     }
 }
@@ -165,15 +229,71 @@ impl BasePipeline {
         &mut self,
         all_data: Vec<DtItem>,
     ) -> anyhow::Result<(usize, Option<Position>, Option<Position>)> {
-        let (mut data, last_received_position, last_commit_position) = Self::fetch_dml(all_data);
+        // kafka json messages carry the source position of each row
+        let keep_position = matches!(
+            self.sinker_config,
+            SinkerConfig::Kafka {
+                message_format: KafkaMessageFormat::Json,
+                ..
+            }
+        );
+        let (mut data, last_received_position, last_commit_position) =
+            Self::fetch_dml(all_data, keep_position);
         let count = data.len();
         if count > 0 {
+            // evaluated before lua / transforms change the values
+            if let Some(row_filter) = &self.row_filter {
+                let (kept, filtered_count) = row_filter.process(data);
+                data = kept;
+                if filtered_count > 0 {
+                    self.monitor
+                        .lock()
+                        .unwrap()
+                        .add_counter(CounterType::FilteredRows, filtered_count);
+                }
+            }
+
             // execute lua processor
             if let Some(lua_processor) = &self.lua_processor {
                 data = lua_processor.process(data)?;
             }
 
-            self.parallelizer.sink_dml(data, &self.sinkers).await?
+            if let Some(time_zone_convertor) = &self.time_zone_convertor {
+                data = time_zone_convertor.process(data)?;
+            }
+
+            if let Some(column_value_transformer) = &self.column_value_transformer {
+                data = column_value_transformer.process(data)?;
+            }
+
+            if let Some(column_value_truncator) = &mut self.column_value_truncator {
+                data = column_value_truncator.process(data).await?;
+            }
+
+            if let Some(integrity_hash_chain) = &mut self.integrity_hash_chain {
+                // data is moved into parallelizer, a failed sink fails the task anyway
+                integrity_hash_chain.update(&data)?;
+            }
+
+            // schema.tb -> (records, bytes), rows moved to dead letter queue are included
+            let mut table_counters: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+            for row_data in data.iter() {
+                let counter = table_counters
+                    .entry(format!("{}.{}", row_data.schema, row_data.tb))
+                    .or_default();
+                counter.0 += 1;
+                counter.1 += row_data.data_size;
+            }
+
+            if let Some(dead_letter_queue) = &self.dead_letter_queue {
+                dead_letter_queue.lock().await.position = last_received_position.clone();
+            }
+            self.parallelizer.sink_dml(data, &self.sinkers).await?;
+
+            let mut monitor = self.monitor.lock().unwrap();
+            for (tb, (record_count, data_size)) in table_counters {
+                monitor.add_table_counter(&tb, record_count, data_size);
+            }
         }
         Ok((count, last_received_position, last_commit_position))
     }
@@ -192,6 +312,13 @@ impl BasePipeline {
             for sinker in self.sinkers.iter_mut() {
                 sinker.lock().await.refresh_meta(data.clone()).await?;
             }
+
+            let mut monitor = self.monitor.lock().unwrap();
+            for ddl_data in data.iter() {
+                for (schema, tb) in ddl_data.get_dropped_schema_tbs() {
+                    monitor.remove_table_counters(&schema, &tb);
+                }
+            }
         }
         Ok((count, last_received_position, last_commit_position))
     }
@@ -225,7 +352,10 @@ impl BasePipeline {
         (last_received_position, last_commit_position)
     }
 
-    fn fetch_dml(mut data: Vec<DtItem>) -> (Vec<RowData>, Option<Position>, Option<Position>) {
+    fn fetch_dml(
+        mut data: Vec<DtItem>,
+        keep_position: bool,
+    ) -> (Vec<RowData>, Option<Position>, Option<Position>) {
         let mut dml_data = Vec::new();
         let mut last_received_position = Option::None;
         let mut last_commit_position = Option::None;
@@ -237,7 +367,10 @@ impl BasePipeline {
                     continue;
                 }
 
-                DtData::Dml { row_data } => {
+                DtData::Dml { mut row_data } => {
+                    if keep_position {
+                        row_data.position = i.position.to_string();
+                    }
                     last_received_position = Some(i.position);
                     dml_data.push(row_data);
                 }
@@ -299,6 +432,7 @@ impl BasePipeline {
         last_checkpoint_time: Option<Instant>,
         last_received_position: &Position,
         last_commit_position: &Position,
+        position_tracking_time: &mut Duration,
     ) -> Instant {
         if let Some(last) = last_checkpoint_time {
             if last.elapsed().as_secs() < self.checkpoint_interval_secs {
@@ -306,12 +440,80 @@ impl BasePipeline {
             }
         }
 
+        let write_start_time = Instant::now();
         log_position!("current_position | {}", last_received_position.to_string());
         log_position!("checkpoint_position | {}", last_commit_position.to_string());
+        if let Some(integrity_hash_chain) = &self.integrity_hash_chain {
+            log_position!("{}", integrity_hash_chain.to_checkpoint_log());
+        }
 
         if !matches!(last_commit_position, Position::None) {
             self.syncer.lock().unwrap().committed_position = last_commit_position.to_owned();
         }
+
+        // the whole position tracking path since the last checkpoint is counted, including
+        // updating the syncer and saving positions to position_store after every sink
+        *position_tracking_time += write_start_time.elapsed();
+        let interval_ms = match last_checkpoint_time {
+            Some(last) => last.elapsed().as_millis() as u64,
+            None => self.checkpoint_interval_secs * 1000,
+        };
+        RpoCalculator::check_checkpoint_overhead(
+            position_tracking_time.as_millis() as u64,
+            interval_ms,
+        );
+        *position_tracking_time = Duration::ZERO;
         Instant::now()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{col_value::ColValue, row_type::RowType};
+
+    use super::*;
+
+    #[test]
+    fn test_fetch_dml() {
+        let position = Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: "mysql-bin.000001".into(),
+            next_event_position: 100,
+            gtid_set: String::new(),
+            timestamp: String::new(),
+        };
+        let build_data = || {
+            vec![
+                DtItem {
+                    dt_data: DtData::Dml {
+                        row_data: RowData::new(
+                            "db_1".into(),
+                            "tb_1".into(),
+                            RowType::Insert,
+                            None,
+                            Some(HashMap::from([("id".to_string(), ColValue::Long(1))])),
+                        ),
+                    },
+                    position: position.clone(),
+                    data_origin_node: String::new(),
+                },
+                DtItem {
+                    dt_data: DtData::Commit { xid: String::new() },
+                    position: position.clone(),
+                    data_origin_node: String::new(),
+                },
+            ]
+        };
+
+        let (data, last_received, last_commit) = BasePipeline::fetch_dml(build_data(), true);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].position, position.to_string());
+        assert_eq!(last_received, Some(position.clone()));
+        assert_eq!(last_commit, Some(position.clone()));
+
+        let (data, _, _) = BasePipeline::fetch_dml(build_data(), false);
+        assert!(data[0].position.is_empty());
+    }
+}
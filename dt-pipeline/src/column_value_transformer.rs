@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    meta::{col_value::ColValue, row_data::RowData},
+};
+
+const JSON_PREFIX: &str = "json:";
+const DEFAULT_MASK_KEEP_CHARS: usize = 4;
+const MASK_CHAR: char = '*';
+
+type TransformFn = Box<dyn Fn(&mut ColValue) + Send + Sync>;
+
+// apply user configured functions to column values before sinking, e.g. lowercase emails,
+// mask ssns. functions are deterministic, so they are applied to both before and after
+// values of a row, and where conditions of updates / deletes still match transformed rows
+pub struct ColumnValueTransformer {
+    // key: schema.tb.col
+    pub per_col_fns: HashMap<String, TransformFn>,
+}
+
+impl ColumnValueTransformer {
+    // transform_cols=json:{"public.tb_1.email":"lower","public.tb_1.ssn":"mask:4","public.tb_1.memo":"const:n/a"}
+    pub fn from_config(config_str: &str) -> anyhow::Result<Option<Self>> {
+        if config_str.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let per_col_exprs: HashMap<String, String> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        let mut per_col_fns = HashMap::new();
+        for (col, expr) in per_col_exprs.iter() {
+            per_col_fns.insert(col.clone(), Self::parse_fn(col, expr)?);
+        }
+        Ok(Some(Self { per_col_fns }))
+    }
+
+    // supported: lower, upper, trim, mask, mask:{keep_chars}, md5, const, const:{value}
+    fn parse_fn(col: &str, expr: &str) -> anyhow::Result<TransformFn> {
+        let (name, arg) = match expr.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg.to_string())),
+            None => (expr.trim(), None),
+        };
+
+        let transform_fn: TransformFn = match (name, arg) {
+            ("lower", None) => {
+                Box::new(|v: &mut ColValue| Self::map_string(v, |s| s.to_lowercase()))
+            }
+            ("upper", None) => {
+                Box::new(|v: &mut ColValue| Self::map_string(v, |s| s.to_uppercase()))
+            }
+            ("trim", None) => {
+                Box::new(|v: &mut ColValue| Self::map_string(v, |s| s.trim().to_string()))
+            }
+            ("md5", None) => {
+                Box::new(|v: &mut ColValue| Self::map_any(v, |s| format!("{:x}", md5::compute(s))))
+            }
+            ("mask", arg) => {
+                let keep_chars = match arg {
+                    Some(arg) => match arg.trim().parse::<usize>() {
+                        Ok(keep_chars) => keep_chars,
+                        Err(_) => bail! {Error::ConfigError(format!(
+                            "config [processor] transform_cols, invalid mask: {} for column: {}",
+                            expr, col
+                        ))},
+                    },
+                    None => DEFAULT_MASK_KEEP_CHARS,
+                };
+                Box::new(move |v: &mut ColValue| Self::map_any(v, |s| Self::mask(&s, keep_chars)))
+            }
+            // const without value sets the column to NULL
+            ("const", None) => Box::new(|v: &mut ColValue| *v = ColValue::None),
+            ("const", Some(value)) => {
+                Box::new(move |v: &mut ColValue| *v = ColValue::String(value.clone()))
+            }
+            _ => bail! {Error::ConfigError(format!(
+                "config [processor] transform_cols, unsupported function: {} for column: {}",
+                expr, col
+            ))},
+        };
+        Ok(transform_fn)
+    }
+
+    pub fn process(&self, mut data: Vec<RowData>) -> anyhow::Result<Vec<RowData>> {
+        // schema.tb.col, reused by all columns of all rows
+        let mut full_col = String::new();
+        for row_data in data.iter_mut() {
+            full_col.clear();
+            full_col.push_str(&row_data.schema);
+            full_col.push('.');
+            full_col.push_str(&row_data.tb);
+            full_col.push('.');
+            let tb_prefix_len = full_col.len();
+
+            let mut transformed = false;
+            for col_values in [&mut row_data.before, &mut row_data.after]
+                .into_iter()
+                .flatten()
+            {
+                for (col, col_value) in col_values.iter_mut() {
+                    full_col.truncate(tb_prefix_len);
+                    full_col.push_str(col);
+                    if let Some(transform_fn) = self.per_col_fns.get(&full_col) {
+                        transform_fn(col_value);
+                        transformed = true;
+                    }
+                }
+            }
+
+            if transformed {
+                row_data.refresh_data_size();
+            }
+        }
+        Ok(data)
+    }
+
+    // keep the last keep_chars chars, replace others with *
+    pub fn mask(value: &str, keep_chars: usize) -> String {
+        let char_count = value.chars().count();
+        let mask_count = char_count.saturating_sub(keep_chars);
+        value
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i < mask_count { MASK_CHAR } else { c })
+            .collect()
+    }
+
+    // only applies to strings, other values are kept
+    fn map_string(col_value: &mut ColValue, f: impl Fn(&str) -> String) {
+        if let ColValue::String(v) = col_value {
+            *v = f(v);
+        }
+    }
+
+    // non-null values are converted into strings
+    fn map_any(col_value: &mut ColValue, f: impl Fn(String) -> String) {
+        if let Some(v) = col_value.to_option_string() {
+            *col_value = ColValue::String(f(v));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{col_value::ColValue, row_data::RowData, row_type::RowType};
+
+    use super::ColumnValueTransformer;
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(
+            ColumnValueTransformer::mask("123-45-6789", 4),
+            "*******6789"
+        );
+        assert_eq!(ColumnValueTransformer::mask("abc", 4), "abc");
+        assert_eq!(ColumnValueTransformer::mask("中文abc", 1), "****c");
+        assert_eq!(ColumnValueTransformer::mask("abc", 0), "***");
+    }
+
+    #[test]
+    fn test_process() {
+        let transformer = ColumnValueTransformer::from_config(
+            r#"json:{"db_1.tb_1.ssn":"mask","db_1.tb_1.email":"lower","db_1.tb_1.name":"trim",
+            "db_1.tb_1.code":"upper","db_1.tb_1.pwd":"md5","db_1.tb_1.memo":"const:n/a",
+            "db_1.tb_1.note":"const","db_1.tb_2.ssn":"mask:0"}"#,
+        )
+        .unwrap()
+        .unwrap();
+
+        let cols = |ssn: &str| {
+            HashMap::from([
+                ("id".to_string(), ColValue::LongLong(1)),
+                ("ssn".to_string(), ColValue::String(ssn.into())),
+                (
+                    "email".to_string(),
+                    ColValue::String("A@Example.COM".into()),
+                ),
+                ("name".to_string(), ColValue::String(" a ".into())),
+                ("code".to_string(), ColValue::String("ab".into())),
+                ("pwd".to_string(), ColValue::String("abc".into())),
+                ("memo".to_string(), ColValue::String("x".into())),
+                ("note".to_string(), ColValue::String("x".into())),
+                ("other".to_string(), ColValue::String("Keep".into())),
+            ])
+        };
+        let row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Update,
+            Some(cols("123-45-6789")),
+            Some(cols("987-65-4321")),
+        );
+
+        // rows of different tables in one batch
+        let tb_2_row_data = RowData::new(
+            "db_1".into(),
+            "tb_2".into(),
+            RowType::Insert,
+            None,
+            Some(cols("123-45-6789")),
+        );
+
+        let data = transformer.process(vec![row_data, tb_2_row_data]).unwrap();
+        let before = data[0].before.as_ref().unwrap();
+        let after = data[0].after.as_ref().unwrap();
+        // id cols and cols without transforms are untouched
+        assert_eq!(before["id"], ColValue::LongLong(1));
+        assert_eq!(after["id"], ColValue::LongLong(1));
+        assert_eq!(after["other"], ColValue::String("Keep".into()));
+        assert_eq!(before["ssn"], ColValue::String("*******6789".into()));
+        assert_eq!(after["ssn"], ColValue::String("*******4321".into()));
+        assert_eq!(after["email"], ColValue::String("a@example.com".into()));
+        assert_eq!(after["name"], ColValue::String("a".into()));
+        assert_eq!(after["code"], ColValue::String("AB".into()));
+        assert_eq!(
+            after["pwd"],
+            ColValue::String("900150983cd24fb0d6963f7d28e17f72".into())
+        );
+        assert_eq!(after["memo"], ColValue::String("n/a".into()));
+        assert_eq!(after["note"], ColValue::None);
+
+        let after = data[1].after.as_ref().unwrap();
+        assert_eq!(after["ssn"], ColValue::String("***********".into()));
+        assert_eq!(after["email"], ColValue::String("A@Example.COM".into()));
+    }
+
+    #[test]
+    fn test_from_config() {
+        assert!(ColumnValueTransformer::from_config("").unwrap().is_none());
+        assert!(
+            ColumnValueTransformer::from_config(r#"json:{"db_1.tb_1.col_1":"reverse"}"#).is_err()
+        );
+        assert!(
+            ColumnValueTransformer::from_config(r#"json:{"db_1.tb_1.col_1":"mask:x"}"#).is_err()
+        );
+        assert!(
+            ColumnValueTransformer::from_config(r#"json:{"db_1.tb_1.col_1":"lower:1"}"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use dt_common::{
+    log_info,
+    meta::{col_value::ColValue, pg::pg_meta_manager::PgMetaManager, row_data::RowData},
+};
+
+const JSON_PREFIX: &str = "json:";
+
+// truncate string/binary values exceeding column limits of the target, e.g. mysql TEXT -> pg VARCHAR(255),
+// otherwise the rows fail to be inserted
+pub struct ColumnValueTruncator {
+    // key: schema.tb.col, value: max length in bytes
+    pub per_col_limits: HashMap<String, usize>,
+    pub truncation_log_path: Option<PathBuf>,
+    // used to fetch limits of target columns if auto_detect_limits=true
+    pub meta_manager: Option<PgMetaManager>,
+    detected_tbs: HashSet<String>,
+    truncation_log: Option<File>,
+}
+
+impl ColumnValueTruncator {
+    pub fn new(
+        per_col_limits: HashMap<String, usize>,
+        truncation_log_path: Option<PathBuf>,
+        meta_manager: Option<PgMetaManager>,
+    ) -> anyhow::Result<Self> {
+        let truncation_log = match &truncation_log_path {
+            Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+            None => None,
+        };
+        Ok(Self {
+            per_col_limits,
+            truncation_log_path,
+            meta_manager,
+            detected_tbs: HashSet::new(),
+            truncation_log,
+        })
+    }
+
+    // truncate_col_limits=json:{"public.tb_1.col_1":255,"public.tb_1.col_2":1024}
+    pub fn parse_col_limits(config_str: &str) -> anyhow::Result<HashMap<String, usize>> {
+        if config_str.trim().is_empty() {
+            return Ok(HashMap::new());
+        }
+        Ok(serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )?)
+    }
+
+    pub async fn process(&mut self, mut data: Vec<RowData>) -> anyhow::Result<Vec<RowData>> {
+        for row_data in data.iter_mut() {
+            self.detect_limits(&row_data.schema, &row_data.tb).await?;
+
+            let mut truncated_cols = Vec::new();
+            for col_values in [&mut row_data.before, &mut row_data.after]
+                .into_iter()
+                .flatten()
+            {
+                for (col, col_value) in col_values.iter_mut() {
+                    let full_col = format!("{}.{}.{}", row_data.schema, row_data.tb, col);
+                    if let Some(limit) = self.per_col_limits.get(&full_col) {
+                        if let Some(origin_len) = Self::truncate(col_value, *limit) {
+                            truncated_cols.push((col.clone(), origin_len, *limit));
+                        }
+                    }
+                }
+            }
+
+            if !truncated_cols.is_empty() {
+                row_data.refresh_data_size();
+                self.log_truncation(row_data, &truncated_cols)?;
+            }
+        }
+        Ok(data)
+    }
+
+    async fn detect_limits(&mut self, schema: &str, tb: &str) -> anyhow::Result<()> {
+        let meta_manager = match &self.meta_manager {
+            Some(meta_manager) => meta_manager,
+            None => return Ok(()),
+        };
+
+        let full_tb = format!("{}.{}", schema, tb);
+        if self.detected_tbs.contains(&full_tb) {
+            return Ok(());
+        }
+
+        // varchar(n) limits characters, use n as the byte limit so truncated values always fit
+        for (col, limit) in meta_manager.get_col_max_lengths(schema, tb).await? {
+            let full_col = format!("{}.{}", full_tb, col);
+            // limits in config take precedence
+            if !self.per_col_limits.contains_key(&full_col) {
+                log_info!("column: {}, detected limit: {}", full_col, limit);
+                self.per_col_limits.insert(full_col, limit);
+            }
+        }
+        self.detected_tbs.insert(full_tb);
+        Ok(())
+    }
+
+    // returns the original length if truncated
+    pub fn truncate(col_value: &mut ColValue, limit: usize) -> Option<usize> {
+        match col_value {
+            ColValue::String(v) if v.len() > limit => {
+                let origin_len = v.len();
+                let mut end = limit;
+                while !v.is_char_boundary(end) {
+                    end -= 1;
+                }
+                v.truncate(end);
+                Some(origin_len)
+            }
+
+            ColValue::Blob(v) if v.len() > limit => {
+                let origin_len = v.len();
+                v.truncate(limit);
+                Some(origin_len)
+            }
+
+            _ => None,
+        }
+    }
+
+    fn log_truncation(
+        &mut self,
+        row_data: &RowData,
+        truncated_cols: &[(String, usize, usize)],
+    ) -> anyhow::Result<()> {
+        if let Some(file) = &mut self.truncation_log {
+            let cols: Vec<String> = truncated_cols
+                .iter()
+                .map(|(col, origin_len, limit)| format!("{}: {} -> {}", col, origin_len, limit))
+                .collect();
+            writeln!(
+                file,
+                "truncated cols: [{}], row: {}",
+                cols.join(", "),
+                row_data
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::col_value::ColValue;
+
+    use super::ColumnValueTruncator;
+
+    #[test]
+    fn test_truncate() {
+        // multi-byte chars are not split
+        let mut col_value = ColValue::String("abc中文".into());
+        assert_eq!(ColumnValueTruncator::truncate(&mut col_value, 5), Some(9));
+        assert_eq!(col_value, ColValue::String("abc".into()));
+
+        let mut col_value = ColValue::String("abc中文".into());
+        assert_eq!(ColumnValueTruncator::truncate(&mut col_value, 6), Some(9));
+        assert_eq!(col_value, ColValue::String("abc中".into()));
+
+        let mut col_value = ColValue::String("abc".into());
+        assert_eq!(ColumnValueTruncator::truncate(&mut col_value, 3), None);
+        assert_eq!(col_value, ColValue::String("abc".into()));
+
+        let mut col_value = ColValue::Blob(vec![1, 2, 3, 4]);
+        assert_eq!(ColumnValueTruncator::truncate(&mut col_value, 2), Some(4));
+        assert_eq!(col_value, ColValue::Blob(vec![1, 2]));
+
+        let mut col_value = ColValue::LongLong(123456);
+        assert_eq!(ColumnValueTruncator::truncate(&mut col_value, 2), None);
+    }
+
+    #[test]
+    fn test_parse_col_limits() {
+        let limits = ColumnValueTruncator::parse_col_limits(
+            r#"json:{"public.tb_1.col_1":255,"public.tb_1.col_2":1024}"#,
+        )
+        .unwrap();
+        assert_eq!(limits.len(), 2);
+        assert_eq!(limits.get("public.tb_1.col_1"), Some(&255));
+        assert_eq!(limits.get("public.tb_1.col_2"), Some(&1024));
+        assert!(ColumnValueTruncator::parse_col_limits("")
+            .unwrap()
+            .is_empty());
+    }
+}
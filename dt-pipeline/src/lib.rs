@@ -1,6 +1,10 @@
 pub mod base_pipeline;
+pub mod column_value_transformer;
+pub mod column_value_truncator;
 pub mod http_server_pipeline;
 pub mod lua_processor;
+pub mod row_filter;
+pub mod time_zone_convertor;
 
 use async_trait::async_trait;
 
@@ -124,7 +124,11 @@ impl LuaProcessor {
             ColValue::Long(v) => mlua::Value::Integer(v as i64),
             ColValue::UnsignedLong(v) => mlua::Value::Integer(v as i64),
             ColValue::LongLong(v) => mlua::Value::Integer(v),
-            ColValue::UnsignedLongLong(v) => mlua::Value::Integer(v as i64),
+            // lua integers are i64, pass values exceeding i64 as strings
+            ColValue::UnsignedLongLong(v) => match i64::try_from(v) {
+                Ok(v) => mlua::Value::Integer(v),
+                Err(_) => v.to_string().into_lua(lua)?,
+            },
             ColValue::Year(v) => mlua::Value::Integer(v as i64),
             ColValue::Bit(v) => mlua::Value::Integer(v as i64),
             ColValue::Set(v) => mlua::Value::Integer(v as i64),
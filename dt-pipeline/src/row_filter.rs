@@ -0,0 +1,368 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use anyhow::bail;
+use dt_common::{
+    error::Error,
+    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+};
+use serde::{Deserialize, Serialize};
+
+const JSON_PREFIX: &str = "json:";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    In,
+}
+
+// a single comparison on a named column, e.g. status = 'active', age > 18, type IN ('a', 'b')
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowPredicate {
+    pub col: String,
+    pub op: CompareOp,
+    pub values: Vec<String>,
+}
+
+// drop rows not matching the predicates of their tables, inserts are evaluated against
+// after images, deletes against before images, so deletes of previously synced rows still
+// propagate. updates are evaluated against both images: a row moving out of the filter
+// becomes a delete of the before image, a row moving into it becomes an insert of the
+// after image
+pub struct RowFilter {
+    // key: (schema, tb)
+    pub predicates: HashMap<(String, String), RowPredicate>,
+}
+
+impl RowFilter {
+    // row_filters=json:[{"db":"db_1","tb":"tb_1","predicate":"status = 'active'"}]
+    pub fn from_config(config_str: &str) -> anyhow::Result<Option<Self>> {
+        if config_str.trim().is_empty() {
+            return Ok(None);
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct Predicate {
+            db: String,
+            tb: String,
+            predicate: String,
+        }
+        let config: Vec<Predicate> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX))?;
+        let mut predicates = HashMap::new();
+        for i in config {
+            let predicate = RowPredicate::parse(&i.predicate)?;
+            predicates.insert((i.db, i.tb), predicate);
+        }
+        Ok(Some(Self { predicates }))
+    }
+
+    // returns kept rows and the count of filtered rows
+    pub fn process(&self, data: Vec<RowData>) -> (Vec<RowData>, usize) {
+        let count = data.len();
+        let data: Vec<RowData> = data.into_iter().filter_map(|i| self.filter(i)).collect();
+        let filtered_count = count - data.len();
+        (data, filtered_count)
+    }
+
+    // returns None if the row is dropped, updates may be converted to deletes / inserts
+    pub fn filter(&self, row_data: RowData) -> Option<RowData> {
+        let predicate = match self
+            .predicates
+            .get(&(row_data.schema.clone(), row_data.tb.clone()))
+        {
+            Some(predicate) => predicate,
+            None => return Some(row_data),
+        };
+
+        match row_data.row_type {
+            RowType::Insert => Self::matches(predicate, &row_data.after).then_some(row_data),
+            RowType::Delete => Self::matches(predicate, &row_data.before).then_some(row_data),
+            RowType::Update => {
+                let before_matched = Self::matches(predicate, &row_data.before);
+                let after_matched = Self::matches(predicate, &row_data.after);
+                match (before_matched, after_matched) {
+                    (true, true) => Some(row_data),
+                    (true, false) => Some(row_data.split_update_row_data().0),
+                    (false, true) => Some(row_data.split_update_row_data().1),
+                    (false, false) => None,
+                }
+            }
+        }
+    }
+
+    fn matches(predicate: &RowPredicate, col_values: &Option<HashMap<String, ColValue>>) -> bool {
+        match col_values.as_ref().and_then(|v| v.get(&predicate.col)) {
+            Some(col_value) => predicate.evaluate(col_value),
+            // rows without the column are kept, e.g. columns removed by ignore_cols
+            None => true,
+        }
+    }
+}
+
+impl RowPredicate {
+    pub fn parse(predicate: &str) -> anyhow::Result<Self> {
+        let invalid = || {
+            Error::ConfigError(format!(
+                "config [filter] row_filters, invalid predicate: {}",
+                predicate
+            ))
+        };
+
+        let (col, rest) = match Self::split_col(predicate.trim()) {
+            Some(result) => result,
+            None => bail! {invalid()},
+        };
+
+        let rest = rest.trim_start();
+        let (op, rest) =
+            if let Some(rest) = rest.strip_prefix("!=").or_else(|| rest.strip_prefix("<>")) {
+                (CompareOp::Ne, rest)
+            } else if let Some(rest) = rest.strip_prefix('=') {
+                (CompareOp::Eq, rest)
+            } else if let Some(rest) = rest.strip_prefix('>') {
+                (CompareOp::Gt, rest)
+            } else if let Some(rest) = rest.strip_prefix('<') {
+                (CompareOp::Lt, rest)
+            } else if let Some(rest) = Self::strip_in(rest) {
+                (CompareOp::In, rest)
+            } else {
+                bail! {invalid()}
+            };
+
+        let rest = rest.trim();
+        let values = if op == CompareOp::In {
+            match rest.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+                Some(list) => Self::split_list(list)
+                    .iter()
+                    .map(|v| Self::parse_literal(v))
+                    .collect::<Option<Vec<String>>>(),
+                None => None,
+            }
+        } else {
+            Self::parse_literal(rest).map(|v| vec![v])
+        };
+
+        match values {
+            Some(values) if !values.is_empty() => Ok(Self { col, op, values }),
+            _ => bail! {invalid()},
+        }
+    }
+
+    // NULL values never match, same as sql
+    pub fn evaluate(&self, col_value: &ColValue) -> bool {
+        let value = match col_value.to_option_string() {
+            Some(value) => value,
+            None => return false,
+        };
+
+        match self.op {
+            CompareOp::Eq => Self::compare(&value, &self.values[0]) == Ordering::Equal,
+            CompareOp::Ne => Self::compare(&value, &self.values[0]) != Ordering::Equal,
+            CompareOp::Gt => Self::compare(&value, &self.values[0]) == Ordering::Greater,
+            CompareOp::Lt => Self::compare(&value, &self.values[0]) == Ordering::Less,
+            CompareOp::In => self
+                .values
+                .iter()
+                .any(|v| Self::compare(&value, v) == Ordering::Equal),
+        }
+    }
+
+    // compare as numbers if both are numeric, otherwise as strings
+    fn compare(value: &str, literal: &str) -> Ordering {
+        if let (Ok(a), Ok(b)) = (value.parse::<f64>(), literal.parse::<f64>()) {
+            if let Some(ordering) = a.partial_cmp(&b) {
+                return ordering;
+            }
+        }
+        value.cmp(literal)
+    }
+
+    // IN / in followed by a list
+    fn strip_in(rest: &str) -> Option<&str> {
+        if !rest.get(..2)?.eq_ignore_ascii_case("in") {
+            return None;
+        }
+        let rest = &rest[2..];
+        if rest.trim_start().starts_with('(') {
+            Some(rest)
+        } else {
+            None
+        }
+    }
+
+    // col may be quoted by ` or "
+    fn split_col(predicate: &str) -> Option<(String, &str)> {
+        for quote in ['`', '"'] {
+            if let Some(rest) = predicate.strip_prefix(quote) {
+                let end = rest.find(quote)?;
+                return Some((rest[..end].to_string(), &rest[end + 1..]));
+            }
+        }
+
+        let end = predicate.find(|c: char| c.is_whitespace() || "=!<>(".contains(c))?;
+        if end == 0 {
+            return None;
+        }
+        Some((predicate[..end].to_string(), &predicate[end..]))
+    }
+
+    // split by ',' outside of single quotes
+    fn split_list(list: &str) -> Vec<String> {
+        let mut items = Vec::new();
+        let mut item = String::new();
+        let mut in_quote = false;
+        for c in list.chars() {
+            match c {
+                '\'' => {
+                    in_quote = !in_quote;
+                    item.push(c);
+                }
+                ',' if !in_quote => items.push(std::mem::take(&mut item)),
+                _ => item.push(c),
+            }
+        }
+        items.push(item);
+        items
+    }
+
+    // 'active', 'it''s', 18, 1.5
+    fn parse_literal(literal: &str) -> Option<String> {
+        let literal = literal.trim();
+        if literal.is_empty() {
+            return None;
+        }
+        if let Some(quoted) = literal.strip_prefix('\'') {
+            let inner = quoted.strip_suffix('\'')?;
+            return Some(inner.replace("''", "'"));
+        }
+        if literal.contains(char::is_whitespace) {
+            return None;
+        }
+        Some(literal.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let parse = |s: &str| RowPredicate::parse(s).unwrap();
+        let predicate = parse("status = 'active'");
+        assert_eq!(predicate.col, "status");
+        assert_eq!(predicate.op, CompareOp::Eq);
+        assert_eq!(predicate.values, vec!["active"]);
+
+        let predicate = parse("`age`>18");
+        assert_eq!(predicate.col, "age");
+        assert_eq!(predicate.op, CompareOp::Gt);
+        assert_eq!(predicate.values, vec!["18"]);
+
+        assert_eq!(parse("\"age\" < 1.5").op, CompareOp::Lt);
+        assert_eq!(parse("name != 'it''s'").values, vec!["it's"]);
+        assert_eq!(parse("name <> 'a'").op, CompareOp::Ne);
+
+        let predicate = parse("type in ('a', 'b,c', 3)");
+        assert_eq!(predicate.col, "type");
+        assert_eq!(predicate.op, CompareOp::In);
+        assert_eq!(predicate.values, vec!["a", "b,c", "3"]);
+        assert_eq!(parse("type IN('a')").values, vec!["a"]);
+
+        for invalid in [
+            "",
+            "status",
+            "status =",
+            "status = 'active",
+            "status like 'a%'",
+            "status = a b",
+            "type in 'a'",
+            "= 1",
+        ] {
+            assert!(RowPredicate::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let predicate = RowPredicate::parse("age > 9").unwrap();
+        // compared as numbers, not strings
+        assert!(predicate.evaluate(&ColValue::Long(10)));
+        assert!(!predicate.evaluate(&ColValue::Long(9)));
+        assert!(!predicate.evaluate(&ColValue::None));
+
+        let predicate = RowPredicate::parse("status != 'deleted'").unwrap();
+        assert!(predicate.evaluate(&ColValue::String("active".into())));
+        assert!(!predicate.evaluate(&ColValue::String("deleted".into())));
+        assert!(!predicate.evaluate(&ColValue::None));
+
+        let predicate = RowPredicate::parse("type in (1, 2)").unwrap();
+        assert!(predicate.evaluate(&ColValue::Tiny(2)));
+        assert!(!predicate.evaluate(&ColValue::Tiny(3)));
+
+        let predicate = RowPredicate::parse("name < 'b'").unwrap();
+        assert!(predicate.evaluate(&ColValue::String("a".into())));
+        assert!(!predicate.evaluate(&ColValue::String("c".into())));
+    }
+
+    #[test]
+    fn test_process() {
+        let row_filter = RowFilter::from_config(
+            r#"json:[{"db":"db_1","tb":"tb_1","predicate":"status = 'active'"}]"#,
+        )
+        .unwrap()
+        .unwrap();
+
+        let cols = |status: &str| {
+            Some(HashMap::from([
+                ("id".to_string(), ColValue::Long(1)),
+                ("status".to_string(), ColValue::String(status.into())),
+            ]))
+        };
+        let row = |tb: &str, row_type: RowType, before: &str, after: &str| {
+            let before = if before.is_empty() {
+                None
+            } else {
+                cols(before)
+            };
+            let after = if after.is_empty() { None } else { cols(after) };
+            RowData::new("db_1".into(), tb.into(), row_type, before, after)
+        };
+
+        let data = vec![
+            row("tb_1", RowType::Insert, "", "active"),
+            row("tb_1", RowType::Insert, "", "inactive"),
+            row("tb_1", RowType::Update, "active", "active"),
+            row("tb_1", RowType::Update, "inactive", "inactive"),
+            // moved into the filter, becomes an insert of the after image
+            row("tb_1", RowType::Update, "inactive", "active"),
+            // moved out of the filter, becomes a delete of the before image
+            row("tb_1", RowType::Update, "active", "inactive"),
+            // deletes are evaluated against before images
+            row("tb_1", RowType::Delete, "active", ""),
+            row("tb_1", RowType::Delete, "inactive", ""),
+            // tables without predicates are kept
+            row("tb_2", RowType::Insert, "", "inactive"),
+        ];
+
+        let (data, filtered_count) = row_filter.process(data);
+        assert_eq!(filtered_count, 3);
+        assert_eq!(data.len(), 6);
+        assert_eq!(data[0].row_type, RowType::Insert);
+        assert_eq!(data[1].row_type, RowType::Update);
+
+        assert_eq!(data[2].row_type, RowType::Insert);
+        assert!(data[2].before.is_none());
+        assert_eq!(data[2].after, cols("active"));
+
+        assert_eq!(data[3].row_type, RowType::Delete);
+        assert_eq!(data[3].before, cols("active"));
+        assert!(data[3].after.is_none());
+
+        assert_eq!(data[4].row_type, RowType::Delete);
+        assert_eq!(data[5].tb, "tb_2");
+    }
+}
@@ -0,0 +1,190 @@
+use chrono::FixedOffset;
+use dt_common::{
+    meta::{col_value::ColValue, row_data::RowData},
+    utils::time_util::TimeUtil,
+};
+
+const NULL: &str = "null";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZeroDateValue {
+    Keep,
+    Null,
+    Sentinel(String),
+}
+
+// TIMESTAMP values are extracted in source_time_zone (UTC for mysql, sessions of extractors
+// always use time_zone='+00:00'), convert them into target_time_zone for sinkers writing them
+// as plain text, e.g. kafka, starrocks. pg timestamptz values carry their own offsets, which
+// are used instead of source_time_zone. DATETIME values are wall-clock and kept as is.
+// zero dates (0000-00-00 00:00:00) are not valid in most targets, replace them with NULL or a sentinel
+pub struct TimeZoneConvertor {
+    pub source_offset: FixedOffset,
+    pub target_offset: FixedOffset,
+    pub zero_date_value: ZeroDateValue,
+}
+
+impl TimeZoneConvertor {
+    pub fn new(
+        source_time_zone: &str,
+        target_time_zone: &str,
+        zero_date_value: &str,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            source_offset: TimeUtil::parse_time_zone(source_time_zone)?,
+            target_offset: TimeUtil::parse_time_zone(target_time_zone)?,
+            zero_date_value: Self::parse_zero_date_value(zero_date_value),
+        })
+    }
+
+    // zero_date_value=null
+    // zero_date_value=1970-01-01 00:00:01
+    pub fn parse_zero_date_value(config_str: &str) -> ZeroDateValue {
+        let config_str = config_str.trim();
+        if config_str.is_empty() {
+            ZeroDateValue::Keep
+        } else if config_str.eq_ignore_ascii_case(NULL) {
+            ZeroDateValue::Null
+        } else {
+            ZeroDateValue::Sentinel(config_str.to_string())
+        }
+    }
+
+    pub fn is_noop(&self) -> bool {
+        self.source_offset == self.target_offset && self.zero_date_value == ZeroDateValue::Keep
+    }
+
+    pub fn process(&self, mut data: Vec<RowData>) -> anyhow::Result<Vec<RowData>> {
+        for row_data in data.iter_mut() {
+            let mut converted = false;
+            for col_values in [&mut row_data.before, &mut row_data.after]
+                .into_iter()
+                .flatten()
+            {
+                for col_value in col_values.values_mut() {
+                    converted |= self.convert(col_value)?;
+                }
+            }
+
+            if converted {
+                row_data.refresh_data_size();
+            }
+        }
+        Ok(data)
+    }
+
+    // returns true if the value is changed
+    pub fn convert(&self, col_value: &mut ColValue) -> anyhow::Result<bool> {
+        let is_zero_date = matches!(
+            col_value,
+            ColValue::Timestamp(v) | ColValue::DateTime(v) | ColValue::Date(v)
+                if TimeUtil::is_zero_date(v)
+        );
+        if is_zero_date {
+            match &self.zero_date_value {
+                ZeroDateValue::Keep => return Ok(false),
+                ZeroDateValue::Null => *col_value = ColValue::None,
+                ZeroDateValue::Sentinel(sentinel) => {
+                    if let ColValue::Timestamp(v) | ColValue::DateTime(v) | ColValue::Date(v) =
+                        col_value
+                    {
+                        *v = sentinel.clone();
+                    }
+                }
+            }
+            return Ok(true);
+        }
+
+        if self.source_offset == self.target_offset {
+            return Ok(false);
+        }
+        if let ColValue::Timestamp(v) = col_value {
+            // pg special values
+            if v == "infinity" || v == "-infinity" {
+                return Ok(false);
+            }
+            *v = TimeUtil::convert_time_zone(v, &self.source_offset, &self.target_offset)?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::col_value::ColValue;
+
+    use super::{TimeZoneConvertor, ZeroDateValue};
+
+    #[test]
+    fn test_convert() {
+        let convertor = TimeZoneConvertor::new("", "+08:00", "").unwrap();
+        let mut col_value = ColValue::Timestamp("2024-01-01 20:00:00.123".into());
+        assert!(convertor.convert(&mut col_value).unwrap());
+        assert_eq!(
+            col_value,
+            ColValue::Timestamp("2024-01-02 04:00:00.123".into())
+        );
+
+        // DATETIME is wall-clock
+        let mut col_value = ColValue::DateTime("2024-01-01 20:00:00".into());
+        assert!(!convertor.convert(&mut col_value).unwrap());
+        assert_eq!(col_value, ColValue::DateTime("2024-01-01 20:00:00".into()));
+
+        // zero dates are kept by default
+        let mut col_value = ColValue::Timestamp("0000-00-00 00:00:00".into());
+        assert!(!convertor.convert(&mut col_value).unwrap());
+        assert_eq!(col_value, ColValue::Timestamp("0000-00-00 00:00:00".into()));
+    }
+
+    #[test]
+    fn test_convert_pg_timestamptz() {
+        // source_time_zone is ignored for values with offsets
+        let convertor = TimeZoneConvertor::new("-05:00", "+08:00", "").unwrap();
+        for (value, expected) in [
+            ("2024-01-01 20:00:00+00", "2024-01-02 04:00:00+08:00"),
+            (
+                "2024-01-01 20:00:00.123+08",
+                "2024-01-01 20:00:00.123+08:00",
+            ),
+            ("2024-01-01 20:00:00-05:30", "2024-01-02 09:30:00+08:00"),
+        ] {
+            let mut col_value = ColValue::Timestamp(value.into());
+            assert!(convertor.convert(&mut col_value).unwrap());
+            assert_eq!(col_value, ColValue::Timestamp(expected.into()));
+        }
+
+        // pg timestamp without time zone is wall-clock
+        let mut col_value = ColValue::DateTime("2024-01-01 20:00:00".into());
+        assert!(!convertor.convert(&mut col_value).unwrap());
+
+        let mut col_value = ColValue::Timestamp("-infinity".into());
+        assert!(!convertor.convert(&mut col_value).unwrap());
+        assert_eq!(col_value, ColValue::Timestamp("-infinity".into()));
+    }
+
+    #[test]
+    fn test_convert_zero_date() {
+        let convertor = TimeZoneConvertor::new("", "", "NULL").unwrap();
+        assert_eq!(convertor.zero_date_value, ZeroDateValue::Null);
+        for mut col_value in [
+            ColValue::Timestamp("0000-00-00 00:00:00".into()),
+            ColValue::DateTime("0000-00-00 00:00:00.000000".into()),
+            ColValue::Date("0000-00-00".into()),
+        ] {
+            assert!(convertor.convert(&mut col_value).unwrap());
+            assert_eq!(col_value, ColValue::None);
+        }
+
+        let convertor = TimeZoneConvertor::new("", "+08:00", "1970-01-01 00:00:01").unwrap();
+        let mut col_value = ColValue::Timestamp("0000-00-00 00:00:00".into());
+        assert!(convertor.convert(&mut col_value).unwrap());
+        // the sentinel is not converted
+        assert_eq!(col_value, ColValue::Timestamp("1970-01-01 00:00:01".into()));
+
+        assert!(TimeZoneConvertor::new("UTC", "+00:00", "")
+            .unwrap()
+            .is_noop());
+        assert!(TimeZoneConvertor::new("", "Asia/Shanghai", "").is_err());
+    }
+}
@@ -2,9 +2,10 @@ use std::vec;
 
 use anyhow::bail;
 use dt_common::{
-    config::{config_enums::DbType, task_config::TaskConfig},
+    config::{config_enums::DbType, extractor_config::ExtractorConfig, task_config::TaskConfig},
     rdb_filter::RdbFilter,
 };
+use dt_connector::rdb_router::RdbRouter;
 
 use crate::{
     config::precheck_config::PrecheckConfig,
@@ -56,6 +57,13 @@ impl PrecheckerBuilder {
                 filter_config: self.task_config.filter.clone(),
                 precheck_config: self.precheck_config.clone(),
                 is_source,
+                gtid_enabled: matches!(
+                    self.task_config.extractor,
+                    ExtractorConfig::MysqlCdc {
+                        gtid_enabled: true,
+                        ..
+                    }
+                ),
                 fetcher: MysqlFetcher {
                     pool: None,
                     url,
@@ -139,16 +147,73 @@ impl PrecheckerBuilder {
         if self.precheck_config.do_cdc {
             println!("[*]begin to check the cdc setting");
             check_results.push(source_checker.check_cdc_supported().await);
+            if let Some(check_result) = source_checker.check_gtid_mode().await.transpose() {
+                check_results.push(check_result);
+            }
+            match source_checker.check_cdc_configurations().await {
+                Ok(results) => check_results.extend(results.into_iter().map(Ok)),
+                Err(e) => check_results.push(Err(e)),
+            }
         }
 
         println!("[*]begin to check the if the structs is existed or not");
         check_results.push(source_checker.check_struct_existed_or_not().await);
         check_results.push(sink_checker.check_struct_existed_or_not().await);
 
+        println!("[*]begin to check the target tables");
+        let router = RdbRouter::from_config(
+            &self.task_config.router,
+            &self.task_config.sinker_basic.db_type,
+        )?;
+        match source_checker.fetch_table_cols().await {
+            Ok(src_tables) => {
+                if let Some(check_result) = sink_checker
+                    .check_target_tables(&src_tables, &router)
+                    .await
+                    .transpose()
+                {
+                    check_results.push(check_result);
+                }
+            }
+            Err(e) => check_results.push(Err(e)),
+        }
+
         println!("[*]begin to check the database structs");
         check_results.push(source_checker.check_table_structs().await);
         check_results.push(sink_checker.check_table_structs().await);
 
+        println!("[*]begin to check the charsets");
+        match source_checker.fetch_col_charsets().await {
+            Ok(col_charsets) => {
+                if let Some(check_result) = sink_checker
+                    .check_charset(
+                        &router,
+                        &self.task_config.extractor_basic.db_type,
+                        &col_charsets,
+                    )
+                    .await
+                    .transpose()
+                {
+                    check_results.push(check_result);
+                }
+            }
+            Err(e) => check_results.push(Err(e)),
+        }
+
+        println!("[*]begin to check the batch size");
+        match source_checker.fetch_table_widths().await {
+            Ok(table_widths) => {
+                if let Some(check_result) = sink_checker
+                    .check_batch_size(&table_widths, &self.task_config.sinker_basic)
+                    .await
+                    .transpose()
+                {
+                    check_results.push(check_result);
+                }
+            }
+            Err(e) => check_results.push(Err(e)),
+        }
+
         Ok(check_results)
     }
 
@@ -162,7 +227,7 @@ impl PrecheckerBuilder {
                     match check_result {
                         Ok(result) => {
                             result.log();
-                            if !result.is_validate {
+                            if !result.is_validate && !result.is_warning {
                                 error_count += 1;
                             }
                         }
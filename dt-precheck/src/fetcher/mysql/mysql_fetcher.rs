@@ -2,16 +2,25 @@ use std::collections::HashMap;
 
 use anyhow::bail;
 use async_trait::async_trait;
-use dt_common::{error::Error, rdb_filter::RdbFilter};
+use dt_common::{
+    error::Error,
+    meta::mysql::{mysql_col_type::MysqlColType, mysql_meta_manager::MysqlMetaManager},
+    rdb_filter::RdbFilter,
+};
 use dt_task::task_util::TaskUtil;
 use futures::{Stream, TryStreamExt};
 use sqlx::{mysql::MySqlRow, query, MySql, Pool, Row};
 
 use crate::{
     fetcher::traits::Fetcher,
-    meta::database_mode::{Constraint, Database, Schema, Table},
+    meta::database_mode::{
+        ColCategory, ColumnCharset, Constraint, Database, Schema, Table, TableCols, TableWidth,
+    },
 };
 
+// lob columns are counted as 64KB in the worst case row width
+const LOB_WIDTH_BYTES: u64 = 65535;
+
 pub struct MysqlFetcher {
     pub pool: Option<Pool<MySql>>,
     pub url: String,
@@ -198,9 +207,86 @@ impl Fetcher for MysqlFetcher {
 
         Ok(results)
     }
+
+    async fn fetch_table_widths(&mut self) -> anyhow::Result<Vec<TableWidth>> {
+        let mut results: Vec<TableWidth> = vec![];
+        let query_width = format!(
+            "SELECT TABLE_SCHEMA, TABLE_NAME,
+            CAST(SUM(LEAST(IFNULL(CHARACTER_OCTET_LENGTH,
+                CASE WHEN DATA_TYPE IN ('json', 'geometry', 'point', 'linestring', 'polygon', 'multipoint', 'multilinestring', 'multipolygon', 'geometrycollection') THEN {0} ELSE 16 END),
+            {0})) AS UNSIGNED) AS ROW_BYTES,
+            COUNT(*) AS COL_COUNT
+            FROM information_schema.columns
+            WHERE TABLE_SCHEMA NOT IN ({1})
+            GROUP BY TABLE_SCHEMA, TABLE_NAME",
+            LOB_WIDTH_BYTES,
+            Self::get_system_databases()
+                .iter()
+                .map(|d| format!("'{}'", d))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        let rows_result = self.fetch_row(&query_width, "mysql query table widths sql:");
+        match rows_result {
+            Ok(mut rows) => {
+                while let Some(row) = rows.try_next().await.unwrap() {
+                    let (db, tb): (String, String) =
+                        (row.get("TABLE_SCHEMA"), row.get("TABLE_NAME"));
+                    if !self.filter.filter_tb(&db, &tb) {
+                        let row_bytes: u64 = row.get("ROW_BYTES");
+                        let col_count: i64 = row.get("COL_COUNT");
+                        results.push(TableWidth {
+                            database_name: db,
+                            schema_name: String::from(""),
+                            table_name: tb,
+                            row_bytes: row_bytes as usize,
+                            col_count: col_count as usize,
+                        })
+                    }
+                }
+            }
+            Err(e) => bail! {e},
+        }
+
+        Ok(results)
+    }
 }
 
 impl MysqlFetcher {
+    // charsets and collations of string columns, missing tables are skipped
+    pub async fn fetch_col_charsets(&self, tables: &[Table]) -> anyhow::Result<Vec<ColumnCharset>> {
+        let pool = match &self.pool {
+            Some(pool) => pool,
+            None => bail! {Error::from(sqlx::Error::PoolClosed)},
+        };
+        let sql = "SELECT COLUMN_NAME AS column_name, CHARACTER_SET_NAME AS charset, COLLATION_NAME AS collation
+            FROM information_schema.columns
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? AND CHARACTER_SET_NAME IS NOT NULL";
+
+        let mut results: Vec<ColumnCharset> = vec![];
+        for table in tables.iter() {
+            let mut rows = query(sql)
+                .bind(&table.database_name)
+                .bind(&table.table_name)
+                .fetch(pool);
+            while let Some(row) = rows.try_next().await? {
+                let column_name: String = row.try_get("column_name")?;
+                results.push(ColumnCharset {
+                    database_name: table.database_name.clone(),
+                    table_name: table.table_name.clone(),
+                    // the same as col names in tb_meta
+                    column_name: column_name.to_lowercase(),
+                    charset: row.try_get("charset")?,
+                    collation: row
+                        .try_get::<Option<String>, _>("collation")?
+                        .unwrap_or_default(),
+                });
+            }
+        }
+        Ok(results)
+    }
+
     async fn fetch_all(&self, sql: String, mut sql_msg: &str) -> Result<Vec<MySqlRow>, Error> {
         let mysql_pool = match &self.pool {
             Some(pool) => pool,
@@ -232,6 +318,83 @@ impl MysqlFetcher {
         }
     }
 
+    // columns of tables, tables failed to fetch metadata (E.g. not existed) are skipped
+    pub async fn fetch_table_cols(&self, tables: &[Table]) -> anyhow::Result<Vec<TableCols>> {
+        let mut meta_manager = self.create_meta_manager().await?;
+
+        let mut results: Vec<TableCols> = vec![];
+        for table in tables.iter() {
+            let tb_meta = match meta_manager
+                .get_tb_meta(&table.database_name, &table.table_name)
+                .await
+            {
+                Ok(tb_meta) => tb_meta,
+                Err(_) => continue,
+            };
+            let cols = tb_meta
+                .basic
+                .cols
+                .iter()
+                .map(|col| {
+                    let category = match tb_meta.col_type_map.get(col) {
+                        Some(col_type) => Self::get_col_category(col_type),
+                        None => ColCategory::Other,
+                    };
+                    (col.clone(), category)
+                })
+                .collect();
+            results.push(TableCols {
+                schema_name: table.database_name.clone(),
+                table_name: table.table_name.clone(),
+                cols,
+            });
+        }
+        Ok(results)
+    }
+
+    async fn create_meta_manager(&self) -> anyhow::Result<MysqlMetaManager> {
+        let pool = match &self.pool {
+            Some(pool) => pool.clone(),
+            None => bail! {Error::from(sqlx::Error::PoolClosed)},
+        };
+        // not closed since the pool is shared with the fetcher
+        MysqlMetaManager::new(pool).await
+    }
+
+    fn get_col_category(col_type: &MysqlColType) -> ColCategory {
+        match col_type {
+            MysqlColType::TinyInt { .. }
+            | MysqlColType::SmallInt { .. }
+            | MysqlColType::MediumInt { .. }
+            | MysqlColType::Int { .. }
+            | MysqlColType::BigInt { .. }
+            | MysqlColType::Float
+            | MysqlColType::Double
+            | MysqlColType::Decimal { .. } => ColCategory::Numeric,
+            MysqlColType::Char { .. }
+            | MysqlColType::Varchar { .. }
+            | MysqlColType::TinyText { .. }
+            | MysqlColType::MediumText { .. }
+            | MysqlColType::Text { .. }
+            | MysqlColType::LongText { .. }
+            | MysqlColType::Set { .. }
+            | MysqlColType::Enum { .. } => ColCategory::String,
+            MysqlColType::Time { .. }
+            | MysqlColType::Date { .. }
+            | MysqlColType::DateTime { .. }
+            | MysqlColType::Timestamp { .. }
+            | MysqlColType::Year => ColCategory::Temporal,
+            MysqlColType::Binary { .. }
+            | MysqlColType::VarBinary { .. }
+            | MysqlColType::TinyBlob
+            | MysqlColType::MediumBlob
+            | MysqlColType::LongBlob
+            | MysqlColType::Blob => ColCategory::Binary,
+            MysqlColType::Json => ColCategory::Json,
+            _ => ColCategory::Other,
+        }
+    }
+
     fn get_system_databases() -> Vec<String> {
         let dbs = ["mysql", "performance_schema", "sys", "information_schema"];
         dbs.iter().map(|d| d.to_string()).collect()
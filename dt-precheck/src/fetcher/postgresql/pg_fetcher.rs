@@ -2,16 +2,27 @@ use std::collections::HashMap;
 
 use anyhow::bail;
 use async_trait::async_trait;
-use dt_common::{error::Error, rdb_filter::RdbFilter};
+use dt_common::{
+    error::Error,
+    meta::pg::{
+        pg_col_type::PgColType, pg_meta_manager::PgMetaManager, pg_value_type::PgValueType,
+    },
+    rdb_filter::RdbFilter,
+};
 use dt_task::task_util::TaskUtil;
 use futures::{Stream, TryStreamExt};
 use sqlx::{postgres::PgRow, query, Pool, Postgres, Row};
 
 use crate::{
     fetcher::traits::Fetcher,
-    meta::database_mode::{Constraint, Database, Schema, Table},
+    meta::database_mode::{
+        ColCategory, Constraint, Database, Schema, Table, TableCols, TableWidth,
+    },
 };
 
+// lob columns are counted as 64KB in the worst case row width
+const LOB_WIDTH_BYTES: i64 = 65535;
+
 pub struct PgFetcher {
     pub pool: Option<Pool<Postgres>>,
     pub url: String,
@@ -193,6 +204,48 @@ impl Fetcher for PgFetcher {
 
         Ok(constraints)
     }
+
+    async fn fetch_table_widths(&mut self) -> anyhow::Result<Vec<TableWidth>> {
+        let mut widths: Vec<TableWidth> = vec![];
+        let width_sql = format!(
+            "select table_catalog, table_schema, table_name,
+            sum(least(coalesce(character_octet_length,
+                case when data_type in ('text', 'bytea', 'json', 'jsonb', 'xml', 'ARRAY', 'USER-DEFINED') then {0} else 16 end),
+            {0}))::bigint as row_bytes,
+            count(*)::bigint as col_count
+            from information_schema.columns
+            where table_schema not in ('pg_catalog', 'information_schema')
+            group by table_catalog, table_schema, table_name",
+            LOB_WIDTH_BYTES
+        );
+
+        let rows_result = self.fetch_row(&width_sql, "pg query table widths sql");
+        match rows_result {
+            Ok(mut rows) => {
+                while let Some(row) = rows.try_next().await.unwrap() {
+                    let (database_name, schema_name, table_name): (String, String, String) = (
+                        row.get("table_catalog"),
+                        row.get("table_schema"),
+                        row.get("table_name"),
+                    );
+                    if !self.filter.filter_tb(&schema_name, &table_name) {
+                        let (row_bytes, col_count): (i64, i64) =
+                            (row.get("row_bytes"), row.get("col_count"));
+                        widths.push(TableWidth {
+                            database_name,
+                            schema_name,
+                            table_name,
+                            row_bytes: row_bytes as usize,
+                            col_count: col_count as usize,
+                        })
+                    }
+                }
+            }
+            Err(e) => bail! {e},
+        }
+
+        Ok(widths)
+    }
 }
 
 impl PgFetcher {
@@ -227,6 +280,85 @@ impl PgFetcher {
         }
     }
 
+    // columns of tables, tables failed to fetch metadata (E.g. not existed) are skipped
+    pub async fn fetch_table_cols(&self, tables: &[Table]) -> anyhow::Result<Vec<TableCols>> {
+        let pool = match &self.pool {
+            Some(pool) => pool.clone(),
+            None => bail! {Error::from(sqlx::Error::PoolClosed)},
+        };
+        // not closed since the pool is shared with the fetcher
+        let mut meta_manager = PgMetaManager::new(pool).await?;
+
+        let mut results: Vec<TableCols> = vec![];
+        for table in tables.iter() {
+            let tb_meta = match meta_manager
+                .get_tb_meta(&table.schema_name, &table.table_name)
+                .await
+            {
+                Ok(tb_meta) => tb_meta,
+                Err(_) => continue,
+            };
+            let cols = tb_meta
+                .basic
+                .cols
+                .iter()
+                .map(|col| {
+                    let category = match tb_meta.col_type_map.get(col) {
+                        Some(col_type) => Self::get_col_category(col_type),
+                        None => ColCategory::Other,
+                    };
+                    (col.clone(), category)
+                })
+                .collect();
+            results.push(TableCols {
+                schema_name: table.schema_name.clone(),
+                table_name: table.table_name.clone(),
+                cols,
+            });
+        }
+        Ok(results)
+    }
+
+    fn get_col_category(col_type: &PgColType) -> ColCategory {
+        if col_type.is_array() {
+            return ColCategory::Other;
+        }
+        match col_type.base_value_type() {
+            PgValueType::Float32
+            | PgValueType::Float64
+            | PgValueType::Int16
+            | PgValueType::Int32
+            | PgValueType::Int64
+            | PgValueType::Boolean
+            | PgValueType::Numeric => ColCategory::Numeric,
+            PgValueType::Char | PgValueType::String | PgValueType::UUID => ColCategory::String,
+            PgValueType::Timestamp
+            | PgValueType::TimestampTZ
+            | PgValueType::Date
+            | PgValueType::Time
+            | PgValueType::TimeTZ => ColCategory::Temporal,
+            PgValueType::Bytes => ColCategory::Binary,
+            PgValueType::JSON => ColCategory::Json,
+            _ => ColCategory::Other,
+        }
+    }
+
+    pub async fn fetch_database_encoding(&self) -> anyhow::Result<String> {
+        let sql = "select pg_encoding_to_char(encoding)::varchar as encoding from pg_database where datname = current_database()".to_string();
+        let mut encoding = String::new();
+
+        let result = self.fetch_all(sql, "pg query database encoding").await;
+        match result {
+            Ok(rows) => {
+                if let Some(row) = rows.first() {
+                    encoding = row.get("encoding");
+                }
+            }
+            Err(e) => bail! {e},
+        }
+        Ok(encoding)
+    }
+
     pub async fn fetch_slot_names(&self) -> anyhow::Result<Vec<String>> {
         let mut slots: Vec<String> = vec![];
         let slot_query = "select slot_name from pg_catalog.pg_replication_slots".to_string();
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use dt_common::{rdb_filter::RdbFilter, utils::redis_util::RedisUtil};
 
@@ -22,6 +24,25 @@ impl Fetcher for RedisFetcher {
         let version = RedisUtil::get_redis_version(conn)?;
         Ok(version.to_string())
     }
+
+    async fn fetch_configuration(
+        &mut self,
+        config_keys: Vec<String>,
+    ) -> anyhow::Result<HashMap<String, String>> {
+        let conn = self.conn.as_mut().unwrap();
+        let mut result_map: HashMap<String, String> = HashMap::new();
+        for key in config_keys {
+            // CONFIG GET returns [name, value] pairs, empty if the key is unknown
+            let value = RedisUtil::send_cmd(conn, &["CONFIG", "GET", &key])?;
+            let results = RedisUtil::parse_result_as_string(value)?;
+            let value = results
+                .chunks(2)
+                .find(|pair| pair.len() == 2 && pair[0].eq_ignore_ascii_case(&key))
+                .map_or(String::new(), |pair| pair[1].clone());
+            result_map.insert(key, value);
+        }
+        Ok(result_map)
+    }
 }
 
 impl RedisFetcher {}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use async_trait::async_trait;
 
-use crate::meta::database_mode::{Constraint, Database, Schema, Table};
+use crate::meta::database_mode::{Constraint, Database, Schema, Table, TableWidth};
 
 #[async_trait]
 pub trait Fetcher {
@@ -32,4 +32,8 @@ pub trait Fetcher {
     async fn fetch_constraints(&mut self) -> anyhow::Result<Vec<Constraint>> {
         Ok(vec![])
     }
+
+    async fn fetch_table_widths(&mut self) -> anyhow::Result<Vec<TableWidth>> {
+        Ok(vec![])
+    }
 }
@@ -1,4 +1,5 @@
 use dt_common::config::task_config::TaskConfig;
+use dt_task::task_util::TaskUtil;
 
 use crate::{
     builder::prechecker_builder::PrecheckerBuilder, config::task_config::PrecheckTaskConfig,
@@ -11,7 +12,10 @@ pub mod meta;
 pub mod prechecker;
 
 pub async fn do_precheck(config: &str) {
-    let task_config = TaskConfig::new(config).unwrap();
+    let mut task_config = TaskConfig::new(config).unwrap();
+    TaskUtil::resolve_credentials(&mut task_config)
+        .await
+        .unwrap();
     let precheck_config = PrecheckTaskConfig::new(config).unwrap();
 
     let checker_connector = PrecheckerBuilder::build(precheck_config.precheck, task_config);
@@ -7,5 +7,11 @@ pub enum CheckItem {
     CheckAccountPermission,
     CheckIfDatabaseSupportCdc,
     CheckIfStructExisted,
+    CheckIfTargetTableMatched,
     CheckIfTableStructSupported,
+    CheckCharsetCompatible,
+    CheckGtidMode,
+    CheckBatchSize,
+    CheckReplBacklog,
+    CheckMaxmemoryPolicy,
 }
@@ -10,6 +10,8 @@ pub struct CheckResult {
     pub error_msg: String,
     pub is_source: bool,
     pub advise_msg: String,
+    // a failed warning does not fail the precheck
+    pub is_warning: bool,
 }
 
 impl CheckResult {
@@ -21,6 +23,7 @@ impl CheckResult {
             error_msg: String::from(""),
             is_source,
             advise_msg: String::from(""),
+            is_warning: false,
         }
     }
 
@@ -66,6 +69,13 @@ impl CheckResult {
                 );
                 advise_msg = "manually created the missing struct.".to_string();
             }
+            CheckItem::CheckIfTargetTableMatched => {
+                check_desc = format!(
+                    "check whether the routed tables of the {} database exist and contain all source columns with compatible types.",
+                    source_or_sink
+                );
+                advise_msg = "(1)create the missing tables and columns in target, or set [precheck] do_struct_init=true. (2)check [router] settings. (3)alter the mismatched target columns to compatible types.".to_string();
+            }
             CheckItem::CheckIfTableStructSupported => {
                 check_desc = format!(
                     "check whether the data structure of the {} database to be migrated supports",
@@ -73,6 +83,47 @@ impl CheckResult {
                 );
                 advise_msg = "no primary key tables and foreign key tables are currently not supported.these tables can be removed from the migration object.".to_string();
             }
+            CheckItem::CheckCharsetCompatible => {
+                check_desc = format!(
+                    "check whether the character sets of the {} database can hold the source data.",
+                    source_or_sink
+                );
+                match db_type {
+                    DbType::Mysql => advise_msg = "alter the mismatched target columns to the source character set or utf8mb4.".to_string(),
+                    DbType::Pg => advise_msg = "recreate the target database with ENCODING 'UTF8'.".to_string(),
+                    _ => {}
+                }
+            }
+            CheckItem::CheckGtidMode => {
+                check_desc = format!(
+                    "check whether gtid is enabled in the {} database for gtid based cdc",
+                    source_or_sink
+                );
+                advise_msg = "(1)SET GLOBAL enforce_gtid_consistency = ON. (2)SET GLOBAL gtid_mode = OFF_PERMISSIVE. (3)SET GLOBAL gtid_mode = ON_PERMISSIVE. (4)wait until SHOW STATUS LIKE 'ONGOING_ANONYMOUS_TRANSACTION_COUNT' is 0. (5)SET GLOBAL gtid_mode = ON. (6)or set [extractor] gtid_enabled=false to use binlog file/position.".to_string();
+            }
+            CheckItem::CheckBatchSize => {
+                check_desc = format!(
+                    "check whether a batch of [sinker] batch_size rows fits in the packet and bind parameter limits of the {} database.",
+                    source_or_sink
+                );
+                advise_msg = "(1)decrease [sinker] batch_size (or max_batch_size if adaptive_batch_size=true) to the recommended one. (2)or set [sinker] max_packet_bytes to split batches by size.".to_string();
+            }
+            CheckItem::CheckReplBacklog => {
+                check_desc = format!(
+                    "check whether the repl-backlog-size of the {} database is large enough for cdc.",
+                    source_or_sink
+                );
+                advise_msg = "CONFIG SET repl-backlog-size to a larger value, the backlog should hold the writes during a reconnection.".to_string();
+            }
+            CheckItem::CheckMaxmemoryPolicy => {
+                check_desc = format!(
+                    "check whether the maxmemory-policy of the {} database does not evict keys.",
+                    source_or_sink
+                );
+                advise_msg =
+                    "CONFIG SET maxmemory-policy noeviction, evicted keys are lost in the sync."
+                        .to_string();
+            }
             CheckItem::CheckDatabaseVersionSupported => {
                 check_desc = format!("check if the {} database version supports.", source_or_sink);
                 let mut advise_version = String::new();
@@ -97,6 +148,7 @@ impl CheckResult {
                 error_msg: err.to_string(),
                 is_source,
                 advise_msg,
+                is_warning: false,
             },
             None => Self {
                 check_type_name: check_item.to_string(),
@@ -105,12 +157,18 @@ impl CheckResult {
                 error_msg: String::from(""),
                 is_source,
                 advise_msg: String::from(""),
+                is_warning: false,
             },
         }
     }
 
+    pub fn into_warning(mut self) -> Self {
+        self.is_warning = true;
+        self
+    }
+
     pub fn log(&self) {
         println!("======================================");
-        println!("[check_type_name]:{} \n[is_validate]:{} \n[check_desc]:{} \n[error_messaeg]:{} \n[advise_message]:{}\n", self.check_type_name, self.is_validate, self.check_desc, self.error_msg, self.advise_msg);
+        println!("[check_type_name]:{} \n[is_validate]:{} \n[is_warning]:{} \n[check_desc]:{} \n[error_messaeg]:{} \n[advise_message]:{}\n", self.check_type_name, self.is_validate, self.is_warning, self.check_desc, self.error_msg, self.advise_msg);
     }
 }
@@ -32,3 +32,37 @@ pub struct Constraint {
     pub constraint_name: String,
     pub constraint_type: String,
 }
+
+// worst case width of rows of a table, lob columns are counted as 64KB
+pub struct TableWidth {
+    pub database_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    pub row_bytes: usize,
+    pub col_count: usize,
+}
+
+pub struct ColumnCharset {
+    pub database_name: String,
+    pub table_name: String,
+    pub column_name: String,
+    pub charset: String,
+    pub collation: String,
+}
+
+// coarse column type category, to check type compatibility across databases
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColCategory {
+    Numeric,
+    String,
+    Temporal,
+    Binary,
+    Json,
+    Other,
+}
+
+pub struct TableCols {
+    pub schema_name: String,
+    pub table_name: String,
+    pub cols: Vec<(String, ColCategory)>,
+}
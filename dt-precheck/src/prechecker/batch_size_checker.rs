@@ -0,0 +1,79 @@
+use dt_common::config::sinker_config::BasicSinkerConfig;
+use dt_connector::sinker::batch_sizer::BatchSizer;
+
+use crate::meta::database_mode::TableWidth;
+
+// bind parameters of a single statement are limited to u16 by both mysql and pg protocols
+pub const MAX_BIND_PARAMS: usize = 65535;
+
+pub struct BatchSizeChecker {}
+
+impl BatchSizeChecker {
+    // check whether a batch of the widest rows of each table fits in the packet limit and
+    // the bind parameter limit of the target, returns None if all fit, otherwise the error
+    // message with the recommended batch_size
+    pub fn check(
+        table_widths: &[TableWidth],
+        sinker_basic: &BasicSinkerConfig,
+        packet_limit_name: &str,
+        packet_limit: usize,
+    ) -> Option<String> {
+        let batch_size = if sinker_basic.adaptive_batch_size {
+            sinker_basic.max_batch_size.max(sinker_basic.batch_size)
+        } else {
+            sinker_basic.batch_size
+        };
+        // batches are split by max_packet_bytes in sinker
+        let check_packet =
+            sinker_basic.max_packet_bytes == 0 || sinker_basic.max_packet_bytes > packet_limit;
+
+        let mut errs = Vec::new();
+        let mut recommended_batch_size = batch_size;
+        for width in table_widths.iter() {
+            let tb = format!(
+                "`{}`.`{}`",
+                if width.schema_name.is_empty() {
+                    &width.database_name
+                } else {
+                    &width.schema_name
+                },
+                width.table_name
+            );
+
+            let bind_params = batch_size * width.col_count;
+            if bind_params > MAX_BIND_PARAMS {
+                errs.push(format!(
+                    "table: {}, bind parameters: [{}] > limit: [{}]",
+                    tb, bind_params, MAX_BIND_PARAMS
+                ));
+                recommended_batch_size =
+                    recommended_batch_size.min(MAX_BIND_PARAMS / width.col_count.max(1));
+            }
+
+            let packet_bytes =
+                BatchSizer::estimate_batch_bytes(batch_size, width.row_bytes, width.col_count);
+            if check_packet && packet_bytes > packet_limit {
+                errs.push(format!(
+                    "table: {}, estimated packet bytes: [{}] > {}: [{}]",
+                    tb, packet_bytes, packet_limit_name, packet_limit
+                ));
+                let overhead_bytes = BatchSizer::estimate_batch_bytes(0, 0, 0);
+                let row_bytes =
+                    BatchSizer::estimate_batch_bytes(1, width.row_bytes, width.col_count)
+                        - overhead_bytes;
+                recommended_batch_size = recommended_batch_size
+                    .min(packet_limit.saturating_sub(overhead_bytes) / row_bytes.max(1));
+            }
+        }
+
+        if errs.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "batch_size: [{}] may exceed the limits of target, {}. recommended batch_size: [{}]",
+            batch_size,
+            errs.join("; "),
+            recommended_batch_size.max(1)
+        ))
+    }
+}
@@ -1,5 +1,7 @@
+pub mod batch_size_checker;
 pub mod mongo_prechecker;
 pub mod mysql_prechecker;
 pub mod pg_prechecker;
 pub mod redis_prechecker;
+pub mod table_cols_checker;
 pub mod traits;
@@ -1,17 +1,27 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::bail;
 use async_trait::async_trait;
-use dt_common::config::{config_enums::DbType, filter_config::FilterConfig};
+use dt_common::config::{
+    config_enums::DbType, filter_config::FilterConfig, sinker_config::BasicSinkerConfig,
+};
+use dt_connector::rdb_router::RdbRouter;
 use regex::Regex;
 
 use crate::{
     config::precheck_config::PrecheckConfig,
     fetcher::{mysql::mysql_fetcher::MysqlFetcher, traits::Fetcher},
-    meta::{check_item::CheckItem, check_result::CheckResult, db_table_model::DbTable},
+    meta::{
+        check_item::CheckItem,
+        check_result::CheckResult,
+        database_mode::{ColumnCharset, Table, TableCols, TableWidth},
+        db_table_model::DbTable,
+    },
 };
 
-use super::traits::Prechecker;
+use super::{
+    batch_size_checker::BatchSizeChecker, table_cols_checker::TableColsChecker, traits::Prechecker,
+};
 
 const MYSQL_SUPPORT_DB_VERSION_REGEX: &str = r"5\..*|8\..*";
 
@@ -20,6 +30,7 @@ pub struct MySqlPrechecker {
     pub filter_config: FilterConfig,
     pub precheck_config: PrecheckConfig,
     pub is_source: bool,
+    pub gtid_enabled: bool,
 }
 
 #[async_trait]
@@ -139,6 +150,46 @@ impl Prechecker for MySqlPrechecker {
         ))
     }
 
+    async fn check_gtid_mode(&mut self) -> anyhow::Result<Option<CheckResult>> {
+        if !self.is_source || !self.gtid_enabled {
+            return Ok(None);
+        }
+
+        let mut errs: Vec<String> = vec![];
+        let gtid_configs = ["gtid_mode", "enforce_gtid_consistency", "gtid_executed"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>();
+        let configs = self.fetcher.fetch_configuration(gtid_configs).await?;
+        for (k, v) in configs {
+            match k.as_str() {
+                "gtid_mode" | "enforce_gtid_consistency" => {
+                    if v.to_lowercase() != "on" {
+                        errs.push(format!("{} setting:[{}] is not 'on'.", k, v.to_lowercase()));
+                    }
+                }
+                "gtid_executed" => {
+                    if v.trim().is_empty() {
+                        errs.push("gtid_executed is empty, no gtid has been used.".to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let check_error = if errs.is_empty() {
+            None
+        } else {
+            Some(anyhow::Error::msg(errs.join(";")))
+        };
+        Ok(Some(CheckResult::build_with_err(
+            CheckItem::CheckGtidMode,
+            self.is_source,
+            DbType::Mysql,
+            check_error,
+        )))
+    }
+
     async fn check_struct_existed_or_not(&mut self) -> anyhow::Result<CheckResult> {
         let mut check_error = None;
 
@@ -333,4 +384,275 @@ impl Prechecker for MySqlPrechecker {
             check_error,
         ))
     }
+
+    async fn fetch_col_charsets(&mut self) -> anyhow::Result<Vec<ColumnCharset>> {
+        let tables = self.fetcher.fetch_tables().await?;
+        self.fetcher.fetch_col_charsets(&tables).await
+    }
+
+    async fn fetch_table_cols(&mut self) -> anyhow::Result<Vec<TableCols>> {
+        let tables = self.fetcher.fetch_tables().await?;
+        self.fetcher.fetch_table_cols(&tables).await
+    }
+
+    async fn check_target_tables(
+        &mut self,
+        src_tables: &[TableCols],
+        router: &RdbRouter,
+    ) -> anyhow::Result<Option<CheckResult>> {
+        // tables will be created by struct init
+        if self.is_source || self.precheck_config.do_struct_init {
+            return Ok(None);
+        }
+
+        let dst_tables = TableColsChecker::get_dst_tables(src_tables, router);
+        let dst_tables = self.fetcher.fetch_table_cols(&dst_tables).await?;
+        let check_error =
+            TableColsChecker::check(src_tables, &dst_tables, router).map(anyhow::Error::msg);
+        Ok(Some(CheckResult::build_with_err(
+            CheckItem::CheckIfTargetTableMatched,
+            self.is_source,
+            DbType::Mysql,
+            check_error,
+        )))
+    }
+
+    async fn check_charset(
+        &mut self,
+        router: &RdbRouter,
+        src_db_type: &DbType,
+        src_col_charsets: &[ColumnCharset],
+    ) -> anyhow::Result<Option<CheckResult>> {
+        if self.is_source || *src_db_type != DbType::Mysql {
+            return Ok(None);
+        }
+
+        // routed target tables
+        let mut dst_table_set = HashSet::new();
+        let mut dst_tables: Vec<Table> = vec![];
+        for col_charset in src_col_charsets.iter() {
+            let (dst_schema, dst_tb) =
+                router.get_tb_map(&col_charset.database_name, &col_charset.table_name);
+            if dst_table_set.insert((dst_schema.to_string(), dst_tb.to_string())) {
+                dst_tables.push(Table {
+                    database_name: dst_schema.to_string(),
+                    schema_name: String::from(""),
+                    table_name: dst_tb.to_string(),
+                });
+            }
+        }
+        let dst_col_charsets = self.fetcher.fetch_col_charsets(&dst_tables).await?;
+
+        let check_error = Self::check_col_charsets(src_col_charsets, &dst_col_charsets, router)
+            .map(|err| anyhow::Error::msg(format!("charset mismatched columns: {}", err)));
+        Ok(Some(CheckResult::build_with_err(
+            CheckItem::CheckCharsetCompatible,
+            self.is_source,
+            DbType::Mysql,
+            check_error,
+        )))
+    }
+
+    async fn fetch_table_widths(&mut self) -> anyhow::Result<Vec<TableWidth>> {
+        self.fetcher.fetch_table_widths().await
+    }
+
+    async fn check_batch_size(
+        &mut self,
+        table_widths: &[TableWidth],
+        sinker_basic: &BasicSinkerConfig,
+    ) -> anyhow::Result<Option<CheckResult>> {
+        if self.is_source {
+            return Ok(None);
+        }
+
+        let configs = self
+            .fetcher
+            .fetch_configuration(vec!["max_allowed_packet".to_string()])
+            .await?;
+        let check_error = match configs["max_allowed_packet"].parse::<usize>() {
+            Ok(max_allowed_packet) => BatchSizeChecker::check(
+                table_widths,
+                sinker_basic,
+                "max_allowed_packet",
+                max_allowed_packet,
+            )
+            .map(anyhow::Error::msg),
+            Err(_) => Some(anyhow::Error::msg(format!(
+                "max_allowed_packet setting:[{}] is invalid.",
+                configs["max_allowed_packet"]
+            ))),
+        };
+        Ok(Some(
+            CheckResult::build_with_err(
+                CheckItem::CheckBatchSize,
+                self.is_source,
+                DbType::Mysql,
+                check_error,
+            )
+            .into_warning(),
+        ))
+    }
+}
+
+impl MySqlPrechecker {
+    // compare each source column with its routed target column, returns None if all matched,
+    // missing tables and columns are reported by check_target_tables
+    fn check_col_charsets(
+        src_col_charsets: &[ColumnCharset],
+        dst_col_charsets: &[ColumnCharset],
+        router: &RdbRouter,
+    ) -> Option<String> {
+        let dst_map: HashMap<(&str, &str, &str), &ColumnCharset> = dst_col_charsets
+            .iter()
+            .map(|dst| {
+                (
+                    (
+                        dst.database_name.as_str(),
+                        dst.table_name.as_str(),
+                        dst.column_name.as_str(),
+                    ),
+                    dst,
+                )
+            })
+            .collect();
+
+        let mut err_msgs: Vec<String> = vec![];
+        for src in src_col_charsets.iter() {
+            let (dst_schema, dst_tb) = router.get_tb_map(&src.database_name, &src.table_name);
+            let dst_col = router
+                .get_col_map(&src.database_name, &src.table_name)
+                .and_then(|col_map| col_map.get(&src.column_name))
+                .unwrap_or(&src.column_name);
+            let dst = match dst_map.get(&(dst_schema.as_ref(), dst_tb.as_ref(), dst_col.as_str())) {
+                Some(dst) => dst,
+                None => continue,
+            };
+
+            if !Self::is_charset_compatible(&src.charset, &dst.charset) {
+                err_msgs.push(format!(
+                    "`{}`.`{}`.`{}`, source charset:[{}], target charset:[{}]",
+                    dst_schema, dst_tb, dst_col, src.charset, dst.charset
+                ));
+            } else if !Self::is_collation_compatible(src, dst) {
+                err_msgs.push(format!(
+                    "`{}`.`{}`.`{}`, source collation:[{}], target collation:[{}]",
+                    dst_schema, dst_tb, dst_col, src.collation, dst.collation
+                ));
+            }
+        }
+
+        if err_msgs.is_empty() {
+            None
+        } else {
+            Some(err_msgs.join(";"))
+        }
+    }
+
+    // collations decide comparison and uniqueness, E.g. 'a' and 'A' are duplicate keys in a _ci
+    // collation only. Collations of different charsets are compared without the charset prefix,
+    // E.g. utf8mb3_general_ci and utf8mb4_general_ci
+    fn is_collation_compatible(src: &ColumnCharset, dst: &ColumnCharset) -> bool {
+        let strip = |col_charset: &ColumnCharset| {
+            let collation = col_charset.collation.to_lowercase();
+            let prefix = format!("{}_", col_charset.charset.to_lowercase());
+            match collation.strip_prefix(&prefix) {
+                Some(suffix) => suffix.to_string(),
+                None => collation,
+            }
+        };
+        strip(src) == strip(dst)
+    }
+
+    // utf8mb4 holds all characters of other charsets, utf8 is an alias of utf8mb3
+    fn is_charset_compatible(src_charset: &str, dst_charset: &str) -> bool {
+        let normalize = |charset: &str| {
+            let charset = charset.to_lowercase();
+            if charset == "utf8" {
+                "utf8mb3".to_string()
+            } else {
+                charset
+            }
+        };
+        let (src_charset, dst_charset) = (normalize(src_charset), normalize(dst_charset));
+        src_charset == dst_charset || dst_charset == "utf8mb4"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::config::{config_enums::DbType, router_config::RouterConfig};
+
+    use super::*;
+
+    fn col_charset(db: &str, tb: &str, col: &str, charset: &str, collation: &str) -> ColumnCharset {
+        ColumnCharset {
+            database_name: db.into(),
+            table_name: tb.into(),
+            column_name: col.into(),
+            charset: charset.into(),
+            collation: collation.into(),
+        }
+    }
+
+    #[test]
+    fn test_check_col_charsets() {
+        let mut config = RouterConfig::default();
+        let RouterConfig::Rdb {
+            tb_map, col_map, ..
+        } = &mut config;
+        *tb_map = "db_1.tb_1:dst_db.dst_tb".into();
+        *col_map = r#"json:[{"db":"db_1","tb":"tb_1","col_map":{"name":"dst_name"}}]"#.into();
+        let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
+        let src = vec![
+            col_charset("db_1", "tb_1", "name", "utf8", "utf8_general_ci"),
+            col_charset("db_1", "tb_1", "code", "latin1", "latin1_swedish_ci"),
+        ];
+
+        // the target table of the same name is not compared
+        let dst = vec![
+            col_charset("db_1", "tb_1", "name", "latin1", "latin1_swedish_ci"),
+            col_charset(
+                "dst_db",
+                "dst_tb",
+                "dst_name",
+                "utf8mb4",
+                "utf8mb4_general_ci",
+            ),
+            col_charset("dst_db", "dst_tb", "code", "utf8mb4", "utf8mb4_swedish_ci"),
+        ];
+        assert_eq!(
+            MySqlPrechecker::check_col_charsets(&src, &dst, &router),
+            None
+        );
+
+        // charset of the routed col
+        let dst = vec![
+            col_charset(
+                "dst_db",
+                "dst_tb",
+                "dst_name",
+                "latin1",
+                "latin1_swedish_ci",
+            ),
+            col_charset("dst_db", "dst_tb", "code", "latin1", "latin1_swedish_ci"),
+        ];
+        assert_eq!(
+            MySqlPrechecker::check_col_charsets(&src, &dst, &router),
+            Some(
+                "`dst_db`.`dst_tb`.`dst_name`, source charset:[utf8], target charset:[latin1]"
+                    .into()
+            )
+        );
+
+        // collation
+        let dst = vec![
+            col_charset("dst_db", "dst_tb", "dst_name", "utf8mb4", "utf8mb4_bin"),
+            col_charset("dst_db", "dst_tb", "code", "latin1", "latin1_swedish_ci"),
+        ];
+        assert_eq!(
+            MySqlPrechecker::check_col_charsets(&src, &dst, &router),
+            Some("`dst_db`.`dst_tb`.`dst_name`, source collation:[utf8_general_ci], target collation:[utf8mb4_bin]".into())
+        );
+    }
 }
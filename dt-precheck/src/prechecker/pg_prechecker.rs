@@ -2,21 +2,31 @@ use std::collections::HashSet;
 
 use anyhow::bail;
 use async_trait::async_trait;
-use dt_common::config::{config_enums::DbType, filter_config::FilterConfig};
+use dt_common::config::{
+    config_enums::DbType, filter_config::FilterConfig, sinker_config::BasicSinkerConfig,
+};
+use dt_connector::rdb_router::RdbRouter;
 
 use crate::{
     config::precheck_config::PrecheckConfig,
     fetcher::{postgresql::pg_fetcher::PgFetcher, traits::Fetcher},
     meta::{
-        check_item::CheckItem, check_result::CheckResult, db_table_model::DbTable,
+        check_item::CheckItem,
+        check_result::CheckResult,
+        database_mode::{ColumnCharset, TableCols, TableWidth},
+        db_table_model::DbTable,
         pg_enums::ConstraintTypeEnum,
     },
 };
 
-use super::traits::Prechecker;
+use super::{
+    batch_size_checker::BatchSizeChecker, table_cols_checker::TableColsChecker, traits::Prechecker,
+};
 
 const PG_SUPPORT_DB_VERSION_NUM_MIN: i32 = 120000;
 const PG_SUPPORT_DB_VERSION_NUM_MAX: i32 = 149999;
+// a single protocol message is limited to 1GB
+const PG_MAX_MESSAGE_BYTES: usize = 1 << 30;
 
 pub struct PostgresqlPrechecker {
     pub fetcher: PgFetcher,
@@ -351,4 +361,89 @@ impl Prechecker for PostgresqlPrechecker {
             check_error,
         ))
     }
+
+    async fn fetch_table_cols(&mut self) -> anyhow::Result<Vec<TableCols>> {
+        let tables = self.fetcher.fetch_tables().await?;
+        self.fetcher.fetch_table_cols(&tables).await
+    }
+
+    async fn check_target_tables(
+        &mut self,
+        src_tables: &[TableCols],
+        router: &RdbRouter,
+    ) -> anyhow::Result<Option<CheckResult>> {
+        // tables will be created by struct init
+        if self.is_source || self.precheck_config.do_struct_init {
+            return Ok(None);
+        }
+
+        let dst_tables = TableColsChecker::get_dst_tables(src_tables, router);
+        let dst_tables = self.fetcher.fetch_table_cols(&dst_tables).await?;
+        let check_error =
+            TableColsChecker::check(src_tables, &dst_tables, router).map(anyhow::Error::msg);
+        Ok(Some(CheckResult::build_with_err(
+            CheckItem::CheckIfTargetTableMatched,
+            self.is_source,
+            DbType::Pg,
+            check_error,
+        )))
+    }
+
+    async fn check_charset(
+        &mut self,
+        _router: &RdbRouter,
+        src_db_type: &DbType,
+        _src_col_charsets: &[ColumnCharset],
+    ) -> anyhow::Result<Option<CheckResult>> {
+        if self.is_source || *src_db_type != DbType::Mysql {
+            return Ok(None);
+        }
+
+        let encoding = self.fetcher.fetch_database_encoding().await?;
+        let check_error = if encoding.eq_ignore_ascii_case("UTF8") {
+            None
+        } else {
+            Some(anyhow::Error::msg(format!(
+                "database encoding:[{}] is not UTF8.",
+                encoding
+            )))
+        };
+        Ok(Some(CheckResult::build_with_err(
+            CheckItem::CheckCharsetCompatible,
+            self.is_source,
+            DbType::Pg,
+            check_error,
+        )))
+    }
+
+    async fn fetch_table_widths(&mut self) -> anyhow::Result<Vec<TableWidth>> {
+        self.fetcher.fetch_table_widths().await
+    }
+
+    async fn check_batch_size(
+        &mut self,
+        table_widths: &[TableWidth],
+        sinker_basic: &BasicSinkerConfig,
+    ) -> anyhow::Result<Option<CheckResult>> {
+        if self.is_source {
+            return Ok(None);
+        }
+
+        let check_error = BatchSizeChecker::check(
+            table_widths,
+            sinker_basic,
+            "max message size",
+            PG_MAX_MESSAGE_BYTES,
+        )
+        .map(anyhow::Error::msg);
+        Ok(Some(
+            CheckResult::build_with_err(
+                CheckItem::CheckBatchSize,
+                self.is_source,
+                DbType::Pg,
+                check_error,
+            )
+            .into_warning(),
+        ))
+    }
 }
@@ -38,6 +38,10 @@ pub struct RedisPrechecker {
 }
 
 const MIN_SUPPORTED_VERSION: f32 = 2.8;
+// the default repl-backlog-size of redis
+const MIN_REPL_BACKLOG_SIZE: u64 = 1024 * 1024;
+const REPL_BACKLOG_SIZE: &str = "repl-backlog-size";
+const MAXMEMORY_POLICY: &str = "maxmemory-policy";
 
 #[async_trait]
 impl Prechecker for RedisPrechecker {
@@ -86,9 +90,11 @@ impl Prechecker for RedisPrechecker {
             buffer,
             router: RdbRouter::from_config(&self.task_config.router, &DbType::Redis)?,
             shut_down: Arc::new(AtomicBool::new(false)),
+            stopped: Arc::new(AtomicBool::new(false)),
             monitor: ExtractorMonitor::new(monitor),
             data_marker: None,
             time_filter: TimeFilter::default(),
+            rate_limiter: None,
         };
 
         let mut psyncer = RedisPsyncExtractor {
@@ -122,6 +128,79 @@ impl Prechecker for RedisPrechecker {
         }
     }
 
+    async fn check_cdc_configurations(&mut self) -> anyhow::Result<Vec<CheckResult>> {
+        if !self.is_source {
+            return Ok(vec![]);
+        }
+
+        let configs = match self
+            .fetcher
+            .fetch_configuration(vec![
+                REPL_BACKLOG_SIZE.to_string(),
+                MAXMEMORY_POLICY.to_string(),
+            ])
+            .await
+        {
+            Ok(configs) => configs,
+            // CONFIG is disabled on managed redis, E.g. ElastiCache, the settings can't be checked
+            Err(e) => {
+                let error = format!("CONFIG GET failed, the setting can not be checked: {}", e);
+                return Ok(
+                    [CheckItem::CheckReplBacklog, CheckItem::CheckMaxmemoryPolicy]
+                        .into_iter()
+                        .map(|check_item| {
+                            CheckResult::build_with_err(
+                                check_item,
+                                self.is_source,
+                                DbType::Redis,
+                                Some(anyhow::Error::msg(error.clone())),
+                            )
+                            .into_warning()
+                        })
+                        .collect(),
+                );
+            }
+        };
+
+        let repl_backlog_size = &configs[REPL_BACKLOG_SIZE];
+        let backlog_error = match repl_backlog_size.parse::<u64>() {
+            Ok(size) if size >= MIN_REPL_BACKLOG_SIZE => None,
+            Ok(size) => Some(anyhow::Error::msg(format!(
+                "{}:[{}] is too small, the minimum is {}.",
+                REPL_BACKLOG_SIZE, size, MIN_REPL_BACKLOG_SIZE
+            ))),
+            Err(_) => Some(anyhow::Error::msg(format!(
+                "{}:[{}] is invalid.",
+                REPL_BACKLOG_SIZE, repl_backlog_size
+            ))),
+        };
+
+        let maxmemory_policy = configs[MAXMEMORY_POLICY].to_lowercase();
+        let policy_error = if maxmemory_policy == "noeviction" {
+            None
+        } else {
+            Some(anyhow::Error::msg(format!(
+                "{}:[{}] may evict keys, only noeviction is supported.",
+                MAXMEMORY_POLICY, maxmemory_policy
+            )))
+        };
+
+        Ok(vec![
+            CheckResult::build_with_err(
+                CheckItem::CheckReplBacklog,
+                self.is_source,
+                DbType::Redis,
+                backlog_error,
+            ),
+            CheckResult::build_with_err(
+                CheckItem::CheckMaxmemoryPolicy,
+                self.is_source,
+                DbType::Redis,
+                policy_error,
+            ),
+        ])
+    }
+
     async fn check_permission(&mut self) -> anyhow::Result<CheckResult> {
         Ok(CheckResult::build(
             CheckItem::CheckAccountPermission,
@@ -0,0 +1,83 @@
+use dt_connector::rdb_router::RdbRouter;
+
+use crate::meta::database_mode::{ColCategory, Table, TableCols};
+
+pub struct TableColsChecker {}
+
+impl TableColsChecker {
+    // routed target tables of the source tables
+    pub fn get_dst_tables(src_tables: &[TableCols], router: &RdbRouter) -> Vec<Table> {
+        src_tables
+            .iter()
+            .map(|src| {
+                let (dst_schema, dst_tb) = router.get_tb_map(&src.schema_name, &src.table_name);
+                Table {
+                    database_name: dst_schema.to_string(),
+                    schema_name: dst_schema.to_string(),
+                    table_name: dst_tb.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    // check whether every routed target table exists and contains all source columns
+    // with compatible types, returns None if all matched, otherwise all problems found
+    pub fn check(
+        src_tables: &[TableCols],
+        dst_tables: &[TableCols],
+        router: &RdbRouter,
+    ) -> Option<String> {
+        let mut errs: Vec<String> = Vec::new();
+        for src in src_tables.iter() {
+            let (dst_schema, dst_tb) = router.get_tb_map(&src.schema_name, &src.table_name);
+            let dst = dst_tables
+                .iter()
+                .find(|dst| dst.schema_name == dst_schema && dst.table_name == dst_tb);
+            let dst = match dst {
+                Some(dst) => dst,
+                None => {
+                    errs.push(format!(
+                        "table: `{}`.`{}` not existed, routed from: `{}`.`{}`",
+                        dst_schema, dst_tb, src.schema_name, src.table_name
+                    ));
+                    continue;
+                }
+            };
+
+            let col_map = router.get_col_map(&src.schema_name, &src.table_name);
+            for (src_col, src_category) in src.cols.iter() {
+                let dst_col = col_map
+                    .and_then(|col_map| col_map.get(src_col))
+                    .unwrap_or(src_col);
+                match dst.cols.iter().find(|(col, _)| col == dst_col) {
+                    Some((_, dst_category)) => {
+                        if !Self::is_category_compatible(src_category, dst_category) {
+                            errs.push(format!(
+                                "column: `{}`.`{}`.`{}` type mismatched, source: {:?}, target: {:?}",
+                                dst_schema, dst_tb, dst_col, src_category, dst_category
+                            ));
+                        }
+                    }
+                    None => errs.push(format!(
+                        "column: `{}`.`{}`.`{}` not existed",
+                        dst_schema, dst_tb, dst_col
+                    )),
+                }
+            }
+        }
+
+        if errs.is_empty() {
+            None
+        } else {
+            Some(errs.join("; "))
+        }
+    }
+
+    // any value can be written to a string column
+    fn is_category_compatible(src: &ColCategory, dst: &ColCategory) -> bool {
+        src == dst
+            || *src == ColCategory::Other
+            || *dst == ColCategory::Other
+            || *dst == ColCategory::String
+    }
+}
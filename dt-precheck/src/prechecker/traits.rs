@@ -1,6 +1,11 @@
 use async_trait::async_trait;
+use dt_common::config::{config_enums::DbType, sinker_config::BasicSinkerConfig};
+use dt_connector::rdb_router::RdbRouter;
 
-use crate::meta::check_result::CheckResult;
+use crate::meta::{
+    check_result::CheckResult,
+    database_mode::{ColumnCharset, TableCols, TableWidth},
+};
 
 #[async_trait]
 pub trait Prechecker {
@@ -15,4 +20,57 @@ pub trait Prechecker {
     async fn check_struct_existed_or_not(&mut self) -> anyhow::Result<CheckResult>;
 
     async fn check_table_structs(&mut self) -> anyhow::Result<CheckResult>;
+
+    // only for databases with gtid based cdc, None if not applicable
+    async fn check_gtid_mode(&mut self) -> anyhow::Result<Option<CheckResult>> {
+        Ok(None)
+    }
+
+    // columns of the tables to sync, empty if not applicable
+    async fn fetch_table_cols(&mut self) -> anyhow::Result<Vec<TableCols>> {
+        Ok(vec![])
+    }
+
+    // only for targets without struct init, None if not applicable
+    async fn check_target_tables(
+        &mut self,
+        _src_tables: &[TableCols],
+        _router: &RdbRouter,
+    ) -> anyhow::Result<Option<CheckResult>> {
+        Ok(None)
+    }
+
+    // charsets of the string columns to sync, empty if not applicable
+    async fn fetch_col_charsets(&mut self) -> anyhow::Result<Vec<ColumnCharset>> {
+        Ok(vec![])
+    }
+
+    // only for targets which may not hold the source charsets, None if not applicable
+    async fn check_charset(
+        &mut self,
+        _router: &RdbRouter,
+        _src_db_type: &DbType,
+        _src_col_charsets: &[ColumnCharset],
+    ) -> anyhow::Result<Option<CheckResult>> {
+        Ok(None)
+    }
+
+    // cdc related settings checked besides check_cdc_supported, empty if not applicable
+    async fn check_cdc_configurations(&mut self) -> anyhow::Result<Vec<CheckResult>> {
+        Ok(vec![])
+    }
+
+    // worst case row widths of the tables to sync, empty if not applicable
+    async fn fetch_table_widths(&mut self) -> anyhow::Result<Vec<TableWidth>> {
+        Ok(vec![])
+    }
+
+    // only for targets with packet limits, None if not applicable
+    async fn check_batch_size(
+        &mut self,
+        _table_widths: &[TableWidth],
+        _sinker_basic: &BasicSinkerConfig,
+    ) -> anyhow::Result<Option<CheckResult>> {
+        Ok(None)
+    }
 }
@@ -4,12 +4,16 @@ use std::{
     sync::{atomic::AtomicBool, Arc, Mutex},
 };
 
+use anyhow::bail;
 use dt_common::{
     config::{
         config_enums::{DbType, ExtractType},
+        config_token_parser::ConfigTokenParser,
         extractor_config::ExtractorConfig,
+        sinker_config::SinkerConfig,
         task_config::TaskConfig,
     },
+    error::Error,
     meta::{
         dt_queue::DtQueue, mysql::mysql_meta_manager::MysqlMetaManager,
         rdb_meta_manager::RdbMetaManager,
@@ -24,12 +28,13 @@ use dt_common::{
         pg::pg_meta_manager::PgMetaManager, redis::redis_statistic_type::RedisStatisticType,
         syncer::Syncer,
     },
-    utils::redis_util::RedisUtil,
+    utils::{redis_util::RedisUtil, sql_util::SqlUtil},
 };
 use dt_connector::{
     data_marker::DataMarker,
     extractor::{
         base_extractor::BaseExtractor,
+        extract_rate_limiter::ExtractRateLimiter,
         extractor_monitor::ExtractorMonitor,
         foxlake::foxlake_s3_extractor::FoxlakeS3Extractor,
         kafka::kafka_extractor::KafkaExtractor,
@@ -38,7 +43,12 @@ use dt_connector::{
             mongo_snapshot_extractor::MongoSnapshotExtractor,
         },
         mysql::{
-            mysql_cdc_extractor::MysqlCdcExtractor, mysql_check_extractor::MysqlCheckExtractor,
+            binlog_event_sizer::BinlogEventSizer,
+            cross_database_join_extractor::{CrossDatabaseJoinExtractor, JoinSql},
+            mysql_cdc_extractor::MysqlCdcExtractor,
+            mysql_check_extractor::MysqlCheckExtractor,
+            mysql_consistent_snapshot::MysqlConsistentSnapshot,
+            mysql_rds_extractor::MysqlRdsExtractor,
             mysql_snapshot_extractor::MysqlSnapshotExtractor,
             mysql_struct_extractor::MysqlStructExtractor,
         },
@@ -68,20 +78,27 @@ impl ExtractorUtil {
         extractor_config: &ExtractorConfig,
         buffer: Arc<DtQueue>,
         shut_down: Arc<AtomicBool>,
+        stopped: Arc<AtomicBool>,
         syncer: Arc<Mutex<Syncer>>,
         monitor: Arc<Mutex<Monitor>>,
         data_marker: Option<DataMarker>,
         router: RdbRouter,
         snapshot_resumer: SnapshotResumer,
         cdc_resumer: CdcResumer,
+        mysql_consistent_snapshot: Option<Arc<MysqlConsistentSnapshot>>,
+        rate_limiter: Option<Arc<ExtractRateLimiter>>,
     ) -> anyhow::Result<Box<dyn Extractor + Send>> {
+        let mut extractor_monitor = ExtractorMonitor::new(monitor);
+        extractor_monitor.report_extract_rate = rate_limiter.is_some();
         let mut base_extractor = BaseExtractor {
             buffer,
             router,
             shut_down,
-            monitor: ExtractorMonitor::new(monitor),
+            stopped,
+            monitor: extractor_monitor,
             data_marker,
             time_filter: TimeFilter::default(),
+            rate_limiter,
         };
 
         let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&config.runtime.log_level);
@@ -95,9 +112,25 @@ impl ExtractorUtil {
                 sample_interval,
                 parallel_size,
                 batch_size,
+                force_index,
+                auto_detect_force_index,
+                use_partition_based_extraction,
+                partition_concurrency,
+                parallel_slices,
+                slice_progress_interval,
+                snapshot_desc,
+                checksum_check,
+                tb_query_hints,
+                ..
             } => {
                 // max_connections: 1 for extracting data from table, 1 for db-meta-manager
-                let max_connections = cmp::max(2, parallel_size as u32 + 1);
+                let extract_connections = if use_partition_based_extraction {
+                    cmp::max(parallel_size, partition_concurrency)
+                } else {
+                    parallel_size
+                };
+                let extract_connections = cmp::max(extract_connections, parallel_slices);
+                let max_connections = cmp::max(2, extract_connections as u32 + 1);
                 let conn_pool =
                     TaskUtil::create_mysql_conn_pool(&url, max_connections, enable_sqlx_log)
                         .await?;
@@ -108,6 +141,7 @@ impl ExtractorUtil {
                     config.meta_center.clone(),
                 )
                 .await?;
+                let query_hint = tb_query_hints.get(&(db.clone(), tb.clone())).cloned();
                 let extractor = MysqlSnapshotExtractor {
                     conn_pool: conn_pool.clone(),
                     meta_manager,
@@ -119,6 +153,85 @@ impl ExtractorUtil {
                     parallel_size,
                     base_extractor,
                     filter,
+                    force_index: if force_index.is_empty() {
+                        None
+                    } else {
+                        Some(force_index)
+                    },
+                    auto_detect_force_index,
+                    use_partition_based_extraction,
+                    partition_concurrency,
+                    consistent_snapshot: mysql_consistent_snapshot,
+                    parallel_slices,
+                    slice_progress_interval,
+                    progress_total_count: 0,
+                    snapshot_desc,
+                    query_hint,
+                    checksum_target_conn_pool: match (&config.sinker, checksum_check) {
+                        (SinkerConfig::MysqlCheck { url, .. }, true) => {
+                            Some(TaskUtil::create_mysql_conn_pool(url, 1, enable_sqlx_log).await?)
+                        }
+                        _ => None,
+                    },
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::MysqlJoin {
+                url,
+                join_sql,
+                virtual_table,
+            } => {
+                // validate before connecting
+                let join_sql = JoinSql::parse(&join_sql)?;
+                let tokens =
+                    ConfigTokenParser::parse_config(&virtual_table, &DbType::Mysql, &['.'])?;
+                if tokens.len() != 2 {
+                    bail! {Error::ConfigError(format!(
+                        "config [extractor] virtual_table should be like db.tb, got: {}",
+                        virtual_table
+                    ))}
+                }
+                let virtual_db = SqlUtil::unescape_by_db_type(&tokens[0], &DbType::Mysql);
+                let virtual_tb = SqlUtil::unescape_by_db_type(&tokens[1], &DbType::Mysql);
+                // the virtual table does not exist in source, allow it explicitly
+                let mut filter = filter;
+                filter.add_do_tb(&virtual_db, &virtual_tb);
+
+                let conn_pool = TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log).await?;
+                let meta_manager = TaskUtil::create_mysql_meta_manager(
+                    &url,
+                    &config.runtime.log_level,
+                    DbType::Mysql,
+                    config.meta_center.clone(),
+                )
+                .await?;
+                let extractor = CrossDatabaseJoinExtractor {
+                    base_extractor,
+                    conn_pool,
+                    meta_manager,
+                    filter,
+                    join_sql,
+                    virtual_db,
+                    virtual_tb,
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::MysqlRds {
+                resource_arn,
+                secret_arn,
+                region,
+                batch_size,
+            } => {
+                let client = TaskUtil::create_rds_data_client(&region).await;
+                let extractor = MysqlRdsExtractor {
+                    base_extractor,
+                    client,
+                    filter,
+                    resource_arn,
+                    secret_arn,
+                    batch_size,
                 };
                 Box::new(extractor)
             }
@@ -127,6 +240,7 @@ impl ExtractorUtil {
                 url,
                 check_log_dir,
                 batch_size,
+                delete_extra,
             } => {
                 let conn_pool = TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let meta_manager = TaskUtil::create_mysql_meta_manager(
@@ -141,6 +255,7 @@ impl ExtractorUtil {
                     meta_manager,
                     check_log_dir,
                     batch_size,
+                    delete_extra,
                     base_extractor,
                     filter,
                 };
@@ -160,6 +275,7 @@ impl ExtractorUtil {
                 heartbeat_tb,
                 start_time_utc,
                 end_time_utc,
+                large_event_threshold_bytes,
             } => {
                 let conn_pool = TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let meta_manager = TaskUtil::create_mysql_meta_manager(
@@ -187,6 +303,7 @@ impl ExtractorUtil {
                     resumer: cdc_resumer,
                     gtid_enabled,
                     gtid_set,
+                    event_sizer: BinlogEventSizer::new(large_event_threshold_bytes)?,
                 };
                 Box::new(extractor)
             }
@@ -197,9 +314,18 @@ impl ExtractorUtil {
                 tb,
                 sample_interval,
                 batch_size,
+                shared_snapshot_id,
+                ctid_slicing,
+                checksum_check,
+                ..
             } => {
                 let conn_pool = TaskUtil::create_pg_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
+                let shared_snapshot_id = if shared_snapshot_id.is_empty() {
+                    None
+                } else {
+                    Some(shared_snapshot_id)
+                };
                 let extractor = PgSnapshotExtractor {
                     conn_pool,
                     meta_manager,
@@ -210,6 +336,14 @@ impl ExtractorUtil {
                     tb,
                     base_extractor,
                     filter,
+                    shared_snapshot_id,
+                    ctid_slicing,
+                    checksum_target_conn_pool: match (&config.sinker, checksum_check) {
+                        (SinkerConfig::PgCheck { url, .. }, true) => {
+                            Some(TaskUtil::create_pg_conn_pool(url, 1, enable_sqlx_log).await?)
+                        }
+                        _ => None,
+                    },
                 };
                 Box::new(extractor)
             }
@@ -218,6 +352,7 @@ impl ExtractorUtil {
                 url,
                 check_log_dir,
                 batch_size,
+                delete_extra,
             } => {
                 let conn_pool = TaskUtil::create_pg_conn_pool(&url, 2, enable_sqlx_log).await?;
                 let meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
@@ -226,6 +361,7 @@ impl ExtractorUtil {
                     meta_manager,
                     check_log_dir,
                     batch_size,
+                    delete_extra,
                     base_extractor,
                     filter,
                 };
@@ -335,6 +471,7 @@ impl ExtractorUtil {
                     db,
                     filter,
                     base_extractor,
+                    split_indexes: Self::split_struct_indexes(config),
                 };
                 Box::new(extractor)
             }
@@ -347,6 +484,7 @@ impl ExtractorUtil {
                     schema,
                     filter,
                     base_extractor,
+                    split_indexes: Self::split_struct_indexes(config),
                 };
                 Box::new(extractor)
             }
@@ -525,4 +663,12 @@ impl ExtractorUtil {
         };
         Ok(meta_manager)
     }
+
+    // struct checkers and sinkers of other db types handle indexes with their tables
+    fn split_struct_indexes(config: &TaskConfig) -> bool {
+        matches!(
+            config.sinker,
+            SinkerConfig::MysqlStruct { .. } | SinkerConfig::PgStruct { .. }
+        )
+    }
 }
@@ -0,0 +1,327 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::bail;
+use dt_common::{
+    config::{config_enums::DbType, sinker_config::SinkerConfig, task_config::TaskConfig},
+    error::Error,
+    log_info,
+    meta::{col_value::ColValue, row_data::RowData},
+    utils::integrity_hash_chain::{IntegrityHashChain, IntegrityManifestEntry, DIGEST_LEN},
+};
+use dt_connector::{rdb_query_builder::RdbQueryBuilder, rdb_router::RdbRouter};
+use futures::TryStreamExt;
+
+use crate::task_util::TaskUtil;
+
+// verify the signed integrity manifest against the hash chain checkpoints in position.log,
+// then rebuild the table digests by re-reading the target and compare them with the manifest
+pub struct IntegrityVerifier {
+    pub config: TaskConfig,
+}
+
+// digest of a target table expected by the manifest
+struct ExpectedTbDigest {
+    digest: [u8; DIGEST_LEN],
+    row_count: i64,
+    // target col -> source col
+    col_map: HashMap<String, String>,
+}
+
+impl IntegrityVerifier {
+    pub async fn new(task_config_file: &str) -> anyhow::Result<Self> {
+        let mut config = TaskConfig::new(task_config_file)?;
+        TaskUtil::resolve_credentials(&mut config).await?;
+        Ok(Self { config })
+    }
+
+    pub async fn verify(&self) -> anyhow::Result<()> {
+        let pipeline_config = &self.config.pipeline;
+        let position_log_file = format!("{}/position.log", self.config.runtime.log_dir);
+        let trusted_public_key = if !pipeline_config.integrity_public_key.is_empty() {
+            pipeline_config.integrity_public_key.clone()
+        } else if !pipeline_config.integrity_private_key_file.is_empty() {
+            IntegrityHashChain::get_public_key_hex(&pipeline_config.integrity_private_key_file)?
+        } else {
+            bail! {Error::ConfigError(
+                "config [pipeline] integrity_public_key or integrity_private_key_file is required by --verify-integrity".into()
+            )}
+        };
+        let entries = IntegrityHashChain::verify_manifest(
+            &pipeline_config.integrity_manifest_file,
+            &trusted_public_key,
+            &position_log_file,
+        )?;
+
+        for entry in entries.iter() {
+            log_info!(
+                "integrity verified, task_id: {}, hash: {}, batch_count: {}, row_count: {}",
+                entry.task_id,
+                entry.final_hash,
+                entry.batch_count,
+                entry.row_count
+            );
+        }
+
+        self.verify_target_data(&entries).await?;
+        log_info!("integrity verification passed, tasks: {}", entries.len());
+        Ok(())
+    }
+
+    async fn verify_target_data(&self, entries: &[IntegrityManifestEntry]) -> anyhow::Result<()> {
+        let (url, db_type) = match &self.config.sinker {
+            SinkerConfig::Mysql { url, .. } => (url, DbType::Mysql),
+            SinkerConfig::Pg { url, .. } => (url, DbType::Pg),
+            _ => bail! {Error::ConfigError(
+                "--verify-integrity only supports mysql / pg sinkers to re-read target data".into()
+            )},
+        };
+        let router = RdbRouter::from_config(&self.config.router, &db_type)?;
+        let expected_digests = Self::get_expected_digests(entries, &router)?;
+
+        let log_level = &self.config.runtime.log_level;
+        for ((schema, tb), expected) in expected_digests.iter() {
+            let (digest, row_count) = match db_type {
+                DbType::Mysql => {
+                    Self::get_mysql_tb_digest(url, log_level, schema, tb, &expected.col_map).await?
+                }
+                _ => Self::get_pg_tb_digest(url, log_level, schema, tb, &expected.col_map).await?,
+            };
+            if digest != expected.digest || row_count != expected.row_count {
+                bail! {Error::IntegrityError(format!(
+                    "target data mismatch, schema: {}, tb: {}, manifest: {} (row_count={}), target: {} (row_count={})",
+                    schema,
+                    tb,
+                    hex::encode(expected.digest),
+                    expected.row_count,
+                    hex::encode(digest),
+                    row_count
+                ))}
+            }
+            log_info!(
+                "target data verified, schema: {}, tb: {}, row_count: {}",
+                schema,
+                tb,
+                row_count
+            );
+        }
+        Ok(())
+    }
+
+    // digests of source tables routed to the same target table (E.g. merged shards,
+    // or restarted runs of a task) are summed up
+    fn get_expected_digests(
+        entries: &[IntegrityManifestEntry],
+        router: &RdbRouter,
+    ) -> anyhow::Result<BTreeMap<(String, String), ExpectedTbDigest>> {
+        let mut expected_digests = BTreeMap::new();
+        for entry in entries.iter() {
+            for tb_digest in entry.tb_digests.iter() {
+                let (dst_schema, dst_tb) = router.get_tb_map(&tb_digest.schema, &tb_digest.tb);
+                let expected = expected_digests
+                    .entry((dst_schema.to_string(), dst_tb.to_string()))
+                    .or_insert_with(|| ExpectedTbDigest {
+                        digest: [0; DIGEST_LEN],
+                        row_count: 0,
+                        col_map: router
+                            .get_col_map(&tb_digest.schema, &tb_digest.tb)
+                            .map(|col_map| {
+                                col_map
+                                    .iter()
+                                    .map(|(src, dst)| (dst.clone(), src.clone()))
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    });
+
+                let mut digest = [0; DIGEST_LEN];
+                hex::decode_to_slice(&tb_digest.digest, &mut digest)?;
+                IntegrityHashChain::add_digest(&mut expected.digest, &digest);
+                expected.row_count += tb_digest.row_count;
+            }
+        }
+        Ok(expected_digests)
+    }
+
+    async fn get_mysql_tb_digest(
+        url: &str,
+        log_level: &str,
+        schema: &str,
+        tb: &str,
+        col_map: &HashMap<String, String>,
+    ) -> anyhow::Result<([u8; DIGEST_LEN], i64)> {
+        let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(log_level);
+        let conn_pool = TaskUtil::create_mysql_conn_pool(url, 1, enable_sqlx_log).await?;
+        let mut meta_manager =
+            TaskUtil::create_mysql_meta_manager(url, log_level, DbType::Mysql, None).await?;
+        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+        let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
+        let sql = format!(
+            "SELECT {} FROM `{}`.`{}`",
+            query_builder.build_extract_cols_str()?,
+            schema,
+            tb
+        );
+
+        let mut result = ([0; DIGEST_LEN], 0);
+        {
+            let mut rows = sqlx::query(&sql).fetch(&conn_pool);
+            while let Some(row) = rows.try_next().await? {
+                let row_data = RowData::from_mysql_row(&row, &tb_meta, &None);
+                Self::add_row(&mut result, row_data, col_map)?;
+            }
+        }
+        meta_manager.close().await?;
+        conn_pool.close().await;
+        Ok(result)
+    }
+
+    async fn get_pg_tb_digest(
+        url: &str,
+        log_level: &str,
+        schema: &str,
+        tb: &str,
+        col_map: &HashMap<String, String>,
+    ) -> anyhow::Result<([u8; DIGEST_LEN], i64)> {
+        let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(log_level);
+        let conn_pool = TaskUtil::create_pg_conn_pool(url, 1, enable_sqlx_log).await?;
+        let mut meta_manager = TaskUtil::create_pg_meta_manager(url, log_level).await?;
+        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+        let query_builder = RdbQueryBuilder::new_for_pg(&tb_meta, None);
+        let sql = format!(
+            r#"SELECT {} FROM "{}"."{}""#,
+            query_builder.build_extract_cols_str()?,
+            schema,
+            tb
+        );
+
+        let mut result = ([0; DIGEST_LEN], 0);
+        {
+            let mut rows = sqlx::query(&sql).fetch(&conn_pool);
+            while let Some(row) = rows.try_next().await? {
+                let row_data = RowData::from_pg_row(&row, &tb_meta, &None);
+                Self::add_row(&mut result, row_data, col_map)?;
+            }
+        }
+        meta_manager.close().await?;
+        conn_pool.close().await;
+        Ok(result)
+    }
+
+    // cols renamed by [router] col_map are hashed by their source names
+    fn add_row(
+        result: &mut ([u8; DIGEST_LEN], i64),
+        row_data: RowData,
+        col_map: &HashMap<String, String>,
+    ) -> anyhow::Result<()> {
+        let after: HashMap<String, ColValue> = row_data
+            .after
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(col, value)| match col_map.get(&col) {
+                Some(src_col) => (src_col.clone(), value),
+                None => (col, value),
+            })
+            .collect();
+        IntegrityHashChain::add_digest(&mut result.0, &IntegrityHashChain::get_row_digest(&after)?);
+        result.1 += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::{
+        config::{config_enums::DbType, router_config::RouterConfig},
+        meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+        utils::integrity_hash_chain::{
+            IntegrityHashChain, IntegrityManifestEntry, IntegrityTbDigest, DIGEST_LEN,
+        },
+    };
+    use dt_connector::rdb_router::RdbRouter;
+
+    use super::IntegrityVerifier;
+
+    fn mock_row_data(tb: &str, id: i32, name_col: &str) -> RowData {
+        let after = HashMap::from([
+            ("id".to_string(), ColValue::Long(id)),
+            (
+                name_col.to_string(),
+                ColValue::String(format!("name_{}", id)),
+            ),
+        ]);
+        RowData::new("db_1".into(), tb.into(), RowType::Insert, None, Some(after))
+    }
+
+    fn mock_entry(chain: &IntegrityHashChain) -> IntegrityManifestEntry {
+        IntegrityManifestEntry {
+            task_id: chain.task_id.clone(),
+            run_id: chain.run_id.clone(),
+            final_hash: chain.hash_hex(),
+            batch_count: chain.batch_count,
+            row_count: chain.row_count,
+            tb_digests: chain
+                .tb_digests
+                .iter()
+                .map(|((schema, tb), (digest, row_count))| IntegrityTbDigest {
+                    schema: schema.clone(),
+                    tb: tb.clone(),
+                    digest: hex::encode(digest),
+                    row_count: *row_count,
+                })
+                .collect(),
+            public_key: String::new(),
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_get_expected_digests() {
+        // shards db_1.tb_1 and db_1.tb_2 are merged into dst_db.tb with name renamed
+        let mut config = RouterConfig::default();
+        let RouterConfig::Rdb {
+            tb_map, col_map, ..
+        } = &mut config;
+        *tb_map = "db_1.tb_1:dst_db.tb,db_1.tb_2:dst_db.tb".into();
+        *col_map = r#"json:[{"db":"db_1","tb":"tb_1","col_map":{"name":"dst_name"}},{"db":"db_1","tb":"tb_2","col_map":{"name":"dst_name"}}]"#.into();
+        let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
+
+        let mut chain_1 = IntegrityHashChain::new("db_1.tb_1");
+        chain_1.update(&[mock_row_data("tb_1", 1, "name")]).unwrap();
+        let mut chain_2 = IntegrityHashChain::new("db_1.tb_2");
+        chain_2
+            .update(&[
+                mock_row_data("tb_2", 2, "name"),
+                mock_row_data("tb_2", 3, "name"),
+            ])
+            .unwrap();
+        let entries = vec![mock_entry(&chain_1), mock_entry(&chain_2)];
+
+        let expected_digests = IntegrityVerifier::get_expected_digests(&entries, &router).unwrap();
+        assert_eq!(expected_digests.len(), 1);
+        let expected = &expected_digests[&("dst_db".to_string(), "tb".to_string())];
+        assert_eq!(expected.row_count, 3);
+        assert_eq!(
+            expected.col_map,
+            HashMap::from([("dst_name".to_string(), "name".to_string())])
+        );
+
+        let get_target_digest = |ids: &[i32]| {
+            let mut result = ([0; DIGEST_LEN], 0);
+            for id in ids {
+                let row_data = mock_row_data("tb", *id, "dst_name");
+                IntegrityVerifier::add_row(&mut result, row_data, &expected.col_map).unwrap();
+            }
+            result
+        };
+        // the target rows in any order
+        assert_eq!(
+            get_target_digest(&[3, 1, 2]),
+            (expected.digest, expected.row_count)
+        );
+        // tampered target rows
+        assert_ne!(get_target_digest(&[1, 2, 4]).0, expected.digest);
+        assert_ne!(get_target_digest(&[1, 2]).0, expected.digest);
+    }
+}
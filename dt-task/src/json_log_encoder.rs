@@ -0,0 +1,216 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use dt_common::meta::{position::Position, syncer::Syncer};
+use log::Record;
+use log4rs::{
+    config::{Deserialize, Deserializers},
+    encode::{Encode, Write},
+};
+use serde_json::{json, Map, Value};
+use serde_yaml::Mapping;
+
+pub const JSON_LOG_ENCODER_KIND: &str = "dts_json";
+
+tokio::task_local! {
+    // set for the extractor / pipeline of each single task, lines logged by them get the fields
+    pub static LOG_CONTEXT: LogContext;
+}
+
+#[derive(Clone, Default)]
+pub struct LogContext {
+    pub db: String,
+    pub tb: String,
+    pub syncer: Option<Arc<Mutex<Syncer>>>,
+}
+
+// one json object for each line:
+// {"timestamp":..,"level":..,"module":..,"message":..,"db":..,"tb":..,"position":..}
+// db / tb / position are added if the line is logged in a task with LogContext
+#[derive(Debug, Default)]
+pub struct JsonLogEncoder {}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonLogEncoderConfig {}
+
+pub struct JsonLogEncoderDeserializer;
+
+impl Encode for JsonLogEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let context = LOG_CONTEXT.try_with(|c| c.clone()).ok();
+        let line = Self::to_json(record, context.as_ref());
+        w.write_all(format!("{}\n", line).as_bytes())?;
+        Ok(())
+    }
+}
+
+impl JsonLogEncoder {
+    pub fn to_json(record: &Record, context: Option<&LogContext>) -> Value {
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".into(),
+            json!(Utc::now().format("%Y-%m-%d %H:%M:%S%.6f").to_string()),
+        );
+        line.insert("level".into(), json!(record.level().to_string()));
+        line.insert(
+            "module".into(),
+            json!(record.module_path().unwrap_or(record.target())),
+        );
+        line.insert("message".into(), json!(record.args().to_string()));
+
+        if let Some(context) = context {
+            if !context.db.is_empty() {
+                line.insert("db".into(), json!(context.db));
+            }
+            if !context.tb.is_empty() {
+                line.insert("tb".into(), json!(context.tb));
+            }
+            // never block logging, the logging thread may be holding the syncer lock
+            if let Some(syncer) = &context.syncer {
+                if let Ok(syncer) = syncer.try_lock() {
+                    if syncer.committed_position != Position::None {
+                        line.insert("position".into(), json!(syncer.committed_position));
+                    }
+                }
+            }
+        }
+        Value::Object(line)
+    }
+
+    // switch appenders of leveled logs (default.log, commit.log, stdout) to json,
+    // appenders of logs parsed by ape-dts (position.log, finished.log, etc.) are kept
+    pub fn apply_to_config(config: &mut serde_yaml::Value) {
+        let appenders = match config.get_mut("appenders").and_then(|v| v.as_mapping_mut()) {
+            Some(appenders) => appenders,
+            None => return,
+        };
+
+        for (_, appender) in appenders.iter_mut() {
+            let leveled = match appender.get("encoder") {
+                Some(encoder) => encoder
+                    .get("pattern")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|v| v.contains("{level}") || v.contains("{l}")),
+                // the default pattern of console appenders has levels
+                None => appender.get("kind").and_then(|v| v.as_str()) == Some("console"),
+            };
+
+            if let (true, Some(appender)) = (leveled, appender.as_mapping_mut()) {
+                let mut encoder = Mapping::new();
+                encoder.insert("kind".into(), JSON_LOG_ENCODER_KIND.into());
+                appender.insert("encoder".into(), encoder.into());
+            }
+        }
+    }
+
+    pub fn register(deserializers: &mut Deserializers) {
+        deserializers.insert(JSON_LOG_ENCODER_KIND, JsonLogEncoderDeserializer);
+    }
+}
+
+impl Deserialize for JsonLogEncoderDeserializer {
+    type Trait = dyn Encode;
+    type Config = JsonLogEncoderConfig;
+
+    fn deserialize(
+        &self,
+        _config: JsonLogEncoderConfig,
+        _deserializers: &Deserializers,
+    ) -> anyhow::Result<Box<dyn Encode>> {
+        Ok(Box::<JsonLogEncoder>::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let syncer = Arc::new(Mutex::new(Syncer::default()));
+        let context = LogContext {
+            db: "db_1".into(),
+            tb: "tb_1".into(),
+            syncer: Some(syncer.clone()),
+        };
+
+        let line = JsonLogEncoder::to_json(
+            &Record::builder()
+                .args(format_args!("sinked {} rows", 10))
+                .level(Level::Info)
+                .module_path(Some("dt_pipeline::base_pipeline"))
+                .build(),
+            Some(&context),
+        );
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["module"], "dt_pipeline::base_pipeline");
+        assert_eq!(line["message"], "sinked 10 rows");
+        assert_eq!(line["db"], "db_1");
+        assert_eq!(line["tb"], "tb_1");
+        assert!(line["timestamp"].is_string());
+        // no committed position yet
+        assert!(line.get("position").is_none());
+
+        syncer.lock().unwrap().committed_position = Position::PgCdc {
+            lsn: "0/406E2C30".into(),
+            timestamp: String::new(),
+        };
+        let line = JsonLogEncoder::to_json(
+            &Record::builder()
+                .args(format_args!("checkpoint"))
+                .level(Level::Warn)
+                .build(),
+            Some(&context),
+        );
+        assert_eq!(line["position"]["lsn"], "0/406E2C30");
+
+        let line = JsonLogEncoder::to_json(
+            &Record::builder()
+                .args(format_args!("no context"))
+                .level(Level::Error)
+                .target("default_logger")
+                .build(),
+            None,
+        );
+        assert_eq!(line["module"], "default_logger");
+        assert!(line.get("db").is_none());
+    }
+
+    #[test]
+    fn test_apply_to_config() {
+        let mut config: serde_yaml::Value = serde_yaml::from_str(
+            r#"
+appenders:
+  stdout:
+    kind: console
+  default_appender:
+    kind: file
+    path: "default.log"
+    encoder:
+      pattern: "{d} - {level} - {m}{n}"
+  position_appender:
+    kind: file
+    path: "position.log"
+    encoder:
+      pattern: "{d} | {m}{n}"
+"#,
+        )
+        .unwrap();
+
+        JsonLogEncoder::apply_to_config(&mut config);
+        let kind = |name: &str| {
+            config["appenders"][name]["encoder"]["kind"]
+                .as_str()
+                .map(|v| v.to_string())
+        };
+        assert_eq!(kind("stdout"), Some(JSON_LOG_ENCODER_KIND.to_string()));
+        assert_eq!(
+            kind("default_appender"),
+            Some(JSON_LOG_ENCODER_KIND.to_string())
+        );
+        assert_eq!(kind("position_appender"), None);
+    }
+}
@@ -1,7 +1,15 @@
 #![allow(clippy::too_many_arguments)]
 
+pub mod admin_server;
+pub mod config_watcher;
 pub mod extractor_util;
+pub mod integrity_verifier;
+pub mod json_log_encoder;
+pub mod metrics_server;
 pub mod parallelizer_util;
+pub mod schema_snapshot_exporter;
 pub mod sinker_util;
+pub mod target_table_creator;
+pub mod task_registry;
 pub mod task_runner;
 pub mod task_util;
@@ -0,0 +1,272 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use actix_web::{dev::ServerHandle, web, App, HttpResponse, HttpServer};
+use dt_common::{log_info, monitor::monitor::Monitor};
+
+use crate::task_registry::TaskRegistry;
+
+const METRIC_PREFIX: &str = "ape_dts";
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+// http server for scrapers and probes:
+// GET /metrics, counters of all running tasks in prometheus text format
+// GET /healthz, 200 if running, 503 once the process is shutting down
+#[derive(Clone)]
+pub struct MetricsServer {
+    pub addr: String,
+    pub task_registry: Arc<Mutex<TaskRegistry>>,
+    pub shutting_down: Arc<AtomicBool>,
+}
+
+// metric name -> (type, samples)
+#[derive(Default)]
+pub struct PrometheusMetrics {
+    metrics: BTreeMap<String, (&'static str, Vec<String>)>,
+}
+
+impl MetricsServer {
+    pub fn start(&self) -> anyhow::Result<ServerHandle> {
+        let app_data = self.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(app_data.clone()))
+                .configure(config_routes)
+        })
+        .workers(1)
+        .bind(&self.addr)?
+        .run();
+
+        let handle = server.handle();
+        tokio::spawn(server);
+        log_info!("metrics server starts, addr: {}", self.addr);
+        Ok(handle)
+    }
+
+    fn render(&self) -> String {
+        let task_handles: Vec<_> = self
+            .task_registry
+            .lock()
+            .unwrap()
+            .tasks
+            .values()
+            .cloned()
+            .collect();
+
+        let mut metrics = PrometheusMetrics::default();
+        for task_handle in task_handles.iter() {
+            let task_labels = [("task", task_handle.task_id.as_str())];
+            metrics.add(
+                "task_uptime_secs",
+                "gauge",
+                &task_labels,
+                task_handle.start_time.elapsed().as_secs() as usize,
+            );
+            metrics.add(
+                "task_paused",
+                "gauge",
+                &task_labels,
+                task_handle.is_paused() as usize,
+            );
+
+            for (component, monitor) in [
+                ("extractor", &task_handle.extractor_monitor),
+                ("pipeline", &task_handle.pipeline_monitor),
+                ("sinker", &task_handle.sinker_monitor),
+            ] {
+                metrics.add_monitor(
+                    &task_handle.task_id,
+                    component,
+                    &mut monitor.lock().unwrap(),
+                );
+            }
+        }
+        metrics.render()
+    }
+}
+
+impl PrometheusMetrics {
+    // counters of the monitor are named as ape_dts_{counter}_{aggregate}, e.g. ape_dts_cdc_lag_ms_max,
+    // table counters as ape_dts_table_records_total / ape_dts_table_bytes_total with db / tb labels
+    pub fn add_monitor(&mut self, task_id: &str, component: &str, monitor: &mut Monitor) {
+        let labels = [("task", task_id), ("component", component)];
+        for (counter_type, values) in monitor.get_statistics() {
+            for (aggregate_type, value) in values {
+                // no window counters are accumulated since the task starts
+                if aggregate_type == "latest" {
+                    let name = format!("{}_total", counter_type);
+                    self.add(&name, "counter", &labels, value);
+                } else {
+                    let name = format!(
+                        "{}_{}",
+                        counter_type,
+                        Self::get_gauge_suffix(&aggregate_type)
+                    );
+                    self.add(&name, "gauge", &labels, value);
+                }
+            }
+        }
+
+        for (tb, (record_total, data_bytes_total)) in monitor.get_table_totals() {
+            let (schema, tb) = tb.split_once('.').unwrap_or(("", tb.as_str()));
+            let labels = [
+                ("task", task_id),
+                ("component", component),
+                ("db", schema),
+                ("tb", tb),
+            ];
+            self.add("table_records_total", "counter", &labels, record_total);
+            self.add("table_bytes_total", "counter", &labels, data_bytes_total);
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        name: &str,
+        metric_type: &'static str,
+        labels: &[(&str, &str)],
+        value: usize,
+    ) {
+        let name = format!("{}_{}", METRIC_PREFIX, name);
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(k, v)| format!("{}=\"{}\"", k, Self::escape(v)))
+            .collect();
+        let sample = format!("{}{{{}}} {}", name, labels.join(","), value);
+        self.metrics
+            .entry(name)
+            .or_insert_with(|| (metric_type, Vec::new()))
+            .1
+            .push(sample);
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        for (name, (metric_type, samples)) in self.metrics.iter() {
+            let _ = writeln!(text, "# TYPE {} {}", name, metric_type);
+            for sample in samples {
+                let _ = writeln!(text, "{}", sample);
+            }
+        }
+        text
+    }
+
+    // _sum and _count are reserved for summaries and histograms
+    fn get_gauge_suffix(aggregate_type: &str) -> &str {
+        match aggregate_type {
+            "sum" => "window",
+            "count" => "window_samples",
+            _ => aggregate_type,
+        }
+    }
+
+    fn escape(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    }
+}
+
+fn config_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/metrics").route(web::get().to(get_metrics)))
+        .service(web::resource("/healthz").route(web::get().to(get_health)));
+}
+
+async fn get_metrics(server: web::Data<MetricsServer>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(CONTENT_TYPE)
+        .body(server.render())
+}
+
+async fn get_health(server: web::Data<MetricsServer>) -> HttpResponse {
+    if server.shutting_down.load(Ordering::Acquire) {
+        HttpResponse::ServiceUnavailable().body("shutting down")
+    } else {
+        HttpResponse::Ok().body("ok")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test};
+    use dt_common::monitor::counter_type::CounterType;
+
+    use super::*;
+
+    #[test]
+    fn test_add_monitor() {
+        let mut monitor = Monitor::new("pipeline", "db_1.tb_1", 10, 100, 4);
+        monitor
+            .add_counter(CounterType::SinkedCount, 10)
+            .add_counter(CounterType::DeadlockRetries, 2)
+            .add_table_counter("db_1.tb_1", 10, 1000);
+
+        let mut metrics = PrometheusMetrics::default();
+        metrics.add_monitor("db_1.tb_1", "pipeline", &mut monitor);
+        let text = metrics.render();
+
+        assert!(text.contains("# TYPE ape_dts_sinked_count_total counter\n"));
+        assert!(text.contains(
+            "ape_dts_sinked_count_total{task=\"db_1.tb_1\",component=\"pipeline\"} 10\n"
+        ));
+        assert!(text.contains("# TYPE ape_dts_deadlock_retries_window gauge\n"));
+        assert!(text.contains(
+            "ape_dts_deadlock_retries_window{task=\"db_1.tb_1\",component=\"pipeline\"} 2\n"
+        ));
+        assert!(!text.contains("_sum{"));
+        assert!(!text.contains("_count{"));
+        assert!(text.contains(
+            "ape_dts_table_records_total{task=\"db_1.tb_1\",component=\"pipeline\",db=\"db_1\",tb=\"tb_1\"} 10\n"
+        ));
+        assert!(text.contains(
+            "ape_dts_table_bytes_total{task=\"db_1.tb_1\",component=\"pipeline\",db=\"db_1\",tb=\"tb_1\"} 1000\n"
+        ));
+        // one TYPE line for each metric
+        assert_eq!(
+            text.matches("# TYPE ape_dts_table_records_total").count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(PrometheusMetrics::escape(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(PrometheusMetrics::escape("a\nb"), "a\\nb");
+    }
+
+    #[actix_web::test]
+    async fn test_metrics_server() {
+        let shutting_down = Arc::new(AtomicBool::new(false));
+        let server = MetricsServer {
+            addr: String::new(),
+            task_registry: Arc::new(Mutex::new(TaskRegistry::default())),
+            shutting_down: shutting_down.clone(),
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(server))
+                .configure(config_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get().uri("/healthz").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        shutting_down.store(true, Ordering::Release);
+        let req = test::TestRequest::get().uri("/healthz").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -3,22 +3,26 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use anyhow::bail;
 use dt_common::{
     config::{
-        config_enums::{DbType, ParallelType},
+        config_enums::{DbType, ParallelType, SnapshotPartitionMode},
         sinker_config::SinkerConfig,
         task_config::TaskConfig,
     },
+    error::Error,
     monitor::monitor::Monitor,
 };
 use dt_common::{meta::redis::command::key_parser::KeyParser, utils::redis_util::RedisUtil};
 use dt_parallelizer::{
     base_parallelizer::BaseParallelizer, check_parallelizer::CheckParallelizer,
-    foxlake_parallelizer::FoxlakeParallelizer, merge_parallelizer::MergeParallelizer,
-    mongo_merger::MongoMerger, partition_parallelizer::PartitionParallelizer,
-    rdb_merger::RdbMerger, rdb_partitioner::RdbPartitioner, redis_parallelizer::RedisParallelizer,
+    data_skew_analyzer::DataSkewAnalyzer, foxlake_parallelizer::FoxlakeParallelizer,
+    merge_parallelizer::MergeParallelizer, mongo_merger::MongoMerger,
+    partition_parallelizer::PartitionParallelizer, rdb_merger::RdbMerger,
+    rdb_partitioner::RdbPartitioner, redis_parallelizer::RedisParallelizer,
     serial_parallelizer::SerialParallelizer, snapshot_parallelizer::SnapshotParallelizer,
-    table_parallelizer::TableParallelizer, Merger, Parallelizer,
+    table_parallelizer::TableParallelizer, transaction_parallelizer::TransactionParallelizer,
+    Merger, Parallelizer,
 };
 use ratelimit::Ratelimiter;
 
@@ -30,7 +34,7 @@ impl ParallelizerUtil {
     pub async fn create_parallelizer(
         config: &TaskConfig,
         monitor: Arc<Mutex<Monitor>>,
-        rps_limiter: Option<Ratelimiter>,
+        rps_limiter: Option<Arc<Ratelimiter>>,
     ) -> anyhow::Result<Box<dyn Parallelizer + Send>> {
         let parallel_size = config.parallelizer.parallel_size;
         let parallel_type = &config.parallelizer.parallel_type;
@@ -38,13 +42,35 @@ impl ParallelizerUtil {
             poped_data: VecDeque::new(),
             monitor: monitor.clone(),
             rps_limiter,
+            chunk_transaction: config.parallelizer.chunk_transaction,
         };
 
         let parallelizer: Box<dyn Parallelizer + Send> = match parallel_type {
-            ParallelType::Snapshot => Box::new(SnapshotParallelizer {
-                base_parallelizer,
-                parallel_size,
-            }),
+            ParallelType::Snapshot => {
+                let partition_mode = config.parallelizer.snapshot_partition_mode.clone();
+                let meta_manager = match partition_mode {
+                    SnapshotPartitionMode::Position => None,
+                    SnapshotPartitionMode::IdCols => {
+                        match TaskUtil::create_rdb_meta_manager(config).await? {
+                            Some(meta_manager) => Some(meta_manager),
+                            None => bail! {Error::ConfigError(
+                                "snapshot_partition_mode=id_cols is only supported by rdb sinkers"
+                                    .into()
+                            )},
+                        }
+                    }
+                };
+                let mut skew_analyzer = Self::create_skew_analyzer(config, monitor.clone());
+                // rebalancing moves rows among partitions and breaks the order of rows by key
+                skew_analyzer.rebalance &= partition_mode == SnapshotPartitionMode::Position;
+                Box::new(SnapshotParallelizer {
+                    base_parallelizer,
+                    parallel_size,
+                    skew_analyzer,
+                    partition_mode,
+                    meta_manager,
+                })
+            }
 
             ParallelType::RdbPartition => {
                 let partitioner = Self::create_rdb_partitioner(config).await?;
@@ -55,6 +81,21 @@ impl ParallelizerUtil {
                 })
             }
 
+            ParallelType::Transaction => {
+                let meta_manager = match TaskUtil::create_rdb_meta_manager(config).await? {
+                    Some(meta_manager) => meta_manager,
+                    None => bail! {Error::ConfigError(
+                        "parallel_type=transaction is only supported by rdb sinkers".into()
+                    )},
+                };
+                Box::new(TransactionParallelizer {
+                    base_parallelizer,
+                    parallel_size,
+                    meta_manager,
+                    tx_row_counts: Vec::new(),
+                })
+            }
+
             ParallelType::RdbMerge => {
                 let merger = Self::create_rdb_merger(config).await?;
                 let meta_manager = TaskUtil::create_rdb_meta_manager(config).await?;
@@ -119,6 +160,9 @@ impl ParallelizerUtil {
                 let snapshot_parallelizer = SnapshotParallelizer {
                     base_parallelizer,
                     parallel_size,
+                    skew_analyzer: Self::create_skew_analyzer(config, monitor.clone()),
+                    partition_mode: SnapshotPartitionMode::Position,
+                    meta_manager: None,
                 };
                 Box::new(FoxlakeParallelizer {
                     task_config: config.clone(),
@@ -129,6 +173,14 @@ impl ParallelizerUtil {
         Ok(parallelizer)
     }
 
+    fn create_skew_analyzer(config: &TaskConfig, monitor: Arc<Mutex<Monitor>>) -> DataSkewAnalyzer {
+        DataSkewAnalyzer::new(
+            config.parallelizer.skew_threshold,
+            config.parallelizer.skew_rebalance,
+            monitor,
+        )
+    }
+
     async fn create_rdb_merger(
         config: &TaskConfig,
     ) -> anyhow::Result<Box<dyn Merger + Send + Sync>> {
@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use anyhow::bail;
+use dt_common::{
+    config::{config_enums::DbType, task_config::TaskConfig},
+    error::Error,
+    log_info,
+    meta::{
+        mysql::mysql_meta_manager::MysqlMetaManager, struct_meta::schema_snapshot::SchemaSnapshot,
+    },
+    rdb_filter::RdbFilter,
+};
+use dt_connector::meta_fetcher::{
+    mysql::mysql_struct_fetcher::MysqlStructFetcher, pg::pg_struct_fetcher::PgStructFetcher,
+};
+
+use crate::task_util::TaskUtil;
+
+// export structures of all filtered schemas in source to a json file
+pub struct SchemaSnapshotExporter {
+    pub config: TaskConfig,
+}
+
+impl SchemaSnapshotExporter {
+    pub async fn new(task_config_file: &str) -> anyhow::Result<Self> {
+        let mut config = TaskConfig::new(task_config_file)?;
+        TaskUtil::resolve_credentials(&mut config).await?;
+        Ok(Self { config })
+    }
+
+    pub async fn export(&self, output_file: &str) -> anyhow::Result<()> {
+        let db_type = &self.config.extractor_basic.db_type;
+        let url = &self.config.extractor_basic.url;
+        let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&self.config.runtime.log_level);
+        let mut filter = RdbFilter::from_config(&self.config.filter, db_type)?;
+
+        let mut snapshot = SchemaSnapshot {
+            db_type: db_type.clone(),
+            ..Default::default()
+        };
+
+        let mut schemas = Vec::new();
+        for schema in TaskUtil::list_schemas(url, db_type).await? {
+            if filter.filter_schema(&schema) {
+                log_info!("schema: {} filtered", schema);
+                continue;
+            }
+            schemas.push(schema);
+        }
+
+        match db_type {
+            DbType::Mysql => {
+                let conn_pool = TaskUtil::create_mysql_conn_pool(url, 2, enable_sqlx_log).await?;
+                let meta_manager = MysqlMetaManager::new(conn_pool.clone()).await?;
+                for db in schemas {
+                    let mut fetcher = MysqlStructFetcher {
+                        conn_pool: conn_pool.clone(),
+                        db,
+                        filter: Some(filter.clone()),
+                        meta_manager: meta_manager.clone(),
+                    };
+                    snapshot
+                        .mysql_databases
+                        .push(fetcher.get_create_database_statement().await?);
+                    snapshot
+                        .mysql_tables
+                        .extend(fetcher.get_create_table_statements("").await?);
+                }
+                conn_pool.close().await;
+            }
+
+            DbType::Pg => {
+                let conn_pool = TaskUtil::create_pg_conn_pool(url, 2, enable_sqlx_log).await?;
+                for schema in schemas {
+                    let mut fetcher = PgStructFetcher {
+                        conn_pool: conn_pool.clone(),
+                        schema,
+                        filter: Some(filter.clone()),
+                    };
+                    snapshot
+                        .pg_schemas
+                        .push(fetcher.get_create_schema_statement().await?);
+                    snapshot
+                        .pg_tables
+                        .extend(fetcher.get_create_table_statements("").await?);
+                }
+                conn_pool.close().await;
+            }
+
+            _ => bail! {Error::ConfigError(format!(
+                "schema export not supported for db_type: {}",
+                db_type
+            ))},
+        }
+
+        snapshot.save(Path::new(output_file))?;
+        log_info!(
+            "schema snapshot exported to: {}, tables: {}",
+            output_file,
+            snapshot.mysql_tables.len() + snapshot.pg_tables.len()
+        );
+        Ok(())
+    }
+}
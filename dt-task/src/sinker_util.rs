@@ -1,14 +1,20 @@
 use std::{
+    collections::{HashMap, HashSet},
     str::FromStr,
     sync::{Arc, Mutex, RwLock},
 };
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use dt_common::{
     config::{
-        config_enums::DbType, extractor_config::ExtractorConfig, sinker_config::SinkerConfig,
+        config_enums::{CollationPolicy, ConflictPolicyEnum, DbType},
+        conflict_cols_config::ConflictColsConfig,
+        dml_function_config::DmlFunctionConfig,
+        extractor_config::ExtractorConfig,
+        sinker_config::SinkerConfig,
         task_config::TaskConfig,
     },
+    error::Error,
     meta::redis::command::key_parser::KeyParser,
     monitor::monitor::Monitor,
     rdb_filter::RdbFilter,
@@ -18,7 +24,10 @@ use dt_common::{
         avro::avro_converter::AvroConverter,
         mysql::mysql_meta_manager::MysqlMetaManager,
         pg::pg_meta_manager::PgMetaManager,
-        redis::{redis_statistic_type::RedisStatisticType, redis_write_method::RedisWriteMethod},
+        redis::{
+            redis_expired_key_policy::RedisExpiredKeyPolicy,
+            redis_statistic_type::RedisStatisticType, redis_write_method::RedisWriteMethod,
+        },
     },
     utils::redis_util::RedisUtil,
 };
@@ -27,9 +36,13 @@ use dt_connector::{
     data_marker::DataMarker,
     rdb_router::RdbRouter,
     sinker::{
+        batch_sizer::BatchSizer,
         clickhouse::{
             clickhouse_sinker::ClickhouseSinker, clickhouse_struct_sinker::ClickhouseStructSinker,
         },
+        connection_pool_metrics::ConnectionPoolMetrics,
+        csv_sinker::CsvSinker,
+        dead_letter_queue::DeadLetterQueue,
         dummy_sinker::DummySinker,
         foxlake::{
             foxlake_merger::FoxlakeMerger, foxlake_pusher::FoxlakePusher,
@@ -41,8 +54,13 @@ use dt_connector::{
         mysql::{
             mysql_checker::MysqlChecker, mysql_sinker::MysqlSinker,
             mysql_struct_sinker::MysqlStructSinker,
+            server_side_migration_sinker::ServerSideMigrationSinker,
+        },
+        parquet_sinker::ParquetSinker,
+        pg::{
+            pg_checker::PgChecker, pg_partman_router::PgPartmanRouter, pg_sinker::PgSinker,
+            pg_struct_sinker::PgStructSinker,
         },
-        pg::{pg_checker::PgChecker, pg_sinker::PgSinker, pg_struct_sinker::PgStructSinker},
         redis::{redis_sinker::RedisSinker, redis_statistic_sinker::RedisStatisticSinker},
         sql_sinker::SqlSinker,
         starrocks::{
@@ -70,6 +88,7 @@ impl SinkerUtil {
         extractor_config: &ExtractorConfig,
         monitor: Arc<Mutex<Monitor>>,
         data_marker: Option<Arc<RwLock<DataMarker>>>,
+        dead_letter_queue: Option<Arc<async_mutex::Mutex<DeadLetterQueue>>>,
     ) -> anyhow::Result<Sinkers> {
         let log_level = &task_config.runtime.log_level;
         let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(log_level);
@@ -87,13 +106,19 @@ impl SinkerUtil {
             SinkerConfig::Mysql {
                 url,
                 batch_size,
-                replace,
+                conflict_mode,
+                json_merge,
             } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Mysql)?;
                 let conn_pool =
                     TaskUtil::create_mysql_conn_pool(&url, parallel_size * 2, enable_sqlx_log)
                         .await?;
                 let meta_manager = MysqlMetaManager::new(conn_pool.clone()).await?;
+                let pool_metrics = ConnectionPoolMetrics::new(
+                    conn_pool.clone(),
+                    task_config.sinker_basic.max_acceptable_wait_ms,
+                    monitor.clone(),
+                );
                 // to avoid contention for monitor write lock between sinker threads,
                 // create a monitor for each sinker instead of sharing a single monitor between sinkers,
                 // sometimes a sinker may cost several millis to get write lock for a global monitor.
@@ -103,21 +128,39 @@ impl SinkerUtil {
                         conn_pool: conn_pool.clone(),
                         meta_manager: meta_manager.clone(),
                         router: router.clone(),
-                        batch_size,
+                        batch_sizer: BatchSizer::new(batch_size, &task_config.sinker_basic),
                         monitor: monitor.clone(),
                         data_marker: data_marker.clone(),
-                        replace,
+                        conflict_mode: conflict_mode.clone(),
+                        json_merge,
+                        pool_metrics: pool_metrics.clone(),
+                        deadlock_max_retries: task_config.sinker_basic.deadlock_max_retries,
+                        deadlock_retry_interval_ms: task_config
+                            .sinker_basic
+                            .deadlock_retry_interval_ms,
+                        reconnect_max_wait_secs: task_config.sinker_basic.reconnect_max_wait_secs,
+                        reconnect_interval_ms: task_config.sinker_basic.reconnect_interval_ms,
+                        dead_letter_queue: dead_letter_queue.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
             }
 
+            SinkerConfig::MysqlServerSide { .. } => {
+                bail! {Error::ConfigError(
+                    "server side migration is started by TaskRunner without sinkers".into()
+                )}
+            }
+
             SinkerConfig::MysqlCheck {
-                url, batch_size, ..
+                url,
+                batch_size,
+                sample_percent,
+                ..
             } => {
                 // checker needs the reverse router
                 let reverse_router =
-                    RdbRouter::from_config(&task_config.router, &DbType::Mysql)?.reverse();
+                    RdbRouter::from_config(&task_config.router, &DbType::Mysql)?.reverse()?;
                 let filter = RdbFilter::from_config(&task_config.filter, &DbType::Mysql)?;
                 let extractor_meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
                     .await?
@@ -137,6 +180,7 @@ impl SinkerUtil {
                         filter: filter.clone(),
                         batch_size,
                         monitor: monitor.clone(),
+                        sample_percent,
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -145,12 +189,55 @@ impl SinkerUtil {
             SinkerConfig::Pg {
                 url,
                 batch_size,
-                replace,
+                conflict_mode,
+                warn_on_unlogged,
+                use_dml_functions,
+                dml_functions,
+                table_lock,
+                lock_timeout_ms,
+                use_advisory_locks,
+                lock_retry_count,
+                lock_retry_delay_ms,
+                collation_mismatch_policy,
+                partman_aware,
+                prepared_statement_cache_size,
+                exclude_conflict_policy,
+                conflict_cols,
             } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Pg)?;
-                let conn_pool =
-                    TaskUtil::create_pg_conn_pool(&url, parallel_size * 2, enable_sqlx_log).await?;
-                let meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
+                let src_db_type = &task_config.extractor_basic.db_type;
+                // collation names differ between mysql and pg, only compare them for pg -> pg
+                let src_meta_manager = if collation_mismatch_policy != CollationPolicy::Ignore
+                    && *src_db_type == DbType::Pg
+                {
+                    ExtractorUtil::get_extractor_meta_manager(task_config)
+                        .await?
+                        .and_then(|meta_manager| meta_manager.pg_meta_manager)
+                } else {
+                    None
+                };
+                // only needed to map target tables back to the source for collation checks
+                let reverse_router = if src_meta_manager.is_some() {
+                    router.reverse()?
+                } else {
+                    RdbRouter::default()
+                };
+                let dml_functions = DmlFunctionConfig::parse_config(&dml_functions)?;
+                let conflict_cols = ConflictColsConfig::parse_config(&conflict_cols)?;
+                let conn_pool = TaskUtil::create_pg_conn_pool_with_statement_cache(
+                    &url,
+                    parallel_size * 2,
+                    enable_sqlx_log,
+                    prepared_statement_cache_size,
+                )
+                .await?;
+                let mut meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
+                PgSinker::check_conflict_cols(&mut meta_manager, &conflict_cols).await?;
+                let pool_metrics = ConnectionPoolMetrics::new(
+                    conn_pool.clone(),
+                    task_config.sinker_basic.max_acceptable_wait_ms,
+                    monitor.clone(),
+                );
 
                 for _ in 0..parallel_size {
                     let sinker = PgSinker {
@@ -158,21 +245,53 @@ impl SinkerUtil {
                         conn_pool: conn_pool.clone(),
                         meta_manager: meta_manager.clone(),
                         router: router.clone(),
-                        batch_size,
+                        batch_sizer: BatchSizer::new(batch_size, &task_config.sinker_basic),
                         monitor: monitor.clone(),
                         data_marker: data_marker.clone(),
-                        replace,
+                        conflict_mode: conflict_mode.clone(),
+                        warn_on_unlogged,
+                        reported_unlogged_tbs: HashSet::new(),
+                        use_dml_functions,
+                        dml_functions: dml_functions.clone(),
+                        table_lock: table_lock.clone(),
+                        lock_timeout_ms,
+                        use_advisory_locks,
+                        lock_retry_count,
+                        lock_retry_delay_ms,
+                        apply_collations: *src_db_type != DbType::Pg,
+                        collation_mismatch_policy: collation_mismatch_policy.clone(),
+                        src_meta_manager: src_meta_manager.clone(),
+                        reverse_router: reverse_router.clone(),
+                        checked_collation_tbs: HashSet::new(),
+                        partman_aware,
+                        partman_router: PgPartmanRouter::new(conn_pool.clone()),
+                        ignore_exclude_conflicts: exclude_conflict_policy
+                            == ConflictPolicyEnum::Ignore,
+                        conflict_cols: conflict_cols.clone(),
+                        pool_metrics: pool_metrics.clone(),
+                        chunk_tx: None,
+                        in_chunk_tx: false,
+                        deadlock_max_retries: task_config.sinker_basic.deadlock_max_retries,
+                        deadlock_retry_interval_ms: task_config
+                            .sinker_basic
+                            .deadlock_retry_interval_ms,
+                        reconnect_max_wait_secs: task_config.sinker_basic.reconnect_max_wait_secs,
+                        reconnect_interval_ms: task_config.sinker_basic.reconnect_interval_ms,
+                        dead_letter_queue: dead_letter_queue.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
             }
 
             SinkerConfig::PgCheck {
-                url, batch_size, ..
+                url,
+                batch_size,
+                sample_percent,
+                ..
             } => {
                 // checker needs the reverse router
                 let reverse_router =
-                    RdbRouter::from_config(&task_config.router, &DbType::Pg)?.reverse();
+                    RdbRouter::from_config(&task_config.router, &DbType::Pg)?.reverse()?;
                 let filter = RdbFilter::from_config(&task_config.filter, &DbType::Pg)?;
                 let extractor_meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
                     .await?
@@ -191,6 +310,7 @@ impl SinkerUtil {
                         filter: filter.clone(),
                         batch_size,
                         monitor: monitor.clone(),
+                        sample_percent,
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -218,10 +338,11 @@ impl SinkerUtil {
                 url,
                 app_name,
                 batch_size,
+                sample_percent,
                 ..
             } => {
                 let reverse_router =
-                    RdbRouter::from_config(&task_config.router, &DbType::Mongo)?.reverse();
+                    RdbRouter::from_config(&task_config.router, &DbType::Mongo)?.reverse()?;
                 for _ in 0..parallel_size {
                     let mongo_client = TaskUtil::create_mongo_client(&url, &app_name).await?;
                     let sinker = MongoChecker {
@@ -229,6 +350,7 @@ impl SinkerUtil {
                         reverse_router: reverse_router.clone(),
                         mongo_client,
                         monitor: monitor.clone(),
+                        sample_percent,
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -240,6 +362,9 @@ impl SinkerUtil {
                 ack_timeout_secs,
                 required_acks,
                 with_field_defs,
+                message_format,
+                partition_key,
+                ddl_topic,
             } => {
                 let router = RdbRouter::from_config(
                     &task_config.router,
@@ -273,6 +398,9 @@ impl SinkerUtil {
                         producer,
                         avro_converter: avro_converter.clone(),
                         monitor: monitor.clone(),
+                        message_format: message_format.clone(),
+                        partition_key: partition_key.clone(),
+                        ddl_topic: ddl_topic.clone(),
                     };
                     sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
                 }
@@ -309,6 +437,7 @@ impl SinkerUtil {
                     conflict_policy: conflict_policy.clone(),
                     filter: filter.clone(),
                     router,
+                    session_sqls: Vec::new(),
                 };
                 sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
             }
@@ -318,12 +447,15 @@ impl SinkerUtil {
                 batch_size,
                 method,
                 is_cluster,
+                ignore_ttl,
+                expired_key_policy,
             } => {
                 // redis sinker may need meta data from RDB extractor
                 let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
                 let mut conn = RedisUtil::create_redis_conn(&url).await?;
                 let version = RedisUtil::get_redis_version(&mut conn)?;
                 let method = RedisWriteMethod::from_str(&method)?;
+                let expired_key_policy = RedisExpiredKeyPolicy::from_str(&expired_key_policy)?;
 
                 if is_cluster {
                     let url_info = Url::parse(&url)?;
@@ -345,6 +477,8 @@ impl SinkerUtil {
                             now_db_id: -1,
                             version,
                             method: method.clone(),
+                            ignore_ttl,
+                            expired_key_policy: expired_key_policy.clone(),
                             meta_manager: meta_manager.clone(),
                             monitor: monitor.clone(),
                             data_marker: data_marker.clone(),
@@ -362,6 +496,8 @@ impl SinkerUtil {
                             now_db_id: -1,
                             version,
                             method: method.clone(),
+                            ignore_ttl,
+                            expired_key_policy: expired_key_policy.clone(),
                             meta_manager: meta_manager.clone(),
                             monitor: monitor.clone(),
                             data_marker: data_marker.clone(),
@@ -542,6 +678,73 @@ impl SinkerUtil {
                 }
             }
 
+            SinkerConfig::Csv {
+                output_dir,
+                delimiter,
+                null_token,
+                ignore_non_insert,
+            } => {
+                let router = RdbRouter::from_config(
+                    &task_config.router,
+                    &task_config.extractor_basic.db_type,
+                )?;
+                let reverse_router = router.reverse()?;
+                std::fs::create_dir_all(&output_dir)
+                    .with_context(|| format!("failed to create csv output_dir: {}", output_dir))?;
+                let writers = Arc::new(Mutex::new(HashMap::new()));
+
+                for _ in 0..parallel_size {
+                    let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
+                        .await?
+                        .unwrap();
+                    let sinker = CsvSinker {
+                        meta_manager,
+                        router: router.clone(),
+                        reverse_router: reverse_router.clone(),
+                        output_dir: output_dir.clone(),
+                        delimiter: delimiter.clone(),
+                        null_token: null_token.clone(),
+                        ignore_non_insert,
+                        writers: writers.clone(),
+                        monitor: monitor.clone(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
+            SinkerConfig::Parquet {
+                output_dir,
+                batch_size,
+                ignore_non_insert,
+            } => {
+                let router = RdbRouter::from_config(
+                    &task_config.router,
+                    &task_config.extractor_basic.db_type,
+                )?;
+                let reverse_router = router.reverse()?;
+                std::fs::create_dir_all(&output_dir).with_context(|| {
+                    format!("failed to create parquet output_dir: {}", output_dir)
+                })?;
+                let tables = Arc::new(Mutex::new(HashMap::new()));
+
+                for _ in 0..parallel_size {
+                    let meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config)
+                        .await?
+                        .unwrap();
+                    let sinker = ParquetSinker {
+                        meta_manager,
+                        router: router.clone(),
+                        reverse_router: reverse_router.clone(),
+                        output_dir: output_dir.clone(),
+                        batch_size,
+                        ignore_non_insert,
+                        tables: tables.clone(),
+                        monitor: monitor.clone(),
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
+
             SinkerConfig::Foxlake {
                 url,
                 batch_size,
@@ -550,7 +753,7 @@ impl SinkerUtil {
                 engine,
             } => {
                 let router = RdbRouter::from_config(&task_config.router, &DbType::Mysql)?;
-                let reverse_router = router.reverse();
+                let reverse_router = router.reverse()?;
                 let conn_pool =
                     TaskUtil::create_mysql_conn_pool(&url, parallel_size * 2, enable_sqlx_log)
                         .await?;
@@ -617,7 +820,7 @@ impl SinkerUtil {
                         .await?;
                 let s3_client: S3Client = TaskUtil::create_s3_client(&s3_config);
                 let reverse_router =
-                    RdbRouter::from_config(&task_config.router, &DbType::Mysql)?.reverse();
+                    RdbRouter::from_config(&task_config.router, &DbType::Mysql)?.reverse()?;
                 let orc_sequencer = Arc::new(Mutex::new(OrcSequencer::new()));
 
                 for _ in 0..parallel_size {
@@ -693,4 +896,30 @@ impl SinkerUtil {
         };
         Ok(sub_sinkers)
     }
+
+    pub async fn create_server_side_migration_sinker(
+        task_config: &TaskConfig,
+        monitor: Arc<Mutex<Monitor>>,
+    ) -> anyhow::Result<ServerSideMigrationSinker> {
+        let (url, batch_size) = match &task_config.sinker {
+            SinkerConfig::MysqlServerSide { url, batch_size } => (url, *batch_size),
+            _ => bail! {Error::ConfigError(
+                "sinker config is not for server side migration".into()
+            )},
+        };
+
+        let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&task_config.runtime.log_level);
+        let filter = RdbFilter::from_config(&task_config.filter, &DbType::Mysql)?;
+        let router = RdbRouter::from_config(&task_config.router, &DbType::Mysql)?;
+        let conn_pool = TaskUtil::create_mysql_conn_pool(url, 2, enable_sqlx_log).await?;
+        let meta_manager = MysqlMetaManager::new(conn_pool.clone()).await?;
+        Ok(ServerSideMigrationSinker {
+            conn_pool,
+            meta_manager,
+            router,
+            filter,
+            batch_size,
+            monitor,
+        })
+    }
 }
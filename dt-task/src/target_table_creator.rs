@@ -0,0 +1,731 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{self, File},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+use dt_common::{
+    config::{
+        config_enums::{ConflictPolicyEnum, DbType},
+        filter_config::FilterConfig,
+        sinker_config::BasicSinkerConfig,
+    },
+    log_info,
+    meta::{
+        mysql::mysql_meta_manager::MysqlMetaManager,
+        struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
+    },
+    rdb_filter::RdbFilter,
+};
+use dt_connector::{
+    meta_fetcher::{
+        mysql::mysql_struct_fetcher::MysqlStructFetcher, pg::pg_struct_fetcher::PgStructFetcher,
+    },
+    rdb_router::RdbRouter,
+    sinker::base_struct_sinker::{BaseStructSinker, DBConnPool},
+};
+
+use crate::task_util::TaskUtil;
+
+// create tables missing in target from source structures before snapshot,
+// tables already existing in target are kept as they are
+pub struct TargetTableCreator<'a> {
+    pub src_url: String,
+    pub dst_url: String,
+    pub db_type: DbType,
+    pub router: &'a RdbRouter,
+    pub filter: RdbFilter,
+    // tables whose secondary indexes and constraints are created after snapshot,
+    // None means no table
+    pub defer_indexes_filter: Option<RdbFilter>,
+    // deferred sqls not executed yet are kept in this file, so a restarted task
+    // still creates them for the tables created by the previous run
+    pub deferred_log: String,
+    // the file left by the previous run, loaded if resume_from_log
+    pub resume_deferred_log: Option<String>,
+    pub enable_sqlx_log: bool,
+}
+
+impl TargetTableCreator<'_> {
+    pub fn build_defer_indexes_filter(
+        config: &BasicSinkerConfig,
+        db_type: &DbType,
+    ) -> anyhow::Result<Option<RdbFilter>> {
+        if !config.defer_indexes {
+            return Ok(None);
+        }
+        let filter_config = FilterConfig {
+            do_tbs: config.defer_indexes_tbs.clone(),
+            ..Default::default()
+        };
+        Ok(Some(RdbFilter::from_config(&filter_config, db_type)?))
+    }
+
+    // returns the sqls of deferred indexes and constraints, including those
+    // not created by the previous run
+    pub async fn create<'b>(
+        &mut self,
+        tbs: impl Iterator<Item = &'b (String, String)>,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut sqls = match &self.resume_deferred_log {
+            Some(resume_deferred_log) => Self::load_deferred_sqls(resume_deferred_log)?,
+            None => Vec::new(),
+        };
+        if !sqls.is_empty() {
+            log_info!(
+                "{} deferred indexes and constraints left by the previous run",
+                sqls.len()
+            );
+        }
+
+        let schema_tbs = Self::group_by_schema(tbs);
+        let deferred = match self.db_type {
+            DbType::Mysql => self.create_mysql_tbs(&schema_tbs).await?,
+            DbType::Pg => self.create_pg_tbs(&schema_tbs).await?,
+            _ => Vec::new(),
+        };
+        for mut struct_data in deferred {
+            for (_, sql) in struct_data.statement.to_sqls(&self.filter)? {
+                if !sqls.contains(&sql) {
+                    sqls.push(sql);
+                }
+            }
+        }
+
+        Self::save_deferred_sqls(&self.deferred_log, &sqls)?;
+        Ok(sqls)
+    }
+
+    pub async fn create_deferred(&self, mut sqls: Vec<String>) -> anyhow::Result<()> {
+        log_info!("creating {} deferred indexes and constraints", sqls.len());
+        let conn_pool = match self.db_type {
+            DbType::Mysql => DBConnPool::MySQL(
+                TaskUtil::create_mysql_conn_pool(&self.dst_url, 1, self.enable_sqlx_log).await?,
+            ),
+            _ => DBConnPool::PostgreSQL(
+                TaskUtil::create_pg_conn_pool(&self.dst_url, 1, self.enable_sqlx_log).await?,
+            ),
+        };
+        // remove each sql from the file once executed, so a task stopped halfway
+        // does not execute it again on restart
+        while !sqls.is_empty() {
+            let sql = sqls.remove(0);
+            log_info!("ddl begin: {}", sql);
+            BaseStructSinker::execute(&conn_pool, &sql).await?;
+            log_info!("ddl succeed");
+            Self::save_deferred_sqls(&self.deferred_log, &sqls)?;
+        }
+        match conn_pool {
+            DBConnPool::MySQL(conn_pool) => conn_pool.close().await,
+            DBConnPool::PostgreSQL(conn_pool) => conn_pool.close().await,
+        }
+        Ok(())
+    }
+
+    // each line is a json string since sqls may contain line breaks
+    fn load_deferred_sqls(path: &str) -> anyhow::Result<Vec<String>> {
+        if !Path::new(path).exists() {
+            return Ok(Vec::new());
+        }
+        let mut sqls = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            sqls.push(serde_json::from_str(&line)?);
+        }
+        Ok(sqls)
+    }
+
+    fn save_deferred_sqls(path: &str, sqls: &[String]) -> anyhow::Result<()> {
+        if sqls.is_empty() {
+            if Path::new(path).exists() {
+                fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        if let Some(dir) = Path::new(path).parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // write to a temp file and rename, so the file is never half written
+        let tmp_path = format!("{}.tmp", path);
+        let mut file = File::create(&tmp_path)?;
+        for sql in sqls {
+            writeln!(file, "{}", serde_json::to_string(sql)?)?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    async fn create_mysql_tbs(
+        &mut self,
+        schema_tbs: &BTreeMap<String, HashSet<String>>,
+    ) -> anyhow::Result<Vec<StructData>> {
+        let src_conn_pool =
+            TaskUtil::create_mysql_conn_pool(&self.src_url, 2, self.enable_sqlx_log).await?;
+        let dst_conn_pool =
+            TaskUtil::create_mysql_conn_pool(&self.dst_url, 1, self.enable_sqlx_log).await?;
+        let meta_manager = MysqlMetaManager::new(src_conn_pool.clone()).await?;
+        let dst_schemas = TaskUtil::list_schemas(&self.dst_url, &self.db_type).await?;
+
+        let mut deferred = Vec::new();
+        for (schema, tbs) in schema_tbs {
+            let mut fetcher = MysqlStructFetcher {
+                conn_pool: src_conn_pool.clone(),
+                db: schema.clone(),
+                filter: Some(self.filter.clone()),
+                meta_manager: meta_manager.clone(),
+            };
+            let tb_statements = fetcher
+                .get_create_table_statements("")
+                .await?
+                .into_iter()
+                .filter(|i| tbs.contains(&i.table.table_name))
+                .map(StructStatement::MysqlCreateTable)
+                .collect();
+            let database_statement = fetcher.get_create_database_statement().await?;
+
+            let mut schema_statements = Vec::new();
+            for dst_schema in self.get_missing_dst_schemas(schema, tbs, &dst_schemas) {
+                let mut statement = database_statement.clone();
+                statement.route(&dst_schema);
+                schema_statements.push(StructStatement::MysqlCreateDatabase(statement));
+            }
+
+            let tb_deferred = self
+                .create_missing_tbs(
+                    &DBConnPool::MySQL(dst_conn_pool.clone()),
+                    schema,
+                    schema_statements,
+                    tb_statements,
+                )
+                .await?;
+            deferred.extend(tb_deferred);
+        }
+
+        src_conn_pool.close().await;
+        dst_conn_pool.close().await;
+        Ok(deferred)
+    }
+
+    async fn create_pg_tbs(
+        &mut self,
+        schema_tbs: &BTreeMap<String, HashSet<String>>,
+    ) -> anyhow::Result<Vec<StructData>> {
+        let src_conn_pool =
+            TaskUtil::create_pg_conn_pool(&self.src_url, 2, self.enable_sqlx_log).await?;
+        let dst_conn_pool =
+            TaskUtil::create_pg_conn_pool(&self.dst_url, 1, self.enable_sqlx_log).await?;
+        let dst_schemas = TaskUtil::list_schemas(&self.dst_url, &self.db_type).await?;
+
+        let mut deferred = Vec::new();
+        for (schema, tbs) in schema_tbs {
+            let mut fetcher = PgStructFetcher {
+                conn_pool: src_conn_pool.clone(),
+                schema: schema.clone(),
+                filter: Some(self.filter.clone()),
+            };
+            let tb_statements = fetcher
+                .get_create_table_statements("")
+                .await?
+                .into_iter()
+                .filter(|i| tbs.contains(&i.table.table_name))
+                .map(StructStatement::PgCreateTable)
+                .collect();
+            let schema_statement = fetcher.get_create_schema_statement().await?;
+
+            // domains are only created along with a new schema since they
+            // can not be created with IF NOT EXISTS
+            let mut schema_statements = Vec::new();
+            for dst_schema in self.get_missing_dst_schemas(schema, tbs, &dst_schemas) {
+                let mut statement = schema_statement.clone();
+                statement.route(&dst_schema);
+                schema_statements.push(StructStatement::PgCreateSchema(statement));
+                for mut domain_statement in fetcher.get_create_domain_statements().await? {
+                    domain_statement.route(&dst_schema);
+                    schema_statements.push(StructStatement::PgCreateDomain(domain_statement));
+                }
+            }
+
+            let tb_deferred = self
+                .create_missing_tbs(
+                    &DBConnPool::PostgreSQL(dst_conn_pool.clone()),
+                    schema,
+                    schema_statements,
+                    tb_statements,
+                )
+                .await?;
+            deferred.extend(tb_deferred);
+        }
+
+        src_conn_pool.close().await;
+        dst_conn_pool.close().await;
+        Ok(deferred)
+    }
+
+    async fn create_missing_tbs(
+        &mut self,
+        conn_pool: &DBConnPool,
+        schema: &str,
+        schema_statements: Vec<StructStatement>,
+        tb_statements: Vec<StructStatement>,
+    ) -> anyhow::Result<Vec<StructData>> {
+        // existing tables of the routed target schemas
+        let mut dst_tbs: HashMap<String, Vec<String>> = HashMap::new();
+        for statement in tb_statements.iter() {
+            if let Some((src_schema, src_tb)) = Self::get_schema_tb(statement) {
+                let dst_schema = self.router.get_tb_map(&src_schema, &src_tb).0.to_string();
+                if !dst_tbs.contains_key(&dst_schema) {
+                    let tbs = TaskUtil::list_tbs(&self.dst_url, &dst_schema, &self.db_type).await?;
+                    dst_tbs.insert(dst_schema, tbs);
+                }
+            }
+        }
+
+        let (data, deferred) =
+            self.get_missing_tb_structs(schema, schema_statements, tb_statements, &dst_tbs);
+        if data.is_empty() {
+            return Ok(deferred);
+        }
+
+        BaseStructSinker::sink_structs(
+            conn_pool,
+            &ConflictPolicyEnum::Interrupt,
+            data,
+            &self.filter,
+        )
+        .await?;
+        Ok(deferred)
+    }
+
+    // returns structs to be created now and those deferred, both are empty if no table is missing
+    fn get_missing_tb_structs(
+        &mut self,
+        schema: &str,
+        schema_statements: Vec<StructStatement>,
+        tb_statements: Vec<StructStatement>,
+        dst_tbs: &HashMap<String, Vec<String>>,
+    ) -> (Vec<StructData>, Vec<StructData>) {
+        let mut missing_tb_count = 0;
+        let mut indexes = Vec::new();
+        let mut constraints = Vec::new();
+        let mut data: Vec<StructData> = schema_statements
+            .into_iter()
+            .map(|statement| StructData {
+                schema: schema.to_string(),
+                statement,
+            })
+            .collect();
+
+        for statement in tb_statements {
+            let defer_indexes = match Self::get_schema_tb(&statement) {
+                Some((src_schema, src_tb)) => self.is_indexes_deferred(&src_schema, &src_tb),
+                None => false,
+            };
+            let mut struct_data = self.router.route_struct(StructData {
+                schema: schema.to_string(),
+                statement,
+            });
+            let (dst_schema, dst_tb) = match Self::get_schema_tb(&struct_data.statement) {
+                Some(schema_tb) => schema_tb,
+                None => continue,
+            };
+
+            if dst_tbs
+                .get(&dst_schema)
+                .is_some_and(|tbs| tbs.contains(&dst_tb))
+            {
+                log_info!(
+                    "target tb: {}.{} already exists, skip creating",
+                    dst_schema,
+                    dst_tb
+                );
+                continue;
+            }
+
+            log_info!("target tb: {}.{} not exists, creating", dst_schema, dst_tb);
+            missing_tb_count += 1;
+            let (tb_indexes, tb_constraints) = struct_data.statement.split();
+            indexes.extend(tb_indexes.into_iter().map(|i| (i, defer_indexes)));
+            constraints.extend(tb_constraints.into_iter().map(|i| (i, defer_indexes)));
+            data.push(struct_data);
+        }
+
+        // schemas are only created along with missing tables
+        if missing_tb_count == 0 {
+            return (Vec::new(), Vec::new());
+        }
+
+        // indexes and constraints are created after all tables, so foreign keys do not
+        // fail on tables not created yet
+        let mut deferred = Vec::new();
+        for (statement, defer_indexes) in indexes.into_iter().chain(constraints) {
+            let struct_data = StructData {
+                schema: schema.to_string(),
+                statement,
+            };
+            if defer_indexes && struct_data.statement.is_deferrable() {
+                deferred.push(struct_data);
+            } else {
+                data.push(struct_data);
+            }
+        }
+        (data, deferred)
+    }
+
+    // filter_tb caches results and needs mut
+    fn is_indexes_deferred(&mut self, schema: &str, tb: &str) -> bool {
+        match self.defer_indexes_filter.as_mut() {
+            Some(filter) => !filter.filter_tb(schema, tb),
+            None => false,
+        }
+    }
+
+    // dst schemas of the tbs which do not exist in target
+    fn get_missing_dst_schemas(
+        &self,
+        schema: &str,
+        tbs: &HashSet<String>,
+        dst_schemas: &[String],
+    ) -> Vec<String> {
+        let mut missing_schemas: Vec<String> = tbs
+            .iter()
+            .map(|tb| self.router.get_tb_map(schema, tb).0.to_string())
+            .filter(|dst_schema| !dst_schemas.contains(dst_schema))
+            .collect::<HashSet<String>>()
+            .into_iter()
+            .collect();
+        missing_schemas.sort();
+        missing_schemas
+    }
+
+    fn get_schema_tb(statement: &StructStatement) -> Option<(String, String)> {
+        match statement {
+            StructStatement::MysqlCreateTable(s) => {
+                Some((s.table.database_name.clone(), s.table.table_name.clone()))
+            }
+            StructStatement::PgCreateTable(s) => {
+                Some((s.table.schema_name.clone(), s.table.table_name.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn group_by_schema<'b>(
+        tbs: impl Iterator<Item = &'b (String, String)>,
+    ) -> BTreeMap<String, HashSet<String>> {
+        let mut schema_tbs: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+        for (schema, tb) in tbs {
+            schema_tbs
+                .entry(schema.clone())
+                .or_default()
+                .insert(tb.clone());
+        }
+        schema_tbs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::{
+        config::router_config::RouterConfig,
+        meta::struct_meta::{
+            statement::{
+                mysql_create_database_statement::MysqlCreateDatabaseStatement,
+                mysql_create_table_statement::MysqlCreateTableStatement,
+            },
+            structure::{
+                constraint::{Constraint, ConstraintType},
+                database::Database,
+                index::{Index, IndexKind},
+                table::Table,
+            },
+        },
+    };
+
+    use super::*;
+
+    fn mock_creator(router: &RdbRouter) -> TargetTableCreator<'_> {
+        TargetTableCreator {
+            src_url: String::new(),
+            dst_url: String::new(),
+            db_type: DbType::Mysql,
+            router,
+            filter: RdbFilter::from_config(&FilterConfig::default(), &DbType::Mysql).unwrap(),
+            defer_indexes_filter: None,
+            deferred_log: String::new(),
+            resume_deferred_log: None,
+            enable_sqlx_log: false,
+        }
+    }
+
+    fn mock_router(schema_map: &str, tb_map: &str) -> RdbRouter {
+        let mut config = RouterConfig::default();
+        let RouterConfig::Rdb {
+            schema_map: config_schema_map,
+            tb_map: config_tb_map,
+            ..
+        } = &mut config;
+        *config_schema_map = schema_map.into();
+        *config_tb_map = tb_map.into();
+        RdbRouter::from_config(&config, &DbType::Mysql).unwrap()
+    }
+
+    fn mock_create_table(db: &str, tb: &str) -> StructStatement {
+        StructStatement::MysqlCreateTable(MysqlCreateTableStatement {
+            table: Table {
+                database_name: db.into(),
+                table_name: tb.into(),
+                ..Default::default()
+            },
+            constraints: vec![Constraint {
+                database_name: db.into(),
+                schema_name: String::new(),
+                table_name: tb.into(),
+                constraint_name: format!("fk_{}", tb),
+                constraint_type: ConstraintType::Foregin,
+                definition: String::new(),
+            }],
+            indexes: vec![
+                Index {
+                    database_name: db.into(),
+                    table_name: tb.into(),
+                    index_name: format!("uk_{}", tb),
+                    index_kind: IndexKind::Unique,
+                    ..Default::default()
+                },
+                Index {
+                    database_name: db.into(),
+                    table_name: tb.into(),
+                    index_name: format!("idx_{}", tb),
+                    ..Default::default()
+                },
+            ],
+        })
+    }
+
+    fn get_names(data: &[StructData]) -> Vec<String> {
+        data.iter()
+            .map(|i| match &i.statement {
+                StructStatement::MysqlCreateDatabase(s) => format!("db:{}", s.database.name),
+                StructStatement::MysqlCreateTable(s) => {
+                    format!("tb:{}.{}", s.table.database_name, s.table.table_name)
+                }
+                StructStatement::MysqlCreateIndex(s) => {
+                    format!("index:{}.{}", s.index.table_name, s.index.index_name)
+                }
+                StructStatement::MysqlAddConstraint(s) => format!(
+                    "constraint:{}.{}",
+                    s.constraint.table_name, s.constraint.constraint_name
+                ),
+                _ => String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_schema() {
+        let tbs = vec![
+            ("db_2".to_string(), "tb_1".to_string()),
+            ("db_1".to_string(), "tb_1".to_string()),
+            ("db_1".to_string(), "tb_2".to_string()),
+            ("db_1".to_string(), "tb_1".to_string()),
+        ];
+        let schema_tbs = TargetTableCreator::group_by_schema(tbs.iter());
+        assert_eq!(
+            schema_tbs.keys().cloned().collect::<Vec<String>>(),
+            vec!["db_1", "db_2"]
+        );
+        assert_eq!(
+            schema_tbs["db_1"],
+            HashSet::from(["tb_1".to_string(), "tb_2".to_string()])
+        );
+        assert_eq!(schema_tbs["db_2"], HashSet::from(["tb_1".to_string()]));
+        assert!(TargetTableCreator::group_by_schema(Vec::new().iter()).is_empty());
+    }
+
+    #[test]
+    fn test_get_missing_dst_schemas() {
+        let router = mock_router("db_1:dst_db_1", "db_1.tb_3:dst_db_3.tb_3");
+        let creator = mock_creator(&router);
+        let tbs = HashSet::from(["tb_1".to_string(), "tb_2".to_string(), "tb_3".to_string()]);
+
+        // routed schemas, sorted and deduplicated
+        assert_eq!(
+            creator.get_missing_dst_schemas("db_1", &tbs, &[]),
+            vec!["dst_db_1", "dst_db_3"]
+        );
+        assert_eq!(
+            creator.get_missing_dst_schemas("db_1", &tbs, &["dst_db_1".to_string()]),
+            vec!["dst_db_3"]
+        );
+        assert!(creator
+            .get_missing_dst_schemas(
+                "db_1",
+                &tbs,
+                &["dst_db_1".to_string(), "dst_db_3".to_string()]
+            )
+            .is_empty());
+        // schemas not routed
+        assert_eq!(
+            creator.get_missing_dst_schemas("db_2", &tbs, &["db_1".to_string()]),
+            vec!["db_2"]
+        );
+    }
+
+    #[test]
+    fn test_get_missing_tb_structs() {
+        let router = mock_router("db_1:dst_db_1", "");
+        let mut creator = mock_creator(&router);
+        let schema_statements = || {
+            vec![StructStatement::MysqlCreateDatabase(
+                MysqlCreateDatabaseStatement {
+                    database: Database {
+                        name: "dst_db_1".into(),
+                        ..Default::default()
+                    },
+                },
+            )]
+        };
+        let tb_statements = || {
+            vec![
+                mock_create_table("db_1", "tb_1"),
+                mock_create_table("db_1", "tb_2"),
+            ]
+        };
+
+        // tables are routed, indexes and constraints are created after all tables
+        let (data, deferred) = creator.get_missing_tb_structs(
+            "db_1",
+            schema_statements(),
+            tb_statements(),
+            &HashMap::new(),
+        );
+        assert_eq!(
+            get_names(&data),
+            vec![
+                "db:dst_db_1",
+                "tb:dst_db_1.tb_1",
+                "tb:dst_db_1.tb_2",
+                "index:tb_1.uk_tb_1",
+                "index:tb_1.idx_tb_1",
+                "index:tb_2.uk_tb_2",
+                "index:tb_2.idx_tb_2",
+                "constraint:tb_1.fk_tb_1",
+                "constraint:tb_2.fk_tb_2",
+            ]
+        );
+        assert!(deferred.is_empty());
+
+        // existing tables are skipped
+        let dst_tbs = HashMap::from([("dst_db_1".to_string(), vec!["tb_1".to_string()])]);
+        let (data, _) =
+            creator.get_missing_tb_structs("db_1", schema_statements(), tb_statements(), &dst_tbs);
+        assert_eq!(
+            get_names(&data),
+            vec![
+                "db:dst_db_1",
+                "tb:dst_db_1.tb_2",
+                "index:tb_2.uk_tb_2",
+                "index:tb_2.idx_tb_2",
+                "constraint:tb_2.fk_tb_2",
+            ]
+        );
+
+        // nothing is created if no table is missing, including schemas
+        let dst_tbs = HashMap::from([(
+            "dst_db_1".to_string(),
+            vec!["tb_1".to_string(), "tb_2".to_string()],
+        )]);
+        let (data, deferred) =
+            creator.get_missing_tb_structs("db_1", schema_statements(), tb_statements(), &dst_tbs);
+        assert!(data.is_empty());
+        assert!(deferred.is_empty());
+    }
+
+    #[test]
+    fn test_save_load_deferred_sqls() {
+        let dir =
+            std::env::temp_dir().join(format!("ape_dts_deferred_sqls_{}", std::process::id()));
+        let path = dir
+            .join("deferred_structs.log")
+            .to_string_lossy()
+            .to_string();
+
+        assert!(TargetTableCreator::load_deferred_sqls(&path)
+            .unwrap()
+            .is_empty());
+
+        let sqls = vec![
+            "CREATE INDEX `idx_1` ON `db_1`.`tb_1` (`f_1`)".to_string(),
+            "ALTER TABLE \"s_1\".\"tb_1\"\n  ADD CONSTRAINT \"c_1\" CHECK (f_1 > 0)".to_string(),
+        ];
+        TargetTableCreator::save_deferred_sqls(&path, &sqls).unwrap();
+        assert_eq!(TargetTableCreator::load_deferred_sqls(&path).unwrap(), sqls);
+
+        TargetTableCreator::save_deferred_sqls(&path, &sqls[1..]).unwrap();
+        assert_eq!(
+            TargetTableCreator::load_deferred_sqls(&path).unwrap(),
+            sqls[1..].to_vec()
+        );
+
+        // the file is removed once all sqls are executed
+        TargetTableCreator::save_deferred_sqls(&path, &[]).unwrap();
+        assert!(!Path::new(&path).exists());
+        assert!(TargetTableCreator::load_deferred_sqls(&path)
+            .unwrap()
+            .is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_defer_indexes_per_tb() {
+        let router = mock_router("", "");
+        let mut creator = mock_creator(&router);
+        assert!(!creator.is_indexes_deferred("db_1", "tb_1"));
+
+        let config = BasicSinkerConfig {
+            defer_indexes: true,
+            defer_indexes_tbs: "db_1.tb_1,db_2.*".into(),
+            ..Default::default()
+        };
+        creator.defer_indexes_filter =
+            TargetTableCreator::build_defer_indexes_filter(&config, &DbType::Mysql).unwrap();
+        assert!(creator.is_indexes_deferred("db_1", "tb_1"));
+        assert!(!creator.is_indexes_deferred("db_1", "tb_2"));
+        assert!(creator.is_indexes_deferred("db_2", "tb_2"));
+        // cached results are the same
+        assert!(creator.is_indexes_deferred("db_1", "tb_1"));
+        assert!(!creator.is_indexes_deferred("db_1", "tb_2"));
+
+        // only deferrable indexes and constraints of deferred tables are deferred
+        let (data, deferred) = creator.get_missing_tb_structs(
+            "db_1",
+            Vec::new(),
+            vec![
+                mock_create_table("db_1", "tb_1"),
+                mock_create_table("db_1", "tb_2"),
+            ],
+            &HashMap::new(),
+        );
+        assert_eq!(
+            get_names(&data),
+            vec![
+                "tb:db_1.tb_1",
+                "tb:db_1.tb_2",
+                "index:tb_1.uk_tb_1",
+                "index:tb_2.uk_tb_2",
+                "index:tb_2.idx_tb_2",
+                "constraint:tb_2.fk_tb_2",
+            ]
+        );
+        assert_eq!(
+            get_names(&deferred),
+            vec!["index:tb_1.idx_tb_1", "constraint:tb_1.fk_tb_1"]
+        );
+    }
+}
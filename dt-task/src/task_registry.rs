@@ -0,0 +1,65 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use dt_common::monitor::monitor::Monitor;
+
+use crate::config_watcher::ConfigWatcher;
+
+// single tasks running in this process, shared with the admin server
+#[derive(Default)]
+pub struct TaskRegistry {
+    pub tasks: HashMap<String, TaskHandle>,
+}
+
+#[derive(Clone)]
+pub struct TaskHandle {
+    pub task_id: String,
+    pub start_time: Instant,
+    // the pipeline stops fetching new batches if paused
+    pub paused: Arc<AtomicBool>,
+    // the extractor is stopped and the pipeline exits after the buffer is drained
+    pub stopped: Arc<AtomicBool>,
+    pub extractor_monitor: Arc<Mutex<Monitor>>,
+    pub pipeline_monitor: Arc<Mutex<Monitor>>,
+    pub sinker_monitor: Arc<Mutex<Monitor>>,
+    // applies hot reload supported configs
+    pub config_watcher: Arc<async_mutex::Mutex<ConfigWatcher>>,
+}
+
+impl TaskRegistry {
+    pub fn register(&mut self, task_handle: TaskHandle) {
+        self.tasks.insert(task_handle.task_id.clone(), task_handle);
+    }
+
+    pub fn unregister(&mut self, task_id: &str) {
+        self.tasks.remove(task_id);
+    }
+
+    pub fn get(&self, task_id: &str) -> Option<TaskHandle> {
+        self.tasks.get(task_id).cloned()
+    }
+
+    // paused pipelines never drain their buffers, resume them to let tasks exit
+    pub fn stop_all(&self) {
+        for task_handle in self.tasks.values() {
+            task_handle.stopped.store(true, Ordering::Release);
+            task_handle.paused.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl TaskHandle {
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Acquire)
+    }
+}
@@ -2,8 +2,8 @@ use std::{str::FromStr, time::Duration};
 
 use dt_common::config::s3_config::S3Config;
 use dt_common::config::{
-    config_enums::DbType, meta_center_config::MetaCenterConfig, sinker_config::SinkerConfig,
-    task_config::TaskConfig,
+    config_enums::DbType, credential_resolver::CredentialResolver,
+    meta_center_config::MetaCenterConfig, sinker_config::SinkerConfig, task_config::TaskConfig,
 };
 use dt_common::log_info;
 use dt_common::meta::mysql::mysql_dbengine_meta_center::MysqlDbEngineMetaCenter;
@@ -20,7 +20,7 @@ use sqlx::Executor;
 use sqlx::{
     mysql::{MySqlConnectOptions, MySqlPoolOptions},
     postgres::{PgConnectOptions, PgPoolOptions},
-    ConnectOptions, MySql, Pool, Postgres, Row,
+    ConnectOptions, MySql, Pool, Postgres, Row, Transaction,
 };
 
 const MYSQL_SYS_DBS: [&str; 4] = ["information_schema", "mysql", "performance_schema", "sys"];
@@ -62,6 +62,18 @@ impl TaskUtil {
         url: &str,
         max_connections: u32,
         enable_sqlx_log: bool,
+    ) -> anyhow::Result<Pool<Postgres>> {
+        Self::create_pg_conn_pool_with_statement_cache(url, max_connections, enable_sqlx_log, 0)
+            .await
+    }
+
+    // statement_cache_capacity: prepared statements kept by sqlx for each connection,
+    // 0 means the sqlx default
+    pub async fn create_pg_conn_pool_with_statement_cache(
+        url: &str,
+        max_connections: u32,
+        enable_sqlx_log: bool,
+        statement_cache_capacity: usize,
     ) -> anyhow::Result<Pool<Postgres>> {
         let mut conn_options = PgConnectOptions::from_str(url)?;
         conn_options
@@ -71,6 +83,9 @@ impl TaskUtil {
         if !enable_sqlx_log {
             conn_options.disable_statement_logging();
         }
+        if statement_cache_capacity > 0 {
+            conn_options = conn_options.statement_cache_capacity(statement_cache_capacity);
+        }
 
         let conn_pool = PgPoolOptions::new()
             .max_connections(max_connections)
@@ -171,6 +186,45 @@ impl TaskUtil {
         log_level == "debug" || log_level == "trace"
     }
 
+    // for one-off commands, E.g. precheck / schema export, urls are used right after being
+    // resolved, so leased secrets are not fetched again
+    pub async fn resolve_credentials(config: &mut TaskConfig) -> anyhow::Result<()> {
+        let mut credential_resolver =
+            CredentialResolver::new(config.runtime.credential_refresh_margin_secs);
+        config.resolve_credentials(&mut credential_resolver).await
+    }
+
+    // export a snapshot in a repeatable read transaction, the snapshot can be imported by other
+    // sessions until the transaction ends, returns the transaction, snapshot id, and old_snapshot_threshold
+    // in seconds (None if disabled or removed, pg 17+)
+    pub async fn export_pg_snapshot(
+        url: &str,
+    ) -> anyhow::Result<(Transaction<'static, Postgres>, String, Option<u64>)> {
+        let conn_pool = TaskUtil::create_pg_conn_pool(url, 1, false).await?;
+
+        let mut old_snapshot_threshold_secs = None;
+        let sql = "SELECT setting FROM pg_settings WHERE name = 'old_snapshot_threshold'";
+        if let Some(row) = sqlx::query(sql).fetch_optional(&conn_pool).await? {
+            // in minutes, -1 means disabled
+            let setting: String = row.try_get("setting")?;
+            let minutes: i64 = setting.parse()?;
+            if minutes >= 0 {
+                old_snapshot_threshold_secs = Some(minutes as u64 * 60);
+            }
+        }
+
+        let mut tx = conn_pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut tx)
+            .await?;
+        let row = sqlx::query("SELECT pg_export_snapshot() AS snapshot_id")
+            .fetch_one(&mut tx)
+            .await?;
+        let snapshot_id: String = row.try_get("snapshot_id")?;
+        log_info!("pg snapshot exported: {}", snapshot_id);
+        Ok((tx, snapshot_id, old_snapshot_threshold_secs))
+    }
+
     pub async fn list_schemas(url: &str, db_type: &DbType) -> anyhow::Result<Vec<String>> {
         let mut dbs = match db_type {
             DbType::Mysql => Self::list_mysql_dbs(url).await?,
@@ -182,6 +236,28 @@ impl TaskUtil {
         Ok(dbs)
     }
 
+    // None if the db type is not supported
+    pub async fn get_max_connections(url: &str, db_type: &DbType) -> anyhow::Result<Option<usize>> {
+        let max_connections: String = match db_type {
+            DbType::Mysql => {
+                let conn_pool = Self::create_mysql_conn_pool(url, 1, false).await?;
+                let sql = "SELECT CAST(@@max_connections AS CHAR)";
+                let max_connections = sqlx::query_scalar(sql).fetch_one(&conn_pool).await?;
+                conn_pool.close().await;
+                max_connections
+            }
+            DbType::Pg => {
+                let conn_pool = Self::create_pg_conn_pool(url, 1, false).await?;
+                let sql = "SHOW max_connections";
+                let max_connections = sqlx::query_scalar(sql).fetch_one(&conn_pool).await?;
+                conn_pool.close().await;
+                max_connections
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(max_connections.trim().parse()?))
+    }
+
     pub async fn list_tbs(
         url: &str,
         schema: &str,
@@ -346,6 +422,15 @@ impl TaskUtil {
         Ok(tbs)
     }
 
+    pub async fn create_rds_data_client(region: &str) -> aws_sdk_rdsdata::Client {
+        // credentials are resolved by the default provider chain (env, profile, instance role)
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+        if !region.is_empty() {
+            loader = loader.region(aws_config::Region::new(region.to_string()));
+        }
+        aws_sdk_rdsdata::Client::new(&loader.load().await)
+    }
+
     pub fn create_s3_client(s3_config: &S3Config) -> S3Client {
         let region = if s3_config.endpoint.is_empty() {
             Region::from_str(&s3_config.region).unwrap()
@@ -12,6 +12,11 @@ ALTER TABLE test_db_1.tb_1 DROP COLUMN f_2;
 
 INSERT INTO test_db_1.tb_1 VALUES (3,3,3);
 
+-- modify column type, the next insert should use the new type
+ALTER TABLE test_db_1.tb_1 MODIFY COLUMN f_3 int DEFAULT NULL;
+
+INSERT INTO test_db_1.tb_1 VALUES (4,4,40000);
+
 -- truncate table
 TRUNCATE test_db_1.truncate_tb_1;
 TRUNCATE TABLE test_db_1.truncate_tb_2;
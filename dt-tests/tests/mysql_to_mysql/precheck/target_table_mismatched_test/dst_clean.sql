@@ -0,0 +1 @@
+DROP DATABASE IF EXISTS precheck_it_mysql2mysql_8;
\ No newline at end of file
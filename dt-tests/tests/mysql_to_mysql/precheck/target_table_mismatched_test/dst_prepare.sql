@@ -0,0 +1,6 @@
+DROP DATABASE IF EXISTS precheck_it_mysql2mysql_8;
+
+CREATE DATABASE precheck_it_mysql2mysql_8;
+
+-- tb_1 is routed to dst_tb_1, which misses price and has an incompatible created, tb_2 is missing
+CREATE TABLE precheck_it_mysql2mysql_8.dst_tb_1(id integer, name varchar(10), created integer, primary key (id));
@@ -0,0 +1,6 @@
+DROP DATABASE IF EXISTS precheck_it_mysql2mysql_8;
+
+CREATE DATABASE precheck_it_mysql2mysql_8;
+
+CREATE TABLE precheck_it_mysql2mysql_8.tb_1(id integer, name varchar(10), price decimal(10, 2), created datetime, primary key (id));
+CREATE TABLE precheck_it_mysql2mysql_8.tb_2(id integer, primary key (id));
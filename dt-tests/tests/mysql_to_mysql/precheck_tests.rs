@@ -163,4 +163,21 @@ mod test {
         )
         .await
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn target_table_mismatched_test() {
+        let test_dir = "mysql_to_mysql/precheck/target_table_mismatched_test";
+
+        let mut dst_expected_results = HashMap::new();
+        dst_expected_results.insert(CheckItem::CheckIfTargetTableMatched.to_string(), false);
+
+        TestBase::run_precheck_test(
+            test_dir,
+            &HashSet::new(),
+            &HashMap::new(),
+            &dst_expected_results,
+        )
+        .await
+    }
 }
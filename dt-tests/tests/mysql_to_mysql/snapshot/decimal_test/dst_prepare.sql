@@ -0,0 +1,5 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.decimal_test(f_0 INT AUTO_INCREMENT, f_1 DECIMAL(38,10), f_2 DECIMAL(65,30), f_3 DECIMAL(10,0), PRIMARY KEY(f_0));
@@ -0,0 +1,6 @@
+INSERT INTO test_db_1.decimal_test VALUES (NULL, 12345678901234567890.0123456789, 12345678901234567890123456789012345.012345678901234567890123456789, 1234567890)
+INSERT INTO test_db_1.decimal_test VALUES (NULL, -12345678901234567890.0123456789, -12345678901234567890123456789012345.012345678901234567890123456789, -1234567890)
+INSERT INTO test_db_1.decimal_test VALUES (NULL, 0.0000000001, 0.000000000000000000000000000001, 0)
+INSERT INTO test_db_1.decimal_test VALUES (NULL, 9999999999999999999999999999.9999999999, 1000000000.000000000000000000000000000000, 9999999999)
+INSERT INTO test_db_1.decimal_test VALUES (NULL, 0, 0, NULL)
+INSERT INTO test_db_1.decimal_test VALUES (NULL, NULL, NULL, NULL)
@@ -0,0 +1,17 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+-- row count is a multiple of batch_size, the last batch is empty
+CREATE TABLE test_db_1.tb_1 (`id` int(11) NOT NULL, `value` int(11) DEFAULT NULL, PRIMARY KEY (`id`)); 
+
+-- row count is not a multiple of batch_size
+CREATE TABLE test_db_1.tb_2 (`id` bigint NOT NULL, `value` int(11) DEFAULT NULL, PRIMARY KEY (`id`)); 
+
+-- string order_col
+CREATE TABLE test_db_1.tb_3 (`id` varchar(255) NOT NULL, `value` int(11) DEFAULT NULL, PRIMARY KEY (`id`)); 
+
+-- composite primary key, duplicate tenant_id values across batch boundaries
+CREATE TABLE test_db_1.tb_4 (`tenant_id` int(11) NOT NULL, `id` int(11) NOT NULL, `value` int(11) DEFAULT NULL, PRIMARY KEY (`tenant_id`, `id`)); 
+
+CREATE TABLE test_db_1.where_condition_1 ( f_0 int, f_1 int, PRIMARY KEY (f_0) ); 
@@ -0,0 +1,10 @@
+INSERT INTO test_db_1.tb_1 VALUES (1,1),(2,2),(3,3),(7,7),(9,9),(10,10),(11,11),(12,12),(14,14),(16,16),(17,17),(18,18);
+
+INSERT INTO test_db_1.tb_2 VALUES (-9223372036854775808,1),(-1,2),(0,3),(7,7),(9,9),(10,10),(11,11),(12,12),(14,14),(16,16),(17,17),(18,18),(9223372036854775807,19);
+
+INSERT INTO test_db_1.tb_3 VALUES ("1",1),("10",10),("11",11),("2",2),("3",3),("a",4),("A1",5),("b",6),("中文",7),("",8);
+
+INSERT INTO test_db_1.tb_4 VALUES (1,1,1),(1,2,2),(1,3,3),(1,4,4),(2,1,5),(2,2,6),(2,3,7),(3,1,8),(3,2,9),(3,3,10),(3,4,11),(3,5,12),(4,1,13);
+
+-- test where condition
+INSERT INTO test_db_1.where_condition_1 VALUES(1, 1),(2, 2),(3, 3),(4, 4),(5, 5),(6, 6),(7, 7),(8, 8),(9, 9),(10, 10);
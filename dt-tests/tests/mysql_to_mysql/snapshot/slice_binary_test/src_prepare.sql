@@ -0,0 +1,7 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.binary_pk(f_0 BINARY(16), f_1 INT, PRIMARY KEY(f_0));
+
+CREATE TABLE test_db_1.varbinary_pk(f_0 VARBINARY(16), f_1 INT, PRIMARY KEY(f_0));
@@ -0,0 +1,24 @@
+-- random 16-byte keys, slice boundaries are picked from them
+INSERT INTO test_db_1.binary_pk VALUES (X'8AF156E17D9701213F0A937D1D0C1FD4', 0),(X'B2DCA4F78BC5695878FB074F21F77FEE', 1),(X'4C23D18291C790BEDC683B8745A4464C', 2),(X'E384C2B69B54CAED2E5F128D6B26B887', 3),(X'42C618B2251C6A8E0D127051B7FCB229', 4),(X'A5EDA6DA19BE0B54D6BC57EF096BA7B5', 5),(X'B1E32C431A54A543014EC738AD17066F', 6),(X'3C5986CDB0B72BC733E69E3DE63F2E88', 7),(X'27707BC5C4D3DC7C0258E0F776D51865', 8),(X'ED4E38DE5A06C902E43DC9197A98B7E6', 9);
+INSERT INTO test_db_1.binary_pk VALUES (X'DD269333E4714399254D384E668F8D6D', 10),(X'AC71434F4C6BE9497A7BDCB46653594E', 11),(X'900CF1170431CAEBED83500C89D6912E', 12),(X'1E4713D2D3DAC155092124DA656F6DEA', 13),(X'DFA388691F55B93C633F02A3A4556842', 14),(X'60FFA080C9517E991BC9DA05A500A199', 15),(X'9899F7348E34E2CDFEE64BB34161B9A0', 16),(X'2865238C65A1C675DC0C8D0775983CE6', 17),(X'287FBE8EB19E3B1CC49A769F73652226', 18),(X'DC51FB26F1B042C0EBF8D9585A1D8423', 19);
+INSERT INTO test_db_1.binary_pk VALUES (X'5F18B579C73F6CA05637A736B7A42B82', 20),(X'10A5E689DC687E52A114CD6A569D9304', 21),(X'0140A7F36C172291966E2C1354B7E7AB', 22),(X'D6A7403B08882359B32ECA2E2991CAF3', 23),(X'A10F306672ED2F9BC4BBBD00C4749F81', 24),(X'6AF2D4DF98C77ADDF3E33295DC9E400F', 25),(X'A89374925480AE76530779F0C8E1695E', 26),(X'FD918C5DFF7E91C9B139207C60056878', 27),(X'789B4D74E7A6E48A75E6C27520155B3E', 28),(X'2F5CB531097C1BFD68B54A7F444BF88D', 29);
+INSERT INTO test_db_1.binary_pk VALUES (X'5D47F5CF7B24A7F8B983F959C3C1A1EA', 30),(X'FCC02860F92705275CB85F673EBD3F3C', 31),(X'1756E827528C4D1EC9251D79A516307A', 32),(X'3B2648541564577C008DE1EF73D907FD', 33),(X'6C778870441BE2872F44D6C93544FE26', 34),(X'492898A7693396C0D8C73879B8EE2E8E', 35),(X'FAD461A58D6DDE4CA6A4396648C71F3C', 36),(X'B86835AE78CD051EE4E852F3BC2D29D6', 37),(X'7A6DA3FE01EE28DDF7B6523D29979300', 38),(X'D5554DFBF7159CD2724297310B5487CD', 39);
+INSERT INTO test_db_1.binary_pk VALUES (X'B6AD445DF85DFDA4B32DA2456D210F60', 40),(X'5304F45B4EE18F940E4B339156B195C0', 41),(X'8E984E77F4B2EEC72898F8487D989E5B', 42),(X'0E48B015EFE54E7B7B075C6801155298', 43),(X'3B4751509334B60D6AACC16A5898A3A6', 44),(X'A8A2034EC75ED2ACD4FB793ACE8914E3', 45),(X'32E989E217B16FA30A7F720B41FF8A36', 46),(X'B49049380CBBF031C401BF46B5E8487A', 47),(X'C5CB1A0E203FDCB2BBECB4214396338B', 48),(X'8B46013EF7D8734BA70A15113D8818EA', 49);
+INSERT INTO test_db_1.binary_pk VALUES (X'31179E2141BCCA2682AA10F38DD0D0CF', 50),(X'718868B03BD5BEE22DF1BD6FF9E80CD3', 51),(X'8C779077A29921DE769267883D6F4644', 52),(X'63F09B94A088EDA5BE3348370B3DA21D', 53),(X'91FA027F2F33D3CA000FA5E586986E39', 54),(X'6EB826BCCDC62090271CAEE5C845070F', 55),(X'FDFECCD5F614802804DFA7FE26EBAC4D', 56),(X'968A72E53379AA03E2E896EE16F42005', 57),(X'0B36732F4F92B1415A1C32AE83A5A825', 58),(X'6F1356A0F79796F27AFCB8759A78962B', 59);
+INSERT INTO test_db_1.binary_pk VALUES (X'3D0729FDF93D905BC55941D890FF588B', 60),(X'27DF6BD6541731B14239840BD0924844', 61),(X'E0C490E5871F4F39FC405C2D2FB40890', 62),(X'4A3C3E6DABBEC547F863157DA1E11F86', 63),(X'9EDD6793B4CDAE9748245758C0E8B38F', 64),(X'D28C4F7718AD4F9D3997E101475E6618', 65),(X'26BBFC587189E2DA5F2CB17F780B603A', 66),(X'E9B0E85FDAC2FEC30F778488A6B27E13', 67),(X'0612BFE5BD7F14B7AEA56DC6D43D480B', 68),(X'689FC429D8817B0C41D2A5801BA7C636', 69);
+INSERT INTO test_db_1.binary_pk VALUES (X'A76287F181D6C3979CC134A743B984AD', 70),(X'081C2D27E83CFA577637F9F8FE9E9AD1', 71),(X'0B73F0F6AE64A7FF1465D7C3104829E8', 72),(X'30126C4116E00D849A9B5195B71D9A41', 73),(X'C4FB2DF30AC52EFDE1DE3331B5F20ECD', 74),(X'8B6EE4E67DFADED326A4C33FC9496DF9', 75),(X'2B8256FFE706B37BE1FA93621B4FD178', 76),(X'33ABDAE53951539FF413D4E689A8AB01', 77),(X'E35555E61D50B46CBCF9C7A99D986D3E', 78),(X'2940AC96C7AAC2D1D282FC28CBF2C61F', 79);
+INSERT INTO test_db_1.binary_pk VALUES (X'3F984FFDB8D752EF06564C7C970307AB', 80),(X'CBAD06A2C849324A74A3A306E9DCF183', 81),(X'3CCAD7742D99EB62DA4C5DA4B29C7980', 82),(X'553DDEA7FEDCA4163F68ED2B3458F82D', 83),(X'5606163A12A7750331BDE72E8D0B66B2', 84),(X'10D83DF2FDA67482CAB2A8F34C82DEC0', 85),(X'BAF7A41256B47FFA707371C3CC0CCD1F', 86),(X'7EAA99583ECDB7CE7EE01ADFBD4D6D43', 87),(X'35A3861BD34646144EDFD48039A49C56', 88),(X'46DCD4E53F41A57CC6E03DE208BDA92B', 89);
+INSERT INTO test_db_1.binary_pk VALUES (X'F79D8A3956CD5B1F346BDB6C4E08666E', 90),(X'7481C23AB94A5F14EC8047C507EC1195', 91),(X'69B6FD32952EABB966926C7DEFC4B712', 92),(X'B0975A6C6F6EE5D1903F557345BBE6F4', 93),(X'68AF538E9D1D5D446666884D07FF2007', 94),(X'0EEBF27530AB59DCD3CA3460DEDF0440', 95),(X'A23ED59E570075A3B1649DD94E421573', 96),(X'00000000000000000000000000000000', 97),(X'FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF', 98),(X'00000000000000000000000000000001', 99);
+INSERT INTO test_db_1.binary_pk VALUES (X'7F000000000000000000000000000000', 100),(X'80000000000000000000000000000000', 101);
+
+INSERT INTO test_db_1.varbinary_pk VALUES (X'A820358B58CE30B3198CDE325FCE1CB7', 0),(X'933D4127DFF5B5F4F4BCEA01B6064460', 1),(X'31E6002FA0C78237938523B527240E0C', 2),(X'35AE174E47FF2F38495D372EE51C7A34', 3),(X'F37EA23475312BC96033488FF24C7257', 4),(X'06BAFB6F645AC74F1EFD11BF8E18CA76', 5),(X'8322133711D35BE49D7F88143D3BC9F5', 6),(X'E1272FEDF28261F0ABFE4416EF9F2AE2', 7),(X'7B6EA7AB615F502AF0CFC45F5C184C2B', 8),(X'0B7323D0030863A7B5E2F4FF039B8F70', 9);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'AC69440E86949CB53880473C049B0775', 10),(X'BC37BB39CFC9C0C5DEE85CEEE9FD2E35', 11),(X'65843AA7804886FC779A13A88D59E142', 12),(X'7772510D01C9D7010A11516A3B3DB5FF', 13),(X'12C063805B8C5B6F703AB24275F14EF8', 14),(X'503931F514F419AF2CE22E871F7CDC51', 15),(X'BA744DD00C03B4C2631736D6C8D5F3A5', 16),(X'FE25145848A8E5E9107BB4693FB49A11', 17),(X'A0E1B317EAA0214F313E26264A08B429', 18),(X'58FDBD0FC23E767D08B8A3E712463EC0', 19);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'FDDB26333F26EA584AE3FB2249521196', 20),(X'08B80D161DCB26E99F2D1E8AFD1C1F33', 21),(X'ACEECA29DEC581DF407267E48AE29283', 22),(X'68A001937DE39954FDBA22B5A30258D5', 23),(X'3EF431565B00C7FB84F9D421FECA5C8F', 24),(X'080BA30DE3320771149D53B08E222CDD', 25),(X'F92414327F3CE760CC13CA8F905BCB66', 26),(X'0A505796C48EC70E57014577A15E62C4', 27),(X'70CD00D5E7EB2346680278FBFB169AAC', 28),(X'FFB5D225F153C1525777BC83818782E4', 29);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'D2A34C9BB1E107BA6F257FC112ED0E39', 30),(X'35209DD0C36551FD70ED6CD36416FB4B', 31),(X'93B3807F8259121B28CDACAB868D7495', 32),(X'C39C1EA476E700524877BD904AB2B9E8', 33),(X'03CC7F6A6EB34E9E9289B594D73588DF', 34),(X'090C2C197A06E9A733D61FABF4672F74', 35),(X'18C229BE3530734B198AF55156531DC9', 36),(X'88816F5111F4F05403358DE557B9FC82', 37),(X'06DCDFC303FA877D8BFA1CA0FFA6E0DF', 38),(X'D01EC724AE4F2C64D237B7E672BD02B1', 39);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'A89259D0ED140EAF272B131B0CE72729', 40),(X'337C7231934EB963CAA6CA8CB5963395', 41),(X'F0490DB0F50A496D5B6D6E7B1376BF78', 42),(X'1C2AAC65CF2E2C67BCB3E2D42129403A', 43),(X'5DE39B2269D6C7D89CED7987FA20DE0E', 44),(X'0099FD8E7FC7D6DA56E141C695DBCA3E', 45),(X'B31CDA49F4AB06F103B4E90C3FAEA33B', 46),(X'48757CC6AD0D46F25804DA047D0D0545', 47),(X'4C6389E08B0D8048740047AA01CBE741', 48),(X'764F33470A7EDD44E7D4E0F0AAD42C7D', 49);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'1FCA1F247FAD4F13AB0BD29AB011F612', 50),(X'4821BC3EEFC3FDA95F5195CB13DA87BF', 51),(X'C0517BAE098093FDA2CD24E678AEDDBE', 52),(X'FB8EF3A9F4311DED2E6C1CBF2AA81D76', 53),(X'F573044FA5905C8574F6DA4911ED41B6', 54),(X'124CC48E23A7CE6ED6946D6A26C4BEE6', 55),(X'99A5837D010946729D12C455A4075347', 56),(X'F3B7E4AFF31643BD0AB8BE9DACE32139', 57),(X'36AF7FA68F5A6AA86253EF45D89243F0', 58),(X'2F8B48A011E483BEF782C3620FDDB782', 59);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'1869427174C559F005F6ED9393B5D420', 60),(X'63DD4D2B5F909D82F54DD675D257110A', 61),(X'1DF77138CE481CFAC1B643200BA8DA16', 62),(X'3F4E6C8EC79D48DE7AD5AA983649FBE1', 63),(X'A0C22678D9DAB741096483F32EA5A050', 64),(X'7B273337C31625DDC5A7908AC9DA18A0', 65),(X'1AF63D97C4C95D699DDF47FC5F7D931D', 66),(X'32BC6BEFDCC7BD5A9507C9A3FC44FF9F', 67),(X'95018121C33BFC52F3237189A46B31D8', 68),(X'9A1BFB4441CB602200FE3AFFC4CB21EC', 69);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'2F3CB28A6985750BAFCDD1B506E68934', 70),(X'F0791E9C1036F79C04B814C1FD0E9B36', 71),(X'A13B78CBC0B7413A438C7DA21A72CD7C', 72),(X'D1FF211CF07EAEDD3E27772E855422A2', 73),(X'05189DC68568BCA32A5DBDAB03A6698E', 74),(X'0AD1039452CBA8AE3C40F9E2F5097591', 75),(X'E035DC0C81B14B2B466848828E4744C7', 76),(X'C27FFA93A8C6F9796C928B22495D188A', 77),(X'46E3A6AA120935F38631A4E2E7E250F9', 78),(X'3F2E96207BA1E325CF446462296528CD', 79);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'76C7200FED10C0204E1B380F90DBD4B8', 80),(X'697D8B5263A75EB26243CA484B3974AC', 81),(X'8CB52FFAFA7B4DF87DC29FF26EC74CBD', 82),(X'598965B9EDF8490132B7F8CFA4382B32', 83),(X'B2B9DE6EF8DBF3617DC8DAD55EF481BD', 84),(X'5DCB21A18E50C97288EDCB7EDDE05D2E', 85),(X'B0BC0D7F42AB558DEB0CBB1F7F362BEB', 86),(X'CE00ED51A9B3927AF416A98DE44BCC6F', 87),(X'45B76BD833B7DF1DB4F621909200822C', 88),(X'6CFFEA31C4EF2CE0E9E3C25E9731B482', 89);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'057D7CF8579A61FAF0F537721B6CB92C', 90),(X'5BAB3E5C64A9F7C46801730BBD584616', 91),(X'1E745A0C7F93A42E72E6BA1F46027D4B', 92),(X'80C8E926468CD8F5246733D976313F83', 93),(X'9216124CCDCA36A09EC80DF3C8906B47', 94),(X'C1FD81AD8E348AE2865B68C23274D213', 95),(X'FF3EBDFD5FFD4513C4F138619ACAF8ED', 96),(X'00000000000000000000000000000000', 97),(X'FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF', 98),(X'00000000000000000000000000000001', 99);
+INSERT INTO test_db_1.varbinary_pk VALUES (X'7F000000000000000000000000000000', 100),(X'80000000000000000000000000000000', 101),(X'00', 102),(X'80', 103),(X'8000', 104);
@@ -73,6 +73,12 @@ mod test {
         TestBase::run_snapshot_test("mysql_to_mysql/snapshot/json_test").await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_decimal_test() {
+        TestBase::run_snapshot_test("mysql_to_mysql/snapshot/decimal_test").await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_route_test() {
@@ -91,6 +97,22 @@ mod test {
         TestBase::run_snapshot_test("mysql_to_mysql/snapshot/parallel_test").await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_slice_binary_test() {
+        // [extractor]
+        // parallel_slices=4
+        TestBase::run_snapshot_test("mysql_to_mysql/snapshot/slice_binary_test").await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_desc_test() {
+        // [extractor]
+        // snapshot_desc=true
+        TestBase::run_snapshot_test("mysql_to_mysql/snapshot/desc_test").await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_tb_parallel_test() {
@@ -12,6 +12,11 @@ ALTER TABLE test_db_1.tb_1 DROP COLUMN f_2;
 
 INSERT INTO test_db_1.tb_1 VALUES (3,3,3);
 
+-- change column type, the next insert should use the new type
+ALTER TABLE test_db_1.tb_1 ALTER COLUMN f_3 TYPE bigint;
+
+INSERT INTO test_db_1.tb_1 VALUES (4,4,4000000000);
+
 -- truncate table
 TRUNCATE test_db_1.truncate_tb_1;
 TRUNCATE TABLE test_db_1.truncate_tb_2;
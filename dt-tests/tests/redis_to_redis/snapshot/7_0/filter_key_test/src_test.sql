@@ -0,0 +1,17 @@
+SET session:0 0
+SET session:tmp:0 0
+SET user:0 0
+HSET session:1 field1 "hello" field2 "world"
+HSET user:1 field1 "hello" field2 "world"
+RPUSH session:2 "Hello"
+RPUSH user:2 "Hello"
+SADD session:3 "Hello"
+SADD user:3 "Hello"
+ZADD session:4 0 a 1 b 2 c
+ZADD user:4 0 a 1 b 2 c
+XADD session:5 1526919030474-55 message "Hello,"
+XADD user:5 1526919030474-55 message "Hello,"
+
+SELECT 1
+SET session:0 0
+SET order:1 1
\ No newline at end of file
@@ -381,7 +381,7 @@ impl MongoTestRunner {
         let src_data = self.fetch_data(db, tb, SRC).await;
 
         let (dst_db, dst_tb) = self.router.get_tb_map(db, tb);
-        let dst_data = self.fetch_data(dst_db, dst_tb, DST).await;
+        let dst_data = self.fetch_data(&dst_db, &dst_tb, DST).await;
 
         assert_eq!(src_data.len(), dst_data.len());
         for id in src_data.keys() {
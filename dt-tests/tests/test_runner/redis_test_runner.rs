@@ -270,7 +270,7 @@ impl RedisTestRunner {
                 src_kvs, dst_kvs
             );
 
-            if self.filter.filter_schema(db) {
+            if self.filter.filter_schema(db) || self.filter.filter_key(key) {
                 println!("filtered, db: {}, key: {}", db, key);
                 assert_eq!(dst_kvs.len(), 0);
             } else {
@@ -338,7 +338,7 @@ impl RedisTestRunner {
             cmd, src_result, dst_result
         );
 
-        if self.filter.filter_schema(db) {
+        if self.filter.filter_schema(db) || self.filter.filter_key(key) {
             println!("filtered, db: {}, key: {}", db, key);
             match dst_result {
                 Value::Array(v) | Value::Set(v) => assert_eq!(v, vec![]),